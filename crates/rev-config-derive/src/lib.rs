@@ -32,34 +32,113 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
                     pub #field_name: Option<#field_type>,
                 };
 
-                let cli_gen = quote! {
-                    if let Some(#field_name) = matches.remove_one::<#field_type>(stringify!(#field_name)) {
-                        s.#field_name = Some(#field_name);
+                let screaming_field_name = syn::Ident::new(&field_name.to_string().to_shouty_snake_case(), field_name.span());
+                let kdl_to_string = quote! {
+                    |value: &::kdl::KdlValue| match (value.as_string(), value.as_i64()) {
+                        (Some(s), _) => s.to_string(),
+                        (_, Some(i)) => i.to_string(),
+                        _ => value.to_string(),
                     }
                 };
 
-                let cli_args_gen = quote! {
-                    .arg(
-                        ::clap::Arg::new(stringify!(#field_name))
-                            .long(stringify!(#field_name))
-                            .action(::clap::ArgAction::Set)
-                            .help_heading("Config")
-                            .global(true)
-                    )
-                };
+                let (cli_gen, cli_args_gen, env_gen, config_gen) = if is_string_list(field_type) {
+                    let cli_gen = quote! {
+                        if let Some(#field_name) = matches.remove_many::<String>(stringify!(#field_name)) {
+                            s.#field_name = Some(#field_name.collect());
+                        }
+                    };
 
-                let screaming_field_name = syn::Ident::new(&field_name.to_string().to_shouty_snake_case(), field_name.span());
-                let env_gen = quote! {
-                    if let Ok(item) =  std::env::var(format!("REV_{}", stringify!(#screaming_field_name))) {
-                        self.#field_name = Some(item);
-                    }
-                };
+                    let cli_args_gen = quote! {
+                        .arg(
+                            ::clap::Arg::new(stringify!(#field_name))
+                                .long(stringify!(#field_name))
+                                .action(::clap::ArgAction::Append)
+                                .value_parser(::clap::value_parser!(String))
+                                .help_heading("Config")
+                                .global(true)
+                        )
+                    };
 
-                let config_gen = quote! {
-                    if let Some(item) = config.get(stringify!(#field_name)).and_then(|i| i.entries().first()).map(|i| i.value()) {
-                        tracing::debug!("found item: {}", item);
-                        self.#field_name = item.as_string().map(|i| i.to_string());
-                    }
+                    // Lists are comma separated in the environment, e.g. REV_LABELS=a,b
+                    let env_gen = quote! {
+                        if let Some(item) = get(&format!("REV_{}", stringify!(#screaming_field_name))) {
+                            self.#field_name = Some(
+                                item.split(',')
+                                    .map(|i| i.trim())
+                                    .filter(|i| !i.is_empty())
+                                    .map(|i| i.to_string())
+                                    .collect(),
+                            );
+                        }
+                    };
+
+                    let config_gen = quote! {
+                        if let Some(item) = config.get(stringify!(#field_name)) {
+                            tracing::debug!("found item: {}", item);
+                            self.#field_name = Some(
+                                item.entries()
+                                    .iter()
+                                    .filter(|i| i.name().is_none())
+                                    .map(|i| (#kdl_to_string)(i.value()))
+                                    .collect(),
+                            );
+                        }
+                    };
+
+                    (cli_gen, cli_args_gen, env_gen, config_gen)
+                } else {
+                    let cli_gen = quote! {
+                        if let Some(#field_name) = matches.remove_one::<#field_type>(stringify!(#field_name)) {
+                            s.#field_name = Some(#field_name);
+                        }
+                    };
+
+                    let cli_args_gen = quote! {
+                        .arg(
+                            ::clap::Arg::new(stringify!(#field_name))
+                                .long(stringify!(#field_name))
+                                .action(::clap::ArgAction::Set)
+                                .value_parser(::clap::value_parser!(#field_type))
+                                .help_heading("Config")
+                                .global(true)
+                        )
+                    };
+
+                    let env_gen = quote! {
+                        if let Some(item) = get(&format!("REV_{}", stringify!(#screaming_field_name))) {
+                            let parsed = item.parse::<#field_type>().map_err(|e| {
+                                ::rev_config::EnvError::EnvError(::anyhow::anyhow!(
+                                    "REV_{} must be a valid {}, got {:?}: {}",
+                                    stringify!(#screaming_field_name),
+                                    stringify!(#field_type),
+                                    item,
+                                    e
+                                ))
+                            })?;
+                            self.#field_name = Some(parsed);
+                        }
+                    };
+
+                    // Numbers and booleans are written unquoted in kdl, e.g. `port 8080`, so every
+                    // value is read through its string form
+                    let config_gen = quote! {
+                        if let Some(item) = config.get(stringify!(#field_name)).and_then(|i| i.entries().first()).map(|i| i.value()) {
+                            tracing::debug!("found item: {}", item);
+                            let raw = (#kdl_to_string)(item);
+                            let parsed = raw.parse::<#field_type>().map_err(|e| {
+                                ::rev_config::ConfigFileError::ConfigFileError(::anyhow::anyhow!(
+                                    "config.{} must be a valid {}, got {}: {}",
+                                    stringify!(#field_name),
+                                    stringify!(#field_type),
+                                    item,
+                                    e
+                                ))
+                            })?;
+                            self.#field_name = Some(parsed);
+                        }
+                    };
+
+                    (cli_gen, cli_args_gen, env_gen, config_gen)
                 };
 
                 (try_gen, field_gen, cli_gen, cli_args_gen, env_gen, config_gen)
@@ -97,14 +176,7 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
             }
 
             pub fn get_config_file_path(&self) -> ::std::path::PathBuf {
-                std::env::var("REV_CONFIG_HOME")
-                    .map(::std::path::PathBuf::from)
-                    .unwrap_or_else(|_| {
-                        let project = ::directories::ProjectDirs::from("io", "kjuulh", "rev")
-                            .expect("to be able to find XDG home variables");
-
-                        project.config_dir().to_path_buf()
-                   })
+                ::rev_config::config_home()
             }
 
         }
@@ -112,7 +184,7 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
         impl TryFrom<#namespace::#struct_name> for #struct_name {
             type Error = ::anyhow::Error;
 
-            fn try_from(value: inner_application_config::#struct_name) -> Result<Self, Self::Error> {
+            fn try_from(value: #namespace::#struct_name) -> Result<Self, Self::Error> {
                 Ok(Self {
                     #try_gen
                 })
@@ -120,6 +192,10 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
         }
 
         pub mod #namespace {
+            // Field types such as `PathBuf` are named as they are imported next to the struct
+            #[allow(unused_imports)]
+            use super::*;
+
             #[derive(Default)]
             pub struct #struct_name {
                 config_file: ::std::path::PathBuf,
@@ -140,17 +216,7 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
                     let config = if let Some(config_file) = matches.remove_one::<String>("config-file") {
                         ::std::path::PathBuf::from(config_file)
                     } else {
-                        let project_home = std::env::var("REV_CONFIG_HOME")
-                            .map(::std::path::PathBuf::from)
-                            .unwrap_or_else(|_| {
-                                let project = ::directories::ProjectDirs::from("io", "kjuulh", "rev")
-                                    .expect("to be able to find XDG home variables");
-
-                                project.config_dir().to_path_buf()
-                            });
-
-
-                        project_home.join("rev.kdl")
+                        ::rev_config::config_home().join("rev.kdl")
                     };
 
                     if let Err(e) = s.set_from_config_file(&config) {
@@ -176,17 +242,7 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
                     let config = if let Some(config_file) = matches.remove_one::<String>("config-file") {
                         ::std::path::PathBuf::from(config_file)
                     } else {
-                        let project_home = std::env::var("REV_CONFIG_HOME")
-                            .map(::std::path::PathBuf::from)
-                            .unwrap_or_else(|_| {
-                                let project = ::directories::ProjectDirs::from("io", "kjuulh", "rev")
-                                    .expect("to be able to find XDG home variables");
-
-                                project.config_dir().to_path_buf()
-                            });
-
-
-                        project_home.join("rev.kdl")
+                        ::rev_config::config_home().join("rev.kdl")
                     };
 
                     if let Err(e) = self.set_from_config_file(&config) {
@@ -228,7 +284,10 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
             }
 
             impl ::rev_config::Env for #struct_name {
-                fn set_from_env(&mut self) -> Result<(), ::rev_config::EnvError> {
+                fn set_from_env_with(
+                    &mut self,
+                    get: impl Fn(&str) -> Option<String>,
+                ) -> Result<(), ::rev_config::EnvError> {
                     #env_gen
 
                     Ok(())
@@ -267,3 +326,25 @@ pub fn app_config_derive(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Whether the field is a `Vec<String>`, which takes several values instead of being parsed with
+/// `FromStr`.
+fn is_string_list(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident != "Vec" {
+        return false;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => matches!(
+            args.args.first(),
+            Some(syn::GenericArgument::Type(syn::Type::Path(inner))) if inner.path.is_ident("String")
+        ),
+        _ => false,
+    }
+}
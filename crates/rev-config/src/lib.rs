@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub trait AppConfig {}
 
@@ -9,7 +9,12 @@ pub enum EnvError {
 }
 
 pub trait Env {
-    fn set_from_env(&mut self) -> Result<(), EnvError>;
+    fn set_from_env(&mut self) -> Result<(), EnvError> {
+        self.set_from_env_with(|key| std::env::var(key).ok())
+    }
+
+    /// [`Env::set_from_env`] with the environment looked up through `get`.
+    fn set_from_env_with(&mut self, get: impl Fn(&str) -> Option<String>) -> Result<(), EnvError>;
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -21,3 +26,37 @@ pub enum ConfigFileError {
 pub trait ConfigFile {
     fn set_from_config_file(&mut self, config_file: &Path) -> Result<(), ConfigFileError>;
 }
+
+/// Resolves the directory holding `rev.kdl`.
+///
+/// `REV_CONFIG_HOME` always wins, otherwise the platform specific config dir is used
+/// (XDG on linux, `~/Library/Application Support` on macOS and `%APPDATA%` on Windows).
+/// If none of these can be resolved we fall back on a `.config` dir in the current directory.
+pub fn config_home() -> PathBuf {
+    config_home_from_env(|key| std::env::var(key).ok())
+}
+
+/// [`config_home`] with the environment looked up through `get`.
+pub fn config_home_from_env(get: impl Fn(&str) -> Option<String>) -> PathBuf {
+    get("REV_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            directories::ProjectDirs::from("io", "kjuulh", "rev")
+                .map(|p| p.config_dir().to_path_buf())
+                .unwrap_or_else(|| PathBuf::from(".").join(".config"))
+        })
+}
+
+/// Resolves the directory used for logs and other local state.
+pub fn data_home() -> PathBuf {
+    data_home_from_env(|key| std::env::var(key).ok())
+}
+
+/// [`data_home`] with the environment looked up through `get`.
+pub fn data_home_from_env(get: impl Fn(&str) -> Option<String>) -> PathBuf {
+    get("REV_DATA_HOME").map(PathBuf::from).unwrap_or_else(|| {
+        directories::ProjectDirs::from("io", "kjuulh", "rev")
+            .map(|p| p.data_local_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from(".").join(".data"))
+    })
+}
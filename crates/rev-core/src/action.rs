@@ -0,0 +1,359 @@
+use rev_git_provider::models::{
+    Comments, Commit, Issue, RateLimit, RepositoryListItem, Review, ReviewListItem, StatusCheck,
+    Vulnerability,
+};
+
+use crate::{
+    description_lint::DescriptionLint, file_badges::FileBadge, patch::FileDiff,
+    saved_searches::SavedSearch,
+};
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Tick,
+    Render,
+    Resize(u16, u16),
+    Suspend,
+    Resume,
+    Quit,
+    Refresh,
+    Error(String),
+    Toast(String),
+    Help,
+    GotoPage(String),
+    GitHubPrs(GitHubPrAction),
+    Discussions(DiscussionAction),
+    BeginReview,
+    /// Moves on to the next review, keeping this one away for a day.
+    SkipReview,
+    /// Keeps the review out of the queue and the list until it's unsnoozed.
+    SnoozeReview,
+    SnoozeUntilChecksComplete,
+    ToggleCommentFilters,
+    NextComment,
+    PreviousComment,
+    EditComment,
+    DeleteComment,
+    SelectNext,
+    SelectPrevious,
+    /// Moves the selected review up or down the review plan.
+    MoveUp,
+    MoveDown,
+    TogglePin,
+    /// Stars the repository of the selected review, or takes the star away again.
+    ToggleStar,
+    /// Shows a page on top of the current one, or returns from it when it's already shown.
+    TogglePage(String),
+    EditSearch,
+    /// Narrows the list to the reviews matching what's typed, esc clears it again.
+    FilterList,
+    /// Switches the list to the next saved search, or back to the review requests. The review,
+    /// which has no queues, moves the focus to the next pane instead.
+    NextQueue,
+    /// Switches the list between one and two lines per review.
+    ToggleDensity,
+    /// Orders the queue from the smallest review to the largest, by the lines changed.
+    SortBySize,
+    /// Switches the changed files between the diff of the selected file and the lines changed
+    /// per directory and file type.
+    ToggleStats,
+    Open,
+    Back,
+    AddComment,
+    /// Submits the shown review as approved, [`Action::AddComment`] submits it as a comment.
+    Approve,
+    RequestChanges,
+    /// Asks how to merge the shown review, then merges it.
+    MergeReview,
+    /// Selects the next link in the description and comments, [`Action::Open`] opens it.
+    NextLink,
+    PreviousLink,
+    /// Shows the changes pushed to the review since it was last visited.
+    DiffSinceLastVisit,
+    /// Compares the commits of the review before and after it was force-pushed.
+    RangeDiff,
+    /// Runs git with the arguments in a checkout, e.g. `diff a...b`, and shows the output on the
+    /// diff page.
+    ShowDiff {
+        cwd: std::path::PathBuf,
+        args: String,
+    },
+    /// Shows the changes of the review as fetched from the provider, file by file.
+    OpenFiles,
+    /// The files of the review in the form `owner/name#number`, shown on the files page.
+    ShowFiles {
+        review: String,
+        files: Vec<FileDiff>,
+    },
+    /// Comments on the review asking the author to fill in the description.
+    RequestDescription,
+    /// Asks the author to fix the commits breaking the commit lint rules, after editing the
+    /// comment.
+    RequestCommitFixes,
+    /// Switches the comments pane between the conversation and the threads on the diff.
+    NextCommentsTab,
+    /// Moves the focus of the review to the next pane, which the selection keys then move.
+    NextPane,
+    PreviousPane,
+    /// Moves the focused pane of the review by a page.
+    PageDown,
+    PageUp,
+    /// Scrolls the diff by half a page.
+    HalfPageDown,
+    HalfPageUp,
+    /// Selects the next thread on the diff, in the review threads tab of the comments pane.
+    NextThread,
+    PreviousThread,
+    /// Replies to the selected thread, after writing the reply.
+    ReplyToThread,
+    ResolveThread,
+    /// Copies the permalink of the selected comment.
+    CopyCommentLink,
+    /// Copies the permalink of the selected thread, by its first comment.
+    CopyThreadLink,
+    /// Hands the shown review over to the teammate with the fewest open review requests.
+    ReassignReview,
+    /// Opens the selected or shown review in the browser.
+    OpenInBrowser,
+    /// Pipes the conversation of the shown review to the summarizer set in rev.kdl.
+    Summarize,
+    /// Opens a shell in the checkout of the shown review in a tmux or wezterm pane.
+    CheckoutPane,
+    /// Follows the checks of the shown review in a tmux or wezterm pane.
+    LogsPane,
+    /// Runs the verify command of the panes block in the checkout, in a tmux or wezterm pane.
+    VerifyPane,
+    /// A left click at the position, only sent when the mouse is enabled.
+    Click {
+        column: u16,
+        row: u16,
+    },
+    /// The mouse wheel turned at the position.
+    Scroll {
+        column: u16,
+        row: u16,
+        down: bool,
+    },
+    /// Suspends the ui to edit the draft in the editor of the user.
+    EditExternally(String),
+    /// The draft as saved in the editor.
+    EditedExternally(String),
+    /// Shows or hides the frame times and pending requests on top of the page.
+    ToggleProfiler,
+    /// Shows only the reviews passing the filter of the list at the index, on top of the other
+    /// filters toggled on, or stops filtering by it.
+    ToggleListFilter(usize),
+    /// The queue the list switched to, by the name of its saved search, `None` for the review
+    /// requests.
+    QueueShown(Option<String>),
+    /// The keybind profile now in use, `None` for the default keybinds.
+    KeybindProfile(Option<String>),
+    /// A text input has focus, so keys shouldn't trigger keybinds.
+    EnterInsertMode,
+    ExitInsertMode,
+    /// The review opened from the new review form, by its reference.
+    ReviewCreated(String),
+    /// Opening the review failed, with why.
+    ReviewCreationFailed(String),
+    /// The repositories fetched for the repositories page.
+    RepositoriesLoaded(Vec<RepositoryListItem>),
+    /// Shows the open reviews of the repository, in the form `owner/name`, on the list.
+    BrowseRepository(String),
+}
+
+impl Action {
+    /// The action bound by the name in the keybinds of rev.kdl, e.g. `approve`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let action = match name {
+            "quit" => Action::Quit,
+            "refresh" => Action::Refresh,
+            "begin-review" => Action::BeginReview,
+            "skip-review" => Action::SkipReview,
+            "snooze-review" => Action::SnoozeReview,
+            "snoozed" => Action::TogglePage("snoozed".into()),
+            "repositories" => Action::TogglePage("repositories".into()),
+            "wait-for-checks" => Action::SnoozeUntilChecksComplete,
+            "toggle-comment-filters" => Action::ToggleCommentFilters,
+            "next-comment" => Action::NextComment,
+            "previous-comment" => Action::PreviousComment,
+            "edit-comment" => Action::EditComment,
+            "delete-comment" => Action::DeleteComment,
+            "select-next" => Action::SelectNext,
+            "select-previous" => Action::SelectPrevious,
+            "move-up" => Action::MoveUp,
+            "move-down" => Action::MoveDown,
+            "toggle-pin" => Action::TogglePin,
+            "toggle-star" => Action::ToggleStar,
+            "history" => Action::TogglePage("history".into()),
+            "discussions" => Action::TogglePage("discussions".into()),
+            "edit-search" => Action::EditSearch,
+            "filter-list" => Action::FilterList,
+            "next-queue" => Action::NextQueue,
+            "toggle-density" => Action::ToggleDensity,
+            "sort-by-size" => Action::SortBySize,
+            "toggle-stats" => Action::ToggleStats,
+            "open" => Action::Open,
+            "back" => Action::Back,
+            "comment" => Action::AddComment,
+            "approve" => Action::Approve,
+            "request-changes" => Action::RequestChanges,
+            "merge" => Action::MergeReview,
+            "next-link" => Action::NextLink,
+            "previous-link" => Action::PreviousLink,
+            "diff-since-last-visit" => Action::DiffSinceLastVisit,
+            "range-diff" => Action::RangeDiff,
+            "open-files" => Action::OpenFiles,
+            "request-description" => Action::RequestDescription,
+            "request-commit-fixes" => Action::RequestCommitFixes,
+            "next-comments-tab" => Action::NextCommentsTab,
+            "next-pane" => Action::NextPane,
+            "previous-pane" => Action::PreviousPane,
+            "page-down" => Action::PageDown,
+            "page-up" => Action::PageUp,
+            "half-page-down" => Action::HalfPageDown,
+            "half-page-up" => Action::HalfPageUp,
+            "next-thread" => Action::NextThread,
+            "previous-thread" => Action::PreviousThread,
+            "reply-to-thread" => Action::ReplyToThread,
+            "resolve-thread" => Action::ResolveThread,
+            "copy-comment-link" => Action::CopyCommentLink,
+            "copy-thread-link" => Action::CopyThreadLink,
+            "reassign-review" => Action::ReassignReview,
+            "summarize" => Action::Summarize,
+            "open-in-browser" => Action::OpenInBrowser,
+            "checkout-pane" => Action::CheckoutPane,
+            "logs-pane" => Action::LogsPane,
+            "verify-pane" => Action::VerifyPane,
+            "toggle-profiler" => Action::ToggleProfiler,
+            _ => match name.strip_prefix("toggle-filter-").map(str::parse::<usize>) {
+                Some(Ok(n @ 1..=9)) => Action::ToggleListFilter(n - 1),
+                _ => return None,
+            },
+        };
+
+        Some(action)
+    }
+
+    /// Whether a count prefix repeats the action, e.g. `5j` to move five reviews down.
+    pub fn is_motion(&self) -> bool {
+        matches!(
+            self,
+            Action::SelectNext
+                | Action::SelectPrevious
+                | Action::MoveUp
+                | Action::MoveDown
+                | Action::NextComment
+                | Action::PreviousComment
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum GitHubPrAction {
+    Normal,
+    EnterProcessing,
+    /// A page of the reviews of the queue, by the name of its saved search.
+    AddReviews {
+        queue: Option<String>,
+        items: Vec<ReviewListItem>,
+        /// Whether the page is the first of a fetch, replacing the reviews fetched before.
+        replace: bool,
+    },
+    NextReview {
+        pr: Box<Review>,
+    },
+    DoneReview,
+    ExitProcessing,
+    ReviewComments {
+        id: String,
+        comments: Option<Comments>,
+    },
+    ReviewStatusChecks {
+        id: String,
+        checks: Option<Vec<StatusCheck>>,
+    },
+    ReviewCommits {
+        id: String,
+        commits: Vec<Commit>,
+    },
+    /// The head of the review changed while it was shown, e.g. because it was force-pushed.
+    HeadChanged {
+        id: String,
+        head: String,
+    },
+    /// The known vulnerabilities fixed by a dependency update review.
+    SecurityFixes {
+        id: String,
+        fixes: Vec<Vulnerability>,
+    },
+    /// The merge of the review finished, with the message to show in the status bar.
+    MergeFinished {
+        id: String,
+        message: String,
+    },
+    /// The description of a review is empty or misses sections of the template.
+    DescriptionLint {
+        id: String,
+        lint: DescriptionLint,
+    },
+    /// A search was edited and ran successfully, so it can be saved.
+    SearchValidated {
+        search: SavedSearch,
+        count: usize,
+    },
+    /// The badges computed from the paths a review changes, along with the paths.
+    FileBadges {
+        id: String,
+        badges: Vec<FileBadge>,
+        paths: Vec<String>,
+    },
+    /// What the summarizer made of the conversation of a review.
+    Summary {
+        id: String,
+        summary: String,
+    },
+    /// The api budget left after fetching the queue, shown in the status bar.
+    RateLimit(RateLimit),
+    /// Fetching the queue failed, e.g. as the token lacks a scope, which the list shows.
+    QueueFailed {
+        queue: Option<String>,
+        message: String,
+    },
+    /// Fetching the reviews to go through failed, which the review shows.
+    ReviewsFailed(String),
+}
+
+impl PartialEq for GitHubPrAction {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Normal, Self::Normal)
+                | (Self::EnterProcessing, Self::EnterProcessing)
+                | (Self::ExitProcessing, Self::ExitProcessing)
+                | (
+                    GitHubPrAction::AddReviews { .. },
+                    GitHubPrAction::AddReviews { .. }
+                )
+        )
+    }
+}
+
+impl Eq for GitHubPrAction {}
+
+#[derive(Debug, Clone)]
+pub enum DiscussionAction {
+    Items { items: Vec<ReviewListItem> },
+    Issue { issue: Box<Issue> },
+}
+
+impl PartialEq for DiscussionAction {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Items { .. }, Self::Items { .. }) | (Self::Issue { .. }, Self::Issue { .. })
+        )
+    }
+}
+
+impl Eq for DiscussionAction {}
@@ -0,0 +1,501 @@
+use std::time::Instant;
+
+use crossterm::event::{KeyEvent, MouseButton, MouseEventKind};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::{
+    action::Action,
+    application_config::ApplicationSettings,
+    components::{
+        diff::GitDiff,
+        discussions::Discussions,
+        effect::{EffectRunner, Update},
+        files::Files,
+        github_pr::GithubPr,
+        github_prs::GithubPrs,
+        history::History,
+        home::Home,
+        new_review::NewReviewForm,
+        profiler::{Profiler, Timings},
+        repositories::Repositories,
+        snoozed::Snoozed,
+        toast::Toast,
+        Component,
+    },
+    config::{Config, Keybinds},
+    git_issues::GitIssues,
+    git_pull_requests::{GitPullRequest, GitPullRequests},
+    i18n::{tr, Text},
+    links::ReviewTarget,
+    list_filters,
+    new_review::BranchReview,
+    page::Page,
+    platform, self_update,
+    state::State,
+    store::Store,
+    tui,
+};
+
+/// The most a count prefix repeats a motion, so a typo doesn't flood the actions.
+const MAX_COUNT: usize = 999;
+
+pub struct App {
+    config: Config,
+    tick_rate: f64,
+    frame_rate: f64,
+    should_quit: bool,
+    insert_mode: bool,
+    /// The count typed before a motion, e.g. the `5` of `5j`.
+    count: Option<usize>,
+    pages: Vec<Page>,
+    overlays: Vec<Box<dyn Component>>,
+    current_page: Option<String>,
+    /// Where to return to when leaving a page toggled on top, such as the history.
+    previous_page: Option<String>,
+    /// The reviews and the session shared by the pages.
+    state: State,
+    /// The keybind profile in use, `None` for the default keybinds.
+    keybind_profile: Option<String>,
+    /// The review to open from the form shown instead of the list, see [`App::new_review`].
+    new_review: Option<BranchReview>,
+    /// The review shown instead of the list, see [`App::review_target`].
+    review_target: Option<ReviewTarget>,
+    timings: Timings,
+}
+
+impl App {
+    pub fn new(tick_rate: f64, frame_rate: f64) -> Self {
+        Self {
+            tick_rate,
+            frame_rate,
+            config: Config::load(&ApplicationSettings::default().config_file_path())
+                .unwrap_or_else(|e| {
+                    tracing::warn!("failed to load tui config, using defaults: {e:#}");
+                    Config::default()
+                }),
+            should_quit: false,
+            insert_mode: false,
+            count: None,
+            pages: Vec::new(),
+            overlays: vec![Box::new(Toast::new())],
+            current_page: None,
+            previous_page: None,
+            state: State::default(),
+            keybind_profile: None,
+            new_review: None,
+            review_target: None,
+            timings: Timings::default(),
+        }
+    }
+
+    pub fn committer(mut self, committer: Option<String>) -> Self {
+        self.config.committer = committer;
+        self
+    }
+
+    /// Uses the provider and queue of the profile in rev.kdl instead of those next to it.
+    pub fn profile(mut self, profile: Option<&str>) -> anyhow::Result<Self> {
+        self.config = self.config.with_profile(profile)?;
+        Ok(self)
+    }
+
+    /// Reaches github at the graphql endpoint instead of the one in rev.kdl.
+    pub fn github_api_url(mut self, url: Option<&str>) -> Self {
+        self.config = self.config.with_github_api_url(url);
+        self
+    }
+
+    /// Overrides which review requests are queued, e.g. from the command line, where unset
+    /// values keep those of the config.
+    pub fn review_query(
+        mut self,
+        reviewer: Option<String>,
+        org: Option<String>,
+        labels: Vec<String>,
+    ) -> Self {
+        self.config.queue = self.config.queue.overridden(reviewer, org, labels);
+        self
+    }
+
+    /// Starts on the review, and at the comment the target links to, rather than on the list of
+    /// review requests.
+    pub fn review_target(mut self, target: Option<ReviewTarget>) -> Self {
+        self.review_target = target;
+        self
+    }
+
+    /// Starts on a form for opening the review, rather than on the list of review requests.
+    pub fn new_review(mut self, review: BranchReview) -> Self {
+        self.new_review = Some(review);
+        self
+    }
+
+    fn get_current_page(&mut self) -> Option<&mut Page> {
+        if let Some(page) = self.current_page.as_ref() {
+            return self.pages.iter_mut().find(|p| p.name() == page);
+        }
+
+        None
+    }
+
+    fn draw(&mut self, f: &mut tui::Frame<'_>, action_tx: &UnboundedSender<Action>) {
+        if let Some(page) = self.get_current_page() {
+            if let Err(e) = page.draw(f) {
+                action_tx
+                    .send(Action::Error(format!("failed to draw {:?}", e)))
+                    .expect("to send error message");
+            }
+        }
+
+        for overlay in self.overlays.iter_mut() {
+            if let Err(e) = overlay.draw(f, f.size()) {
+                action_tx
+                    .send(Action::Error(format!("failed to draw {:?}", e)))
+                    .expect("to send error message");
+            }
+        }
+    }
+
+    fn render(
+        &mut self,
+        tui: &mut tui::Tui,
+        action_tx: &UnboundedSender<Action>,
+    ) -> anyhow::Result<()> {
+        let started = Instant::now();
+        tui.draw(|f| self.draw(f, action_tx))?;
+        self.timings.frame(started.elapsed());
+
+        Ok(())
+    }
+
+    pub async fn register_pages(&mut self) -> anyhow::Result<&mut Self> {
+        let git_provider = self.config.provider.connect()?;
+        let store = Store::default();
+        let git_pull_requests = GitPullRequests::new(git_provider.clone())
+            .queue(self.config.queue.clone())
+            .starred(self.config.starred.clone())
+            .store(store.clone());
+        let git_pull_request = GitPullRequest::new(
+            git_provider.clone(),
+            git_pull_requests.clone(),
+            store.clone(),
+        );
+
+        self.pages
+            .push(Page::new("home", vec![Box::new(Home::new())]));
+        self.pages
+            .push(Page::new("diff", vec![Box::new(GitDiff::new())]));
+        self.pages.push(Page::new(
+            "github_review_list",
+            vec![Box::new(GithubPrs::new(
+                git_pull_requests.clone(),
+                store.clone(),
+                self.state.clone(),
+            ))],
+        ));
+        self.pages.push(Page::new(
+            "discussions",
+            vec![Box::new(Discussions::new(GitIssues::new(
+                git_provider.clone(),
+                store.clone(),
+            )))],
+        ));
+        self.pages.push(Page::new(
+            "history",
+            vec![Box::new(History::new(store.clone()))],
+        ));
+        self.pages.push(Page::new(
+            "snoozed",
+            vec![Box::new(Snoozed::new(store.clone()))],
+        ));
+        self.pages.push(Page::new(
+            "repositories",
+            vec![Box::new(Repositories::new(
+                git_provider.clone(),
+                store.clone(),
+            ))],
+        ));
+        self.pages
+            .push(Page::new("files", vec![Box::new(Files::new())]));
+        self.pages.push(Page::new(
+            "github_review",
+            vec![Box::new(
+                GithubPr::new(git_pull_request, self.state.clone())
+                    .focus(self.review_target.clone()),
+            )],
+        ));
+        //self.current_page = Some(home.clone());
+        self.current_page = Some("github_review_list".into());
+        if self.review_target.is_some() {
+            self.current_page = Some("github_review".into());
+        }
+        if let Some(review) = self.new_review.take() {
+            self.pages.push(Page::new(
+                "new_review",
+                vec![Box::new(NewReviewForm::new(
+                    review,
+                    git_provider.clone(),
+                    store,
+                ))],
+            ));
+            self.current_page = Some("new_review".into());
+        }
+
+        self.overlays
+            .push(Box::new(Profiler::new(self.timings.clone(), git_provider)));
+
+        Ok(self)
+    }
+
+    fn keybinds(&self) -> &Keybinds {
+        self.config
+            .keybind_profiles
+            .get(self.keybind_profile.as_deref())
+            .unwrap_or(&self.config.keybinds)
+    }
+
+    /// The filter of the list toggled by the key. Digits bound to an action keep it, and a count
+    /// which is already started keeps taking digits.
+    fn list_filter_key(&self, key: KeyEvent) -> Option<usize> {
+        if self.current_page.as_deref() != Some("github_review_list")
+            || self.count.is_some()
+            || self.keybinds().contains_key(&vec![key])
+        {
+            return None;
+        }
+
+        list_filters::filter_key(&self.config.filters, key)
+    }
+
+    /// Switches to the keybind profile of the current page and queue, telling the page which is
+    /// in use when either changed.
+    fn switch_keybind_profile(
+        &mut self,
+        page_changed: bool,
+        action_tx: &UnboundedSender<Action>,
+    ) -> anyhow::Result<()> {
+        let profile = self
+            .config
+            .keybind_profiles
+            .active(
+                self.current_page.as_deref(),
+                self.state.read().session.queue.as_deref(),
+            )
+            .map(|p| p.to_string());
+
+        if page_changed || profile != self.keybind_profile {
+            self.keybind_profile = profile.clone();
+            action_tx.send(Action::KeybindProfile(profile))?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn run(&mut self) -> anyhow::Result<()> {
+        let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+        tracing::debug!("terminal capabilities: {:?}", self.config.terminal);
+
+        let mut tui = tui::Tui::new()?
+            .tick_rate(self.tick_rate)
+            .frame_rate(self.frame_rate)
+            .mouse(!self.config.disable_mouse)
+            .synchronized(self.config.terminal.synchronized);
+        tui.enter()?;
+
+        for page in self.pages.iter_mut() {
+            page.apply(|c| {
+                c.register_action_handler(action_tx.clone())?;
+                c.register_config_handler(self.config.clone())
+            })?;
+        }
+
+        for overlay in self.overlays.iter_mut() {
+            overlay.register_action_handler(action_tx.clone())?;
+            overlay.register_config_handler(self.config.clone())?;
+        }
+
+        for page in self.pages.iter_mut() {
+            page.apply(|c| c.init())?;
+        }
+        let effects = EffectRunner::new(action_tx.clone());
+
+        if self.config.update.check {
+            let settings = self.config.update.clone();
+            let tx = action_tx.clone();
+            tokio::spawn(async move {
+                match self_update::check(&settings, &Store::default()).await {
+                    Ok(Some(version)) => {
+                        let _ = tx.send(Action::Toast(format!(
+                            "{} {version}",
+                            tr(Text::UpdateAvailable)
+                        )));
+                    }
+                    Ok(None) => {}
+                    // Being offline shouldn't get in the way of reviewing
+                    Err(e) => tracing::debug!("failed to check for updates: {e:#}"),
+                }
+            });
+        }
+
+        loop {
+            let event = tui.next().await;
+            let received = event.as_ref().map(|_| Instant::now());
+            if let Some(e) = event {
+                match e {
+                    tui::Event::Init => {
+                        tracing::info!("sent init event");
+                        let page = self
+                            .current_page
+                            .clone()
+                            .unwrap_or("github_review_list".into());
+                        action_tx.send(Action::GotoPage(page))?
+                    }
+                    tui::Event::Quit => action_tx.send(Action::Quit)?,
+                    tui::Event::Key(_) if self.insert_mode => {}
+                    tui::Event::Key(key) => {
+                        if let Some(index) = self.list_filter_key(key) {
+                            action_tx.send(Action::ToggleListFilter(index))?;
+                        } else if let Some(digit) =
+                            self.keybinds().count_digit(key, self.count.is_some())
+                        {
+                            let count = self.count.unwrap_or_default() * 10 + digit;
+                            self.count = Some(count.min(MAX_COUNT));
+                        } else {
+                            let count = self.count.take().unwrap_or(1);
+                            if let Some(action) = self
+                                .keybinds()
+                                .action(self.current_page.as_deref(), &vec![key])
+                            {
+                                tracing::info!("got action: {action:?}");
+                                let times = if action.is_motion() { count } else { 1 };
+                                for _ in 0..times {
+                                    action_tx.send(action.clone())?;
+                                }
+                            }
+                        }
+                    }
+                    tui::Event::Mouse(_) if self.insert_mode => {}
+                    tui::Event::Mouse(mouse) => {
+                        let (column, row) = (mouse.column, mouse.row);
+                        match mouse.kind {
+                            MouseEventKind::Down(MouseButton::Left) => {
+                                action_tx.send(Action::Click { column, row })?
+                            }
+                            MouseEventKind::ScrollDown => action_tx.send(Action::Scroll {
+                                column,
+                                row,
+                                down: true,
+                            })?,
+                            MouseEventKind::ScrollUp => action_tx.send(Action::Scroll {
+                                column,
+                                row,
+                                down: false,
+                            })?,
+                            _ => {}
+                        }
+                    }
+                    tui::Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
+                    tui::Event::Tick => action_tx.send(Action::Tick)?,
+                    tui::Event::Render => action_tx.send(Action::Render)?,
+                    _ => {}
+                }
+                for page in self.pages.iter_mut() {
+                    page.apply(|c| {
+                        effects.run(Update {
+                            action: c.handle_events(Some(e.clone()))?,
+                            effects: c.take_effects(),
+                        })
+                    })?;
+                }
+            }
+
+            while let Ok(action) = action_rx.try_recv() {
+                if action != Action::Tick && action != Action::Render {
+                    tracing::debug!("{action:?}");
+                }
+
+                self.state.reduce(&action);
+                let page = self.current_page.clone();
+                match action {
+                    Action::GotoPage(ref page) => {
+                        self.current_page = Some(page.clone());
+                    }
+                    // The draw resizes to the terminal, as part of the frame
+                    Action::Resize(..) => self.render(&mut tui, &action_tx)?,
+                    Action::Suspend => tui.suspend()?,
+                    Action::Resume => tui.resume()?,
+                    Action::EditExternally(ref text) => {
+                        tui.suspend()?;
+                        let edited = platform::edit_in_editor(text);
+                        tui.resume()?;
+
+                        match edited {
+                            Ok(text) => action_tx.send(Action::EditedExternally(text))?,
+                            Err(e) => action_tx.send(Action::Error(format!("{e:#}")))?,
+                        }
+                    }
+                    Action::Quit => self.should_quit = true,
+                    Action::EnterInsertMode => {
+                        self.insert_mode = true;
+                        self.count = None;
+                    }
+                    Action::ExitInsertMode => self.insert_mode = false,
+                    Action::Render => self.render(&mut tui, &action_tx)?,
+                    // The diff page picks up the range below, as it's now the current page
+                    Action::ShowDiff { .. } if self.current_page.as_deref() != Some("diff") => {
+                        self.previous_page = self.current_page.replace("diff".into());
+                    }
+                    Action::ShowFiles { .. } if self.current_page.as_deref() != Some("files") => {
+                        self.previous_page = self.current_page.replace("files".into());
+                    }
+                    Action::BeginReview => {
+                        action_tx.send(Action::GotoPage("github_review".into()))?;
+                    }
+                    Action::TogglePage(ref page) => {
+                        if self.current_page.as_ref() == Some(page) {
+                            // Going back directly, as pages start over on GotoPage
+                            if let Some(page) = self.previous_page.take() {
+                                self.current_page = Some(page);
+                            }
+                        } else {
+                            self.previous_page = self.current_page.clone();
+                            action_tx.send(Action::GotoPage(page.clone()))?;
+                        }
+                    }
+                    _ => {}
+                }
+                if !matches!(action, Action::Tick | Action::Render) {
+                    self.switch_keybind_profile(page != self.current_page, &action_tx)?;
+                }
+
+                for overlay in self.overlays.iter_mut() {
+                    effects.run(overlay.handle(action.clone())?)?;
+                }
+
+                if let Some(page) = self.get_current_page() {
+                    page.apply(|c| effects.run(c.handle(action.clone())?))?;
+                }
+            }
+
+            if let Some(received) = received {
+                self.timings.event(received.elapsed());
+            }
+
+            if self.should_quit {
+                effects.cancel_all();
+                tui.stop()?;
+                break;
+            }
+        }
+
+        tui.exit()?;
+
+        Ok(())
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new(10.0, 64.0)
+    }
+}
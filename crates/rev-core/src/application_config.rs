@@ -17,18 +17,16 @@ impl ApplicationSettings {
     }
 }
 
+impl ApplicationSettings {
+    pub fn config_file_path(&self) -> PathBuf {
+        self.config_home
+            .join(format!("rev.{}", self.config_file_ext))
+    }
+}
+
 impl Default for ApplicationSettings {
     fn default() -> Self {
-        let project_home = std::env::var("REV_CONFIG_HOME")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| {
-                let project = directories::ProjectDirs::from("io", "kjuulh", "rev")
-                    .expect("to be able to find XDG home variables");
-
-                project.config_dir().to_path_buf()
-            });
-
-        Self::new(project_home, "kdl")
+        Self::new(crate::platform::config_dir(), "kdl")
     }
 }
 
@@ -62,9 +60,61 @@ impl Deref for ApplicationConfig {
 
 #[cfg(test)]
 mod test {
+    use std::{collections::HashMap, path::PathBuf};
+
     use kdl::KdlDocument;
+    use rev_config::{ConfigFile, Env};
+    use rev_config_derive::AppConfig;
+
+    #[derive(AppConfig, Clone, Debug)]
+    pub struct TypedConfig {
+        pub theme_path: PathBuf,
+        pub verbose: bool,
+        pub page_size: u16,
+        pub reviewers: Vec<String>,
+    }
+
+    #[test]
+    fn test_derive_parses_typed_fields() -> anyhow::Result<()> {
+        let file = std::env::temp_dir().join(format!("rev-typed-{}.kdl", std::process::id()));
+        std::fs::write(
+            &file,
+            r#"config {
+    theme_path "/tmp/theme.kdl"
+    verbose true
+    page_size 25
+    reviewers "kjuulh" "octocat"
+}"#,
+        )?;
+
+        let mut config = typed_config::TypedConfig::default();
+        config.set_from_config_file(&file)?;
+        std::fs::remove_file(&file)?;
+
+        let mut env = HashMap::from([
+            ("REV_PAGE_SIZE", "50".to_string()),
+            ("REV_REVIEWERS", "a, b".to_string()),
+        ]);
+        config.set_from_env_with(|key| env.get(key).cloned())?;
+        env.insert("REV_VERBOSE", "maybe".to_string());
+        let error = config
+            .set_from_env_with(|key| env.get(key).cloned())
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .contains("REV_VERBOSE must be a valid bool"));
+        let config = TypedConfig::from(config)?;
+        assert_eq!(PathBuf::from("/tmp/theme.kdl"), config.theme_path);
+        assert!(config.verbose);
+        assert_eq!(50, config.page_size);
+        assert_eq!(vec!["a".to_string(), "b".to_string()], config.reviewers);
+
+        Ok(())
+    }
 
     #[tokio::test]
+    #[allow(unreachable_code)]
     async fn test_can_parse_kdl() -> anyhow::Result<()> {
         let project = directories::ProjectDirs::from("io", "kjuulh", "rev")
             .expect("to be able to find XDG home variables");
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+const AUDIT_DOCUMENT: &str = "audit";
+/// The oldest entries are dropped beyond this, to keep the document small.
+const MAX_ENTRIES: usize = 1000;
+
+/// A change made to a review through rev.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    /// What made the change, e.g. `bot`.
+    pub source: String,
+    pub action: String,
+    /// The review in the form `owner/name#number`.
+    pub review: String,
+    pub parameters: BTreeMap<String, String>,
+    /// Set when the change failed.
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(source: &str, action: &str, review: impl Into<String>) -> Self {
+        Self {
+            at: Utc::now(),
+            source: source.into(),
+            action: action.into(),
+            review: review.into(),
+            parameters: BTreeMap::new(),
+            error: None,
+        }
+    }
+
+    pub fn parameter(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.parameters.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn result<T>(mut self, result: &anyhow::Result<T>) -> Self {
+        self.error = result.as_ref().err().map(|e| format!("{e:#}"));
+        self
+    }
+
+    /// The parameters, and the error if the change failed, on a single line.
+    pub fn details(&self) -> String {
+        let mut details = self
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{name}={}", value.replace('\n', " ")))
+            .collect::<Vec<_>>();
+        if let Some(error) = self.error.as_ref() {
+            details.push(format!("failed: {error}"));
+        }
+
+        details.join(" ")
+    }
+
+    pub fn render(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.at.format("%Y-%m-%d %H:%M:%S"),
+            self.source,
+            self.action,
+            self.review,
+            self.details()
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(AUDIT_DOCUMENT)
+    }
+
+    pub fn record(store: &Store, entry: AuditEntry) -> anyhow::Result<()> {
+        let mut log = Self::load(store)?;
+        log.entries.push(entry);
+        if log.entries.len() > MAX_ENTRIES {
+            log.entries.drain(..log.entries.len() - MAX_ENTRIES);
+        }
+
+        store.save(AUDIT_DOCUMENT, &log)
+    }
+
+    /// Records an entry from within the tui, where failing to write it shouldn't stop anything.
+    pub fn record_or_warn(store: &Store, entry: AuditEntry) {
+        if let Err(e) = Self::record(store, entry) {
+            tracing::warn!("failed to record audit entry: {e:#}");
+        }
+    }
+
+    /// The latest entries, newest first.
+    pub fn latest(&self, limit: usize) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter().rev().take(limit)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AuditEntry, AuditLog};
+    use crate::store::Store;
+
+    #[test]
+    fn test_record_appends() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("rev-audit-test-{}", std::process::id()));
+        let store = Store::new(&dir);
+
+        AuditLog::record(
+            &store,
+            AuditEntry::new("bot", "approve", "kjuulh/rev#1").parameter("rule", "deps"),
+        )?;
+        AuditLog::record(
+            &store,
+            AuditEntry::new("bot", "auto-merge", "kjuulh/rev#1")
+                .result(&Err::<(), _>(anyhow::anyhow!("not allowed"))),
+        )?;
+
+        let log = AuditLog::load(&store)?;
+        assert_eq!(2, log.entries.len());
+        assert_eq!(
+            Some("deps"),
+            log.entries[0].parameters.get("rule").map(|s| s.as_str())
+        );
+        assert_eq!(Some("not allowed".into()), log.entries[1].error);
+        assert!(log
+            .latest(1)
+            .next()
+            .unwrap()
+            .render()
+            .ends_with("bot auto-merge kjuulh/rev#1 failed: not allowed"));
+
+        std::fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,716 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use kdl::{KdlDocument, KdlNode};
+use rev_git_provider::{
+    models::{CurrentState, Review, ReviewEvent},
+    GitProvider,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    config::{kdl_strings, kdl_value},
+    git_pull_requests::{fetch_reviews, search_all},
+    glob,
+    labels::{self, LabelRule},
+    metrics::Metrics,
+    store::Store,
+};
+
+const BOT_DOCUMENT: &str = "bot";
+/// How many actions taken are remembered, the oldest are forgotten first.
+const MAX_TAKEN: usize = 1000;
+
+/// A rule the bot evaluates against every review in the queue, the first matching rule wins.
+///
+/// ```kdl
+/// bot {
+///     rule "dependency bumps" {
+///         authors "dependabot[bot]" "renovate[bot]"
+///         files "**/Cargo.lock" "**/package-lock.json"
+///         checks-green true
+///         then {
+///             approve "lockfile only, checks are green"
+///             auto-merge
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotRule {
+    pub name: String,
+    /// Globs of which one must match the author, any author matches when empty.
+    pub authors: Vec<String>,
+    /// Globs of which one must match every changed file, any change matches when empty.
+    pub files: Vec<String>,
+    pub checks_green: bool,
+    pub actions: Vec<BotAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BotAction {
+    Approve(Option<String>),
+    Comment(String),
+    AutoMerge,
+}
+
+impl BotAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BotAction::Approve(_) => "approve",
+            BotAction::Comment(_) => "comment",
+            BotAction::AutoMerge => "auto-merge",
+        }
+    }
+
+    async fn perform(&self, provider: &GitProvider, review: &Review) -> anyhow::Result<()> {
+        match self {
+            BotAction::Approve(body) => {
+                provider
+                    .submit_review(review.id.clone(), ReviewEvent::Approve, body.clone())
+                    .await
+            }
+            BotAction::Comment(body) => provider.add_comment(review.id.clone(), body.clone()).await,
+            BotAction::AutoMerge => provider.enable_auto_merge(review.id.clone()).await,
+        }
+    }
+}
+
+impl BotRule {
+    pub fn from_kdl(node: &KdlNode) -> anyhow::Result<Self> {
+        let name = node
+            .entries()
+            .first()
+            .and_then(|e| e.value().as_string())
+            .context("bot rules must be named, e.g. rule \"dependency bumps\" {}")?
+            .to_string();
+        let empty = KdlDocument::new();
+        let doc = node.children().unwrap_or(&empty);
+
+        let checks_green = match kdl_value(doc, "checks-green") {
+            Some(value) => value
+                .as_bool()
+                .with_context(|| format!("bot rule {name}: checks-green must be true or false"))?,
+            None => false,
+        };
+
+        let actions = doc
+            .get("then")
+            .and_then(|n| n.children())
+            .map(|then| {
+                then.nodes()
+                    .iter()
+                    .map(|action| {
+                        let text = action
+                            .entries()
+                            .first()
+                            .and_then(|e| e.value().as_string())
+                            .map(|s| s.to_string());
+
+                        match (action.name().value(), text) {
+                            ("approve", text) => Ok(BotAction::Approve(text)),
+                            ("comment", Some(text)) => Ok(BotAction::Comment(text)),
+                            ("auto-merge", _) => Ok(BotAction::AutoMerge),
+                            (other, _) => anyhow::bail!("bot rule {name}: unknown action {other}"),
+                        }
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        if actions.is_empty() {
+            anyhow::bail!("bot rule {name}: needs at least one action in a then block");
+        }
+
+        Ok(Self {
+            authors: kdl_strings(doc, "authors")?.unwrap_or_default(),
+            files: kdl_strings(doc, "files")?.unwrap_or_default(),
+            checks_green,
+            actions,
+            name,
+        })
+    }
+
+    fn matches_author(&self, author: &str) -> bool {
+        self.authors.is_empty() || self.authors.iter().any(|a| glob::matches(a, author))
+    }
+
+    fn matches_files(&self, files: &[String]) -> bool {
+        self.files.is_empty()
+            || (!files.is_empty()
+                && files
+                    .iter()
+                    .all(|f| self.files.iter().any(|p| glob::matches(p, f))))
+    }
+}
+
+/// Whether every check finished successfully, a review without checks isn't considered green.
+fn checks_green(review: &Review) -> bool {
+    let checks = review.status_checks.as_deref().unwrap_or_default();
+
+    !checks.is_empty()
+        && checks
+            .iter()
+            .all(|c| matches!(c.current(), CurrentState::Success))
+}
+
+/// The actions the bot took, keyed by the review id, the rule, the head of the review and the
+/// action, so later runs don't comment or approve again until the review is pushed to.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+struct TakenActions {
+    actions: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl TakenActions {
+    fn key(review: &Review, rule: &str, action: &BotAction) -> String {
+        format!("{} {rule} {} {}", review.id, review.head_sha, action.name())
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        self.actions.contains_key(key)
+    }
+
+    fn record(&mut self, key: String) {
+        self.actions.insert(key, Utc::now());
+
+        while self.actions.len() > MAX_TAKEN {
+            let oldest = self
+                .actions
+                .iter()
+                .min_by_key(|(_, at)| **at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => self.actions.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+}
+
+/// What the bot did, or would do in a dry run, for a review.
+#[derive(Debug, Clone)]
+pub struct BotOutcome {
+    /// The review in the form `owner/name#number`.
+    pub review: String,
+    pub title: String,
+    pub rule: Option<String>,
+    pub actions: Vec<(BotAction, Option<String>)>,
+}
+
+pub async fn run(
+    provider: GitProvider,
+    store: Store,
+    rules: &[BotRule],
+    label_rules: &[LabelRule],
+    query: &str,
+    dry_run: bool,
+) -> anyhow::Result<Vec<BotOutcome>> {
+    let reviews = queue(&provider, query).await?;
+    apply(&provider, &store, rules, label_rules, reviews, dry_run).await
+}
+
+/// Runs the bot every interval until it's stopped, handing each round to `on_round` and
+/// recording it in the metrics. A failed round is retried on the next interval.
+#[allow(clippy::too_many_arguments)]
+pub async fn watch(
+    provider: GitProvider,
+    store: Store,
+    rules: &[BotRule],
+    label_rules: &[LabelRule],
+    query: &str,
+    dry_run: bool,
+    interval: Duration,
+    metrics: &Metrics,
+    mut on_round: impl FnMut(anyhow::Result<Vec<BotOutcome>>),
+) {
+    loop {
+        on_round(
+            round(
+                &provider,
+                &store,
+                rules,
+                label_rules,
+                query,
+                dry_run,
+                metrics,
+            )
+            .await,
+        );
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// A single round of [`watch`], recorded in the metrics.
+async fn round(
+    provider: &GitProvider,
+    store: &Store,
+    rules: &[BotRule],
+    label_rules: &[LabelRule],
+    query: &str,
+    dry_run: bool,
+    metrics: &Metrics,
+) -> anyhow::Result<Vec<BotOutcome>> {
+    let started = Instant::now();
+    let round = match queue(provider, query).await {
+        Ok(reviews) => {
+            metrics.record_fetch(started.elapsed(), reviews.len());
+            apply(provider, store, rules, label_rules, reviews, dry_run).await
+        }
+        Err(e) => Err(e),
+    };
+
+    let errors = match &round {
+        Ok(outcomes) => outcomes
+            .iter()
+            .flat_map(|o| &o.actions)
+            .filter(|(_, error)| error.is_some())
+            .count(),
+        Err(_) => 1,
+    };
+    metrics.record_provider_errors(errors);
+    metrics.record_round(&provider.stats());
+
+    round
+}
+
+/// The reviews the query matches, fetched in full.
+async fn queue(provider: &GitProvider, query: &str) -> anyhow::Result<Vec<Review>> {
+    let items = search_all(provider, query).await?;
+    let ids = items.into_iter().map(|i| i.id).collect::<Vec<_>>();
+
+    fetch_reviews(provider, ids).await
+}
+
+/// Performs the actions of the first rule matching each review, leaving out the ones already
+/// taken at the head of the review. Failed actions aren't recorded, so they're tried again.
+async fn apply(
+    provider: &GitProvider,
+    store: &Store,
+    rules: &[BotRule],
+    label_rules: &[LabelRule],
+    reviews: Vec<Review>,
+    dry_run: bool,
+) -> anyhow::Result<Vec<BotOutcome>> {
+    let mut taken: TakenActions = store.load(BOT_DOCUMENT)?;
+    let mut outcomes = Vec::new();
+    for mut review in reviews {
+        let (owner, name) = review
+            .owner_and_name()
+            .map(|(o, n)| (o.to_string(), n.to_string()))
+            .context("review repository to be in the form owner/name")?;
+        let label = review.reference();
+
+        // Files and checks are only fetched when some rule needs them
+        let mut files = None;
+        let mut rule = None;
+        for candidate in rules.iter().filter(|r| r.matches_author(&review.author)) {
+            if !candidate.files.is_empty() && files.is_none() {
+                files = Some(
+                    provider
+                        .get_changed_files(owner.clone(), name.clone(), review.number)
+                        .await?
+                        .into_iter()
+                        .map(|f| f.path)
+                        .collect::<Vec<_>>(),
+                );
+            }
+            if !candidate.matches_files(files.as_deref().unwrap_or_default()) {
+                continue;
+            }
+
+            if candidate.checks_green {
+                if review.status_checks.is_none() {
+                    review.status_checks = provider
+                        .get_status_checks(owner.clone(), name.clone(), review.number)
+                        .await?;
+                }
+                if !checks_green(&review) {
+                    continue;
+                }
+            }
+
+            rule = Some(candidate);
+            break;
+        }
+
+        let mut outcome = BotOutcome {
+            review: label.clone(),
+            title: review.title.clone(),
+            rule: rule.map(|r| r.name.clone()),
+            actions: Vec::new(),
+        };
+
+        for action in rule.iter().flat_map(|r| r.actions.iter()) {
+            let key =
+                TakenActions::key(&review, outcome.rule.as_deref().unwrap_or_default(), action);
+            if taken.contains(&key) {
+                continue;
+            }
+            if dry_run {
+                outcome.actions.push((action.clone(), None));
+                continue;
+            }
+
+            let result = action.perform(provider, &review).await;
+            let mut entry = AuditEntry::new("bot", action.name(), &label)
+                .parameter("rule", outcome.rule.clone().unwrap_or_default())
+                .parameter("author", &review.author)
+                .result(&result);
+            if let BotAction::Approve(Some(body)) | BotAction::Comment(body) = action {
+                entry = entry.parameter("body", body);
+            }
+            AuditLog::record(store, entry)?;
+            if result.is_ok() {
+                taken.record(key);
+                store.save(BOT_DOCUMENT, &taken)?;
+            }
+
+            if let (BotAction::Approve(_), Ok(())) = (action, &result) {
+                let labelled = labels::apply(
+                    provider,
+                    store,
+                    "bot",
+                    label_rules,
+                    &review,
+                    ReviewEvent::Approve,
+                )
+                .await;
+                if let Err(e) = labelled {
+                    tracing::warn!("failed to change labels of {label}: {e:#}");
+                }
+            }
+
+            outcome
+                .actions
+                .push((action.clone(), result.err().map(|e| format!("{e:#}"))));
+        }
+
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use kdl::KdlDocument;
+    use rev_git_provider::{
+        models::{
+            ChangedFile, Comments, Commit, Ecosystem, Issue, MergeStrategy, NewReview,
+            ProviderStats, RepositoryListItem, Review, ReviewEvent, ReviewList, ReviewListItem,
+            StatusCheck, TokenStatus, Vulnerability,
+        },
+        traits::{
+            GitAdvisory, GitAuth, GitComment, GitIssue, GitReview, GitReviewAction, GitUserReview,
+        },
+        GitProvider, Provider,
+    };
+
+    use super::{round, BotAction, BotRule};
+    use crate::{metrics::Metrics, store::Store};
+
+    /// A queue of a single review, recording the comments added to it.
+    struct FakeProvider {
+        comments: Arc<Mutex<Vec<String>>>,
+    }
+
+    fn review() -> Review {
+        Review {
+            id: "PR_1".into(),
+            number: 1,
+            title: "Bump serde".into(),
+            repository: "kjuulh/rev".into(),
+            url: String::new(),
+            description: String::new(),
+            head_sha: "abc".into(),
+            head_branch: "dependabot/serde".into(),
+            author: "dependabot[bot]".into(),
+            publish_at: None,
+            labels: Vec::new(),
+            additions: 1,
+            deletions: 1,
+            changed_files: 1,
+            comments: None,
+            status_checks: None,
+            participants: Vec::new(),
+        }
+    }
+
+    impl Provider for FakeProvider {
+        fn stats(&self) -> ProviderStats {
+            ProviderStats::default()
+        }
+    }
+
+    #[async_trait]
+    impl GitUserReview for FakeProvider {
+        async fn get_user_reviews(
+            &self,
+            _: Option<&str>,
+            _: Option<&str>,
+            _: Option<Vec<String>>,
+        ) -> anyhow::Result<ReviewList> {
+            unimplemented!()
+        }
+        async fn get_user_reviews_cursor(
+            &self,
+            _: Option<&str>,
+            _: Option<&str>,
+            _: Option<Vec<String>>,
+            _: Option<String>,
+        ) -> anyhow::Result<ReviewList> {
+            unimplemented!()
+        }
+        async fn search_reviews(&self, _: &str, _: Option<String>) -> anyhow::Result<ReviewList> {
+            Ok(ReviewList {
+                items: vec![ReviewListItem {
+                    id: review().id,
+                    ..Default::default()
+                }],
+                last_cursor: None,
+                has_more: false,
+            })
+        }
+        async fn count_search(&self, _: &str) -> anyhow::Result<usize> {
+            unimplemented!()
+        }
+        async fn get_repositories(&self, _: &str) -> anyhow::Result<Vec<RepositoryListItem>> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl GitReview for FakeProvider {
+        async fn get_review(
+            &self,
+            _: String,
+            _: String,
+            _: usize,
+        ) -> anyhow::Result<Option<Review>> {
+            unimplemented!()
+        }
+        async fn get_comments(
+            &self,
+            _: String,
+            _: String,
+            _: usize,
+        ) -> anyhow::Result<Option<Comments>> {
+            unimplemented!()
+        }
+        async fn get_status_checks(
+            &self,
+            _: String,
+            _: String,
+            _: usize,
+        ) -> anyhow::Result<Option<Vec<StatusCheck>>> {
+            unimplemented!()
+        }
+        async fn get_changed_files(
+            &self,
+            _: String,
+            _: String,
+            _: usize,
+        ) -> anyhow::Result<Vec<ChangedFile>> {
+            unimplemented!()
+        }
+        async fn get_diff(&self, _: String, _: String, _: usize) -> anyhow::Result<String> {
+            unimplemented!()
+        }
+        async fn get_reviews(&self, _: Vec<String>) -> anyhow::Result<Vec<Review>> {
+            Ok(vec![review()])
+        }
+        fn invalidate_review(&self, _: String, _: String, _: usize) {}
+        async fn get_head_sha(
+            &self,
+            _: String,
+            _: String,
+            _: usize,
+        ) -> anyhow::Result<Option<String>> {
+            unimplemented!()
+        }
+        async fn get_commits(&self, _: String, _: String, _: usize) -> anyhow::Result<Vec<Commit>> {
+            unimplemented!()
+        }
+        async fn get_review_template(
+            &self,
+            _: String,
+            _: String,
+        ) -> anyhow::Result<Option<String>> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl GitComment for FakeProvider {
+        async fn add_comment(&self, _: String, body: String) -> anyhow::Result<()> {
+            self.comments.lock().unwrap().push(body);
+            Ok(())
+        }
+        async fn update_comment(&self, _: String, _: String) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn delete_comment(&self, _: String) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn reply_to_thread(&self, _: String, _: String) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn resolve_thread(&self, _: String) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl GitReviewAction for FakeProvider {
+        async fn submit_review(
+            &self,
+            _: String,
+            _: ReviewEvent,
+            _: Option<String>,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn add_labels(
+            &self,
+            _: String,
+            _: String,
+            _: String,
+            _: Vec<String>,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn remove_labels(
+            &self,
+            _: String,
+            _: String,
+            _: String,
+            _: Vec<String>,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn enable_auto_merge(&self, _: String) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn merge_review(
+            &self,
+            _: String,
+            _: String,
+            _: usize,
+            _: MergeStrategy,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn create_review(&self, _: String, _: String, _: NewReview) -> anyhow::Result<usize> {
+            unimplemented!()
+        }
+        async fn reassign_reviewer(
+            &self,
+            _: String,
+            _: String,
+            _: usize,
+            _: String,
+            _: String,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl GitIssue for FakeProvider {
+        async fn search_issues(&self, _: &str, _: Option<String>) -> anyhow::Result<ReviewList> {
+            unimplemented!()
+        }
+        async fn get_issue(&self, _: String, _: String, _: usize) -> anyhow::Result<Option<Issue>> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl GitAdvisory for FakeProvider {
+        async fn get_vulnerabilities(
+            &self,
+            _: Ecosystem,
+            _: &str,
+        ) -> anyhow::Result<Vec<Vulnerability>> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait]
+    impl GitAuth for FakeProvider {
+        async fn get_token_status(&self) -> anyhow::Result<TokenStatus> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_takes_actions_once() -> anyhow::Result<()> {
+        let comments = Arc::<Mutex<Vec<String>>>::default();
+        let provider = GitProvider::custom(FakeProvider {
+            comments: comments.clone(),
+        });
+        let store = Store::in_memory();
+        let rules = vec![BotRule {
+            name: "dependency bumps".into(),
+            authors: Vec::new(),
+            files: Vec::new(),
+            checks_green: false,
+            actions: vec![BotAction::Comment("lockfile only".into())],
+        }];
+        let metrics = Metrics::default();
+
+        for _ in 0..2 {
+            let outcomes = round(&provider, &store, &rules, &[], "is:pr", false, &metrics).await?;
+            assert_eq!(1, outcomes.len());
+        }
+
+        assert_eq!(vec!["lockfile only".to_string()], *comments.lock().unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_and_match_rule() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+rule "dependency bumps" {
+    authors "dependabot[bot]"
+    files "**/Cargo.lock"
+    checks-green true
+    then {
+        approve
+        comment "approved by rev"
+        auto-merge
+    }
+}
+"#
+        .parse()?;
+
+        let rule = BotRule::from_kdl(doc.get("rule").unwrap())?;
+
+        assert!(rule.checks_green);
+        assert_eq!(
+            vec![
+                BotAction::Approve(None),
+                BotAction::Comment("approved by rev".into()),
+                BotAction::AutoMerge
+            ],
+            rule.actions
+        );
+        assert!(rule.matches_author("dependabot[bot]"));
+        assert!(!rule.matches_author("kjuulh"));
+        assert!(rule.matches_files(&["Cargo.lock".into(), "crates/rev/Cargo.lock".into()]));
+        assert!(!rule.matches_files(&["Cargo.lock".into(), "src/main.rs".into()]));
+        assert!(!rule.matches_files(&[]));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use rev_git_provider::models::{CurrentState, StatusCheck};
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+const CHECK_HISTORY_DOCUMENT: &str = "check_history";
+/// How many heads are remembered per check, older ones are forgotten.
+const MAX_HEADS: usize = 20;
+/// How many times a check must have failed and then passed on the same head to be flaky.
+const FLAKY_THRESHOLD: usize = 2;
+
+/// What a check did on the heads it was seen on.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct CheckRecord {
+    /// Heads the check was seen failing on.
+    pub failed: Vec<String>,
+    /// Heads the check failed on and later passed, i.e. it passed after a rerun.
+    pub flaked: Vec<String>,
+}
+
+/// The outcomes of checks over time, keyed by [`check_key`], to tell flaky checks apart.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct CheckHistory {
+    pub checks: BTreeMap<String, CheckRecord>,
+}
+
+/// Checks are remembered per repository, as the same name means different things elsewhere.
+pub fn check_key(repository: &str, check: &StatusCheck) -> String {
+    format!("{repository}: {}", check.name())
+}
+
+impl CheckHistory {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(CHECK_HISTORY_DOCUMENT)
+    }
+
+    /// Records the checks as seen on the head of a review.
+    pub fn record(
+        store: &Store,
+        repository: &str,
+        head: &str,
+        checks: &[StatusCheck],
+    ) -> anyhow::Result<()> {
+        let mut history = Self::load(store)?;
+        if history.observe(repository, head, checks) {
+            store.save(CHECK_HISTORY_DOCUMENT, &history)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether anything changed.
+    fn observe(&mut self, repository: &str, head: &str, checks: &[StatusCheck]) -> bool {
+        let mut changed = false;
+
+        for check in checks {
+            let key = check_key(repository, check);
+            match check.current() {
+                CurrentState::Failure => {
+                    let record = self.checks.entry(key).or_default();
+                    if !record.failed.iter().any(|h| h == head) {
+                        push_capped(&mut record.failed, head);
+                        changed = true;
+                    }
+                }
+                CurrentState::Success => {
+                    let Some(record) = self.checks.get_mut(&key) else {
+                        continue;
+                    };
+                    if record.failed.iter().any(|h| h == head)
+                        && !record.flaked.iter().any(|h| h == head)
+                    {
+                        push_capped(&mut record.flaked, head);
+                        changed = true;
+                    }
+                }
+                CurrentState::Pending | CurrentState::Expired => {}
+            }
+        }
+
+        changed
+    }
+
+    pub fn is_flaky(&self, repository: &str, check: &StatusCheck) -> bool {
+        self.checks
+            .get(&check_key(repository, check))
+            .map(|r| r.flaked.len() >= FLAKY_THRESHOLD)
+            .unwrap_or_default()
+    }
+}
+
+fn push_capped(heads: &mut Vec<String>, head: &str) {
+    heads.push(head.to_string());
+    if heads.len() > MAX_HEADS {
+        heads.remove(0);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{CurrentState, StatusCheck};
+
+    use super::CheckHistory;
+
+    fn check(current: CurrentState) -> StatusCheck {
+        StatusCheck::CheckRun {
+            id: "1".into(),
+            name: "integration".into(),
+            status: "completed".into(),
+            conclusion: "".into(),
+            current,
+            url: None,
+            app: "GitHub Actions".into(),
+        }
+    }
+
+    #[test]
+    fn test_fail_then_pass_is_flaky() {
+        let mut history = CheckHistory::default();
+        let repository = "kjuulh/rev";
+
+        for head in ["a", "b"] {
+            history.observe(repository, head, &[check(CurrentState::Failure)]);
+            assert!(!history.is_flaky(repository, &check(CurrentState::Success)));
+            history.observe(repository, head, &[check(CurrentState::Success)]);
+        }
+        // A real fix on a new head isn't a flake
+        history.observe(repository, "c", &[check(CurrentState::Success)]);
+
+        assert!(history.is_flaky(repository, &check(CurrentState::Success)));
+        assert!(!history.is_flaky("kjuulh/other", &check(CurrentState::Success)));
+        assert_eq!(2, history.checks["kjuulh/rev: integration"].flaked.len());
+    }
+}
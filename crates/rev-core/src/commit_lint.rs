@@ -0,0 +1,213 @@
+use rev_git_provider::models::Commit;
+
+use crate::{
+    config::CommitLintSettings,
+    i18n::{tr, Text},
+};
+
+/// The types of conventional commits, see https://www.conventionalcommits.org.
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test",
+];
+
+/// The comment asking the author to fix the commits, `{author}` and `{warnings}` are replaced.
+const DEFAULT_COMMENT: &str = "@{author} a few commits don't follow the commit conventions of this repository, could you reword them?\n\n{warnings}";
+
+/// A rule a commit or the branch of a review breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitRule {
+    NotConventional,
+    SubjectTooLong { length: usize, max: usize },
+    WorkInProgress,
+}
+
+impl CommitRule {
+    pub fn describe(&self) -> String {
+        match self {
+            CommitRule::NotConventional => tr(Text::NotConventional).to_string(),
+            CommitRule::SubjectTooLong { length, max } => {
+                format!("{} ({length}/{max})", tr(Text::SubjectTooLong))
+            }
+            CommitRule::WorkInProgress => tr(Text::WorkInProgress).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitWarning {
+    /// The short sha of the commit, `None` when it's the branch breaking the rule.
+    pub commit: Option<String>,
+    /// The subject of the commit, or the name of the branch.
+    pub subject: String,
+    pub rule: CommitRule,
+}
+
+/// The rules the commits and branch of a review break, as configured in the `commit-lint` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitLint {
+    pub warnings: Vec<CommitWarning>,
+}
+
+impl CommitLint {
+    /// Lints the commits and branch, returning nothing when they're fine or no rules are set.
+    pub fn lint(settings: &CommitLintSettings, branch: &str, commits: &[Commit]) -> Option<Self> {
+        let mut warnings = Vec::new();
+
+        let wip_branch = branch
+            .split(['/', '-', '_'])
+            .any(|part| part.eq_ignore_ascii_case("wip"));
+        if settings.no_wip && wip_branch {
+            warnings.push(CommitWarning {
+                commit: None,
+                subject: branch.to_string(),
+                rule: CommitRule::WorkInProgress,
+            });
+        }
+
+        for commit in commits {
+            let warning = |rule| CommitWarning {
+                commit: Some(commit.short_oid.clone()),
+                subject: commit.headline.clone(),
+                rule,
+            };
+            // Merges are made by the provider or git, not worded by the author
+            if commit.headline.starts_with("Merge ") {
+                continue;
+            }
+
+            if settings.conventional_commits && !is_conventional(&commit.headline) {
+                warnings.push(warning(CommitRule::NotConventional));
+            }
+            if let Some(max) = settings.max_subject_length {
+                let length = commit.headline.chars().count();
+                if length > max {
+                    warnings.push(warning(CommitRule::SubjectTooLong { length, max }));
+                }
+            }
+            if settings.no_wip && is_wip(&commit.headline) {
+                warnings.push(warning(CommitRule::WorkInProgress));
+            }
+        }
+
+        (!warnings.is_empty()).then_some(Self { warnings })
+    }
+
+    /// The warnings of a commit, by its short sha.
+    pub fn of_commit<'a>(&'a self, short_oid: &'a str) -> impl Iterator<Item = &'a CommitWarning> {
+        self.warnings
+            .iter()
+            .filter(move |w| w.commit.as_deref() == Some(short_oid))
+    }
+
+    /// The warnings on the branch rather than a commit.
+    pub fn of_branch(&self) -> impl Iterator<Item = &CommitWarning> {
+        self.warnings.iter().filter(|w| w.commit.is_none())
+    }
+
+    /// The comment asking the author to fix the commits, from the template in the settings.
+    pub fn request(&self, settings: &CommitLintSettings, author: &str) -> String {
+        let warnings = self
+            .warnings
+            .iter()
+            .map(|w| match w.commit.as_deref() {
+                Some(commit) => format!("- `{commit}` {}: {}", w.subject, w.rule.describe()),
+                None => format!("- branch `{}`: {}", w.subject, w.rule.describe()),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        settings
+            .comment
+            .as_deref()
+            .unwrap_or(DEFAULT_COMMENT)
+            .replace("{author}", author)
+            .replace("{warnings}", &warnings)
+    }
+}
+
+/// Whether the subject is in the form `type(scope)!: description`.
+fn is_conventional(subject: &str) -> bool {
+    let Some((prefix, description)) = subject.split_once(": ") else {
+        return false;
+    };
+    let prefix = prefix.trim_end_matches('!');
+    let kind = match prefix.split_once('(') {
+        Some((kind, scope)) if scope.ends_with(')') && scope.len() > 1 => kind,
+        Some(_) => return false,
+        None => prefix,
+    };
+
+    CONVENTIONAL_TYPES.contains(&kind) && !description.trim().is_empty()
+}
+
+fn is_wip(subject: &str) -> bool {
+    let lower = subject.to_lowercase();
+    lower.starts_with("wip") || lower.starts_with("fixup!") || lower.starts_with("squash!")
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::Commit;
+
+    use super::{is_conventional, CommitLint, CommitRule};
+    use crate::config::CommitLintSettings;
+
+    fn commit(short_oid: &str, headline: &str) -> Commit {
+        Commit {
+            oid: format!("{short_oid}000"),
+            short_oid: short_oid.into(),
+            headline: headline.into(),
+            author: "kjuulh".into(),
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_lint_commits() {
+        assert!(is_conventional("feat(tui)!: add lint"));
+        assert!(is_conventional("fix: typo"));
+        assert!(!is_conventional("feature: add lint"));
+        assert!(!is_conventional("fix(): typo"));
+        assert!(!is_conventional("Add lint"));
+
+        let settings = CommitLintSettings {
+            conventional_commits: true,
+            max_subject_length: Some(20),
+            no_wip: true,
+            comment: None,
+        };
+        let commits = [
+            commit("aaa", "feat: add lint"),
+            commit("bbb", "WIP"),
+            commit("ccc", "fix: a subject which is too long"),
+            commit("ddd", "Merge branch 'main' into lint"),
+        ];
+
+        let lint = CommitLint::lint(&settings, "kjuulh/wip-lint", &commits).unwrap();
+
+        assert_eq!(1, lint.of_branch().count());
+        assert_eq!(0, lint.of_commit("aaa").count());
+        assert_eq!(
+            vec![&CommitRule::NotConventional, &CommitRule::WorkInProgress],
+            lint.of_commit("bbb").map(|w| &w.rule).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&CommitRule::SubjectTooLong {
+                length: 32,
+                max: 20
+            }],
+            lint.of_commit("ccc").map(|w| &w.rule).collect::<Vec<_>>()
+        );
+        assert_eq!(0, lint.of_commit("ddd").count());
+
+        let request = lint.request(&settings, "octocat");
+        assert!(request.starts_with("@octocat"));
+        assert!(request.contains("- branch `kjuulh/wip-lint`"));
+        assert!(request.contains("- `bbb` WIP"));
+
+        assert_eq!(
+            None,
+            CommitLint::lint(&CommitLintSettings::default(), "wip", &commits)
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::{prelude::*, widgets::Paragraph};
+use tokio::sync::mpsc::UnboundedSender;
+
+pub mod chips;
+pub mod composer;
+pub mod confirm;
+pub mod diff;
+pub mod discussions;
+pub mod effect;
+pub mod files;
+pub mod github_pr;
+pub mod github_prs;
+pub mod history;
+pub mod home;
+pub mod new_review;
+pub mod profiler;
+pub mod repositories;
+pub mod snoozed;
+pub mod text_area;
+pub mod toast;
+
+pub use self::effect::{Effect, EffectRunner, Update};
+use crate::{
+    action::Action,
+    config::Config,
+    i18n::{tr, Text},
+    platform,
+    theme::Theme,
+    tui::{Event, Frame},
+};
+
+#[allow(unused_variables)]
+pub trait Component {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn init(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> anyhow::Result<Option<Action>> {
+        let r = match event {
+            Some(Event::Key(key_event)) => self.handle_key_events(key_event)?,
+            Some(Event::Mouse(mouse_event)) => self.handle_mouse_events(mouse_event)?,
+            Some(Event::Paste(text)) => self.handle_paste(text)?,
+            _ => None,
+        };
+
+        Ok(r)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> anyhow::Result<Option<Action>> {
+        Ok(None)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> anyhow::Result<Option<Action>> {
+        Ok(None)
+    }
+
+    /// Text pasted in one go, which shouldn't be taken as key presses.
+    fn handle_paste(&mut self, text: String) -> anyhow::Result<Option<Action>> {
+        Ok(None)
+    }
+
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        Ok(None)
+    }
+
+    /// Like [`Component::update`], but may leave work to be done off the event loop as effects,
+    /// which the app runs and cancels, rather than spawning tasks of its own.
+    fn handle(&mut self, action: Action) -> anyhow::Result<Update> {
+        Ok(Update {
+            action: self.update(action)?,
+            effects: self.take_effects(),
+        })
+    }
+
+    /// The effects left by handling events or actions, for components whose helpers are shared
+    /// between the two rather than returning an [`Update`].
+    fn take_effects(&mut self) -> Vec<Effect> {
+        Vec::new()
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> anyhow::Result<()>;
+}
+
+/// A popup of the given percentage of the area, in its middle.
+pub fn centered(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = area.height * percent_y / 100;
+
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Whether the position of a mouse event is within the area.
+pub fn contains(area: Rect, column: u16, row: u16) -> bool {
+    (area.x..area.right()).contains(&column) && (area.y..area.bottom()).contains(&row)
+}
+
+/// The index of the table row drawn at `row`, for a table in a bordered block scrolled to
+/// `offset`, whose header and rows take the given number of lines, margins included.
+pub fn table_row_at(
+    area: Rect,
+    offset: usize,
+    header_height: u16,
+    row_height: u16,
+    row: u16,
+) -> Option<usize> {
+    let first = area.y + 1 + header_height;
+    if row < first || row >= area.bottom().saturating_sub(1) {
+        return None;
+    }
+
+    Some(offset + ((row - first) / row_height.max(1)) as usize)
+}
+
+/// Opens the page of a review in the browser, toasting why when it can't.
+pub fn open_in_browser(url: &str) -> Option<Action> {
+    if url.is_empty() {
+        return Some(Action::Toast(tr(Text::ReviewWithoutUrl).into()));
+    }
+
+    platform::open_url(url)
+        .err()
+        .map(|e| Action::Toast(format!("{e:#}")))
+}
+
+/// The size of a review, e.g. `+120 -43 (7 files)`, leaving out the files when they aren't known.
+pub fn size_spans<'a>(
+    theme: &Theme,
+    additions: usize,
+    deletions: usize,
+    changed_files: usize,
+) -> Vec<Span<'a>> {
+    let mut spans = vec![
+        Span::styled(format!("+{additions}"), Style::default().fg(theme.success)),
+        Span::raw(" "),
+        Span::styled(format!("-{deletions}"), Style::default().fg(theme.failure)),
+    ];
+    if changed_files > 0 {
+        spans.push(Span::styled(
+            format!(" ({changed_files} {})", tr(Text::Files)),
+            Style::default().fg(theme.muted),
+        ));
+    }
+
+    spans
+}
+
+/// Whether the changed lines are within a size bound of a filter, such as `<100` or `>500`,
+/// `None` when the word isn't a bound.
+pub fn within_size(word: &str, lines: usize) -> Option<bool> {
+    if let Some(max) = word.strip_prefix('<') {
+        return max.parse::<usize>().ok().map(|max| lines < max);
+    }
+    let min = word.strip_prefix('>')?.parse::<usize>().ok()?;
+
+    Some(lines > min)
+}
+
+/// The line at the bottom of a page, led by the keybind profile in use when it isn't the default.
+pub fn status_bar<'a>(theme: &Theme, profile: Option<&str>, help: &'a str) -> Paragraph<'a> {
+    let mut spans = Vec::new();
+    if !platform::data_dir_writable() {
+        spans.push(Span::styled(
+            format!("[{}] ", tr(Text::DataDirReadOnly)),
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(profile) = profile {
+        spans.push(Span::styled(
+            format!("[{}: {profile}] ", tr(Text::KeybindProfile)),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.push(Span::raw(help));
+
+    Paragraph::new(Line::from(spans)).style(theme.status_bar())
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::layout::Rect;
+
+    use super::{contains, table_row_at, within_size};
+
+    #[test]
+    fn test_table_row_at() {
+        let area = Rect::new(0, 2, 80, 20);
+
+        assert!(contains(area, 79, 21));
+        assert!(!contains(area, 80, 10));
+        // The border and header take the first three lines
+        assert_eq!(None, table_row_at(area, 0, 2, 1, 4));
+        assert_eq!(Some(0), table_row_at(area, 0, 2, 1, 5));
+        assert_eq!(Some(12), table_row_at(area, 10, 2, 3, 11));
+        assert_eq!(None, table_row_at(area, 0, 2, 1, 21));
+    }
+
+    #[test]
+    fn test_within_size() {
+        assert_eq!(Some(true), within_size("<100", 99));
+        assert_eq!(Some(false), within_size("<100", 100));
+        assert_eq!(Some(true), within_size(">500", 501));
+        assert_eq!(None, within_size("<lots", 10));
+        assert_eq!(None, within_size("retries", 10));
+    }
+}
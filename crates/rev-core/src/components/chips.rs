@@ -0,0 +1,99 @@
+use ratatui::{prelude::*, widgets::Widget};
+use rev_git_provider::models::Label;
+
+use crate::terminal::Capabilities;
+
+/// Labels drawn as chips in the color they have on the provider, with black or white text
+/// depending on which reads better on it, see [`Label::is_light`]. Labels without a color are drawn in gray.
+pub struct Chips<'a> {
+    labels: &'a [Label],
+    terminal: &'a Capabilities,
+}
+
+impl<'a> Chips<'a> {
+    pub fn new(labels: &'a [Label], terminal: &'a Capabilities) -> Self {
+        Self { labels, terminal }
+    }
+
+    /// The chips as spans, separated by a space, for use within a line or a table cell.
+    pub fn spans(&self) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+        for (i, label) in self.labels.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::styled(format!(" {} ", label.name), self.style(label)));
+        }
+
+        spans
+    }
+
+    pub fn line(&self) -> Line<'static> {
+        Line::from(self.spans())
+    }
+
+    fn style(&self, label: &Label) -> Style {
+        let (background, text) = match (label.rgb(), label.is_light()) {
+            (Some((r, g, b)), Some(true)) => (Color::Rgb(r, g, b), Color::Black),
+            (Some((r, g, b)), _) => (Color::Rgb(r, g, b), Color::White),
+            (None, _) => (Color::DarkGray, Color::White),
+        };
+
+        Style::default()
+            .bg(self.terminal.fit(background))
+            .fg(self.terminal.fit(text))
+    }
+}
+
+impl Widget for Chips<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        buf.set_line(area.x, area.y, &self.line(), area.width);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::style::Color;
+    use rev_git_provider::models::Label;
+
+    use super::Chips;
+    use crate::terminal::{Capabilities, ColorDepth};
+
+    #[test]
+    fn test_chips_contrast() {
+        let terminal = Capabilities {
+            colors: ColorDepth::TrueColor,
+            ..Default::default()
+        };
+        let labels = vec![
+            Label {
+                name: "bug".into(),
+                color: Some("d73a4a".into()),
+                description: None,
+            },
+            Label {
+                name: "good first issue".into(),
+                color: Some("#7057ff".into()),
+                description: None,
+            },
+            Label::new("no color"),
+        ];
+
+        let spans = Chips::new(&labels, &terminal).spans();
+
+        assert_eq!(5, spans.len());
+        assert_eq!(" bug ", spans[0].content);
+        assert_eq!(Some(Color::Rgb(0xd7, 0x3a, 0x4a)), spans[0].style.bg);
+        assert_eq!(Some(Color::Rgb(0x70, 0x57, 0xff)), spans[2].style.bg);
+        assert_eq!(Some(Color::DarkGray), spans[4].style.bg);
+
+        assert_eq!(Some(Color::White), spans[0].style.fg);
+
+        let yellow = Label {
+            color: Some("fbca04".into()),
+            ..Label::new("needs-triage")
+        };
+        let spans = Chips::new(std::slice::from_ref(&yellow), &terminal).spans();
+        assert_eq!(Some(Color::Black), spans[0].style.fg);
+    }
+}
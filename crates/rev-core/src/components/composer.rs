@@ -0,0 +1,134 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+
+use super::{centered, text_area::TextArea};
+
+/// What happened to the composer as the result of a key press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComposerEvent {
+    None,
+    Submit(String),
+    Cancel,
+    /// The draft should be opened in an external editor, see [`Composer::set_text`].
+    EditExternally(String),
+}
+
+/// A text box drawn on top of the page for writing comments.
+///
+/// Enter inserts a new line, ctrl-s submits, ctrl-o opens the draft in `$EDITOR` and esc cancels,
+/// everything else is handled by the
+/// [`TextArea`].
+#[derive(Default)]
+pub struct Composer {
+    title: String,
+    text: TextArea,
+    active: bool,
+}
+
+impl Composer {
+    pub fn open(&mut self, title: impl Into<String>, text: impl Into<String>) {
+        self.title = title.into();
+        self.text.set_text(&text.into());
+        self.active = true;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> ComposerEvent {
+        if !self.active {
+            return ComposerEvent::None;
+        }
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => {
+                self.active = false;
+                ComposerEvent::Cancel
+            }
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+                self.active = false;
+                ComposerEvent::Submit(self.text.text())
+            }
+            (KeyCode::Char('o'), KeyModifiers::CONTROL) => {
+                ComposerEvent::EditExternally(self.text.text())
+            }
+            _ => {
+                self.text.input(key);
+                ComposerEvent::None
+            }
+        }
+    }
+
+    /// Replaces the draft, e.g. with the text from the external editor.
+    pub fn set_text(&mut self, text: &str) {
+        if self.active {
+            self.text.set_text(text);
+        }
+    }
+
+    /// Inserts pasted text as is, line endings included.
+    pub fn paste(&mut self, text: &str) {
+        if self.active {
+            self.text
+                .insert(&text.replace("\r\n", "\n").replace('\r', "\n"));
+        }
+    }
+
+    pub fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) {
+        if !self.active {
+            return;
+        }
+
+        let popup = centered(area, 80, 50);
+        f.render_widget(Clear, popup);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.title.as_str())
+            .title(
+                block::Title::from(
+                    "[ ctrl-s: submit, esc: cancel, ctrl-z: undo, ctrl-o: $EDITOR ]",
+                )
+                .position(block::Position::Bottom),
+            );
+        self.text.draw(f, popup, block);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{Composer, ComposerEvent};
+
+    #[test]
+    fn test_compose_and_submit() {
+        let mut composer = Composer::default();
+        composer.open("edit comment", "hi");
+
+        for key in [KeyCode::Char('!'), KeyCode::Enter, KeyCode::Char('x')] {
+            composer.handle_key(KeyEvent::new(key, KeyModifiers::empty()));
+        }
+        composer.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::empty()));
+
+        let event = composer.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+
+        assert_eq!(ComposerEvent::Submit("hi!\n".into()), event);
+        assert!(!composer.is_active());
+    }
+
+    #[test]
+    fn test_paste() {
+        let mut composer = Composer::default();
+        composer.paste("ignored");
+        composer.open("add comment", "see ");
+
+        composer.paste("fn main() {\r\n    todo!()\r\n}");
+
+        let event = composer.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert_eq!(
+            ComposerEvent::Submit("see fn main() {\n    todo!()\n}".into()),
+            event
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+
+use super::centered;
+
+/// What happened to the dialog as the result of a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmEvent {
+    None,
+    /// One of the choices was picked, by its key.
+    Chosen(char),
+    Cancel,
+}
+
+/// A question drawn on top of the page, answered by the key of one of its choices or dismissed
+/// with esc. Other keys are ignored, so a stray key press never confirms anything.
+#[derive(Default)]
+pub struct Confirm {
+    title: String,
+    choices: Vec<(char, String)>,
+    active: bool,
+}
+
+impl Confirm {
+    pub fn open(&mut self, title: impl Into<String>, choices: Vec<(char, String)>) {
+        self.title = title.into();
+        self.choices = choices;
+        self.active = true;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> ConfirmEvent {
+        if !self.active {
+            return ConfirmEvent::None;
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                self.active = false;
+                ConfirmEvent::Cancel
+            }
+            KeyCode::Char(c) if self.choices.iter().any(|(k, _)| *k == c) => {
+                self.active = false;
+                ConfirmEvent::Chosen(c)
+            }
+            _ => ConfirmEvent::None,
+        }
+    }
+
+    pub fn draw(&self, f: &mut crate::tui::Frame<'_>, area: Rect) {
+        if !self.active {
+            return;
+        }
+
+        let mut popup = centered(area, 50, 0);
+        popup.height = 3.min(area.height);
+        f.render_widget(Clear, popup);
+
+        let choices = self
+            .choices
+            .iter()
+            .map(|(key, name)| format!("{key}: {name}"))
+            .chain(std::iter::once("esc: cancel".to_string()))
+            .collect::<Vec<_>>()
+            .join("  ");
+        f.render_widget(
+            Paragraph::new(choices).alignment(Alignment::Center).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.title.as_str()),
+            ),
+            popup,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{Confirm, ConfirmEvent};
+
+    #[test]
+    fn test_only_choices_confirm() {
+        let mut confirm = Confirm::default();
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty());
+        confirm.open(
+            "merge?",
+            vec![('m', "merge".into()), ('s', "squash".into())],
+        );
+
+        assert_eq!(ConfirmEvent::None, confirm.handle_key(key('x')));
+        assert!(confirm.is_active());
+        assert_eq!(ConfirmEvent::Chosen('s'), confirm.handle_key(key('s')));
+        assert!(!confirm.is_active());
+
+        confirm.open("merge?", vec![('m', "merge".into())]);
+        assert_eq!(
+            ConfirmEvent::Cancel,
+            confirm.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::empty()))
+        );
+    }
+}
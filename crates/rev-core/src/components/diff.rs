@@ -0,0 +1,525 @@
+use std::{
+    io::Read,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use portable_pty::{ChildKiller, CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders,
+    },
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tui_term::widget::PseudoTerminal;
+
+use super::Component;
+use crate::{
+    action::Action,
+    config::Config,
+    i18n::{tr, Text},
+};
+
+/// The rows of output kept above the screen, which is as far up as the diff scrolls.
+const SCROLLBACK: usize = 10_000;
+
+/// The pager used when delta is installed and no `diff-command` is set.
+const DELTA: &str = "delta --paging=never";
+
+/// The rows the mouse wheel scrolls by.
+const WHEEL_ROWS: usize = 3;
+
+pub struct GitDiff {
+    /// The checkout git runs in.
+    cwd: PathBuf,
+    /// The command the output of git is piped through, `None` to show it as is.
+    pager: Option<String>,
+    pty_system: NativePtySystem,
+    action_tx: Option<UnboundedSender<Action>>,
+    /// The running diff, fed by a reader thread as the output arrives.
+    running: Option<Running>,
+    /// Where the diff was last drawn, the size of the pty.
+    area: Option<Rect>,
+    /// The row of the output at the top of the view, counted from the start.
+    top: usize,
+    /// The git arguments, when it isn't the diff of the working tree.
+    args: Option<String>,
+    /// Whether the diff is the page shown, so it takes the keys which aren't bound to actions.
+    shown: bool,
+    /// Whether `g` was just pressed, another goes to the top.
+    pending_g: bool,
+    /// The pattern typed after `/`, keys go to it rather than to the keybinds meanwhile.
+    typing: Option<String>,
+    /// The pattern searched for last, `n` and `N` go to its next and previous match.
+    pattern: Option<String>,
+}
+
+/// Where to look for a match of the pattern, from the row at the top of the view.
+#[derive(Clone, Copy)]
+enum Search {
+    /// The first match from the top of the view, including it.
+    First,
+    Next,
+    Previous,
+}
+
+/// The diff in the pty, the output parsed so far and the ends kept to resize or stop it.
+struct Running {
+    parser: Arc<RwLock<vt100::Parser>>,
+    /// Whether a render was asked for since the last draw, so a burst of output renders once.
+    render_pending: Arc<AtomicBool>,
+    master: Box<dyn MasterPty + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    size: (u16, u16),
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        // The diff may still be running when it's replaced, and has exited otherwise
+        let _ = self.killer.kill();
+    }
+}
+
+impl Default for GitDiff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitDiff {
+    pub fn new() -> Self {
+        Self {
+            cwd: std::env::current_dir().unwrap(),
+            pager: pager(None),
+            pty_system: NativePtySystem::default(),
+            action_tx: None,
+            running: None,
+            area: None,
+            top: 0,
+            args: None,
+            shown: false,
+            pending_g: false,
+            typing: None,
+            pattern: None,
+        }
+    }
+
+    /// Runs git with the arguments in the checkout instead, e.g. `diff a...b` or
+    /// `range-diff a...b`, starting over.
+    pub fn show(&mut self, cwd: PathBuf, args: String) -> anyhow::Result<()> {
+        self.cwd = cwd;
+        self.top = 0;
+        self.args = Some(args);
+        self.start()
+    }
+
+    /// The rows and columns of the pty, inside the border of the area last drawn, or of the
+    /// terminal before the first draw.
+    fn size(&self) -> (u16, u16) {
+        let (cols, rows) = match self.area {
+            Some(area) => (area.width, area.height),
+            None => crossterm::terminal::size().unwrap_or((80, 24)),
+        };
+
+        (rows.saturating_sub(2).max(1), cols.saturating_sub(2).max(1))
+    }
+
+    /// Spawns the diff in a pty, stopping the one running, and reads its output on a thread,
+    /// asking for a render whenever more arrived.
+    fn start(&mut self) -> anyhow::Result<()> {
+        self.running = None;
+        let (rows, cols) = self.size();
+        let pair = self.pty_system.openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+
+        let mut cmd = git_command(
+            self.args.as_deref().unwrap_or("diff"),
+            self.pager.as_deref(),
+        );
+        cmd.cwd(&self.cwd);
+        let mut child = pair.slave.spawn_command(cmd)?;
+        drop(pair.slave);
+        let killer = child.clone_killer();
+        std::thread::spawn(move || child.wait());
+
+        let mut reader = pair.master.try_clone_reader()?;
+        let parser = Arc::new(RwLock::new(vt100::Parser::new(rows, cols, SCROLLBACK)));
+        let render_pending = Arc::new(AtomicBool::new(false));
+        {
+            let parser = parser.clone();
+            let render_pending = render_pending.clone();
+            let tx = self.action_tx.clone();
+            std::thread::spawn(move || {
+                let mut buf = [0; 8192];
+                // Ends once the diff exits and the pty is closed
+                while let Ok(n @ 1..) = reader.read(&mut buf) {
+                    parser.write().unwrap().process(&buf[..n]);
+                    if render_pending.swap(true, Ordering::AcqRel) {
+                        continue;
+                    }
+                    if let Some(tx) = tx.as_ref() {
+                        if tx.send(Action::Render).is_err() {
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+
+        self.running = Some(Running {
+            parser,
+            render_pending,
+            master: pair.master,
+            killer,
+            size: (rows, cols),
+        });
+
+        Ok(())
+    }
+
+    /// Fits the pty and the screen to the area, as the diff wraps to the size of the pty.
+    fn resize(&mut self, area: Rect) -> anyhow::Result<()> {
+        self.area = Some(area);
+        let size = self.size();
+        let Some(running) = self.running.as_mut().filter(|r| r.size != size) else {
+            return Ok(());
+        };
+
+        let (rows, cols) = size;
+        running.master.resize(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+        running.parser.write().unwrap().set_size(rows, cols);
+        running.size = size;
+
+        Ok(())
+    }
+
+    /// Scrolls the view to start at the row, as far down as the output reaches.
+    fn scroll_to(&mut self, top: usize) {
+        self.top = match self.running.as_ref() {
+            Some(running) => top.min(scrollback_len(&mut running.parser.write().unwrap())),
+            None => 0,
+        };
+    }
+
+    /// The rows of the view, which a page scrolls by.
+    fn page(&self) -> usize {
+        self.size().0 as usize
+    }
+
+    /// Scrolls to the match of the pattern searched for, wrapping around the output, or tells
+    /// that there's none.
+    fn search(&mut self, search: Search) -> Option<Action> {
+        let pattern = self.pattern.as_deref()?;
+        let rows = output_rows(&mut self.running.as_ref()?.parser.write().unwrap());
+        let start = match search {
+            Search::First => self.top,
+            Search::Next => self.top + 1,
+            Search::Previous => self.top + rows.len().saturating_sub(1),
+        };
+
+        match find(&rows, pattern, start, !matches!(search, Search::Previous)) {
+            Some(row) => {
+                self.scroll_to(row);
+                None
+            }
+            None => Some(Action::Toast(tr(Text::PatternNotFound).into())),
+        }
+    }
+
+    fn pattern_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let typing = self.typing.as_mut()?;
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => {
+                self.typing = None;
+                Some(Action::ExitInsertMode)
+            }
+            (KeyCode::Enter, _) => {
+                // An empty pattern searches for the last one again, like in less
+                if let Some(pattern) = self.typing.take().filter(|p| !p.is_empty()) {
+                    self.pattern = Some(pattern);
+                }
+                if let Some(action) = self.search(Search::First) {
+                    let _ = self.action_tx.as_ref()?.send(action);
+                }
+                Some(Action::ExitInsertMode)
+            }
+            (KeyCode::Backspace, _) => {
+                typing.pop();
+                None
+            }
+            (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                typing.clear();
+                None
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                typing.push(c);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The pager of the `diff-command` set, delta when it's installed and none is, or none to show
+/// the output of git as is.
+fn pager(diff_command: Option<&str>) -> Option<String> {
+    match diff_command {
+        Some(command) => Some(command.to_string()).filter(|c| !c.trim().is_empty()),
+        None => which::which("delta").ok().map(|_| DELTA.to_string()),
+    }
+}
+
+/// Runs git with the arguments, e.g. `diff a...b`, without a shell in between. git pipes its
+/// output through the pager itself, as it runs in the pty.
+fn git_command(args: &str, pager: Option<&str>) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new("git");
+    match pager {
+        Some(pager) => cmd.env("GIT_PAGER", pager),
+        None => cmd.arg("--no-pager"),
+    }
+
+    let mut args = args.split_whitespace();
+    cmd.args(args.next());
+    cmd.arg("--color=always");
+    cmd.args(args);
+
+    cmd
+}
+
+/// The rows of output above the screen, which the view scrolls through down to the screen.
+fn scrollback_len(parser: &mut vt100::Parser) -> usize {
+    let offset = parser.screen().scrollback();
+    parser.set_scrollback(usize::MAX);
+    let len = parser.screen().scrollback();
+    parser.set_scrollback(offset);
+
+    len
+}
+
+/// The rows of the whole output, from the top of the scrollback to the bottom of the screen.
+fn output_rows(parser: &mut vt100::Parser) -> Vec<String> {
+    let offset = parser.screen().scrollback();
+    let len = scrollback_len(parser);
+    let (rows, cols) = parser.screen().size();
+    let total = len + rows as usize;
+
+    // The screen only shows the rows at the scrollback offset, so it's paged through
+    let mut output = Vec::with_capacity(total);
+    while output.len() < total {
+        let top = output.len().min(len);
+        parser.set_scrollback(len - top);
+        let skip = output.len() - top;
+        output.extend(parser.screen().rows(0, cols).skip(skip));
+    }
+    parser.set_scrollback(offset);
+
+    output
+}
+
+/// The row of the first match of the pattern from the start row on, or back from it, wrapping
+/// around the rows.
+fn find(rows: &[String], pattern: &str, start: usize, forward: bool) -> Option<usize> {
+    let len = rows.len();
+    (0..len)
+        .map(|step| match forward {
+            true => (start + step) % len,
+            false => (start % len + len - step) % len,
+        })
+        .find(|&row| rows[row].contains(pattern))
+}
+
+impl Component for GitDiff {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> anyhow::Result<()> {
+        self.action_tx = Some(tx);
+
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> anyhow::Result<Option<Action>> {
+        if self.typing.is_some() {
+            return Ok(self.pattern_key(key));
+        }
+        if !self.shown {
+            return Ok(None);
+        }
+
+        // The keybinds only take single keys, so the vim motions of the diff are handled here
+        let pending_g = std::mem::take(&mut self.pending_g);
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('g'), KeyModifiers::NONE) if pending_g => self.scroll_to(0),
+            (KeyCode::Char('g'), KeyModifiers::NONE) => self.pending_g = true,
+            (KeyCode::Char('G'), _) => self.scroll_to(usize::MAX),
+            (KeyCode::Char('n'), KeyModifiers::NONE) => return Ok(self.search(Search::Next)),
+            (KeyCode::Char('N'), _) => return Ok(self.search(Search::Previous)),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.pager = pager(config.diff_command.as_deref());
+
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        action: crate::action::Action,
+    ) -> anyhow::Result<Option<crate::action::Action>> {
+        // Actions only reach the page shown, so it's shown until one leaves it
+        self.shown = match &action {
+            Action::GotoPage(page) => page == "diff",
+            Action::TogglePage(_) | Action::Back => false,
+            _ => true,
+        };
+
+        match action {
+            Action::GotoPage(page) if page == "diff" && self.running.is_none() => self.start()?,
+            Action::ShowDiff { cwd, args } => self.show(cwd, args)?,
+            Action::SelectNext => self.scroll_to(self.top + 1),
+            Action::SelectPrevious => self.scroll_to(self.top.saturating_sub(1)),
+            Action::PageDown => self.scroll_to(self.top + self.page()),
+            Action::PageUp => self.scroll_to(self.top.saturating_sub(self.page())),
+            Action::HalfPageDown => self.scroll_to(self.top + self.page() / 2),
+            Action::HalfPageUp => self.scroll_to(self.top.saturating_sub(self.page() / 2)),
+            Action::Scroll { down: true, .. } => self.scroll_to(self.top + WHEEL_ROWS),
+            Action::Scroll { down: false, .. } => {
+                self.scroll_to(self.top.saturating_sub(WHEEL_ROWS))
+            }
+            Action::FilterList => {
+                self.typing = Some(String::new());
+                return Ok(Some(Action::EnterInsertMode));
+            }
+            Action::Back => return Ok(Some(Action::TogglePage("diff".into()))),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(
+        &mut self,
+        f: &mut crate::tui::Frame<'_>,
+        area: ratatui::prelude::Rect,
+    ) -> anyhow::Result<()> {
+        self.resize(area)?;
+        if self.running.is_none() {
+            self.start()?;
+        }
+        let Some(running) = self.running.as_ref() else {
+            return Ok(());
+        };
+
+        running.render_pending.store(false, Ordering::Release);
+        let mut parser = running.parser.write().unwrap();
+        // The output keeps growing below the view while the diff runs, so the offset from the
+        // bottom is worked out again for the row at the top
+        let len = scrollback_len(&mut parser);
+        self.top = self.top.min(len);
+        parser.set_scrollback(len - self.top);
+        let screen = parser.screen();
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(tr(Text::RunningGitDiff)))
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        if let Some(args) = self.args.as_deref() {
+            block = block.title(Title::from(format!("git {args}")).alignment(Alignment::Right));
+        }
+        let footer = match (self.typing.as_deref(), self.pattern.as_deref()) {
+            (Some(typing), _) => format!("/{typing}"),
+            (None, Some(pattern)) => format!("{} | /{pattern}", tr(Text::DiffHelp)),
+            (None, None) => tr(Text::DiffHelp).to_string(),
+        };
+        block = block.title(Title::from(footer).position(Position::Bottom));
+        let pseudo_term = PseudoTerminal::new(screen).block(block.clone());
+        f.render_widget(pseudo_term, area);
+        f.render_widget(block, f.size());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find, git_command, output_rows, pager};
+
+    fn argv(cmd: &portable_pty::CommandBuilder) -> Vec<String> {
+        cmd.get_argv()
+            .iter()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_git_command_without_pager() {
+        let cmd = git_command("range-diff a...b", None);
+
+        assert_eq!(
+            vec!["git", "--no-pager", "range-diff", "--color=always", "a...b"],
+            argv(&cmd)
+        );
+        assert_eq!(None, cmd.get_env("GIT_PAGER"));
+    }
+
+    #[test]
+    fn test_git_command_with_pager() {
+        let cmd = git_command("diff", Some("delta --paging=never"));
+
+        assert_eq!(vec!["git", "diff", "--color=always"], argv(&cmd));
+        assert_eq!(
+            Some("delta --paging=never"),
+            cmd.get_env("GIT_PAGER").and_then(|p| p.to_str())
+        );
+    }
+
+    #[test]
+    fn test_pager_of_diff_command() {
+        assert_eq!(
+            Some("diff-so-fancy".to_string()),
+            pager(Some("diff-so-fancy"))
+        );
+        assert_eq!(None, pager(Some("")));
+    }
+
+    #[test]
+    fn test_output_rows_span_the_scrollback() {
+        let mut parser = vt100::Parser::new(3, 20, 100);
+        parser.process(b"one\r\ntwo\r\nthree\r\nfour\r\nfive");
+
+        let rows = output_rows(&mut parser);
+
+        assert_eq!(vec!["one", "two", "three", "four", "five"], rows);
+        assert_eq!(0, parser.screen().scrollback());
+    }
+
+    #[test]
+    fn test_find_wraps_around() {
+        let rows = ["a match", "b", "c match", "d"].map(String::from);
+
+        assert_eq!(Some(0), find(&rows, "match", 0, true));
+        assert_eq!(Some(2), find(&rows, "match", 1, true));
+        assert_eq!(Some(0), find(&rows, "match", 3, true));
+        assert_eq!(Some(2), find(&rows, "match", 3, false));
+        assert_eq!(Some(0), find(&rows, "match", 1 + rows.len(), false));
+        assert_eq!(None, find(&rows, "missing", 0, true));
+    }
+}
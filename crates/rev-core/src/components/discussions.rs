@@ -0,0 +1,360 @@
+use chrono::Utc;
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::*};
+use rev_git_provider::models::{Issue, ReviewListItem};
+use rev_widget_list::SelectableWidgetList;
+use timeago::Formatter;
+
+use super::{self as components, chips::Chips, Component, Effect};
+use crate::{
+    action::{Action, DiscussionAction},
+    components::{
+        composer::{Composer, ComposerEvent},
+        github_pr::comments::{filter_comments, CommentItem},
+    },
+    config::Config,
+    git_issues::GitIssues,
+    i18n::{tr, Text},
+};
+
+/// Lists the issues matching the discussions query, such as RFCs, and shows one with its
+/// comments for reading and replying.
+pub struct Discussions {
+    issues: GitIssues,
+    config: Config,
+    items: Option<Vec<ReviewListItem>>,
+    table_state: TableState,
+    /// Where the list was last drawn, to tell which row a click is on.
+    list_area: Rect,
+    /// The opened issue, the list is shown when none is open.
+    issue: Option<Issue>,
+    selected_comment: usize,
+    composer: Composer,
+    /// The effects left by the helpers, until the app picks them up.
+    effects: Vec<Effect>,
+}
+
+impl Discussions {
+    pub fn new(issues: GitIssues) -> Self {
+        Self {
+            issues,
+            config: Config::default(),
+            items: None,
+            table_state: TableState::default(),
+            list_area: Rect::default(),
+            issue: None,
+            selected_comment: 0,
+            composer: Composer::default(),
+            effects: Vec::new(),
+        }
+    }
+
+    fn fetch_items(&mut self) {
+        let issues = self.issues.clone();
+        let query = self.config.discussions.query.clone();
+        self.effects.push(
+            Effect::new(async move {
+                let items = issues.list(&query).await?;
+                Ok(Some(Action::Discussions(DiscussionAction::Items { items })))
+            })
+            .replacing("discussions"),
+        );
+    }
+
+    fn fetch_issue(&mut self, item: ReviewListItem) {
+        let issues = self.issues.clone();
+        self.effects.push(
+            Effect::new(async move {
+                let issue = issues.get(&item).await?;
+                Ok(Some(Action::Discussions(DiscussionAction::Issue {
+                    issue: Box::new(issue),
+                })))
+            })
+            .replacing("discussion"),
+        );
+    }
+
+    fn selected_item(&self) -> Option<ReviewListItem> {
+        self.items
+            .as_ref()?
+            .get(self.table_state.selected()?)
+            .cloned()
+    }
+
+    fn select(&mut self, next: bool) {
+        if let Some(issue) = self.issue.as_ref() {
+            let len = issue.comments.comments.len();
+            self.selected_comment = if next {
+                (self.selected_comment + 1).min(len.saturating_sub(1))
+            } else {
+                self.selected_comment.saturating_sub(1)
+            };
+            return;
+        }
+
+        let len = self.items.as_ref().map(|i| i.len()).unwrap_or_default();
+        if len == 0 {
+            return;
+        }
+        let index = self.table_state.selected().unwrap_or_default();
+        self.table_state.select(Some(if next {
+            (index + 1).min(len - 1)
+        } else {
+            index.saturating_sub(1)
+        }));
+    }
+
+    fn click(&mut self, column: u16, row: u16) {
+        if self.issue.is_some() || !components::contains(self.list_area, column, row) {
+            return;
+        }
+
+        let len = self.items.as_ref().map(|i| i.len()).unwrap_or_default();
+        if let Some(index) =
+            components::table_row_at(self.list_area, self.table_state.offset(), 2, 2, row)
+                .filter(|i| *i < len)
+        {
+            self.table_state.select(Some(index));
+        }
+    }
+
+    fn refresh(&mut self) {
+        match self.issue.as_ref() {
+            Some(_) => {
+                if let Some(item) = self.selected_item() {
+                    self.fetch_issue(item);
+                }
+            }
+            None => self.fetch_items(),
+        }
+    }
+
+    fn submit_comment(&mut self, body: String) {
+        let Some(issue) = self.issue.clone() else {
+            return;
+        };
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+
+        let issues = self.issues.clone();
+        self.effects.push(Effect::sending(|tx| async move {
+            issues.comment(&issue, body).await?;
+            tx.send(Action::Toast(tr(Text::CommentAdded).into()))?;
+
+            // Fetched again to show the comment as it was posted
+            Ok(issues.get(&item).await.ok().map(|issue| {
+                Action::Discussions(DiscussionAction::Issue {
+                    issue: Box::new(issue),
+                })
+            }))
+        }));
+    }
+
+    fn draw_list(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) {
+        self.list_area = area;
+        let block = self.config.theme.block().title(tr(Text::Discussions));
+
+        let Some(items) = self.items.as_ref() else {
+            f.render_widget(Paragraph::new(tr(Text::Processing)).block(block), area);
+            return;
+        };
+        if items.is_empty() {
+            f.render_widget(
+                Paragraph::new(tr(Text::DiscussionsEmpty)).block(block),
+                area,
+            );
+            return;
+        }
+
+        let formatter = Formatter::default();
+        let header = Row::new(
+            [
+                Text::Owner,
+                Text::Repository,
+                Text::Title,
+                Text::DateCreated,
+            ]
+            .into_iter()
+            .map(|h| Cell::from(tr(h)).style(self.config.theme.header())),
+        )
+        .height(1)
+        .bottom_margin(1);
+        let rows = items.iter().map(|item| {
+            Row::new([
+                Cell::from(item.owner.clone()),
+                Cell::from(item.name.clone()),
+                Cell::from(format!("#{} {}", item.number, item.title)),
+                Cell::from(formatter.convert_chrono(item.date, Utc::now())),
+            ])
+            .height(1)
+            .bottom_margin(1)
+        });
+
+        let table = Table::new(rows)
+            .header(header)
+            .column_spacing(3)
+            .highlight_style(self.config.theme.selection())
+            .block(block)
+            .widths(&[
+                Constraint::Percentage(10),
+                Constraint::Percentage(15),
+                Constraint::Percentage(55),
+                Constraint::Percentage(20),
+            ]);
+
+        f.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn draw_issue(&self, f: &mut crate::tui::Frame<'_>, area: Rect, issue: &Issue) {
+        let main = Layout::new()
+            .constraints(vec![Constraint::Length(1), Constraint::Percentage(100)])
+            .split(area);
+        let mut header = vec![Span::raw(format!(
+            "{} - {}",
+            issue.reference(),
+            issue.author
+        ))];
+        if !issue.labels.is_empty() {
+            header.push(Span::raw(" "));
+            header.extend(Chips::new(&issue.labels, &self.config.terminal).spans());
+        }
+        f.render_widget(Paragraph::new(Line::from(header)), main[0]);
+
+        let body = Layout::new()
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .direction(Direction::Horizontal)
+            .split(main[1]);
+        let block = self.config.theme.block();
+
+        let description = if issue.description.is_empty() {
+            tr(Text::NoDescription)
+        } else {
+            issue.description.as_str()
+        };
+        f.render_widget(
+            Paragraph::new(description)
+                .wrap(Wrap { trim: true })
+                .block(block.clone().title(issue.title.as_str())),
+            body[0],
+        );
+
+        let filtered = filter_comments(&issue.comments.comments, &self.config.comments);
+        let items = filtered
+            .comments
+            .iter()
+            .map(|c| {
+                let mine = self.config.committer.as_deref() == Some(c.author.as_str());
+                CommentItem::new(&self.config.theme, &c.title(), c.text.clone(), mine, 4)
+            })
+            .collect::<Vec<_>>();
+        let title = if filtered.hidden > 0 {
+            format!(
+                "{} ({} {})",
+                tr(Text::Comments),
+                filtered.hidden,
+                tr(Text::Hidden)
+            )
+        } else {
+            tr(Text::Comments).to_string()
+        };
+        let mut comments = SelectableWidgetList::new(items)
+            .block(block.title(title))
+            .truncate(true);
+        comments.state.select(Some(self.selected_comment));
+        f.render_widget(&mut comments, body[1]);
+    }
+}
+
+impl Component for Discussions {
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.config = config;
+
+        Ok(())
+    }
+
+    fn take_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.effects)
+    }
+
+    fn handle_paste(&mut self, text: String) -> anyhow::Result<Option<Action>> {
+        self.composer.paste(&text);
+
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> anyhow::Result<Option<Action>> {
+        if !self.composer.is_active() {
+            return Ok(None);
+        }
+
+        match self.composer.handle_key(key) {
+            ComposerEvent::None => Ok(None),
+            ComposerEvent::EditExternally(text) => Ok(Some(Action::EditExternally(text))),
+            ComposerEvent::Cancel => Ok(Some(Action::ExitInsertMode)),
+            ComposerEvent::Submit(body) => {
+                self.submit_comment(body);
+                Ok(Some(Action::ExitInsertMode))
+            }
+        }
+    }
+
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        match action {
+            Action::GotoPage(page) if page == "discussions" && self.items.is_none() => {
+                self.fetch_items()
+            }
+            Action::Refresh => self.refresh(),
+            Action::EditedExternally(text) => self.composer.set_text(&text),
+            Action::SelectNext | Action::NextComment => self.select(true),
+            Action::SelectPrevious | Action::PreviousComment => self.select(false),
+            Action::Scroll { down, .. } => self.select(down),
+            Action::Click { column, row } => self.click(column, row),
+            Action::Open if self.issue.is_none() => {
+                if let Some(item) = self.selected_item() {
+                    self.fetch_issue(item);
+                }
+            }
+            Action::Back => self.issue = None,
+            Action::AddComment if self.issue.is_some() => {
+                self.composer.open(tr(Text::AddComment), "");
+                return Ok(Some(Action::EnterInsertMode));
+            }
+            Action::Discussions(action) => match action {
+                DiscussionAction::Items { items } => {
+                    self.items = Some(items);
+                    self.table_state.select(Some(0));
+                }
+                DiscussionAction::Issue { issue } => {
+                    if self.issue.as_ref().map(|i| &i.id) != Some(&issue.id) {
+                        self.selected_comment = 0;
+                    }
+                    self.issue = Some(*issue);
+                }
+            },
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        let layout = Layout::new()
+            .constraints(vec![Constraint::Percentage(100), Constraint::Min(1)])
+            .split(area);
+
+        match self.issue.as_ref() {
+            Some(issue) => self.draw_issue(f, layout[0], issue),
+            None => self.draw_list(f, layout[0]),
+        }
+
+        f.render_widget(
+            Paragraph::new(tr(Text::DiscussionsHelp)).style(self.config.theme.status_bar()),
+            layout[1],
+        );
+
+        self.composer.draw(f, area);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+
+use crate::action::Action;
+
+/// What an update of a component leads to, an action sent right away and effects run in the
+/// background.
+#[derive(Default)]
+pub struct Update {
+    pub action: Option<Action>,
+    pub effects: Vec<Effect>,
+}
+
+impl Update {
+    pub fn action(action: Option<Action>) -> Self {
+        Self {
+            action,
+            effects: Vec::new(),
+        }
+    }
+
+    pub fn effect(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+}
+
+impl From<Option<Action>> for Update {
+    fn from(action: Option<Action>) -> Self {
+        Self::action(action)
+    }
+}
+
+type Run = Box<
+    dyn FnOnce(UnboundedSender<Action>) -> BoxFuture<'static, anyhow::Result<Option<Action>>>
+        + Send,
+>;
+
+/// Work a component wants done off the event loop, such as fetching from the provider. The
+/// action it resolves to is sent like any other, a failure is toasted.
+pub struct Effect {
+    key: Option<String>,
+    run: Run,
+}
+
+impl Effect {
+    pub fn new(
+        future: impl Future<Output = anyhow::Result<Option<Action>>> + Send + 'static,
+    ) -> Self {
+        Self::sending(|_| future)
+    }
+
+    /// An effect sending actions along the way as well, such as the progress of a fetch.
+    pub fn sending<F>(run: impl FnOnce(UnboundedSender<Action>) -> F + Send + 'static) -> Self
+    where
+        F: Future<Output = anyhow::Result<Option<Action>>> + Send + 'static,
+    {
+        Self {
+            key: None,
+            run: Box::new(|tx| run(tx).boxed()),
+        }
+    }
+
+    /// Cancels the effect of the key still running, without doing anything in its place.
+    pub fn cancelling(key: impl Into<String>) -> Self {
+        Self::new(async { Ok(None) }).replacing(key)
+    }
+
+    /// Cancels the effect of the same key still running, e.g. a fetch superseded by a newer one,
+    /// so the stale result never arrives after the fresh one.
+    pub fn replacing(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+}
+
+/// Runs the effects of the components, sending what they resolve to back as actions.
+pub struct EffectRunner {
+    action_tx: UnboundedSender<Action>,
+    /// The effects running by their key, along with the id of the one spawned last.
+    running: Arc<Mutex<HashMap<String, (u64, CancellationToken)>>>,
+    next_id: AtomicU64,
+    /// Cancels every effect, once rev quits.
+    cancel: CancellationToken,
+}
+
+impl EffectRunner {
+    pub fn new(action_tx: UnboundedSender<Action>) -> Self {
+        Self {
+            action_tx,
+            running: Arc::default(),
+            next_id: AtomicU64::new(0),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Sends the action of the update and spawns its effects.
+    pub fn run(&self, update: Update) -> anyhow::Result<()> {
+        if let Some(action) = update.action {
+            self.action_tx.send(action)?;
+        }
+        for effect in update.effects {
+            self.spawn(effect);
+        }
+
+        Ok(())
+    }
+
+    fn spawn(&self, effect: Effect) {
+        let token = self.cancel.child_token();
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Some(key) = &effect.key {
+            let previous = self
+                .running
+                .lock()
+                .unwrap()
+                .insert(key.clone(), (id, token.clone()));
+            if let Some((_, previous)) = previous {
+                previous.cancel();
+            }
+        }
+
+        let tx = self.action_tx.clone();
+        let running = self.running.clone();
+        let future = (effect.run)(tx.clone());
+        tokio::spawn(async move {
+            let result = tokio::select! {
+                _ = token.cancelled() => None,
+                result = future => Some(result),
+            };
+            // Unless it was replaced in the meantime, in which case the key is the new one's
+            if let Some(key) = effect.key {
+                let mut running = running.lock().unwrap();
+                if running.get(&key).is_some_and(|(running, _)| *running == id) {
+                    running.remove(&key);
+                }
+            }
+            let Some(result) = result else {
+                return;
+            };
+
+            let action = match result {
+                Ok(action) => action,
+                Err(e) => Some(Action::Toast(format!("{e:#}"))),
+            };
+            if let Some(action) = action {
+                // The receiver is gone once rev quits
+                let _ = tx.send(action);
+            }
+        });
+    }
+
+    /// Cancels the effects still running.
+    pub fn cancel_all(&self) {
+        self.cancel.cancel();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use tokio::sync::mpsc;
+
+    use super::{Effect, EffectRunner, Update};
+    use crate::action::Action;
+
+    #[tokio::test]
+    async fn test_replacing_effects() -> anyhow::Result<()> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = EffectRunner::new(tx);
+
+        let slow = Effect::new(async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(Some(Action::Toast("stale".into())))
+        })
+        .replacing("fetch");
+        let fresh =
+            Effect::new(async { Ok(Some(Action::Toast("fresh".into()))) }).replacing("fetch");
+        let failing = Effect::new(async { anyhow::bail!("offline") });
+
+        runner.run(Update::action(Some(Action::Refresh)).effect(slow))?;
+        runner.run(Update::default().effect(fresh).effect(failing))?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut actions = Vec::new();
+        while let Ok(action) = rx.try_recv() {
+            actions.push(action);
+        }
+        assert_eq!(Some(&Action::Refresh), actions.first());
+        assert!(actions.contains(&Action::Toast("fresh".into())));
+        assert!(actions.contains(&Action::Toast("offline".into())));
+        assert!(!actions.contains(&Action::Toast("stale".into())));
+        assert!(runner.running.lock().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sending_effects() -> anyhow::Result<()> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = EffectRunner::new(tx);
+
+        let polling = Effect::sending(|tx| async move {
+            tx.send(Action::Toast("polling".into()))?;
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(Some(Action::Toast("polled".into())))
+        })
+        .replacing("poll");
+        runner.run(Update::default().effect(polling))?;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(runner.running.lock().unwrap().contains_key("poll"));
+
+        runner.run(Update::default().effect(Effect::cancelling("poll")))?;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(runner.running.lock().unwrap().is_empty());
+
+        let mut actions = Vec::new();
+        while let Ok(action) = rx.try_recv() {
+            actions.push(action);
+        }
+        assert_eq!(vec![Action::Toast("polling".into())], actions);
+
+        Ok(())
+    }
+}
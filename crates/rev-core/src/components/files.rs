@@ -0,0 +1,268 @@
+use ratatui::{prelude::*, widgets::*};
+
+use super::Component;
+use crate::{
+    action::Action,
+    config::Config,
+    diff_stats::{DiffStat, DiffStats},
+    i18n::{tr, Text},
+    patch::{DiffLine, FileDiff},
+    terminal::Capabilities,
+    theme::Theme,
+};
+
+/// Shows the changes of a review file by file, as fetched from the provider.
+#[derive(Default)]
+pub struct Files {
+    /// The review in the form `owner/name#number`.
+    review: String,
+    files: Vec<FileDiff>,
+    stats: DiffStats,
+    /// Shows the stats in place of the diff of the selected file.
+    show_stats: bool,
+    selected: usize,
+    /// The first line of the selected file shown.
+    scroll: usize,
+    theme: Theme,
+    terminal: Capabilities,
+}
+
+impl Files {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index.min(self.files.len().saturating_sub(1));
+        self.scroll = 0;
+    }
+
+    /// Scrolls to the next or previous hunk of the selected file.
+    fn jump_to_hunk(&mut self, forward: bool) {
+        let Some(file) = self.files.get(self.selected) else {
+            return;
+        };
+        let mut hunks = file
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| matches!(l, DiffLine::Hunk(_)))
+            .map(|(i, _)| i);
+
+        let hunk = if forward {
+            hunks.find(|i| *i > self.scroll)
+        } else {
+            hunks.rev().find(|i| *i < self.scroll)
+        };
+        if let Some(hunk) = hunk {
+            self.scroll = hunk;
+        }
+    }
+
+    fn draw_stats(&self, f: &mut crate::tui::Frame<'_>, area: Rect) {
+        let height = |stats: &[DiffStat]| stats.len() as u16 + 2;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(height(&self.stats.directories)),
+                Constraint::Min(height(&self.stats.file_types)),
+            ])
+            .split(area);
+
+        for (stats, title, area) in [
+            (&self.stats.directories, Text::Directories, chunks[0]),
+            (&self.stats.file_types, Text::FileTypes, chunks[1]),
+        ] {
+            // The borders take up a column on either side
+            let width = area.width.saturating_sub(2) as usize;
+            f.render_widget(
+                Paragraph::new(self.gauges(stats, width))
+                    .block(self.theme.block().title(tr(title))),
+                area,
+            );
+        }
+    }
+
+    /// A line per group, with a bar as long as its share of the largest group split into its
+    /// additions and deletions.
+    fn gauges<'a>(&self, stats: &[DiffStat], width: usize) -> Vec<Line<'a>> {
+        let symbols = self.terminal.symbols();
+        let name_width = stats
+            .iter()
+            .map(|s| s.name.chars().count())
+            .max()
+            .unwrap_or_default()
+            .min(width / 3);
+        let counts = stats
+            .iter()
+            .map(|s| {
+                format!(
+                    "+{} -{} ({} {})",
+                    s.additions,
+                    s.deletions,
+                    s.files,
+                    tr(Text::Files)
+                )
+            })
+            .collect::<Vec<_>>();
+        let counts_width = counts
+            .iter()
+            .map(|c| c.chars().count())
+            .max()
+            .unwrap_or_default();
+        let bar_width = width.saturating_sub(name_width + counts_width + 2);
+        let largest = stats
+            .iter()
+            .map(|s| s.lines())
+            .max()
+            .unwrap_or_default()
+            .max(1);
+
+        stats
+            .iter()
+            .zip(counts)
+            .map(|(stat, counts)| {
+                let length = stat.lines() * bar_width / largest;
+                let added = (stat.additions * length)
+                    .checked_div(stat.lines())
+                    .unwrap_or_default();
+                let name = stat.name.chars().take(name_width).collect::<String>();
+
+                Line::from(vec![
+                    Span::raw(format!("{name:<name_width$} ")),
+                    Span::styled(
+                        symbols.bar.repeat(added),
+                        Style::default().fg(self.theme.success),
+                    ),
+                    Span::styled(
+                        symbols.bar.repeat(length - added),
+                        Style::default().fg(self.theme.failure),
+                    ),
+                    Span::raw(" ".repeat(bar_width - length + 1)),
+                    Span::styled(counts, Style::default().fg(self.theme.muted)),
+                ])
+            })
+            .collect()
+    }
+
+    fn lines<'a>(&self, file: &FileDiff) -> Vec<Line<'a>> {
+        file.lines
+            .iter()
+            .map(|line| match line {
+                DiffLine::Hunk(text) => {
+                    Line::styled(text.clone(), Style::default().fg(self.theme.accent))
+                }
+                DiffLine::Added(text) => {
+                    Line::styled(format!("+{text}"), Style::default().fg(self.theme.success))
+                }
+                DiffLine::Removed(text) => {
+                    Line::styled(format!("-{text}"), Style::default().fg(self.theme.failure))
+                }
+                DiffLine::Context(text) => Line::from(format!(" {text}")),
+            })
+            .collect()
+    }
+}
+
+impl Component for Files {
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.theme = config.theme;
+        self.terminal = config.terminal;
+
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        match action {
+            Action::ShowFiles { review, files } => {
+                self.review = review;
+                self.stats = DiffStats::new(&files);
+                self.files = files;
+                self.select(0);
+            }
+            Action::ToggleStats => self.show_stats = !self.show_stats,
+            Action::SelectNext => self.select(self.selected + 1),
+            Action::SelectPrevious => self.select(self.selected.saturating_sub(1)),
+            Action::NextComment => self.jump_to_hunk(true),
+            Action::PreviousComment => self.jump_to_hunk(false),
+            Action::Scroll { down, .. } => {
+                let len = self
+                    .files
+                    .get(self.selected)
+                    .map(|f| f.lines.len())
+                    .unwrap_or_default();
+                self.scroll = match down {
+                    true => (self.scroll + 3).min(len.saturating_sub(1)),
+                    false => self.scroll.saturating_sub(3),
+                };
+            }
+            Action::Back => return Ok(Some(Action::TogglePage("files".into()))),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+            .split(area);
+
+        let items = self
+            .files
+            .iter()
+            .map(|file| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(file.path.clone()),
+                    Span::styled(
+                        format!(" +{}", file.additions()),
+                        Style::default().fg(self.theme.success),
+                    ),
+                    Span::styled(
+                        format!(" -{}", file.deletions()),
+                        Style::default().fg(self.theme.failure),
+                    ),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_stateful_widget(
+            List::new(items)
+                .block(self.theme.block().title(format!(
+                    "{} {}",
+                    tr(Text::ChangedFiles),
+                    self.review
+                )))
+                .highlight_style(self.theme.selection()),
+            chunks[0],
+            &mut state,
+        );
+
+        if self.show_stats {
+            self.draw_stats(f, chunks[1]);
+            return Ok(());
+        }
+
+        let Some(file) = self.files.get(self.selected) else {
+            f.render_widget(
+                Paragraph::new(tr(Text::NoChangedFiles)).block(self.theme.block()),
+                chunks[1],
+            );
+            return Ok(());
+        };
+
+        let title = match file.old_path.as_ref() {
+            Some(old_path) => format!("{old_path} -> {}", file.path),
+            None => file.path.clone(),
+        };
+        f.render_widget(
+            Paragraph::new(self.lines(file))
+                .block(self.theme.block().title(title))
+                .scroll((self.scroll.min(u16::MAX as usize) as u16, 0)),
+            chunks[1],
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,2156 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::*};
+use rev_git_provider::models::{
+    Comment, Commit, MergeStrategy, Review, ReviewEvent, ReviewThread, StatusCheck, Vulnerability,
+};
+use rev_widget_list::{SelectableWidgetList, WidgetListItem};
+
+use tokio::sync::{
+    mpsc::{Receiver, UnboundedSender},
+    Mutex,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    action::{Action, GitHubPrAction},
+    commit_lint::CommitLint,
+    components::{
+        composer::{Composer, ComposerEvent},
+        confirm::{Confirm, ConfirmEvent},
+        github_pr::{
+            comments::{
+                self as comment_tabs, filter_comments, CommentItem, CommentsTab, DisplayComment,
+                FilteredComments,
+            },
+            status::StatusCheckItem,
+        },
+    },
+    config::{Config, PaneCommand},
+    coverage::{self, Coverage, CoverageKind},
+    description_lint::DescriptionLint,
+    drafts::ReviewDraft,
+    file_badges,
+    git_pull_requests::GitPullRequest,
+    i18n::{tr, Text},
+    links::{comment_anchor, find_links, Link, ReviewTarget},
+    multiplexer::Multiplexer,
+    platform, snooze,
+    state::State,
+    store::Store,
+    summarizer,
+    theme::Theme,
+    workspace::Workspace,
+};
+
+pub mod comments;
+pub mod commits;
+pub mod links;
+pub mod participants;
+pub mod status;
+pub mod threads;
+
+use super::{self as components, chips::Chips, Component, Effect};
+
+pub struct GithubPr {
+    vertical_scroll_state: ScrollbarState,
+    prs_provider: GitPullRequest,
+    /// The state shared with the list, such as the badges of the reviews it fetched already.
+    app_state: State,
+    action_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    state: GitHubPrAction,
+    pr: Option<Review>,
+    prs_stream: Arc<Mutex<Option<Receiver<anyhow::Result<Review>>>>>,
+    /// Reviews whose checks completed while snoozed, these go before the rest of the queue.
+    resurfaced: Arc<std::sync::Mutex<VecDeque<Review>>>,
+    show_all_comments: bool,
+    /// The tab of the comments pane picked last, see [`GithubPr::shown_comments_tab`].
+    comments_tab: CommentsTab,
+    selected_comment: usize,
+    /// The index of the selected check, in the order of [`GithubPr::checks`].
+    selected_check: usize,
+    /// The index of the selected thread, in the order of [`GithubPr::threads`].
+    selected_thread: usize,
+    /// The names of the checks of the shown review known to be flaky.
+    flaky_checks: HashSet<String>,
+    /// The vulnerabilities the shown review fixes, when it's a dependency update.
+    security_fixes: Vec<Vulnerability>,
+    description_lint: Option<DescriptionLint>,
+    /// What the summarizer made of the conversation of the shown review.
+    summary: Option<String>,
+    /// The commits of the shown review, `None` until they're fetched.
+    commits: Option<Vec<Commit>>,
+    /// The commit lint rules the commits and branch of the shown review break.
+    commit_lint: Option<CommitLint>,
+    /// The head of the shown review at the last visit, when changes were pushed since.
+    last_seen_head: Option<String>,
+    /// The heads of the shown review before and after it was force-pushed.
+    force_push: Option<(String, String)>,
+    /// The diff last shown of a review, shown again when it's refreshed.
+    shown_diff: Option<ShownDiff>,
+    /// The index of the selected link, in the order of [`GithubPr::links`].
+    selected_link: Option<usize>,
+    composer: Composer,
+    /// The id of the comment being edited in the composer.
+    editing_comment: Option<String>,
+    /// The verdict the review is submitted with once the composer is.
+    submitting: Option<ReviewEvent>,
+    /// The composer holds the comment asking the author to fix the commits.
+    requesting_commit_fixes: bool,
+    /// The thread the reply in the composer goes to.
+    replying_to_thread: Option<ReviewThread>,
+    /// The id of a comment waiting for the delete to be confirmed.
+    pending_delete: Option<String>,
+    /// The effects left by the helpers, until the app picks them up.
+    effects: Vec<Effect>,
+    force_pushed: bool,
+    /// Asks how to merge the shown review.
+    merge_dialog: Confirm,
+    /// The outcome of the last merge, shown in the status bar.
+    status: Option<String>,
+    /// Why fetching the reviews failed, shown until the next review is fetched.
+    fetch_error: Option<String>,
+    /// Where the panes were last drawn, to scroll the one under the mouse.
+    description_area: Rect,
+    comments_area: Option<Rect>,
+    checks_area: Option<Rect>,
+    description_scroll: u16,
+    /// The lines of the description as last drawn, to stop scrolling at its end.
+    description_lines: u16,
+    /// The pane the selection and page keys move.
+    focused_pane: Pane,
+    /// The saved search the queue was started from, the queue starts over when it changes.
+    queue_search: Option<String>,
+    /// The review to show before the queue, such as one given to `rev review`.
+    focus: Option<ReviewTarget>,
+    /// The anchor of the comment to select once the comments of the shown review are in.
+    focus_comment: Option<u64>,
+}
+
+/// A diff run in the checkout of a review on the diff page.
+#[derive(Clone)]
+struct ShownDiff {
+    repository: String,
+    number: usize,
+    /// The commits fetched into the checkout first, as they may no longer be on its branch.
+    commits: Vec<String>,
+    args: String,
+}
+
+/// How many comments, threads or checks a page key moves by, as they span several lines each.
+const PAGE_ITEMS: usize = 5;
+
+const CHECKS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const HEAD_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// The key of the effect polling the head of the shown review.
+const HEAD_WATCH: &str = "head-watch";
+
+impl GithubPr {
+    pub fn new(prs_provider: GitPullRequest, app_state: State) -> Self {
+        Self {
+            prs_provider,
+            app_state,
+            action_tx: None,
+            config: Config::default(),
+            state: GitHubPrAction::Normal,
+            pr: None,
+            prs_stream: Arc::default(),
+            resurfaced: Arc::default(),
+            show_all_comments: false,
+            comments_tab: CommentsTab::default(),
+            selected_comment: 0,
+            selected_check: 0,
+            selected_thread: 0,
+            flaky_checks: HashSet::new(),
+            security_fixes: Vec::new(),
+            description_lint: None,
+            summary: None,
+            commits: None,
+            commit_lint: None,
+            last_seen_head: None,
+            force_push: None,
+            shown_diff: None,
+            selected_link: None,
+            composer: Composer::default(),
+            editing_comment: None,
+            submitting: None,
+            requesting_commit_fixes: false,
+            replying_to_thread: None,
+            pending_delete: None,
+            effects: Vec::new(),
+            force_pushed: false,
+            merge_dialog: Confirm::default(),
+            status: None,
+            fetch_error: None,
+            queue_search: None,
+            description_area: Rect::default(),
+            comments_area: None,
+            checks_area: None,
+            description_scroll: 0,
+            description_lines: 0,
+            focused_pane: Pane::default(),
+            vertical_scroll_state: ScrollbarState::default(),
+            focus: None,
+            focus_comment: None,
+        }
+    }
+
+    /// Starts on the review, at the comment when the target links to one, rather than on the
+    /// queue.
+    pub fn focus(mut self, target: Option<ReviewTarget>) -> Self {
+        self.focus = target;
+        self
+    }
+
+    fn open_target(&mut self, target: ReviewTarget) {
+        self.focus_comment = target.comment;
+
+        let prs = self.prs_provider.clone();
+        self.spawn(
+            Effect::new(async move {
+                let reference = format!("{}#{}", target.repository, target.number);
+                Ok(Some(
+                    match prs.get_review(&target.repository, target.number).await? {
+                        Some(review) => Action::GitHubPrs(GitHubPrAction::NextReview {
+                            pr: Box::new(review),
+                        }),
+                        None => Action::Toast(format!("{} {reference}", tr(Text::ReviewNotFound))),
+                    },
+                ))
+            })
+            .replacing("review"),
+        );
+    }
+
+    /// Leaves the effect to the app, see [`Component::take_effects`].
+    fn spawn(&mut self, effect: Effect) {
+        self.effects.push(effect);
+    }
+
+    /// Selects the comment or thread the review was opened at, once its comments are in.
+    fn scroll_description(&mut self, down: bool, lines: u16) {
+        self.description_scroll = if down {
+            self.description_scroll
+                .saturating_add(lines)
+                .min(self.description_lines.saturating_sub(1))
+        } else {
+            self.description_scroll.saturating_sub(lines)
+        };
+    }
+
+    /// Moves the focused pane down or up by a line or an item, or by a page of them.
+    fn move_focused(&mut self, down: bool, page: bool) {
+        let step = |index: usize, len: usize| {
+            let by = if page { PAGE_ITEMS } else { 1 };
+            if down {
+                (index + by).min(len.saturating_sub(1))
+            } else {
+                index.saturating_sub(by)
+            }
+        };
+
+        match self.focused_pane {
+            Pane::Description => {
+                // A page keeps the last line of the one before in view
+                let lines = match page {
+                    true => self.description_area.height.saturating_sub(3).max(1),
+                    false => 1,
+                };
+                self.scroll_description(down, lines);
+            }
+            Pane::Comments if self.shown_comments_tab() == CommentsTab::Threads => {
+                self.selected_thread = step(self.selected_thread, self.threads().len());
+            }
+            Pane::Comments => {
+                let len = self.visible_comments().comments.len();
+                self.selected_comment = step(self.selected_comment, len);
+            }
+            Pane::Checks => self.selected_check = step(self.selected_check, self.checks().len()),
+        }
+    }
+
+    /// The pane after or before the focused one, skipping the panes which aren't shown.
+    fn next_pane(&self, forward: bool) -> Pane {
+        let comments = self
+            .pr
+            .as_ref()
+            .and_then(|pr| pr.comments.as_ref())
+            .is_some_and(|c| !c.comments.is_empty() || !c.threads.is_empty());
+        let shown = [
+            Some(Pane::Description),
+            comments.then_some(Pane::Comments),
+            self.checks_area.map(|_| Pane::Checks),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        let index = shown
+            .iter()
+            .position(|p| *p == self.focused_pane)
+            .unwrap_or_default();
+        let next = match forward {
+            true => (index + 1) % shown.len(),
+            false => (index + shown.len() - 1) % shown.len(),
+        };
+
+        shown[next]
+    }
+
+    fn pane_at(&self, column: u16, row: u16) -> Option<Pane> {
+        let over = |area: Option<Rect>| {
+            area.map(|a| components::contains(a, column, row))
+                .unwrap_or_default()
+        };
+
+        if over(Some(self.description_area)) {
+            Some(Pane::Description)
+        } else if over(self.comments_area) {
+            Some(Pane::Comments)
+        } else if over(self.checks_area) {
+            Some(Pane::Checks)
+        } else {
+            None
+        }
+    }
+
+    fn select_focused_comment(&mut self) -> Option<Action> {
+        let anchor = self.focus_comment.take()?;
+        let linked = |c: &Comment| comment_anchor(&c.url) == Some(anchor);
+
+        if let Some(id) = self
+            .comments()
+            .iter()
+            .find(|c| linked(c))
+            .map(|c| c.id.clone())
+        {
+            // The comment may be hidden by the comment filters
+            if !self.visible_comments().comments.iter().any(|c| c.id == id) {
+                self.show_all_comments = true;
+            }
+            self.selected_comment = self
+                .visible_comments()
+                .comments
+                .iter()
+                .position(|c| c.id == id)
+                .unwrap_or_default();
+            self.comments_tab = CommentsTab::Conversation;
+            return None;
+        }
+
+        match self
+            .threads()
+            .iter()
+            .position(|t| t.comments.iter().any(linked))
+        {
+            Some(index) => {
+                self.selected_thread = index;
+                self.comments_tab = CommentsTab::Threads;
+                None
+            }
+            None => Some(Action::Toast(tr(Text::CommentNotFound).into())),
+        }
+    }
+
+    /// Copies the permalink of the selected comment, or of the first comment of the selected
+    /// thread.
+    fn copy_comment_link(&self, thread: bool) -> Option<Action> {
+        let url = if thread {
+            self.selected_thread()?.comments.first()?.url.clone()
+        } else {
+            self.visible_comments()
+                .comments
+                .into_iter()
+                .nth(self.selected_comment)?
+                .url
+        };
+        if url.is_empty() {
+            return Some(Action::Toast(tr(Text::NoCommentLink).into()));
+        }
+
+        Some(Action::Toast(match self.config.terminal.copy(&url) {
+            Ok(()) => format!("{} {url}", tr(Text::LinkCopied)),
+            Err(e) => format!("{e:#}"),
+        }))
+    }
+
+    fn schedule_fetch(&mut self) {
+        let prs = self.prs_provider.clone();
+        let prs_stream = self.prs_stream.clone();
+        let tags = self.config.queue.tags();
+        let fetch = Effect::sending(|tx| async move {
+            let mut prs_stream = prs_stream.lock().await;
+            tx.send(Action::GitHubPrs(GitHubPrAction::EnterProcessing))?;
+            if prs_stream.is_none() {
+                *prs_stream = prs.run(tags).await.ok();
+            }
+
+            if let Some(ref mut pr_stream) = *prs_stream {
+                match pr_stream.recv().await {
+                    Some(Ok(pr)) => tx.send(Action::GitHubPrs(GitHubPrAction::NextReview {
+                        pr: Box::new(pr),
+                    }))?,
+                    Some(Err(e)) => tx.send(Action::GitHubPrs(GitHubPrAction::ReviewsFailed(
+                        format!("{e:#}"),
+                    )))?,
+                    None => tx.send(Action::GitHubPrs(GitHubPrAction::DoneReview))?,
+                }
+            }
+
+            Ok(Some(Action::GitHubPrs(GitHubPrAction::ExitProcessing)))
+        });
+        self.spawn(fetch.replacing("review"));
+    }
+
+    /// The comments of the shown review, empty until they're loaded.
+    fn comments(&self) -> &[Comment] {
+        self.pr
+            .as_ref()
+            .and_then(|pr| pr.comments.as_ref())
+            .map(|c| c.comments.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn comments_mut(&mut self) -> impl Iterator<Item = &mut Comment> {
+        self.pr
+            .iter_mut()
+            .filter_map(|pr| pr.comments.as_mut())
+            .flat_map(|c| c.comments.iter_mut())
+    }
+
+    fn visible_comments(&self) -> FilteredComments {
+        if self.show_all_comments {
+            FilteredComments {
+                comments: self.comments().iter().map(DisplayComment::new).collect(),
+                hidden: 0,
+            }
+        } else {
+            filter_comments(self.comments(), &self.config.comments)
+        }
+    }
+
+    /// Fetches the parts of the review left out of the summary, once it's shown. Those of the
+    /// review shown before are cancelled, as they're of no use anymore.
+    fn load_details(&mut self) {
+        let Some(pr) = self.pr.clone() else {
+            return;
+        };
+
+        if pr.comments.is_none() {
+            let prs = self.prs_provider.clone();
+            let pr = pr.clone();
+            self.spawn(
+                Effect::new(async move {
+                    Ok(match prs.get_comments(&pr).await {
+                        Ok(comments) => Some(Action::GitHubPrs(GitHubPrAction::ReviewComments {
+                            id: pr.id,
+                            comments,
+                        })),
+                        Err(e) => {
+                            tracing::warn!("failed to load comments: {e}");
+                            None
+                        }
+                    })
+                })
+                .replacing("comments"),
+            );
+        }
+
+        {
+            let prs = self.prs_provider.clone();
+            let pr = pr.clone();
+            self.spawn(
+                Effect::new(async move {
+                    Ok(match prs.security_fixes(&pr).await {
+                        Ok(fixes) if !fixes.is_empty() => {
+                            Some(Action::GitHubPrs(GitHubPrAction::SecurityFixes {
+                                id: pr.id,
+                                fixes,
+                            }))
+                        }
+                        Ok(_) => None,
+                        Err(e) => {
+                            tracing::warn!("failed to look up security fixes: {e}");
+                            None
+                        }
+                    })
+                })
+                .replacing("security-fixes"),
+            );
+        }
+
+        {
+            let prs = self.prs_provider.clone();
+            let pr = pr.clone();
+            self.spawn(
+                Effect::new(async move {
+                    Ok(match prs.lint_description(&pr).await {
+                        Ok(lint) => lint.map(|lint| {
+                            Action::GitHubPrs(GitHubPrAction::DescriptionLint { id: pr.id, lint })
+                        }),
+                        Err(e) => {
+                            tracing::warn!("failed to lint description: {e}");
+                            None
+                        }
+                    })
+                })
+                .replacing("description-lint"),
+            );
+        }
+
+        {
+            let prs = self.prs_provider.clone();
+            let settings = self.config.badges.clone();
+            let pr = pr.clone();
+            self.spawn(
+                Effect::new(async move {
+                    Ok(match prs.changed_paths(&pr).await {
+                        Ok(paths) => Some(Action::GitHubPrs(GitHubPrAction::FileBadges {
+                            id: pr.id,
+                            badges: file_badges::badges(&settings, &paths),
+                            paths,
+                        })),
+                        Err(e) => {
+                            tracing::warn!("failed to load changed files: {e}");
+                            None
+                        }
+                    })
+                })
+                .replacing("file-badges"),
+            );
+        }
+
+        {
+            let prs = self.prs_provider.clone();
+            let pr = pr.clone();
+            self.spawn(
+                Effect::new(async move {
+                    Ok(match prs.get_commits(&pr).await {
+                        Ok(commits) => Some(Action::GitHubPrs(GitHubPrAction::ReviewCommits {
+                            id: pr.id,
+                            commits,
+                        })),
+                        Err(e) => {
+                            tracing::warn!("failed to load commits: {e}");
+                            None
+                        }
+                    })
+                })
+                .replacing("commits"),
+            );
+        }
+
+        if pr.status_checks.is_none() {
+            let prs = self.prs_provider.clone();
+            self.spawn(
+                Effect::new(async move {
+                    Ok(match prs.get_status_checks(&pr).await {
+                        Ok(checks) => Some(Action::GitHubPrs(GitHubPrAction::ReviewStatusChecks {
+                            id: pr.id,
+                            checks,
+                        })),
+                        Err(e) => {
+                            tracing::warn!("failed to load status checks: {e}");
+                            None
+                        }
+                    })
+                })
+                .replacing("status-checks"),
+            );
+        }
+    }
+
+    /// The checks of the shown review grouped by app, as they're listed.
+    fn checks(&self) -> Vec<&StatusCheck> {
+        let checks = self
+            .pr
+            .as_ref()
+            .and_then(|pr| pr.status_checks.as_deref())
+            .unwrap_or_default();
+
+        status::group_by_app(checks)
+            .into_iter()
+            .flat_map(|(_, checks)| checks)
+            .collect()
+    }
+
+    /// The threads on the diff of the shown review grouped by file, as they're listed.
+    fn threads(&self) -> Vec<&ReviewThread> {
+        self.pr
+            .as_ref()
+            .and_then(|pr| pr.comments.as_ref())
+            .map(|comments| {
+                comments
+                    .threads_by_file()
+                    .into_iter()
+                    .flat_map(|(_, threads)| threads)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The tab of the comments pane shown, the other one when the picked one has nothing in it.
+    fn shown_comments_tab(&self) -> CommentsTab {
+        match self.comments_tab {
+            CommentsTab::Conversation
+                if self.comments().is_empty() && !self.threads().is_empty() =>
+            {
+                CommentsTab::Threads
+            }
+            CommentsTab::Threads if self.threads().is_empty() => CommentsTab::Conversation,
+            tab => tab,
+        }
+    }
+
+    fn selected_thread(&self) -> Option<ReviewThread> {
+        self.threads()
+            .get(self.selected_thread)
+            .map(|t| (*t).clone())
+    }
+
+    fn open_check(&self) -> Option<Action> {
+        let checks = self.checks();
+        let check = checks.get(self.selected_check)?;
+        let Some(url) = check.url() else {
+            return Some(Action::Toast(tr(Text::CheckWithoutUrl).into()));
+        };
+
+        platform::open_url(url)
+            .err()
+            .map(|e| Action::Toast(format!("{e:#}")))
+    }
+
+    /// The links of the description, followed by those of the visible comments along with the
+    /// index of their comment.
+    fn links(&self) -> Vec<(Option<usize>, Link)> {
+        let Some(pr) = self.pr.as_ref() else {
+            return Vec::new();
+        };
+
+        let mut all = find_links(&pr.description)
+            .into_iter()
+            .map(|(_, link)| (None, link))
+            .collect::<Vec<_>>();
+        for (index, comment) in self.visible_comments().comments.iter().enumerate() {
+            all.extend(
+                find_links(&comment.text)
+                    .into_iter()
+                    .map(|(_, link)| (Some(index), link)),
+            );
+        }
+
+        all
+    }
+
+    /// Moves to the next or previous link, going around at the ends.
+    fn select_link(&mut self, forward: bool) -> Option<Action> {
+        let links = self.links();
+        if links.is_empty() {
+            return Some(Action::Toast(tr(Text::NoLinks).into()));
+        }
+
+        let index = match (self.selected_link, forward) {
+            (None, true) => 0,
+            (None, false) => links.len() - 1,
+            (Some(i), true) => (i + 1) % links.len(),
+            (Some(i), false) => (i + links.len() - 1) % links.len(),
+        };
+        if let Some(comment) = links[index].0 {
+            self.selected_comment = comment;
+        }
+        self.selected_link = Some(index);
+
+        None
+    }
+
+    /// Opens the selected link, reviews are shown in rev and everything else in the browser.
+    fn open_link(&mut self, index: usize) -> Option<Action> {
+        let pr = self.pr.clone()?;
+        let (_, link) = self.links().into_iter().nth(index)?;
+
+        let Link::Review { repository, number } = &link else {
+            return platform::open_url(&link.url(&pr.repository))
+                .err()
+                .map(|e| Action::Toast(format!("{e:#}")));
+        };
+
+        let repository = repository.clone().unwrap_or(pr.repository.clone());
+        let number = *number;
+        let prs = self.prs_provider.clone();
+        let resurfaced = self.resurfaced.clone();
+        self.spawn(
+            Effect::new(async move {
+                match prs.get_review(&repository, number).await {
+                    Ok(Some(review)) => {
+                        // Comes back to the review the link was in afterwards
+                        resurfaced.lock().unwrap().push_front(pr);
+                        Ok(Some(Action::GitHubPrs(GitHubPrAction::NextReview {
+                            pr: Box::new(review),
+                        })))
+                    }
+                    // Most likely an issue rather than a pull request
+                    _ => {
+                        platform::open_url(&link.url(&repository))?;
+                        Ok(None)
+                    }
+                }
+            })
+            .replacing("link"),
+        );
+
+        None
+    }
+
+    fn request_description(&mut self) -> Option<Action> {
+        let pr = self.pr.clone()?;
+        let lint = self.description_lint.take()?;
+
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::new(async move {
+            prs.request_description(&pr, &lint).await?;
+            Ok(Some(Action::Toast(tr(Text::DescriptionRequested).into())))
+        }));
+
+        None
+    }
+
+    /// Opens the composer with the comment asking the author to fix the commits.
+    fn compose_commit_fixes(&mut self) -> Option<Action> {
+        let pr = self.pr.as_ref()?;
+        let Some(lint) = self.commit_lint.as_ref() else {
+            return Some(Action::Toast(tr(Text::NoCommitLintWarnings).into()));
+        };
+
+        self.composer.open(
+            tr(Text::RequestCommitFixes),
+            lint.request(&self.config.commit_lint, &pr.author),
+        );
+        self.requesting_commit_fixes = true;
+
+        Some(Action::EnterInsertMode)
+    }
+
+    fn request_commit_fixes(&mut self, body: String) {
+        let Some(pr) = self.pr.clone() else {
+            return;
+        };
+
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::new(async move {
+            prs.request_commit_fixes(&pr, body).await?;
+            Ok(Some(Action::Toast(tr(Text::CommitFixesRequested).into())))
+        }));
+    }
+
+    fn compose_thread_reply(&mut self) -> Option<Action> {
+        self.pr.as_ref()?;
+        let Some(thread) = self.selected_thread() else {
+            return Some(Action::Toast(tr(Text::NoThreadSelected).into()));
+        };
+
+        self.composer.open(tr(Text::ReplyToThread), String::new());
+        self.replying_to_thread = Some(thread);
+
+        Some(Action::EnterInsertMode)
+    }
+
+    fn reply_to_thread(&mut self, thread: ReviewThread, body: String) {
+        let Some(pr) = self.pr.clone() else {
+            return;
+        };
+
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::sending(|tx| async move {
+            prs.reply_to_thread(&pr, &thread, body).await?;
+            Ok(Some(
+                thread_changed(&tx, &prs, &pr, tr(Text::ReplySent)).await?,
+            ))
+        }));
+    }
+
+    fn resolve_thread(&mut self) -> Option<Action> {
+        let pr = self.pr.clone()?;
+        let Some(thread) = self.selected_thread() else {
+            return Some(Action::Toast(tr(Text::NoThreadSelected).into()));
+        };
+
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::sending(|tx| async move {
+            prs.resolve_thread(&pr, &thread).await?;
+            Ok(Some(
+                thread_changed(&tx, &prs, &pr, tr(Text::ThreadResolved)).await?,
+            ))
+        }));
+
+        None
+    }
+
+    /// Hands the shown review over to the teammate with the fewest open review requests, then
+    /// moves on to the next review.
+    fn reassign_review(&mut self) -> Option<Action> {
+        let pr = self.pr.clone()?;
+        let Some(me) = self.config.committer.clone() else {
+            return Some(Action::Toast(tr(Text::NoCommitter).into()));
+        };
+        if self.config.team.members.is_empty() {
+            return Some(Action::Toast(tr(Text::NoTeam).into()));
+        }
+
+        let members = self.config.team.members.clone();
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::sending(|tx| async move {
+            let to = prs.reassign_to_least_loaded(&pr, &me, &members).await?;
+            tx.send(Action::Toast(format!(
+                "{} {to}",
+                tr(Text::ReviewReassigned)
+            )))?;
+            Ok(Some(Action::SkipReview))
+        }));
+
+        None
+    }
+
+    /// Opens the command in a pane next to rev, in the checkout of the review when it needs the
+    /// code.
+    fn open_pane(&mut self, pane: PaneCommand, in_checkout: bool) -> Option<Action> {
+        let pr = self.pr.as_ref()?;
+        let Some(multiplexer) = Multiplexer::detect() else {
+            return Some(Action::Toast(tr(Text::NoMultiplexer).into()));
+        };
+        let command = pane.command.map(|c| {
+            c.replace("{repository}", &pr.repository)
+                .replace("{number}", &pr.number.to_string())
+                .replace("{branch}", &pr.head_branch)
+        });
+
+        let workspace = Workspace::new(self.config.workspace.clone(), Store::default());
+        let repository = pr.repository.clone();
+        let number = pr.number;
+        self.spawn(Effect::new(async move {
+            let cwd = if in_checkout {
+                let (owner, name) = repository.split_once('/').ok_or(anyhow::anyhow!(
+                    "review repository to be in the form owner/name"
+                ))?;
+                workspace.checkout(owner, name, number).await?
+            } else {
+                std::env::current_dir()?
+            };
+
+            multiplexer
+                .open(pane.placement, &cwd, command.as_deref())
+                .await?;
+            Ok(None)
+        }));
+
+        in_checkout.then(|| Action::Toast(tr(Text::CheckingOut).into()))
+    }
+
+    fn summarize(&mut self) -> Option<Action> {
+        let Some(command) = self.config.summarizer.command.clone() else {
+            return Some(Action::Toast(tr(Text::NoSummarizer).into()));
+        };
+        let pr = self.pr.as_ref()?;
+
+        let id = pr.id.clone();
+        let conversation = summarizer::conversation(pr);
+        self.spawn(
+            Effect::new(async move {
+                let summary = summarizer::summarize(&command, conversation).await?;
+                Ok(Some(Action::GitHubPrs(GitHubPrAction::Summary {
+                    id,
+                    summary,
+                })))
+            })
+            .replacing("summary"),
+        );
+
+        Some(Action::Toast(tr(Text::Summarizing).into()))
+    }
+
+    fn is_mine(&self, author: &str) -> bool {
+        self.config.committer.as_deref() == Some(author)
+    }
+
+    /// Returns the selected comment, if it was written by the current user.
+    fn selected_own_comment(&self) -> Option<DisplayComment> {
+        self.visible_comments()
+            .comments
+            .into_iter()
+            .nth(self.selected_comment)
+            .filter(|c| self.is_mine(&c.author))
+    }
+
+    fn edit_comment(&mut self) -> Option<Action> {
+        let Some(comment) = self.selected_own_comment() else {
+            return Some(Action::Toast(tr(Text::NotYourComment).into()));
+        };
+
+        let body = self
+            .comments()
+            .iter()
+            .find(|c| c.id == comment.id)
+            .map(|c| c.body.clone())
+            .unwrap_or(comment.text);
+
+        self.composer.open(tr(Text::EditComment), body);
+        self.editing_comment = Some(comment.id);
+
+        Some(Action::EnterInsertMode)
+    }
+
+    fn submit_comment_edit(&mut self, id: String, body: String) {
+        if let Some(comment) = self.comments_mut().find(|c| c.id == id) {
+            comment.text = body.clone();
+            comment.body = body.clone();
+            comment.last_edited_at = Some(chrono::Utc::now());
+        }
+
+        let review = self
+            .pr
+            .as_ref()
+            .map(|pr| pr.reference())
+            .unwrap_or_default();
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::new(async move {
+            prs.update_comment(&review, id, body).await?;
+            Ok(Some(Action::Toast(tr(Text::CommentUpdated).into())))
+        }));
+    }
+
+    /// Opens the composer for the message of the review, the verdict is submitted with it.
+    fn compose_review(&mut self, event: ReviewEvent) -> Option<Action> {
+        let pr = self.pr.as_ref()?;
+        // Carry on with a draft saved when submitting failed or imported from elsewhere
+        let draft = self
+            .prs_provider
+            .draft(&pr.reference())
+            .and_then(|d| d.submission_body())
+            .unwrap_or_default();
+
+        let title = match event {
+            ReviewEvent::Approve => Text::ApproveReview,
+            ReviewEvent::RequestChanges => Text::RequestChangesReview,
+            ReviewEvent::Comment => Text::CommentReview,
+        };
+        self.composer.open(tr(title), &draft);
+        self.submitting = Some(event);
+
+        Some(Action::EnterInsertMode)
+    }
+
+    /// Submits the shown review and moves on to the next one. If submitting fails the review
+    /// comes back after the next one and the message is kept as a draft, so neither is lost.
+    fn submit_review(&mut self, event: ReviewEvent, body: String) {
+        let tx = self.action_tx.clone().unwrap();
+        let body = Some(body.trim().to_string()).filter(|b| !b.is_empty());
+        if body.is_none() && event != ReviewEvent::Approve {
+            tx.send(Action::Toast(tr(Text::ReviewNeedsBody).into()))
+                .unwrap();
+            return;
+        }
+        let Some(pr) = self.pr.clone() else {
+            return;
+        };
+
+        let prs = self.prs_provider.clone();
+        let label_rules = self.config.labels.clone();
+        let resurfaced = self.resurfaced.clone();
+        self.spawn(Effect::new(async move {
+            let result = prs
+                .submit_review(&pr, event, body.clone(), &label_rules)
+                .await;
+            if result.is_err() {
+                // The composer started from the whole draft, so the message replaces it
+                let draft = ReviewDraft {
+                    verdict: Some(event.into()),
+                    body: body.unwrap_or_default(),
+                    ..ReviewDraft::new(pr.reference())
+                };
+                if let Err(e) = prs.save_draft(draft) {
+                    tracing::warn!("failed to save draft of {}: {e:#}", pr.reference());
+                }
+                resurfaced.lock().unwrap().push_front(pr);
+            }
+
+            result?;
+            Ok(Some(Action::Toast(tr(Text::ReviewSubmitted).into())))
+        }));
+
+        self.next_review();
+    }
+
+    fn confirm_merge(&mut self) -> Option<Action> {
+        let pr = self.pr.as_ref()?;
+        self.merge_dialog.open(
+            format!("{} {}?", tr(Text::MergeReviewWith), pr.reference()),
+            MERGE_STRATEGIES
+                .iter()
+                .map(|s| (merge_key(*s), s.name().to_string()))
+                .collect(),
+        );
+
+        Some(Action::EnterInsertMode)
+    }
+
+    fn merge(&mut self, strategy: MergeStrategy) {
+        let Some(pr) = self.pr.clone() else {
+            return;
+        };
+        self.status = Some(format!("{} {}...", tr(Text::Merging), pr.reference()));
+
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::new(async move {
+            let message = match prs.merge(&pr, strategy).await {
+                Ok(()) => format!(
+                    "{} {} ({})",
+                    tr(Text::Merged),
+                    pr.reference(),
+                    strategy.name()
+                ),
+                Err(e) => format!("{e:#}"),
+            };
+            Ok(Some(Action::GitHubPrs(GitHubPrAction::MergeFinished {
+                id: pr.id,
+                message,
+            })))
+        }));
+    }
+
+    fn delete_comment(&mut self) -> Option<Action> {
+        let Some(comment) = self.selected_own_comment() else {
+            return Some(Action::Toast(tr(Text::NotYourComment).into()));
+        };
+
+        if self.pending_delete.as_ref() != Some(&comment.id) {
+            self.pending_delete = Some(comment.id);
+            return Some(Action::Toast(tr(Text::ConfirmDeleteComment).into()));
+        }
+        self.pending_delete = None;
+
+        if let Some(comments) = self.pr.as_mut().and_then(|pr| pr.comments.as_mut()) {
+            comments.comments.retain(|c| c.id != comment.id);
+        }
+        self.selected_comment = self.selected_comment.saturating_sub(1);
+
+        let review = self
+            .pr
+            .as_ref()
+            .map(|pr| pr.reference())
+            .unwrap_or_default();
+        let prs = self.prs_provider.clone();
+        self.spawn(Effect::new(async move {
+            prs.delete_comment(&review, comment.id).await?;
+            Ok(Some(Action::Toast(tr(Text::CommentDeleted).into())))
+        }));
+
+        None
+    }
+
+    fn snooze(&self, duration: Option<chrono::Duration>) -> Option<Action> {
+        let pr = self.pr.as_ref()?;
+
+        match self.prs_provider.snooze(pr, duration) {
+            // Skipping is what moving on has always done, only snoozing is worth telling about
+            Ok(()) if duration.is_some() => None,
+            Ok(()) => Some(Action::Toast(format!(
+                "{} {}",
+                tr(Text::SnoozedReview),
+                pr.reference()
+            ))),
+            Err(e) => Some(Action::Toast(format!("{e:#}"))),
+        }
+    }
+
+    fn next_review(&mut self) {
+        self.selected_comment = 0;
+        self.selected_check = 0;
+        self.selected_thread = 0;
+        self.pending_delete = None;
+        self.fetch_error = None;
+        self.stop_head_watch();
+        let search = self.prs_provider.search();
+        if search != self.queue_search {
+            self.queue_search = search;
+            self.prs_stream = Arc::default();
+        }
+
+        if let Some(target) = self.focus.take() {
+            self.open_target(target);
+            return;
+        }
+
+        let resurfaced = self.resurfaced.lock().unwrap().pop_front();
+        match resurfaced {
+            Some(pr) => self.show_review(pr),
+            None => self.schedule_fetch(),
+        }
+    }
+
+    fn show_review(&mut self, pr: Review) {
+        self.force_pushed = false;
+        self.security_fixes = Vec::new();
+        self.description_lint = None;
+        self.commits = None;
+        self.commit_lint = None;
+        self.selected_link = None;
+        // A refresh keeps comparing with the visit before the review was first shown
+        let last_seen_head = self.prs_provider.record_visit(&pr);
+        if self.pr.as_ref().map(|p| &p.id) != Some(&pr.id) {
+            self.summary = None;
+            self.last_seen_head = last_seen_head;
+            self.force_push = None;
+            self.description_scroll = 0;
+        } else if self.last_seen_head.is_none() {
+            self.last_seen_head = last_seen_head;
+        }
+
+        // Replaces the watch of the review shown before
+        let prs = self.prs_provider.clone();
+        let watched = pr.clone();
+        self.spawn(
+            Effect::new(async move {
+                let id = watched.id.clone();
+                Ok(match prs.watch_head(watched, HEAD_POLL_INTERVAL).await {
+                    Ok(head) => {
+                        tracing::info!(head, "head of review changed");
+                        Some(Action::GitHubPrs(GitHubPrAction::HeadChanged { id, head }))
+                    }
+                    Err(e) => {
+                        tracing::warn!("failed to watch head of review: {e}");
+                        None
+                    }
+                })
+            })
+            .replacing(HEAD_WATCH),
+        );
+
+        self.pr = Some(pr);
+        self.load_details();
+    }
+
+    fn stop_head_watch(&mut self) {
+        self.spawn(Effect::cancelling(HEAD_WATCH));
+    }
+
+    /// Fetches the review again, along with the diff last shown of it.
+    fn refresh(&mut self) -> Option<Action> {
+        let pr = self.pr.clone()?;
+        let diff = self
+            .shown_diff
+            .clone()
+            .filter(|diff| diff.repository == pr.repository && diff.number == pr.number);
+
+        let prs = self.prs_provider.clone();
+        let workspace = self.workspace();
+        let refresh = Effect::sending(|tx| async move {
+            let Some(pr) = prs.refresh(&pr).await? else {
+                tracing::warn!("review {}#{} is gone", pr.repository, pr.number);
+                return Ok(None);
+            };
+            tx.send(Action::GitHubPrs(GitHubPrAction::NextReview {
+                pr: Box::new(pr),
+            }))?;
+            tx.send(Action::Toast(tr(Text::Refreshed).into()))?;
+
+            match diff {
+                Some(diff) => Ok(Some(checkout_and_diff(workspace, diff).await?)),
+                None => Ok(None),
+            }
+        });
+        self.spawn(refresh.replacing("refresh"));
+
+        None
+    }
+
+    /// Checks out the review and diffs what was pushed since the last visit.
+    fn diff_since_last_visit(&mut self) -> Option<Action> {
+        let pr = self.pr.clone()?;
+        let Some(since) = self.last_seen_head.clone() else {
+            return Some(Action::Toast(tr(Text::NoChangesSinceLastVisit).into()));
+        };
+
+        let args = format!("diff {since}...HEAD");
+        Some(self.checkout_and_show(&pr, vec![since], args))
+    }
+
+    /// Compares the commit series before and after the force-push, computed from the checkout.
+    fn range_diff(&mut self) -> Option<Action> {
+        let pr = self.pr.clone()?;
+        let Some((before, after)) = self.force_push.clone() else {
+            return Some(Action::Toast(tr(Text::NotForcePushed).into()));
+        };
+
+        let args = format!("range-diff {before}...{after}");
+        Some(self.checkout_and_show(&pr, vec![before, after], args))
+    }
+
+    /// Checks out the review, fetching the commits which may no longer be on its branch, and runs
+    /// git with the arguments in it on the diff page.
+    fn checkout_and_show(&mut self, pr: &Review, commits: Vec<String>, args: String) -> Action {
+        let diff = ShownDiff {
+            repository: pr.repository.clone(),
+            number: pr.number,
+            commits,
+            args,
+        };
+        self.shown_diff = Some(diff.clone());
+
+        let workspace = self.workspace();
+        self.spawn(
+            Effect::new(async move { Ok(Some(checkout_and_diff(workspace, diff).await?)) })
+                .replacing("diff"),
+        );
+
+        Action::Toast(tr(Text::CheckingOut).into())
+    }
+
+    fn workspace(&self) -> Workspace {
+        Workspace::new(self.config.workspace.clone(), Store::default())
+    }
+
+    fn open_files(&mut self) -> Option<Action> {
+        let pr = self.pr.clone()?;
+
+        let prs = self.prs_provider.clone();
+        self.spawn(
+            Effect::new(async move {
+                let files = prs.get_diff(&pr).await?;
+                Ok(Some(Action::ShowFiles {
+                    review: pr.reference(),
+                    files,
+                }))
+            })
+            .replacing("files"),
+        );
+
+        Some(Action::Toast(tr(Text::FetchingDiff).into()))
+    }
+
+    fn snooze_until_checks_complete(&mut self) -> Option<Action> {
+        if !self.pr.as_ref()?.has_pending_checks() {
+            return Some(Action::Toast(tr(Text::NoPendingChecks).into()));
+        }
+        let pr = self.pr.take()?;
+
+        let prs = self.prs_provider.clone();
+        let resurfaced = self.resurfaced.clone();
+        // One poll per review, snoozing it again starts its poll over
+        let key = format!("checks-{}", pr.id);
+        self.spawn(
+            Effect::new(async move {
+                let snoozed = pr.clone();
+                let message = match prs.wait_for_checks(pr, CHECKS_POLL_INTERVAL).await {
+                    Ok(pr) => {
+                        let message = format!(
+                            "{}: {}#{}",
+                            tr(Text::ChecksCompleted),
+                            pr.repository,
+                            pr.number
+                        );
+                        resurfaced.lock().unwrap().push_front(pr);
+                        message
+                    }
+                    Err(e) => {
+                        tracing::warn!("failed to wait for checks: {e:#}");
+                        // Brought back as is, rather than snoozed for good
+                        resurfaced.lock().unwrap().push_front(snoozed);
+                        format!("{}: {e:#}", tr(Text::ChecksWaitFailed))
+                    }
+                };
+                Ok(Some(Action::Toast(message)))
+            })
+            .replacing(key),
+        );
+
+        self.next_review();
+
+        Some(Action::Toast(tr(Text::SnoozedUntilChecksComplete).into()))
+    }
+}
+
+/// Checks out the review of the diff, fetching its commits, into the action showing the diff.
+async fn checkout_and_diff(workspace: Workspace, diff: ShownDiff) -> anyhow::Result<Action> {
+    let (owner, name) = diff.repository.split_once('/').ok_or(anyhow::anyhow!(
+        "review repository to be in the form owner/name"
+    ))?;
+    let cwd = workspace.checkout(owner, name, diff.number).await?;
+    for commit in &diff.commits {
+        workspace.fetch_commit(owner, name, commit).await?;
+    }
+
+    Ok(Action::ShowDiff {
+        cwd,
+        args: diff.args,
+    })
+}
+
+impl Component for GithubPr {
+    fn register_action_handler(
+        &mut self,
+        tx: tokio::sync::mpsc::UnboundedSender<crate::action::Action>,
+    ) -> anyhow::Result<()> {
+        self.action_tx = Some(tx);
+
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.config = config;
+
+        Ok(())
+    }
+
+    fn take_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.effects)
+    }
+
+    fn handle_paste(&mut self, text: String) -> anyhow::Result<Option<Action>> {
+        self.composer.paste(&text);
+
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> anyhow::Result<Option<Action>> {
+        if self.merge_dialog.is_active() {
+            return Ok(match self.merge_dialog.handle_key(key) {
+                ConfirmEvent::None => None,
+                ConfirmEvent::Cancel => Some(Action::ExitInsertMode),
+                ConfirmEvent::Chosen(key) => {
+                    let strategy = MERGE_STRATEGIES.into_iter().find(|s| merge_key(*s) == key);
+                    if let Some(strategy) = strategy {
+                        self.merge(strategy);
+                    }
+                    Some(Action::ExitInsertMode)
+                }
+            });
+        }
+        if !self.composer.is_active() {
+            return Ok(None);
+        }
+
+        match self.composer.handle_key(key) {
+            ComposerEvent::None => Ok(None),
+            ComposerEvent::EditExternally(text) => Ok(Some(Action::EditExternally(text))),
+            ComposerEvent::Cancel => {
+                self.editing_comment = None;
+                self.submitting = None;
+                self.requesting_commit_fixes = false;
+                self.replying_to_thread = None;
+                Ok(Some(Action::ExitInsertMode))
+            }
+            ComposerEvent::Submit(body) => {
+                if std::mem::take(&mut self.requesting_commit_fixes) {
+                    self.request_commit_fixes(body);
+                } else if let Some(thread) = self.replying_to_thread.take() {
+                    self.reply_to_thread(thread, body);
+                } else if let Some(id) = self.editing_comment.take() {
+                    self.submit_comment_edit(id, body);
+                } else if let Some(event) = self.submitting.take() {
+                    self.submit_review(event, body);
+                }
+                Ok(Some(Action::ExitInsertMode))
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        action: crate::action::Action,
+    ) -> anyhow::Result<Option<crate::action::Action>> {
+        match action {
+            Action::GotoPage(page) if page == "github_review" => {
+                tracing::info!("schedule fetch");
+                self.next_review()
+            }
+            Action::SkipReview => {
+                let toast = self.snooze(Some(snooze::skip_duration()));
+                self.next_review();
+                return Ok(toast);
+            }
+            Action::SnoozeReview => {
+                let toast = self.snooze(None);
+                self.next_review();
+                return Ok(toast);
+            }
+            Action::EditedExternally(text) => self.composer.set_text(&text),
+            Action::SnoozeUntilChecksComplete => return Ok(self.snooze_until_checks_complete()),
+            Action::ToggleCommentFilters => {
+                self.show_all_comments = !self.show_all_comments;
+                self.selected_comment = 0;
+            }
+            Action::Scroll { column, row, down } => {
+                let over = |area: Option<Rect>| {
+                    area.map(|a| components::contains(a, column, row))
+                        .unwrap_or_default()
+                };
+                if over(self.comments_area) {
+                    return self.update(if down {
+                        Action::NextComment
+                    } else {
+                        Action::PreviousComment
+                    });
+                }
+                if over(self.checks_area) {
+                    return self.update(if down {
+                        Action::SelectNext
+                    } else {
+                        Action::SelectPrevious
+                    });
+                }
+                if over(Some(self.description_area)) {
+                    self.scroll_description(down, 1);
+                }
+            }
+            Action::Click { column, row } => {
+                if let Some(pane) = self.pane_at(column, row) {
+                    self.focused_pane = pane;
+                }
+            }
+            Action::NextPane => self.focused_pane = self.next_pane(true),
+            Action::PreviousPane => self.focused_pane = self.next_pane(false),
+            Action::PageDown => self.move_focused(true, true),
+            Action::PageUp => self.move_focused(false, true),
+            Action::NextCommentsTab => self.comments_tab = self.shown_comments_tab().toggle(),
+            Action::NextComment => {
+                self.comments_tab = CommentsTab::Conversation;
+                let len = self.visible_comments().comments.len();
+                self.selected_comment = (self.selected_comment + 1).min(len.saturating_sub(1));
+            }
+            Action::PreviousComment => {
+                self.comments_tab = CommentsTab::Conversation;
+                self.selected_comment = self.selected_comment.saturating_sub(1)
+            }
+            Action::SelectNext => self.move_focused(true, false),
+            Action::SelectPrevious => self.move_focused(false, false),
+            Action::NextThread => {
+                self.comments_tab = CommentsTab::Threads;
+                let len = self.threads().len();
+                self.selected_thread = (self.selected_thread + 1).min(len.saturating_sub(1));
+            }
+            Action::PreviousThread => {
+                self.comments_tab = CommentsTab::Threads;
+                self.selected_thread = self.selected_thread.saturating_sub(1)
+            }
+            Action::ReplyToThread => return Ok(self.compose_thread_reply()),
+            Action::ResolveThread => return Ok(self.resolve_thread()),
+            Action::CopyCommentLink => return Ok(self.copy_comment_link(false)),
+            Action::CopyThreadLink => return Ok(self.copy_comment_link(true)),
+            Action::ReassignReview => return Ok(self.reassign_review()),
+            Action::Summarize => return Ok(self.summarize()),
+            Action::CheckoutPane => {
+                let pane = self.config.panes.checkout.clone();
+                return Ok(self.open_pane(pane, true));
+            }
+            Action::LogsPane => {
+                let pane = self.config.panes.logs.clone();
+                return Ok(self.open_pane(pane, false));
+            }
+            Action::VerifyPane => {
+                let pane = self.config.panes.verify.clone();
+                if pane.command.is_none() {
+                    return Ok(Some(Action::Toast(tr(Text::NoVerifyCommand).into())));
+                }
+                return Ok(self.open_pane(pane, true));
+            }
+            Action::OpenInBrowser => {
+                if let Some(pr) = self.pr.as_ref() {
+                    return Ok(components::open_in_browser(&pr.url));
+                }
+            }
+            Action::Open => match self.selected_link {
+                Some(index) => return Ok(self.open_link(index)),
+                None => return Ok(self.open_check()),
+            },
+            Action::NextLink => return Ok(self.select_link(true)),
+            Action::PreviousLink => return Ok(self.select_link(false)),
+            Action::Back => self.selected_link = None,
+            Action::EditComment => return Ok(self.edit_comment()),
+            Action::DeleteComment => return Ok(self.delete_comment()),
+            Action::Approve => return Ok(self.compose_review(ReviewEvent::Approve)),
+            Action::RequestChanges => return Ok(self.compose_review(ReviewEvent::RequestChanges)),
+            Action::MergeReview => return Ok(self.confirm_merge()),
+            Action::AddComment => return Ok(self.compose_review(ReviewEvent::Comment)),
+            Action::Refresh => return Ok(self.refresh()),
+            Action::RequestDescription => return Ok(self.request_description()),
+            Action::RequestCommitFixes => return Ok(self.compose_commit_fixes()),
+            Action::DiffSinceLastVisit => return Ok(self.diff_since_last_visit()),
+            Action::RangeDiff => return Ok(self.range_diff()),
+            Action::OpenFiles => return Ok(self.open_files()),
+            Action::GitHubPrs(action) => {
+                //tracing::info!("received action: {:?}", action);
+                match action {
+                    GitHubPrAction::Normal => self.state = action,
+                    GitHubPrAction::EnterProcessing => self.state = action,
+                    GitHubPrAction::AddReviews { .. } => {}
+                    GitHubPrAction::SearchValidated { .. } => {}
+                    GitHubPrAction::RateLimit(_) => {}
+                    GitHubPrAction::QueueFailed { .. } => {}
+                    // The failed fetch is over, the next review starts a new one
+                    GitHubPrAction::ReviewsFailed(message) => {
+                        self.prs_stream = Arc::default();
+                        self.fetch_error = Some(format!("{}: {message}", tr(Text::FetchFailed)));
+                    }
+                    GitHubPrAction::ExitProcessing => self.state = action,
+                    GitHubPrAction::NextReview { pr } => self.show_review(*pr),
+                    GitHubPrAction::ReviewComments { id, comments } => {
+                        if let Some(pr) = self.pr.as_mut().filter(|pr| pr.id == id) {
+                            pr.comments = Some(comments.unwrap_or_default());
+                            return Ok(self.select_focused_comment());
+                        }
+                    }
+                    GitHubPrAction::ReviewStatusChecks { id, checks } => {
+                        if let Some(pr) = self.pr.as_mut().filter(|pr| pr.id == id) {
+                            let checks = checks.unwrap_or_default();
+                            self.flaky_checks = self.prs_provider.flaky_checks(pr, &checks);
+                            pr.status_checks = Some(checks);
+                        }
+                    }
+                    GitHubPrAction::ReviewCommits { id, commits } => {
+                        if let Some(pr) = self.pr.as_ref().filter(|pr| pr.id == id) {
+                            self.commit_lint = CommitLint::lint(
+                                &self.config.commit_lint,
+                                &pr.head_branch,
+                                &commits,
+                            );
+                            self.commits = Some(commits);
+                        }
+                    }
+                    GitHubPrAction::SecurityFixes { id, fixes } => {
+                        if self.pr.as_ref().map(|pr| &pr.id) == Some(&id) {
+                            self.security_fixes = fixes;
+                        }
+                    }
+                    // Kept even when the review moved on, as it's the only sign the merge finished
+                    GitHubPrAction::MergeFinished { message, .. } => self.status = Some(message),
+                    // Reduced into the state, where the header reads them from
+                    GitHubPrAction::FileBadges { .. } => {}
+                    GitHubPrAction::Summary { id, summary } => {
+                        if self.pr.as_ref().map(|pr| &pr.id) == Some(&id) {
+                            self.summary = Some(summary);
+                        }
+                    }
+                    GitHubPrAction::DescriptionLint { id, lint } => {
+                        if self.pr.as_ref().map(|pr| &pr.id) == Some(&id) {
+                            self.description_lint = Some(lint);
+                        }
+                    }
+                    GitHubPrAction::HeadChanged { id, head } => {
+                        if let Some(pr) = self.pr.as_ref().filter(|pr| pr.id == id) {
+                            self.force_pushed = true;
+                            // Compares with the series first shown, across several pushes
+                            let before = match self.force_push.take() {
+                                Some((before, _)) => before,
+                                None => pr.head_sha.clone(),
+                            };
+                            self.force_push = Some((before, head));
+                        }
+                    }
+                    GitHubPrAction::DoneReview => {
+                        self.stop_head_watch();
+                        self.prs_stream = Arc::default();
+                        self.state = GitHubPrAction::Normal;
+                        self.pr = None;
+
+                        return Ok(Some(Action::GotoPage("github_review_list".to_string())));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(
+        &mut self,
+        f: &mut crate::tui::Frame<'_>,
+        area: ratatui::prelude::Rect,
+    ) -> anyhow::Result<()> {
+        let layout = Layout::new()
+            .constraints(vec![Constraint::Percentage(100), Constraint::Min(1)])
+            .split(area);
+        let app_state = self.app_state.clone();
+        let state = app_state.read();
+        let theme = &self.config.theme;
+        let block = theme.block();
+        let pane_block = |pane: Pane| match self.focused_pane == pane {
+            true => theme.focused_block(),
+            false => theme.block(),
+        };
+
+        if self.pr.is_none() {
+            let text = match self.fetch_error.as_deref() {
+                Some(error) => Paragraph::new(error)
+                    .style(Style::default().fg(theme.failure))
+                    .wrap(Wrap { trim: false }),
+                None => Paragraph::new(tr(Text::Processing)),
+            };
+            f.render_widget(text, layout[0]);
+            return Ok(());
+        }
+        let keybind_profile = state.session.keybind_profile.as_deref();
+        // A failure after the first review leaves the reviews fetched before it to go through
+        let status = self.fetch_error.as_ref().or(self.status.as_ref());
+        if keybind_profile.is_some() || status.is_some() {
+            f.render_widget(
+                components::status_bar(
+                    theme,
+                    keybind_profile,
+                    status.map(|s| s.as_str()).unwrap_or_default(),
+                ),
+                layout[1],
+            );
+        }
+        let pr = self.pr.as_ref().unwrap();
+        let main = Layout::new()
+            .constraints(vec![
+                Constraint::Length(self.force_pushed as u16),
+                Constraint::Length(!self.security_fixes.is_empty() as u16),
+                Constraint::Length(self.description_lint.is_some() as u16),
+                Constraint::Min(3),
+                Constraint::Percentage(100),
+            ])
+            .split(layout[0]);
+        if self.force_pushed {
+            f.render_widget(
+                Paragraph::new(tr(Text::ForcePushed))
+                    .alignment(Alignment::Center)
+                    .style(
+                        Style::default()
+                            .fg(theme.header)
+                            .bg(theme.failure)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                main[0],
+            );
+        }
+        if !self.security_fixes.is_empty() {
+            f.render_widget(
+                Paragraph::new(security_fixes_text(&self.security_fixes))
+                    .alignment(Alignment::Center)
+                    .style(
+                        Style::default()
+                            .fg(theme.status_bar_text)
+                            .bg(severity_color(theme, &self.security_fixes))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                main[1],
+            );
+        }
+        if let Some(lint) = self.description_lint.as_ref() {
+            f.render_widget(
+                Paragraph::new(description_lint_text(lint))
+                    .alignment(Alignment::Center)
+                    .style(
+                        Style::default()
+                            .fg(theme.status_bar_text)
+                            .bg(theme.warning)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                main[2],
+            );
+        }
+        let symbols = self.config.terminal.symbols();
+        let header = Layout::new()
+            .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
+            .direction(Direction::Horizontal)
+            .split(main[3]);
+        let reference = format!("{} - #{}", &pr.repository, &pr.number);
+        self.config.terminal.link_area(
+            Rect {
+                width: (reference.width() as u16).min(header[0].width),
+                height: 1.min(header[0].height),
+                ..header[0]
+            },
+            &pr.url,
+        );
+        let mut title = vec![Span::raw(reference)];
+        if pr.changed_files > 0 {
+            title.push(Span::raw(" "));
+            title.extend(components::size_spans(
+                theme,
+                pr.additions,
+                pr.deletions,
+                pr.changed_files,
+            ));
+        }
+        if let Some(coverage) = pr
+            .status_checks
+            .as_deref()
+            .filter(|_| self.config.coverage.shows(&pr.repository))
+            .and_then(coverage::coverage)
+        {
+            title.push(Span::styled(
+                format!(" {}", coverage_text(&coverage)),
+                if coverage.below_target() {
+                    Style::default().fg(theme.failure)
+                } else {
+                    Style::default()
+                },
+            ));
+        }
+        if let Some((summary, verified)) = self
+            .commits
+            .as_deref()
+            .and_then(commits::signatures_summary)
+        {
+            let color = if verified {
+                theme.success
+            } else {
+                theme.warning
+            };
+            title.push(Span::styled(
+                format!(" ({summary})"),
+                Style::default().fg(color),
+            ));
+        }
+        if self.last_seen_head.is_some() {
+            title.push(Span::styled(
+                format!(" ({})", tr(Text::UpdatedSinceLastVisit)),
+                Style::default().fg(theme.warning),
+            ));
+        }
+        if !pr.labels.is_empty() {
+            title.push(Span::raw(" "));
+            title.extend(Chips::new(&pr.labels, &self.config.terminal).spans());
+        }
+        if let Some(badges) = state
+            .files(&pr.id)
+            .map(|f| &f.badges)
+            .filter(|b| !b.is_empty())
+        {
+            title.push(Span::raw(" "));
+            title.extend(Chips::new(&file_badges::labels(badges), &self.config.terminal).spans());
+        }
+        f.render_widget(Paragraph::new(Line::from(title)), header[0]);
+        f.render_widget(
+            Paragraph::new(participants::participants_line(
+                &pr.participants,
+                &symbols,
+                theme,
+            ))
+            .wrap(Wrap { trim: true }),
+            header[1],
+        );
+
+        let body = Layout::new()
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .direction(Direction::Horizontal)
+            .split(main[4]);
+
+        let mut right_body_contraints = 0;
+        let summary = self.summary.as_deref().map(|summary| {
+            right_body_contraints += 1;
+            Paragraph::new(summary)
+                .wrap(Wrap { trim: false })
+                .block(block.clone().title(tr(Text::Summary)))
+        });
+        let comments_tab = self.shown_comments_tab();
+        let comments_title = pr.comments.as_ref().map(|comments| {
+            let filtered = self.visible_comments();
+            let conversation = if filtered.hidden > 0 {
+                format!(
+                    "{} ({}, {} {})",
+                    tr(Text::Conversation),
+                    filtered.comments.len(),
+                    filtered.hidden,
+                    tr(Text::Hidden)
+                )
+            } else {
+                format!("{} ({})", tr(Text::Conversation), filtered.comments.len())
+            };
+            let unresolved = comments.threads.iter().filter(|t| !t.resolved).count();
+            let threads = format!(
+                "{} ({unresolved}/{})",
+                tr(Text::ReviewThreads),
+                comments.threads.len()
+            );
+            comment_tabs::tabs_title(comments_tab, conversation, threads, theme)
+        });
+        let comment_list = match (pr.comments.as_ref(), comments_title) {
+            (None, _) => {
+                right_body_contraints += 1;
+                Some(CommentsPane::Conversation(loading_list(
+                    pane_block(Pane::Comments),
+                    tr(Text::Comments),
+                )))
+            }
+            (Some(comments), _) if comments.comments.is_empty() && comments.threads.is_empty() => {
+                None
+            }
+            (Some(comments), Some(title)) if comments_tab == CommentsTab::Threads => {
+                right_body_contraints += 1;
+
+                let mut items = Vec::new();
+                let mut selected = None;
+                let mut index = 0;
+                for (path, file_threads) in comments.threads_by_file() {
+                    items.push(ListItem::new(threads::file_line(path)));
+                    for thread in file_threads {
+                        if index == self.selected_thread {
+                            selected = Some(items.len());
+                        }
+                        items.push(ListItem::new(threads::thread_lines(
+                            thread, &symbols, theme,
+                        )));
+                        index += 1;
+                    }
+                }
+
+                let list = List::new(items)
+                    .highlight_style(theme.selection())
+                    .block(pane_block(Pane::Comments).title(title));
+                Some(CommentsPane::Threads(
+                    list,
+                    ListState::default().with_selected(selected),
+                ))
+            }
+            (Some(_), title) => {
+                right_body_contraints += 1;
+
+                let mut first_link = find_links(&pr.description).len();
+                let comments_list_items = self
+                    .visible_comments()
+                    .comments
+                    .iter()
+                    .map(|c| {
+                        let comment_links = find_links(&c.text);
+                        let text = links::linked_text(
+                            &c.text,
+                            &comment_links,
+                            first_link,
+                            self.selected_link,
+                        );
+                        first_link += comment_links.len();
+
+                        let mine = self.is_mine(&c.author);
+                        let title = if mine {
+                            format!("{} ({})", c.title(), tr(Text::You))
+                        } else {
+                            c.title()
+                        };
+                        CommentItem::new(theme, &title, text, mine, 4)
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut comments_list = SelectableWidgetList::new(comments_list_items)
+                    .block(pane_block(Pane::Comments).title(title.unwrap_or_default()))
+                    .truncate(true);
+                comments_list.state.select(Some(self.selected_comment));
+
+                Some(CommentsPane::Conversation(comments_list))
+            }
+        };
+
+        let status_checks_list = {
+            if pr.status_checks.is_none() {
+                right_body_contraints += 1;
+                Some(loading_list(
+                    pane_block(Pane::Checks),
+                    tr(Text::StatusChecks),
+                ))
+            } else if pr.status_checks.iter().flatten().next().is_none() {
+                None
+            } else {
+                let mut checks_items = Vec::new();
+                let mut selected = None;
+                let mut index = 0;
+                for (app, checks) in
+                    status::group_by_app(pr.status_checks.as_deref().unwrap_or_default())
+                {
+                    checks_items.push(StatusCheckItem::group(app, &checks, &symbols, theme));
+                    for check in checks {
+                        if index == self.selected_check {
+                            selected = Some(checks_items.len());
+                        }
+                        checks_items.push(
+                            StatusCheckItem::new(
+                                status::StatusCheckInput::Github(check.to_owned()),
+                                &symbols,
+                                theme,
+                                self.flaky_checks.contains(check.name()),
+                                4,
+                            )
+                            .link(check.url(), &self.config.terminal),
+                        );
+                        index += 1;
+                    }
+                }
+
+                let mut status_checks_list = SelectableWidgetList::new(checks_items)
+                    .block(pane_block(Pane::Checks).title(tr(Text::StatusChecks)))
+                    .truncate(true);
+                status_checks_list.state.select(selected);
+
+                right_body_contraints += 1;
+                Some(status_checks_list)
+            }
+        };
+
+        let commits_list = self
+            .commits
+            .as_deref()
+            .filter(|c| !c.is_empty())
+            .map(|commits| {
+                right_body_contraints += 1;
+
+                let unverified = commits.iter().filter(|c| !c.is_verified()).count();
+                let mut notes = Vec::new();
+                if unverified > 0 {
+                    notes.push(format!("{unverified} {}", tr(Text::Unverified)));
+                }
+                if let Some(lint) = self.commit_lint.as_ref() {
+                    notes.push(format!(
+                        "{} {}",
+                        lint.warnings.len(),
+                        tr(Text::CommitLintWarnings)
+                    ));
+                }
+                let title = if notes.is_empty() {
+                    tr(Text::Commits).to_string()
+                } else {
+                    format!("{} ({})", tr(Text::Commits), notes.join(", "))
+                };
+
+                let lint = self.commit_lint.as_ref();
+                let mut items = Vec::new();
+                if let Some(line) =
+                    lint.and_then(|l| commits::lint_line(l.of_branch(), &symbols, theme))
+                {
+                    items.push(ListItem::new(line));
+                }
+                // The latest commit first, like the other panes
+                for commit in commits.iter().rev() {
+                    let mut lines = vec![commits::commit_line(commit, &symbols, theme)];
+                    lines.extend(lint.and_then(|l| {
+                        commits::lint_line(l.of_commit(&commit.short_oid), &symbols, theme)
+                    }));
+                    items.push(ListItem::new(lines));
+                }
+
+                List::new(items).block(block.clone().title(title))
+            });
+
+        let right_body = Layout::new()
+            .constraints(
+                (0..=right_body_contraints)
+                    .map(|_| Constraint::Ratio(1, right_body_contraints))
+                    .collect::<Vec<_>>(),
+            )
+            .direction(Direction::Vertical)
+            .split(body[1]);
+
+        //tracing::info!(
+        //    "len of right body: {}, status_checks {}, comments {}",
+        //    right_body.len(),
+        //    status_checks_list.is_some(),
+        //    comment_list.is_some()
+        //);
+
+        let description = body[0];
+        //let statusChecks = rightBody[1];
+
+        let mut next = 0;
+        if let Some(summary) = summary {
+            f.render_widget(summary, right_body[next]);
+            next += 1;
+        }
+
+        self.comments_area = None;
+        match comment_list {
+            Some(CommentsPane::Conversation(mut comments_list)) => {
+                let comments = right_body[next];
+                f.render_widget(&mut comments_list, comments);
+                self.comments_area = Some(comments);
+                next += 1;
+            }
+            Some(CommentsPane::Threads(threads_list, mut state)) => {
+                f.render_stateful_widget(threads_list, right_body[next], &mut state);
+                next += 1;
+            }
+            None => {}
+        }
+
+        self.checks_area = None;
+        if let Some(mut status_checks_list) = status_checks_list {
+            let status_checks = right_body[next];
+            f.render_widget(&mut status_checks_list, status_checks);
+            self.checks_area = Some(status_checks);
+            next += 1;
+        }
+
+        if let Some(commits_list) = commits_list {
+            f.render_widget(commits_list, right_body[next]);
+        }
+
+        let description_text = links::linked_text(
+            &pr.description,
+            &find_links(&pr.description),
+            0,
+            self.selected_link,
+        );
+        self.description_area = description;
+        self.description_lines =
+            wrapped_lines(&description_text, description.width.saturating_sub(2));
+        self.description_scroll = self
+            .description_scroll
+            .min(self.description_lines.saturating_sub(1));
+        self.vertical_scroll_state = self
+            .vertical_scroll_state
+            .content_length(self.description_lines)
+            .viewport_content_length(description.height.saturating_sub(2))
+            .position(self.description_scroll);
+        f.render_widget(
+            Paragraph::new(description_text)
+                .wrap(Wrap { trim: true })
+                .scroll((self.description_scroll, 0))
+                .block(pane_block(Pane::Description).title(pr.title.as_str())),
+            description,
+        );
+        f.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some(symbols.scroll_up))
+                .end_symbol(Some(symbols.scroll_down)),
+            description,
+            &mut self.vertical_scroll_state,
+        );
+
+        self.composer.draw(f, area);
+        self.merge_dialog.draw(f, area);
+
+        Ok(())
+    }
+}
+
+/// The toast of a change to a thread which went through, showing the change by fetching the
+/// comments again.
+async fn thread_changed(
+    tx: &UnboundedSender<Action>,
+    prs: &GitPullRequest,
+    pr: &Review,
+    message: &str,
+) -> anyhow::Result<Action> {
+    match prs.get_comments(pr).await {
+        Ok(comments) => tx.send(Action::GitHubPrs(GitHubPrAction::ReviewComments {
+            id: pr.id.clone(),
+            comments,
+        }))?,
+        Err(e) => tracing::warn!("failed to load comments: {e}"),
+    }
+
+    Ok(Action::Toast(message.to_string()))
+}
+
+const MERGE_STRATEGIES: [MergeStrategy; 3] = [
+    MergeStrategy::Merge,
+    MergeStrategy::Squash,
+    MergeStrategy::Rebase,
+];
+
+/// The key picking the strategy in the merge dialog.
+fn merge_key(strategy: MergeStrategy) -> char {
+    match strategy {
+        MergeStrategy::Merge => 'm',
+        MergeStrategy::Squash => 's',
+        MergeStrategy::Rebase => 'r',
+    }
+}
+
+fn coverage_text(coverage: &Coverage) -> String {
+    let kind = match coverage.kind {
+        CoverageKind::Patch => tr(Text::PatchCoverage),
+        CoverageKind::Project => tr(Text::ProjectCoverage),
+    };
+
+    match coverage.target {
+        Some(target) => format!(
+            "({kind} {:.1}%, {} {:.1}%)",
+            coverage.percent,
+            tr(Text::CoverageTarget),
+            target
+        ),
+        None => format!("({kind} {:.1}%)", coverage.percent),
+    }
+}
+
+fn description_lint_text(lint: &DescriptionLint) -> String {
+    if lint.empty {
+        return tr(Text::DescriptionEmpty).to_string();
+    }
+
+    format!(
+        "{}: {}",
+        tr(Text::DescriptionMissingSections),
+        lint.missing.join(", ")
+    )
+}
+
+fn security_fixes_text(fixes: &[Vulnerability]) -> String {
+    let fixes = fixes
+        .iter()
+        .map(|v| format!("{} ({}): {}", v.ghsa_id, v.severity, v.summary))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    format!("{} - {fixes}", tr(Text::SecurityFixes))
+}
+
+/// The color of the most severe of the fixed vulnerabilities.
+fn severity_color(theme: &Theme, fixes: &[Vulnerability]) -> Color {
+    let severity = |s: &str| match s {
+        "critical" => 3,
+        "high" => 2,
+        "moderate" => 1,
+        _ => 0,
+    };
+
+    match fixes.iter().map(|v| severity(&v.severity)).max() {
+        Some(3) | Some(2) => theme.failure,
+        Some(1) => theme.warning,
+        _ => theme.success,
+    }
+}
+
+/// An empty pane shown in place of a list whose items are still being fetched.
+/// The comments pane as drawn, showing one of its tabs.
+enum CommentsPane<'a> {
+    Conversation(SelectableWidgetList<'a, CommentItem<'a>>),
+    Threads(List<'a>, ListState),
+}
+
+/// The panes of the review which the selection and page keys can move.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Pane {
+    #[default]
+    Description,
+    Comments,
+    Checks,
+}
+
+/// How many lines the text takes up wrapped at the width, counting the words wrapped onto the
+/// next line as if they were cut at the width instead.
+fn wrapped_lines(text: &ratatui::text::Text<'_>, width: u16) -> u16 {
+    let width = usize::from(width.max(1));
+    let lines = text
+        .lines
+        .iter()
+        .map(|l| l.width().max(1).div_ceil(width))
+        .sum::<usize>();
+
+    u16::try_from(lines).unwrap_or(u16::MAX)
+}
+
+fn loading_list<'a, T>(block: Block<'a>, title: &str) -> SelectableWidgetList<'a, T>
+where
+    T: Widget + Into<WidgetListItem<T>> + Clone,
+{
+    SelectableWidgetList::new(Vec::new())
+        .block(block.title(format!("{title} ({})", tr(Text::Loading))))
+}
@@ -0,0 +1,264 @@
+use ratatui::{prelude::*, widgets::*};
+
+use rev_git_provider::models::Comment;
+use rev_widget_list::WidgetListItem;
+
+use crate::{
+    config::CommentFilters,
+    glob,
+    i18n::{tr, Text},
+    theme::Theme,
+};
+
+/// A comment as it should be displayed after filters have been applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayComment {
+    pub id: String,
+    pub author: String,
+    pub text: String,
+    /// The permalink to the comment, empty where the provider has none.
+    pub url: String,
+    pub edited: bool,
+    /// How many identical comments were folded into this one.
+    pub repeated: usize,
+}
+
+impl DisplayComment {
+    pub fn new(comment: &Comment) -> Self {
+        Self {
+            id: comment.id.clone(),
+            author: comment.author.clone(),
+            text: comment.text.clone(),
+            url: comment.url.clone(),
+            edited: comment.last_edited_at.is_some(),
+            repeated: 1,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        let mut title = self.author.clone();
+        if self.repeated > 1 {
+            title.push_str(&format!(" (x{})", self.repeated));
+        }
+        if self.edited {
+            title.push_str(&format!(" ({})", tr(Text::Edited)));
+        }
+
+        title
+    }
+}
+
+/// The tabs of the comments pane, the conversation on the review and the threads on its diff are
+/// kept apart as mixing them makes long conversations hard to follow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommentsTab {
+    #[default]
+    Conversation,
+    Threads,
+}
+
+impl CommentsTab {
+    pub fn toggle(self) -> Self {
+        match self {
+            CommentsTab::Conversation => CommentsTab::Threads,
+            CommentsTab::Threads => CommentsTab::Conversation,
+        }
+    }
+}
+
+/// The title of the comments pane, a tab for each kind of comment with its count, the shown one
+/// highlighted.
+pub fn tabs_title(
+    shown: CommentsTab,
+    conversation: String,
+    threads: String,
+    theme: &Theme,
+) -> Line<'static> {
+    let tab = |tab: CommentsTab, title: String| {
+        if tab == shown {
+            Span::styled(title, theme.selection())
+        } else {
+            Span::styled(title, Style::default().fg(theme.muted))
+        }
+    };
+
+    Line::from(vec![
+        tab(CommentsTab::Conversation, conversation),
+        Span::raw(" | "),
+        tab(CommentsTab::Threads, threads),
+    ])
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FilteredComments {
+    pub comments: Vec<DisplayComment>,
+    pub hidden: usize,
+}
+
+pub fn filter_comments(comments: &[Comment], filters: &CommentFilters) -> FilteredComments {
+    let mut filtered = FilteredComments::default();
+
+    for comment in comments {
+        if filters
+            .hide_authors
+            .iter()
+            .any(|pattern| glob::matches(pattern, &comment.author))
+        {
+            filtered.hidden += 1;
+            continue;
+        }
+
+        let text = match filters.collapse_after {
+            Some(max_lines) if comment.text.lines().count() > max_lines => {
+                let remaining = comment.text.lines().count() - max_lines;
+                let mut text = comment
+                    .text
+                    .lines()
+                    .take(max_lines)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                text.push_str(&format!("\n... {} {}", remaining, tr(Text::MoreLines)));
+                text
+            }
+            _ => comment.text.clone(),
+        };
+
+        // CI bots tend to post the same result again on every push, only keep the newest
+        if filters.dedupe {
+            if let Some(pos) = filtered
+                .comments
+                .iter()
+                .position(|c| c.author == comment.author && c.text == text)
+            {
+                let previous = filtered.comments.remove(pos);
+                filtered.comments.push(DisplayComment {
+                    text,
+                    repeated: previous.repeated + 1,
+                    ..DisplayComment::new(comment)
+                });
+                filtered.hidden += 1;
+                continue;
+            }
+        }
+
+        filtered.comments.push(DisplayComment {
+            text,
+            ..DisplayComment::new(comment)
+        });
+    }
+
+    filtered
+}
+
+#[derive(Debug, Clone)]
+pub struct CommentItem<'a> {
+    paragraph: Paragraph<'a>,
+    height: u16,
+    selection: Style,
+}
+
+impl<'a> CommentItem<'a> {
+    pub fn new(
+        theme: &Theme,
+        author: &str,
+        body: impl Into<text::Text<'a>>,
+        mine: bool,
+        height: u16,
+    ) -> Self {
+        let border_style = if mine {
+            Style::default().fg(theme.accent)
+        } else {
+            Style::default().fg(theme.border)
+        };
+
+        let body: text::Text = body.into();
+        let body_len = body.lines.len().max(1) as u16;
+        let paragraph = Paragraph::new(body)
+            .wrap(Wrap { trim: false })
+            .style(Style::default().bg(theme.background))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title(author.to_string()),
+            );
+
+        Self {
+            paragraph,
+            height: body_len + height - 2,
+            selection: theme.selection(),
+        }
+    }
+
+    // Render the item differently depending on the selection state
+    fn modify_fn(mut item: WidgetListItem<Self>, selected: Option<bool>) -> WidgetListItem<Self> {
+        if let Some(selected) = selected {
+            if selected {
+                let style = item.content.selection;
+                item.content.paragraph = item.content.paragraph.style(style);
+            }
+        }
+        item
+    }
+}
+
+impl<'a> From<CommentItem<'a>> for WidgetListItem<CommentItem<'a>> {
+    fn from(val: CommentItem<'a>) -> Self {
+        let height = val.height.to_owned();
+        Self::new(val, height).modify_fn(CommentItem::modify_fn)
+    }
+}
+
+impl<'a> Widget for CommentItem<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        self.paragraph.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::Comment;
+
+    use super::filter_comments;
+    use crate::config::CommentFilters;
+
+    fn comment(author: &str, text: &str) -> Comment {
+        Comment {
+            id: format!("{author}-{text}"),
+            author: author.into(),
+            text: text.into(),
+            body: text.into(),
+            url: String::new(),
+            last_edited_at: None,
+        }
+    }
+
+    #[test]
+    fn test_hides_bots_and_dedupes() {
+        let comments = vec![
+            comment("dependabot[bot]", "bumped"),
+            comment("ci", "build failed"),
+            comment("kjuulh", "looks good"),
+            comment("ci", "build failed"),
+        ];
+
+        let filtered = filter_comments(&comments, &CommentFilters::default());
+
+        assert_eq!(2, filtered.hidden);
+        assert_eq!(2, filtered.comments.len());
+        assert_eq!("kjuulh", filtered.comments[0].author);
+        assert_eq!("ci (x2)", filtered.comments[1].title());
+    }
+
+    #[test]
+    fn test_collapses_long_comments() {
+        let filters = CommentFilters {
+            collapse_after: Some(2),
+            ..Default::default()
+        };
+
+        let filtered = filter_comments(&[comment("kjuulh", "one\ntwo\nthree\nfour")], &filters);
+
+        assert!(filtered.comments[0].text.starts_with("one\ntwo\n... 2"));
+    }
+}
@@ -0,0 +1,117 @@
+use ratatui::prelude::*;
+use rev_git_provider::models::Commit;
+
+use crate::{
+    commit_lint::CommitWarning,
+    i18n::{tr, Text},
+    terminal::Symbols,
+    theme::Theme,
+};
+
+/// A commit as a line of the commits pane, prefixed with whether its signature is verified.
+///
+/// Verified commits are green, commits with a signature which couldn't be verified red, and
+/// unsigned commits dimmed.
+pub fn commit_line<'a>(commit: &Commit, symbols: &Symbols, theme: &Theme) -> Line<'a> {
+    let (symbol, style, signature) = match commit.signature.as_ref() {
+        Some(signature) if signature.valid => (
+            symbols.success,
+            Style::default().fg(theme.success),
+            match signature.signer.as_deref() {
+                Some(signer) => format!("{} {signer}", signature.kind),
+                None => signature.kind.clone(),
+            },
+        ),
+        Some(signature) => (
+            symbols.failure,
+            Style::default().fg(theme.failure),
+            format!("{} {}", signature.kind, tr(Text::Unverified)),
+        ),
+        None => (
+            "-",
+            Style::default().add_modifier(Modifier::DIM),
+            tr(Text::Unsigned).to_string(),
+        ),
+    };
+
+    Line::from(vec![
+        Span::styled(format!("{symbol} "), style),
+        Span::styled(format!("{} ", commit.short_oid), style),
+        Span::raw(commit.headline.clone()),
+        Span::styled(
+            format!(" ({}, {signature})", commit.author),
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+    ])
+}
+
+/// The lint rules a commit or the branch breaks, indented under the commit it belongs to.
+pub fn lint_line<'a, 'w>(
+    warnings: impl Iterator<Item = &'w CommitWarning>,
+    symbols: &Symbols,
+    theme: &Theme,
+) -> Option<Line<'a>> {
+    let rules = warnings
+        .map(|w| match w.commit {
+            Some(_) => w.rule.describe(),
+            None => format!("{} {}", w.subject, w.rule.describe()),
+        })
+        .collect::<Vec<_>>();
+    if rules.is_empty() {
+        return None;
+    }
+
+    Some(Line::from(Span::styled(
+        format!("  {} {}", symbols.warning, rules.join(", ")),
+        Style::default().fg(theme.warning),
+    )))
+}
+
+/// How many of the commits are verified, e.g. `3/4 commits verified`, shown in the header.
+pub fn signatures_summary(commits: &[Commit]) -> Option<(String, bool)> {
+    if commits.is_empty() {
+        return None;
+    }
+
+    let verified = commits.iter().filter(|c| c.is_verified()).count();
+
+    Some((
+        format!("{verified}/{} {}", commits.len(), tr(Text::CommitsVerified)),
+        verified == commits.len(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{Commit, CommitSignature};
+
+    use super::signatures_summary;
+
+    fn commit(valid: Option<bool>) -> Commit {
+        Commit {
+            oid: "0123456789".into(),
+            short_oid: "0123456".into(),
+            headline: "fix the thing".into(),
+            author: "kjuulh".into(),
+            signature: valid.map(|valid| CommitSignature {
+                kind: "gpg".into(),
+                valid,
+                signer: Some("kjuulh".into()),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_signatures_summary() {
+        assert_eq!(None, signatures_summary(&[]));
+
+        let (text, all) = signatures_summary(&[commit(Some(true)), commit(Some(true))]).unwrap();
+        assert!(text.starts_with("2/2"));
+        assert!(all);
+
+        let (text, all) =
+            signatures_summary(&[commit(Some(true)), commit(Some(false)), commit(None)]).unwrap();
+        assert!(text.starts_with("1/3"));
+        assert!(!all);
+    }
+}
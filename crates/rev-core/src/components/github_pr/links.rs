@@ -0,0 +1,46 @@
+use std::ops::Range;
+
+use ratatui::prelude::*;
+
+use crate::links::Link;
+
+/// The text with its links underlined, where `first` is the index of its first link among all
+/// links of the review, and the selected link is highlighted.
+pub fn linked_text<'a>(
+    text: &str,
+    links: &[(Range<usize>, Link)],
+    first: usize,
+    selected: Option<usize>,
+) -> Text<'a> {
+    let mut lines = Vec::new();
+    let mut links = links.iter().enumerate().peekable();
+
+    let mut start = 0;
+    for line in text.split('\n') {
+        let end = start + line.len();
+        let mut spans = Vec::new();
+        let mut at = start;
+
+        // Links never span lines, as they end at whitespace
+        while let Some((index, (range, _))) = links.next_if(|(_, (r, _))| r.start < end) {
+            if range.start > at {
+                spans.push(Span::raw(text[at..range.start].to_string()));
+            }
+
+            let mut style = Style::default().add_modifier(Modifier::UNDERLINED);
+            if selected == Some(first + index) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::styled(text[range.clone()].to_string(), style));
+            at = range.end;
+        }
+        if end > at {
+            spans.push(Span::raw(text[at..end].to_string()));
+        }
+
+        lines.push(Line::from(spans));
+        start = end + 1;
+    }
+
+    Text::from(lines)
+}
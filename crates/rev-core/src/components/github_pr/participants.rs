@@ -0,0 +1,61 @@
+use ratatui::prelude::*;
+use rev_git_provider::models::{Participant, ParticipantRole, ReviewState};
+
+use crate::{
+    i18n::{tr, Text},
+    terminal::Symbols,
+    theme::Theme,
+};
+
+/// Renders participants as initials chips, followed by their role and latest review.
+///
+/// Participants which currently have a review requested are highlighted.
+pub fn participants_line<'a>(
+    participants: &[Participant],
+    symbols: &Symbols,
+    theme: &Theme,
+) -> Line<'a> {
+    let mut spans = Vec::new();
+
+    for participant in participants {
+        let chip_style = match (participant.requested, &participant.role) {
+            (true, _) => Style::default()
+                .fg(theme.status_bar_text)
+                .bg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+            (false, ParticipantRole::Author) => {
+                Style::default().fg(theme.status_bar_text).bg(theme.accent)
+            }
+            (false, _) => theme.status_bar(),
+        };
+
+        let role = match participant.role {
+            ParticipantRole::Author => tr(Text::RoleAuthor),
+            ParticipantRole::Reviewer => tr(Text::RoleReviewer),
+            ParticipantRole::Commenter => tr(Text::RoleCommenter),
+        };
+
+        spans.push(Span::styled(
+            format!(" {} ", participant.initials()),
+            chip_style,
+        ));
+        spans.push(Span::raw(format!(" {} ({})", participant.login, role)));
+
+        let review = match participant.review {
+            Some(ReviewState::Approved) => Some((symbols.success, theme.success)),
+            Some(ReviewState::ChangesRequested) => Some((symbols.failure, theme.failure)),
+            Some(ReviewState::Commented) => Some((symbols.pending, theme.info)),
+            _ => None,
+        };
+        if let Some((symbol, color)) = review {
+            spans.push(Span::styled(
+                format!(" {symbol}"),
+                Style::default().fg(color),
+            ));
+        }
+
+        spans.push(Span::raw("  "));
+    }
+
+    Line::from(spans)
+}
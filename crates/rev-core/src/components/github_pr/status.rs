@@ -0,0 +1,218 @@
+use ratatui::{prelude::*, widgets::*};
+
+use rev_git_provider::models::{CurrentState, StatusCheck};
+use rev_widget_list::WidgetListItem;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    i18n::{tr, Text},
+    terminal::{self, Capabilities, Symbols},
+    theme::Theme,
+};
+
+/// Groups the checks by the app running them, keeping the order the apps first appear in.
+pub fn group_by_app(checks: &[StatusCheck]) -> Vec<(&str, Vec<&StatusCheck>)> {
+    let mut groups: Vec<(&str, Vec<&StatusCheck>)> = Vec::new();
+    for check in checks {
+        match groups.iter_mut().find(|(app, _)| *app == check.app()) {
+            Some((_, group)) => group.push(check),
+            None => groups.push((check.app(), vec![check])),
+        }
+    }
+
+    groups
+}
+
+#[derive(Clone, Debug)]
+pub enum StatusCheckInput {
+    Github(rev_git_provider::models::StatusCheck),
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusCheckItem<'a> {
+    list: List<'a>,
+    height: u16,
+    selection: Style,
+    /// The name of the check, linked to the url of its run when the terminal supports links.
+    name: String,
+    link: Option<String>,
+}
+
+impl StatusCheckItem<'_> {
+    pub fn new(
+        input: StatusCheckInput,
+        symbols: &Symbols,
+        theme: &Theme,
+        flaky: bool,
+        height: u16,
+    ) -> Self {
+        let block = theme.block();
+        let StatusCheckInput::Github(check) = &input;
+        let name = check.name().to_string();
+        let title = |name: String| -> Line<'_> {
+            if flaky {
+                Line::from(vec![
+                    name.into(),
+                    Span::styled(
+                        format!(" {} {}", tr(Text::Flaky), symbols.flaky),
+                        Style::default().fg(theme.warning),
+                    ),
+                ])
+            } else {
+                Line::from(name)
+            }
+        };
+
+        let get_state = |current: CurrentState, state: String| -> Line<'_> {
+            let (symbol, style) = match current {
+                CurrentState::Success => (symbols.success, Style::default().fg(theme.success)),
+                CurrentState::Pending => (symbols.pending, Style::default().fg(theme.warning)),
+                CurrentState::Failure => (symbols.failure, Style::default().fg(theme.failure)),
+                CurrentState::Expired => (symbols.pending, Style::default().fg(theme.info)),
+            };
+            Line::styled(format!("{symbol} {state}"), style)
+        };
+
+        let list = match input {
+            StatusCheckInput::Github(github) => match github {
+                rev_git_provider::models::StatusCheck::StatusContext {
+                    state,
+                    description,
+                    context,
+                    current,
+                    ..
+                } => {
+                    if let Some(desc) = description {
+                        List::new(vec![
+                            ListItem::new(Line::from(vec![desc.into()])),
+                            ListItem::new(get_state(current, state.clone())),
+                        ])
+                        .block(block.title(title(context)))
+                    } else {
+                        List::new(vec![
+                            ListItem::new(Line::from(vec![tr(Text::NoDescription).into()])),
+                            ListItem::new(get_state(current, state.clone())),
+                        ])
+                        .block(block.title(title(context)))
+                    }
+                }
+                rev_git_provider::models::StatusCheck::CheckRun {
+                    name,
+                    status,
+                    conclusion,
+                    current,
+                    ..
+                } => List::new(vec![
+                    ListItem::new(vec![Line::from(vec![status.into()])]),
+                    ListItem::new(get_state(current, conclusion.clone())),
+                ])
+                .block(block.title(title(name))),
+            },
+        };
+
+        Self {
+            list,
+            height,
+            selection: theme.selection(),
+            name,
+            link: None,
+        }
+    }
+
+    /// Links the name of the check to its run, if the terminal supports links.
+    pub fn link(mut self, url: Option<&str>, terminal: &Capabilities) -> Self {
+        self.link = url.filter(|_| terminal.hyperlinks).map(str::to_string);
+        self
+    }
+
+    /// The heading of the checks of an app, summarizing how many are in each state.
+    pub fn group(app: &str, checks: &[&StatusCheck], symbols: &Symbols, theme: &Theme) -> Self {
+        let count = |state: CurrentState| checks.iter().filter(|c| *c.current() == state).count();
+        let summary = [
+            (symbols.success, count(CurrentState::Success)),
+            (
+                symbols.pending,
+                count(CurrentState::Pending) + count(CurrentState::Expired),
+            ),
+            (symbols.failure, count(CurrentState::Failure)),
+        ]
+        .into_iter()
+        .filter(|(_, n)| *n > 0)
+        .map(|(symbol, n)| format!("{n} {symbol}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+        Self {
+            list: List::new(vec![ListItem::new(Line::styled(
+                format!("{app} {summary}"),
+                Style::default().add_modifier(Modifier::BOLD),
+            ))]),
+            height: 1,
+            selection: theme.selection(),
+            name: app.to_string(),
+            link: None,
+        }
+    }
+
+    // Render the item differently depending on the selection state
+    fn modify_fn(mut item: WidgetListItem<Self>, selected: Option<bool>) -> WidgetListItem<Self> {
+        if selected == Some(true) {
+            item.content.list = item.content.list.style(item.content.selection);
+        }
+        item
+    }
+}
+
+impl<'a> From<StatusCheckItem<'a>> for WidgetListItem<StatusCheckItem<'a>> {
+    fn from(val: StatusCheckItem<'a>) -> Self {
+        let height = val.height.to_owned();
+        Self::new(val, height).modify_fn(StatusCheckItem::modify_fn)
+    }
+}
+
+impl<'a> Widget for StatusCheckItem<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(url) = self.link.as_deref().filter(|_| area.height > 0) {
+            // The name is the title, on the top border of the block
+            let width = (self.name.width() as u16).min(area.width.saturating_sub(2));
+            terminal::register_link(Rect::new(area.x + 1, area.y, width, 1), url);
+        }
+        ratatui::widgets::Widget::render(self.list, area, buf);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{CurrentState, StatusCheck};
+
+    use super::group_by_app;
+
+    fn check(name: &str, app: &str) -> StatusCheck {
+        StatusCheck::CheckRun {
+            id: name.into(),
+            name: name.into(),
+            status: "completed".into(),
+            conclusion: "success".into(),
+            current: CurrentState::Success,
+            url: None,
+            app: app.into(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_app() {
+        let checks = vec![
+            check("build", "GitHub Actions"),
+            check("coverage", "Codecov"),
+            check("test", "GitHub Actions"),
+        ];
+
+        let groups = group_by_app(&checks)
+            .into_iter()
+            .map(|(app, checks)| (app, checks.len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![("GitHub Actions", 2), ("Codecov", 1)], groups);
+    }
+}
@@ -0,0 +1,140 @@
+use ratatui::prelude::*;
+use rev_git_provider::models::ReviewThread;
+
+use crate::{
+    i18n::{tr, Text},
+    terminal::Symbols,
+    theme::Theme,
+};
+
+/// How many lines of the diff are shown above a thread.
+const CONTEXT_LINES: usize = 3;
+
+/// The path of a file heading its threads in the review threads tab of the comments pane.
+pub fn file_line(path: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        path.to_string(),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// A thread as lines of the review threads tab, the line it's on followed by the end of its diff
+/// hunk and its comments.
+///
+/// Resolved and outdated threads are dimmed, they rarely need attention.
+pub fn thread_lines(thread: &ReviewThread, symbols: &Symbols, theme: &Theme) -> Vec<Line<'static>> {
+    let base = if thread.resolved || thread.outdated {
+        Style::default().add_modifier(Modifier::DIM)
+    } else {
+        Style::default()
+    };
+
+    let mut header = vec![Span::styled(
+        match thread.line {
+            Some(line) => format!("  L{line}"),
+            None => "  -".to_string(),
+        },
+        base.fg(theme.accent),
+    )];
+    if thread.resolved {
+        header.push(Span::styled(
+            format!(" {} {}", symbols.success, tr(Text::Resolved)),
+            base.fg(theme.success),
+        ));
+    }
+    if thread.outdated {
+        header.push(Span::styled(
+            format!(" ({})", tr(Text::Outdated)),
+            base.fg(theme.warning),
+        ));
+    }
+    let mut lines = vec![Line::from(header)];
+
+    for context in thread.context(CONTEXT_LINES) {
+        let color = match context.chars().next() {
+            Some('+') => theme.success,
+            Some('-') => theme.failure,
+            _ => Color::Reset,
+        };
+        lines.push(Line::from(Span::styled(
+            format!("    {context}"),
+            Style::default().fg(color).add_modifier(Modifier::DIM),
+        )));
+    }
+
+    for comment in &thread.comments {
+        lines.push(Line::from(vec![
+            Span::styled(format!("    {}: ", comment.author), base.fg(theme.info)),
+            Span::styled(
+                comment.text.lines().next().unwrap_or_default().to_string(),
+                base,
+            ),
+        ]));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{Comment, Comments, ReviewThread};
+
+    use super::thread_lines;
+    use crate::{terminal::Symbols, theme::Theme};
+
+    fn thread(path: &str, line: usize, resolved: bool) -> ReviewThread {
+        ReviewThread {
+            id: format!("{path}:{line}"),
+            path: path.into(),
+            line: Some(line),
+            diff_hunk: "@@ -1,4 +1,5 @@\n fn main() {\n-    old();\n+    new();\n+    more();"
+                .into(),
+            resolved,
+            outdated: false,
+            comments: vec![Comment {
+                id: "c1".into(),
+                author: "octocat".into(),
+                text: "Why more?\nIt seems redundant".into(),
+                body: "Why more?\nIt seems redundant".into(),
+                url: String::new(),
+                last_edited_at: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_threads_grouped_by_file() {
+        let comments = Comments {
+            has_previous: false,
+            comments: Vec::new(),
+            threads: vec![
+                thread("src/main.rs", 3, false),
+                thread("src/lib.rs", 10, true),
+                thread("src/main.rs", 12, false),
+            ],
+        };
+
+        let files = comments.threads_by_file();
+        assert_eq!(2, files.len());
+        assert_eq!("src/main.rs", files[0].0);
+        assert_eq!(2, files[0].1.len());
+
+        let lines = thread_lines(files[1].1[0], &Symbols::unicode(), &Theme::default());
+        let text = lines
+            .iter()
+            .map(|l| {
+                l.spans
+                    .iter()
+                    .map(|s| s.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert!(text[0].starts_with("  L10"));
+        assert!(text[0].contains("resolved"));
+        assert_eq!(
+            vec!["    -    old();", "    +    new();", "    +    more();"],
+            text[1..4]
+        );
+        assert_eq!("    octocat: Why more?", text[4]);
+    }
+}
@@ -0,0 +1,1105 @@
+use std::{collections::BTreeSet, ops::Range};
+
+use chrono::{DateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use rev_git_provider::models::{CurrentState, ReviewDecision, ReviewListItem};
+use timeago::Formatter;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    action::{Action, GitHubPrAction},
+    components::composer::{Composer, ComposerEvent},
+    config::{Column, Config, Density},
+    due_dates::{self, DueDates},
+    file_badges, fuzzy,
+    git_pull_requests::GitPullRequests,
+    i18n::{tr, Text},
+    review_plan::ReviewPlan,
+    saved_searches::{SavedSearch, SavedSearches},
+    snooze::{Snooze, Snoozes},
+    starred::{self, StarredRepositories},
+    state::{AppState, State},
+    store::Store,
+    terminal::{Capabilities, Symbols},
+    theme::Theme,
+};
+
+use super::{self as components, chips::Chips, Component, Effect};
+
+const RELATIVE_NUMBER_WIDTH: u16 = 4;
+
+pub struct GithubPrs {
+    prs_provider: GitPullRequests,
+    action_tx: Option<UnboundedSender<Action>>,
+    state: GitHubPrAction,
+    table_state: TableState,
+    config: Config,
+    store: Store,
+    /// Holds the reviews of the queues, which the list keeps in the order of the plan.
+    app_state: State,
+    plan: ReviewPlan,
+    searches: Vec<SavedSearch>,
+    /// The index of the saved search shown, the review requests are shown when none is.
+    current_search: Option<usize>,
+    /// The repositories whose reviews go first.
+    starred: Vec<String>,
+    /// Whether the first of the searches is the queue of the starred repositories.
+    starred_queue: bool,
+    composer: Composer,
+    density: Density,
+    /// Where the table was last drawn, to tell which row a click is on.
+    table_area: Rect,
+    /// The columns of the queue tabs in the title, with the queue each switches to.
+    tabs: Vec<(Range<u16>, Option<usize>)>,
+    /// Narrows the list to the reviews whose title, repository or author match it.
+    filter: String,
+    /// Whether keys are typed into the filter.
+    filtering: bool,
+    /// The indices of the filters of the config toggled on.
+    active_filters: BTreeSet<usize>,
+    due_dates: DueDates,
+    /// The effects left by the helpers, until the app picks them up.
+    effects: Vec<Effect>,
+}
+
+impl GithubPrs {
+    pub fn new(prs_provider: GitPullRequests, store: Store, app_state: State) -> Self {
+        Self {
+            prs_provider,
+            action_tx: None,
+            state: GitHubPrAction::Normal,
+            table_state: TableState::default(),
+            config: Config::default(),
+            store,
+            app_state,
+            plan: ReviewPlan::default(),
+            searches: Vec::new(),
+            current_search: None,
+            starred: Vec::new(),
+            starred_queue: false,
+            composer: Composer::default(),
+            density: Density::default(),
+            table_area: Rect::default(),
+            tabs: Vec::new(),
+            filter: String::new(),
+            filtering: false,
+            active_filters: BTreeSet::new(),
+            due_dates: DueDates::default(),
+            effects: Vec::new(),
+        }
+    }
+
+    fn current_search(&self) -> Option<&SavedSearch> {
+        self.searches.get(self.current_search?)
+    }
+
+    /// The name of the queue shown, `None` for the review requests.
+    fn queue(&self) -> Option<String> {
+        self.current_search().map(|s| s.name.clone())
+    }
+
+    /// Shows the saved search at the index, or the review requests for none, from the start. The
+    /// reviews fetched for it before are shown until the first page arrives again.
+    fn show_queue(&mut self, index: Option<usize>) {
+        self.current_search = index;
+        self.prs_provider
+            .set_search(self.current_search().map(|s| s.query.clone()));
+        self.filter.clear();
+        self.table_state = TableState::default();
+        self.app_state
+            .write()
+            .restart_queue(self.queue().as_deref());
+        self.refresh();
+        self.schedule_fetch();
+
+        if let Some(tx) = self.action_tx.as_ref() {
+            let _ = tx.send(Action::QueueShown(self.queue()));
+        }
+    }
+
+    /// Orders the reviews of the queue shown by the plan, e.g. once a page of them arrived.
+    fn refresh(&mut self) {
+        self.sort_by_plan();
+        if self.table_state.selected().is_none() {
+            self.select(0);
+        }
+    }
+
+    /// Shows the open reviews of the repository as a queue of its own, kept until rev quits.
+    fn browse_repository(&mut self, repository: String) {
+        let query = format!("is:pr state:open repo:{repository}");
+        let index = match self.searches.iter().position(|s| s.query == query) {
+            Some(index) => index,
+            None => {
+                self.searches.push(SavedSearch {
+                    name: repository,
+                    query,
+                });
+                self.searches.len() - 1
+            }
+        };
+        self.show_queue(Some(index));
+    }
+
+    /// Keeps the queue of the starred repositories first among the searches, the queue shown
+    /// stays the same.
+    fn set_starred(&mut self, starred: Vec<String>) {
+        let showing_starred = self.starred_queue && self.current_search == Some(0);
+        if self.starred_queue {
+            self.searches.remove(0);
+            self.current_search = self.current_search.and_then(|i| i.checked_sub(1));
+        }
+
+        let queue = starred::queue(tr(Text::StarredQueue), &starred);
+        self.starred_queue = queue.is_some();
+        if let Some(queue) = queue {
+            self.searches.insert(0, queue);
+            self.current_search = self.current_search.map(|i| i + 1);
+        }
+        self.starred = starred;
+
+        if showing_starred {
+            self.show_queue(self.starred_queue.then_some(0));
+        } else {
+            self.sort_by_plan();
+        }
+    }
+
+    /// Stars the repository of the selected review, or takes the star away again.
+    fn toggle_star(&mut self) -> Option<Action> {
+        let item = self.selected_item()?;
+        let repository = format!("{}/{}", item.owner, item.name);
+        if self
+            .config
+            .starred
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(&repository))
+        {
+            return Some(Action::Toast(format!(
+                "{repository} {}",
+                tr(Text::StarredInConfig)
+            )));
+        }
+
+        let toast = match StarredRepositories::toggle(&self.store, &repository) {
+            Ok(true) => format!("{} {repository}", tr(Text::Starred)),
+            Ok(false) => format!("{} {repository}", tr(Text::Unstarred)),
+            Err(e) => return Some(Action::Toast(format!("{e:#}"))),
+        };
+        self.set_starred(starred::load_or_warn(&self.store, &self.config.starred));
+
+        Some(Action::Toast(toast))
+    }
+
+    fn next_queue(&mut self) {
+        let next = match self.current_search {
+            None if !self.searches.is_empty() => Some(0),
+            Some(i) if i + 1 < self.searches.len() => Some(i + 1),
+            _ => None,
+        };
+        self.show_queue(next);
+    }
+
+    fn click(&mut self, column: u16, row: u16) {
+        if row == self.table_area.y {
+            if let Some((_, queue)) = self.tabs.iter().find(|(c, _)| c.contains(&column)) {
+                if *queue != self.current_search {
+                    self.show_queue(*queue);
+                }
+            }
+            return;
+        }
+        if !components::contains(self.table_area, column, row) {
+            return;
+        }
+
+        let row_height = match self.density {
+            Density::Compact => 1,
+            Density::Detailed => 3,
+        };
+        if let Some(index) = components::table_row_at(
+            self.table_area,
+            self.table_state.offset(),
+            2,
+            row_height,
+            row,
+        ) {
+            if index < self.visible().len() {
+                self.table_state.select(Some(index));
+            }
+        }
+    }
+
+    /// The title of the list, with a tab per queue and the reviews fetched for it, remembering
+    /// where each tab is drawn.
+    fn title(&mut self, state: &AppState) -> Line<'static> {
+        let queues = std::iter::once((None, tr(Text::ReviewRequests).to_string())).chain(
+            self.searches
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (Some(i), s.name.clone())),
+        );
+
+        let mut spans = vec![Span::raw(format!("{} - ", tr(Text::GithubPullRequests)))];
+        // The title starts after the corner of the border
+        let mut column = self.table_area.x + 1 + spans[0].width() as u16;
+        self.tabs.clear();
+        for (queue, mut name) in queues {
+            let search = queue
+                .and_then(|i| self.searches.get(i))
+                .map(|s| s.name.as_str());
+            if let Some(count) = state.count(search) {
+                name = format!("{name} ({count})");
+            }
+            if queue.is_some() {
+                spans.push(Span::raw(" | "));
+                column += 3;
+            }
+            let span = if queue == self.current_search {
+                Span::styled(name, self.config.theme.selection())
+            } else {
+                Span::raw(name)
+            };
+            let width = span.width() as u16;
+            self.tabs.push((column..column + width, queue));
+            column += width;
+            spans.push(span);
+        }
+
+        let filters = self
+            .active_filters
+            .iter()
+            .filter_map(|i| self.config.filters.get(*i))
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>();
+        if !filters.is_empty() {
+            spans.push(Span::styled(
+                format!("  [{}]", filters.join(", ")),
+                Style::default().fg(self.config.theme.accent),
+            ));
+        }
+
+        Line::from(spans)
+    }
+
+    fn edit_search(&mut self) -> Option<Action> {
+        let text = self
+            .current_search()
+            .map(|s| s.render())
+            .unwrap_or_default();
+        self.composer.open(tr(Text::EditSearch), text);
+
+        Some(Action::EnterInsertMode)
+    }
+
+    /// Runs the search before saving it, so a broken query never ends up as a queue.
+    fn validate_search(&mut self, raw: String) -> Option<Action> {
+        // The composer is multi-line, but a search is a single line
+        let Some(search) = SavedSearch::parse(&raw.replace('\n', " ")) else {
+            return Some(Action::Toast(tr(Text::InvalidSearch).into()));
+        };
+
+        let prs = self.prs_provider.clone();
+        self.spawn(
+            Effect::new(async move {
+                let count = prs.validate_search(&search.query).await?;
+                Ok(Some(Action::GitHubPrs(GitHubPrAction::SearchValidated {
+                    search,
+                    count,
+                })))
+            })
+            .replacing("validate-search"),
+        );
+
+        None
+    }
+
+    fn save_search(&mut self, search: SavedSearch, count: usize) -> Option<Action> {
+        if let Err(e) = SavedSearches::save(&self.store, search.clone()) {
+            return Some(Action::Toast(format!("{e:#}")));
+        }
+
+        let index = match self.searches.iter().position(|s| s.name == search.name) {
+            Some(index) => {
+                self.searches[index] = search;
+                index
+            }
+            None => {
+                self.searches.push(search);
+                self.searches.len() - 1
+            }
+        };
+        self.show_queue(Some(index));
+
+        Some(Action::Toast(format!("{} {count}", tr(Text::SearchSaved))))
+    }
+
+    /// The indices of the reviews matching the filter and the filters toggled on, all of them
+    /// when there's none.
+    fn visible(&self) -> Vec<usize> {
+        self.visible_in(&self.app_state.read())
+    }
+
+    /// See [`GithubPrs::visible`], for when the state is read already.
+    fn visible_in(&self, state: &AppState) -> Vec<usize> {
+        self.reviews(state)
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| {
+                let paths = state.files(&p.id).map(|f| f.paths.as_slice());
+                if !self
+                    .active_filters
+                    .iter()
+                    .filter_map(|i| self.config.filters.get(*i))
+                    .all(|f| f.matches(p, paths))
+                {
+                    return false;
+                }
+
+                // Bounds such as `<100` narrow by the lines changed, the other words by name
+                let lines = p.additions + p.deletions;
+                let mut words = Vec::new();
+                for word in self.filter.split_whitespace() {
+                    match components::within_size(word, lines) {
+                        Some(false) => return false,
+                        Some(true) => {}
+                        None => words.push(word),
+                    }
+                }
+
+                let repository = format!("{}/{}", p.owner, p.name);
+                fuzzy::matches(&words.join(" "), &[&p.title, &repository, &p.author])
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The reviews of the queue shown, in the order of the plan.
+    fn reviews<'a>(&self, state: &'a AppState) -> &'a [ReviewListItem] {
+        state.reviews(self.queue().as_deref()).unwrap_or_default()
+    }
+
+    fn selected_item(&self) -> Option<ReviewListItem> {
+        let state = self.app_state.read();
+        let index = *self.visible_in(&state).get(self.table_state.selected()?)?;
+        self.reviews(&state).get(index).cloned()
+    }
+
+    fn selected_id(&self) -> Option<String> {
+        self.selected_item().map(|p| p.id.clone())
+    }
+
+    fn select(&mut self, index: usize) {
+        let len = self.visible().len();
+        if len > 0 {
+            self.table_state.select(Some(index.min(len - 1)));
+        }
+    }
+
+    /// Sorts the queue by the plan, keeping the selection on the same review.
+    fn sort_by_plan(&mut self) {
+        let selected = self.selected_id();
+        let queue = self.queue();
+        let mut state = self.app_state.write();
+        let Some(prs) = state.reviews_mut(queue.as_deref()) else {
+            return;
+        };
+
+        self.plan.sort(prs, |p| &p.id);
+        starred::boost(&self.starred, &self.plan, prs);
+
+        let visible = self.visible_in(&state);
+        let prs = self.reviews(&state);
+        if let Some(index) = selected.and_then(|id| visible.iter().position(|i| prs[*i].id == id)) {
+            self.table_state.select(Some(index));
+        }
+    }
+
+    fn move_selected(&mut self, up: bool) -> Option<Action> {
+        let index = self.table_state.selected()?;
+        let other = if up { index.checked_sub(1)? } else { index + 1 };
+        // Swaps the review with the next one shown, the filtered out ones keep their spot
+        let visible = self.visible();
+        let (&from, &to) = visible.get(index).zip(visible.get(other))?;
+        let queue = self.queue();
+        let mut state = self.app_state.write();
+        let prs = state.reviews_mut(queue.as_deref())?;
+
+        prs.swap(from, to);
+        self.table_state.select(Some(other));
+
+        let ids = prs.iter().map(|p| p.id.clone()).collect::<Vec<_>>();
+        drop(state);
+        self.plan.record(&ids);
+        // Pinned reviews keep their spot at the top, so move them within the pins as well
+        self.plan.pinned = ids
+            .into_iter()
+            .filter(|id| self.plan.is_pinned(id))
+            .collect();
+        self.sort_by_plan();
+
+        self.save_plan()
+    }
+
+    /// Moves the smallest reviews to the top, recording the order so it's kept like a manual one.
+    fn sort_by_size(&mut self) -> Option<Action> {
+        let queue = self.queue();
+        let mut state = self.app_state.write();
+        let prs = state.reviews_mut(queue.as_deref())?;
+        prs.sort_by_key(|p| p.additions + p.deletions);
+
+        let ids = prs.iter().map(|p| p.id.clone()).collect::<Vec<_>>();
+        drop(state);
+        self.plan.record(&ids);
+        self.sort_by_plan();
+        self.select(0);
+
+        self.save_plan()
+    }
+
+    /// Drops the selected review from the list until it's unsnoozed from the snoozed page.
+    fn snooze_selected(&mut self) -> Option<Action> {
+        let item = self.selected_item()?;
+        let id = item.id.clone();
+        let reference = format!("{}/{}#{}", item.owner, item.name, item.number);
+        let snooze = Snooze::new(reference.clone(), item.title.clone());
+
+        if let Err(e) = Snoozes::snooze(&self.store, &id, snooze) {
+            return Some(Action::Toast(format!("{e:#}")));
+        }
+
+        let index = self.table_state.selected().unwrap_or_default();
+        self.app_state.write().remove_review(&id);
+        if self.visible().is_empty() {
+            self.table_state.select(None);
+        } else {
+            self.select(index);
+        }
+
+        Some(Action::Toast(format!(
+            "{} {reference}",
+            tr(Text::SnoozedReview)
+        )))
+    }
+
+    fn toggle_pin(&mut self) -> Option<Action> {
+        let id = self.selected_id()?;
+        self.plan.toggle_pin(&id);
+        self.sort_by_plan();
+
+        self.save_plan()
+    }
+
+    fn toggle_list_filter(&mut self, index: usize) {
+        if index >= self.config.filters.len() {
+            return;
+        }
+        let selected = self.selected_id();
+        if !self.active_filters.remove(&index) {
+            self.active_filters.insert(index);
+        }
+        self.reselect(selected);
+    }
+
+    /// Narrows the list as the filter is typed, keeping the selection on the same review when it
+    /// still matches.
+    fn set_filter(&mut self, filter: String) {
+        let selected = self.selected_id();
+        self.filter = filter;
+        self.reselect(selected);
+    }
+
+    /// Selects the review again after the list was narrowed, the first one when it's filtered out.
+    fn reselect(&mut self, selected: Option<String>) {
+        let state = self.app_state.read();
+        let visible = self.visible_in(&state);
+        let prs = self.reviews(&state);
+        let index = selected
+            .and_then(|id| visible.iter().position(|i| prs[*i].id == id))
+            .unwrap_or_default();
+        self.table_state = TableState::default();
+        if !visible.is_empty() {
+            self.table_state.select(Some(index));
+        }
+    }
+
+    /// Types into the filter, enter keeps it and esc clears it.
+    fn filter_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => {
+                self.filtering = false;
+                self.set_filter(String::new());
+                Some(Action::ExitInsertMode)
+            }
+            (KeyCode::Enter, _) => {
+                self.filtering = false;
+                Some(Action::ExitInsertMode)
+            }
+            (KeyCode::Backspace, _) => {
+                let mut filter = self.filter.clone();
+                filter.pop();
+                self.set_filter(filter);
+                None
+            }
+            (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                self.set_filter(String::new());
+                None
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                self.set_filter(format!("{}{c}", self.filter));
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn save_plan(&self) -> Option<Action> {
+        self.plan
+            .save(&self.store)
+            .err()
+            .map(|e| Action::Toast(format!("{e:#}")))
+    }
+
+    /// Fetches the changed files of the items in the background, to badge them in the list.
+    fn load_badges(&mut self, items: &[ReviewListItem]) {
+        let items = items
+            .iter()
+            .filter(|item| self.app_state.read().files(&item.id).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            return;
+        }
+
+        // Not replacing, the badges of each page are loaded while the next one is fetched
+        let prs = self.prs_provider.clone();
+        let settings = self.config.badges.clone();
+        self.spawn(Effect::sending(|tx| async move {
+            for item in items {
+                match prs.changed_paths(&item).await {
+                    Ok(paths) => tx.send(Action::GitHubPrs(GitHubPrAction::FileBadges {
+                        id: item.id,
+                        badges: file_badges::badges(&settings, &paths),
+                        paths,
+                    }))?,
+                    Err(e) => tracing::warn!("failed to load changed files: {e}"),
+                }
+            }
+
+            Ok(None)
+        }));
+    }
+
+    /// Leaves the effect to the app, see [`Component::take_effects`].
+    fn spawn(&mut self, effect: Effect) {
+        self.effects.push(effect);
+    }
+
+    /// Fetches the next page of the queue shown, the fetch of the queue shown before is
+    /// cancelled.
+    fn schedule_fetch(&mut self) {
+        let prs = self.prs_provider.clone();
+        let queue = self.queue();
+        let tags = self.config.queue.tags();
+        let cursor = self.app_state.write().cursor(queue.as_deref());
+        let fetch = Effect::sending(|tx| async move {
+            let mut cursor = cursor.lock().await;
+            tx.send(Action::GitHubPrs(GitHubPrAction::EnterProcessing))?;
+            match cursor.next_page(&prs, tags).await {
+                // The first page replaces the reviews fetched before, even when the queue emptied
+                Ok((prs_res, replace)) if replace || !prs_res.is_empty() => {
+                    tx.send(Action::GitHubPrs(GitHubPrAction::AddReviews {
+                        queue,
+                        items: prs_res,
+                        replace,
+                    }))?;
+                }
+                Ok(_) => {}
+                Err(e) => tx.send(Action::GitHubPrs(GitHubPrAction::QueueFailed {
+                    queue,
+                    message: format!("{e:#}"),
+                }))?,
+            }
+            if let Some(limit) = prs.rate_limit() {
+                tx.send(Action::GitHubPrs(GitHubPrAction::RateLimit(limit)))?;
+            }
+
+            Ok(Some(Action::GitHubPrs(GitHubPrAction::ExitProcessing)))
+        });
+        self.spawn(fetch.replacing("queue"));
+    }
+}
+
+impl Component for GithubPrs {
+    fn register_action_handler(
+        &mut self,
+        tx: tokio::sync::mpsc::UnboundedSender<crate::action::Action>,
+    ) -> anyhow::Result<()> {
+        self.action_tx = Some(tx);
+
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.density = config.list.density;
+        self.config = config;
+
+        Ok(())
+    }
+
+    fn init(&mut self) -> anyhow::Result<()> {
+        // A broken plan shouldn't keep me from reviewing, the api order will do
+        self.plan = ReviewPlan::load(&self.store).unwrap_or_else(|e| {
+            tracing::warn!("failed to load review plan: {e:#}");
+            ReviewPlan::default()
+        });
+        self.searches =
+            SavedSearches::all(&self.store, &self.config.searches).unwrap_or_else(|e| {
+                tracing::warn!("failed to load saved searches: {e:#}");
+                self.config.searches.clone()
+            });
+        self.due_dates = due_dates::load_or_warn(&self.store);
+        self.set_starred(starred::load_or_warn(&self.store, &self.config.starred));
+
+        Ok(())
+    }
+
+    fn take_effects(&mut self) -> Vec<Effect> {
+        std::mem::take(&mut self.effects)
+    }
+
+    fn handle_paste(&mut self, text: String) -> anyhow::Result<Option<Action>> {
+        self.composer.paste(&text);
+
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> anyhow::Result<Option<Action>> {
+        if self.filtering {
+            return Ok(self.filter_key(key));
+        }
+        if !self.composer.is_active() {
+            return Ok(None);
+        }
+
+        match self.composer.handle_key(key) {
+            ComposerEvent::None => Ok(None),
+            ComposerEvent::EditExternally(text) => Ok(Some(Action::EditExternally(text))),
+            ComposerEvent::Cancel => Ok(Some(Action::ExitInsertMode)),
+            ComposerEvent::Submit(raw) => {
+                if let Some(action) = self.validate_search(raw) {
+                    self.action_tx.as_ref().unwrap().send(action)?;
+                }
+                Ok(Some(Action::ExitInsertMode))
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        action: crate::action::Action,
+    ) -> anyhow::Result<Option<crate::action::Action>> {
+        match action {
+            Action::GotoPage(page) if page == "github_review_list" => {
+                // Repositories may have been starred on the repositories page meanwhile
+                let starred = starred::load_or_warn(&self.store, &self.config.starred);
+                if starred != self.starred {
+                    self.set_starred(starred);
+                }
+                self.refresh();
+                tracing::info!("schedule fetch");
+                self.schedule_fetch()
+            }
+            Action::SelectNext => self.select(
+                self.table_state
+                    .selected()
+                    .map(|i| i + 1)
+                    .unwrap_or_default(),
+            ),
+            Action::SelectPrevious => self.select(
+                self.table_state
+                    .selected()
+                    .unwrap_or_default()
+                    .saturating_sub(1),
+            ),
+            Action::MoveUp => return Ok(self.move_selected(true)),
+            Action::MoveDown => return Ok(self.move_selected(false)),
+            Action::TogglePin => return Ok(self.toggle_pin()),
+            Action::ToggleStar => return Ok(self.toggle_star()),
+            Action::SnoozeReview => return Ok(self.snooze_selected()),
+            Action::ToggleListFilter(index) => self.toggle_list_filter(index),
+            Action::OpenInBrowser => {
+                if let Some(item) = self.selected_item() {
+                    return Ok(components::open_in_browser(&item.url));
+                }
+            }
+            Action::FilterList => {
+                self.filtering = true;
+                return Ok(Some(Action::EnterInsertMode));
+            }
+            Action::Back if !self.filter.is_empty() => self.set_filter(String::new()),
+            Action::EditSearch => return Ok(self.edit_search()),
+            Action::EditedExternally(text) => self.composer.set_text(&text),
+            Action::NextQueue => self.next_queue(),
+            Action::BrowseRepository(repository) => self.browse_repository(repository),
+            Action::ToggleDensity => self.density = self.density.toggle(),
+            Action::SortBySize => return Ok(self.sort_by_size()),
+            Action::Click { column, row } => self.click(column, row),
+            Action::Scroll { down, .. } => {
+                let selected = self.table_state.selected().unwrap_or_default();
+                self.select(if down {
+                    selected + 1
+                } else {
+                    selected.saturating_sub(1)
+                })
+            }
+            Action::GitHubPrs(action) => {
+                tracing::info!("received action: {:?}", action);
+                match action {
+                    GitHubPrAction::Normal => self.state = action,
+                    GitHubPrAction::EnterProcessing => self.state = action,
+                    GitHubPrAction::RateLimit(_) => {}
+                    // Reduced into the state, where the list reads it from
+                    GitHubPrAction::QueueFailed { .. } => {}
+                    GitHubPrAction::ReviewsFailed(_) => {}
+                    // Pages of other queues still fetching are only kept in the state
+                    GitHubPrAction::AddReviews { queue, items, .. } if queue == self.queue() => {
+                        self.load_badges(&items);
+                        self.refresh();
+
+                        let count = self.app_state.read().count(queue.as_deref());
+                        if count.is_some_and(|count| count < 30) {
+                            self.schedule_fetch();
+                        }
+                    }
+                    GitHubPrAction::AddReviews { .. } => {}
+                    GitHubPrAction::ExitProcessing => self.state = action,
+                    GitHubPrAction::NextReview { .. } => {}
+                    GitHubPrAction::DoneReview => {}
+                    GitHubPrAction::ReviewComments { .. } => {}
+                    GitHubPrAction::ReviewStatusChecks { .. } => {}
+                    GitHubPrAction::ReviewCommits { .. } => {}
+                    GitHubPrAction::HeadChanged { .. } => {}
+                    GitHubPrAction::SecurityFixes { .. } => {}
+                    GitHubPrAction::DescriptionLint { .. } => {}
+                    GitHubPrAction::Summary { .. } => {}
+                    GitHubPrAction::FileBadges { .. } => {}
+                    GitHubPrAction::MergeFinished { .. } => {}
+                    GitHubPrAction::SearchValidated { search, count } => {
+                        return Ok(self.save_search(search, count))
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(
+        &mut self,
+        f: &mut crate::tui::Frame<'_>,
+        area: ratatui::prelude::Rect,
+    ) -> anyhow::Result<()> {
+        let show_filter = self.filtering || !self.filter.is_empty();
+        let layout = Layout::new()
+            .constraints(vec![
+                Constraint::Percentage(100),
+                Constraint::Length(show_filter as u16),
+                Constraint::Min(1),
+            ])
+            .split(area);
+
+        self.table_area = layout[0];
+        let app_state = self.app_state.clone();
+        let state = app_state.read();
+        let title = self.title(&state);
+        let visible = self.visible_in(&state);
+        let queue = self.queue();
+        if let Some(prs) = state.reviews(queue.as_deref()) {
+            let formatter = Formatter::default();
+
+            let normal_style = Style::default();
+
+            let columns = &self.config.list.columns;
+            let relative_numbers = self.config.list.relative_numbers;
+            let selected = self.table_state.selected();
+            let number_cell = relative_numbers.then(|| Cell::from(""));
+            let header_cells = number_cell.into_iter().chain(columns.iter().map(|c| {
+                Cell::from(tr(column_header(c.column))).style(self.config.theme.header())
+            }));
+
+            let header = Row::new(header_cells)
+                .style(normal_style)
+                .height(1)
+                .bottom_margin(1);
+
+            let symbols = self.config.terminal.symbols();
+            let theme = &self.config.theme;
+            let now = Utc::now();
+            let rows = visible.iter().enumerate().map(|(index, &i)| {
+                let item = &prs[i];
+                let due = self.due_dates.due(&self.config.due, item);
+                let overdue = due.is_some_and(|due| due <= now);
+                let number_cell = relative_numbers.then(|| relative_number(theme, index, selected));
+                let cells = number_cell
+                    .into_iter()
+                    .chain(columns.iter().map(|c| match c.column {
+                        Column::Owner => Cell::from(item.owner.clone()),
+                        Column::Repository => Cell::from(item.name.clone()),
+                        Column::Title => {
+                            let mut title = if self.plan.is_pinned(&item.id) {
+                                Line::from(format!("{} {}", symbols.pinned, item.title))
+                            } else {
+                                Line::from(item.title.clone())
+                            };
+                            if let Some(badges) = state
+                                .files(&item.id)
+                                .map(|f| &f.badges)
+                                .filter(|b| !b.is_empty())
+                            {
+                                title.spans.push(Span::raw(" "));
+                                title.spans.extend(
+                                    Chips::new(&file_badges::labels(badges), &self.config.terminal)
+                                        .spans(),
+                                );
+                            }
+                            match self.density {
+                                Density::Compact => Cell::from(title),
+                                Density::Detailed => Cell::from(vec![
+                                    title,
+                                    details(item, &symbols, &self.config.terminal, theme),
+                                ]),
+                            }
+                        }
+                        Column::Author => Cell::from(item.author.clone()),
+                        Column::Age => {
+                            let age = Cell::from(formatter.convert_chrono(item.date, now));
+                            match overdue {
+                                true => age.style(Style::default().fg(theme.failure)),
+                                false => age,
+                            }
+                        }
+                        Column::Labels => {
+                            Cell::from(Chips::new(&item.labels, &self.config.terminal).line())
+                        }
+                        Column::Checks => indicator_cell(checks(item, &symbols, theme)),
+                        Column::Review => indicator_cell(review(item, &symbols, theme)),
+                        Column::Size => Cell::from(Line::from(components::size_spans(
+                            theme,
+                            item.additions,
+                            item.deletions,
+                            item.changed_files,
+                        ))),
+                        Column::Approvals => Cell::from(item.approvals.to_string()),
+                        Column::Due => due_cell(due, now, theme),
+                    }));
+
+                match self.density {
+                    Density::Compact => Row::new(cells).height(1),
+                    Density::Detailed => Row::new(cells).height(2).bottom_margin(1),
+                }
+            });
+            let widths = relative_numbers
+                .then_some(Constraint::Length(RELATIVE_NUMBER_WIDTH))
+                .into_iter()
+                .chain(
+                    self.config
+                        .list
+                        .widths()
+                        .into_iter()
+                        .map(Constraint::Percentage),
+                )
+                .collect::<Vec<_>>();
+
+            let t = Table::new(rows)
+                .header(header)
+                .column_spacing(3)
+                .highlight_style(self.config.theme.selection())
+                .block(self.config.theme.block().title(title))
+                .widths(&widths);
+
+            f.render_stateful_widget(t, layout[0], &mut self.table_state);
+        } else if let Some(error) = state.error(queue.as_deref()) {
+            f.render_widget(
+                Paragraph::new(format!("{}: {error}", tr(Text::FetchFailed)))
+                    .style(Style::default().fg(self.config.theme.failure))
+                    .wrap(Wrap { trim: false }),
+                layout[0],
+            )
+        } else {
+            f.render_widget(Paragraph::new(tr(Text::Processing)), layout[0])
+        }
+
+        let help = match state.session.rate_limit {
+            Some(limit) => format!(
+                "{} | {} {}/{}",
+                tr(Text::StatusBarHelp),
+                tr(Text::ApiBudget),
+                limit.remaining,
+                limit.limit
+            ),
+            None => tr(Text::StatusBarHelp).to_string(),
+        };
+        // A failure after the first page leaves the reviews fetched before it shown
+        let help = match state.error(queue.as_deref()) {
+            Some(error) => format!("{}: {error}", tr(Text::FetchFailed)),
+            None => help,
+        };
+        if show_filter {
+            let total = state.count(queue.as_deref()).unwrap_or_default();
+            f.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::raw(format!("/{}", self.filter)),
+                    Span::styled(
+                        format!("  {}/{total}", visible.len()),
+                        Style::default().fg(self.config.theme.muted),
+                    ),
+                ])),
+                layout[1],
+            );
+            if self.filtering {
+                let column = layout[1].x + 1 + Span::raw(self.filter.as_str()).width() as u16;
+                f.set_cursor(column.min(layout[1].right().saturating_sub(1)), layout[1].y);
+            }
+        }
+        f.render_widget(
+            components::status_bar(
+                &self.config.theme,
+                state.session.keybind_profile.as_deref(),
+                &help,
+            ),
+            layout[2],
+        );
+
+        self.composer.draw(f, area);
+
+        Ok(())
+    }
+}
+
+fn column_header(column: Column) -> Text {
+    match column {
+        Column::Owner => Text::Owner,
+        Column::Repository => Text::Repository,
+        Column::Title => Text::Title,
+        Column::Author => Text::Author,
+        Column::Age => Text::DateCreated,
+        Column::Labels => Text::Labels,
+        Column::Checks => Text::Checks,
+        Column::Review => Text::Review,
+        Column::Size => Text::Size,
+        Column::Approvals => Text::Approvals,
+        Column::Due => Text::Due,
+    }
+}
+
+/// The distance of the row from the selected one, the selected row shows its own number like in
+/// vim.
+/// How long is left until the review is due, or how long it's overdue.
+fn due_cell<'a>(due: Option<DateTime<Utc>>, now: DateTime<Utc>, theme: &Theme) -> Cell<'a> {
+    let Some(due) = due else {
+        return Cell::from("");
+    };
+
+    match (due - now).to_std() {
+        Ok(left) => Cell::from(Formatter::new().ago(tr(Text::DueLeft)).convert(left)),
+        Err(_) => Cell::from(Formatter::default().convert_chrono(due, now))
+            .style(Style::default().fg(theme.failure)),
+    }
+}
+
+fn relative_number<'a>(theme: &Theme, index: usize, selected: Option<usize>) -> Cell<'a> {
+    match selected {
+        Some(selected) if selected == index => {
+            Cell::from(format!("{}", index + 1)).style(Style::default().fg(theme.warning))
+        }
+        Some(selected) => Cell::from(format!("{}", index.abs_diff(selected)))
+            .style(Style::default().fg(theme.muted)),
+        None => Cell::from(format!("{}", index + 1)).style(Style::default().fg(theme.muted)),
+    }
+}
+
+/// A symbol along with what it means, in the color of how good it is.
+struct Indicator {
+    symbol: &'static str,
+    text: Text,
+    color: Color,
+}
+
+fn checks(item: &ReviewListItem, symbols: &Symbols, theme: &Theme) -> Option<Indicator> {
+    let (symbol, text, color) = match item.checks.as_ref()? {
+        CurrentState::Success => (symbols.success, Text::ChecksPassed, theme.success),
+        CurrentState::Failure => (symbols.failure, Text::ChecksFailed, theme.failure),
+        CurrentState::Pending | CurrentState::Expired => {
+            (symbols.pending, Text::ChecksPending, theme.warning)
+        }
+    };
+
+    Some(Indicator {
+        symbol,
+        text,
+        color,
+    })
+}
+
+/// The review decision, drafts aren't up for review yet so they show as such instead.
+fn review(item: &ReviewListItem, symbols: &Symbols, theme: &Theme) -> Option<Indicator> {
+    let (symbol, text, color) = if item.draft {
+        (symbols.draft, Text::Draft, theme.muted)
+    } else {
+        match item.review_decision? {
+            ReviewDecision::Approved => (symbols.success, Text::ReviewApproved, theme.success),
+            ReviewDecision::ChangesRequested => {
+                (symbols.failure, Text::ReviewChangesRequested, theme.failure)
+            }
+            ReviewDecision::ReviewRequired => {
+                (symbols.waiting, Text::ReviewRequired, theme.warning)
+            }
+        }
+    };
+
+    Some(Indicator {
+        symbol,
+        text,
+        color,
+    })
+}
+
+fn indicator_cell<'a>(indicator: Option<Indicator>) -> Cell<'a> {
+    match indicator {
+        Some(i) => Cell::from(i.symbol).style(Style::default().fg(i.color)),
+        None => Cell::from(""),
+    }
+}
+
+/// The second line of a review in the detailed list, its checks, review decision and labels.
+fn details<'a>(
+    item: &ReviewListItem,
+    symbols: &Symbols,
+    terminal: &Capabilities,
+    theme: &Theme,
+) -> Line<'a> {
+    let mut spans = Vec::new();
+    for indicator in [checks(item, symbols, theme), review(item, symbols, theme)]
+        .into_iter()
+        .flatten()
+    {
+        if !spans.is_empty() {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(
+            format!("{} {}", indicator.symbol, tr(indicator.text)),
+            Style::default().fg(indicator.color),
+        ));
+    }
+    if !item.labels.is_empty() {
+        if !spans.is_empty() {
+            spans.push(Span::raw("  "));
+        }
+        spans.extend(Chips::new(&item.labels, terminal).spans());
+    }
+
+    Line::from(spans)
+}
@@ -0,0 +1,90 @@
+use ratatui::{prelude::*, widgets::*};
+
+use super::Component;
+use crate::{
+    action::Action,
+    audit::{AuditEntry, AuditLog},
+    config::Config,
+    i18n::{tr, Text},
+    store::Store,
+    theme::Theme,
+};
+
+const HISTORY_LIMIT: usize = 200;
+
+/// Lists the changes made through rev, newest first.
+pub struct History {
+    store: Store,
+    entries: Vec<AuditEntry>,
+    theme: Theme,
+}
+
+impl History {
+    pub fn new(store: Store) -> Self {
+        Self {
+            store,
+            entries: Vec::new(),
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl Component for History {
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.theme = config.theme;
+
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        if let Action::GotoPage(page) = action {
+            if page == "history" {
+                self.entries = AuditLog::load(&self.store)?
+                    .latest(HISTORY_LIMIT)
+                    .cloned()
+                    .collect();
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        let block = self.theme.block().title(tr(Text::History));
+
+        if self.entries.is_empty() {
+            f.render_widget(Paragraph::new(tr(Text::HistoryEmpty)).block(block), area);
+            return Ok(());
+        }
+
+        let rows = self.entries.iter().map(|e| {
+            let style = if e.error.is_some() {
+                Style::default().fg(self.theme.failure)
+            } else {
+                Style::default()
+            };
+
+            Row::new([
+                Cell::from(e.at.format("%Y-%m-%d %H:%M").to_string()),
+                Cell::from(e.source.clone()),
+                Cell::from(e.action.clone()),
+                Cell::from(e.review.clone()),
+                Cell::from(e.details()),
+            ])
+            .style(style)
+        });
+
+        f.render_widget(
+            Table::new(rows).block(block).column_spacing(2).widths(&[
+                Constraint::Length(16),
+                Constraint::Length(5),
+                Constraint::Length(15),
+                Constraint::Percentage(25),
+                Constraint::Percentage(50),
+            ]),
+            area,
+        );
+
+        Ok(())
+    }
+}
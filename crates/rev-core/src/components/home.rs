@@ -1,16 +1,26 @@
 use super::Component;
+use crate::{config::Config, theme::Theme};
 
 use ratatui::{prelude::*, widgets::*};
 
-pub struct Home {}
+#[derive(Default)]
+pub struct Home {
+    theme: Theme,
+}
 
 impl Home {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
 }
 
 impl Component for Home {
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.theme = config.theme;
+
+        Ok(())
+    }
+
     fn draw(
         &mut self,
         f: &mut crate::tui::Frame<'_>,
@@ -27,13 +37,9 @@ impl Component for Home {
             )
             .split(area);
 
-        let main = Block::new()
-            .style(Style::default().bg(Color::Red))
-            .borders(Borders::ALL);
-        let input = Block::new()
-            .style(Style::default().bg(Color::Green))
-            .borders(Borders::ALL);
-        let help = Block::new().style(Style::default().bg(Color::Blue));
+        let main = self.theme.block();
+        let input = self.theme.block();
+        let help = Block::new().style(self.theme.status_bar());
 
         f.render_widget(Paragraph::new("hello world one").block(main), rects[0]);
         f.render_widget(Paragraph::new("hello world two").block(input), rects[1]);
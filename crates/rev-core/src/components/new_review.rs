@@ -0,0 +1,298 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use rev_git_provider::GitProvider;
+
+use super::{text_area::TextArea, Component, Effect};
+use crate::{
+    action::Action,
+    config::Config,
+    i18n::{tr, Text},
+    new_review::BranchReview,
+    store::Store,
+    theme::Theme,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Body,
+}
+
+/// A form for opening a review of the checked out branch, shown by `rev pr create`.
+///
+/// The fields start out pre-filled, see [`BranchReview::from_checkout`]. Keys go to the fields
+/// until the review is created, after which the keybinds apply again, e.g. `q` to quit.
+pub struct NewReviewForm {
+    branch: BranchReview,
+    provider: GitProvider,
+    store: Store,
+    title: TextArea,
+    body: TextArea,
+    focus: Field,
+    active: bool,
+    status: Option<String>,
+    /// Creates the review once submitted, until the app picks it up.
+    creating: Option<Effect>,
+    theme: Theme,
+}
+
+impl NewReviewForm {
+    pub fn new(branch: BranchReview, provider: GitProvider, store: Store) -> Self {
+        let mut title = TextArea::default();
+        title.set_text(&branch.review.title);
+        let mut body = TextArea::default();
+        body.set_text(&branch.review.body);
+
+        Self {
+            branch,
+            provider,
+            store,
+            title,
+            body,
+            focus: Field::Title,
+            active: false,
+            status: None,
+            creating: None,
+            theme: Theme::default(),
+        }
+    }
+
+    fn focused(&mut self) -> &mut TextArea {
+        match self.focus {
+            Field::Title => &mut self.title,
+            Field::Body => &mut self.body,
+        }
+    }
+
+    fn next_field(&mut self) {
+        self.focus = match self.focus {
+            Field::Title => Field::Body,
+            Field::Body => Field::Title,
+        };
+    }
+
+    fn submit(&mut self) {
+        let title = self.title.text().trim().to_string();
+        if title.is_empty() {
+            self.status = Some(tr(Text::ReviewNeedsTitle).to_string());
+            return;
+        }
+
+        let mut branch = self.branch.clone();
+        branch.review.title = title;
+        branch.review.body = self.body.text();
+        self.active = false;
+        self.status = Some(tr(Text::CreatingReview).to_string());
+
+        let provider = self.provider.clone();
+        let store = self.store.clone();
+        self.creating = Some(Effect::new(async move {
+            Ok(Some(match branch.create(&provider, &store, "tui").await {
+                Ok(reference) => Action::ReviewCreated(reference),
+                Err(e) => Action::ReviewCreationFailed(format!("{e:#}")),
+            }))
+        }));
+    }
+}
+
+impl Component for NewReviewForm {
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.theme = config.theme;
+
+        Ok(())
+    }
+
+    fn take_effects(&mut self) -> Vec<Effect> {
+        self.creating.take().into_iter().collect()
+    }
+
+    fn handle_paste(&mut self, text: String) -> anyhow::Result<Option<Action>> {
+        if !self.active {
+            return Ok(None);
+        }
+
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        match self.focus {
+            Field::Title => self.title.insert(&text.replace('\n', " ")),
+            Field::Body => self.body.insert(&text),
+        }
+
+        Ok(None)
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> anyhow::Result<Option<Action>> {
+        if !self.active {
+            return Ok(None);
+        }
+
+        let action = match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) => Some(Action::Quit),
+            (KeyCode::Tab | KeyCode::BackTab, _) => {
+                self.next_field();
+                None
+            }
+            (KeyCode::Enter, _) if self.focus == Field::Title => {
+                self.focus = Field::Body;
+                None
+            }
+            (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+                self.branch.review.draft = !self.branch.review.draft;
+                None
+            }
+            (KeyCode::Char('o'), KeyModifiers::CONTROL) => {
+                self.focus = Field::Body;
+                Some(Action::EditExternally(self.body.text()))
+            }
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+                self.submit();
+                (!self.active).then_some(Action::ExitInsertMode)
+            }
+            _ => {
+                self.focused().input(key);
+                None
+            }
+        };
+
+        Ok(action)
+    }
+
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        match action {
+            Action::GotoPage(page) if page == "new_review" => {
+                self.active = true;
+                return Ok(Some(Action::EnterInsertMode));
+            }
+            Action::EditedExternally(text) => self.body.set_text(&text),
+            Action::ReviewCreated(reference) => {
+                self.status = Some(format!("{} {reference}", tr(Text::CreatedReview)));
+            }
+            Action::ReviewCreationFailed(message) => {
+                // Back to the form, so the review can be fixed up and created again
+                self.status = Some(message);
+                self.active = true;
+                return Ok(Some(Action::EnterInsertMode));
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        let layout = Layout::new()
+            .constraints(vec![
+                Constraint::Length(1),
+                Constraint::Length(3),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let review = &self.branch.review;
+        let mut header = vec![
+            Span::styled(
+                format!("{}: ", tr(Text::NewReview)),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("{} ", self.branch.repository())),
+            Span::styled(review.head.clone(), Style::default().fg(self.theme.accent)),
+        ];
+        if let Some(base) = review.base.as_deref() {
+            header.push(Span::raw(" -> "));
+            header.push(Span::styled(
+                base.to_string(),
+                Style::default().fg(self.theme.accent),
+            ));
+        }
+        if review.draft {
+            header.push(Span::styled(
+                format!(" [{}]", tr(Text::DraftReview)),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+        f.render_widget(Paragraph::new(Line::from(header)), layout[0]);
+
+        let field = |name: Text, focused: bool| {
+            let block = self.theme.block().title(tr(name));
+            match focused {
+                true => block.border_style(Style::default().fg(self.theme.accent)),
+                false => block,
+            }
+        };
+        // The focused field is drawn last, as it places the cursor
+        let title_focused = self.focus == Field::Title;
+        if title_focused {
+            self.body.draw(f, layout[2], field(Text::ReviewBody, false));
+            self.title
+                .draw(f, layout[1], field(Text::ReviewTitle, true));
+        } else {
+            self.title
+                .draw(f, layout[1], field(Text::ReviewTitle, false));
+            self.body.draw(f, layout[2], field(Text::ReviewBody, true));
+        }
+
+        let status = self
+            .status
+            .clone()
+            .unwrap_or_else(|| tr(Text::NewReviewHelp).to_string());
+        f.render_widget(super::status_bar(&self.theme, None, &status), layout[3]);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use rev_git_provider::{gitea::GiteaOptions, models::NewReview, GitProvider};
+
+    use super::{Field, NewReviewForm};
+    use crate::{action::Action, components::Component, new_review::BranchReview, store::Store};
+
+    fn press(form: &mut NewReviewForm, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        form.handle_key_events(KeyEvent::new(code, modifiers))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_edits_prefilled_fields() -> anyhow::Result<()> {
+        let branch = BranchReview {
+            owner: "kjuulh".into(),
+            name: "rev".into(),
+            review: NewReview {
+                head: "create".into(),
+                title: "feat: create".into(),
+                body: "## Why".into(),
+                ..Default::default()
+            },
+        };
+        let provider =
+            GitProvider::gitea(GiteaOptions::new("http://localhost:3000").with_token("token"))?;
+        let mut form = NewReviewForm::new(branch, provider, Store::default());
+
+        assert_eq!(
+            None,
+            press(&mut form, KeyCode::Char('x'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            Some(Action::EnterInsertMode),
+            form.update(Action::GotoPage("new_review".into()))?
+        );
+
+        press(&mut form, KeyCode::Char('s'), KeyModifiers::NONE);
+        press(&mut form, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(Field::Body, form.focus);
+        press(&mut form, KeyCode::Char('!'), KeyModifiers::NONE);
+        press(&mut form, KeyCode::Char('d'), KeyModifiers::CONTROL);
+
+        assert_eq!("feat: creates", form.title.text());
+        assert_eq!("## Why!", form.body.text());
+        assert!(form.branch.review.draft);
+        assert_eq!(
+            Some(Action::Quit),
+            press(&mut form, KeyCode::Esc, KeyModifiers::NONE)
+        );
+
+        Ok(())
+    }
+}
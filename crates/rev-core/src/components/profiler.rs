@@ -0,0 +1,189 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ratatui::{prelude::*, widgets::*};
+use rev_git_provider::GitProvider;
+
+use super::Component;
+use crate::{
+    action::Action,
+    i18n::{tr, Text},
+};
+
+/// How many of the latest frames and events the timings are summarized over.
+const SAMPLES: usize = 120;
+
+/// Timings measured by the event loop, shared with the [`Profiler`] which shows them.
+#[derive(Clone, Default)]
+pub struct Timings(Arc<Mutex<Samples>>);
+
+#[derive(Default)]
+struct Samples {
+    frames: VecDeque<Duration>,
+    events: VecDeque<Duration>,
+}
+
+impl Timings {
+    /// Records how long drawing a frame took.
+    pub fn frame(&self, took: Duration) {
+        push(&mut self.0.lock().unwrap().frames, took);
+    }
+
+    /// Records how long it took from an event arriving until the actions it caused were handled.
+    pub fn event(&self, took: Duration) {
+        push(&mut self.0.lock().unwrap().events, took);
+    }
+}
+
+fn push(samples: &mut VecDeque<Duration>, sample: Duration) {
+    samples.push_back(sample);
+    while samples.len() > SAMPLES {
+        samples.pop_front();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Summary {
+    average: Duration,
+    max: Duration,
+}
+
+fn summarize(samples: &VecDeque<Duration>) -> Option<Summary> {
+    let max = samples.iter().max().copied()?;
+    let average = samples.iter().sum::<Duration>() / samples.len() as u32;
+
+    Some(Summary { average, max })
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MiB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KiB", b as f64 / 1024.0),
+        b => format!("{b} B"),
+    }
+}
+
+/// Shows frame times, event loop latency and what the provider is doing in the top right corner,
+/// to find out why the ui stutters.
+pub struct Profiler {
+    timings: Timings,
+    provider: GitProvider,
+    visible: bool,
+}
+
+impl Profiler {
+    pub fn new(timings: Timings, provider: GitProvider) -> Self {
+        Self {
+            timings,
+            provider,
+            visible: false,
+        }
+    }
+}
+
+impl Component for Profiler {
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        if action == Action::ToggleProfiler {
+            self.visible = !self.visible;
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+
+        let (frames, events) = {
+            let samples = self.timings.0.lock().unwrap();
+            (summarize(&samples.frames), summarize(&samples.events))
+        };
+        let timing = |label: Text, summary: Option<Summary>| {
+            let value = match summary {
+                Some(summary) => format!(
+                    "{} {:.1}ms, {} {:.1}ms",
+                    tr(Text::Average),
+                    summary.average.as_secs_f64() * 1000.0,
+                    tr(Text::Maximum),
+                    summary.max.as_secs_f64() * 1000.0,
+                ),
+                None => "-".into(),
+            };
+            Line::from(format!("{}: {value}", tr(label)))
+        };
+        let stats = self.provider.stats();
+
+        let lines = vec![
+            timing(Text::FrameTime, frames),
+            timing(Text::EventLoopLatency, events),
+            Line::from(format!(
+                "{}: {}",
+                tr(Text::PendingRequests),
+                stats.pending_requests
+            )),
+            Line::from(format!(
+                "{}: {} ({})",
+                tr(Text::CachedResponses),
+                stats.cached_responses,
+                format_bytes(stats.cached_bytes)
+            )),
+        ];
+
+        let width = lines
+            .iter()
+            .map(|l| l.width() as u16 + 4)
+            .max()
+            .unwrap_or_default()
+            .min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let profiler_area = Rect::new(
+            area.right().saturating_sub(width),
+            area.top(),
+            width,
+            height,
+        );
+
+        f.render_widget(Clear, profiler_area);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(tr(Text::Profiler)),
+            ),
+            profiler_area,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::VecDeque, time::Duration};
+
+    use super::{format_bytes, push, summarize, SAMPLES};
+
+    #[test]
+    fn test_summarize_latest_samples() {
+        let mut samples = VecDeque::new();
+        assert_eq!(None, summarize(&samples));
+
+        push(&mut samples, Duration::from_millis(100));
+        for _ in 0..SAMPLES {
+            push(&mut samples, Duration::from_millis(2));
+        }
+        push(&mut samples, Duration::from_millis(8));
+
+        assert_eq!(SAMPLES, samples.len());
+        let summary = summarize(&samples).unwrap();
+        assert_eq!(Duration::from_millis(8), summary.max);
+        assert!(summary.average < Duration::from_millis(3));
+
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+    }
+}
@@ -0,0 +1,310 @@
+use chrono::Utc;
+use ratatui::{prelude::*, widgets::*};
+use rev_git_provider::{models::RepositoryListItem, GitProvider};
+use timeago::Formatter;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{self as components, Component, Effect, Update};
+use crate::{
+    action::Action,
+    config::Config,
+    i18n::{tr, Text},
+    starred::{self, StarredRepositories},
+    store::Store,
+};
+
+/// Lists the repositories of the org of the queue with their number of open reviews, opening
+/// the reviews of the selected one on the list, requested from me or not. Starred repositories
+/// are listed first.
+pub struct Repositories {
+    provider: GitProvider,
+    store: Store,
+    starred: Vec<String>,
+    action_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    repositories: Option<Vec<RepositoryListItem>>,
+    table_state: TableState,
+    /// Where the table was last drawn, to tell which row a click is on.
+    table_area: Rect,
+}
+
+impl Repositories {
+    pub fn new(provider: GitProvider, store: Store) -> Self {
+        Self {
+            provider,
+            store,
+            starred: Vec::new(),
+            action_tx: None,
+            config: Config::default(),
+            repositories: None,
+            table_state: TableState::default(),
+            table_area: Rect::default(),
+        }
+    }
+
+    /// The org of the queue, or my own repositories without one.
+    fn owner(&self) -> Option<String> {
+        self.config
+            .queue
+            .org
+            .clone()
+            .or_else(|| self.config.committer.clone())
+    }
+
+    /// Fetches the repositories, a refresh replacing the fetch still running.
+    fn fetch(&self) -> Update {
+        let Some(owner) = self.owner() else {
+            return Update::default();
+        };
+        let provider = self.provider.clone();
+        Update::default().effect(
+            Effect::new(async move {
+                let repositories = provider.get_repositories(&owner).await?;
+                Ok(Some(Action::RepositoriesLoaded(repositories)))
+            })
+            .replacing("repositories"),
+        )
+    }
+
+    /// Lists the starred repositories first, the busiest first otherwise.
+    fn sort(&mut self) {
+        let starred = &self.starred;
+        if let Some(repositories) = self.repositories.as_mut() {
+            repositories.sort_by_key(|r| {
+                (
+                    !starred
+                        .iter()
+                        .any(|s| s.eq_ignore_ascii_case(&r.full_name())),
+                    std::cmp::Reverse(r.open_reviews),
+                )
+            });
+        }
+    }
+
+    fn toggle_star(&mut self) -> Option<Action> {
+        let repository = self
+            .repositories
+            .as_ref()?
+            .get(self.table_state.selected()?)?
+            .full_name();
+        if self
+            .config
+            .starred
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(&repository))
+        {
+            return Some(Action::Toast(format!(
+                "{repository} {}",
+                tr(Text::StarredInConfig)
+            )));
+        }
+
+        let toast = match StarredRepositories::toggle(&self.store, &repository) {
+            Ok(true) => format!("{} {repository}", tr(Text::Starred)),
+            Ok(false) => format!("{} {repository}", tr(Text::Unstarred)),
+            Err(e) => return Some(Action::Toast(format!("{e:#}"))),
+        };
+        self.starred = starred::load_or_warn(&self.store, &self.config.starred);
+        self.sort();
+        // Keeps the selection on the repository as it moves
+        if let Some(index) = self
+            .repositories
+            .as_ref()
+            .and_then(|r| r.iter().position(|r| r.full_name() == repository))
+        {
+            self.table_state.select(Some(index));
+        }
+
+        Some(Action::Toast(toast))
+    }
+
+    fn select(&mut self, index: usize) {
+        match self
+            .repositories
+            .as_ref()
+            .map(|r| r.len())
+            .unwrap_or_default()
+        {
+            0 => self.table_state.select(None),
+            len => self.table_state.select(Some(index.min(len - 1))),
+        }
+    }
+
+    fn click(&mut self, column: u16, row: u16) {
+        if !components::contains(self.table_area, column, row) {
+            return;
+        }
+
+        if let Some(index) =
+            components::table_row_at(self.table_area, self.table_state.offset(), 2, 1, row)
+        {
+            self.select(index);
+        }
+    }
+
+    fn browse_selected(&self) -> Option<Action> {
+        let repository = self
+            .repositories
+            .as_ref()?
+            .get(self.table_state.selected()?)?
+            .full_name();
+
+        // The list takes the repository once it's the current page
+        let tx = self.action_tx.as_ref()?;
+        tx.send(Action::GotoPage("github_review_list".into()))
+            .ok()?;
+        tx.send(Action::BrowseRepository(repository)).ok()?;
+
+        None
+    }
+}
+
+impl Component for Repositories {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> anyhow::Result<()> {
+        self.action_tx = Some(tx);
+
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.config = config;
+
+        Ok(())
+    }
+
+    fn init(&mut self) -> anyhow::Result<()> {
+        self.starred = starred::load_or_warn(&self.store, &self.config.starred);
+
+        Ok(())
+    }
+
+    fn handle(&mut self, action: Action) -> anyhow::Result<Update> {
+        match action {
+            Action::GotoPage(page) if page == "repositories" && self.repositories.is_none() => {
+                Ok(self.fetch())
+            }
+            Action::Refresh => Ok(self.fetch()),
+            action => Ok(self.update(action)?.into()),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        match action {
+            Action::RepositoriesLoaded(repositories) => {
+                self.repositories = Some(repositories);
+                self.sort();
+                self.select(self.table_state.selected().unwrap_or_default());
+            }
+            Action::SelectNext => self.select(self.table_state.selected().map_or(0, |i| i + 1)),
+            Action::SelectPrevious => self.select(
+                self.table_state
+                    .selected()
+                    .unwrap_or_default()
+                    .saturating_sub(1),
+            ),
+            Action::Scroll { down, .. } => {
+                let selected = self.table_state.selected().unwrap_or_default();
+                self.select(if down {
+                    selected + 1
+                } else {
+                    selected.saturating_sub(1)
+                })
+            }
+            Action::Click { column, row } => self.click(column, row),
+            Action::Open => return Ok(self.browse_selected()),
+            Action::ToggleStar => return Ok(self.toggle_star()),
+            Action::OpenInBrowser => {
+                if let Some(repository) = self
+                    .table_state
+                    .selected()
+                    .and_then(|i| self.repositories.as_ref()?.get(i))
+                {
+                    return Ok(components::open_in_browser(&repository.url));
+                }
+            }
+            Action::Back => return Ok(Some(Action::TogglePage("repositories".into()))),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        self.table_area = area;
+        let theme = &self.config.theme;
+        let title = match self.owner() {
+            Some(owner) => format!("{owner} {}", tr(Text::Repositories)),
+            None => tr(Text::Repositories).to_string(),
+        };
+        let block = theme.block().title(title);
+
+        let message = match (self.owner(), self.repositories.as_ref()) {
+            (None, _) => Some(tr(Text::NoRepositoryOwner)),
+            (Some(_), None) => Some(tr(Text::Processing)),
+            (Some(_), Some(repositories)) if repositories.is_empty() => {
+                Some(tr(Text::NoRepositories))
+            }
+            _ => None,
+        };
+        if let Some(message) = message {
+            f.render_widget(Paragraph::new(message).block(block), area);
+            return Ok(());
+        }
+        let repositories = self.repositories.as_deref().unwrap_or_default();
+
+        let formatter = Formatter::default();
+        let now = Utc::now();
+        let header = Row::new(
+            [
+                Text::Repository,
+                Text::OpenReviews,
+                Text::LastPushed,
+                Text::RepositoryDescription,
+            ]
+            .into_iter()
+            .map(|h| Cell::from(tr(h)).style(theme.header())),
+        )
+        .height(1)
+        .bottom_margin(1);
+        let symbols = self.config.terminal.symbols();
+        let rows = repositories.iter().map(|r| {
+            let open = Cell::from(r.open_reviews.to_string());
+            let starred = self
+                .starred
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(&r.full_name()));
+            Row::new([
+                Cell::from(match starred {
+                    true => format!("{} {}", symbols.pinned, r.name),
+                    false => r.name.clone(),
+                }),
+                match r.open_reviews {
+                    0 => open.style(Style::default().fg(theme.muted)),
+                    _ => open,
+                },
+                Cell::from(
+                    r.pushed_at
+                        .map(|p| formatter.convert_chrono(p, now))
+                        .unwrap_or_default(),
+                ),
+                Cell::from(r.description.clone().unwrap_or_default()),
+            ])
+        });
+
+        let table = Table::new(rows)
+            .header(header)
+            .column_spacing(3)
+            .highlight_style(theme.selection())
+            .block(block)
+            .widths(&[
+                Constraint::Percentage(25),
+                Constraint::Percentage(10),
+                Constraint::Percentage(15),
+                Constraint::Percentage(50),
+            ]);
+
+        f.render_stateful_widget(table, area, &mut self.table_state);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use ratatui::{prelude::*, widgets::*};
+
+use super::Component;
+use crate::{
+    action::Action,
+    config::Config,
+    i18n::{tr, Text},
+    snooze::{Snooze, Snoozes},
+    store::Store,
+    theme::Theme,
+};
+
+/// Lists the skipped and snoozed reviews, bringing the selected one back on enter.
+pub struct Snoozed {
+    store: Store,
+    reviews: Vec<(String, Snooze)>,
+    state: TableState,
+    theme: Theme,
+}
+
+impl Snoozed {
+    pub fn new(store: Store) -> Self {
+        Self {
+            store,
+            reviews: Vec::new(),
+            state: TableState::default(),
+            theme: Theme::default(),
+        }
+    }
+
+    fn load(&mut self) -> anyhow::Result<()> {
+        self.reviews = Snoozes::load(&self.store)?.reviews.into_iter().collect();
+        // Soonest back first, the ones snoozed until further notice last
+        self.reviews
+            .sort_by_key(|(_, s)| (s.until.is_none(), s.until, s.review.clone()));
+        self.select(self.state.selected().unwrap_or_default());
+
+        Ok(())
+    }
+
+    fn select(&mut self, index: usize) {
+        match self.reviews.len() {
+            0 => self.state.select(None),
+            len => self.state.select(Some(index.min(len - 1))),
+        }
+    }
+
+    fn unsnooze(&mut self) -> anyhow::Result<Option<Action>> {
+        let Some((id, snooze)) = self.state.selected().and_then(|i| self.reviews.get(i)) else {
+            return Ok(None);
+        };
+        let toast = format!("{} {}", tr(Text::Unsnoozed), snooze.review);
+
+        Snoozes::unsnooze(&self.store, id)?;
+        self.load()?;
+
+        Ok(Some(Action::Toast(toast)))
+    }
+}
+
+impl Component for Snoozed {
+    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
+        self.theme = config.theme;
+
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        match action {
+            Action::GotoPage(page) if page == "snoozed" => self.load()?,
+            Action::SelectNext => self.select(self.state.selected().map_or(0, |i| i + 1)),
+            Action::SelectPrevious => {
+                self.select(self.state.selected().unwrap_or_default().saturating_sub(1))
+            }
+            Action::Open => return self.unsnooze(),
+            Action::Back => return Ok(Some(Action::TogglePage("snoozed".into()))),
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        let block = self.theme.block().title(tr(Text::Snoozed));
+
+        if self.reviews.is_empty() {
+            f.render_widget(
+                Paragraph::new(tr(Text::NoSnoozedReviews)).block(block),
+                area,
+            );
+            return Ok(());
+        }
+
+        let rows = self.reviews.iter().map(|(_, s)| {
+            let until = match s.until {
+                Some(until) => format!(
+                    "{} {}",
+                    tr(Text::Until),
+                    until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ),
+                None => tr(Text::UntilUnsnoozed).to_string(),
+            };
+
+            Row::new([
+                Cell::from(s.review.clone()),
+                Cell::from(s.title.clone()),
+                Cell::from(until),
+            ])
+        });
+
+        f.render_stateful_widget(
+            Table::new(rows)
+                .block(block)
+                .column_spacing(2)
+                .highlight_style(self.theme.selection())
+                .widths(&[
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(50),
+                    Constraint::Percentage(25),
+                ]),
+            area,
+            &mut self.state,
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,733 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// How many undo steps and killed texts are remembered.
+const MAX_HISTORY: usize = 100;
+const MAX_KILL_RING: usize = 16;
+
+/// A position in the text, the column counts graphemes rather than bytes or chars.
+type Position = (usize, usize);
+
+/// A multi-line text editor with emacs style keys, used by the [`super::composer::Composer`].
+///
+/// - Arrows, `ctrl-a/e/b/f/p/n` and `home`/`end` move, `alt-b/f` and `ctrl-left/right` move by
+///   word, holding shift selects.
+/// - `ctrl-w`/`alt-backspace` and `alt-d` kill words, `ctrl-k` and `ctrl-u` kill to the end and
+///   start of the line, `ctrl-x` cuts and `alt-w` copies the selection.
+/// - `ctrl-y` yanks the last kill, `alt-y` right after cycles through the older ones.
+/// - `ctrl-z` undoes and `ctrl-r` redoes.
+///
+/// Lines are soft wrapped to the width they're drawn in.
+#[derive(Debug, Clone)]
+pub struct TextArea {
+    lines: Vec<String>,
+    cursor: Position,
+    /// Where the selection started, the selection runs from here to the cursor.
+    anchor: Option<Position>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// Consecutive inserts are undone together.
+    last_edit: Option<Edit>,
+    kill_ring: Vec<String>,
+    /// The text inserted by the last yank and which kill it was, for `alt-y`.
+    last_yank: Option<(Position, Position, usize)>,
+    /// The first visual row drawn, kept so the cursor stays in view.
+    scroll: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    lines: Vec<String>,
+    cursor: Position,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Insert,
+    Delete,
+    Other,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+            cursor: (0, 0),
+            anchor: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: None,
+            kill_ring: Vec::new(),
+            last_yank: None,
+            scroll: 0,
+        }
+    }
+}
+
+impl TextArea {
+    /// Replaces the text, placing the cursor at its end and forgetting the undo history. The
+    /// kill ring is kept, so text can be moved between drafts.
+    pub fn set_text(&mut self, text: &str) {
+        let kill_ring = std::mem::take(&mut self.kill_ring);
+        *self = Self {
+            lines: text.split('\n').map(str::to_string).collect(),
+            kill_ring,
+            ..Self::default()
+        };
+        self.move_bottom(false);
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// Handles a key, returning whether it was used.
+    pub fn input(&mut self, key: KeyEvent) -> bool {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let yanking = self.last_yank.take();
+
+        match key.code {
+            KeyCode::Char(c) if ctrl => match c {
+                'a' => self.move_home(false),
+                'e' => self.move_end(false),
+                'b' => self.move_left(false),
+                'f' => self.move_right(false),
+                'p' => self.move_up(false),
+                'n' => self.move_down(false),
+                'w' => self.kill(Self::word_left),
+                'k' => self.kill_line_end(),
+                'u' => self.kill(|t, _| (t.cursor.0, 0)),
+                'x' => self.cut(),
+                'y' => self.yank(),
+                'z' => self.undo(),
+                'r' => self.redo(),
+                _ => return false,
+            },
+            KeyCode::Char(c) if alt => match c {
+                'b' => self.move_word_left(false),
+                'f' => self.move_word_right(false),
+                'd' => self.kill(Self::word_right),
+                'w' => self.copy(),
+                'y' => match yanking {
+                    Some(yank) => self.yank_pop(yank),
+                    None => return false,
+                },
+                _ => return false,
+            },
+            KeyCode::Char(c) => self.insert(&c.to_string()),
+            KeyCode::Tab => self.insert("    "),
+            KeyCode::Enter => self.insert("\n"),
+            KeyCode::Backspace if alt || ctrl => self.kill(Self::word_left),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if ctrl || alt => self.move_word_left(shift),
+            KeyCode::Right if ctrl || alt => self.move_word_right(shift),
+            KeyCode::Left => self.move_left(shift),
+            KeyCode::Right => self.move_right(shift),
+            KeyCode::Up => self.move_up(shift),
+            KeyCode::Down => self.move_down(shift),
+            KeyCode::Home if ctrl => self.move_top(shift),
+            KeyCode::End if ctrl => self.move_bottom(shift),
+            KeyCode::Home => self.move_home(shift),
+            KeyCode::End => self.move_end(shift),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Inserts the text at the cursor, replacing the selection.
+    pub fn insert(&mut self, text: &str) {
+        self.edit(Edit::Insert);
+        self.delete_selection();
+
+        let (row, col) = self.cursor;
+        let at = byte_index(&self.lines[row], col);
+        let rest = self.lines[row].split_off(at);
+
+        let mut inserted = text.split('\n');
+        self.lines[row].push_str(inserted.next().unwrap_or_default());
+        let mut last = row;
+        for line in inserted {
+            last += 1;
+            self.lines.insert(last, line.to_string());
+        }
+
+        let col = graphemes(&self.lines[last]).len();
+        self.lines[last].push_str(&rest);
+        self.cursor = (last, col);
+    }
+
+    fn backspace(&mut self) {
+        if self.anchor.is_some() {
+            self.edit(Edit::Delete);
+            self.delete_selection();
+            return;
+        }
+
+        let start = self.left_of(self.cursor);
+        if start != self.cursor {
+            self.edit(Edit::Delete);
+            self.remove(start, self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.anchor.is_some() {
+            self.edit(Edit::Delete);
+            self.delete_selection();
+            return;
+        }
+
+        let end = self.right_of(self.cursor);
+        if end != self.cursor {
+            self.edit(Edit::Delete);
+            self.remove(self.cursor, end);
+        }
+    }
+
+    /// Removes the text between the cursor and where `to` moves it, onto the kill ring.
+    fn kill(&mut self, to: impl Fn(&Self, Position) -> Position) {
+        if self.anchor.is_some() {
+            return self.cut();
+        }
+
+        let other = to(self, self.cursor);
+        let (start, end) = ordered(self.cursor, other);
+        if start == end {
+            return;
+        }
+
+        self.edit(Edit::Other);
+        let killed = self.remove(start, end);
+        self.push_kill(killed);
+    }
+
+    /// Kills the rest of the line, or the line break when at the end of it.
+    fn kill_line_end(&mut self) {
+        let (row, col) = self.cursor;
+        if col == graphemes(&self.lines[row]).len() {
+            self.kill(Self::right_of);
+        } else {
+            self.kill(|t, (row, _)| (row, graphemes(&t.lines[row]).len()));
+        }
+    }
+
+    fn cut(&mut self) {
+        let Some(anchor) = self.anchor else {
+            return;
+        };
+
+        self.edit(Edit::Other);
+        let (start, end) = ordered(anchor, self.cursor);
+        let killed = self.remove(start, end);
+        self.push_kill(killed);
+    }
+
+    fn copy(&mut self) {
+        let Some(anchor) = self.anchor.take() else {
+            return;
+        };
+
+        let (start, end) = ordered(anchor, self.cursor);
+        let copied = self.slice(start, end);
+        self.push_kill(copied);
+    }
+
+    fn yank(&mut self) {
+        let Some(text) = self.kill_ring.last().cloned() else {
+            return;
+        };
+
+        self.edit(Edit::Other);
+        let start = self.selection().map(|(s, _)| s).unwrap_or(self.cursor);
+        self.insert(&text);
+        self.last_edit = Some(Edit::Other);
+        self.last_yank = Some((start, self.cursor, self.kill_ring.len() - 1));
+    }
+
+    /// Replaces the text of the last yank with the kill before it.
+    fn yank_pop(&mut self, (start, end, index): (Position, Position, usize)) {
+        let index = index.checked_sub(1).unwrap_or(self.kill_ring.len() - 1);
+        let text = self.kill_ring[index].clone();
+
+        self.remove(start, end);
+        self.insert(&text);
+        self.last_edit = Some(Edit::Other);
+        self.last_yank = Some((start, self.cursor, index));
+    }
+
+    fn push_kill(&mut self, text: String) {
+        self.kill_ring.push(text);
+        if self.kill_ring.len() > MAX_KILL_RING {
+            self.kill_ring.remove(0);
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo.pop() {
+            self.redo.push(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo.pop() {
+            self.undo.push(self.snapshot());
+            self.restore(snapshot);
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.lines = snapshot.lines;
+        self.cursor = snapshot.cursor;
+        self.anchor = None;
+        self.last_edit = None;
+    }
+
+    /// Remembers the text before an edit, unless it continues the edit before it.
+    fn edit(&mut self, edit: Edit) {
+        if self.last_edit == Some(edit) && edit != Edit::Other {
+            return;
+        }
+
+        self.undo.push(self.snapshot());
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.last_edit = Some(edit);
+    }
+
+    fn selection(&self) -> Option<(Position, Position)> {
+        self.anchor
+            .map(|a| ordered(a, self.cursor))
+            .filter(|(s, e)| s != e)
+    }
+
+    fn delete_selection(&mut self) {
+        if let Some((start, end)) = self.selection() {
+            self.remove(start, end);
+        }
+        self.anchor = None;
+    }
+
+    fn slice(&self, (start_row, start_col): Position, (end_row, end_col): Position) -> String {
+        if start_row == end_row {
+            let line = &self.lines[start_row];
+            return line[byte_index(line, start_col)..byte_index(line, end_col)].to_string();
+        }
+
+        let first = &self.lines[start_row];
+        let last = &self.lines[end_row];
+        let mut text = first[byte_index(first, start_col)..].to_string();
+        for line in &self.lines[start_row + 1..end_row] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&last[..byte_index(last, end_col)]);
+
+        text
+    }
+
+    /// Removes the text between the positions, returning it and placing the cursor at the start.
+    fn remove(&mut self, start: Position, end: Position) -> String {
+        let removed = self.slice(start, end);
+
+        let (start_row, start_col) = start;
+        let (end_row, end_col) = end;
+        let tail = {
+            let last = &self.lines[end_row];
+            last[byte_index(last, end_col)..].to_string()
+        };
+        let at = byte_index(&self.lines[start_row], start_col);
+        self.lines[start_row].truncate(at);
+        self.lines[start_row].push_str(&tail);
+        self.lines.drain(start_row + 1..=end_row);
+
+        self.cursor = start;
+        self.anchor = None;
+
+        removed
+    }
+
+    /// Moves the cursor, extending the selection when selecting and dropping it otherwise.
+    fn move_to(&mut self, position: Position, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = position;
+        self.last_edit = None;
+    }
+
+    fn left_of(&self, (row, col): Position) -> Position {
+        match (row, col) {
+            (0, 0) => (0, 0),
+            (row, 0) => (row - 1, graphemes(&self.lines[row - 1]).len()),
+            (row, col) => (row, col - 1),
+        }
+    }
+
+    fn right_of(&self, (row, col): Position) -> Position {
+        if col < graphemes(&self.lines[row]).len() {
+            (row, col + 1)
+        } else if row + 1 < self.lines.len() {
+            (row + 1, 0)
+        } else {
+            (row, col)
+        }
+    }
+
+    /// The start of the word before the position, skipping whitespace and punctuation first.
+    fn word_left(&self, position: Position) -> Position {
+        let (row, col) = position;
+        if col == 0 {
+            return self.left_of(position);
+        }
+
+        let line = graphemes(&self.lines[row]);
+        let mut col = col;
+        while col > 0 && !is_word(line[col - 1]) {
+            col -= 1;
+        }
+        while col > 0 && is_word(line[col - 1]) {
+            col -= 1;
+        }
+
+        (row, col)
+    }
+
+    /// The end of the word after the position, skipping whitespace and punctuation first.
+    fn word_right(&self, position: Position) -> Position {
+        let (row, col) = position;
+        let line = graphemes(&self.lines[row]);
+        if col == line.len() {
+            return self.right_of(position);
+        }
+
+        let mut col = col;
+        while col < line.len() && !is_word(line[col]) {
+            col += 1;
+        }
+        while col < line.len() && is_word(line[col]) {
+            col += 1;
+        }
+
+        (row, col)
+    }
+
+    fn move_left(&mut self, select: bool) {
+        self.move_to(self.left_of(self.cursor), select)
+    }
+
+    fn move_right(&mut self, select: bool) {
+        self.move_to(self.right_of(self.cursor), select)
+    }
+
+    fn move_word_left(&mut self, select: bool) {
+        self.move_to(self.word_left(self.cursor), select)
+    }
+
+    fn move_word_right(&mut self, select: bool) {
+        self.move_to(self.word_right(self.cursor), select)
+    }
+
+    fn move_up(&mut self, select: bool) {
+        let (row, col) = self.cursor;
+        let position = match row.checked_sub(1) {
+            Some(row) => (row, col.min(graphemes(&self.lines[row]).len())),
+            None => (0, 0),
+        };
+        self.move_to(position, select)
+    }
+
+    fn move_down(&mut self, select: bool) {
+        let (row, col) = self.cursor;
+        let position = if row + 1 < self.lines.len() {
+            (row + 1, col.min(graphemes(&self.lines[row + 1]).len()))
+        } else {
+            (row, graphemes(&self.lines[row]).len())
+        };
+        self.move_to(position, select)
+    }
+
+    fn move_home(&mut self, select: bool) {
+        self.move_to((self.cursor.0, 0), select)
+    }
+
+    fn move_end(&mut self, select: bool) {
+        let row = self.cursor.0;
+        self.move_to((row, graphemes(&self.lines[row]).len()), select)
+    }
+
+    fn move_top(&mut self, select: bool) {
+        self.move_to((0, 0), select)
+    }
+
+    fn move_bottom(&mut self, select: bool) {
+        let row = self.lines.len() - 1;
+        self.move_to((row, graphemes(&self.lines[row]).len()), select)
+    }
+
+    /// Draws the text soft wrapped inside the block, placing the terminal cursor at the cursor.
+    pub fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect, block: Block<'_>) {
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        if inner.width == 0 || inner.height == 0 {
+            return;
+        }
+
+        let rows = self.wrap(inner.width as usize);
+        let (cursor_row, cursor_x) = rows
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, r)| r.line == self.cursor.0 && r.start <= self.cursor.1)
+            .map(|(i, r)| {
+                let line = graphemes(&self.lines[r.line]);
+                (i, line[r.start..self.cursor.1].concat().width())
+            })
+            .unwrap_or_default();
+
+        let height = inner.height as usize;
+        if cursor_row < self.scroll {
+            self.scroll = cursor_row;
+        } else if cursor_row >= self.scroll + height {
+            self.scroll = cursor_row + 1 - height;
+        }
+
+        let selection = self.selection();
+        let selected = |position: Position| {
+            selection
+                .map(|(start, end)| start <= position && position < end)
+                .unwrap_or_default()
+        };
+        let lines = rows
+            .iter()
+            .skip(self.scroll)
+            .take(height)
+            .map(|r| {
+                let line = graphemes(&self.lines[r.line]);
+                Line::from(
+                    line[r.start..r.end]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, g)| {
+                            if selected((r.line, r.start + i)) {
+                                Span::styled(*g, Style::default().add_modifier(Modifier::REVERSED))
+                            } else {
+                                Span::raw(*g)
+                            }
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        f.render_widget(Paragraph::new(lines), inner);
+
+        let x = (cursor_x as u16).min(inner.width - 1);
+        f.set_cursor(inner.x + x, inner.y + (cursor_row - self.scroll) as u16);
+    }
+
+    /// Splits the lines into rows no wider than the width, breaking after whitespace when it can.
+    fn wrap(&self, width: usize) -> Vec<Row> {
+        let mut rows = Vec::new();
+
+        for (index, line) in self.lines.iter().enumerate() {
+            let line = graphemes(line);
+            let mut start = 0;
+            loop {
+                let mut end = start;
+                let mut used = 0;
+                while end < line.len() && used + line[end].width() <= width {
+                    used += line[end].width();
+                    end += 1;
+                }
+                if end >= line.len() {
+                    rows.push(Row {
+                        line: index,
+                        start,
+                        end: line.len(),
+                    });
+                    break;
+                }
+
+                // Break after the last whitespace, unless the word fills the whole row
+                let end = match line[start..end].iter().rposition(|g| g.trim().is_empty()) {
+                    Some(space) if space > 0 => start + space + 1,
+                    _ => end.max(start + 1),
+                };
+                rows.push(Row {
+                    line: index,
+                    start,
+                    end,
+                });
+                start = end;
+            }
+        }
+
+        rows
+    }
+}
+
+/// A visual row of a soft wrapped line, the range of graphemes it shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Row {
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+fn graphemes(line: &str) -> Vec<&str> {
+    line.graphemes(true).collect()
+}
+
+/// The byte offset of the grapheme at the column, or the length of the line after its end.
+fn byte_index(line: &str, col: usize) -> usize {
+    line.grapheme_indices(true)
+        .nth(col)
+        .map(|(i, _)| i)
+        .unwrap_or(line.len())
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().any(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn ordered(a: Position, b: Position) -> (Position, Position) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{Row, TextArea};
+
+    fn press(area: &mut TextArea, code: KeyCode, modifiers: KeyModifiers) {
+        area.input(KeyEvent::new(code, modifiers));
+    }
+
+    fn typed(area: &mut TextArea, text: &str) {
+        for c in text.chars() {
+            press(area, KeyCode::Char(c), KeyModifiers::empty());
+        }
+    }
+
+    #[test]
+    fn test_edits_by_grapheme_and_word() {
+        let mut area = TextArea::default();
+        area.set_text("héllo wörld 👍🏽");
+
+        press(&mut area, KeyCode::Backspace, KeyModifiers::empty());
+        assert_eq!("héllo wörld ", area.text());
+
+        press(&mut area, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!("héllo ", area.text());
+
+        press(&mut area, KeyCode::Left, KeyModifiers::ALT);
+        typed(&mut area, "oh ");
+        assert_eq!("oh héllo ", area.text());
+        assert_eq!((0, 3), area.cursor());
+
+        press(&mut area, KeyCode::Char('y'), KeyModifiers::CONTROL);
+        assert_eq!("oh wörld héllo ", area.text());
+    }
+
+    #[test]
+    fn test_selection_and_kill_ring() {
+        let mut area = TextArea::default();
+        area.set_text("one two\nthree");
+
+        press(&mut area, KeyCode::Up, KeyModifiers::empty());
+        press(&mut area, KeyCode::Home, KeyModifiers::empty());
+        for _ in 0..4 {
+            press(&mut area, KeyCode::Right, KeyModifiers::SHIFT);
+        }
+        press(&mut area, KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!("two\nthree", area.text());
+
+        press(&mut area, KeyCode::Char('k'), KeyModifiers::CONTROL);
+        assert_eq!("\nthree", area.text());
+
+        // Yanks the last kill, then swaps it for the one before
+        press(&mut area, KeyCode::Char('y'), KeyModifiers::CONTROL);
+        assert_eq!("two\nthree", area.text());
+        press(&mut area, KeyCode::Char('y'), KeyModifiers::ALT);
+        assert_eq!("one \nthree", area.text());
+    }
+
+    #[test]
+    fn test_undo_groups_typing() {
+        let mut area = TextArea::default();
+        typed(&mut area, "draft");
+        press(&mut area, KeyCode::Enter, KeyModifiers::empty());
+        typed(&mut area, "more");
+        assert_eq!("draft\nmore", area.text());
+
+        press(&mut area, KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert_eq!("", area.text());
+        press(&mut area, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!("draft\nmore", area.text());
+
+        press(&mut area, KeyCode::Backspace, KeyModifiers::empty());
+        press(&mut area, KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert_eq!("draft\nmore", area.text());
+    }
+
+    #[test]
+    fn test_soft_wrap() {
+        let mut area = TextArea::default();
+        area.set_text("a quick fox\nincomprehensible");
+
+        let rows = area.wrap(8);
+
+        assert_eq!(
+            vec![
+                Row {
+                    line: 0,
+                    start: 0,
+                    end: 8
+                },
+                Row {
+                    line: 0,
+                    start: 8,
+                    end: 11
+                },
+                Row {
+                    line: 1,
+                    start: 0,
+                    end: 8
+                },
+                Row {
+                    line: 1,
+                    start: 8,
+                    end: 16
+                },
+            ],
+            rows
+        );
+    }
+}
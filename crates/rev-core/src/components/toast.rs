@@ -0,0 +1,61 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{prelude::*, widgets::*};
+
+use super::Component;
+use crate::action::Action;
+
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// Shows short lived notifications in the bottom right corner on top of the current page.
+#[derive(Default)]
+pub struct Toast {
+    message: Option<(String, Instant)>,
+}
+
+impl Toast {
+    pub fn new() -> Self {
+        Self { message: None }
+    }
+}
+
+impl Component for Toast {
+    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
+        match action {
+            Action::Toast(message) => self.message = Some((message, Instant::now())),
+            Action::Tick => {
+                if let Some((_, shown_at)) = self.message.as_ref() {
+                    if shown_at.elapsed() > TOAST_DURATION {
+                        self.message = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut crate::tui::Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        let Some((message, _)) = self.message.as_ref() else {
+            return Ok(());
+        };
+
+        let width = (message.chars().count() as u16 + 4).min(area.width);
+        let height = 3.min(area.height);
+        let toast_area = Rect::new(
+            area.right().saturating_sub(width),
+            area.bottom().saturating_sub(height + 1),
+            width,
+            height,
+        );
+
+        f.render_widget(Clear, toast_area);
+        f.render_widget(
+            Paragraph::new(message.as_str()).block(Block::default().borders(Borders::ALL)),
+            toast_area,
+        );
+
+        Ok(())
+    }
+}
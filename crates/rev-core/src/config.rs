@@ -0,0 +1,2212 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kdl::{KdlDocument, KdlNode, KdlValue};
+use rev_git_provider::{
+    bitbucket::BitbucketOptions,
+    gitea::GiteaOptions,
+    github::{GithubOptions, GITHUB_HOST},
+    GitProvider,
+};
+
+use crate::{
+    action::Action,
+    bot::BotRule,
+    due_dates::DueSettings,
+    glob,
+    i18n::{self, Locale},
+    labels::LabelRule,
+    list_filters::{self, ListFilter},
+    multiplexer::Placement,
+    platform,
+    saved_searches::SavedSearch,
+    terminal::Capabilities,
+    theme::Theme,
+};
+
+mod migrations;
+
+pub use migrations::CONFIG_VERSION;
+
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub keybinds: Keybinds,
+    pub keybind_profiles: KeybindProfiles,
+    pub terminal: Capabilities,
+    /// Already fitted to the colors the terminal is able to display.
+    pub theme: Theme,
+    pub comments: CommentFilters,
+    /// The login of the user running rev, used to highlight their own comments.
+    pub committer: Option<String>,
+    pub bot: Vec<BotRule>,
+    /// Labels changed on reviews after they're submitted.
+    pub labels: Vec<LabelRule>,
+    pub discussions: Discussions,
+    pub workspace: WorkspaceSettings,
+    pub queue: QueueSettings,
+    /// Search queries to use as queues, next to the ones saved from the tui.
+    pub searches: Vec<SavedSearch>,
+    /// Repositories whose reviews go first and make up a queue of their own, next to the ones
+    /// starred from the tui, set with `starred { repositories "acme/web" }`.
+    pub starred: Vec<String>,
+    pub coverage: CoverageSettings,
+    pub commit_lint: CommitLintSettings,
+    pub badges: FileBadgeSettings,
+    pub team: TeamSettings,
+    pub out_of_office: OutOfOfficeSettings,
+    pub summarizer: SummarizerSettings,
+    pub panes: PaneSettings,
+    pub update: UpdateSettings,
+    pub list: ListSettings,
+    /// Filters of the list toggled with `1` to `9`, in order.
+    pub filters: Vec<ListFilter>,
+    pub due: DueSettings,
+    /// Set with `mouse false` in rev.kdl, e.g. to keep selecting text in tmux copy-mode.
+    pub disable_mouse: bool,
+    /// The command the output of git is piped through on the diff page, set with
+    /// `diff-command "delta --paging=never"` in rev.kdl. delta is used when it's installed and
+    /// none is set, `diff-command ""` shows the output of git as is.
+    pub diff_command: Option<String>,
+    /// Set with e.g. `locale "da"` in rev.kdl, `REV_LOCALE` wins over it.
+    pub locale: Option<Locale>,
+    pub provider: ProviderSettings,
+    pub profiles: Vec<Profile>,
+}
+
+impl Config {
+    /// Loads the tui settings from the blocks next to `config {}` in rev.kdl, blocks which aren't
+    /// present keep their defaults.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut config = Self::default();
+        if !path.exists() {
+            return Ok(config);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file: {}", path.display()))?;
+        let mut doc: KdlDocument = content.parse().context("failed to parse kdl config file")?;
+        if let Some(from) = migrations::migrate(&mut doc)? {
+            if let Err(e) = write_migrated(path, from, &content, &doc) {
+                tracing::warn!(
+                    "failed to write the upgraded config, upgrading it in memory: {e:#}"
+                );
+            }
+        }
+
+        if let Some(comments) = doc.get("comments").and_then(|n| n.children()) {
+            config.comments = CommentFilters::from_kdl(comments)?;
+        }
+        if let Some(discussions) = doc.get("discussions").and_then(|n| n.children()) {
+            config.discussions = Discussions::from_kdl(discussions)?;
+        }
+        if let Some(searches) = doc.get("searches").and_then(|n| n.children()) {
+            config.searches = searches
+                .nodes()
+                .iter()
+                .filter(|n| n.name().value() == "search")
+                .map(|n| {
+                    let values = n
+                        .entries()
+                        .iter()
+                        .filter_map(|e| e.value().as_string())
+                        .collect::<Vec<_>>();
+                    match values.as_slice() {
+                        [name, query] => Ok(SavedSearch {
+                            name: name.to_string(),
+                            query: query.to_string(),
+                        }),
+                        _ => {
+                            anyhow::bail!("searches must be in the form search \"name\" \"query\"")
+                        }
+                    }
+                })
+                .collect::<anyhow::Result<_>>()?;
+        }
+        if let Some(starred) = doc.get("starred").and_then(|n| n.children()) {
+            config.starred = kdl_strings(starred, "repositories")?.unwrap_or_default();
+        }
+        if let Some(coverage) = doc.get("coverage").and_then(|n| n.children()) {
+            config.coverage = CoverageSettings {
+                hide: kdl_strings(coverage, "hide")?.unwrap_or_default(),
+            };
+        }
+        if let Some(commit_lint) = doc.get("commit-lint").and_then(|n| n.children()) {
+            config.commit_lint = CommitLintSettings::from_kdl(commit_lint)?;
+        }
+        if let Some(badges) = doc.get("badges").and_then(|n| n.children()) {
+            config.badges = FileBadgeSettings::from_kdl(badges)?;
+        }
+        if let Some(team) = doc.get("team").and_then(|n| n.children()) {
+            config.team = TeamSettings {
+                members: kdl_strings(team, "members")?.unwrap_or_default(),
+            };
+        }
+        if let Some(out_of_office) = doc.get("out-of-office").and_then(|n| n.children()) {
+            config.out_of_office = OutOfOfficeSettings::from_kdl(out_of_office)?;
+        }
+        if let Some(summarizer) = doc.get("summarizer").and_then(|n| n.children()) {
+            config.summarizer = SummarizerSettings {
+                command: kdl_value(summarizer, "command")
+                    .map(|c| {
+                        c.as_string()
+                            .map(str::to_string)
+                            .context("summarizer.command must be a string")
+                    })
+                    .transpose()?,
+            };
+        }
+        if let Some(update) = doc.get("update").and_then(|n| n.children()) {
+            config.update = UpdateSettings::from_kdl(update)?;
+        }
+        if let Some(panes) = doc.get("panes").and_then(|n| n.children()) {
+            config.panes = PaneSettings::from_kdl(panes)?;
+        }
+        // Before the theme, which is fitted to the terminal
+        if let Some(terminal) = doc.get("terminal").and_then(|n| n.children()) {
+            config.terminal = TerminalSettings::from_kdl(terminal)?.capabilities();
+        }
+        if let Some(theme) = doc.get("theme").and_then(|n| n.children()) {
+            config.theme = Theme::from_kdl(theme)?.fit(&config.terminal);
+        }
+        if let Some(list) = doc.get("list").and_then(|n| n.children()) {
+            config.list = ListSettings::from_kdl(list)?;
+        }
+        if let Some(filters) = doc.get("filters").and_then(|n| n.children()) {
+            config.filters = filters
+                .nodes()
+                .iter()
+                .filter(|n| n.name().value() == "filter")
+                .map(ListFilter::from_kdl)
+                .collect::<anyhow::Result<_>>()?;
+            if config.filters.len() > list_filters::MAX_FILTERS {
+                anyhow::bail!(
+                    "filters can have at most {} filters, one for each of the keys 1 to 9",
+                    list_filters::MAX_FILTERS
+                );
+            }
+        }
+        if let Some(due) = doc.get("due").and_then(|n| n.children()) {
+            config.due = DueSettings::from_kdl(due)?;
+        }
+        if let Some(queue) = doc.get("queue").and_then(|n| n.children()) {
+            config.queue = QueueSettings::from_kdl(queue)?;
+        }
+        if let Some(workspace) = doc.get("workspace").and_then(|n| n.children()) {
+            config.workspace = WorkspaceSettings::from_kdl(workspace)?;
+        }
+        if let Some(keybinds) = doc.get("keybinds").and_then(|n| n.children()) {
+            config.keybind_profiles = KeybindProfiles::from_kdl(keybinds, &config.keybinds)?;
+        }
+        if let Some(provider) = doc.get("provider") {
+            config.provider = ProviderSettings::from_kdl(provider)?;
+        }
+        if let Some(profiles) = doc.get("profiles").and_then(|n| n.children()) {
+            config.profiles = profiles
+                .nodes()
+                .iter()
+                .filter(|n| n.name().value() == "profile")
+                .map(Profile::from_kdl)
+                .collect::<anyhow::Result<_>>()?;
+        }
+        if let Some(mouse) = doc.get("mouse").and_then(|n| n.entries().first()) {
+            config.disable_mouse = !mouse
+                .value()
+                .as_bool()
+                .context("mouse must be true or false")?;
+        }
+        if let Some(command) = doc.get("diff-command").and_then(|n| n.entries().first()) {
+            config.diff_command = Some(
+                command
+                    .value()
+                    .as_string()
+                    .context("diff-command must be a string")?
+                    .to_string(),
+            );
+        }
+        if let Some(locale) = kdl_locale(&doc)? {
+            config.locale = Some(locale);
+            i18n::configure(locale);
+        }
+        if let Some(labels) = doc.get("labels").and_then(|n| n.children()) {
+            config.labels = labels
+                .nodes()
+                .iter()
+                .filter(|n| n.name().value() == "on")
+                .map(LabelRule::from_kdl)
+                .collect::<anyhow::Result<_>>()?;
+        }
+        if let Some(bot) = doc.get("bot").and_then(|n| n.children()) {
+            config.bot = bot
+                .nodes()
+                .iter()
+                .filter(|n| n.name().value() == "rule")
+                .map(BotRule::from_kdl)
+                .collect::<anyhow::Result<_>>()?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// The top-level `locale "da"` of rev.kdl.
+fn kdl_locale(doc: &KdlDocument) -> anyhow::Result<Option<Locale>> {
+    let Some(locale) = doc.get("locale").and_then(|n| n.entries().first()) else {
+        return Ok(None);
+    };
+    let locale = locale
+        .value()
+        .as_string()
+        .context("locale must be a string")?;
+
+    Locale::try_parse(locale)
+        .map(Some)
+        .with_context(|| format!("locale must be one of en or da, got {locale}"))
+}
+
+/// Writes the upgraded config over the original, keeping the original next to it as e.g.
+/// `rev.kdl.v0.bak`.
+fn write_migrated(path: &Path, from: i64, original: &str, doc: &KdlDocument) -> anyhow::Result<()> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".v{from}.bak"));
+    let backup = PathBuf::from(backup);
+
+    std::fs::write(&backup, original)
+        .with_context(|| format!("failed to back up config to {}", backup.display()))?;
+    std::fs::write(path, doc.to_string())
+        .with_context(|| format!("failed to write config file: {}", path.display()))?;
+    tracing::info!(
+        "upgraded {} from version {from} to {CONFIG_VERSION}, the original is at {}",
+        path.display(),
+        backup.display()
+    );
+
+    Ok(())
+}
+
+/// Filters applied to the comments pane to cut down on bot and CI noise.
+///
+/// ```kdl
+/// comments {
+///     hide-authors "*[bot]" "renovate"
+///     collapse-after 15
+///     dedupe true
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommentFilters {
+    pub hide_authors: Vec<String>,
+    pub collapse_after: Option<usize>,
+    pub dedupe: bool,
+}
+
+impl Default for CommentFilters {
+    fn default() -> Self {
+        Self {
+            hide_authors: vec!["*[bot]".into()],
+            collapse_after: Some(15),
+            dedupe: true,
+        }
+    }
+}
+
+impl CommentFilters {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut filters = Self::default();
+
+        if let Some(authors) = kdl_strings(doc, "hide-authors")? {
+            filters.hide_authors = authors;
+        }
+        if let Some(collapse_after) = kdl_value(doc, "collapse-after") {
+            filters.collapse_after = match collapse_after.as_i64() {
+                Some(n) if n > 0 => Some(n as usize),
+                Some(_) => None,
+                None => anyhow::bail!("comments.collapse-after must be a number"),
+            };
+        }
+        if let Some(dedupe) = kdl_value(doc, "dedupe") {
+            filters.dedupe = dedupe
+                .as_bool()
+                .context("comments.dedupe must be true or false")?;
+        }
+
+        Ok(filters)
+    }
+}
+
+/// Long-form issues, such as RFCs, listed on the discussions page.
+///
+/// ```kdl
+/// discussions {
+///     query "is:issue label:rfc state:open"
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Discussions {
+    pub query: String,
+}
+
+impl Default for Discussions {
+    fn default() -> Self {
+        Self {
+            query: "is:issue label:rfc state:open".into(),
+        }
+    }
+}
+
+impl Discussions {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut discussions = Self::default();
+
+        if let Some(query) = kdl_value(doc, "query") {
+            discussions.query = query
+                .as_string()
+                .context("discussions.query must be a string")?
+                .to_string();
+        }
+
+        Ok(discussions)
+    }
+}
+
+/// Rules the commits and branch of a review are linted against, all are off unless set.
+///
+/// ```kdl
+/// commit-lint {
+///     conventional-commits true
+///     max-subject-length 72
+///     no-wip true
+///     comment "@{author} could you reword these commits?\n\n{warnings}"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CommitLintSettings {
+    pub conventional_commits: bool,
+    pub max_subject_length: Option<usize>,
+    /// Flags `WIP`, `fixup!` and `squash!` commits, and branches named wip.
+    pub no_wip: bool,
+    /// The comment offered to the author, `{author}` and `{warnings}` are replaced.
+    pub comment: Option<String>,
+}
+
+impl CommitLintSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+
+        if let Some(conventional) = kdl_value(doc, "conventional-commits") {
+            settings.conventional_commits = conventional
+                .as_bool()
+                .context("commit-lint.conventional-commits must be true or false")?;
+        }
+        if let Some(max) = kdl_value(doc, "max-subject-length") {
+            settings.max_subject_length = Some(
+                max.as_i64()
+                    .filter(|n| *n > 0)
+                    .context("commit-lint.max-subject-length must be a positive number")?
+                    as usize,
+            );
+        }
+        if let Some(no_wip) = kdl_value(doc, "no-wip") {
+            settings.no_wip = no_wip
+                .as_bool()
+                .context("commit-lint.no-wip must be true or false")?;
+        }
+        if let Some(comment) = kdl_value(doc, "comment") {
+            settings.comment = Some(
+                comment
+                    .as_string()
+                    .context("commit-lint.comment must be a string")?
+                    .to_string(),
+            );
+        }
+
+        Ok(settings)
+    }
+}
+
+/// Which repositories show the coverage reported by Codecov or Coveralls.
+///
+/// ```kdl
+/// coverage {
+///     hide "acme/legacy-*"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CoverageSettings {
+    /// Globs of repositories, in the form `owner/name`, whose coverage isn't shown.
+    pub hide: Vec<String>,
+}
+
+impl CoverageSettings {
+    pub fn shows(&self, repository: &str) -> bool {
+        !self.hide.iter().any(|h| glob::matches(h, repository))
+    }
+}
+
+/// The globs of the paths the badges of a review are computed from, see
+/// [`crate::file_badges`]. Each one set replaces the defaults.
+///
+/// ```kdl
+/// badges {
+///     tests "**/*_test.go" "**/testdata/**"
+///     docs "**/*.md" "docs/**"
+///     ci ".github/workflows/**" "Jenkinsfile"
+///     lockfiles "**/go.sum"
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileBadgeSettings {
+    pub tests: Vec<String>,
+    pub docs: Vec<String>,
+    pub ci: Vec<String>,
+    pub lockfiles: Vec<String>,
+}
+
+impl Default for FileBadgeSettings {
+    fn default() -> Self {
+        let globs = |globs: &[&str]| globs.iter().map(|g| g.to_string()).collect();
+
+        Self {
+            tests: globs(&[
+                "**/test/**",
+                "**/tests/**",
+                "**/__tests__/**",
+                "**/spec/**",
+                "**/*_test.*",
+                "**/*.test.*",
+                "**/*.spec.*",
+                "**/test_*",
+            ]),
+            docs: globs(&["**/*.md", "**/*.rst", "**/*.adoc", "docs/**"]),
+            ci: globs(&[
+                ".github/workflows/**",
+                ".gitlab-ci.yml",
+                ".circleci/**",
+                ".buildkite/**",
+                ".drone.yml",
+                ".woodpecker/**",
+                "azure-pipelines.yml",
+                "Jenkinsfile",
+            ]),
+            lockfiles: globs(&[
+                "**/Cargo.lock",
+                "**/package-lock.json",
+                "**/yarn.lock",
+                "**/pnpm-lock.yaml",
+                "**/go.sum",
+                "**/poetry.lock",
+                "**/uv.lock",
+                "**/Gemfile.lock",
+                "**/composer.lock",
+                "**/flake.lock",
+            ]),
+        }
+    }
+}
+
+impl FileBadgeSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let defaults = Self::default();
+
+        Ok(Self {
+            tests: kdl_strings(doc, "tests")?.unwrap_or(defaults.tests),
+            docs: kdl_strings(doc, "docs")?.unwrap_or(defaults.docs),
+            ci: kdl_strings(doc, "ci")?.unwrap_or(defaults.ci),
+            lockfiles: kdl_strings(doc, "lockfiles")?.unwrap_or(defaults.lockfiles),
+        })
+    }
+}
+
+/// The teammates reviews can be handed over to, see [`Action::ReassignReview`].
+///
+/// ```kdl
+/// team {
+///     members "alice" "bob" "carol"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TeamSettings {
+    /// The logins of the members, the committer is left out when picking one.
+    pub members: Vec<String>,
+}
+
+/// The command summarizing the conversation of a review, see [`crate::summarizer`].
+///
+/// ```kdl
+/// summarizer {
+///     command "llm -s 'Summarize this pull request discussion in a few bullet points'"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SummarizerSettings {
+    /// Run through the shell with the conversation on stdin, `None` disables summaries.
+    pub command: Option<String>,
+}
+
+/// Where new versions of rev are looked for, see `rev self-update`.
+///
+/// ```kdl
+/// update {
+///     // Don't look for new versions when the tui starts
+///     check false
+///     feed "https://git.example.com/api/v1/repos/acme/rev/releases/latest"
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UpdateSettings {
+    pub check: bool,
+    /// The latest release, in the format of the gitea or github release api.
+    pub feed: String,
+    /// How long to wait between checks when the tui starts.
+    pub interval_hours: i64,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            check: true,
+            feed: "https://git.front.kjuulh.io/api/v1/repos/kjuulh/rev/releases/latest".into(),
+            interval_hours: 24,
+        }
+    }
+}
+
+impl UpdateSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+
+        if let Some(check) = kdl_value(doc, "check") {
+            settings.check = check
+                .as_bool()
+                .context("update.check must be true or false")?;
+        }
+        if let Some(feed) = kdl_value(doc, "feed") {
+            settings.feed = feed
+                .as_string()
+                .context("update.feed must be a string")?
+                .to_string();
+        }
+        if let Some(interval) = kdl_value(doc, "interval-hours") {
+            settings.interval_hours = interval
+                .as_i64()
+                .filter(|h| *h > 0)
+                .context("update.interval-hours must be a positive number")?;
+        }
+
+        Ok(settings)
+    }
+}
+
+/// Overrides of what's detected of the terminal, see [`Capabilities`]. The `REV_*` variables
+/// win over these.
+///
+/// ```kdl
+/// terminal {
+///     // Copy through the terminal with OSC 52, rather than the clipboard program
+///     clipboard "osc52"
+///     hyperlinks false
+///     // Fit the theme to 256 colors and draw ascii symbols, e.g. for an older terminal
+///     colors "256"
+///     unicode false
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TerminalSettings {
+    /// `auto`, `osc52` or `native`.
+    pub clipboard: Option<String>,
+    pub hyperlinks: Option<bool>,
+    /// `truecolor`, `256` or `16`.
+    pub colors: Option<String>,
+    pub unicode: Option<bool>,
+}
+
+impl TerminalSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+
+        if let Some(clipboard) = kdl_value(doc, "clipboard") {
+            settings.clipboard = Some(
+                clipboard
+                    .as_string()
+                    .filter(|c| ["auto", "osc52", "native"].contains(c))
+                    .context("terminal.clipboard must be auto, osc52 or native")?
+                    .to_string(),
+            );
+        }
+        if let Some(hyperlinks) = kdl_value(doc, "hyperlinks") {
+            settings.hyperlinks = Some(
+                hyperlinks
+                    .as_bool()
+                    .context("terminal.hyperlinks must be true or false")?,
+            );
+        }
+        if let Some(colors) = kdl_value(doc, "colors") {
+            // Numbers are taken too, as `colors 256` reads naturally
+            let colors = match colors.as_i64() {
+                Some(depth) => depth.to_string(),
+                None => colors.as_string().unwrap_or_default().to_string(),
+            };
+            if !["truecolor", "256", "16"].contains(&colors.as_str()) {
+                anyhow::bail!("terminal.colors must be truecolor, 256 or 16");
+            }
+            settings.colors = Some(colors);
+        }
+        if let Some(unicode) = kdl_value(doc, "unicode") {
+            settings.unicode = Some(
+                unicode
+                    .as_bool()
+                    .context("terminal.unicode must be true or false")?,
+            );
+        }
+
+        Ok(settings)
+    }
+
+    /// The setting in the form of the variable overriding it, e.g. `REV_CLIPBOARD`.
+    fn var(&self, key: &str) -> Option<String> {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        match key {
+            "REV_CLIPBOARD" => self.clipboard.clone(),
+            "REV_HYPERLINKS" => self.hyperlinks.map(on_off),
+            "REV_COLORS" => self.colors.clone(),
+            "REV_UNICODE" => self.unicode.map(on_off),
+            _ => None,
+        }
+    }
+
+    /// Detects the capabilities of the terminal, with the settings overriding the detection.
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities::from_env(|key| std::env::var(key).ok().or_else(|| self.var(key)))
+    }
+}
+
+/// Commands opened in a tmux or wezterm pane next to rev, see [`crate::multiplexer`].
+///
+/// `{repository}`, `{number}` and `{branch}` are replaced in the commands. The checkout and verify
+/// commands run in the checkout of the review, the checkout opens a shell without a command.
+///
+/// ```kdl
+/// panes {
+///     checkout placement="window"
+///     logs "gh pr checks {number} --repo {repository} --watch"
+///     verify "cargo test" placement="pane"
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PaneSettings {
+    pub checkout: PaneCommand,
+    pub logs: PaneCommand,
+    pub verify: PaneCommand,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PaneCommand {
+    pub command: Option<String>,
+    pub placement: Placement,
+}
+
+impl Default for PaneSettings {
+    fn default() -> Self {
+        Self {
+            checkout: PaneCommand::default(),
+            logs: PaneCommand {
+                command: Some("gh pr checks {number} --repo {repository} --watch".into()),
+                placement: Placement::Pane,
+            },
+            verify: PaneCommand::default(),
+        }
+    }
+}
+
+impl PaneSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+        for node in doc.nodes() {
+            let name = node.name().value();
+            let pane = match name {
+                "checkout" => &mut settings.checkout,
+                "logs" => &mut settings.logs,
+                "verify" => &mut settings.verify,
+                other => anyhow::bail!("panes.{other} isn't one of checkout, logs or verify"),
+            };
+
+            if let Some(command) = node.entries().iter().find(|e| e.name().is_none()) {
+                pane.command = Some(
+                    command
+                        .value()
+                        .as_string()
+                        .with_context(|| format!("panes.{name} command must be a string"))?
+                        .to_string(),
+                );
+            }
+            if let Some(placement) = node.get("placement") {
+                pane.placement = placement
+                    .value()
+                    .as_string()
+                    .and_then(Placement::parse)
+                    .with_context(|| format!("panes.{name} placement must be pane or window"))?;
+            }
+        }
+
+        Ok(settings)
+    }
+}
+
+/// What happens to review requests arriving while out of office, see `rev ooo`.
+///
+/// ```kdl
+/// out-of-office {
+///     reassign true
+///     comment "I'm out of office until {until}, someone else from the team will take this one"
+///     repositories "acme/*"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OutOfOfficeSettings {
+    /// Hands the requests over to the member of the team with the fewest review requests.
+    pub reassign: bool,
+    /// Commented on the requests, `{until}` is replaced with the last day away.
+    pub comment: Option<String>,
+    /// Globs of repositories, in the form `owner/name`, whose requests are handled, all when
+    /// empty.
+    pub repositories: Vec<String>,
+}
+
+impl OutOfOfficeSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut settings = Self {
+            repositories: kdl_strings(doc, "repositories")?.unwrap_or_default(),
+            ..Default::default()
+        };
+
+        if let Some(reassign) = kdl_value(doc, "reassign") {
+            settings.reassign = reassign
+                .as_bool()
+                .context("out-of-office.reassign must be true or false")?;
+        }
+        if let Some(comment) = kdl_value(doc, "comment") {
+            settings.comment = Some(
+                comment
+                    .as_string()
+                    .context("out-of-office.comment must be a string")?
+                    .to_string(),
+            );
+        }
+
+        Ok(settings)
+    }
+
+    pub fn handles(&self, repository: &str) -> bool {
+        self.repositories.is_empty()
+            || self
+                .repositories
+                .iter()
+                .any(|r| glob::matches(r, repository))
+    }
+}
+
+/// The columns of the list of reviews, in the order they're shown.
+///
+/// ```kdl
+/// list {
+///     column "repo" width=20
+///     column "title"
+///     column "checks" width=8
+///     column "review" width=8
+///     column "age" width=15
+///     density "detailed"
+///     relative-numbers true
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ListSettings {
+    pub columns: Vec<ListColumn>,
+    pub density: Density,
+    /// Numbers the rows relative to the selected one, for count prefixed motions such as `5j`.
+    pub relative_numbers: bool,
+}
+
+/// How much of each review the list shows, toggled from the list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Density {
+    /// A single line per review.
+    #[default]
+    Compact,
+    /// A second line per review with its labels and checks.
+    Detailed,
+}
+
+impl Density {
+    pub fn toggle(self) -> Self {
+        match self {
+            Density::Compact => Density::Detailed,
+            Density::Detailed => Density::Compact,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListColumn {
+    pub column: Column,
+    /// The percentage of the width to use, columns without one share what's left.
+    pub width: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Owner,
+    Repository,
+    Title,
+    Author,
+    Age,
+    Labels,
+    Checks,
+    /// The review decision, and whether the review is a draft.
+    Review,
+    Size,
+    Approvals,
+    /// When the review is due, see [`DueSettings`].
+    Due,
+}
+
+impl Column {
+    fn parse(raw: &str) -> anyhow::Result<Self> {
+        Ok(match raw {
+            "owner" => Self::Owner,
+            "repo" => Self::Repository,
+            "title" => Self::Title,
+            "author" => Self::Author,
+            "age" => Self::Age,
+            "labels" => Self::Labels,
+            "checks" => Self::Checks,
+            "review" => Self::Review,
+            "size" => Self::Size,
+            "approvals" => Self::Approvals,
+            "due" => Self::Due,
+            _ => anyhow::bail!(
+                "list.column must be one of owner, repo, title, author, age, labels, checks, review, size, approvals or due, got: {raw}"
+            ),
+        })
+    }
+}
+
+impl Default for ListSettings {
+    fn default() -> Self {
+        let column = |column, width| ListColumn {
+            column,
+            width: Some(width),
+        };
+
+        Self {
+            columns: vec![
+                column(Column::Owner, 10),
+                column(Column::Repository, 15),
+                column(Column::Title, 45),
+                column(Column::Checks, 5),
+                column(Column::Review, 5),
+                column(Column::Age, 20),
+            ],
+            density: Density::default(),
+            relative_numbers: false,
+        }
+    }
+}
+
+impl ListSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let columns = doc
+            .nodes()
+            .iter()
+            .filter(|n| n.name().value() == "column")
+            .map(|n| {
+                let column = n
+                    .entries()
+                    .iter()
+                    .find(|e| e.name().is_none())
+                    .and_then(|e| e.value().as_string())
+                    .context("list.column must have a name, e.g. column \"title\"")?;
+                let width = n
+                    .get("width")
+                    .map(|w| {
+                        w.value()
+                            .as_i64()
+                            .filter(|w| (1..=100).contains(w))
+                            .map(|w| w as u16)
+                            .context("list.column width must be a percentage between 1 and 100")
+                    })
+                    .transpose()?;
+
+                Ok(ListColumn {
+                    column: Column::parse(column)?,
+                    width,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut settings = Self::default();
+        if !columns.is_empty() {
+            settings.columns = columns;
+        }
+        if let Some(density) = kdl_value(doc, "density") {
+            settings.density = match density.as_string() {
+                Some("compact") => Density::Compact,
+                Some("detailed") => Density::Detailed,
+                _ => anyhow::bail!("list.density must be either \"compact\" or \"detailed\""),
+            };
+        }
+
+        if let Some(relative_numbers) = kdl_value(doc, "relative-numbers") {
+            settings.relative_numbers = relative_numbers
+                .as_bool()
+                .context("list.relative-numbers must be true or false")?;
+        }
+
+        Ok(settings)
+    }
+
+    /// The percentage of the width of each column, those without a width hint share the rest.
+    pub fn widths(&self) -> Vec<u16> {
+        let hinted = self.columns.iter().filter_map(|c| c.width).sum::<u16>();
+        let unhinted = self.columns.iter().filter(|c| c.width.is_none()).count() as u16;
+        let rest = 100u16.saturating_sub(hinted) / unhinted.max(1);
+
+        self.columns
+            .iter()
+            .map(|c| c.width.unwrap_or(rest))
+            .collect()
+    }
+}
+
+/// The forge reviews come from, github unless configured otherwise. A github host other than
+/// `github.com` is github enterprise, its token can be given in rev.kdl or named by `token-env`,
+/// falling back on the one of `rev auth login` and `gh` for the host.
+///
+/// ```kdl
+/// provider "github" {
+///     host "github.acme.com"
+///     token-env "GHE_TOKEN"
+/// }
+/// ```
+///
+/// The graphql endpoint is `https://<host>/api/graphql` unless `api-url` says otherwise, and the
+/// token of `gh` is the one of the host unless it's logged in to it as `gh-host`.
+///
+/// ```kdl
+/// provider "github" {
+///     host "github.acme.com"
+///     api-url "https://api.github.acme.com/graphql"
+///     gh-host "acme.ghe.com"
+/// }
+/// ```
+///
+/// The gitea provider also works with Forgejo, its token falls back on `GITEA_TOKEN`.
+///
+/// ```kdl
+/// provider "gitea" {
+///     url "https://codeberg.org"
+///     token "..."
+/// }
+/// ```
+///
+/// Bitbucket can't search across repositories, so the ones to review are listed. A username
+/// makes the token an app password, falling back on `BITBUCKET_USERNAME` and `BITBUCKET_TOKEN`.
+///
+/// ```kdl
+/// provider "bitbucket" {
+///     repositories "acme/payments" "acme/web"
+///     username "kjuulh"
+///     token "..."
+/// }
+/// ```
+#[derive(Clone)]
+pub enum ProviderSettings {
+    Github {
+        /// `github.com` when unset.
+        host: Option<String>,
+        /// The graphql endpoint, when it isn't the one of the host.
+        api_url: Option<String>,
+        /// The host `gh` hands out the token of, when it isn't the host.
+        gh_host: Option<String>,
+        token: Option<String>,
+        /// The environment variable holding the token.
+        token_env: Option<String>,
+    },
+    Gitea {
+        url: String,
+        token: Option<String>,
+    },
+    Bitbucket {
+        repositories: Vec<String>,
+        username: Option<String>,
+        token: Option<String>,
+    },
+}
+
+impl Default for ProviderSettings {
+    fn default() -> Self {
+        Self::Github {
+            host: None,
+            api_url: None,
+            gh_host: None,
+            token: None,
+            token_env: None,
+        }
+    }
+}
+
+// Keeps the token out of logs
+impl std::fmt::Debug for ProviderSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Github {
+                host,
+                api_url,
+                gh_host,
+                token_env,
+                ..
+            } => f
+                .debug_struct("Github")
+                .field("host", host)
+                .field("api_url", api_url)
+                .field("gh_host", gh_host)
+                .field("token_env", token_env)
+                .finish(),
+            Self::Gitea { url, .. } => f.debug_struct("Gitea").field("url", url).finish(),
+            Self::Bitbucket {
+                repositories,
+                username,
+                ..
+            } => f
+                .debug_struct("Bitbucket")
+                .field("repositories", repositories)
+                .field("username", username)
+                .finish(),
+        }
+    }
+}
+
+impl ProviderSettings {
+    fn from_kdl(node: &KdlNode) -> anyhow::Result<Self> {
+        let kind = node
+            .entries()
+            .first()
+            .and_then(|e| e.value().as_string())
+            .context("provider must name the provider, e.g. provider \"gitea\"")?;
+        let children = node.children();
+        let string = |name: &str| -> anyhow::Result<Option<String>> {
+            children
+                .and_then(|c| kdl_value(c, name))
+                .map(|v| {
+                    v.as_string()
+                        .map(|s| s.to_string())
+                        .with_context(|| format!("provider.{name} must be a string"))
+                })
+                .transpose()
+        };
+
+        match kind {
+            "github" => Ok(Self::Github {
+                host: string("host")?,
+                api_url: string("api-url")?,
+                gh_host: string("gh-host")?,
+                token: string("token")?,
+                token_env: string("token-env")?,
+            }),
+            "gitea" | "forgejo" => Ok(Self::Gitea {
+                url: string("url")?.context("provider \"gitea\" needs the url of the instance")?,
+                token: string("token")?,
+            }),
+            "bitbucket" => Ok(Self::Bitbucket {
+                repositories: children
+                    .map(|c| kdl_strings(c, "repositories"))
+                    .transpose()?
+                    .flatten()
+                    .filter(|r| !r.is_empty())
+                    .context("provider \"bitbucket\" needs the repositories to review")?,
+                username: string("username")?,
+                token: string("token")?,
+            }),
+            other => anyhow::bail!("unknown provider {other}, expected github, gitea or bitbucket"),
+        }
+    }
+
+    /// The github host to log in to, `None` for the other providers.
+    pub fn github_host(&self) -> Option<&str> {
+        match self {
+            Self::Github { host, .. } => Some(host.as_deref().unwrap_or(GITHUB_HOST)),
+            _ => None,
+        }
+    }
+
+    /// Uses the graphql endpoint instead of the one in rev.kdl, e.g. given with
+    /// `--github-api-url`. Only github has one, it's ignored for the other providers.
+    pub fn with_github_api_url(mut self, url: Option<&str>) -> Self {
+        if let (Self::Github { api_url, .. }, Some(url)) = (&mut self, url) {
+            *api_url = Some(url.to_string());
+        }
+        self
+    }
+
+    /// The host the responses of github are cached under, `None` for github.com.
+    fn github_cache_host(&self) -> Option<&str> {
+        let Self::Github { api_url, .. } = self else {
+            return None;
+        };
+        let host = match api_url {
+            Some(url) => url
+                .split_once("://")
+                .map_or(url.as_str(), |(_, rest)| rest)
+                .split(['/', ':'])
+                .next()
+                .unwrap_or_default(),
+            None => self.github_host()?,
+        };
+
+        Some(host).filter(|h| *h != GITHUB_HOST && *h != "api.github.com")
+    }
+
+    /// How to reach github and which token to use, `None` for the other providers.
+    pub fn github_options(&self) -> anyhow::Result<Option<GithubOptions>> {
+        let Self::Github {
+            api_url,
+            gh_host,
+            token,
+            token_env,
+            ..
+        } = self
+        else {
+            return Ok(None);
+        };
+        let host = self.github_host().unwrap_or(GITHUB_HOST);
+
+        let mut options = GithubOptions::default()
+            .with_host(host)
+            .with_token_file(platform::token_file(host));
+        if let Some(api_url) = api_url {
+            options = options.with_uri(api_url);
+        }
+        if let Some(gh_host) = gh_host {
+            options = options.with_gh_host(gh_host);
+        }
+        if let Some(token) = token {
+            options = options.with_token(token);
+        } else if let Some(name) = token_env {
+            let token = std::env::var(name)
+                .ok()
+                .filter(|t| !t.is_empty())
+                .with_context(|| format!("{name} is named by provider.token-env but isn't set"))?;
+            options = options.with_token(token);
+        }
+
+        Ok(Some(options))
+    }
+
+    pub fn connect(&self) -> anyhow::Result<GitProvider> {
+        match self {
+            Self::Github { .. } => {
+                let mut options = self.github_options()?.context("a github provider")?;
+                // Without a writable data dir the responses are only cached in memory
+                if platform::data_dir_writable() {
+                    let mut cache = platform::data_dir().join("cache");
+                    // Responses of one host are no answer for another
+                    if let Some(host) = self.github_cache_host() {
+                        cache = cache.join(host);
+                    }
+                    options = options.with_disk_cache(cache);
+                }
+                GitProvider::github_with(options)
+            }
+            Self::Gitea { url, token } => {
+                let mut options = GiteaOptions::new(url);
+                if let Some(token) = token {
+                    options = options.with_token(token);
+                }
+                GitProvider::gitea(options)
+            }
+            Self::Bitbucket {
+                repositories,
+                username,
+                token,
+            } => {
+                let mut options = BitbucketOptions::new(repositories.clone());
+                if let Some(username) = username {
+                    options = options.with_username(username);
+                }
+                if let Some(token) = token {
+                    options = options.with_token(token);
+                }
+                GitProvider::bitbucket(options)
+            }
+        }
+    }
+}
+
+/// A named provider with its own queue, used instead of those next to it with `--profile`, e.g.
+/// to review on both github.com and github enterprise.
+///
+/// ```kdl
+/// profiles {
+///     profile "work" {
+///         provider "github" {
+///             host "github.acme.com"
+///             token-env "GHE_TOKEN"
+///         }
+///         queue {
+///             org "acme"
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Profile {
+    pub name: String,
+    pub provider: ProviderSettings,
+    /// Keeps the queue next to the profiles when unset.
+    pub queue: Option<QueueSettings>,
+}
+
+impl Profile {
+    fn from_kdl(node: &KdlNode) -> anyhow::Result<Self> {
+        let name = node
+            .entries()
+            .first()
+            .and_then(|e| e.value().as_string())
+            .context("profiles must be named, e.g. profile \"work\"")?;
+        let children = node.children();
+
+        Ok(Self {
+            name: name.to_string(),
+            provider: children
+                .and_then(|c| c.get("provider"))
+                .map(ProviderSettings::from_kdl)
+                .transpose()
+                .with_context(|| format!("invalid provider of profile {name}"))?
+                .unwrap_or_default(),
+            queue: children
+                .and_then(|c| c.get("queue"))
+                .and_then(|n| n.children())
+                .map(QueueSettings::from_kdl)
+                .transpose()
+                .with_context(|| format!("invalid queue of profile {name}"))?,
+        })
+    }
+}
+
+impl Config {
+    /// The config with the provider and queue of the profile, unchanged without a profile.
+    pub fn with_profile(mut self, name: Option<&str>) -> anyhow::Result<Self> {
+        let Some(name) = name else {
+            return Ok(self);
+        };
+        let profile = self
+            .profiles
+            .iter()
+            .find(|p| p.name == name)
+            .cloned()
+            .with_context(|| {
+                let names = self
+                    .profiles
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .collect::<Vec<_>>();
+                match names.as_slice() {
+                    [] => format!("no profile named {name}, rev.kdl has no profiles"),
+                    names => format!(
+                        "no profile named {name}, expected one of {}",
+                        names.join(", ")
+                    ),
+                }
+            })?;
+
+        self.provider = profile.provider;
+        if let Some(queue) = profile.queue {
+            self.queue = queue;
+        }
+
+        Ok(self)
+    }
+
+    /// The config with the github api reached at the url, e.g. given with `--github-api-url`,
+    /// unchanged without one.
+    pub fn with_github_api_url(mut self, url: Option<&str>) -> Self {
+        self.provider = self.provider.with_github_api_url(url);
+        self
+    }
+}
+
+/// Which review requests make up the queue, narrowed to the parts of a monorepo I own.
+///
+/// ```kdl
+/// queue {
+///     reviewer "acme/payments"
+///     org "acme"
+///     labels "backend" "needs-review"
+///     scopes "services/payments/**" "libs/billing/**"
+///     out-of-scope "deprioritize"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueueSettings {
+    /// The user, or team in the form `org/team`, whose review requests are queued. Defaults to
+    /// the requests of the current user.
+    pub reviewer: Option<String>,
+    /// Only queue reviews in repositories of the organization.
+    pub org: Option<String>,
+    /// Only queue reviews with any of the labels.
+    pub labels: Vec<String>,
+    /// Globs of the paths in scope, every review is in scope when empty.
+    pub scopes: Vec<String>,
+    pub out_of_scope: OutOfScope,
+}
+
+/// What happens to reviews whose changed files all fall outside the scopes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutOfScope {
+    Hide,
+    /// Moves them to the end of the queue.
+    #[default]
+    Deprioritize,
+}
+
+impl QueueSettings {
+    /// The labels the queue is narrowed to, `None` for any.
+    pub fn tags(&self) -> Option<Vec<String>> {
+        (!self.labels.is_empty()).then(|| self.labels.clone())
+    }
+
+    pub(crate) fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+
+        if let Some(reviewer) = kdl_value(doc, "reviewer") {
+            settings.reviewer = Some(
+                reviewer
+                    .as_string()
+                    .context("queue.reviewer must be a user or a team in the form org/team")?
+                    .to_string(),
+            );
+        }
+        if let Some(org) = kdl_value(doc, "org") {
+            settings.org = Some(
+                org.as_string()
+                    .context("queue.org must be a string")?
+                    .to_string(),
+            );
+        }
+        if let Some(labels) = kdl_strings(doc, "labels")? {
+            settings.labels = labels;
+        }
+        if let Some(scopes) = kdl_strings(doc, "scopes")? {
+            settings.scopes = scopes;
+        }
+        if let Some(out_of_scope) = kdl_value(doc, "out-of-scope") {
+            settings.out_of_scope = match out_of_scope.as_string() {
+                Some("hide") => OutOfScope::Hide,
+                Some("deprioritize") => OutOfScope::Deprioritize,
+                _ => {
+                    anyhow::bail!("queue.out-of-scope must be either \"hide\" or \"deprioritize\"")
+                }
+            };
+        }
+
+        Ok(settings)
+    }
+
+    /// The settings with the values given e.g. on the command line, unset values keep these.
+    pub fn overridden(
+        mut self,
+        reviewer: Option<String>,
+        org: Option<String>,
+        labels: Vec<String>,
+    ) -> Self {
+        self.reviewer = reviewer.or(self.reviewer);
+        self.org = org.or(self.org);
+        if !labels.is_empty() {
+            self.labels = labels;
+        }
+        self
+    }
+
+    pub fn is_scoped(&self) -> bool {
+        !self.scopes.is_empty()
+    }
+
+    /// Whether any of the changed files is in scope, a review without files is kept in scope as
+    /// there's nothing to judge it by.
+    pub fn in_scope(&self, files: &[String]) -> bool {
+        !self.is_scoped()
+            || files.is_empty()
+            || files
+                .iter()
+                .any(|f| self.scopes.iter().any(|s| glob::matches(s, f)))
+    }
+}
+
+/// Where reviews are checked out, see [`crate::workspace::Workspace`].
+///
+/// ```kdl
+/// workspace {
+///     root "/home/me/src/reviews"
+///     remote "git@github.com:{owner}/{name}.git"
+///     max-worktrees 20
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WorkspaceSettings {
+    /// Defaults to a directory in the data dir.
+    pub root: Option<PathBuf>,
+    /// The url to clone repositories from, `{owner}` and `{name}` are replaced.
+    pub remote: String,
+    /// The least recently used worktrees are removed beyond this.
+    pub max_worktrees: usize,
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            root: None,
+            remote: "https://github.com/{owner}/{name}.git".into(),
+            max_worktrees: 20,
+        }
+    }
+}
+
+impl WorkspaceSettings {
+    fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+
+        if let Some(root) = kdl_value(doc, "root") {
+            settings.root = Some(
+                root.as_string()
+                    .context("workspace.root must be a string")?
+                    .into(),
+            );
+        }
+        if let Some(remote) = kdl_value(doc, "remote") {
+            settings.remote = remote
+                .as_string()
+                .context("workspace.remote must be a string")?
+                .to_string();
+        }
+        if let Some(max_worktrees) = kdl_value(doc, "max-worktrees") {
+            settings.max_worktrees = max_worktrees
+                .as_i64()
+                .filter(|n| *n > 0)
+                .context("workspace.max-worktrees must be a positive number")?
+                as usize;
+        }
+
+        Ok(settings)
+    }
+}
+
+pub(crate) fn kdl_value<'a>(doc: &'a KdlDocument, name: &str) -> Option<&'a KdlValue> {
+    doc.get(name)
+        .and_then(|n| n.entries().first())
+        .map(|e| e.value())
+}
+
+pub(crate) fn kdl_strings(doc: &KdlDocument, name: &str) -> anyhow::Result<Option<Vec<String>>> {
+    let Some(node) = doc.get(name) else {
+        return Ok(None);
+    };
+
+    node.entries()
+        .iter()
+        .map(|e| {
+            e.value()
+                .as_string()
+                .map(|s| s.to_string())
+                .with_context(|| format!("{name} must only contain strings"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map(Some)
+}
+
+pub type InnerKeybinds = HashMap<Vec<KeyEvent>, Action>;
+
+#[derive(Clone, Debug)]
+pub struct Keybinds(pub InnerKeybinds);
+
+impl Deref for Keybinds {
+    type Target = InnerKeybinds;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Keybinds {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Keybinds {
+    /// Reads a digit of a count prefix, such as the `5` of `5j`, unless the digit is bound to an
+    /// action itself. A `0` only continues a count which is already `counting`.
+    pub fn count_digit(&self, key: KeyEvent, counting: bool) -> Option<usize> {
+        let KeyCode::Char(c) = key.code else {
+            return None;
+        };
+        if !key.modifiers.is_empty() || self.contains_key(&vec![key]) {
+            return None;
+        }
+
+        c.to_digit(10)
+            .filter(|d| *d > 0 || counting)
+            .map(|d| d as usize)
+    }
+
+    /// The action the keys are bound to on the page. The review has no queues to go through, so
+    /// the keys of `next-queue`, `tab` by default, move to the next pane there instead.
+    pub fn action(&self, page: Option<&str>, keys: &Vec<KeyEvent>) -> Option<Action> {
+        match (page, self.get(keys)?) {
+            (Some("github_review"), Action::NextQueue) => Some(Action::NextPane),
+            (_, action) => Some(action.clone()),
+        }
+    }
+}
+
+/// Keybinds switched in by page or queue, e.g. single keys to get through a queue of dependency
+/// updates quickly, and modifiers for destructive keys while reading a review closely. Profiles
+/// start from the default keybinds. Pages are named as in `github_review_list`, `github_review`,
+/// `diff` and `files`, queues by their saved search, with `requests` for the review requests.
+///
+/// ```kdl
+/// keybinds {
+///     profile "triage" {
+///         bind "x" "skip-review"
+///         bind "z" "wait-for-checks"
+///     }
+///     profile "deep-review" {
+///         unbind "a" "r" "d"
+///         bind "ctrl-a" "approve"
+///         bind "ctrl-r" "request-changes"
+///     }
+///     page "github_review" "deep-review"
+///     queue "Dependabot" "triage"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct KeybindProfiles {
+    pub profiles: BTreeMap<String, Keybinds>,
+    /// The profile by page.
+    pub pages: HashMap<String, String>,
+    /// The profile by queue, used on the list over the one of the page.
+    pub queues: HashMap<String, String>,
+}
+
+impl KeybindProfiles {
+    fn from_kdl(doc: &KdlDocument, defaults: &Keybinds) -> anyhow::Result<Self> {
+        let mut profiles = Self::default();
+
+        for node in doc.nodes() {
+            let values = node
+                .entries()
+                .iter()
+                .filter_map(|e| e.value().as_string())
+                .collect::<Vec<_>>();
+            match (node.name().value(), values.as_slice()) {
+                ("profile", [name]) => {
+                    let mut keybinds = defaults.clone();
+                    for bind in node.children().map(|c| c.nodes()).unwrap_or_default() {
+                        keybinds.apply_kdl(bind).with_context(|| {
+                            format!("keybinds.profile \"{name}\" is invalid")
+                        })?;
+                    }
+                    profiles.profiles.insert(name.to_string(), keybinds);
+                }
+                ("page", [page, profile]) => {
+                    profiles.pages.insert(page.to_string(), profile.to_string());
+                }
+                ("queue", [queue, profile]) => {
+                    profiles
+                        .queues
+                        .insert(queue.to_string(), profile.to_string());
+                }
+                (name, _) => anyhow::bail!(
+                    "keybinds.{name} must be in the form profile \"name\" {{ ... }}, page \"page\" \"profile\" or queue \"queue\" \"profile\""
+                ),
+            }
+        }
+
+        if let Some(unknown) = profiles
+            .pages
+            .values()
+            .chain(profiles.queues.values())
+            .find(|p| !profiles.profiles.contains_key(*p))
+        {
+            anyhow::bail!("keybinds uses the profile {unknown}, which isn't defined");
+        }
+
+        Ok(profiles)
+    }
+
+    /// The profile in use on the page, with the queue shown when the page is the list.
+    pub fn active(&self, page: Option<&str>, queue: Option<&str>) -> Option<&str> {
+        let queue = match page {
+            Some("github_review_list") => self.queues.get(queue.unwrap_or("requests")),
+            _ => None,
+        };
+
+        queue.or_else(|| self.pages.get(page?)).map(|p| p.as_str())
+    }
+
+    pub fn get(&self, profile: Option<&str>) -> Option<&Keybinds> {
+        self.profiles.get(profile?)
+    }
+}
+
+impl Keybinds {
+    /// Applies a `bind "key" "action"` or `unbind "key"...` node of a profile.
+    fn apply_kdl(&mut self, node: &KdlNode) -> anyhow::Result<()> {
+        let values = node
+            .entries()
+            .iter()
+            .map(|e| {
+                e.value()
+                    .as_string()
+                    .context("keys and actions must be strings")
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        match (node.name().value(), values.as_slice()) {
+            ("bind", [key, action]) => {
+                let action = Action::from_name(action)
+                    .with_context(|| format!("unknown action {action}"))?;
+                self.insert(vec![parse_key_event(key)?], action);
+            }
+            ("unbind", keys) => {
+                for key in keys {
+                    self.remove(&vec![parse_key_event(key)?]);
+                }
+            }
+            (name, _) => {
+                anyhow::bail!("{name} must be bind \"key\" \"action\" or unbind \"key\"")
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Keybinds {
+    fn default() -> Self {
+        let mut keybinds = HashMap::new();
+        keybinds.insert(vec![parse_key_event("q").unwrap()], Action::Quit);
+        keybinds.insert(vec![parse_key_event("b").unwrap()], Action::BeginReview);
+        keybinds.insert(vec![parse_key_event("s").unwrap()], Action::SkipReview);
+        keybinds.insert(vec![parse_key_event("Z").unwrap()], Action::SnoozeReview);
+        keybinds.insert(
+            vec![parse_key_event("U").unwrap()],
+            Action::TogglePage("snoozed".into()),
+        );
+        keybinds.insert(
+            vec![parse_key_event("w").unwrap()],
+            Action::SnoozeUntilChecksComplete,
+        );
+        keybinds.insert(
+            vec![parse_key_event("f").unwrap()],
+            Action::ToggleCommentFilters,
+        );
+        keybinds.insert(vec![parse_key_event("]").unwrap()], Action::NextComment);
+        keybinds.insert(vec![parse_key_event("[").unwrap()], Action::PreviousComment);
+        keybinds.insert(vec![parse_key_event("e").unwrap()], Action::EditComment);
+        keybinds.insert(vec![parse_key_event("d").unwrap()], Action::DeleteComment);
+        keybinds.insert(vec![parse_key_event("R").unwrap()], Action::Refresh);
+        keybinds.insert(vec![parse_key_event("j").unwrap()], Action::SelectNext);
+        keybinds.insert(vec![parse_key_event("k").unwrap()], Action::SelectPrevious);
+        keybinds.insert(vec![parse_key_event("J").unwrap()], Action::MoveDown);
+        keybinds.insert(vec![parse_key_event("K").unwrap()], Action::MoveUp);
+        keybinds.insert(vec![parse_key_event("p").unwrap()], Action::TogglePin);
+        keybinds.insert(vec![parse_key_event("*").unwrap()], Action::ToggleStar);
+        keybinds.insert(
+            vec![parse_key_event("H").unwrap()],
+            Action::TogglePage("history".into()),
+        );
+        keybinds.insert(
+            vec![parse_key_event("O").unwrap()],
+            Action::TogglePage("repositories".into()),
+        );
+        keybinds.insert(
+            vec![parse_key_event("D").unwrap()],
+            Action::TogglePage("discussions".into()),
+        );
+        keybinds.insert(vec![parse_key_event("enter").unwrap()], Action::Open);
+        keybinds.insert(vec![parse_key_event("esc").unwrap()], Action::Back);
+        keybinds.insert(vec![parse_key_event("c").unwrap()], Action::AddComment);
+        keybinds.insert(vec![parse_key_event("a").unwrap()], Action::Approve);
+        keybinds.insert(vec![parse_key_event("r").unwrap()], Action::RequestChanges);
+        keybinds.insert(vec![parse_key_event("M").unwrap()], Action::MergeReview);
+        keybinds.insert(vec![parse_key_event("/").unwrap()], Action::FilterList);
+        keybinds.insert(vec![parse_key_event("N").unwrap()], Action::EditSearch);
+        keybinds.insert(vec![parse_key_event("tab").unwrap()], Action::NextQueue);
+        keybinds.insert(vec![parse_key_event("v").unwrap()], Action::ToggleDensity);
+        keybinds.insert(vec![parse_key_event("z").unwrap()], Action::SortBySize);
+        keybinds.insert(vec![parse_key_event("i").unwrap()], Action::ToggleStats);
+        keybinds.insert(
+            vec![parse_key_event("u").unwrap()],
+            Action::DiffSinceLastVisit,
+        );
+        keybinds.insert(vec![parse_key_event("F").unwrap()], Action::RangeDiff);
+        keybinds.insert(vec![parse_key_event("=").unwrap()], Action::OpenFiles);
+        keybinds.insert(vec![parse_key_event("l").unwrap()], Action::NextLink);
+        keybinds.insert(vec![parse_key_event("L").unwrap()], Action::PreviousLink);
+        keybinds.insert(
+            vec![parse_key_event("T").unwrap()],
+            Action::RequestDescription,
+        );
+        keybinds.insert(
+            vec![parse_key_event("C").unwrap()],
+            Action::RequestCommitFixes,
+        );
+        keybinds.insert(vec![parse_key_event("n").unwrap()], Action::NextCommentsTab);
+        keybinds.insert(vec![parse_key_event("ctrl-w").unwrap()], Action::NextPane);
+        keybinds.insert(
+            vec![parse_key_event("backtab").unwrap()],
+            Action::PreviousPane,
+        );
+        keybinds.insert(vec![parse_key_event("pagedown").unwrap()], Action::PageDown);
+        keybinds.insert(vec![parse_key_event("pageup").unwrap()], Action::PageUp);
+        keybinds.insert(
+            vec![parse_key_event("ctrl-d").unwrap()],
+            Action::HalfPageDown,
+        );
+        keybinds.insert(vec![parse_key_event("ctrl-u").unwrap()], Action::HalfPageUp);
+        keybinds.insert(vec![parse_key_event("}").unwrap()], Action::NextThread);
+        keybinds.insert(vec![parse_key_event("{").unwrap()], Action::PreviousThread);
+        keybinds.insert(vec![parse_key_event("t").unwrap()], Action::ReplyToThread);
+        keybinds.insert(vec![parse_key_event("X").unwrap()], Action::ResolveThread);
+        keybinds.insert(vec![parse_key_event("y").unwrap()], Action::CopyCommentLink);
+        keybinds.insert(vec![parse_key_event("Y").unwrap()], Action::CopyThreadLink);
+        keybinds.insert(vec![parse_key_event("A").unwrap()], Action::ReassignReview);
+        keybinds.insert(vec![parse_key_event("S").unwrap()], Action::Summarize);
+        keybinds.insert(vec![parse_key_event("o").unwrap()], Action::OpenInBrowser);
+        keybinds.insert(vec![parse_key_event("g").unwrap()], Action::CheckoutPane);
+        keybinds.insert(vec![parse_key_event("G").unwrap()], Action::LogsPane);
+        keybinds.insert(vec![parse_key_event("V").unwrap()], Action::VerifyPane);
+        keybinds.insert(vec![parse_key_event("P").unwrap()], Action::ToggleProfiler);
+
+        Self(keybinds)
+    }
+}
+
+fn parse_key_event(raw: &str) -> anyhow::Result<KeyEvent> {
+    if let Some(key) = raw.strip_prefix("ctrl-") {
+        let event = parse_key_event(key)?;
+        return Ok(KeyEvent::new(
+            event.code,
+            event.modifiers | KeyModifiers::CONTROL,
+        ));
+    }
+    if let Some(key) = raw.strip_prefix("alt-") {
+        let event = parse_key_event(key)?;
+        return Ok(KeyEvent::new(
+            event.code,
+            event.modifiers | KeyModifiers::ALT,
+        ));
+    }
+
+    let (e, modifiers) = match raw {
+        "enter" => (KeyCode::Enter, KeyModifiers::empty()),
+        "esc" => (KeyCode::Esc, KeyModifiers::empty()),
+        "tab" => (KeyCode::Tab, KeyModifiers::empty()),
+        // Shift-tab, which the terminal reports together with shift
+        "backtab" => (KeyCode::BackTab, KeyModifiers::SHIFT),
+        "pagedown" => (KeyCode::PageDown, KeyModifiers::empty()),
+        "pageup" => (KeyCode::PageUp, KeyModifiers::empty()),
+        // The terminal reports uppercase letters together with shift
+        c if c.len() == 1 && c.chars().all(|c| c.is_ascii_uppercase()) => {
+            let c = c.chars().next().expect("to get next key code");
+            (KeyCode::Char(c), KeyModifiers::SHIFT)
+        }
+        c if c.len() == 1 => {
+            let c = c.chars().next().expect("to get next key code");
+            (KeyCode::Char(c.to_ascii_lowercase()), KeyModifiers::empty())
+        }
+        _ => anyhow::bail!("Unable to parse {raw}"),
+    };
+
+    Ok(KeyEvent::new(e, modifiers))
+}
+
+#[cfg(test)]
+mod test {
+    use kdl::KdlDocument;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{
+        kdl_locale, parse_key_event, Column, CommentFilters, CommitLintSettings, Config, Density,
+        KeybindProfiles, Keybinds, ListColumn, ListSettings, OutOfScope, Profile, ProviderSettings,
+        QueueSettings, TerminalSettings,
+    };
+    use crate::{
+        action::Action,
+        i18n::Locale,
+        terminal::{Capabilities, ColorDepth},
+    };
+
+    #[test]
+    fn test_parse_key_event_keeps_uppercase() -> anyhow::Result<()> {
+        assert_eq!(
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::empty()),
+            parse_key_event("r")?
+        );
+        assert_eq!(
+            KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT),
+            parse_key_event("R")?
+        );
+        assert_eq!(
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::empty()),
+            parse_key_event("enter")?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_keybind_profiles() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+profile "triage" {
+    bind "x" "skip-review"
+}
+profile "deep-review" {
+    unbind "a"
+    bind "ctrl-a" "approve"
+}
+page "github_review" "deep-review"
+queue "Dependabot" "triage"
+"#
+        .parse()?;
+
+        let profiles = KeybindProfiles::from_kdl(&doc, &Keybinds::default())?;
+
+        assert_eq!(
+            Some("triage"),
+            profiles.active(Some("github_review_list"), Some("Dependabot"))
+        );
+        assert_eq!(None, profiles.active(Some("github_review_list"), None));
+        assert_eq!(
+            Some("deep-review"),
+            profiles.active(Some("github_review"), Some("Dependabot"))
+        );
+
+        let triage = profiles.get(Some("triage")).unwrap();
+        assert_eq!(
+            Some(&Action::SkipReview),
+            triage.get(&vec![parse_key_event("x")?])
+        );
+        assert_eq!(
+            Some(&Action::Approve),
+            triage.get(&vec![parse_key_event("a")?])
+        );
+
+        let deep = profiles.get(Some("deep-review")).unwrap();
+        assert_eq!(None, deep.get(&vec![parse_key_event("a")?]));
+        assert_eq!(
+            Some(&Action::Approve),
+            deep.get(&vec![KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::CONTROL
+            )])
+        );
+        let tab = vec![parse_key_event("tab")?];
+        assert_eq!(
+            Some(Action::NextPane),
+            deep.action(Some("github_review"), &tab)
+        );
+        assert_eq!(
+            Some(Action::NextPane),
+            Keybinds::default().action(Some("github_review"), &tab)
+        );
+        assert_eq!(
+            Some(Action::NextQueue),
+            Keybinds::default().action(Some("github_review_list"), &tab)
+        );
+
+        let doc: KdlDocument = r#"page "diff" "missing""#.parse()?;
+        assert!(KeybindProfiles::from_kdl(&doc, &Keybinds::default()).is_err());
+        let doc: KdlDocument = "profile \"x\" {\n    bind \"a\" \"launch\"\n}".parse()?;
+        assert!(KeybindProfiles::from_kdl(&doc, &Keybinds::default()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_digit() -> anyhow::Result<()> {
+        let keybinds = Keybinds::default();
+
+        assert_eq!(Some(5), keybinds.count_digit(parse_key_event("5")?, false));
+        assert_eq!(None, keybinds.count_digit(parse_key_event("0")?, false));
+        assert_eq!(Some(0), keybinds.count_digit(parse_key_event("0")?, true));
+        assert_eq!(None, keybinds.count_digit(parse_key_event("j")?, true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_comment_filters() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+comments {
+    hide-authors "*[bot]" "renovate"
+    collapse-after 0
+    dedupe false
+}
+"#
+        .parse()?;
+
+        let filters = CommentFilters::from_kdl(doc.get("comments").unwrap().children().unwrap())?;
+
+        assert_eq!(vec!["*[bot]", "renovate"], filters.hide_authors);
+        assert_eq!(None, filters.collapse_after);
+        assert!(!filters.dedupe);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_locale() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"locale "da_DK""#.parse()?;
+        assert_eq!(Some(Locale::Da), kdl_locale(&doc)?);
+
+        let doc: KdlDocument = "mouse false".parse()?;
+        assert_eq!(None, kdl_locale(&doc)?);
+
+        let doc: KdlDocument = r#"locale "fr""#.parse()?;
+        assert!(kdl_locale(&doc).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_terminal() -> anyhow::Result<()> {
+        let doc: KdlDocument =
+            "terminal {\n    clipboard \"osc52\"\n    hyperlinks false\n}".parse()?;
+
+        let settings =
+            TerminalSettings::from_kdl(doc.get("terminal").unwrap().children().unwrap())?;
+
+        assert_eq!(Some("osc52".to_string()), settings.var("REV_CLIPBOARD"));
+        assert_eq!(Some("off".to_string()), settings.var("REV_HYPERLINKS"));
+        assert_eq!(None, settings.var("REV_COLORS"));
+
+        let doc: KdlDocument = "terminal {\n    colors 256\n    unicode false\n}".parse()?;
+        let settings =
+            TerminalSettings::from_kdl(doc.get("terminal").unwrap().children().unwrap())?;
+        assert_eq!(Some("256".to_string()), settings.var("REV_COLORS"));
+        assert_eq!(Some("off".to_string()), settings.var("REV_UNICODE"));
+        let terminal = Capabilities::from_env(|key| settings.var(key));
+        assert_eq!(ColorDepth::Ansi256, terminal.colors);
+        assert!(!terminal.unicode);
+
+        let doc: KdlDocument = "terminal {\n    clipboard \"x11\"\n}".parse()?;
+        assert!(
+            TerminalSettings::from_kdl(doc.get("terminal").unwrap().children().unwrap()).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_commit_lint() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+commit-lint {
+    conventional-commits true
+    max-subject-length 72
+    comment "@{author}: {warnings}"
+}
+"#
+        .parse()?;
+
+        let settings =
+            CommitLintSettings::from_kdl(doc.get("commit-lint").unwrap().children().unwrap())?;
+
+        assert!(settings.conventional_commits);
+        assert_eq!(Some(72), settings.max_subject_length);
+        assert!(!settings.no_wip);
+        assert_eq!(Some("@{author}: {warnings}"), settings.comment.as_deref());
+
+        let doc: KdlDocument = "commit-lint {\n    max-subject-length 0\n}".parse()?;
+        assert!(
+            CommitLintSettings::from_kdl(doc.get("commit-lint").unwrap().children().unwrap())
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_queue_scopes() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+queue {
+    reviewer "acme/payments"
+    labels "backend"
+    scopes "services/payments/**"
+    out-of-scope "hide"
+}
+"#
+        .parse()?;
+
+        let queue = QueueSettings::from_kdl(doc.get("queue").unwrap().children().unwrap())?;
+
+        assert_eq!(Some("acme/payments"), queue.reviewer.as_deref());
+        assert_eq!(None, queue.org);
+        assert_eq!(vec!["backend"], queue.labels);
+        assert_eq!(OutOfScope::Hide, queue.out_of_scope);
+        assert!(queue.in_scope(&["README.md".into(), "services/payments/src/main.rs".into()]));
+        assert!(!queue.in_scope(&["services/search/src/main.rs".into()]));
+        assert!(queue.in_scope(&[]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_provider() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+provider "forgejo" {
+    url "https://codeberg.org"
+    token "secret"
+}
+"#
+        .parse()?;
+
+        let provider = ProviderSettings::from_kdl(doc.get("provider").unwrap())?;
+
+        assert!(matches!(
+            &provider,
+            ProviderSettings::Gitea { url, token: Some(_) } if url == "https://codeberg.org"
+        ));
+        assert!(!format!("{provider:?}").contains("secret"));
+
+        let doc: KdlDocument = r#"provider "gitea""#.parse()?;
+        assert!(ProviderSettings::from_kdl(doc.get("provider").unwrap()).is_err());
+
+        let doc: KdlDocument = r#"
+provider "bitbucket" {
+    repositories "acme/payments" "acme/web"
+    username "kjuulh"
+}
+"#
+        .parse()?;
+        assert!(matches!(
+            ProviderSettings::from_kdl(doc.get("provider").unwrap())?,
+            ProviderSettings::Bitbucket { repositories, username: Some(_), token: None }
+                if repositories == ["acme/payments", "acme/web"]
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_github_api_url() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+provider "github" {
+    host "github.acme.com"
+    api-url "https://api.github.acme.com/graphql"
+    gh-host "acme.ghe.com"
+}
+"#
+        .parse()?;
+
+        let provider = ProviderSettings::from_kdl(doc.get("provider").unwrap())?;
+        assert!(matches!(
+            &provider,
+            ProviderSettings::Github { api_url: Some(url), gh_host: Some(gh_host), .. }
+                if url == "https://api.github.acme.com/graphql" && gh_host == "acme.ghe.com"
+        ));
+        assert_eq!(Some("api.github.acme.com"), provider.github_cache_host());
+
+        let provider = provider.with_github_api_url(Some("http://localhost:8080/graphql"));
+        assert!(matches!(
+            &provider,
+            ProviderSettings::Github { api_url: Some(url), .. }
+                if url == "http://localhost:8080/graphql"
+        ));
+        assert_eq!(Some("localhost"), provider.github_cache_host());
+
+        assert_eq!(None, ProviderSettings::default().github_cache_host());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_profiles() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+profile "work" {
+    provider "github" {
+        host "github.acme.com"
+        token "secret"
+    }
+    queue {
+        org "acme"
+    }
+}
+profile "oss"
+"#
+        .parse()?;
+        let config = Config {
+            queue: QueueSettings {
+                org: Some("kjuulh".into()),
+                ..Default::default()
+            },
+            profiles: doc
+                .nodes()
+                .iter()
+                .map(Profile::from_kdl)
+                .collect::<anyhow::Result<_>>()?,
+            ..Default::default()
+        };
+
+        let work = config.clone().with_profile(Some("work"))?;
+        assert_eq!(Some("github.acme.com"), work.provider.github_host());
+        assert_eq!(Some("acme".to_string()), work.queue.org);
+        assert!(!format!("{:?}", work.profiles).contains("secret"));
+
+        let oss = config.clone().with_profile(Some("oss"))?;
+        assert_eq!(Some("github.com"), oss.provider.github_host());
+        assert_eq!(Some("kjuulh".to_string()), oss.queue.org);
+
+        let error = config.with_profile(Some("home")).unwrap_err();
+        assert!(error.to_string().contains("expected one of work, oss"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_list_columns() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+list {
+    column "repo" width=20
+    column "title"
+    column "checks" width=10
+    column "author"
+    density "detailed"
+    relative-numbers true
+}
+"#
+        .parse()?;
+
+        let list = ListSettings::from_kdl(doc.get("list").unwrap().children().unwrap())?;
+
+        assert_eq!(
+            ListColumn {
+                column: Column::Repository,
+                width: Some(20),
+            },
+            list.columns[0]
+        );
+        assert_eq!(vec![20, 35, 10, 35], list.widths());
+        assert_eq!(Density::Detailed, list.density);
+        assert!(list.relative_numbers);
+
+        let doc: KdlDocument = r#"list { column "reviewers"; }"#.parse()?;
+        assert!(ListSettings::from_kdl(doc.get("list").unwrap().children().unwrap()).is_err());
+
+        Ok(())
+    }
+}
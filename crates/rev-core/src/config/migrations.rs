@@ -0,0 +1,127 @@
+use anyhow::Context;
+use kdl::{KdlDocument, KdlNode};
+
+/// The layout of rev.kdl this version of rev reads, written at the top as `version 1`.
+pub const CONFIG_VERSION: i64 = 1;
+
+/// Upgrades rev.kdl one version at a time, the first entry upgrades from version 0, i.e. a config
+/// without a version, to version 1.
+const MIGRATIONS: &[fn(&mut KdlDocument) -> anyhow::Result<()>] = &[queue_block];
+
+/// The version of the layout of the document, configs from before versioning are version 0.
+pub fn version(doc: &KdlDocument) -> anyhow::Result<i64> {
+    let Some(version) = doc.get("version") else {
+        return Ok(0);
+    };
+
+    version
+        .entries()
+        .first()
+        .and_then(|e| e.value().as_i64())
+        .filter(|v| *v >= 0)
+        .context("version must be a number, e.g. version 1")
+}
+
+/// Upgrades the document to [`CONFIG_VERSION`], returning the version it was at when it had to be
+/// upgraded.
+pub fn migrate(doc: &mut KdlDocument) -> anyhow::Result<Option<i64>> {
+    let from = version(doc)?;
+    if from > CONFIG_VERSION {
+        anyhow::bail!(
+            "rev.kdl is version {from}, but this rev only reads up to version {CONFIG_VERSION}, try upgrading rev"
+        );
+    }
+    if from == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(doc)?;
+    }
+    set_version(doc, CONFIG_VERSION);
+
+    Ok(Some(from))
+}
+
+fn set_version(doc: &mut KdlDocument, version: i64) {
+    if let Some(node) = doc.get_mut("version") {
+        node.clear_entries();
+        node.push(version);
+        return;
+    }
+
+    let mut node = KdlNode::new("version");
+    node.push(version);
+    node.set_trailing("\n");
+    doc.nodes_mut().insert(0, node);
+}
+
+/// Version 1 moved `scopes` and `out-of-scope` from the top level into the `queue {}` block.
+fn queue_block(doc: &mut KdlDocument) -> anyhow::Result<()> {
+    move_into_block(doc, "queue", &["scopes", "out-of-scope"])
+}
+
+/// Moves top level nodes into the block, creating the block if needed. Nodes already set in the
+/// block win over the moved ones.
+fn move_into_block(doc: &mut KdlDocument, block: &str, names: &[&str]) -> anyhow::Result<()> {
+    let (moved, kept) = std::mem::take(doc.nodes_mut())
+        .into_iter()
+        .partition::<Vec<_>, _>(|n| names.contains(&n.name().value()));
+    *doc.nodes_mut() = kept;
+    if moved.is_empty() {
+        return Ok(());
+    }
+
+    if doc.get(block).is_none() {
+        let mut node = KdlNode::new(block);
+        node.set_trailing("\n");
+        doc.nodes_mut().push(node);
+    }
+    let node = doc.get_mut(block).context("block to exist")?;
+    let children = node.ensure_children();
+    for mut moved in moved {
+        if children.get(moved.name().value()).is_none() {
+            moved.clear_fmt();
+            children.nodes_mut().push(moved);
+        }
+    }
+    node.fmt();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use kdl::KdlDocument;
+
+    use super::{migrate, version, CONFIG_VERSION};
+
+    #[test]
+    fn test_migrates_unversioned_config() -> anyhow::Result<()> {
+        let mut doc: KdlDocument = r#"config {
+    committer "kjuulh"
+}
+scopes "services/payments/**"
+out-of-scope "hide"
+"#
+        .parse()?;
+
+        assert_eq!(Some(0), migrate(&mut doc)?);
+        assert_eq!(CONFIG_VERSION, version(&doc)?);
+
+        // The migrated document is written back, so it has to read as the new layout
+        let doc: KdlDocument = doc.to_string().parse()?;
+        assert!(doc.get("scopes").is_none());
+        let queue = doc.get("queue").unwrap().children().unwrap();
+        assert!(queue.get("scopes").is_some());
+        assert!(queue.get("out-of-scope").is_some());
+
+        let mut doc = doc;
+        assert_eq!(None, migrate(&mut doc)?);
+
+        let mut newer: KdlDocument = "version 99".parse()?;
+        assert!(migrate(&mut newer).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use rev_git_provider::models::StatusCheck;
+
+/// Coverage as reported by a coverage service through a status on the review.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coverage {
+    pub kind: CoverageKind,
+    pub percent: f64,
+    pub target: Option<f64>,
+    /// The status the coverage was read from, e.g. `codecov/patch`.
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageKind {
+    /// Coverage of the changed lines only.
+    Patch,
+    Project,
+}
+
+impl Coverage {
+    pub fn below_target(&self) -> bool {
+        self.target.map(|t| self.percent < t).unwrap_or_default()
+    }
+}
+
+/// Reads the coverage from the statuses posted by Codecov or Coveralls, preferring patch coverage.
+pub fn coverage(checks: &[StatusCheck]) -> Option<Coverage> {
+    let mut found = checks.iter().filter_map(|check| {
+        let StatusCheck::StatusContext {
+            context,
+            description: Some(description),
+            ..
+        } = check
+        else {
+            return None;
+        };
+        let name = context.to_lowercase();
+
+        let (kind, percent, target) = if name.starts_with("codecov/patch") {
+            (
+                CoverageKind::Patch,
+                percent(description)?,
+                description
+                    .split_once("target")
+                    .and_then(|(_, t)| percent(t)),
+            )
+        } else if name.starts_with("codecov/project") {
+            (
+                CoverageKind::Project,
+                percent(description)?,
+                description
+                    .split_once("target")
+                    .and_then(|(_, t)| percent(t)),
+            )
+        } else if name.contains("coveralls") {
+            // e.g. "Coverage increased (+0.02%) to 87.316%"
+            let (_, total) = description.rsplit_once(" to ")?;
+            (CoverageKind::Project, percent(total)?, None)
+        } else {
+            return None;
+        };
+
+        Some(Coverage {
+            kind,
+            percent,
+            target,
+            source: context.clone(),
+        })
+    });
+
+    let first = found.next()?;
+    if first.kind == CoverageKind::Patch {
+        return Some(first);
+    }
+
+    Some(
+        found
+            .find(|c| c.kind == CoverageKind::Patch)
+            .unwrap_or(first),
+    )
+}
+
+/// The first percentage in the text, e.g. `91.66` in `91.66% of diff hit`.
+fn percent(text: &str) -> Option<f64> {
+    let (number, _) = text.split_once('%')?;
+    let start = number
+        .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    number[start..].parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{CurrentState, StatusCheck};
+
+    use super::{coverage, CoverageKind};
+
+    fn status(context: &str, description: &str) -> StatusCheck {
+        StatusCheck::StatusContext {
+            id: context.into(),
+            state: "success".into(),
+            description: Some(description.into()),
+            context: context.into(),
+            current: CurrentState::Success,
+            url: None,
+            app: "codecov".into(),
+        }
+    }
+
+    #[test]
+    fn test_reads_patch_coverage() {
+        let checks = vec![
+            status("codecov/project", "85.20% (+0.10%) compared to 1a2b3c4"),
+            status("codecov/patch", "71.42% of diff hit (target 80.00%)"),
+        ];
+
+        let found = coverage(&checks).unwrap();
+
+        assert_eq!(CoverageKind::Patch, found.kind);
+        assert_eq!(71.42, found.percent);
+        assert_eq!(Some(80.0), found.target);
+        assert!(found.below_target());
+    }
+
+    #[test]
+    fn test_reads_coveralls() {
+        let checks = vec![status(
+            "coverage/coveralls",
+            "Coverage increased (+0.02%) to 87.316%",
+        )];
+
+        let found = coverage(&checks).unwrap();
+
+        assert_eq!(CoverageKind::Project, found.kind);
+        assert_eq!(87.316, found.percent);
+        assert_eq!(None, coverage(&[status("ci/build", "passed 100%")]));
+    }
+}
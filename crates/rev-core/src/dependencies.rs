@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+
+use rev_git_provider::models::{Ecosystem, Vulnerability};
+
+/// A dependency bump as opened by Dependabot or Renovate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyUpdate {
+    pub package: String,
+    /// Renovate leaves the old version out of the title, so it's read from the body when missing.
+    pub from: Option<String>,
+    pub to: String,
+}
+
+impl DependencyUpdate {
+    /// Parses titles such as `Bump serde from 1.0.188 to 1.0.190` or
+    /// `Update dependency lodash to v4.17.21`, reading the old version from a
+    /// `4.17.20 -> 4.17.21` table in the body when the title doesn't have it.
+    pub fn parse(title: &str, body: &str) -> Option<Self> {
+        let words = title.split_whitespace().collect::<Vec<_>>();
+        let lower = title.to_lowercase();
+        if !lower.contains("bump") && !lower.contains("update") {
+            return None;
+        }
+
+        let to_index = words.iter().rposition(|w| w.eq_ignore_ascii_case("to"))?;
+        let to = version(words.get(to_index + 1)?)?;
+
+        let from_index = words
+            .iter()
+            .take(to_index)
+            .rposition(|w| w.eq_ignore_ascii_case("from"));
+        let (package, from) = match from_index {
+            Some(i) if i + 2 == to_index => (*words.get(i.checked_sub(1)?)?, version(words[i + 1])),
+            _ => (*words.get(to_index.checked_sub(1)?)?, None),
+        };
+        // Grouped updates, e.g. `Bump the npm group with 3 updates`, aren't a single package
+        if package.eq_ignore_ascii_case("the") || package.eq_ignore_ascii_case("dependencies") {
+            return None;
+        }
+
+        Some(Self {
+            package: package.trim_matches(['`', '"', '\'']).to_string(),
+            from: from.or_else(|| previous_version(body, &to)),
+            to,
+        })
+    }
+
+    /// The vulnerabilities affecting the old version which the new version no longer has.
+    pub fn fixes(&self, vulnerabilities: &[Vulnerability]) -> Vec<Vulnerability> {
+        let Some(from) = self.from.as_ref() else {
+            return Vec::new();
+        };
+
+        vulnerabilities
+            .iter()
+            .filter(|v| {
+                in_range(from, &v.vulnerable_range) && !in_range(&self.to, &v.vulnerable_range)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Guesses the ecosystem from the changed files, falling back on hints in the title.
+pub fn ecosystem(title: &str, files: &[String]) -> Option<Ecosystem> {
+    let by_file = files.iter().find_map(|f| {
+        let name = f.rsplit('/').next().unwrap_or(f);
+        match name {
+            "Cargo.toml" | "Cargo.lock" => Some(Ecosystem::Rust),
+            "package.json" | "package-lock.json" | "yarn.lock" | "pnpm-lock.yaml" => {
+                Some(Ecosystem::Npm)
+            }
+            "go.mod" | "go.sum" => Some(Ecosystem::Go),
+            "requirements.txt" | "Pipfile.lock" | "poetry.lock" | "pyproject.toml" => {
+                Some(Ecosystem::Pip)
+            }
+            "Gemfile" | "Gemfile.lock" => Some(Ecosystem::Rubygems),
+            "pom.xml" | "build.gradle" | "build.gradle.kts" => Some(Ecosystem::Maven),
+            "composer.json" | "composer.lock" => Some(Ecosystem::Composer),
+            "mix.exs" | "mix.lock" => Some(Ecosystem::Erlang),
+            "pubspec.yaml" | "pubspec.lock" => Some(Ecosystem::Pub),
+            "Package.swift" | "Package.resolved" => Some(Ecosystem::Swift),
+            "packages.config" | "Directory.Packages.props" => Some(Ecosystem::Nuget),
+            _ if name.ends_with(".csproj") => Some(Ecosystem::Nuget),
+            _ if f.starts_with(".github/workflows/") => Some(Ecosystem::Actions),
+            _ => None,
+        }
+    });
+
+    let title = title.to_lowercase();
+    by_file.or(if title.contains("rust crate") {
+        Some(Ecosystem::Rust)
+    } else if title.contains("npm") {
+        Some(Ecosystem::Npm)
+    } else if title.contains("action") {
+        Some(Ecosystem::Actions)
+    } else {
+        None
+    })
+}
+
+/// Reads a version, e.g. `v1.2.3` or `1.2.3,`, requiring it to start with a digit.
+fn version(raw: &str) -> Option<String> {
+    let version = raw
+        .trim_matches(['`', '"', '\'', ',', '.', ')', '('])
+        .trim_start_matches('v');
+
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| version.to_string())
+}
+
+/// Finds `<old> -> <to>` in the body, the form of the Renovate update table.
+fn previous_version(body: &str, to: &str) -> Option<String> {
+    body.lines().find_map(|line| {
+        let (before, after) = line.split_once("->").or_else(|| line.split_once('→'))?;
+        let new = version(after.split_whitespace().next()?)?;
+        if new != to {
+            return None;
+        }
+
+        version(before.split_whitespace().last()?)
+    })
+}
+
+/// Compares versions component by component, only looking at the leading number of each.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| {
+        v.trim_start_matches('v')
+            .split(['.', '-', '+'])
+            .map(|p| {
+                p.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse::<u64>()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>()
+    };
+    let (a, b) = (parts(a), parts(b));
+
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Whether the version is in a range of the advisory database, e.g. `>= 4.3.0, < 4.3.5`.
+fn in_range(version: &str, range: &str) -> bool {
+    range.split(',').all(|bound| {
+        let bound = bound.trim();
+        let (op, other) = ["<=", ">=", "<", ">", "="]
+            .iter()
+            .find_map(|op| bound.strip_prefix(op).map(|v| (*op, v.trim())))
+            .unwrap_or(("=", bound));
+        let ordering = compare_versions(version, other);
+
+        match op {
+            "<=" => ordering.is_le(),
+            ">=" => ordering.is_ge(),
+            "<" => ordering.is_lt(),
+            ">" => ordering.is_gt(),
+            _ => ordering.is_eq(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{Ecosystem, Vulnerability};
+
+    use super::{ecosystem, in_range, DependencyUpdate};
+
+    #[test]
+    fn test_parse_dependency_updates() {
+        assert_eq!(
+            Some(DependencyUpdate {
+                package: "lodash".into(),
+                from: Some("4.17.15".into()),
+                to: "4.17.21".into(),
+            }),
+            DependencyUpdate::parse("chore(deps): Bump lodash from 4.17.15 to 4.17.21", "")
+        );
+        assert_eq!(
+            Some(DependencyUpdate {
+                package: "serde".into(),
+                from: Some("1.0.188".into()),
+                to: "1.0.190".into(),
+            }),
+            DependencyUpdate::parse(
+                "fix(deps): update rust crate serde to v1.0.190",
+                "| serde | dependencies | patch | `1.0.188` -> `1.0.190` |"
+            )
+        );
+        assert_eq!(
+            None,
+            DependencyUpdate::parse("Bump the npm group with 3 updates", "")
+        );
+        assert_eq!(None, DependencyUpdate::parse("Add a history page", ""));
+        assert_eq!(
+            Some(Ecosystem::Rust),
+            ecosystem("", &["crates/rev/Cargo.toml".into()])
+        );
+    }
+
+    #[test]
+    fn test_fixes_vulnerabilities() {
+        assert!(in_range("4.3.2", ">= 4.3.0, < 4.3.5"));
+        assert!(!in_range("4.3.5", ">= 4.3.0, < 4.3.5"));
+        assert!(in_range("1.10.0", "<= 1.10.0"));
+        assert!(!in_range("0.2.1", "= 0.2.0"));
+
+        let vulnerability = Vulnerability {
+            ghsa_id: "GHSA-p6mc-m468-83gw".into(),
+            summary: "Prototype Pollution in lodash".into(),
+            severity: "high".into(),
+            vulnerable_range: "< 4.17.19".into(),
+            first_patched: Some("4.17.19".into()),
+        };
+        let update = DependencyUpdate::parse("Bump lodash from 4.17.15 to 4.17.21", "").unwrap();
+
+        assert_eq!(
+            vec![vulnerability.clone()],
+            update.fixes(std::slice::from_ref(&vulnerability))
+        );
+        let unrelated = DependencyUpdate::parse("Bump lodash from 4.17.19 to 4.17.21", "").unwrap();
+        assert!(unrelated.fixes(&[vulnerability]).is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+/// What's wrong with the description of a review, compared to the template of its repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptionLint {
+    pub empty: bool,
+    /// Headings of the template which aren't in the description.
+    pub missing: Vec<String>,
+}
+
+impl DescriptionLint {
+    /// Lints the description, which is the plain text of the review body, returning nothing when
+    /// it's fine.
+    pub fn lint(description: &str, template: Option<&str>) -> Option<Self> {
+        if description.trim().is_empty() {
+            return Some(Self {
+                empty: true,
+                missing: template.map(template_sections).unwrap_or_default(),
+            });
+        }
+
+        // The plain text drops the markdown, so a heading is left as a line of its own
+        let lines = description
+            .lines()
+            .map(|l| l.trim().to_lowercase())
+            .collect::<Vec<_>>();
+        let missing = template
+            .map(template_sections)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| !lines.iter().any(|l| l.starts_with(&s.to_lowercase())))
+            .collect::<Vec<_>>();
+
+        (!missing.is_empty()).then_some(Self {
+            empty: false,
+            missing,
+        })
+    }
+
+    /// The comment asking the author to fill in the description.
+    pub fn request(&self, author: &str) -> String {
+        if self.missing.is_empty() {
+            return format!("@{author} could you add a description to this pull request?");
+        }
+
+        let sections = self
+            .missing
+            .iter()
+            .map(|s| format!("- {s}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.empty {
+            format!(
+                "@{author} could you fill in the description using the pull request template?\n\n{sections}"
+            )
+        } else {
+            format!(
+                "@{author} the description is missing these sections of the pull request template, could you fill them in?\n\n{sections}"
+            )
+        }
+    }
+}
+
+/// The headings of a markdown template, leaving out those in html comments.
+fn template_sections(template: &str) -> Vec<String> {
+    let mut in_comment = false;
+    let mut sections = Vec::new();
+
+    for line in template.lines().map(str::trim) {
+        if in_comment {
+            in_comment = !line.contains("-->");
+            continue;
+        }
+        if line.starts_with("<!--") {
+            in_comment = !line.contains("-->");
+            continue;
+        }
+
+        if line.starts_with('#') {
+            let heading = line.trim_start_matches('#').trim();
+            if !heading.is_empty() {
+                sections.push(heading.to_string());
+            }
+        }
+    }
+
+    sections
+}
+
+#[cfg(test)]
+mod test {
+    use super::DescriptionLint;
+
+    const TEMPLATE: &str = "## Summary\n<!-- What does this change?\n## Not a section\n-->\n\n## Testing\n- [ ] tests added\n";
+
+    #[test]
+    fn test_lint_description() {
+        assert_eq!(
+            Some(DescriptionLint {
+                empty: false,
+                missing: vec!["Testing".into()],
+            }),
+            DescriptionLint::lint("Summary\nAdds a history page", Some(TEMPLATE))
+        );
+        assert_eq!(
+            None,
+            DescriptionLint::lint(
+                "summary\nAdds a history page\ntesting\nran it",
+                Some(TEMPLATE)
+            )
+        );
+        assert_eq!(None, DescriptionLint::lint("Adds a history page", None));
+
+        let empty = DescriptionLint::lint(" ", Some(TEMPLATE)).unwrap();
+        assert!(empty.empty);
+        assert_eq!(vec!["Summary".to_string(), "Testing".into()], empty.missing);
+        assert!(empty.request("kjuulh").starts_with("@kjuulh"));
+    }
+}
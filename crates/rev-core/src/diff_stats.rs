@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use crate::patch::FileDiff;
+
+/// The lines changed in a group of files, such as a directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    pub name: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub files: usize,
+}
+
+impl DiffStat {
+    pub fn lines(&self) -> usize {
+        self.additions + self.deletions
+    }
+}
+
+/// The shape of a diff, the lines changed per top-level directory and per file type, largest
+/// first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub directories: Vec<DiffStat>,
+    pub file_types: Vec<DiffStat>,
+}
+
+impl DiffStats {
+    pub fn new(files: &[FileDiff]) -> Self {
+        let mut directories = BTreeMap::new();
+        let mut file_types = BTreeMap::new();
+
+        for file in files {
+            for (groups, name) in [
+                (&mut directories, directory(&file.path)),
+                (&mut file_types, file_type(&file.path)),
+            ] {
+                let stat = groups.entry(name.clone()).or_insert_with(|| DiffStat {
+                    name,
+                    ..Default::default()
+                });
+                stat.additions += file.additions();
+                stat.deletions += file.deletions();
+                stat.files += 1;
+            }
+        }
+
+        Self {
+            directories: largest_first(directories),
+            file_types: largest_first(file_types),
+        }
+    }
+}
+
+fn largest_first(groups: BTreeMap<String, DiffStat>) -> Vec<DiffStat> {
+    let mut stats = groups.into_values().collect::<Vec<_>>();
+    // Stable, so groups of the same size stay sorted by name
+    stats.sort_by_key(|s| std::cmp::Reverse(s.lines()));
+    stats
+}
+
+/// The top-level directory of the path, files at the root are grouped as `.`.
+fn directory(path: &str) -> String {
+    match path.split_once('/') {
+        Some((directory, _)) => format!("{directory}/"),
+        None => ".".into(),
+    }
+}
+
+/// The extension of the file, or its name for files without one such as `Makefile`.
+fn file_type(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => format!(".{extension}"),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DiffStat, DiffStats};
+    use crate::patch::{DiffLine, FileDiff};
+
+    fn file(path: &str, additions: usize, deletions: usize) -> FileDiff {
+        FileDiff {
+            path: path.into(),
+            old_path: None,
+            lines: std::iter::repeat_n(DiffLine::Added("+".into()), additions)
+                .chain(std::iter::repeat_n(
+                    DiffLine::Removed("-".into()),
+                    deletions,
+                ))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_rolls_up_directories_and_file_types() {
+        let stats = DiffStats::new(&[
+            file("crates/rev/src/main.rs", 10, 2),
+            file("crates/rev-core/src/app.rs", 5, 5),
+            file("docs/config.md", 30, 0),
+            file("Makefile", 1, 1),
+            file(".gitignore", 1, 0),
+        ]);
+
+        let stat = |name: &str, additions, deletions, files| DiffStat {
+            name: name.into(),
+            additions,
+            deletions,
+            files,
+        };
+        assert_eq!(
+            vec![
+                stat("docs/", 30, 0, 1),
+                stat("crates/", 15, 7, 2),
+                stat(".", 2, 1, 2),
+            ],
+            stats.directories
+        );
+        assert_eq!(
+            vec![
+                stat(".md", 30, 0, 1),
+                stat(".rs", 15, 7, 2),
+                stat("Makefile", 1, 1, 1),
+                stat(".gitignore", 1, 0, 1),
+            ],
+            stats.file_types
+        );
+    }
+}
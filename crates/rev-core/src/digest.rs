@@ -0,0 +1,190 @@
+use std::io::Write;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use rev_git_provider::{models::ReviewListItem, GitProvider};
+use serde::{Deserialize, Serialize};
+use timeago::Formatter;
+
+use crate::{
+    git_pull_requests::search_all,
+    i18n::{tr, Text},
+    platform,
+    store::Store,
+};
+
+const HISTORY_DOCUMENT: &str = "digest";
+
+/// What the previous digest contained, so the next one can tell what arrived since.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct DigestHistory {
+    pub last_run: Option<DateTime<Utc>>,
+    pub seen: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub generated_at: DateTime<Utc>,
+    pub new_requests: Vec<ReviewListItem>,
+    pub waiting_longest: Vec<ReviewListItem>,
+    pub my_pull_requests: Vec<ReviewListItem>,
+}
+
+impl Digest {
+    pub fn build(
+        requests: Vec<ReviewListItem>,
+        authored: Vec<ReviewListItem>,
+        history: &DigestHistory,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Self {
+        // Without history we don't know what is new, so we consider the last day instead
+        let new_requests = requests
+            .iter()
+            .filter(|r| match history.last_run {
+                Some(_) => !history.seen.contains(&r.id),
+                None => r.date > now - Duration::days(1),
+            })
+            .cloned()
+            .collect();
+
+        let mut waiting_longest = requests;
+        waiting_longest.sort_by_key(|r| r.date);
+        waiting_longest.truncate(limit);
+
+        Self {
+            generated_at: now,
+            new_requests,
+            waiting_longest,
+            my_pull_requests: authored,
+        }
+    }
+
+    pub fn history(&self, requests: &[ReviewListItem]) -> DigestHistory {
+        DigestHistory {
+            last_run: Some(self.generated_at),
+            seen: requests.iter().map(|r| r.id.clone()).collect(),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let formatter = Formatter::default();
+        let mut out = format!("rev digest - {}\n", self.generated_at.format("%Y-%m-%d"));
+
+        let sections = [
+            (Text::DigestNewRequests, &self.new_requests),
+            (Text::DigestWaitingLongest, &self.waiting_longest),
+            (Text::DigestMyPullRequests, &self.my_pull_requests),
+        ];
+
+        for (title, items) in sections {
+            out.push_str(&format!("\n{} ({})\n", tr(title), items.len()));
+            if items.is_empty() {
+                out.push_str(&format!("  {}\n", tr(Text::DigestNothing)));
+            }
+
+            for item in items {
+                out.push_str(&format!(
+                    "  {}/{}#{} {} ({})\n",
+                    item.owner,
+                    item.name,
+                    item.number,
+                    item.title,
+                    formatter.convert_chrono(item.date, self.generated_at)
+                ));
+            }
+        }
+
+        out
+    }
+}
+
+pub async fn run(provider: GitProvider, store: Store, limit: usize) -> anyhow::Result<Digest> {
+    let history: DigestHistory = store.load(HISTORY_DOCUMENT)?;
+
+    let requests = search_all(&provider, "is:pr review-requested:@me state:open").await?;
+    let authored = search_all(&provider, "is:pr author:@me state:open review:required").await?;
+
+    let digest = Digest::build(requests.clone(), authored, &history, Utc::now(), limit);
+    store.save(HISTORY_DOCUMENT, &digest.history(&requests))?;
+
+    Ok(digest)
+}
+
+/// Pipes the rendered digest into a user supplied command, such as a mail or slack cli.
+pub fn send_to_hook(hook: &str, digest: &str) -> anyhow::Result<()> {
+    let mut child = platform::shell_process(hook)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to start digest hook: {hook}"))?;
+
+    child
+        .stdin
+        .take()
+        .context("hook stdin to be available")?
+        .write_all(digest.as_bytes())?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("digest hook exited with: {status}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use rev_git_provider::models::ReviewListItem;
+
+    use super::{Digest, DigestHistory};
+
+    fn item(id: &str, age_hours: i64) -> ReviewListItem {
+        ReviewListItem {
+            id: id.into(),
+            name: "rev".into(),
+            title: format!("title {id}"),
+            owner: "kjuulh".into(),
+            date: Utc::now() - Duration::hours(age_hours),
+            number: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_requests_without_history_uses_last_day() {
+        let digest = Digest::build(
+            vec![item("old", 72), item("new", 2)],
+            Vec::new(),
+            &DigestHistory::default(),
+            Utc::now(),
+            5,
+        );
+
+        assert_eq!(vec!["new"], ids(&digest.new_requests));
+        assert_eq!(vec!["old", "new"], ids(&digest.waiting_longest));
+    }
+
+    #[test]
+    fn test_new_requests_uses_seen_history() {
+        let history = DigestHistory {
+            last_run: Some(Utc::now() - Duration::hours(12)),
+            seen: vec!["new".into()],
+        };
+        let digest = Digest::build(
+            vec![item("old", 72), item("new", 2)],
+            Vec::new(),
+            &history,
+            Utc::now(),
+            1,
+        );
+
+        assert_eq!(vec!["old"], ids(&digest.new_requests));
+        assert_eq!(vec!["old"], ids(&digest.waiting_longest));
+        assert!(digest.render().contains("kjuulh/rev#1 title old"));
+    }
+
+    fn ids(items: &[ReviewListItem]) -> Vec<&str> {
+        items.iter().map(|i| i.id.as_str()).collect()
+    }
+}
@@ -0,0 +1,253 @@
+use std::path::Path;
+
+use rev_git_provider::{auth, github::token_source, models::TokenStatus};
+
+use crate::{
+    config::{Config, ProviderSettings},
+    i18n::{tr, Text},
+    platform,
+    terminal::{Capabilities, ColorDepth, Symbols},
+};
+
+/// The outcome of a single check of `rev doctor`.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: Text,
+    pub passed: bool,
+    pub detail: String,
+    /// How to fix the check when it failed.
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: Text, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: true,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn fail(name: Text, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            passed: false,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    pub fn render(&self, symbols: &Symbols) -> String {
+        let symbol = if self.passed {
+            symbols.success
+        } else {
+            symbols.failure
+        };
+        let mut line = format!("{symbol} {}: {}", tr(self.name), self.detail);
+        if let Some(hint) = self.hint.as_ref() {
+            line.push_str(&format!("\n    {}: {hint}", tr(Text::DoctorHint)));
+        }
+
+        line
+    }
+}
+
+/// Checks that rev can authenticate with github, read its config and write its data, each check
+/// running regardless of whether the ones before it passed. The provider is the one of the
+/// profile when given, reached at the github api url when given.
+pub async fn run(
+    config_file: &Path,
+    profile: Option<&str>,
+    github_api_url: Option<&str>,
+) -> Vec<Check> {
+    let provider = Config::load(config_file)
+        .and_then(|c| c.with_profile(profile))
+        .map(|c| c.with_github_api_url(github_api_url).provider)
+        .unwrap_or_default();
+    let mut checks = vec![check_gh(), check_token(&provider)];
+    checks.extend(check_api(&provider).await);
+    checks.push(check_terminal());
+    checks.push(check_config(config_file));
+    checks.push(check_data_dir(&platform::data_dir()));
+
+    checks
+}
+
+fn check_gh() -> Check {
+    match std::process::Command::new("gh").arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            Check::pass(
+                Text::DoctorGh,
+                version.lines().next().unwrap_or_default().to_string(),
+            )
+        }
+        _ => Check::fail(Text::DoctorGh, "-", tr(Text::DoctorInstallGh)),
+    }
+}
+
+fn check_token(provider: &ProviderSettings) -> Check {
+    match provider.github_options() {
+        Ok(Some(options)) => match token_source(&options) {
+            Some(source) => Check::pass(Text::DoctorToken, source.name()),
+            None => Check::fail(Text::DoctorToken, "-", tr(Text::DoctorLogin)),
+        },
+        // The other providers are checked by reaching them
+        Ok(None) => Check::pass(Text::DoctorToken, "-"),
+        Err(e) => Check::fail(Text::DoctorToken, format!("{e:#}"), tr(Text::DoctorLogin)),
+    }
+}
+
+/// Whether the provider can be reached, the token is accepted and it has the scopes rev needs.
+async fn check_api(provider: &ProviderSettings) -> Vec<Check> {
+    let status = match provider.connect() {
+        Ok(provider) => provider.get_token_status().await,
+        Err(e) => {
+            return vec![Check::fail(
+                Text::DoctorApi,
+                format!("{e:#}"),
+                tr(Text::DoctorLogin),
+            )]
+        }
+    };
+
+    match status {
+        Ok(TokenStatus::Valid { login, scopes }) => vec![
+            Check::pass(Text::DoctorApi, login),
+            check_scopes(scopes.as_deref()),
+        ],
+        Ok(TokenStatus::Rejected) => vec![Check::fail(
+            Text::DoctorApi,
+            tr(Text::DoctorTokenRejected),
+            tr(Text::DoctorRefreshToken),
+        )],
+        Err(e) => vec![Check::fail(
+            Text::DoctorApi,
+            format!("{e:#}"),
+            tr(Text::DoctorCheckNetwork),
+        )],
+    }
+}
+
+fn check_scopes(scopes: Option<&[String]>) -> Check {
+    let Some(scopes) = scopes else {
+        return Check::pass(Text::DoctorScopes, tr(Text::DoctorFineGrainedToken));
+    };
+
+    let missing = missing_scopes(scopes);
+    if missing.is_empty() {
+        return Check::pass(Text::DoctorScopes, scopes.join(", "));
+    }
+
+    Check::fail(
+        Text::DoctorScopes,
+        format!("{}: {}", tr(Text::DoctorMissingScopes), missing.join(", ")),
+        format!("gh auth refresh -s {}", missing.join(",")),
+    )
+}
+
+/// The required scopes the token lacks, a broader scope such as `admin:org` covers `read:org`.
+fn missing_scopes(scopes: &[String]) -> Vec<&'static str> {
+    auth::SCOPES
+        .iter()
+        .filter(|required| {
+            let covering = match required.split_once(':') {
+                Some((_, resource)) => vec![
+                    required.to_string(),
+                    format!("write:{resource}"),
+                    format!("admin:{resource}"),
+                ],
+                None => vec![required.to_string()],
+            };
+            !scopes.iter().any(|s| covering.contains(s))
+        })
+        .copied()
+        .collect()
+}
+
+fn check_terminal() -> Check {
+    let capabilities = Capabilities::detect();
+    let colors = match capabilities.colors {
+        ColorDepth::TrueColor => "truecolor",
+        ColorDepth::Ansi256 => "256 colors",
+        ColorDepth::Ansi16 => "16 colors",
+    };
+    let flag = |name: &str, on: bool| format!("{name} {}", if on { "on" } else { "off" });
+
+    Check::pass(
+        Text::DoctorTerminal,
+        [
+            colors.to_string(),
+            flag("unicode", capabilities.unicode),
+            flag("hyperlinks", capabilities.hyperlinks),
+            format!("clipboard {:?}", capabilities.clipboard).to_lowercase(),
+            flag("tmux", capabilities.tmux),
+        ]
+        .join(", "),
+    )
+}
+
+fn check_config(config_file: &Path) -> Check {
+    if !config_file.exists() {
+        return Check::pass(
+            Text::DoctorConfig,
+            format!(
+                "{} {}",
+                config_file.display(),
+                tr(Text::DoctorUsingDefaults)
+            ),
+        );
+    }
+
+    match Config::load(config_file) {
+        Ok(_) => Check::pass(Text::DoctorConfig, config_file.display().to_string()),
+        Err(e) => Check::fail(
+            Text::DoctorConfig,
+            format!("{}: {e:#}", config_file.display()),
+            tr(Text::DoctorFixConfig),
+        ),
+    }
+}
+
+/// Whether local state such as the store and logs can be written to the directory.
+fn check_data_dir(dir: &Path) -> Check {
+    let probe = dir.join(".rev-doctor");
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&probe, b"rev"))
+        .and_then(|_| std::fs::remove_file(&probe));
+
+    match result {
+        Ok(()) => Check::pass(Text::DoctorDataDir, dir.display().to_string()),
+        Err(e) => Check::fail(
+            Text::DoctorDataDir,
+            format!("{}: {e}", dir.display()),
+            tr(Text::DoctorFixDataDir),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_data_dir, missing_scopes};
+
+    #[test]
+    fn test_missing_scopes() {
+        let scopes = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(missing_scopes(&scopes(&["repo", "read:org", "gist"])).is_empty());
+        assert!(missing_scopes(&scopes(&["repo", "admin:org"])).is_empty());
+        assert_eq!(vec!["read:org"], missing_scopes(&scopes(&["repo"])));
+        assert_eq!(vec!["repo", "read:org"], missing_scopes(&[]));
+    }
+
+    #[test]
+    fn test_data_dir_is_writable() {
+        let dir = std::env::temp_dir().join(format!("rev-doctor-{}", std::process::id()));
+
+        assert!(check_data_dir(&dir).passed);
+        assert!(!dir.join(".rev-doctor").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
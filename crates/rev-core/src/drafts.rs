@@ -0,0 +1,383 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::Context;
+use rev_git_provider::models::ReviewEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+const DRAFTS_DOCUMENT: &str = "drafts";
+
+/// The verdict a draft is submitted with.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    Approve,
+    RequestChanges,
+    Comment,
+}
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::RequestChanges => "request-changes",
+            Self::Comment => "comment",
+        }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim() {
+            "approve" => Some(Self::Approve),
+            "request-changes" => Some(Self::RequestChanges),
+            "comment" => Some(Self::Comment),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReviewEvent> for Verdict {
+    fn from(event: ReviewEvent) -> Self {
+        match event {
+            ReviewEvent::Approve => Self::Approve,
+            ReviewEvent::RequestChanges => Self::RequestChanges,
+            ReviewEvent::Comment => Self::Comment,
+        }
+    }
+}
+
+impl From<Verdict> for ReviewEvent {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Approve => Self::Approve,
+            Verdict::RequestChanges => Self::RequestChanges,
+            Verdict::Comment => Self::Comment,
+        }
+    }
+}
+
+/// A comment of a draft, on a line of a file or on the review as a whole.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DraftComment {
+    pub path: Option<String>,
+    pub line: Option<usize>,
+    pub body: String,
+}
+
+impl DraftComment {
+    fn location(&self) -> Option<String> {
+        let path = self.path.as_ref()?;
+        Some(match self.line {
+            Some(line) => format!("{path}:{line}"),
+            None => path.clone(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub text: String,
+    pub done: bool,
+}
+
+/// A review not yet submitted, kept so it can be finished later or on another machine.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReviewDraft {
+    /// The review in the form `owner/name#number`.
+    pub review: String,
+    pub verdict: Option<Verdict>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub comments: Vec<DraftComment>,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+}
+
+impl ReviewDraft {
+    pub fn new(review: impl Into<String>) -> Self {
+        Self {
+            review: review.into(),
+            verdict: None,
+            body: String::new(),
+            comments: Vec::new(),
+            checklist: Vec::new(),
+        }
+    }
+
+    /// The message the draft is submitted with, comments on files are quoted with their location
+    /// as the provider only takes a single body.
+    pub fn submission_body(&self) -> Option<String> {
+        let mut sections = Vec::new();
+        if !self.body.trim().is_empty() {
+            sections.push(self.body.trim().to_string());
+        }
+        for comment in self.comments.iter() {
+            match comment.location() {
+                Some(location) => {
+                    sections.push(format!("**{location}**\n\n{}", comment.body.trim()))
+                }
+                None => sections.push(comment.body.trim().to_string()),
+            }
+        }
+        if !self.checklist.is_empty() {
+            sections.push(
+                self.checklist
+                    .iter()
+                    .map(|i| format!("- [{}] {}", if i.done { "x" } else { " " }, i.text))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+
+        Some(sections.join("\n\n")).filter(|b| !b.is_empty())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# Review {}\n\n", self.review);
+        if let Some(verdict) = self.verdict {
+            md.push_str(&format!("Verdict: {}\n\n", verdict.name()));
+        }
+        if !self.body.trim().is_empty() {
+            md.push_str(&format!("{}\n\n", self.body.trim()));
+        }
+        if !self.checklist.is_empty() {
+            md.push_str("## Checklist\n\n");
+            for item in self.checklist.iter() {
+                let mark = if item.done { "x" } else { " " };
+                md.push_str(&format!("- [{mark}] {}\n", item.text));
+            }
+            md.push('\n');
+        }
+        if !self.comments.is_empty() {
+            md.push_str("## Comments\n\n");
+            for comment in self.comments.iter() {
+                let location = comment.location().unwrap_or_else(|| "General".into());
+                md.push_str(&format!("### {location}\n\n{}\n\n", comment.body.trim()));
+            }
+        }
+
+        format!("{}\n", md.trim_end())
+    }
+
+    /// Reads a draft written by [`ReviewDraft::to_markdown`], possibly edited by hand.
+    pub fn from_markdown(md: &str) -> anyhow::Result<Self> {
+        let mut lines = md.lines();
+        let review = lines
+            .by_ref()
+            .find(|l| !l.trim().is_empty())
+            .and_then(|l| l.strip_prefix("# Review "))
+            .context("draft must start with `# Review owner/name#number`")?;
+        let mut draft = Self::new(review.trim());
+
+        enum Section {
+            Body,
+            Checklist,
+            Comments,
+        }
+        let mut section = Section::Body;
+        let mut body = Vec::new();
+        let mut comment: Option<(Option<String>, Vec<&str>)> = None;
+        let mut comments = Vec::new();
+        let mut finish_comment = |comment: &mut Option<(Option<String>, Vec<&str>)>| {
+            if let Some((location, body)) = comment.take() {
+                let (path, line) = match location.as_deref().map(|l| l.rsplit_once(':')) {
+                    Some(Some((path, line))) if line.parse::<usize>().is_ok() => {
+                        (Some(path.to_string()), line.parse().ok())
+                    }
+                    Some(_) => (location, None),
+                    None => (None, None),
+                };
+                comments.push(DraftComment {
+                    path,
+                    line,
+                    body: body.join("\n").trim().to_string(),
+                });
+            }
+        };
+
+        let mut checklist = Vec::new();
+        for line in lines {
+            match line.trim_end() {
+                "## Checklist" => section = Section::Checklist,
+                "## Comments" => section = Section::Comments,
+                l if matches!(section, Section::Comments) && l.starts_with("### ") => {
+                    finish_comment(&mut comment);
+                    let location = l.trim_start_matches("### ").trim();
+                    let location = (location != "General").then(|| location.to_string());
+                    comment = Some((location, Vec::new()));
+                }
+                l => match section {
+                    Section::Body => match l.strip_prefix("Verdict: ") {
+                        Some(verdict) if body.iter().all(|l: &&str| l.trim().is_empty()) => {
+                            draft.verdict = Some(
+                                Verdict::parse(verdict)
+                                    .with_context(|| format!("unknown verdict: {verdict}"))?,
+                            );
+                        }
+                        _ => body.push(line),
+                    },
+                    Section::Checklist => {
+                        let item = l.trim_start();
+                        if let Some(text) = item.strip_prefix("- [ ] ") {
+                            checklist.push(ChecklistItem {
+                                text: text.to_string(),
+                                done: false,
+                            });
+                        } else if let Some(text) = item
+                            .strip_prefix("- [x] ")
+                            .or_else(|| item.strip_prefix("- [X] "))
+                        {
+                            checklist.push(ChecklistItem {
+                                text: text.to_string(),
+                                done: true,
+                            });
+                        }
+                    }
+                    Section::Comments => {
+                        if let Some((_, body)) = comment.as_mut() {
+                            body.push(line);
+                        }
+                    }
+                },
+            }
+        }
+        finish_comment(&mut comment);
+
+        draft.body = body.join("\n").trim().to_string();
+        draft.comments = comments;
+        draft.checklist = checklist;
+
+        Ok(draft)
+    }
+
+    /// Writes the draft as markdown when the file ends in `.md`, as json otherwise.
+    pub fn export(&self, path: &Path) -> anyhow::Result<()> {
+        let content = if is_markdown(path) {
+            self.to_markdown()
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+
+        std::fs::write(path, content)
+            .with_context(|| format!("failed to write draft: {}", path.display()))
+    }
+
+    pub fn import(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read draft: {}", path.display()))?;
+
+        if is_markdown(path) {
+            Self::from_markdown(&content)
+        } else {
+            serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse draft: {}", path.display()))
+        }
+    }
+}
+
+fn is_markdown(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("md" | "markdown")
+    )
+}
+
+/// The drafts of this machine by review.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct Drafts {
+    pub drafts: BTreeMap<String, ReviewDraft>,
+}
+
+impl Drafts {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(DRAFTS_DOCUMENT)
+    }
+
+    pub fn get(store: &Store, review: &str) -> anyhow::Result<Option<ReviewDraft>> {
+        Ok(Self::load(store)?.drafts.remove(review))
+    }
+
+    /// Saves the draft, replacing any earlier draft of the review.
+    pub fn save(store: &Store, draft: ReviewDraft) -> anyhow::Result<()> {
+        let mut drafts = Self::load(store)?;
+        drafts.drafts.insert(draft.review.clone(), draft);
+
+        store.save(DRAFTS_DOCUMENT, &drafts)
+    }
+
+    pub fn remove(store: &Store, review: &str) -> anyhow::Result<Option<ReviewDraft>> {
+        let mut drafts = Self::load(store)?;
+        let removed = drafts.drafts.remove(review);
+        if removed.is_some() {
+            store.save(DRAFTS_DOCUMENT, &drafts)?;
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChecklistItem, DraftComment, ReviewDraft, Verdict};
+
+    fn draft() -> ReviewDraft {
+        ReviewDraft {
+            review: "kjuulh/rev#12".into(),
+            verdict: Some(Verdict::RequestChanges),
+            body: "Looks good, a few nits.\n\nSee below.".into(),
+            comments: vec![
+                DraftComment {
+                    path: Some("src/main.rs".into()),
+                    line: Some(42),
+                    body: "This can panic".into(),
+                },
+                DraftComment {
+                    path: None,
+                    line: None,
+                    body: "Please add a changelog entry".into(),
+                },
+            ],
+            checklist: vec![
+                ChecklistItem {
+                    text: "tests pass".into(),
+                    done: true,
+                },
+                ChecklistItem {
+                    text: "docs updated".into(),
+                    done: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_markdown_roundtrip() -> anyhow::Result<()> {
+        let draft = draft();
+
+        assert_eq!(draft, ReviewDraft::from_markdown(&draft.to_markdown())?);
+        assert!(ReviewDraft::from_markdown("no header").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_and_import() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("rev-drafts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let draft = draft();
+
+        for file in ["draft.json", "draft.md"] {
+            draft.export(&dir.join(file))?;
+            assert_eq!(draft, ReviewDraft::import(&dir.join(file))?);
+        }
+        std::fs::remove_dir_all(dir)?;
+
+        let body = draft.submission_body().unwrap();
+        assert!(body.starts_with("Looks good"));
+        assert!(body.contains("**src/main.rs:42**\n\nThis can panic"));
+        assert!(body.ends_with("- [x] tests pass\n- [ ] docs updated"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,360 @@
+use std::{collections::BTreeMap, io::Write};
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use kdl::{KdlDocument, KdlNode};
+use rev_git_provider::models::ReviewListItem;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::kdl_strings, glob, platform, store::Store};
+
+const DUE_DOCUMENT: &str = "due-dates";
+
+/// When reviews are due, the first rule matching a review makes it due the hours after it was
+/// opened. Due dates set with `rev due set` go before the rules.
+///
+/// The hook is given the due reviews as an ics calendar by `rev due calendar`, to keep the review
+/// commitments in a calendar.
+///
+/// ```kdl
+/// due {
+///     rule within-hours=4 {
+///         labels "hotfix"
+///     }
+///     rule within-hours=48 {
+///         repositories "acme/*"
+///     }
+///     hook "curl -s -T - https://dav.example.com/calendars/me/reviews.ics"
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DueSettings {
+    pub rules: Vec<DueRule>,
+    pub hook: Option<String>,
+}
+
+/// A rule without labels or repositories matches every review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueRule {
+    pub labels: Vec<String>,
+    /// Globs of which one must match the repository, in the form `owner/name`.
+    pub repositories: Vec<String>,
+    pub within_hours: i64,
+}
+
+impl DueSettings {
+    pub fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        Ok(Self {
+            rules: doc
+                .nodes()
+                .iter()
+                .filter(|n| n.name().value() == "rule")
+                .map(DueRule::from_kdl)
+                .collect::<anyhow::Result<_>>()?,
+            hook: doc
+                .get("hook")
+                .and_then(|n| n.entries().first())
+                .map(|h| {
+                    h.value()
+                        .as_string()
+                        .map(str::to_string)
+                        .context("due.hook must be a string")
+                })
+                .transpose()?,
+        })
+    }
+
+    /// When the review is due by the first rule matching it.
+    pub fn rule_due(&self, item: &ReviewListItem) -> Option<DateTime<Utc>> {
+        self.rules
+            .iter()
+            .find(|r| r.matches(item))
+            .map(|r| item.date + Duration::hours(r.within_hours))
+    }
+}
+
+impl DueRule {
+    fn from_kdl(node: &KdlNode) -> anyhow::Result<Self> {
+        let within_hours = node
+            .get("within-hours")
+            .and_then(|h| h.value().as_i64())
+            .filter(|h| *h > 0)
+            .context("due rules must have a positive within-hours, e.g. rule within-hours=24")?;
+        let empty = KdlDocument::new();
+        let doc = node.children().unwrap_or(&empty);
+
+        Ok(Self {
+            labels: kdl_strings(doc, "labels")?.unwrap_or_default(),
+            repositories: kdl_strings(doc, "repositories")?.unwrap_or_default(),
+            within_hours,
+        })
+    }
+
+    fn matches(&self, item: &ReviewListItem) -> bool {
+        let labels = self.labels.is_empty()
+            || item
+                .labels
+                .iter()
+                .any(|l| self.labels.iter().any(|r| r.eq_ignore_ascii_case(&l.name)));
+        let repository = format!("{}/{}", item.owner, item.name);
+        let repositories = self.repositories.is_empty()
+            || self
+                .repositories
+                .iter()
+                .any(|r| glob::matches(r, &repository));
+
+        labels && repositories
+    }
+}
+
+/// The due dates set by hand, by the review in the form `owner/name#number`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct DueDates {
+    pub reviews: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl DueDates {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(DUE_DOCUMENT)
+    }
+
+    pub fn set(store: &Store, reference: &str, due: DateTime<Utc>) -> anyhow::Result<()> {
+        let mut dates = Self::load(store)?;
+        dates.reviews.insert(reference.to_string(), due);
+        store.save(DUE_DOCUMENT, &dates)
+    }
+
+    /// Forgets the due date, returning whether there was one.
+    pub fn clear(store: &Store, reference: &str) -> anyhow::Result<bool> {
+        let mut dates = Self::load(store)?;
+        let cleared = dates.reviews.remove(reference).is_some();
+        store.save(DUE_DOCUMENT, &dates)?;
+
+        Ok(cleared)
+    }
+
+    /// When the review is due, set by hand or by the rules.
+    pub fn due(&self, settings: &DueSettings, item: &ReviewListItem) -> Option<DateTime<Utc>> {
+        self.reviews
+            .get(&reference(item))
+            .copied()
+            .or_else(|| settings.rule_due(item))
+    }
+}
+
+/// Loads the due dates to highlight the list with, leaving them out when they can't be read.
+pub fn load_or_warn(store: &Store) -> DueDates {
+    DueDates::load(store)
+        .context("failed to load due dates")
+        .unwrap_or_else(|e| {
+            tracing::warn!("{e:#}");
+            DueDates::default()
+        })
+}
+
+/// A review which is due, as put in the calendar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueReview {
+    /// The review in the form `owner/name#number`.
+    pub reference: String,
+    pub title: String,
+    pub url: String,
+    pub due: DateTime<Utc>,
+}
+
+/// The reviews which have a due date, soonest first.
+pub fn due_reviews(
+    items: &[ReviewListItem],
+    dates: &DueDates,
+    settings: &DueSettings,
+) -> Vec<DueReview> {
+    let mut reviews = items
+        .iter()
+        .filter_map(|item| {
+            Some(DueReview {
+                reference: reference(item),
+                title: item.title.clone(),
+                url: item.url.clone(),
+                due: dates.due(settings, item)?,
+            })
+        })
+        .collect::<Vec<_>>();
+    reviews.sort_by_key(|r| r.due);
+
+    reviews
+}
+
+/// Parses a due date in the form `2024-07-31`, due at the end of the day, or `2024-07-31 14:00`,
+/// both in the local time zone.
+pub fn parse_due(raw: &str) -> anyhow::Result<DateTime<Utc>> {
+    let local = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .map(|d| d.and_time(NaiveTime::from_hms_opt(23, 59, 0).expect("a valid time")))
+        })
+        .with_context(|| {
+            format!("{raw} isn't a date in the form 2024-07-31 or 2024-07-31 14:00")
+        })?;
+
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|d| d.with_timezone(&Utc))
+        .with_context(|| format!("{raw} doesn't exist in the local time zone"))
+}
+
+/// The due date in the local time zone, as printed by `rev due`.
+pub fn format_due(due: DateTime<Utc>) -> String {
+    due.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+/// Renders the reviews as an ics calendar as of now.
+pub fn calendar(reviews: &[DueReview]) -> String {
+    to_ics(reviews, Utc::now())
+}
+
+/// Renders the reviews as an ics calendar, each a half hour event ending when it's due.
+pub fn to_ics(reviews: &[DueReview], now: DateTime<Utc>) -> String {
+    let timestamp = |d: DateTime<Utc>| d.format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//rev//due reviews//EN".to_string(),
+    ];
+    for review in reviews {
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@rev", review.reference),
+            format!("DTSTAMP:{}", timestamp(now)),
+            format!("DTSTART:{}", timestamp(review.due - Duration::minutes(30))),
+            format!("DTEND:{}", timestamp(review.due)),
+            format!(
+                "SUMMARY:{}",
+                escape_text(&format!("Review {}: {}", review.reference, review.title))
+            ),
+        ]);
+        if !review.url.is_empty() {
+            lines.push(format!("URL:{}", review.url));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.into_iter().map(|l| l + "\r\n").collect()
+}
+
+/// Pipes the calendar into a user supplied command, such as a caldav upload.
+pub fn send_to_hook(hook: &str, calendar: &str) -> anyhow::Result<()> {
+    let mut child = platform::shell_process(hook)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to start calendar hook: {hook}"))?;
+
+    child
+        .stdin
+        .take()
+        .context("hook stdin to be available")?
+        .write_all(calendar.as_bytes())?;
+
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("calendar hook exited with: {status}");
+    }
+
+    Ok(())
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn reference(item: &ReviewListItem) -> String {
+    format!("{}/{}#{}", item.owner, item.name, item.number)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, TimeZone, Utc};
+    use kdl::KdlDocument;
+    use rev_git_provider::models::{Label, ReviewListItem};
+
+    use super::{due_reviews, to_ics, DueDates, DueSettings};
+    use crate::store::Store;
+
+    #[test]
+    fn test_due_dates_and_calendar() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+rule within-hours=4 {
+    labels "hotfix"
+}
+rule within-hours=48 {
+    repositories "kjuulh/*"
+}
+"#
+        .parse()?;
+        let settings = DueSettings::from_kdl(&doc)?;
+
+        let opened = Utc.with_ymd_and_hms(2024, 7, 1, 8, 0, 0).unwrap();
+        let item = |owner: &str, number, labels: &[&str]| ReviewListItem {
+            id: format!("PR_{number}"),
+            owner: owner.into(),
+            name: "rev".into(),
+            number,
+            title: format!("Fix, part {number}"),
+            date: opened,
+            labels: labels
+                .iter()
+                .map(|l| Label {
+                    name: l.to_string(),
+                    color: None,
+                    description: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let items = [
+            item("kjuulh", 1, &[]),
+            item("acme", 2, &["Hotfix"]),
+            item("acme", 3, &[]),
+        ];
+
+        let store = Store::in_memory();
+        let set = opened + Duration::hours(1);
+        DueDates::set(&store, "kjuulh/rev#1", set)?;
+        let dates = DueDates::load(&store)?;
+
+        let reviews = due_reviews(&items, &dates, &settings);
+        let due = reviews
+            .iter()
+            .map(|r| (r.reference.as_str(), r.due))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("kjuulh/rev#1", set),
+                ("acme/rev#2", opened + Duration::hours(4)),
+            ],
+            due
+        );
+
+        DueDates::clear(&store, "kjuulh/rev#1")?;
+        let dates = DueDates::load(&store)?;
+        assert_eq!(
+            Some(opened + Duration::hours(48)),
+            dates.due(&settings, &items[0])
+        );
+
+        let ics = to_ics(&reviews, opened);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:acme/rev#2@rev\r\n"));
+        assert!(ics.contains("DTEND:20240701T120000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Review acme/rev#2: Fix\\, part 2\r\n"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+//! Embeds the review list and the review of rev in other ratatui apps, such as a dashboard with
+//! a reviews panel next to its own.
+//!
+//! The app hands its terminal events to the panel, ticks it to take in what was fetched in the
+//! background, and draws it into an area of its frame. The panel needs a tokio runtime and a
+//! terminal drawn with crossterm on stdout, like the one of rev.
+//!
+//! ```no_run
+//! # async fn example(
+//! #     terminal: &mut ratatui::Terminal<ratatui::prelude::CrosstermBackend<std::io::Stdout>>,
+//! # ) -> anyhow::Result<()> {
+//! use rev_core::embed::{Config, GitProvider, Panel, ReviewsPanel, Store};
+//!
+//! let mut panel = ReviewsPanel::new(GitProvider::github()?, Config::default(), Store::default())?;
+//! loop {
+//!     if crossterm::event::poll(std::time::Duration::from_millis(100))? {
+//!         panel.handle_event(&crossterm::event::read()?)?;
+//!     }
+//!     panel.tick()?;
+//!     terminal.draw(|f| {
+//!         let _ = panel.draw(f, f.size());
+//!     })?;
+//! }
+//! # }
+//! ```
+
+use crossterm::event::{KeyEventKind, MouseButton, MouseEventKind};
+use ratatui::prelude::Rect;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+pub use rev_git_provider::{GitProvider, Provider};
+
+use crate::{
+    action::Action,
+    components::{
+        github_pr::GithubPr, github_prs::GithubPrs, toast::Toast, Component, EffectRunner, Update,
+    },
+    git_pull_requests::{GitPullRequest, GitPullRequests},
+    list_filters,
+    state::State,
+    tui::Event,
+};
+pub use crate::{config::Config, store::Store, tui::Frame};
+
+const LIST_PAGE: &str = "github_review_list";
+const REVIEW_PAGE: &str = "github_review";
+
+/// A part of rev drawn by another app.
+pub trait Panel {
+    /// Handles a terminal event, returning whether the panel took it, the app may use the ones
+    /// it didn't take for itself.
+    fn handle_event(&mut self, event: &crossterm::event::Event) -> anyhow::Result<bool>;
+
+    /// Takes in the reviews and comments fetched meanwhile, to be called before every draw.
+    fn tick(&mut self) -> anyhow::Result<()>;
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> anyhow::Result<()>;
+}
+
+/// The queue of reviews, and the review begun from it, with the keybinds of the config.
+///
+/// The pages the list and the review link to, such as the diff and the history, aren't part of
+/// the panel, and neither are the keybind profiles. `q` is left for the app, to close the panel
+/// with or to quit.
+pub struct ReviewsPanel {
+    config: Config,
+    list: GithubPrs,
+    review: GithubPr,
+    toast: Toast,
+    showing_review: bool,
+    /// Whether keys are typed into a composer or a filter, rather than bound to actions.
+    insert_mode: bool,
+    action_tx: UnboundedSender<Action>,
+    action_rx: UnboundedReceiver<Action>,
+    effects: EffectRunner,
+    state: State,
+}
+
+impl ReviewsPanel {
+    /// The panel on the queue of the provider, which is the one given in the config, starting on
+    /// the list. Fetching begins on the first tick.
+    pub fn new(provider: GitProvider, config: Config, store: Store) -> anyhow::Result<Self> {
+        let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let prs = GitPullRequests::new(provider.clone())
+            .queue(config.queue.clone())
+            .starred(config.starred.clone())
+            .store(store.clone());
+
+        let state = State::default();
+
+        let mut panel = Self {
+            list: GithubPrs::new(prs.clone(), store.clone(), state.clone()),
+            review: GithubPr::new(GitPullRequest::new(provider, prs, store), state.clone()),
+            toast: Toast::new(),
+            config,
+            showing_review: false,
+            insert_mode: false,
+            effects: EffectRunner::new(action_tx.clone()),
+            action_tx,
+            action_rx,
+            state,
+        };
+        let components: [&mut dyn Component; 3] =
+            [&mut panel.list, &mut panel.review, &mut panel.toast];
+        for component in components {
+            component.register_action_handler(panel.action_tx.clone())?;
+            component.register_config_handler(panel.config.clone())?;
+            component.init()?;
+        }
+        panel.action_tx.send(Action::GotoPage(LIST_PAGE.into()))?;
+
+        Ok(panel)
+    }
+
+    /// Whether a review is shown rather than the list.
+    pub fn showing_review(&self) -> bool {
+        self.showing_review
+    }
+
+    fn current(&mut self) -> &mut dyn Component {
+        match self.showing_review {
+            true => &mut self.review,
+            false => &mut self.list,
+        }
+    }
+
+    /// The action the key is bound to, leaving out the ones of pages the panel doesn't have.
+    fn key_action(&self, key: crossterm::event::KeyEvent) -> Option<Action> {
+        let page = match self.showing_review {
+            true => REVIEW_PAGE,
+            false => LIST_PAGE,
+        };
+        let bound = self.config.keybinds.action(Some(page), &vec![key]);
+        if bound.is_none() && !self.showing_review {
+            return list_filters::filter_key(&self.config.filters, key)
+                .map(Action::ToggleListFilter);
+        }
+
+        bound.filter(|a| {
+            !matches!(
+                a,
+                Action::Quit | Action::Suspend | Action::TogglePage(_) | Action::GotoPage(_)
+            )
+        })
+    }
+}
+
+impl Panel for ReviewsPanel {
+    fn handle_event(&mut self, event: &crossterm::event::Event) -> anyhow::Result<bool> {
+        let event = match event {
+            crossterm::event::Event::Key(key) if key.kind == KeyEventKind::Press => {
+                Event::Key(*key)
+            }
+            crossterm::event::Event::Mouse(mouse) => Event::Mouse(*mouse),
+            crossterm::event::Event::Paste(text) => Event::Paste(text.clone()),
+            _ => return Ok(false),
+        };
+
+        let mut taken = self.insert_mode;
+        match &event {
+            Event::Key(key) if !self.insert_mode => {
+                if let Some(action) = self.key_action(*key) {
+                    self.action_tx.send(action)?;
+                    taken = true;
+                }
+            }
+            Event::Mouse(mouse) if !self.insert_mode && !self.config.disable_mouse => {
+                let (column, row) = (mouse.column, mouse.row);
+                let action = match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => Some(Action::Click { column, row }),
+                    MouseEventKind::ScrollDown => Some(Action::Scroll {
+                        column,
+                        row,
+                        down: true,
+                    }),
+                    MouseEventKind::ScrollUp => Some(Action::Scroll {
+                        column,
+                        row,
+                        down: false,
+                    }),
+                    _ => None,
+                };
+                if let Some(action) = action {
+                    self.action_tx.send(action)?;
+                }
+            }
+            _ => {}
+        }
+
+        let current = self.current();
+        let update = Update {
+            action: current.handle_events(Some(event))?,
+            effects: current.take_effects(),
+        };
+        self.effects.run(update)?;
+
+        Ok(taken)
+    }
+
+    fn tick(&mut self) -> anyhow::Result<()> {
+        self.action_tx.send(Action::Tick)?;
+
+        while let Ok(action) = self.action_rx.try_recv() {
+            self.state.reduce(&action);
+            match &action {
+                Action::GotoPage(page) if page == LIST_PAGE => self.showing_review = false,
+                Action::GotoPage(page) if page == REVIEW_PAGE => self.showing_review = true,
+                Action::BeginReview => self.action_tx.send(Action::GotoPage(REVIEW_PAGE.into()))?,
+                Action::EnterInsertMode => self.insert_mode = true,
+                Action::ExitInsertMode => self.insert_mode = false,
+                _ => {}
+            }
+
+            let update = self.toast.handle(action.clone())?;
+            self.effects.run(update)?;
+            let update = self.current().handle(action)?;
+            self.effects.run(update)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> anyhow::Result<()> {
+        self.current().draw(f, area)?;
+        self.toast.draw(f, area)?;
+        // The app draws the frame once this returns, which the links can't be written after
+        crate::terminal::discard_links();
+
+        Ok(())
+    }
+}
+
+impl Drop for ReviewsPanel {
+    fn drop(&mut self) {
+        self.effects.cancel_all();
+    }
+}
@@ -0,0 +1,132 @@
+use rev_git_provider::models::Label;
+
+use crate::{
+    config::FileBadgeSettings,
+    glob,
+    i18n::{tr, Text},
+};
+
+/// A hint about how deep to review, computed from the paths a review changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileBadge {
+    /// Code changed without any of the tests changing along with it.
+    NoTests,
+    DocsUpdated,
+    CiModified,
+    /// Only lockfiles changed, e.g. a dependency bump.
+    LockfileOnly,
+}
+
+impl FileBadge {
+    /// The badge as a label, so it's drawn as a chip like the labels of the review.
+    pub fn label(&self) -> Label {
+        let (text, color) = match self {
+            FileBadge::NoTests => (Text::NoTestsChanged, "fbca04"),
+            FileBadge::DocsUpdated => (Text::DocsUpdated, "0075ca"),
+            FileBadge::CiModified => (Text::CiModified, "d93f0b"),
+            FileBadge::LockfileOnly => (Text::LockfileOnly, "c5def5"),
+        };
+
+        Label {
+            name: tr(text).to_string(),
+            color: Some(color.to_string()),
+            description: None,
+        }
+    }
+}
+
+/// The badges of a review changing the paths, in the order they're shown.
+///
+/// A review changing only lockfiles gets just that badge, there's nothing else to tell about it.
+/// Changes to docs, CI config and lockfiles don't need tests, so they don't count as code.
+pub fn badges(settings: &FileBadgeSettings, paths: &[String]) -> Vec<FileBadge> {
+    let any = |patterns: &[String], path: &str| patterns.iter().any(|p| glob::matches(p, path));
+    if paths.is_empty() {
+        return Vec::new();
+    }
+    if paths.iter().all(|p| any(&settings.lockfiles, p)) {
+        return vec![FileBadge::LockfileOnly];
+    }
+
+    let tests = paths.iter().any(|p| any(&settings.tests, p));
+    let docs = paths.iter().any(|p| any(&settings.docs, p));
+    let ci = paths.iter().any(|p| any(&settings.ci, p));
+    let code = paths.iter().any(|p| {
+        ![
+            &settings.tests,
+            &settings.docs,
+            &settings.ci,
+            &settings.lockfiles,
+        ]
+        .iter()
+        .any(|patterns| any(patterns, p))
+    });
+
+    let mut badges = Vec::new();
+    if code && !tests {
+        badges.push(FileBadge::NoTests);
+    }
+    if docs {
+        badges.push(FileBadge::DocsUpdated);
+    }
+    if ci {
+        badges.push(FileBadge::CiModified);
+    }
+
+    badges
+}
+
+/// The labels of the badges, see [`FileBadge::label`].
+pub fn labels(badges: &[FileBadge]) -> Vec<Label> {
+    badges.iter().map(FileBadge::label).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{badges, FileBadge};
+    use crate::config::FileBadgeSettings;
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_badges_from_paths() {
+        let settings = FileBadgeSettings::default();
+
+        assert_eq!(
+            vec![FileBadge::NoTests],
+            badges(&settings, &paths(&["src/main.rs"]))
+        );
+        assert_eq!(
+            Vec::<FileBadge>::new(),
+            badges(&settings, &paths(&["src/main.rs", "tests/main.rs"]))
+        );
+        assert_eq!(
+            vec![FileBadge::DocsUpdated],
+            badges(&settings, &paths(&["README.md", "docs/setup.md"]))
+        );
+        assert_eq!(
+            vec![FileBadge::NoTests, FileBadge::CiModified],
+            badges(
+                &settings,
+                &paths(&["src/lib.rs", ".github/workflows/ci.yml"])
+            )
+        );
+        assert_eq!(
+            vec![FileBadge::LockfileOnly],
+            badges(&settings, &paths(&["Cargo.lock", "web/package-lock.json"]))
+        );
+        assert_eq!(
+            vec![FileBadge::NoTests],
+            badges(&settings, &paths(&["Cargo.toml", "Cargo.lock"]))
+        );
+        assert!(badges(&settings, &[]).is_empty());
+
+        let settings = FileBadgeSettings {
+            tests: vec!["**/*_spec.rb".into()],
+            ..Default::default()
+        };
+        assert!(badges(&settings, &paths(&["app/user.rb", "spec/user_spec.rb"])).is_empty());
+    }
+}
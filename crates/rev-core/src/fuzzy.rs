@@ -0,0 +1,38 @@
+/// Whether every word of the query is found in one of the fields, ignoring case.
+///
+/// A word matches a field when it's a part of it, or when its characters appear in the field in
+/// order, e.g. `rvw` matches `review`, so half remembered names still narrow a list down.
+pub fn matches(query: &str, fields: &[&str]) -> bool {
+    let fields = fields.iter().map(|f| f.to_lowercase()).collect::<Vec<_>>();
+
+    query.split_whitespace().all(|word| {
+        let word = word.to_lowercase();
+        fields
+            .iter()
+            .any(|field| field.contains(&word) || is_subsequence(&word, field))
+    })
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+#[cfg(test)]
+mod test {
+    use super::matches;
+
+    #[test]
+    fn test_matches_words_across_fields() {
+        let fields = ["Add retries to the client", "kjuulh/rev", "alice"];
+
+        assert!(matches("", &fields));
+        assert!(matches("retries", &fields));
+        assert!(matches("RETRIES alice", &fields));
+        assert!(matches("kjuulh/rev", &fields));
+        assert!(matches("rtrs", &fields));
+        assert!(matches("alc", &fields));
+        assert!(!matches("retries bob", &fields));
+        assert!(!matches("sertir", &fields));
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::Context;
+use rev_git_provider::{
+    models::{Issue, ReviewListItem},
+    GitProvider,
+};
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    store::Store,
+};
+
+/// The most issues listed for a discussion query.
+const MAX_ISSUES: usize = 100;
+
+/// Long-form issues, such as RFCs, which are discussed rather than reviewed.
+#[derive(Clone)]
+pub struct GitIssues {
+    provider: GitProvider,
+    store: Store,
+}
+
+impl GitIssues {
+    pub fn new(provider: GitProvider, store: Store) -> Self {
+        Self { provider, store }
+    }
+
+    pub async fn list(&self, query: &str) -> anyhow::Result<Vec<ReviewListItem>> {
+        let mut items = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let list = self.provider.search_issues(query, cursor).await?;
+            items.extend(list.items);
+            cursor = list.last_cursor;
+
+            if !list.has_more || items.len() >= MAX_ISSUES {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    pub async fn get(&self, item: &ReviewListItem) -> anyhow::Result<Issue> {
+        self.provider
+            .get_issue(item.owner.clone(), item.name.clone(), item.number)
+            .await?
+            .with_context(|| {
+                format!(
+                    "failed to find issue {}/{}#{}",
+                    item.owner, item.name, item.number
+                )
+            })
+    }
+
+    pub async fn comment(&self, issue: &Issue, body: String) -> anyhow::Result<()> {
+        let entry =
+            AuditEntry::new("tui", "comment-on-issue", issue.reference()).parameter("body", &body);
+        let result = self.provider.add_comment(issue.id.clone(), body).await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+}
@@ -0,0 +1,966 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::Context;
+use rev_git_provider::{
+    models::{
+        Comments, Commit, MergeStrategy, RateLimit, Review, ReviewEvent, ReviewList,
+        ReviewListItem, ReviewThread, StatusCheck, Vulnerability,
+    },
+    GitProvider,
+};
+use tokio::sync::mpsc;
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    check_history::CheckHistory,
+    config::{OutOfScope, QueueSettings},
+    dependencies::{self, DependencyUpdate},
+    description_lint::DescriptionLint,
+    drafts::{Drafts, ReviewDraft, Verdict},
+    labels::{self, LabelRule},
+    patch::{self, FileDiff},
+    review_plan::ReviewPlan,
+    snooze, starred,
+    store::Store,
+    visits::ReviewVisits,
+};
+
+/// The most items [`search_all`] collects.
+const MAX_SEARCH_ITEMS: usize = 100;
+
+/// Collects every item of a search query, e.g. `is:pr review-requested:@me state:open`.
+pub async fn search_all(
+    provider: &GitProvider,
+    query: &str,
+) -> anyhow::Result<Vec<ReviewListItem>> {
+    let mut items = Vec::new();
+    let mut cursor = None;
+
+    loop {
+        let list = provider.search_reviews(query, cursor).await?;
+        items.extend(list.items);
+        cursor = list.last_cursor;
+
+        if !list.has_more || items.len() >= MAX_SEARCH_ITEMS {
+            break;
+        }
+    }
+
+    Ok(items)
+}
+
+/// Fetches the reviews in batches, keeping the order of the ids.
+pub async fn fetch_reviews(
+    provider: &GitProvider,
+    ids: Vec<String>,
+) -> anyhow::Result<Vec<Review>> {
+    let mut reviews = Vec::with_capacity(ids.len());
+    for batch in ids.chunks(REVIEW_BATCH_SIZE) {
+        reviews.extend(provider.get_reviews(batch.to_vec()).await?);
+    }
+
+    Ok(reviews)
+}
+
+/// The member of the team with the fewest open review requests, the first listed on a tie,
+/// leaving out `me`.
+pub async fn least_loaded(
+    provider: &GitProvider,
+    me: &str,
+    members: &[String],
+) -> anyhow::Result<String> {
+    let counts = members
+        .iter()
+        .filter(|member| !member.eq_ignore_ascii_case(me))
+        .map(|member| async move {
+            let count = provider
+                .count_search(&format!("is:pr is:open review-requested:{member}"))
+                .await
+                .with_context(|| format!("failed to count the review requests of {member}"))?;
+            anyhow::Ok((member, count))
+        });
+
+    let load = futures::future::try_join_all(counts).await?;
+    load.into_iter()
+        .min_by_key(|(_, count)| *count)
+        .map(|(member, _)| member.clone())
+        .context("the team has no members besides you")
+}
+
+#[derive(Clone)]
+pub struct GitPullRequests {
+    provider: GitProvider,
+    queue: QueueSettings,
+    /// The saved search used as the queue instead of the review requests, shared between clones
+    /// so the list and the review queue agree.
+    search: Arc<RwLock<Option<String>>>,
+    /// Where the snoozed reviews left out of both are kept.
+    store: Store,
+    /// The repositories starred in the config, whose reviews go first.
+    starred: Vec<String>,
+}
+
+impl GitPullRequests {
+    pub fn new(provider: GitProvider) -> Self {
+        Self {
+            provider,
+            queue: QueueSettings::default(),
+            search: Arc::default(),
+            store: Store::default(),
+            starred: Vec::new(),
+        }
+    }
+
+    pub fn search(&self) -> Option<String> {
+        self.search.read().unwrap().clone()
+    }
+
+    pub fn set_search(&self, query: Option<String>) {
+        *self.search.write().unwrap() = query;
+    }
+
+    /// The api budget left as of the last response, when the provider reports it.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.provider.stats().rate_limit
+    }
+
+    /// Validates a search query by running it, returning how many items it matches.
+    pub async fn validate_search(&self, query: &str) -> anyhow::Result<usize> {
+        self.provider.count_search(query).await
+    }
+
+    /// Lists a page of the queue, either the review requests or the saved search, without the
+    /// snoozed reviews.
+    async fn list_page(
+        &self,
+        tags: Option<Vec<String>>,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        let mut page = self.list_all(tags, cursor).await?;
+        let snoozes = snooze::load_or_warn(&self.store);
+        page.items.retain(|i| !snoozes.contains(&i.id));
+
+        Ok(page)
+    }
+
+    async fn list_all(
+        &self,
+        tags: Option<Vec<String>>,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        match self.search() {
+            Some(query) => self.provider.search_reviews(&query, cursor).await,
+            None => {
+                let labels = tags.or_else(|| self.queue.tags());
+                self.provider
+                    .get_user_reviews_cursor(
+                        self.queue.reviewer.as_deref(),
+                        self.queue.org.as_deref(),
+                        labels,
+                        cursor,
+                    )
+                    .await
+            }
+        }
+    }
+
+    /// The paths the review changes, to compute its badges from.
+    pub async fn changed_paths(&self, item: &ReviewListItem) -> anyhow::Result<Vec<String>> {
+        Ok(self
+            .provider
+            .get_changed_files(item.owner.clone(), item.name.clone(), item.number)
+            .await?
+            .into_iter()
+            .map(|f| f.path)
+            .collect())
+    }
+
+    pub fn queue(mut self, queue: QueueSettings) -> Self {
+        self.queue = queue;
+        self
+    }
+
+    pub fn store(mut self, store: Store) -> Self {
+        self.store = store;
+        self
+    }
+
+    pub fn starred(mut self, starred: Vec<String>) -> Self {
+        self.starred = starred;
+        self
+    }
+
+    /// Whether the item touches the scopes of the queue, fetching its changed files if the queue
+    /// is scoped. Failing to fetch them keeps the item in scope rather than losing it.
+    async fn in_scope(&self, item: &ReviewListItem) -> bool {
+        if !self.queue.is_scoped() {
+            return true;
+        }
+
+        match self
+            .provider
+            .get_changed_files(item.owner.clone(), item.name.clone(), item.number)
+            .await
+        {
+            Ok(files) => self
+                .queue
+                .in_scope(&files.into_iter().map(|f| f.path).collect::<Vec<_>>()),
+            Err(e) => {
+                tracing::warn!("failed to get changed files of {}: {e:#}", item.title);
+                true
+            }
+        }
+    }
+
+    /// Sends the item if it's in scope, otherwise hides or defers it. Returns false once the
+    /// receiver is gone.
+    async fn send_in_scope(
+        &self,
+        tx: &mpsc::Sender<anyhow::Result<ReviewListItem>>,
+        item: ReviewListItem,
+        deferred: &mut Vec<ReviewListItem>,
+    ) -> bool {
+        if !self.in_scope(&item).await {
+            if self.queue.out_of_scope == OutOfScope::Deprioritize {
+                deferred.push(item);
+            }
+            return true;
+        }
+
+        tx.send(Ok(item)).await.is_ok()
+    }
+
+    /// Hides or moves the items outside the scopes of the queue to the end, keeping the order
+    /// otherwise.
+    pub async fn apply_scopes(&self, items: Vec<ReviewListItem>) -> Vec<ReviewListItem> {
+        if !self.queue.is_scoped() {
+            return items;
+        }
+
+        let mut in_scope = Vec::with_capacity(items.len());
+        let mut out_of_scope = Vec::new();
+        for item in items {
+            if self.in_scope(&item).await {
+                in_scope.push(item);
+            } else {
+                out_of_scope.push(item);
+            }
+        }
+
+        if self.queue.out_of_scope == OutOfScope::Deprioritize {
+            in_scope.extend(out_of_scope);
+        }
+
+        in_scope
+    }
+
+    async fn run_inner(
+        &self,
+        tx: &mpsc::Sender<anyhow::Result<ReviewListItem>>,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<()> {
+        let mut buffer = VecDeque::new();
+        let mut cursor = None;
+        let mut has_more = true;
+        let mut seen = 0;
+        // Sent once everything in scope has been, when out of scope items are deprioritized
+        let mut deferred = Vec::new();
+
+        loop {
+            if buffer.len() <= 15 && has_more {
+                tracing::debug!("fetching more: len {}", buffer.len());
+                let review_list = self.list_page(tags.clone(), cursor).await?;
+
+                has_more = review_list.has_more;
+                cursor = review_list.last_cursor;
+                seen += review_list.items.len();
+                tracing::debug!("get user reviews got items: {}", review_list.items.len());
+                buffer.extend(review_list.items);
+
+                if !has_more {
+                    break;
+                }
+            }
+
+            if seen > 100 {
+                break;
+            }
+
+            if let Some(item) = buffer.pop_front() {
+                if !self.send_in_scope(tx, item, &mut deferred).await {
+                    return Ok(());
+                }
+            }
+        }
+
+        for item in buffer {
+            if !self.send_in_scope(tx, item, &mut deferred).await {
+                return Ok(());
+            }
+        }
+        for item in deferred {
+            if tx.send(Ok(item)).await.is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collects the whole queue, in the order it's streamed to the tui.
+    pub async fn collect(&self, tags: Option<Vec<String>>) -> anyhow::Result<Vec<ReviewListItem>> {
+        let mut rx = self.run(tags).await?;
+        let mut items = Vec::new();
+        while let Some(item) = rx.recv().await {
+            items.push(item?);
+        }
+
+        Ok(items)
+    }
+
+    /// Streams the queue, ending with the error when fetching it fails, e.g. as the token lacks
+    /// a scope.
+    pub async fn run(
+        &self,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<mpsc::Receiver<anyhow::Result<ReviewListItem>>> {
+        let s = self.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(20);
+
+        tokio::spawn(async move {
+            if let Err(e) = s.run_inner(&tx, tags).await {
+                tracing::error!("failed to fetch the queue: {e:#}");
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+
+        Ok(rx)
+    }
+}
+
+/// How many reviews to fetch in a single request, bigger batches make for slower and more
+/// expensive queries as every review brings its comments and checks along.
+const REVIEW_BATCH_SIZE: usize = 10;
+
+/// Failed polls in a row [`GitPullRequest::wait_for_checks`] rides out, e.g. a flaky network or
+/// a rate limit, before giving up.
+const MAX_CHECK_POLL_FAILURES: u32 = 10;
+
+/// Counts a failed poll of the checks, failing once there have been too many in a row.
+fn retry_poll(failures: &mut u32, error: anyhow::Error, review: &Review) -> anyhow::Result<()> {
+    *failures += 1;
+    if *failures >= MAX_CHECK_POLL_FAILURES {
+        return Err(error.context(format!(
+            "gave up on the checks after {failures} failed polls"
+        )));
+    }
+
+    tracing::warn!(
+        repository = review.repository,
+        number = review.number,
+        failures = *failures,
+        "failed to poll checks, retrying: {error:#}"
+    );
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct GitPullRequest {
+    provider: GitProvider,
+    prs: GitPullRequests,
+    store: Store,
+}
+
+impl GitPullRequest {
+    pub fn new(provider: GitProvider, prs: GitPullRequests, store: Store) -> Self {
+        Self {
+            provider,
+            prs,
+            store,
+        }
+    }
+
+    /// The saved search the queue is made of, if any.
+    pub fn search(&self) -> Option<String> {
+        self.prs.search()
+    }
+
+    async fn run_inner(
+        &self,
+        tx: &mpsc::Sender<anyhow::Result<Review>>,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<()> {
+        let plan = ReviewPlan::load(&self.store).unwrap_or_else(|e| {
+            tracing::warn!("failed to load review plan: {e:#}");
+            ReviewPlan::default()
+        });
+
+        // The plan may move anything to the front, so the queue is listed before fetching the
+        // details of the reviews in the planned order
+        let mut items = Vec::new();
+        let mut cursor = None;
+        loop {
+            let review_list = self.prs.list_page(tags.clone(), cursor).await?;
+            tracing::debug!("get user reviews got items: {}", review_list.items.len());
+
+            items.extend(review_list.items);
+            cursor = review_list.last_cursor;
+
+            if !review_list.has_more || items.len() > 100 {
+                break;
+            }
+        }
+
+        // Scoped first, so the plan can still pull an out of scope review to the front
+        let mut items = self.prs.apply_scopes(items).await;
+        plan.sort(&mut items, |i| &i.id);
+        starred::boost(
+            &starred::load_or_warn(&self.store, &self.prs.starred),
+            &plan,
+            &mut items,
+        );
+
+        for batch in items.chunks(REVIEW_BATCH_SIZE) {
+            let ids = batch.iter().map(|r| r.id.clone()).collect::<Vec<_>>();
+            tracing::debug!(count = ids.len(), "fetching git pull requests");
+
+            for review in self.provider.get_reviews(ids).await? {
+                if tx.send(Ok(review)).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Polls the status checks of the review until none of them are pending anymore, returning
+    /// the refreshed review. Failed polls are retried on the next interval, it only gives up
+    /// after [`MAX_CHECK_POLL_FAILURES`] of them in a row.
+    pub async fn wait_for_checks(
+        &self,
+        review: Review,
+        interval: Duration,
+    ) -> anyhow::Result<Review> {
+        let gone = || {
+            anyhow::anyhow!(
+                "review {}#{} no longer exists",
+                review.repository,
+                review.number
+            )
+        };
+        let mut failures = 0;
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let checks = match self.get_status_checks(&review).await {
+                Ok(Some(checks)) => checks,
+                Ok(None) => return Err(gone()),
+                Err(e) => {
+                    retry_poll(&mut failures, e, &review)?;
+                    continue;
+                }
+            };
+
+            let checked = Review {
+                status_checks: Some(checks),
+                ..review.clone()
+            };
+
+            if !checked.has_pending_checks() {
+                match self.refresh(&review).await {
+                    Ok(Some(mut refreshed)) => {
+                        refreshed.status_checks = checked.status_checks;
+                        return Ok(refreshed);
+                    }
+                    Ok(None) => return Err(gone()),
+                    Err(e) => {
+                        retry_poll(&mut failures, e, &review)?;
+                        continue;
+                    }
+                }
+            }
+            failures = 0;
+            tracing::debug!(
+                repository = review.repository,
+                number = review.number,
+                "checks still pending"
+            );
+        }
+    }
+
+    pub async fn get_comments(&self, review: &Review) -> anyhow::Result<Option<Comments>> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        self.provider
+            .get_comments(owner.to_string(), name.to_string(), review.number)
+            .await
+    }
+
+    pub async fn get_status_checks(
+        &self,
+        review: &Review,
+    ) -> anyhow::Result<Option<Vec<StatusCheck>>> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        let checks = self
+            .provider
+            .get_status_checks(owner.to_string(), name.to_string(), review.number)
+            .await?;
+
+        if let Some(checks) = checks.as_ref() {
+            if let Err(e) =
+                CheckHistory::record(&self.store, &review.repository, &review.head_sha, checks)
+            {
+                tracing::warn!("failed to record check history: {e:#}");
+            }
+        }
+
+        Ok(checks)
+    }
+
+    /// The changes of the review, file by file.
+    pub async fn get_diff(&self, review: &Review) -> anyhow::Result<Vec<FileDiff>> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        let diff = self
+            .provider
+            .get_diff(owner.to_string(), name.to_string(), review.number)
+            .await?;
+
+        Ok(patch::parse(&diff))
+    }
+
+    /// Keeps the review out of the queue and the list for the duration, or until it's unsnoozed.
+    pub fn snooze(
+        &self,
+        review: &Review,
+        duration: Option<chrono::Duration>,
+    ) -> anyhow::Result<()> {
+        let mut snooze = snooze::Snooze::new(review.reference(), &review.title);
+        if let Some(duration) = duration {
+            snooze = snooze.for_duration(duration);
+        }
+
+        snooze::Snoozes::snooze(&self.store, &review.id, snooze)
+    }
+
+    /// Records that the review was shown, returning its head at the last visit when it moved
+    /// since, i.e. there are changes to re-review.
+    pub fn record_visit(&self, review: &Review) -> Option<String> {
+        match ReviewVisits::record(&self.store, &review.reference(), &review.head_sha) {
+            Ok(previous) => previous
+                .map(|v| v.head)
+                .filter(|head| head != &review.head_sha),
+            Err(e) => {
+                tracing::warn!("failed to record visit: {e}");
+                None
+            }
+        }
+    }
+
+    /// The names of the checks of the review which tend to fail and then pass on a rerun.
+    pub fn flaky_checks(&self, review: &Review, checks: &[StatusCheck]) -> HashSet<String> {
+        let history = CheckHistory::load(&self.store).unwrap_or_else(|e| {
+            tracing::warn!("failed to load check history: {e:#}");
+            CheckHistory::default()
+        });
+
+        checks
+            .iter()
+            .filter(|c| history.is_flaky(&review.repository, c))
+            .map(|c| c.name().to_string())
+            .collect()
+    }
+
+    pub async fn get_commits(&self, review: &Review) -> anyhow::Result<Vec<Commit>> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        self.provider
+            .get_commits(owner.to_string(), name.to_string(), review.number)
+            .await
+    }
+
+    /// The paths the review changes, to compute its badges from.
+    pub async fn changed_paths(&self, review: &Review) -> anyhow::Result<Vec<String>> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        Ok(self
+            .provider
+            .get_changed_files(owner.to_string(), name.to_string(), review.number)
+            .await?
+            .into_iter()
+            .map(|f| f.path)
+            .collect())
+    }
+
+    /// The known vulnerabilities a dependency update review fixes, empty for any other review.
+    pub async fn security_fixes(&self, review: &Review) -> anyhow::Result<Vec<Vulnerability>> {
+        let Some(update) = DependencyUpdate::parse(&review.title, &review.description) else {
+            return Ok(Vec::new());
+        };
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        let files = self
+            .provider
+            .get_changed_files(owner.to_string(), name.to_string(), review.number)
+            .await?
+            .into_iter()
+            .map(|f| f.path)
+            .collect::<Vec<_>>();
+        let Some(ecosystem) = dependencies::ecosystem(&review.title, &files) else {
+            tracing::debug!(
+                package = update.package,
+                "unknown ecosystem of dependency update"
+            );
+            return Ok(Vec::new());
+        };
+
+        let vulnerabilities = self
+            .provider
+            .get_vulnerabilities(ecosystem, &update.package)
+            .await?;
+
+        Ok(update.fixes(&vulnerabilities))
+    }
+
+    /// Lints the description of the review against the pull request template of its repository.
+    pub async fn lint_description(
+        &self,
+        review: &Review,
+    ) -> anyhow::Result<Option<DescriptionLint>> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        let template = self
+            .provider
+            .get_review_template(owner.to_string(), name.to_string())
+            .await?;
+
+        Ok(DescriptionLint::lint(
+            &review.description,
+            template.as_deref(),
+        ))
+    }
+
+    /// Asks the author to fill in the description, which is recorded in the audit log.
+    pub async fn request_description(
+        &self,
+        review: &Review,
+        lint: &DescriptionLint,
+    ) -> anyhow::Result<()> {
+        let body = lint.request(&review.author);
+        let entry = AuditEntry::new("tui", "request-description", review.reference())
+            .parameter("body", &body);
+        let result = self.provider.add_comment(review.id.clone(), body).await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+
+    /// Comments on the review asking the author to fix the commits breaking the lint rules,
+    /// which is recorded in the audit log.
+    pub async fn request_commit_fixes(&self, review: &Review, body: String) -> anyhow::Result<()> {
+        let entry = AuditEntry::new("tui", "request-commit-fixes", review.reference())
+            .parameter("body", &body);
+        let result = self.provider.add_comment(review.id.clone(), body).await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+
+    /// Polls the head of the review, returning the new sha once it differs from the one the
+    /// review was fetched with.
+    pub async fn watch_head(&self, review: Review, interval: Duration) -> anyhow::Result<String> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+        let (owner, name) = (owner.to_string(), name.to_string());
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            match self
+                .provider
+                .get_head_sha(owner.clone(), name.clone(), review.number)
+                .await?
+            {
+                Some(sha) if sha != review.head_sha => return Ok(sha),
+                Some(_) => {}
+                None => anyhow::bail!(
+                    "review {}#{} no longer exists",
+                    review.repository,
+                    review.number
+                ),
+            }
+        }
+    }
+
+    /// Fetches a review by reference, `None` when it doesn't exist or is an issue.
+    pub async fn get_review(
+        &self,
+        repository: &str,
+        number: usize,
+    ) -> anyhow::Result<Option<Review>> {
+        let (owner, name) = repository
+            .split_once('/')
+            .context("repository to be in the form owner/name")?;
+
+        self.provider
+            .get_review(owner.to_string(), name.to_string(), number)
+            .await
+    }
+
+    pub async fn refresh(&self, review: &Review) -> anyhow::Result<Option<Review>> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        self.provider
+            .invalidate_review(owner.to_string(), name.to_string(), review.number);
+        self.provider
+            .get_review(owner.to_string(), name.to_string(), review.number)
+            .await
+    }
+
+    /// Edits a comment on `review`, which is recorded in the audit log.
+    pub async fn update_comment(
+        &self,
+        review: &str,
+        id: String,
+        body: String,
+    ) -> anyhow::Result<()> {
+        let entry = AuditEntry::new("tui", "edit-comment", review)
+            .parameter("comment", &id)
+            .parameter("body", &body);
+        let result = self.provider.update_comment(id, body).await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+
+    /// Submits the review with the verdict, then changes its labels by the rules.
+    pub async fn submit_review(
+        &self,
+        review: &Review,
+        event: ReviewEvent,
+        body: Option<String>,
+        label_rules: &[LabelRule],
+    ) -> anyhow::Result<()> {
+        let action = match event {
+            ReviewEvent::Approve => "approve",
+            ReviewEvent::RequestChanges => "request-changes",
+            ReviewEvent::Comment => "comment",
+        };
+        let entry = AuditEntry::new("tui", action, review.reference())
+            .parameter("author", &review.author)
+            .parameter("body", body.as_deref().unwrap_or_default());
+        let result = self
+            .provider
+            .submit_review(review.id.clone(), event, body)
+            .await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+        result?;
+
+        if let Err(e) = Drafts::remove(&self.store, &review.reference()) {
+            tracing::warn!("failed to remove draft of {}: {e:#}", review.reference());
+        }
+
+        // The review is in, so failing to label it is only worth a warning
+        if let Err(e) = labels::apply(
+            &self.provider,
+            &self.store,
+            "tui",
+            label_rules,
+            review,
+            event,
+        )
+        .await
+        {
+            tracing::warn!("failed to change labels of {}: {e:#}", review.reference());
+        }
+
+        Ok(())
+    }
+
+    pub async fn merge(&self, review: &Review, strategy: MergeStrategy) -> anyhow::Result<()> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+
+        let entry = AuditEntry::new("tui", "merge", review.reference())
+            .parameter("strategy", strategy.name());
+        let result = self
+            .provider
+            .merge_review(owner.into(), name.into(), review.number, strategy)
+            .await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+
+    /// The unsubmitted draft of the review, if any.
+    pub fn draft(&self, review: &str) -> Option<ReviewDraft> {
+        Drafts::get(&self.store, review).unwrap_or_else(|e| {
+            tracing::warn!("failed to load drafts: {e:#}");
+            None
+        })
+    }
+
+    pub fn save_draft(&self, draft: ReviewDraft) -> anyhow::Result<()> {
+        Drafts::save(&self.store, draft)
+    }
+
+    /// Submits a draft, e.g. one imported from another machine, commenting when it has no
+    /// verdict.
+    pub async fn submit_draft(
+        &self,
+        draft: &ReviewDraft,
+        label_rules: &[LabelRule],
+    ) -> anyhow::Result<()> {
+        let (repository, number) = draft
+            .review
+            .split_once('#')
+            .and_then(|(repository, number)| Some((repository, number.parse().ok()?)))
+            .context("review must be in the form owner/name#number")?;
+        let review = self
+            .get_review(repository, number)
+            .await?
+            .with_context(|| format!("{} does not exist", draft.review))?;
+
+        let verdict = draft.verdict.unwrap_or(Verdict::Comment);
+        self.submit_review(
+            &review,
+            verdict.into(),
+            draft.submission_body(),
+            label_rules,
+        )
+        .await
+    }
+
+    pub async fn delete_comment(&self, review: &str, id: String) -> anyhow::Result<()> {
+        let entry = AuditEntry::new("tui", "delete-comment", review).parameter("comment", &id);
+        let result = self.provider.delete_comment(id).await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+
+    /// Hands the review over from `me` to the member of the team with the fewest open review
+    /// requests, returning who it went to.
+    pub async fn reassign_to_least_loaded(
+        &self,
+        review: &Review,
+        me: &str,
+        members: &[String],
+    ) -> anyhow::Result<String> {
+        let (owner, name) = review
+            .owner_and_name()
+            .context("review repository to be in the form owner/name")?;
+        let to = least_loaded(&self.provider, me, members).await?;
+
+        let entry = AuditEntry::new("tui", "reassign", review.reference())
+            .parameter("from", me)
+            .parameter("to", &to);
+        let result = self
+            .provider
+            .reassign_reviewer(
+                owner.to_string(),
+                name.to_string(),
+                review.number,
+                me.to_string(),
+                to.clone(),
+            )
+            .await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result.map(|()| to)
+    }
+
+    /// Replies to a thread on the diff of the review, by the location of the thread.
+    pub async fn reply_to_thread(
+        &self,
+        review: &Review,
+        thread: &ReviewThread,
+        body: String,
+    ) -> anyhow::Result<()> {
+        let entry = AuditEntry::new("tui", "reply-to-thread", review.reference())
+            .parameter("thread", thread.location())
+            .parameter("body", &body);
+        let result = self.provider.reply_to_thread(thread.id.clone(), body).await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+
+    pub async fn resolve_thread(
+        &self,
+        review: &Review,
+        thread: &ReviewThread,
+    ) -> anyhow::Result<()> {
+        let entry = AuditEntry::new("tui", "resolve-thread", review.reference())
+            .parameter("thread", thread.location());
+        let result = self.provider.resolve_thread(thread.id.clone()).await;
+        AuditLog::record_or_warn(&self.store, entry.result(&result));
+
+        result
+    }
+
+    /// Streams the reviews of the queue, ending with the error when fetching them fails.
+    pub async fn run(
+        &self,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<mpsc::Receiver<anyhow::Result<Review>>> {
+        let s = self.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(15);
+
+        tokio::spawn(async move {
+            if let Err(e) = s.run_inner(&tx, tags).await {
+                tracing::error!("failed to fetch the reviews: {e:#}");
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+
+        Ok(rx)
+    }
+}
+
+// #[cfg(test)]
+// mod test {
+//     use rev_git_provider::{models::ReviewListItem, GitProvider};
+//     use tracing_test::traced_test;
+
+//     use crate::git_pull_requests::GitPullRequests;
+
+//     #[tokio::test]
+//     #[traced_test]
+//     async fn test_can_fetch_many_prs() -> anyhow::Result<()> {
+//         let mut prs = GitPullRequests::new(GitProvider::github()?);
+
+//         let join = tokio::spawn(async move { while let Some(_item) = rx.recv().await {} });
+
+//         prs.run(tx).await?;
+
+//         join.await?;
+
+//         Ok(())
+//     }
+// }
@@ -0,0 +1,51 @@
+/// Matches text against a glob pattern.
+///
+/// `?` matches a single character, `*` matches anything but a path separator and `**` matches
+/// anything including path separators, so `services/**` matches every file below `services`.
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    matches_inner(&pattern, &text)
+}
+
+fn matches_inner(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `dir/**/file` should match `dir/file` as well, so the slash following `**` is
+            // optional when we're at a path boundary
+            let without_slash = rest.strip_prefix(&['/'][..]).unwrap_or(rest);
+            (0..=text.len()).any(|i| {
+                matches_inner(rest, &text[i..])
+                    || ((i == 0 || text[i - 1] == '/') && matches_inner(without_slash, &text[i..]))
+            })
+        }
+        ['*', rest @ ..] => (0..=text.len())
+            .take_while(|i| *i == 0 || text[i - 1] != '/')
+            .any(|i| matches_inner(rest, &text[i..])),
+        ['?', rest @ ..] => !text.is_empty() && text[0] != '/' && matches_inner(rest, &text[1..]),
+        [p, rest @ ..] => !text.is_empty() && text[0] == *p && matches_inner(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::matches;
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("*[bot]", "dependabot[bot]"));
+        assert!(!matches("*[bot]", "kjuulh"));
+        assert!(matches("renovate", "renovate"));
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(!matches("src/*.rs", "src/components/home.rs"));
+        assert!(matches("src/**", "src/components/home.rs"));
+        assert!(matches("src/**/*.rs", "src/main.rs"));
+        assert!(matches("**/Cargo.lock", "crates/rev/Cargo.lock"));
+        assert!(matches("**/Cargo.lock", "Cargo.lock"));
+        assert!(!matches("**/lock", "foolock"));
+        assert!(matches("?.md", "a.md"));
+        assert!(!matches("?.md", "ab.md"));
+    }
+}
@@ -0,0 +1,837 @@
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Da,
+}
+
+impl Locale {
+    /// Picks the locale from `REV_LOCALE`, then the `locale` of rev.kdl, falling back on the
+    /// posix locale variables.
+    pub fn detect(configured: Option<Locale>) -> Self {
+        let (forced, system) = Self::from_env(|key| std::env::var(key).ok());
+        forced.or(configured).unwrap_or(system)
+    }
+
+    /// The locale of `REV_LOCALE`, if set, and the one of the posix locale variables.
+    fn from_env(get: impl Fn(&str) -> Option<String>) -> (Option<Self>, Self) {
+        let var = |key: &str| get(key).filter(|v| !v.is_empty());
+        let forced = var("REV_LOCALE").map(|l| Self::parse(&l));
+        let system = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|k| var(k))
+            .map(|l| Self::parse(&l))
+            .unwrap_or(Locale::En);
+
+        (forced, system)
+    }
+
+    pub fn parse(raw: &str) -> Self {
+        Self::try_parse(raw).unwrap_or(Locale::En)
+    }
+
+    /// The locale of e.g. `da` or `da_DK.UTF-8`, `None` when there's no translation for it.
+    pub fn try_parse(raw: &str) -> Option<Self> {
+        let lang = raw
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match lang.as_str() {
+            "en" => Some(Locale::En),
+            "da" => Some(Locale::Da),
+            _ => None,
+        }
+    }
+}
+
+/// `REV_LOCALE` and the posix locale, read once.
+static ENV_LOCALE: OnceLock<(Option<Locale>, Locale)> = OnceLock::new();
+
+/// The `locale` of rev.kdl, once the config is loaded.
+static CONFIGURED_LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Translates into the locale of rev.kdl from now on, unless `REV_LOCALE` is set. The first
+/// config loaded wins, as the text already shown isn't translated again.
+pub fn configure(locale: Locale) {
+    let _ = CONFIGURED_LOCALE.set(locale);
+}
+
+pub fn locale() -> Locale {
+    let (forced, system) =
+        *ENV_LOCALE.get_or_init(|| Locale::from_env(|key| std::env::var(key).ok()));
+    forced
+        .or(CONFIGURED_LOCALE.get().copied())
+        .unwrap_or(system)
+}
+
+/// Every user facing string in the interface. Adding a variant requires a translation for each
+/// [`Locale`], which the compiler enforces through the exhaustive matches below.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Text {
+    Processing,
+    FetchFailed,
+    GithubPullRequests,
+    Owner,
+    Repository,
+    Title,
+    DateCreated,
+    Author,
+    Labels,
+    Checks,
+    Size,
+    Approvals,
+    ChecksPassed,
+    ChecksFailed,
+    ChecksPending,
+    Review,
+    ReviewApproved,
+    ReviewChangesRequested,
+    ReviewRequired,
+    Draft,
+    PullRequest,
+    Comments,
+    StatusChecks,
+    Commits,
+    UpdatedSinceLastVisit,
+    NoChangesSinceLastVisit,
+    NotForcePushed,
+    NoLinks,
+    LinkCopied,
+    NoCommentLink,
+    CommentNotFound,
+    ReviewNotFound,
+    ChangedFiles,
+    NoChangedFiles,
+    Files,
+    FetchingDiff,
+    DoctorGh,
+    DoctorToken,
+    DoctorApi,
+    DoctorScopes,
+    DoctorTerminal,
+    DoctorConfig,
+    DoctorDataDir,
+    DoctorHint,
+    DoctorInstallGh,
+    DoctorLogin,
+    DoctorTokenRejected,
+    DoctorRefreshToken,
+    DoctorCheckNetwork,
+    DoctorFineGrainedToken,
+    DoctorMissingScopes,
+    DoctorUsingDefaults,
+    DoctorFixConfig,
+    DoctorFixDataDir,
+    DoctorFailed,
+    ApproveReview,
+    RequestChangesReview,
+    CommentReview,
+    ReviewSubmitted,
+    ReviewNeedsBody,
+    Profiler,
+    FrameTime,
+    EventLoopLatency,
+    PendingRequests,
+    CachedResponses,
+    Average,
+    Maximum,
+    CheckingOut,
+    CommitsVerified,
+    Unverified,
+    Unsigned,
+    NoDescription,
+    StatusBarHelp,
+    RunningGitDiff,
+    DiffHelp,
+    PatternNotFound,
+    ConfigAlreadyExists,
+    UseForceToOverride,
+    WroteConfig,
+    DigestNewRequests,
+    DigestWaitingLongest,
+    DigestMyPullRequests,
+    DigestNothing,
+    NoPendingChecks,
+    SnoozedUntilChecksComplete,
+    ChecksCompleted,
+    ChecksWaitFailed,
+    Hidden,
+    MoreLines,
+    Edited,
+    You,
+    EditComment,
+    CommentUpdated,
+    CommentDeleted,
+    ConfirmDeleteComment,
+    NotYourComment,
+    RoleAuthor,
+    RoleReviewer,
+    RoleCommenter,
+    ForcePushed,
+    Refreshed,
+    Loading,
+    BotNoRules,
+    BotDryRun,
+    BotRoundFailed,
+    BotNoMatchingRule,
+    History,
+    HistoryEmpty,
+    Snoozed,
+    NoSnoozedReviews,
+    SnoozedReview,
+    Unsnoozed,
+    UntilUnsnoozed,
+    Until,
+    NoDrafts,
+    NoDraftFor,
+    WroteDraft,
+    ImportedDraft,
+    KeybindProfile,
+    MergeReviewWith,
+    Merging,
+    Merged,
+    ReviewThreads,
+    Conversation,
+    Due,
+    DueAt,
+    DueLeft,
+    DueDateCleared,
+    NoDueDate,
+    NoDueReviews,
+    CalendarWritten,
+    Repositories,
+    NoRepositories,
+    NoRepositoryOwner,
+    OpenReviews,
+    LastPushed,
+    RepositoryDescription,
+    StarredQueue,
+    Starred,
+    Unstarred,
+    StarredInConfig,
+    AuthEnterCode,
+    AuthAt,
+    AuthWaiting,
+    LoggedInAs,
+    LoggedOut,
+    NotLoggedIn,
+    TokenFrom,
+    Resolved,
+    Outdated,
+    NewReview,
+    ReviewTitle,
+    ReviewBody,
+    DraftReview,
+    CreatingReview,
+    CreatedReview,
+    NewReviewHelp,
+    ReviewNeedsTitle,
+    NotConventional,
+    SubjectTooLong,
+    WorkInProgress,
+    CommitLintWarnings,
+    RequestCommitFixes,
+    CommitFixesRequested,
+    NoCommitLintWarnings,
+    ReplyToThread,
+    ReplySent,
+    ThreadResolved,
+    NoThreadSelected,
+    ReviewReassigned,
+    NoTeam,
+    NoCommitter,
+    ApiBudget,
+    OutOfOfficeUntil,
+    OutOfOfficeEnded,
+    NotOutOfOffice,
+    OutOfOfficeNoRules,
+    OutOfOfficeNothingNew,
+    OutOfOfficeDigestSkipped,
+    Summary,
+    Summarizing,
+    NoSummarizer,
+    NoTestsChanged,
+    DocsUpdated,
+    CiModified,
+    LockfileOnly,
+    Discussions,
+    DiscussionsEmpty,
+    DiscussionsHelp,
+    AddComment,
+    CommentAdded,
+    ReviewRequests,
+    EditSearch,
+    SearchSaved,
+    InvalidSearch,
+    CheckWithoutUrl,
+    ReviewWithoutUrl,
+    VerdictApprove,
+    VerdictChanges,
+    VerdictComment,
+    BounceRate,
+    Total,
+    NoVerdicts,
+    WroteQueue,
+    SearchAdded,
+    SearchReplaced,
+    QueueSettingsSet,
+    NoMultiplexer,
+    NoVerifyCommand,
+    UpdateAvailable,
+    UpToDate,
+    UpdatedTo,
+    DataDirReadOnly,
+    ScriptSucceeded,
+    ScriptFailed,
+    Directories,
+    FileTypes,
+    Flaky,
+    PatchCoverage,
+    ProjectCoverage,
+    CoverageTarget,
+    SecurityFixes,
+    DescriptionEmpty,
+    DescriptionMissingSections,
+    DescriptionRequested,
+}
+
+impl Text {
+    fn en(self) -> &'static str {
+        match self {
+            Text::Processing => "processing",
+            Text::FetchFailed => "failed to fetch",
+            Text::GithubPullRequests => "Github pull requests",
+            Text::Owner => "Owner",
+            Text::Repository => "Repository",
+            Text::Title => "Title",
+            Text::DateCreated => "Date created",
+            Text::Author => "Author",
+            Text::Labels => "Labels",
+            Text::Checks => "Checks",
+            Text::Size => "Size",
+            Text::Approvals => "Approvals",
+            Text::ChecksPassed => "checks passed",
+            Text::ChecksFailed => "checks failed",
+            Text::ChecksPending => "checks pending",
+            Text::Review => "Review",
+            Text::ReviewApproved => "approved",
+            Text::ReviewChangesRequested => "changes requested",
+            Text::ReviewRequired => "review required",
+            Text::Draft => "draft",
+            Text::PullRequest => "Pull request",
+            Text::Comments => "comments",
+            Text::StatusChecks => "status checks",
+            Text::Commits => "commits",
+            Text::UpdatedSinceLastVisit => "updated since your last visit, u: show changes",
+            Text::NoChangesSinceLastVisit => "nothing was pushed since your last visit",
+            Text::NotForcePushed => "the branch wasn't force-pushed while shown",
+            Text::NoLinks => "no links in the description or comments",
+            Text::LinkCopied => "copied",
+            Text::NoCommentLink => "the comment has no link to copy",
+            Text::CommentNotFound => "the linked comment isn't among the latest comments of the review",
+            Text::ReviewNotFound => "no pull request at",
+            Text::ChangedFiles => "changed files",
+            Text::NoChangedFiles => "no changed files",
+            Text::Files => "files",
+            Text::FetchingDiff => "fetching the diff...",
+            Text::DoctorGh => "gh",
+            Text::DoctorToken => "token",
+            Text::DoctorApi => "github api",
+            Text::DoctorScopes => "token scopes",
+            Text::DoctorTerminal => "terminal",
+            Text::DoctorConfig => "config",
+            Text::DoctorDataDir => "data directory",
+            Text::DoctorHint => "hint",
+            Text::DoctorInstallGh => "run rev auth login, or install gh from https://cli.github.com and run gh auth login, or set GITHUB_API_TOKEN",
+            Text::DoctorLogin => "run rev auth login or gh auth login, or set GITHUB_API_TOKEN",
+            Text::DoctorTokenRejected => "the token was rejected",
+            Text::DoctorRefreshToken => "the token is expired or revoked, run gh auth refresh or create a new token",
+            Text::DoctorCheckNetwork => "check your network connection and proxy settings, e.g. HTTPS_PROXY",
+            Text::DoctorFineGrainedToken => "fine-grained token, make sure it can read pull requests and organization members",
+            Text::DoctorMissingScopes => "missing",
+            Text::DoctorUsingDefaults => "doesn't exist, using defaults",
+            Text::DoctorFixConfig => "fix the error in rev.kdl, or move it aside and run rev init",
+            Text::DoctorFixDataDir => "make the directory writable, or point REV_DATA_HOME somewhere writable",
+            Text::DoctorFailed => "some checks failed, see the hints above",
+            Text::ApproveReview => "Approve",
+            Text::RequestChangesReview => "Request changes",
+            Text::CommentReview => "Comment",
+            Text::ReviewSubmitted => "review submitted",
+            Text::ReviewNeedsBody => "requesting changes or commenting needs a message",
+            Text::Profiler => "profiler",
+            Text::FrameTime => "frame",
+            Text::EventLoopLatency => "event loop",
+            Text::PendingRequests => "pending requests",
+            Text::CachedResponses => "cached responses",
+            Text::Average => "avg",
+            Text::Maximum => "max",
+            Text::CheckingOut => "checking out the review...",
+            Text::CommitsVerified => "commits verified",
+            Text::Unverified => "unverified",
+            Text::Unsigned => "unsigned",
+            Text::NoDescription => "no description",
+            Text::StatusBarHelp => {
+                "b: begin review, j/k: select, J/K: move, p: pin, *: star repository, /: filter, 1-9: saved filters, z: sort by size, N: search, tab: next queue, v: density, H: history, D: discussions, O: repositories, s: skip, Z: snooze, U: snoozed, w: wait for checks, f: toggle comment filters, q: quit"
+            }
+            Text::RunningGitDiff => "[ Running: git diff ]",
+            Text::DiffHelp => "j/k: scroll, ctrl-d/ctrl-u: half page, gg/G: top/bottom, /: search, n/N: next/previous match, esc: back",
+            Text::PatternNotFound => "pattern not found",
+            Text::ConfigAlreadyExists => "config file already exists at",
+            Text::UseForceToOverride => {
+                "Use --force to override, be careful you may want to back up your config first"
+            }
+            Text::WroteConfig => "wrote config to",
+            Text::DigestNewRequests => "New review requests",
+            Text::DigestWaitingLongest => "Waiting longest for your review",
+            Text::DigestMyPullRequests => "Your pull requests waiting on others",
+            Text::DigestNothing => "nothing here",
+            Text::NoPendingChecks => "no pending checks to wait for",
+            Text::SnoozedUntilChecksComplete => "snoozed until checks complete",
+            Text::ChecksCompleted => "checks completed",
+            Text::ChecksWaitFailed => "stopped waiting for checks",
+            Text::Hidden => "hidden",
+            Text::MoreLines => "more lines",
+            Text::Edited => "edited",
+            Text::You => "you",
+            Text::EditComment => "edit comment",
+            Text::CommentUpdated => "comment updated",
+            Text::CommentDeleted => "comment deleted",
+            Text::ConfirmDeleteComment => "press d again to delete the comment",
+            Text::NotYourComment => "you can only change your own comments",
+            Text::RoleAuthor => "author",
+            Text::RoleReviewer => "reviewer",
+            Text::RoleCommenter => "commenter",
+            Text::ForcePushed => {
+                "branch was force-pushed, diff may be stale (R: refresh, F: compare)"
+            }
+            Text::Refreshed => "refreshed review",
+            Text::Loading => "loading",
+            Text::BotNoRules => "no bot rules configured, add a bot block to",
+            Text::BotDryRun => "dry run, nothing was changed",
+            Text::BotRoundFailed => "the bot failed, trying again on the next round",
+            Text::BotNoMatchingRule => "no matching rule",
+            Text::History => "history (H: back)",
+            Text::HistoryEmpty => "nothing has been changed through rev yet",
+            Text::Snoozed => "snoozed (enter: unsnooze, U: back)",
+            Text::NoSnoozedReviews => "no snoozed reviews",
+            Text::SnoozedReview => "snoozed",
+            Text::Unsnoozed => "unsnoozed",
+            Text::UntilUnsnoozed => "until unsnoozed",
+            Text::Until => "until",
+            Text::NoDrafts => "no unsubmitted drafts",
+            Text::NoDraftFor => "no draft for",
+            Text::WroteDraft => "wrote draft to",
+            Text::ImportedDraft => "imported draft of",
+            Text::KeybindProfile => "keys",
+            Text::MergeReviewWith => "merge",
+            Text::Merging => "merging",
+            Text::Merged => "merged",
+            Text::ReviewThreads => "review threads",
+            Text::Conversation => "conversation",
+            Text::Due => "Due",
+            Text::DueAt => "due",
+            Text::DueLeft => "left",
+            Text::DueDateCleared => "due date cleared",
+            Text::NoDueDate => "had no due date",
+            Text::NoDueReviews => "no reviews are due",
+            Text::CalendarWritten => "calendar written to",
+            Text::Repositories => "repositories (enter: open reviews, O: back)",
+            Text::NoRepositories => "no repositories",
+            Text::NoRepositoryOwner => "set org in the queue block of rev.kdl to browse its repositories",
+            Text::OpenReviews => "Open reviews",
+            Text::LastPushed => "Last pushed",
+            Text::RepositoryDescription => "Description",
+            Text::StarredQueue => "starred",
+            Text::Starred => "starred",
+            Text::Unstarred => "unstarred",
+            Text::StarredInConfig => "is starred in rev.kdl",
+            Text::AuthEnterCode => "enter the code",
+            Text::AuthAt => "at",
+            Text::AuthWaiting => "waiting for the code to be entered...",
+            Text::LoggedInAs => "logged in as",
+            Text::LoggedOut => "removed the stored token",
+            Text::NotLoggedIn => "no token was stored by rev auth login",
+            Text::TokenFrom => "token from",
+            Text::Resolved => "resolved",
+            Text::Outdated => "outdated",
+            Text::NewReview => "new pull request",
+            Text::ReviewTitle => "title",
+            Text::ReviewBody => "description",
+            Text::DraftReview => "draft",
+            Text::CreatingReview => "creating the pull request...",
+            Text::CreatedReview => "created",
+            Text::NewReviewHelp => {
+                "tab: next field, ctrl-d: toggle draft, ctrl-o: $EDITOR, ctrl-s: create, esc: quit"
+            }
+            Text::ReviewNeedsTitle => "the pull request needs a title",
+            Text::NotConventional => "not a conventional commit",
+            Text::SubjectTooLong => "subject too long",
+            Text::WorkInProgress => "work in progress",
+            Text::CommitLintWarnings => "lint warnings, C: ask the author to fix them",
+            Text::RequestCommitFixes => "ask the author to fix the commits",
+            Text::CommitFixesRequested => "asked the author to fix the commits",
+            Text::NoCommitLintWarnings => "the commits follow the lint rules",
+            Text::ReplyToThread => "reply to thread",
+            Text::ReplySent => "replied to the thread",
+            Text::ThreadResolved => "resolved the thread",
+            Text::NoThreadSelected => "no thread selected",
+            Text::ReviewReassigned => "reassigned the review to",
+            Text::NoTeam => "add the members of your team to the team block in rev.kdl",
+            Text::NoCommitter => "set committer in rev.kdl to reassign reviews",
+            Text::ApiBudget => "api",
+            Text::OutOfOfficeUntil => "out of office through",
+            Text::OutOfOfficeEnded => "welcome back, out of office is off",
+            Text::NotOutOfOffice => "not out of office",
+            Text::OutOfOfficeNoRules => {
+                "no out-of-office rules configured, add an out-of-office block to"
+            }
+            Text::OutOfOfficeNothingNew => "no new review requests",
+            Text::OutOfOfficeDigestSkipped => "out of office, the digest isn't sent",
+            Text::Summary => "Summary",
+            Text::Summarizing => "summarizing...",
+            Text::NoSummarizer => "set a summarizer command in rev.kdl to summarize reviews",
+            Text::NoTestsChanged => "no tests changed",
+            Text::DocsUpdated => "docs updated",
+            Text::CiModified => "CI config modified",
+            Text::LockfileOnly => "lockfile only",
+            Text::Discussions => "discussions (D: back)",
+            Text::DiscussionsEmpty => "no issues match the discussions query",
+            Text::DiscussionsHelp => {
+                "j/k: select, enter: open, esc: back to list, c: comment, ]/[: comments, R: refresh, D: back"
+            }
+            Text::AddComment => "add comment",
+            Text::CommentAdded => "added comment",
+            Text::ReviewRequests => "review requests",
+            Text::EditSearch => "search (name: query)",
+            Text::SearchSaved => "saved search, matching",
+            Text::InvalidSearch => "search needs a query",
+            Text::CheckWithoutUrl => "the check has no link",
+            Text::ReviewWithoutUrl => "the review has no link",
+            Text::VerdictApprove => "approve",
+            Text::VerdictChanges => "changes",
+            Text::VerdictComment => "comment",
+            Text::BounceRate => "bounced",
+            Text::Total => "Total",
+            Text::NoVerdicts => "no reviews submitted through rev yet",
+            Text::WroteQueue => "wrote the queue to",
+            Text::SearchAdded => "added search",
+            Text::SearchReplaced => "replaced search",
+            Text::QueueSettingsSet => "set in the queue block:",
+            Text::NoMultiplexer => "panes need rev to run in tmux or wezterm",
+            Text::NoVerifyCommand => "no verify command in the panes block of rev.kdl",
+            Text::UpdateAvailable => "run git-rev self-update to update to",
+            Text::UpToDate => "already on the latest version",
+            Text::UpdatedTo => "updated git-rev to",
+            Text::DataDirReadOnly => "data dir isn't writable, changes are kept until rev quits",
+            Text::ScriptSucceeded => "succeeded",
+            Text::ScriptFailed => "failed",
+            Text::Directories => "Directories",
+            Text::FileTypes => "File types",
+            Text::Flaky => "flaky",
+            Text::PatchCoverage => "patch coverage",
+            Text::ProjectCoverage => "coverage",
+            Text::CoverageTarget => "target",
+            Text::SecurityFixes => "fixes known vulnerabilities",
+            Text::DescriptionEmpty => "description is empty (T: ask the author to fill it in)",
+            Text::DescriptionMissingSections => {
+                "description is missing sections of the template (T: ask the author to fill them in)"
+            }
+            Text::DescriptionRequested => "asked the author to fill in the description",
+        }
+    }
+
+    fn da(self) -> &'static str {
+        match self {
+            Text::Processing => "henter",
+            Text::FetchFailed => "kunne ikke hente",
+            Text::GithubPullRequests => "Github pull requests",
+            Text::Owner => "Ejer",
+            Text::Repository => "Repository",
+            Text::Title => "Titel",
+            Text::DateCreated => "Oprettet",
+            Text::Author => "Forfatter",
+            Text::Labels => "Labels",
+            Text::Checks => "Tjek",
+            Text::Size => "Størrelse",
+            Text::Approvals => "Godkendelser",
+            Text::ChecksPassed => "tjek bestået",
+            Text::ChecksFailed => "tjek fejlet",
+            Text::ChecksPending => "tjek afventer",
+            Text::Review => "Review",
+            Text::ReviewApproved => "godkendt",
+            Text::ReviewChangesRequested => "ændringer ønsket",
+            Text::ReviewRequired => "mangler review",
+            Text::Draft => "kladde",
+            Text::PullRequest => "Pull request",
+            Text::Comments => "kommentarer",
+            Text::StatusChecks => "statustjek",
+            Text::Commits => "commits",
+            Text::UpdatedSinceLastVisit => "opdateret siden dit sidste besøg, u: vis ændringer",
+            Text::NoChangesSinceLastVisit => "intet er pushet siden dit sidste besøg",
+            Text::NotForcePushed => "branchen blev ikke force-pushed mens den blev vist",
+            Text::NoLinks => "ingen links i beskrivelsen eller kommentarerne",
+            Text::LinkCopied => "kopierede",
+            Text::NoCommentLink => "kommentaren har intet link at kopiere",
+            Text::CommentNotFound => "den linkede kommentar er ikke blandt de seneste kommentarer på reviewet",
+            Text::ReviewNotFound => "ingen pull request på",
+            Text::ChangedFiles => "ændrede filer",
+            Text::NoChangedFiles => "ingen ændrede filer",
+            Text::Files => "filer",
+            Text::FetchingDiff => "henter diffen...",
+            Text::DoctorGh => "gh",
+            Text::DoctorToken => "token",
+            Text::DoctorApi => "github api",
+            Text::DoctorScopes => "token scopes",
+            Text::DoctorTerminal => "terminal",
+            Text::DoctorConfig => "konfiguration",
+            Text::DoctorDataDir => "datamappe",
+            Text::DoctorHint => "tip",
+            Text::DoctorInstallGh => "kør rev auth login, eller installér gh fra https://cli.github.com og kør gh auth login, eller sæt GITHUB_API_TOKEN",
+            Text::DoctorLogin => "kør rev auth login eller gh auth login, eller sæt GITHUB_API_TOKEN",
+            Text::DoctorTokenRejected => "tokenet blev afvist",
+            Text::DoctorRefreshToken => "tokenet er udløbet eller tilbagekaldt, kør gh auth refresh eller opret et nyt token",
+            Text::DoctorCheckNetwork => "tjek din netværksforbindelse og proxyindstillinger, f.eks. HTTPS_PROXY",
+            Text::DoctorFineGrainedToken => "fine-grained token, sørg for at det kan læse pull requests og organisationsmedlemmer",
+            Text::DoctorMissingScopes => "mangler",
+            Text::DoctorUsingDefaults => "findes ikke, bruger standardindstillinger",
+            Text::DoctorFixConfig => "ret fejlen i rev.kdl, eller flyt den væk og kør rev init",
+            Text::DoctorFixDataDir => "gør mappen skrivbar, eller peg REV_DATA_HOME på en skrivbar mappe",
+            Text::DoctorFailed => "nogle tjek fejlede, se tipsene ovenfor",
+            Text::ApproveReview => "Godkend",
+            Text::RequestChangesReview => "Anmod om ændringer",
+            Text::CommentReview => "Kommentér",
+            Text::ReviewSubmitted => "review indsendt",
+            Text::ReviewNeedsBody => "at anmode om ændringer eller kommentere kræver en besked",
+            Text::Profiler => "profiler",
+            Text::FrameTime => "frame",
+            Text::EventLoopLatency => "event loop",
+            Text::PendingRequests => "ventende forespørgsler",
+            Text::CachedResponses => "cachede svar",
+            Text::Average => "gns",
+            Text::Maximum => "maks",
+            Text::CheckingOut => "henter reviewet...",
+            Text::CommitsVerified => "commits verificeret",
+            Text::Unverified => "ikke verificeret",
+            Text::Unsigned => "ikke signeret",
+            Text::NoDescription => "ingen beskrivelse",
+            Text::StatusBarHelp => "b: start review, j/k: vælg, J/K: flyt, p: fastgør, *: stjernemarkér repository, /: filtrér, 1-9: gemte filtre, z: sortér efter størrelse, N: søg, tab: næste kø, v: tæthed, H: historik, D: diskussioner, O: repositories, s: spring over, Z: udsæt, U: udsatte, w: vent på tjek, f: skift kommentarfiltre, q: afslut",
+            Text::RunningGitDiff => "[ Kører: git diff ]",
+            Text::DiffHelp => "j/k: rul, ctrl-d/ctrl-u: halv side, gg/G: top/bund, /: søg, n/N: næste/forrige match, esc: tilbage",
+            Text::PatternNotFound => "mønsteret blev ikke fundet",
+            Text::ConfigAlreadyExists => "konfigurationsfilen findes allerede i",
+            Text::UseForceToOverride => {
+                "Brug --force for at overskrive, overvej at tage en kopi af din konfiguration først"
+            }
+            Text::WroteConfig => "skrev konfiguration til",
+            Text::DigestNewRequests => "Nye review anmodninger",
+            Text::DigestWaitingLongest => "Har ventet længst på dit review",
+            Text::DigestMyPullRequests => "Dine pull requests der venter på andre",
+            Text::DigestNothing => "intet her",
+            Text::NoPendingChecks => "ingen ventende tjek at vente på",
+            Text::SnoozedUntilChecksComplete => "udsat indtil tjek er færdige",
+            Text::ChecksCompleted => "tjek færdige",
+            Text::ChecksWaitFailed => "stoppede med at vente på tjek",
+            Text::Hidden => "skjult",
+            Text::MoreLines => "linjer mere",
+            Text::Edited => "redigeret",
+            Text::You => "dig",
+            Text::EditComment => "rediger kommentar",
+            Text::CommentUpdated => "kommentar opdateret",
+            Text::CommentDeleted => "kommentar slettet",
+            Text::ConfirmDeleteComment => "tryk d igen for at slette kommentaren",
+            Text::NotYourComment => "du kan kun ændre dine egne kommentarer",
+            Text::RoleAuthor => "forfatter",
+            Text::RoleReviewer => "reviewer",
+            Text::RoleCommenter => "kommentator",
+            Text::ForcePushed => {
+                "branchen blev force-pushed, diff kan være forældet (R: genindlæs, F: sammenlign)"
+            }
+            Text::Refreshed => "review genindlæst",
+            Text::Loading => "indlæser",
+            Text::BotNoRules => "ingen bot-regler konfigureret, tilføj en bot-blok til",
+            Text::BotDryRun => "prøvekørsel, intet blev ændret",
+            Text::BotRoundFailed => "botten fejlede, prøver igen i næste runde",
+            Text::BotNoMatchingRule => "ingen regel passer",
+            Text::History => "historik (H: tilbage)",
+            Text::HistoryEmpty => "intet er blevet ændret gennem rev endnu",
+            Text::Snoozed => "udsat (enter: hent tilbage, U: tilbage)",
+            Text::NoSnoozedReviews => "ingen udsatte reviews",
+            Text::SnoozedReview => "udsatte",
+            Text::Unsnoozed => "hentede tilbage",
+            Text::UntilUnsnoozed => "indtil den hentes tilbage",
+            Text::Until => "indtil",
+            Text::NoDrafts => "ingen kladder der mangler at blive indsendt",
+            Text::NoDraftFor => "ingen kladde til",
+            Text::WroteDraft => "skrev kladden til",
+            Text::ImportedDraft => "importerede kladden til",
+            Text::KeybindProfile => "taster",
+            Text::MergeReviewWith => "flet",
+            Text::Merging => "fletter",
+            Text::Merged => "flettede",
+            Text::ReviewThreads => "review-tråde",
+            Text::Conversation => "samtale",
+            Text::Due => "Frist",
+            Text::DueAt => "frist",
+            Text::DueLeft => "tilbage",
+            Text::DueDateCleared => "frist fjernet",
+            Text::NoDueDate => "havde ingen frist",
+            Text::NoDueReviews => "ingen reviews har frist",
+            Text::CalendarWritten => "kalender skrevet til",
+            Text::Repositories => "repositories (enter: åbne reviews, O: tilbage)",
+            Text::NoRepositories => "ingen repositories",
+            Text::NoRepositoryOwner => "angiv org i queue-blokken i rev.kdl for at gennemse dens repositories",
+            Text::OpenReviews => "Åbne reviews",
+            Text::LastPushed => "Seneste push",
+            Text::RepositoryDescription => "Beskrivelse",
+            Text::StarredQueue => "stjernemarkerede",
+            Text::Starred => "stjernemarkerede",
+            Text::Unstarred => "fjernede stjernen fra",
+            Text::StarredInConfig => "er stjernemarkeret i rev.kdl",
+            Text::AuthEnterCode => "indtast koden",
+            Text::AuthAt => "på",
+            Text::AuthWaiting => "venter på at koden bliver indtastet...",
+            Text::LoggedInAs => "logget ind som",
+            Text::LoggedOut => "fjernede det gemte token",
+            Text::NotLoggedIn => "intet token er gemt af rev auth login",
+            Text::TokenFrom => "token fra",
+            Text::Resolved => "løst",
+            Text::Outdated => "forældet",
+            Text::NewReview => "ny pull request",
+            Text::ReviewTitle => "titel",
+            Text::ReviewBody => "beskrivelse",
+            Text::DraftReview => "kladde",
+            Text::CreatingReview => "opretter pull requesten...",
+            Text::CreatedReview => "oprettede",
+            Text::NewReviewHelp => {
+                "tab: næste felt, ctrl-d: skift kladde, ctrl-o: $EDITOR, ctrl-s: opret, esc: afslut"
+            }
+            Text::ReviewNeedsTitle => "pull requesten mangler en titel",
+            Text::NotConventional => "ikke et conventional commit",
+            Text::SubjectTooLong => "emnelinjen er for lang",
+            Text::WorkInProgress => "arbejde i gang",
+            Text::CommitLintWarnings => "lint-advarsler, C: bed forfatteren om at rette dem",
+            Text::RequestCommitFixes => "bed forfatteren om at rette commits",
+            Text::CommitFixesRequested => "bad forfatteren om at rette commits",
+            Text::NoCommitLintWarnings => "commits følger lint-reglerne",
+            Text::ReplyToThread => "svar på tråd",
+            Text::ReplySent => "svarede på tråden",
+            Text::ThreadResolved => "løste tråden",
+            Text::NoThreadSelected => "ingen tråd valgt",
+            Text::ReviewReassigned => "gav reviewet videre til",
+            Text::NoTeam => "tilføj medlemmerne af dit team i team-blokken i rev.kdl",
+            Text::NoCommitter => "angiv committer i rev.kdl for at give reviews videre",
+            Text::ApiBudget => "api",
+            Text::OutOfOfficeUntil => "fraværende til og med",
+            Text::OutOfOfficeEnded => "velkommen tilbage, fravær er slået fra",
+            Text::NotOutOfOffice => "ikke fraværende",
+            Text::OutOfOfficeNoRules => "ingen fraværsregler konfigureret, tilføj en out-of-office-blok til",
+            Text::OutOfOfficeNothingNew => "ingen nye review-anmodninger",
+            Text::OutOfOfficeDigestSkipped => "fraværende, oversigten sendes ikke",
+            Text::Summary => "Opsummering",
+            Text::Summarizing => "opsummerer...",
+            Text::NoSummarizer => "angiv en summarizer-kommando i rev.kdl for at opsummere reviews",
+            Text::NoTestsChanged => "ingen tests ændret",
+            Text::DocsUpdated => "dokumentation opdateret",
+            Text::CiModified => "CI-opsætning ændret",
+            Text::LockfileOnly => "kun lockfiler",
+            Text::Discussions => "diskussioner (D: tilbage)",
+            Text::DiscussionsEmpty => "ingen issues passer til diskussionssøgningen",
+            Text::DiscussionsHelp => {
+                "j/k: vælg, enter: åbn, esc: tilbage til listen, c: kommentér, ]/[: kommentarer, R: genindlæs, D: tilbage"
+            }
+            Text::AddComment => "tilføj kommentar",
+            Text::CommentAdded => "kommentar tilføjet",
+            Text::ReviewRequests => "review-anmodninger",
+            Text::EditSearch => "søgning (navn: forespørgsel)",
+            Text::SearchSaved => "søgning gemt, antal resultater",
+            Text::InvalidSearch => "søgningen mangler en forespørgsel",
+            Text::CheckWithoutUrl => "tjekket har intet link",
+            Text::ReviewWithoutUrl => "reviewet har intet link",
+            Text::VerdictApprove => "godkendt",
+            Text::VerdictChanges => "ændringer",
+            Text::VerdictComment => "kommentar",
+            Text::BounceRate => "afvist",
+            Text::Total => "I alt",
+            Text::NoVerdicts => "ingen reviews indsendt gennem rev endnu",
+            Text::WroteQueue => "skrev køen til",
+            Text::SearchAdded => "tilføjede søgning",
+            Text::SearchReplaced => "erstattede søgning",
+            Text::QueueSettingsSet => "sat i queue-blokken:",
+            Text::NoMultiplexer => "paneler kræver at rev kører i tmux eller wezterm",
+            Text::NoVerifyCommand => "ingen verify-kommando i panes-blokken i rev.kdl",
+            Text::UpdateAvailable => "kør git-rev self-update for at opdatere til",
+            Text::UpToDate => "allerede på den nyeste version",
+            Text::UpdatedTo => "opdaterede git-rev til",
+            Text::DataDirReadOnly => "datamappen er skrivebeskyttet, ændringer gemmes til rev lukkes",
+            Text::ScriptSucceeded => "lykkedes",
+            Text::ScriptFailed => "fejlede",
+            Text::Directories => "Mapper",
+            Text::FileTypes => "Filtyper",
+            Text::Flaky => "ustabil",
+            Text::PatchCoverage => "dækning af ændringer",
+            Text::ProjectCoverage => "dækning",
+            Text::CoverageTarget => "mål",
+            Text::SecurityFixes => "retter kendte sårbarheder",
+            Text::DescriptionEmpty => "beskrivelsen er tom (T: bed forfatteren om at udfylde den)",
+            Text::DescriptionMissingSections => {
+                "beskrivelsen mangler afsnit fra skabelonen (T: bed forfatteren om at udfylde dem)"
+            }
+            Text::DescriptionRequested => "bad forfatteren om at udfylde beskrivelsen",
+        }
+    }
+
+    pub fn in_locale(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => self.en(),
+            Locale::Da => self.da(),
+        }
+    }
+}
+
+/// Translates a piece of interface text into the active locale.
+pub fn tr(text: Text) -> &'static str {
+    text.in_locale(locale())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(Locale::Da, Locale::parse("da_DK.UTF-8"));
+        assert_eq!(Locale::Da, Locale::parse("da"));
+        assert_eq!(Locale::En, Locale::parse("en_US.UTF-8"));
+        assert_eq!(Locale::En, Locale::parse("C"));
+        assert_eq!(None, Locale::try_parse("C"));
+    }
+
+    #[test]
+    fn test_locale_from_env() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        assert_eq!(
+            (None, Locale::Da),
+            Locale::from_env(env(&[("LANG", "da_DK.UTF-8")]))
+        );
+        assert_eq!(
+            (Some(Locale::En), Locale::Da),
+            Locale::from_env(env(&[("REV_LOCALE", "en"), ("LC_ALL", "da_DK.UTF-8")]))
+        );
+    }
+
+    #[test]
+    fn test_translates() {
+        assert_eq!("Title", Text::Title.in_locale(Locale::En));
+        assert_eq!("Titel", Text::Title.in_locale(Locale::Da));
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::Context;
+use kdl::KdlNode;
+use rev_git_provider::{
+    models::{Label, Review, ReviewEvent},
+    GitProvider,
+};
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    config::kdl_strings,
+    store::Store,
+};
+
+/// Labels changed on a review after it's submitted with a verdict, keeping board automations in
+/// step with reviews.
+///
+/// ```kdl
+/// labels {
+///     on "request-changes" {
+///         add "needs-author-response"
+///         remove "approved"
+///     }
+///     on "approve" {
+///         add "approved"
+///         remove "needs-author-response"
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelRule {
+    pub event: ReviewEvent,
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl LabelRule {
+    pub fn from_kdl(node: &KdlNode) -> anyhow::Result<Self> {
+        let event = match node.entries().first().and_then(|e| e.value().as_string()) {
+            Some("approve") => ReviewEvent::Approve,
+            Some("request-changes") => ReviewEvent::RequestChanges,
+            Some("comment") => ReviewEvent::Comment,
+            _ => anyhow::bail!(
+                "label rules must be for approve, request-changes or comment, e.g. on \"approve\" {{}}"
+            ),
+        };
+        let doc = node.children().context(
+            "label rules need an add or remove, e.g. on \"approve\" { add \"approved\"; }",
+        )?;
+
+        Ok(Self {
+            event,
+            add: kdl_strings(doc, "add")?.unwrap_or_default(),
+            remove: kdl_strings(doc, "remove")?.unwrap_or_default(),
+        })
+    }
+}
+
+/// The labels to add and remove for the verdict, leaving out changes which wouldn't do anything.
+fn changes(
+    rules: &[LabelRule],
+    event: ReviewEvent,
+    labels: &[Label],
+) -> (Vec<String>, Vec<String>) {
+    let has = |label: &String| labels.iter().any(|l| l.name.eq_ignore_ascii_case(label));
+    let rules = rules.iter().filter(|r| r.event == event);
+
+    let mut add = Vec::new();
+    let mut remove = Vec::new();
+    for rule in rules {
+        add.extend(rule.add.iter().filter(|l| !has(l)).cloned());
+        remove.extend(rule.remove.iter().filter(|l| has(l)).cloned());
+    }
+    add.dedup();
+    remove.dedup();
+
+    (add, remove)
+}
+
+/// Changes the labels of a review submitted with the verdict, recording each change in the audit
+/// log as made by the source, e.g. `bot`.
+pub async fn apply(
+    provider: &GitProvider,
+    store: &Store,
+    source: &str,
+    rules: &[LabelRule],
+    review: &Review,
+    event: ReviewEvent,
+) -> anyhow::Result<()> {
+    let (add, remove) = changes(rules, event, &review.labels);
+    let (owner, name) = review
+        .owner_and_name()
+        .context("review repository to be in the form owner/name")?;
+
+    if !add.is_empty() {
+        let result = provider
+            .add_labels(
+                review.id.clone(),
+                owner.to_string(),
+                name.to_string(),
+                add.clone(),
+            )
+            .await;
+        record(store, source, "label-add", review, &add, &result)?;
+        result?;
+    }
+
+    if !remove.is_empty() {
+        let result = provider
+            .remove_labels(
+                review.id.clone(),
+                owner.to_string(),
+                name.to_string(),
+                remove.clone(),
+            )
+            .await;
+        record(store, source, "label-remove", review, &remove, &result)?;
+        result?;
+    }
+
+    Ok(())
+}
+
+fn record(
+    store: &Store,
+    source: &str,
+    action: &str,
+    review: &Review,
+    labels: &[String],
+    result: &anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    AuditLog::record(
+        store,
+        AuditEntry::new(source, action, review.reference())
+            .parameter("labels", labels.join(","))
+            .result(result),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use kdl::KdlDocument;
+    use rev_git_provider::models::{Label, ReviewEvent};
+
+    use super::{changes, LabelRule};
+
+    #[test]
+    fn test_parse_and_pick_changes() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+on "request-changes" {
+    add "needs-author-response"
+    remove "approved"
+}
+on "approve" {
+    add "approved"
+    remove "needs-author-response"
+}
+        "#
+        .parse()?;
+        let rules = doc
+            .nodes()
+            .iter()
+            .map(LabelRule::from_kdl)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        assert_eq!(ReviewEvent::RequestChanges, rules[0].event);
+
+        let labels = vec![Label::new("Approved"), Label::new("backend")];
+        assert_eq!(
+            (
+                vec!["needs-author-response".to_string()],
+                vec!["approved".to_string()]
+            ),
+            changes(&rules, ReviewEvent::RequestChanges, &labels)
+        );
+        assert_eq!(
+            (Vec::<String>::new(), Vec::<String>::new()),
+            changes(&rules, ReviewEvent::Approve, &labels)
+        );
+
+        assert!("on \"merge\" {\n    add \"x\"\n}"
+            .parse::<KdlDocument>()?
+            .nodes()
+            .iter()
+            .map(LabelRule::from_kdl)
+            .all(|r| r.is_err()));
+
+        Ok(())
+    }
+}
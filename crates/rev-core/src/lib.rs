@@ -0,0 +1,66 @@
+//! The domain logic and terminal ui framework behind `git-rev`.
+//!
+//! - [`app::App`] drives a set of [`page::Page`]s, each made up of
+//!   [`components::Component`]s, which talk to each other by sending [`action::Action`]s over a
+//!   channel.
+//! - [`git_pull_requests`] turns the paginated api of a [`rev_git_provider::GitProvider`] into
+//!   streams of reviews to go through, ordered by the [`review_plan::ReviewPlan`].
+//! - [`state::State`] holds the reviews and the session the pages share, kept up to date by the
+//!   actions.
+//! - [`store::Store`], [`config::Config`] and [`platform`] hold local state, settings and the
+//!   platform specific bits.
+//!
+//! Frontends other than the terminal, such as a bot, can use the services without the ui.
+
+pub mod action;
+pub mod app;
+pub mod application_config;
+pub mod audit;
+pub mod bot;
+pub mod check_history;
+pub mod commit_lint;
+pub mod components;
+pub mod config;
+pub mod coverage;
+pub mod dependencies;
+pub mod description_lint;
+pub mod diff_stats;
+pub mod digest;
+pub mod doctor;
+pub mod drafts;
+pub mod due_dates;
+pub mod embed;
+pub mod file_badges;
+pub mod fuzzy;
+pub mod git_issues;
+pub mod git_pull_requests;
+pub mod glob;
+pub mod i18n;
+pub mod labels;
+pub mod links;
+pub mod list_filters;
+pub mod listing;
+pub mod metrics;
+pub mod multiplexer;
+pub mod new_review;
+pub mod out_of_office;
+pub mod page;
+pub mod patch;
+pub mod platform;
+pub mod queue_share;
+pub mod review_plan;
+pub mod saved_searches;
+pub mod script;
+pub mod self_update;
+pub mod share;
+pub mod snooze;
+pub mod starred;
+pub mod state;
+pub mod stats;
+pub mod store;
+pub mod summarizer;
+pub mod terminal;
+pub mod theme;
+pub mod tui;
+pub mod visits;
+pub mod workspace;
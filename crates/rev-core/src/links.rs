@@ -0,0 +1,214 @@
+use std::ops::Range;
+
+/// Something in a description or comment which can be opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    Url(String),
+    /// A pull request or issue, written as `owner/name#number`, `#number` for the repository of
+    /// the text, or as its url on github.
+    Review {
+        repository: Option<String>,
+        number: usize,
+    },
+    Commit(String),
+}
+
+impl Link {
+    /// The url of the link on github, relative references resolved against the repository.
+    pub fn url(&self, repository: &str) -> String {
+        match self {
+            Link::Url(url) => url.clone(),
+            Link::Review {
+                repository: other,
+                number,
+            } => format!(
+                "https://github.com/{}/issues/{number}",
+                other.as_deref().unwrap_or(repository)
+            ),
+            Link::Commit(sha) => format!("https://github.com/{repository}/commit/{sha}"),
+        }
+    }
+}
+
+/// Finds the links in the text, returning their byte ranges in order.
+pub fn find_links(text: &str) -> Vec<(Range<usize>, Link)> {
+    let mut links = Vec::new();
+
+    let mut start = 0;
+    for word in text.split_inclusive(|c: char| c.is_whitespace()) {
+        let trimmed = word.trim_end();
+        // Punctuation around a link belongs to the sentence, e.g. `(see #12).`
+        let leading = trimmed.len() - trimmed.trim_start_matches(['(', '[', '<', '"', '\'']).len();
+        let candidate = trimmed[leading..]
+            .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '>', '"', '\'']);
+
+        if let Some(link) = parse_link(candidate) {
+            let begin = start + leading;
+            links.push((begin..begin + candidate.len(), link));
+        }
+
+        start += word.len();
+    }
+
+    links
+}
+
+fn parse_link(word: &str) -> Option<Link> {
+    if word.starts_with("https://") || word.starts_with("http://") {
+        return Some(github_review(word).unwrap_or_else(|| Link::Url(word.to_string())));
+    }
+
+    if let Some((repository, number)) = word.split_once('#') {
+        let number = number.parse().ok().filter(|n| *n > 0)?;
+        if repository.is_empty() {
+            return Some(Link::Review {
+                repository: None,
+                number,
+            });
+        }
+
+        let (owner, name) = repository.split_once('/')?;
+        let valid = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        return (valid(owner) && valid(name)).then(|| Link::Review {
+            repository: Some(repository.to_string()),
+            number,
+        });
+    }
+
+    // Short shas are at least 7 characters, words like `deadbeef` are left alone
+    let is_sha = (7..=40).contains(&word.len())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase())
+        && word.chars().any(|c| c.is_ascii_digit())
+        && word.chars().any(|c| c.is_ascii_alphabetic());
+    is_sha.then(|| Link::Commit(word.to_string()))
+}
+
+/// Pull request and issue urls on github, so they can be opened in rev.
+fn github_review(url: &str) -> Option<Link> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+    let parts = path.split(['/', '#', '?']).collect::<Vec<_>>();
+
+    match parts.as_slice() {
+        [owner, name, "pull" | "issues", number, ..] => Some(Link::Review {
+            repository: Some(format!("{owner}/{name}")),
+            number: number.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// A review to open, optionally at one of its comments, as given to `rev review`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewTarget {
+    /// The repository in the form `owner/name`.
+    pub repository: String,
+    pub number: usize,
+    /// The anchor of the comment to focus, see [`comment_anchor`].
+    pub comment: Option<u64>,
+}
+
+impl ReviewTarget {
+    /// Parses `owner/name#number`, or the url of a pull request or of one of its comments.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (repository, number) = match parse_link(raw.trim())? {
+            Link::Review {
+                repository: Some(repository),
+                number,
+            } => (repository, number),
+            _ => return None,
+        };
+
+        Some(Self {
+            repository,
+            number,
+            comment: comment_anchor(raw),
+        })
+    }
+}
+
+/// The id in the anchor of a comment permalink, e.g. `123` of `#issuecomment-123` for comments on
+/// the conversation and of `#discussion_r123` for comments on lines of the diff.
+pub fn comment_anchor(url: &str) -> Option<u64> {
+    let (_, fragment) = url.rsplit_once('#')?;
+    let id = ["issuecomment-", "discussion_r", "comment-", "r"]
+        .iter()
+        .find_map(|prefix| fragment.strip_prefix(prefix))?;
+
+    id.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{comment_anchor, find_links, Link, ReviewTarget};
+
+    #[test]
+    fn test_find_links() {
+        let text = "Fixes #12 and kjuulh/rev#3 (see https://example.com/a?b=1).\nReverts 3f2a9c1, https://github.com/kjuulh/rev/pull/7/files";
+
+        let links = find_links(text);
+
+        assert_eq!(
+            vec![
+                Link::Review {
+                    repository: None,
+                    number: 12
+                },
+                Link::Review {
+                    repository: Some("kjuulh/rev".into()),
+                    number: 3
+                },
+                Link::Url("https://example.com/a?b=1".into()),
+                Link::Commit("3f2a9c1".into()),
+                Link::Review {
+                    repository: Some("kjuulh/rev".into()),
+                    number: 7
+                },
+            ],
+            links.iter().map(|(_, l)| l.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!("https://example.com/a?b=1", &text[links[2].0.clone()]);
+        assert!(find_links("a deadbeef and #0 or 1234567").is_empty());
+    }
+
+    #[test]
+    fn test_parse_review_target() {
+        let target = |repository: &str, number, comment| ReviewTarget {
+            repository: repository.into(),
+            number,
+            comment,
+        };
+
+        assert_eq!(
+            Some(target("kjuulh/rev", 12, None)),
+            ReviewTarget::parse("kjuulh/rev#12")
+        );
+        assert_eq!(
+            Some(target("kjuulh/rev", 12, Some(1234))),
+            ReviewTarget::parse("https://github.com/kjuulh/rev/pull/12#issuecomment-1234")
+        );
+        assert_eq!(
+            Some(target("kjuulh/rev", 12, Some(98))),
+            ReviewTarget::parse("https://github.com/kjuulh/rev/pull/12/files#r98")
+        );
+        assert_eq!(None, ReviewTarget::parse("#12"));
+        assert_eq!(None, ReviewTarget::parse("https://example.com/a"));
+
+        assert_eq!(
+            Some(7),
+            comment_anchor("https://github.com/kjuulh/rev/pull/1#discussion_r7")
+        );
+        assert_eq!(
+            None,
+            comment_anchor("https://github.com/kjuulh/rev/pull/1#readme")
+        );
+    }
+}
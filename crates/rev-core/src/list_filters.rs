@@ -0,0 +1,233 @@
+use anyhow::Context;
+use crossterm::event::{KeyCode, KeyEvent};
+use kdl::{KdlDocument, KdlNode};
+use rev_git_provider::models::{CurrentState, ReviewListItem};
+
+use crate::{
+    config::{kdl_strings, kdl_value},
+    glob,
+};
+
+/// The most filters there are keys for, `1` to `9`.
+pub const MAX_FILTERS: usize = 9;
+
+/// A named filter of the list, toggled by the digit of its position in the filters block.
+///
+/// Every predicate set must hold for a review to be shown, any of the values of a predicate will
+/// do. Paths match once the changed files of the review are fetched, until then it's hidden.
+///
+/// ```kdl
+/// filters {
+///     filter "frontend" {
+///         labels "frontend" "design"
+///         paths "web/**" "**/*.tsx"
+///     }
+///     filter "needs-2nd-approval" {
+///         approvals 1
+///         checks "success"
+///     }
+///     filter "bots" {
+///         authors "*[bot]"
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListFilter {
+    pub name: String,
+    pub labels: Vec<String>,
+    /// Globs of which one must match any of the changed files.
+    pub paths: Vec<String>,
+    /// Globs of which one must match the author.
+    pub authors: Vec<String>,
+    /// The combined states of the checks, `none` for reviews without checks.
+    pub checks: Vec<ChecksState>,
+    /// The exact number of approvals.
+    pub approvals: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksState {
+    Success,
+    Pending,
+    Failure,
+    None,
+}
+
+impl ChecksState {
+    fn parse(raw: &str) -> anyhow::Result<Self> {
+        Ok(match raw {
+            "success" => Self::Success,
+            "pending" => Self::Pending,
+            "failure" => Self::Failure,
+            "none" => Self::None,
+            _ => {
+                anyhow::bail!("checks must be one of success, pending, failure or none, got: {raw}")
+            }
+        })
+    }
+
+    fn of(checks: Option<&CurrentState>) -> Self {
+        match checks {
+            Some(CurrentState::Success) => Self::Success,
+            Some(CurrentState::Pending) => Self::Pending,
+            Some(CurrentState::Failure | CurrentState::Expired) => Self::Failure,
+            None => Self::None,
+        }
+    }
+}
+
+impl ListFilter {
+    pub fn from_kdl(node: &KdlNode) -> anyhow::Result<Self> {
+        let name = node
+            .entries()
+            .first()
+            .and_then(|e| e.value().as_string())
+            .context("filters must be named, e.g. filter \"frontend\" {}")?
+            .to_string();
+        let empty = KdlDocument::new();
+        let doc = node.children().unwrap_or(&empty);
+
+        let checks = kdl_strings(doc, "checks")?
+            .unwrap_or_default()
+            .iter()
+            .map(|c| ChecksState::parse(c))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .with_context(|| format!("filter {name}"))?;
+        let approvals = kdl_value(doc, "approvals")
+            .map(|a| {
+                a.as_i64()
+                    .and_then(|a| usize::try_from(a).ok())
+                    .with_context(|| format!("filter {name}: approvals must be a number"))
+            })
+            .transpose()?;
+
+        let filter = Self {
+            labels: kdl_strings(doc, "labels")?.unwrap_or_default(),
+            paths: kdl_strings(doc, "paths")?.unwrap_or_default(),
+            authors: kdl_strings(doc, "authors")?.unwrap_or_default(),
+            checks,
+            approvals,
+            name,
+        };
+        if filter.labels.is_empty()
+            && filter.paths.is_empty()
+            && filter.authors.is_empty()
+            && filter.checks.is_empty()
+            && filter.approvals.is_none()
+        {
+            anyhow::bail!(
+                "filter {}: needs at least one of labels, paths, authors, checks or approvals",
+                filter.name
+            );
+        }
+
+        Ok(filter)
+    }
+
+    /// Whether the review passes the filter, `paths` are its changed files if they're fetched.
+    pub fn matches(&self, item: &ReviewListItem, paths: Option<&[String]>) -> bool {
+        let labels = self.labels.is_empty()
+            || item
+                .labels
+                .iter()
+                .any(|l| self.labels.iter().any(|f| f.eq_ignore_ascii_case(&l.name)));
+        let paths = self.paths.is_empty()
+            || paths.is_some_and(|paths| {
+                paths
+                    .iter()
+                    .any(|p| self.paths.iter().any(|f| glob::matches(f, p)))
+            });
+        let authors =
+            self.authors.is_empty() || self.authors.iter().any(|a| glob::matches(a, &item.author));
+        let checks =
+            self.checks.is_empty() || self.checks.contains(&ChecksState::of(item.checks.as_ref()));
+        let approvals = self.approvals.is_none_or(|a| a == item.approvals);
+
+        labels && paths && authors && checks && approvals
+    }
+}
+
+/// The filter toggled by the key, a digit without modifiers for one of the filters.
+pub fn filter_key(filters: &[ListFilter], key: KeyEvent) -> Option<usize> {
+    let KeyCode::Char(c) = key.code else {
+        return None;
+    };
+    if !key.modifiers.is_empty() {
+        return None;
+    }
+
+    let index = (c.to_digit(10)? as usize).checked_sub(1)?;
+    (index < filters.len()).then_some(index)
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use kdl::KdlDocument;
+    use rev_git_provider::models::{CurrentState, Label, ReviewListItem};
+
+    use super::{filter_key, ChecksState, ListFilter};
+
+    #[test]
+    fn test_parse_and_match_filters() -> anyhow::Result<()> {
+        let doc: KdlDocument = r#"
+filter "frontend" {
+    labels "Frontend"
+    paths "web/**"
+}
+filter "needs-2nd-approval" {
+    approvals 1
+    checks "success" "none"
+}
+filter "empty"
+"#
+        .parse()?;
+        let nodes = doc.nodes();
+
+        let frontend = ListFilter::from_kdl(&nodes[0])?;
+        let second = ListFilter::from_kdl(&nodes[1])?;
+        assert_eq!(vec![ChecksState::Success, ChecksState::None], second.checks);
+        assert!(ListFilter::from_kdl(&nodes[2]).is_err());
+
+        let mut item = ReviewListItem {
+            id: "PR_1".into(),
+            name: "rev".into(),
+            title: "Add retries".into(),
+            url: String::new(),
+            owner: "kjuulh".into(),
+            date: chrono::Utc::now(),
+            number: 1,
+            author: "kjuulh".into(),
+            labels: vec![Label {
+                name: "frontend".into(),
+                color: None,
+                description: None,
+            }],
+            additions: 0,
+            deletions: 0,
+            changed_files: 0,
+            approvals: 1,
+            checks: None,
+            review_decision: None,
+            draft: false,
+        };
+
+        let paths = vec!["web/src/app.tsx".to_string()];
+        assert!(frontend.matches(&item, Some(&paths)));
+        // The paths aren't known until the changed files are fetched
+        assert!(!frontend.matches(&item, None));
+        assert!(second.matches(&item, None));
+
+        item.checks = Some(CurrentState::Failure);
+        assert!(!second.matches(&item, None));
+
+        let filters = [frontend, second];
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(Some(0), filter_key(&filters, key('1')));
+        assert_eq!(Some(1), filter_key(&filters, key('2')));
+        assert_eq!(None, filter_key(&filters, key('3')));
+        assert_eq!(None, filter_key(&filters, key('0')));
+
+        Ok(())
+    }
+}
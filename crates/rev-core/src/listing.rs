@@ -0,0 +1,172 @@
+use chrono::{DateTime, Utc};
+use rev_git_provider::models::{CurrentState, ReviewDecision, ReviewListItem};
+use serde::Serialize;
+use timeago::Formatter;
+
+use crate::{
+    i18n::{tr, Text},
+    terminal::Capabilities,
+};
+
+/// The widest the title gets in the table, longer titles are cut off.
+const TITLE_WIDTH: usize = 60;
+
+/// A review of the queue as printed by `rev list`.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ListedReview {
+    /// The review in the form `owner/name#number`.
+    pub reference: String,
+    pub title: String,
+    pub author: String,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+    pub labels: Vec<String>,
+    pub additions: usize,
+    pub deletions: usize,
+    pub changed_files: usize,
+    pub approvals: usize,
+    /// The combined state of the checks, `None` when there are none.
+    pub checks: Option<&'static str>,
+    pub review_decision: Option<&'static str>,
+    pub draft: bool,
+}
+
+impl From<&ReviewListItem> for ListedReview {
+    fn from(item: &ReviewListItem) -> Self {
+        Self {
+            reference: format!("{}/{}#{}", item.owner, item.name, item.number),
+            title: item.title.clone(),
+            author: item.author.clone(),
+            url: item.url.clone(),
+            created_at: item.date,
+            labels: item.labels.iter().map(|l| l.name.clone()).collect(),
+            additions: item.additions,
+            deletions: item.deletions,
+            changed_files: item.changed_files,
+            approvals: item.approvals,
+            checks: item.checks.as_ref().map(|c| match c {
+                CurrentState::Success => "success",
+                CurrentState::Pending => "pending",
+                CurrentState::Failure => "failure",
+                CurrentState::Expired => "expired",
+            }),
+            review_decision: item.review_decision.map(|d| match d {
+                ReviewDecision::Approved => "approved",
+                ReviewDecision::ChangesRequested => "changes-requested",
+                ReviewDecision::ReviewRequired => "review-required",
+            }),
+            draft: item.draft,
+        }
+    }
+}
+
+pub fn to_json(reviews: &[ListedReview]) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(reviews)?)
+}
+
+/// The reviews as a table, one per line, to read or to pick from with e.g. fzf. The references
+/// link to the reviews when the terminal supports links.
+pub fn render(reviews: &[ListedReview], terminal: &Capabilities) -> String {
+    render_at(reviews, Utc::now(), terminal)
+}
+
+fn render_at(reviews: &[ListedReview], now: DateTime<Utc>, terminal: &Capabilities) -> String {
+    let formatter = Formatter::default();
+    let width = reviews
+        .iter()
+        .map(|r| r.reference.chars().count())
+        .chain([tr(Text::PullRequest).chars().count()])
+        .max()
+        .unwrap_or_default();
+
+    let mut out = format!(
+        "{:<width$}  {:<TITLE_WIDTH$}  {:<20}  {:<8}  {:<17}  {}\n",
+        tr(Text::PullRequest),
+        tr(Text::Title),
+        tr(Text::Author),
+        tr(Text::Checks),
+        tr(Text::Review),
+        tr(Text::DateCreated),
+    );
+    for review in reviews {
+        let mut title = review.title.chars().take(TITLE_WIDTH).collect::<String>();
+        if review.draft {
+            title = format!("[{}] {title}", tr(Text::Draft))
+                .chars()
+                .take(TITLE_WIDTH)
+                .collect();
+        }
+        let age = formatter.convert_chrono(review.created_at, now);
+
+        // Padded apart from the link, whose escape sequences take no room
+        let padding = " ".repeat(width - review.reference.chars().count());
+        out.push_str(&format!(
+            "{}{padding}  {title:<TITLE_WIDTH$}  {:<20}  {:<8}  {:<17}  {age}\n",
+            terminal.link(&review.url, &review.reference),
+            review.author,
+            review.checks.unwrap_or("-"),
+            review.review_decision.unwrap_or("-"),
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use rev_git_provider::models::{CurrentState, ReviewDecision, ReviewListItem};
+
+    use super::{render_at, to_json, ListedReview};
+    use crate::terminal::Capabilities;
+
+    fn terminal(hyperlinks: bool) -> Capabilities {
+        Capabilities {
+            hyperlinks,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_listing() -> anyhow::Result<()> {
+        let now = Utc::now();
+        let item = ReviewListItem {
+            id: "PR_1".into(),
+            name: "rev".into(),
+            title: "Add retries".into(),
+            url: "https://github.com/kjuulh/rev/pull/12".into(),
+            owner: "kjuulh".into(),
+            date: now - Duration::days(2),
+            number: 12,
+            author: "alice".into(),
+            checks: Some(CurrentState::Failure),
+            review_decision: Some(ReviewDecision::ChangesRequested),
+            ..Default::default()
+        };
+        let reviews = vec![ListedReview::from(&item)];
+
+        let rendered = render_at(&reviews, now, &terminal(false));
+        let line = rendered.lines().nth(1).unwrap();
+        assert!(line.starts_with("kjuulh/rev#12"));
+        assert!(line.contains("Add retries"));
+        assert!(line.contains("failure"));
+        assert!(line.contains("changes-requested"));
+        assert!(line.ends_with("2 days ago"));
+
+        let linked = render_at(&reviews, now, &terminal(true));
+        let line = linked.lines().nth(1).unwrap();
+        assert!(
+            line.starts_with("\x1b]8;;https://github.com/kjuulh/rev/pull/12\x1b\\kjuulh/rev#12")
+        );
+        assert_eq!(
+            rendered.lines().nth(1).unwrap().len(),
+            line.len() - "\x1b]8;;https://github.com/kjuulh/rev/pull/12\x1b\\\x1b]8;;\x1b\\".len()
+        );
+
+        let json = to_json(&reviews)?;
+        assert!(json.contains("\"reference\": \"kjuulh/rev#12\""));
+        assert!(json.contains("\"review_decision\": \"changes-requested\""));
+
+        Ok(())
+    }
+}
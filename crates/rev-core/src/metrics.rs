@@ -0,0 +1,228 @@
+//! Metrics of `rev bot --watch`, served in the prometheus text format on `--metrics-port` so
+//! the infrastructure running a shared bot can keep an eye on it.
+
+use std::{
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rev_git_provider::models::ProviderStats;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// The largest request read, the path is all that's looked at.
+const MAX_REQUEST_BYTES: usize = 8192;
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    inner: Mutex<Snapshot>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Snapshot {
+    rounds: u64,
+    /// The reviews the query matched in the last round.
+    queue_depth: usize,
+    fetch_seconds: f64,
+    fetches: u64,
+    provider_errors: u64,
+    pending_requests: usize,
+    rate_limit_remaining: Option<usize>,
+}
+
+impl Metrics {
+    /// Counts a round which searched and fetched the queue in the duration.
+    pub fn record_fetch(&self, duration: Duration, queue_depth: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.queue_depth = queue_depth;
+        inner.fetch_seconds += duration.as_secs_f64();
+        inner.fetches += 1;
+    }
+
+    pub fn record_round(&self, stats: &ProviderStats) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.rounds += 1;
+        inner.pending_requests = stats.pending_requests;
+        inner.rate_limit_remaining = stats.rate_limit.map(|r| r.remaining);
+    }
+
+    /// Counts requests to the provider which failed, be it the round or one of its actions.
+    pub fn record_provider_errors(&self, errors: usize) {
+        self.inner.lock().unwrap().provider_errors += errors as u64;
+    }
+
+    /// The metrics in the prometheus text format, the rate limit is left out until the provider
+    /// reported one.
+    pub fn render(&self) -> String {
+        let inner = self.inner.lock().unwrap().clone();
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(&str, String)]| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            for (suffix, value) in samples {
+                let _ = writeln!(out, "{name}{suffix} {value}");
+            }
+        };
+
+        metric(
+            "rev_bot_rounds_total",
+            "counter",
+            "Rounds the bot finished.",
+            &[("", inner.rounds.to_string())],
+        );
+        metric(
+            "rev_queue_depth",
+            "gauge",
+            "Reviews the query of the bot matched in the last round.",
+            &[("", inner.queue_depth.to_string())],
+        );
+        metric(
+            "rev_fetch_duration_seconds",
+            "summary",
+            "Time taken to search and fetch the queue.",
+            &[
+                ("_sum", inner.fetch_seconds.to_string()),
+                ("_count", inner.fetches.to_string()),
+            ],
+        );
+        metric(
+            "rev_provider_errors_total",
+            "counter",
+            "Requests to the provider which failed.",
+            &[("", inner.provider_errors.to_string())],
+        );
+        metric(
+            "rev_pending_requests",
+            "gauge",
+            "Requests to the provider which haven't been answered yet.",
+            &[("", inner.pending_requests.to_string())],
+        );
+        if let Some(remaining) = inner.rate_limit_remaining {
+            metric(
+                "rev_rate_limit_remaining",
+                "gauge",
+                "Requests left until the rate limit of the provider resets.",
+                &[("", remaining.to_string())],
+            );
+        }
+
+        out
+    }
+}
+
+/// Answers `GET /metrics` on the listener with the metrics, and anything else with a 404.
+pub async fn serve(listener: TcpListener, metrics: Arc<Metrics>) -> anyhow::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &metrics).await {
+                tracing::debug!("failed to serve metrics: {e:#}");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, metrics: &Metrics) -> anyhow::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request_line = String::from_utf8_lossy(&request);
+    let mut parts = request_line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", metrics.render()),
+        _ => ("404 Not Found", "not found\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use chrono::Utc;
+    use rev_git_provider::models::{ProviderStats, RateLimit};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::{serve, Metrics};
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        assert!(!metrics.render().contains("rev_rate_limit_remaining"));
+
+        metrics.record_fetch(Duration::from_millis(1500), 12);
+        metrics.record_provider_errors(2);
+        metrics.record_round(&ProviderStats {
+            pending_requests: 0,
+            cached_responses: 0,
+            cached_bytes: 0,
+            rate_limit: Some(RateLimit {
+                limit: 5000,
+                remaining: 4900,
+                reset: Utc::now(),
+            }),
+        });
+
+        let rendered = metrics.render();
+        for line in [
+            "# TYPE rev_queue_depth gauge",
+            "rev_queue_depth 12",
+            "rev_fetch_duration_seconds_sum 1.5",
+            "rev_fetch_duration_seconds_count 1",
+            "rev_provider_errors_total 2",
+            "rev_rate_limit_remaining 4900",
+            "rev_bot_rounds_total 1",
+        ] {
+            assert!(rendered.lines().any(|l| l == line), "{line} in {rendered}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_metrics() -> anyhow::Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let metrics = Arc::new(Metrics::default());
+        metrics.record_fetch(Duration::from_secs(1), 3);
+        tokio::spawn(serve(listener, metrics));
+
+        let get = |path: &'static str| async move {
+            let mut stream = TcpStream::connect(addr).await?;
+            stream
+                .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+                .await?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await?;
+            anyhow::Ok(response)
+        };
+
+        let response = get("/metrics").await?;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("\nrev_queue_depth 3\n"));
+
+        assert!(get("/").await?.starts_with("HTTP/1.1 404"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::path::Path;
+
+use anyhow::Context;
+
+/// The terminal multiplexer rev runs in, which commands can be opened next to rev in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multiplexer {
+    Tmux,
+    Wezterm,
+}
+
+/// Where a command is opened, next to rev or on its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Splits the pane rev runs in.
+    #[default]
+    Pane,
+    /// A new tmux window, or a new tab in wezterm.
+    Window,
+}
+
+impl Placement {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "pane" => Some(Placement::Pane),
+            "window" => Some(Placement::Window),
+            _ => None,
+        }
+    }
+}
+
+impl Multiplexer {
+    /// The multiplexer from the environment, tmux wins when wezterm runs tmux.
+    pub fn detect() -> Option<Self> {
+        Self::from_env(|key| std::env::var(key).ok())
+    }
+
+    fn from_env(get: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        if get("TMUX").is_some() {
+            Some(Multiplexer::Tmux)
+        } else if get("WEZTERM_PANE").is_some() {
+            Some(Multiplexer::Wezterm)
+        } else {
+            None
+        }
+    }
+
+    /// The program and arguments opening the command in the directory, a shell without one.
+    pub fn args(&self, placement: Placement, cwd: &Path, command: Option<&str>) -> Vec<String> {
+        let cwd = cwd.display().to_string();
+        let mut args = match (self, placement) {
+            (Multiplexer::Tmux, Placement::Pane) => vec!["tmux", "split-window", "-h", "-c"],
+            (Multiplexer::Tmux, Placement::Window) => vec!["tmux", "new-window", "-c"],
+            (Multiplexer::Wezterm, Placement::Pane) => {
+                vec!["wezterm", "cli", "split-pane", "--right", "--cwd"]
+            }
+            (Multiplexer::Wezterm, Placement::Window) => vec!["wezterm", "cli", "spawn", "--cwd"],
+        }
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+        args.push(cwd);
+
+        if let Some(command) = command {
+            match self {
+                // tmux runs the command through the shell itself
+                Multiplexer::Tmux => args.push(command.to_string()),
+                Multiplexer::Wezterm => {
+                    args.extend(["--", "sh", "-c", command].into_iter().map(str::to_string))
+                }
+            }
+        }
+
+        args
+    }
+
+    /// Opens the command, returning once the multiplexer has opened it.
+    pub async fn open(
+        &self,
+        placement: Placement,
+        cwd: &Path,
+        command: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let args = self.args(placement, cwd, command);
+        let output = tokio::process::Command::new(&args[0])
+            .args(&args[1..])
+            .output()
+            .await
+            .with_context(|| format!("failed to run {}", args[0]))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} exited with {}: {}",
+                args[0],
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{Multiplexer, Placement};
+
+    #[test]
+    fn test_detects_and_builds_commands() {
+        let env = |vars: &'static [&'static str]| {
+            move |key: &str| vars.contains(&key).then(|| "1".to_string())
+        };
+        assert_eq!(
+            Some(Multiplexer::Tmux),
+            Multiplexer::from_env(env(&["TMUX", "WEZTERM_PANE"]))
+        );
+        assert_eq!(
+            Some(Multiplexer::Wezterm),
+            Multiplexer::from_env(env(&["WEZTERM_PANE"]))
+        );
+        assert_eq!(None, Multiplexer::from_env(env(&[])));
+
+        let cwd = Path::new("/reviews/kjuulh/rev/42");
+        assert_eq!(
+            vec![
+                "tmux",
+                "split-window",
+                "-h",
+                "-c",
+                "/reviews/kjuulh/rev/42",
+                "cargo test"
+            ],
+            Multiplexer::Tmux.args(Placement::Pane, cwd, Some("cargo test"))
+        );
+        assert_eq!(
+            vec!["tmux", "new-window", "-c", "/reviews/kjuulh/rev/42"],
+            Multiplexer::Tmux.args(Placement::Window, cwd, None)
+        );
+        assert_eq!(
+            vec![
+                "wezterm",
+                "cli",
+                "spawn",
+                "--cwd",
+                "/reviews/kjuulh/rev/42",
+                "--",
+                "sh",
+                "-c",
+                "cargo test"
+            ],
+            Multiplexer::Wezterm.args(Placement::Window, cwd, Some("cargo test"))
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::path::Path;
+
+use anyhow::Context;
+use rev_git_provider::{models::NewReview, GitProvider};
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    store::Store,
+    workspace::git,
+};
+
+/// A review of the branch checked out in a local repository, pre-filled from the commits on the
+/// branch and the pull request template of the repository.
+#[derive(Debug, Clone)]
+pub struct BranchReview {
+    pub owner: String,
+    pub name: String,
+    pub review: NewReview,
+}
+
+impl BranchReview {
+    /// Reads the branch checked out in `dir`, which has to be pushed to `origin` as the review is
+    /// created from the remote branch.
+    pub async fn from_checkout(
+        dir: &Path,
+        base: Option<String>,
+        provider: &GitProvider,
+    ) -> anyhow::Result<Self> {
+        let branch = git(dir, &["rev-parse", "--abbrev-ref", "HEAD"])
+            .await?
+            .trim()
+            .to_string();
+        if branch == "HEAD" {
+            anyhow::bail!("no branch is checked out, check out the branch to review first");
+        }
+
+        let remote = git(dir, &["remote", "get-url", "origin"]).await?;
+        let (owner, name) = parse_remote(remote.trim())
+            .with_context(|| format!("origin isn't a repository url: {}", remote.trim()))?;
+
+        let pushed = git(dir, &["rev-list", "--count", "@{upstream}..HEAD"])
+            .await
+            .with_context(|| format!("push the branch first: git push -u origin {branch}"))?;
+        if pushed.trim() != "0" {
+            anyhow::bail!("{branch} has unpushed commits, push them first: git push");
+        }
+
+        let since = match base.as_deref() {
+            Some(base) => format!("origin/{base}"),
+            None => "origin/HEAD".to_string(),
+        };
+        // Without the base fetched, the last commit is the best guess
+        let subjects = match git(
+            dir,
+            &["log", "--reverse", "--format=%s", &format!("{since}..HEAD")],
+        )
+        .await
+        {
+            Ok(log) => log,
+            Err(_) => git(dir, &["log", "-1", "--format=%s"]).await?,
+        };
+        let subjects = subjects.lines().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let template = provider
+            .get_review_template(owner.clone(), name.clone())
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("failed to get the template of {owner}/{name}: {e:#}");
+                None
+            });
+
+        Ok(Self {
+            review: NewReview {
+                title: title(&branch, &subjects),
+                body: body(template, &subjects),
+                head: branch,
+                base,
+                draft: false,
+            },
+            owner,
+            name,
+        })
+    }
+
+    pub fn repository(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+
+    /// Creates the review, which is recorded in the audit log, returning its reference.
+    pub async fn create(
+        &self,
+        provider: &GitProvider,
+        store: &Store,
+        source: &str,
+    ) -> anyhow::Result<String> {
+        let result = provider
+            .create_review(self.owner.clone(), self.name.clone(), self.review.clone())
+            .await
+            .map(|number| format!("{}#{number}", self.repository()));
+
+        let reference = result
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(|_| self.repository());
+        let mut entry = AuditEntry::new(source, "create", reference)
+            .parameter("head", &self.review.head)
+            .result(&result);
+        if let Some(base) = self.review.base.as_deref() {
+            entry = entry.parameter("base", base);
+        }
+        AuditLog::record_or_warn(store, entry);
+
+        result
+    }
+}
+
+/// The owner and name of the repository of a remote url, e.g. `git@github.com:kjuulh/rev.git`
+/// or `https://github.com/kjuulh/rev`.
+fn parse_remote(url: &str) -> Option<(String, String)> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+    let mut parts = path
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit('/');
+    let name = parts.next().filter(|n| !n.is_empty())?;
+    let owner = parts.next().filter(|o| !o.is_empty())?;
+
+    Some((owner.to_string(), name.to_string()))
+}
+
+/// The subject of the first commit, or the branch name in words when there are no commits.
+fn title(branch: &str, subjects: &[String]) -> String {
+    if let Some(subject) = subjects.first() {
+        return subject.clone();
+    }
+
+    let words = branch
+        .rsplit('/')
+        .next()
+        .unwrap_or(branch)
+        .replace(['-', '_'], " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+/// The template of the repository, or the commits as a list when there is more than one.
+fn body(template: Option<String>, subjects: &[String]) -> String {
+    if let Some(template) = template {
+        return template;
+    }
+    if subjects.len() < 2 {
+        return String::new();
+    }
+
+    subjects
+        .iter()
+        .map(|s| format!("- {s}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{body, parse_remote, title};
+
+    #[test]
+    fn test_prefills_from_branch() {
+        let repository = Some(("kjuulh".to_string(), "rev".to_string()));
+        assert_eq!(repository, parse_remote("git@github.com:kjuulh/rev.git"));
+        assert_eq!(repository, parse_remote("https://github.com/kjuulh/rev"));
+        assert_eq!(
+            repository,
+            parse_remote("ssh://git@gitea.example.com:2222/kjuulh/rev.git")
+        );
+        assert_eq!(None, parse_remote("rev"));
+
+        let subjects = vec!["feat: add create".to_string(), "fix: typo".to_string()];
+        assert_eq!("feat: add create", title("kjuulh/create", &subjects));
+        assert_eq!("Add pr create", title("kjuulh/add-pr_create", &[]));
+
+        assert_eq!("- feat: add create\n- fix: typo", body(None, &subjects));
+        assert_eq!("", body(None, &subjects[..1]));
+        assert_eq!("## Why", body(Some("## Why".to_string()), &subjects));
+    }
+}
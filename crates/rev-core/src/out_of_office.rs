@@ -0,0 +1,225 @@
+use std::collections::BTreeSet;
+
+use anyhow::Context;
+use chrono::{Local, NaiveDate};
+use rev_git_provider::{models::ReviewListItem, GitProvider};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    config::{OutOfOfficeSettings, TeamSettings},
+    git_pull_requests::{least_loaded, search_all},
+    store::Store,
+};
+
+const ABSENCE_DOCUMENT: &str = "out-of-office";
+
+/// Being out of office through a date, set with `rev ooo until`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct Absence {
+    /// The last day away, `None` when not away.
+    pub until: Option<NaiveDate>,
+    /// The review requests handled during the absence, in the form `owner/name#number`, so
+    /// they're only reassigned or commented on once.
+    pub handled: BTreeSet<String>,
+}
+
+impl Absence {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(ABSENCE_DOCUMENT)
+    }
+
+    /// Starts an absence lasting through `until`, forgetting the requests of an earlier one.
+    pub fn start(store: &Store, until: NaiveDate) -> anyhow::Result<()> {
+        store.save(
+            ABSENCE_DOCUMENT,
+            &Self {
+                until: Some(until),
+                handled: BTreeSet::new(),
+            },
+        )
+    }
+
+    pub fn end(store: &Store) -> anyhow::Result<()> {
+        store.save(ABSENCE_DOCUMENT, &Self::default())
+    }
+
+    /// The absence in effect today, ending it once its last day has passed.
+    pub fn current(store: &Store) -> anyhow::Result<Option<Self>> {
+        let absence = Self::load(store)?;
+        if absence.until.is_none() {
+            return Ok(None);
+        }
+        if !absence.is_away(Local::now().date_naive()) {
+            Self::end(store)?;
+            return Ok(None);
+        }
+
+        Ok(Some(absence))
+    }
+
+    pub fn is_away(&self, today: NaiveDate) -> bool {
+        self.until.is_some_and(|until| today <= until)
+    }
+}
+
+/// Parses the last day away in the form `2024-07-31`, which can't have passed already.
+pub fn parse_until(date: &str) -> anyhow::Result<NaiveDate> {
+    let until = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("{date} isn't a date in the form 2024-07-31"))?;
+    if until < Local::now().date_naive() {
+        anyhow::bail!("{date} has already passed");
+    }
+
+    Ok(until)
+}
+
+/// What was done, or would be done in a dry run, with a review request arriving while away.
+#[derive(Debug, Clone)]
+pub struct OutOfOfficeOutcome {
+    /// The review in the form `owner/name#number`.
+    pub review: String,
+    pub title: String,
+    pub reassigned_to: Option<String>,
+    pub commented: bool,
+    pub errors: Vec<String>,
+}
+
+/// Reassigns or comments on the review requests which arrived since the last run, as set in the
+/// `out-of-office` block, returning nothing when not away.
+pub async fn run(
+    provider: GitProvider,
+    store: Store,
+    settings: &OutOfOfficeSettings,
+    team: &TeamSettings,
+    me: Option<&str>,
+    dry_run: bool,
+) -> anyhow::Result<Vec<OutOfOfficeOutcome>> {
+    let Some(mut absence) = Absence::current(&store)? else {
+        return Ok(Vec::new());
+    };
+    let until = absence
+        .until
+        .context("an absence to have a last day")?
+        .to_string();
+    let me = match (settings.reassign, me) {
+        (true, None) => anyhow::bail!("set committer in rev.kdl to reassign review requests"),
+        (_, me) => me.unwrap_or_default(),
+    };
+
+    let requests = search_all(&provider, "is:pr is:open review-requested:@me")
+        .await?
+        .into_iter()
+        .filter(|item| settings.handles(&format!("{}/{}", item.owner, item.name)))
+        .filter(|item| !absence.handled.contains(&reference(item)))
+        .collect::<Vec<_>>();
+
+    let mut outcomes = Vec::new();
+    for item in requests {
+        let review = reference(&item);
+        let mut outcome = OutOfOfficeOutcome {
+            review: review.clone(),
+            title: item.title.clone(),
+            reassigned_to: None,
+            commented: false,
+            errors: Vec::new(),
+        };
+
+        if settings.reassign && !team.members.is_empty() {
+            match least_loaded(&provider, me, &team.members).await {
+                Ok(to) if dry_run => outcome.reassigned_to = Some(to),
+                Ok(to) => {
+                    let result = provider
+                        .reassign_reviewer(
+                            item.owner.clone(),
+                            item.name.clone(),
+                            item.number,
+                            me.to_string(),
+                            to.clone(),
+                        )
+                        .await;
+                    let entry = AuditEntry::new("ooo", "reassign", &review)
+                        .parameter("from", me)
+                        .parameter("to", &to)
+                        .result(&result);
+                    AuditLog::record(&store, entry)?;
+
+                    match result {
+                        Ok(()) => outcome.reassigned_to = Some(to),
+                        Err(e) => outcome.errors.push(format!("{e:#}")),
+                    }
+                }
+                Err(e) => outcome.errors.push(format!("{e:#}")),
+            }
+        }
+
+        if let Some(comment) = settings.comment.as_deref() {
+            let body = comment.replace("{until}", &until);
+            if dry_run {
+                outcome.commented = true;
+            } else {
+                let result = provider.add_comment(item.id.clone(), body.clone()).await;
+                let entry = AuditEntry::new("ooo", "comment", &review)
+                    .parameter("body", &body)
+                    .result(&result);
+                AuditLog::record(&store, entry)?;
+
+                match result {
+                    Ok(()) => outcome.commented = true,
+                    Err(e) => outcome.errors.push(format!("{e:#}")),
+                }
+            }
+        }
+
+        // Failed requests are tried again on the next run
+        if !dry_run && outcome.errors.is_empty() {
+            absence.handled.insert(review);
+        }
+        outcomes.push(outcome);
+    }
+
+    if !dry_run {
+        store.save(ABSENCE_DOCUMENT, &absence)?;
+    }
+
+    Ok(outcomes)
+}
+
+fn reference(item: &ReviewListItem) -> String {
+    format!("{}/{}#{}", item.owner, item.name, item.number)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::{parse_until, Absence};
+    use crate::store::Store;
+
+    #[test]
+    fn test_absence_lasts_through_last_day() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("rev-ooo-test-{}", std::process::id()));
+        let store = Store::new(&dir);
+        let until = NaiveDate::from_ymd_opt(2024, 7, 31).unwrap();
+
+        assert_eq!(None, Absence::load(&store)?.until);
+
+        Absence::start(&store, until)?;
+        let absence = Absence::load(&store)?;
+        assert!(absence.is_away(until));
+        assert!(absence.is_away(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()));
+        assert!(!absence.is_away(NaiveDate::from_ymd_opt(2024, 8, 1).unwrap()));
+
+        // The last day is long gone, so the absence is over
+        assert_eq!(None, Absence::current(&store)?);
+        assert_eq!(Absence::default(), Absence::load(&store)?);
+
+        std::fs::remove_dir_all(&dir)?;
+
+        assert!(parse_until("2999-01-31").is_ok());
+        assert!(parse_until("2024-07-31").is_err());
+        assert!(parse_until("31/07/2999").is_err());
+
+        Ok(())
+    }
+}
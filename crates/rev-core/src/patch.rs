@@ -0,0 +1,124 @@
+/// A file of a unified diff, such as the diff of a review.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: String,
+    /// The path before the change, when the file was renamed.
+    pub old_path: Option<String>,
+    pub lines: Vec<DiffLine>,
+}
+
+impl FileDiff {
+    pub fn additions(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| matches!(l, DiffLine::Added(_)))
+            .count()
+    }
+
+    pub fn deletions(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|l| matches!(l, DiffLine::Removed(_)))
+            .count()
+    }
+}
+
+/// A line of a hunk, without its `+`, `-` or space prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// The `@@ -1,4 +1,5 @@` line starting a hunk.
+    Hunk(String),
+    Added(String),
+    Removed(String),
+    Context(String),
+}
+
+/// Splits a unified diff, as printed by `git diff`, into its files.
+pub fn parse(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut in_hunk = false;
+
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            // `a/path b/path`, paths with spaces are only split at the last ` b/`
+            let path = paths
+                .rsplit_once(" b/")
+                .map(|(_, b)| b)
+                .unwrap_or(paths)
+                .to_string();
+            files.push(FileDiff {
+                path,
+                old_path: None,
+                lines: Vec::new(),
+            });
+            in_hunk = false;
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            file.lines.push(DiffLine::Hunk(line.to_string()));
+            in_hunk = true;
+        } else if in_hunk {
+            let (prefix, rest) = line.split_at(line.len().min(1));
+            file.lines.push(match prefix {
+                "+" => DiffLine::Added(rest.to_string()),
+                "-" => DiffLine::Removed(rest.to_string()),
+                // Includes `\ No newline at end of file`
+                _ => DiffLine::Context(rest.to_string()),
+            });
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.old_path = Some(from.to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = to.to_string();
+        } else if line.starts_with("Binary files") {
+            file.lines.push(DiffLine::Context(line.to_string()));
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, DiffLine};
+
+    #[test]
+    fn test_parse_files() {
+        let diff = r#"diff --git a/src/main.rs b/src/main.rs
+index 3f2a9c1..8b1e4d2 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    println!("hello");
++    println!("hello, world");
+ }
+diff --git a/old.txt b/new.txt
+similarity index 90%
+rename from old.txt
+rename to new.txt
+@@ -1 +1,2 @@
+ a
++--- not a header
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+"#;
+
+        let files = parse(diff);
+
+        assert_eq!(3, files.len());
+        assert_eq!("src/main.rs", files[0].path);
+        assert_eq!((1, 1), (files[0].additions(), files[0].deletions()));
+        assert_eq!(DiffLine::Hunk("@@ -1,3 +1,3 @@".into()), files[0].lines[0]);
+        assert_eq!("new.txt", files[1].path);
+        assert_eq!(Some("old.txt".into()), files[1].old_path);
+        assert_eq!(
+            DiffLine::Added("--- not a header".into()),
+            files[1].lines[2]
+        );
+        assert_eq!(1, files[2].lines.len());
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+use anyhow::Context;
+use portable_pty::CommandBuilder;
+
+pub fn config_dir() -> PathBuf {
+    rev_config::config_home()
+}
+
+pub fn data_dir() -> PathBuf {
+    rev_config::data_home()
+}
+
+/// Where `rev auth login` stores the token of the github host, readable only by the user.
+pub fn token_file(host: &str) -> PathBuf {
+    let credentials = data_dir().join("credentials");
+    match host {
+        "github.com" => credentials.join("github-token"),
+        host => credentials.join(format!("github-token-{host}")),
+    }
+}
+
+/// Whether the data dir can be written to, probed once per process.
+///
+/// Network homes and read-only containers may not allow it, rev then keeps its state and caches
+/// in memory instead of refusing to start.
+pub fn data_dir_writable() -> bool {
+    static WRITABLE: OnceLock<bool> = OnceLock::new();
+
+    *WRITABLE.get_or_init(|| {
+        let dir = data_dir();
+        let probe = dir.join(".rev-probe");
+        std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&probe, b"rev"))
+            .and_then(|_| std::fs::remove_file(&probe))
+            .is_ok()
+    })
+}
+
+/// The shell used to run user supplied command lines, such as pipelines.
+///
+/// Windows doesn't ship bash, so we rely on `cmd /C` there, and the posix `sh` everywhere else.
+fn shell() -> (&'static str, &'static str) {
+    if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    }
+}
+
+/// Builds a pty command running `command_line` through the platform shell.
+pub fn shell_command(command_line: &str) -> CommandBuilder {
+    let (shell, flag) = shell();
+    let mut cmd = CommandBuilder::new(shell);
+    cmd.arg(flag);
+    cmd.arg(command_line);
+    cmd
+}
+
+/// Builds a process running `command_line` through the platform shell.
+pub fn shell_process(command_line: &str) -> Command {
+    let (shell, flag) = shell();
+    let mut cmd = Command::new(shell);
+    cmd.arg(flag).arg(command_line);
+    cmd
+}
+
+/// Opens a url in the default browser.
+pub fn open_url(url: &str) -> anyhow::Result<()> {
+    let mut cmd = if cfg!(windows) {
+        // The empty argument is the window title, otherwise `start` treats a quoted url as one
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+
+    cmd.arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to open url: {url}"))?;
+
+    Ok(())
+}
+
+/// The editor of the user, from `$VISUAL` or `$EDITOR`, falling back to one every platform has.
+fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|v| std::env::var(v).ok())
+        .find(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.into())
+}
+
+/// Opens the text in the editor of the user and waits for it to close, returning the saved text.
+///
+/// The terminal has to be handed over first, see [`crate::tui::Tui::suspend`].
+pub fn edit_in_editor(text: &str) -> anyhow::Result<String> {
+    let path = std::env::temp_dir().join(format!("rev-draft-{}.md", std::process::id()));
+    std::fs::write(&path, text).context("failed to write draft")?;
+
+    // Through the shell, so editors with arguments such as `code --wait` work
+    let editor = editor();
+    let status = shell_process(&format!("{editor} \"{}\"", path.display()))
+        .status()
+        .with_context(|| format!("failed to start editor: {editor}"));
+    let edited = std::fs::read_to_string(&path).context("failed to read draft");
+    let _ = std::fs::remove_file(&path);
+
+    let status = status?;
+    if !status.success() {
+        anyhow::bail!("editor exited with {status}");
+    }
+
+    // Editors end the file with a newline, which isn't part of the comment
+    let edited = edited?;
+    Ok(edited
+        .strip_suffix('\n')
+        .map(|e| e.strip_suffix('\r').unwrap_or(e))
+        .unwrap_or(&edited)
+        .to_string())
+}
+
+/// Copies text to the system clipboard using the native clipboard tool of the platform.
+pub fn copy_to_clipboard(text: &str) -> anyhow::Result<()> {
+    let (program, args): (&str, &[&str]) = if cfg!(windows) {
+        ("clip", &[])
+    } else if cfg!(target_os = "macos") {
+        ("pbcopy", &[])
+    } else if std::env::var("WAYLAND_DISPLAY").is_ok() {
+        ("wl-copy", &[])
+    } else {
+        ("xclip", &["-selection", "clipboard"])
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to start clipboard program: {program}"))?;
+
+    child
+        .stdin
+        .take()
+        .context("clipboard stdin to be available")?
+        .write_all(text.as_bytes())?;
+    child.wait()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shell_command_uses_platform_shell() {
+        let cmd = shell_command("git --no-pager diff");
+        let argv = cmd
+            .get_argv()
+            .iter()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+
+        if cfg!(windows) {
+            assert_eq!(vec!["cmd", "/C", "git --no-pager diff"], argv);
+        } else {
+            assert_eq!(vec!["sh", "-c", "git --no-pager diff"], argv);
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_edit_in_editor() {
+        std::env::set_var("VISUAL", "sed -i 's/draft/final/'");
+
+        let edited = edit_in_editor("a draft\n").unwrap();
+
+        std::env::remove_var("VISUAL");
+        assert_eq!("a final", edited);
+    }
+
+    #[test]
+    fn test_config_dir_respects_env() {
+        let get = |key: &str| match key {
+            "REV_CONFIG_HOME" => Some("some-config-home".to_string()),
+            "REV_DATA_HOME" => Some("some-data-home".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            PathBuf::from("some-config-home"),
+            rev_config::config_home_from_env(get)
+        );
+        assert_eq!(
+            PathBuf::from("some-data-home"),
+            rev_config::data_home_from_env(get)
+        );
+    }
+}
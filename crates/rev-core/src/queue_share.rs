@@ -0,0 +1,343 @@
+use std::path::Path;
+
+use anyhow::Context;
+use kdl::{KdlDocument, KdlNode};
+
+use crate::{
+    config::{Config, QueueSettings, CONFIG_VERSION},
+    saved_searches::{SavedSearch, SavedSearches},
+    store::Store,
+};
+
+/// A queue definition shared between teammates, as a snippet of rev.kdl holding the searches to
+/// queue and the filters and ordering of the `queue {}` block.
+///
+/// ```kdl
+/// searches {
+///     search "payments" "is:pr is:open repo:acme/payments"
+/// }
+/// queue {
+///     labels "backend"
+///     scopes "services/payments/**"
+///     out-of-scope "deprioritize"
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueueSnippet {
+    pub searches: Vec<SavedSearch>,
+    /// The children of the `queue {}` block, kept as written so comments survive the import.
+    pub queue: Option<KdlDocument>,
+}
+
+/// What importing a snippet changed in the config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportOutcome {
+    pub added: Vec<String>,
+    /// Searches of the same name in the config, which the imported ones replaced.
+    pub replaced: Vec<String>,
+    /// The settings of the `queue {}` block which were set.
+    pub queue: Vec<String>,
+}
+
+impl QueueSnippet {
+    /// The snippet of the searches, and the `queue {}` block of the config document.
+    pub fn export(searches: Vec<SavedSearch>, config: &KdlDocument) -> Self {
+        Self {
+            searches,
+            queue: config
+                .get("queue")
+                .and_then(|n| n.children())
+                .filter(|c| !c.nodes().is_empty())
+                .cloned(),
+        }
+    }
+
+    /// Parses and validates a snippet, which may only hold the `searches` and `queue` blocks.
+    pub fn parse(snippet: &str) -> anyhow::Result<Self> {
+        let doc: KdlDocument = snippet.parse().context("failed to parse queue snippet")?;
+        if let Some(other) = doc
+            .nodes()
+            .iter()
+            .map(|n| n.name().value())
+            .find(|n| !["searches", "queue"].contains(n))
+        {
+            anyhow::bail!("queue snippets may only hold searches and queue, found {other}");
+        }
+
+        let searches = doc
+            .get("searches")
+            .and_then(|n| n.children())
+            .map(|children| {
+                children
+                    .nodes()
+                    .iter()
+                    .map(search)
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let queue = doc.get("queue").and_then(|n| n.children()).cloned();
+        if let Some(queue) = queue.as_ref() {
+            QueueSettings::from_kdl(queue)?;
+        }
+        if searches.is_empty() && queue.is_none() {
+            anyhow::bail!("queue snippet holds neither searches nor a queue block");
+        }
+
+        Ok(Self { searches, queue })
+    }
+
+    pub fn render(&self) -> String {
+        let mut doc = KdlDocument::new();
+        if !self.searches.is_empty() {
+            let mut searches = KdlNode::new("searches");
+            let children = searches.ensure_children();
+            for s in &self.searches {
+                let mut node = KdlNode::new("search");
+                node.push(s.name.as_str());
+                node.push(s.query.as_str());
+                children.nodes_mut().push(node);
+            }
+            doc.nodes_mut().push(searches);
+        }
+        if let Some(queue) = self.queue.as_ref() {
+            let mut node = KdlNode::new("queue");
+            node.set_children(queue.clone());
+            doc.nodes_mut().push(node);
+        }
+        doc.fmt();
+
+        doc.to_string()
+    }
+
+    /// Merges the snippet into the config document. Searches replace the ones of the same name,
+    /// settings of the queue block replace the ones already set and the rest is kept.
+    pub fn merge_into(&self, config: &mut KdlDocument) -> ImportOutcome {
+        let mut outcome = ImportOutcome::default();
+
+        if !self.searches.is_empty() {
+            let searches = block(config, "searches");
+            for s in &self.searches {
+                let mut node = KdlNode::new("search");
+                node.push(s.name.as_str());
+                node.push(s.query.as_str());
+
+                let existing = searches.nodes_mut().iter_mut().find(|n| {
+                    n.name().value() == "search"
+                        && n.entries().first().and_then(|e| e.value().as_string()) == Some(&s.name)
+                });
+                match existing {
+                    Some(existing) => {
+                        *existing = node;
+                        outcome.replaced.push(s.name.clone());
+                    }
+                    None => {
+                        searches.nodes_mut().push(node);
+                        outcome.added.push(s.name.clone());
+                    }
+                }
+            }
+        }
+
+        if let Some(queue) = self.queue.as_ref() {
+            let block = block(config, "queue");
+            for node in queue.nodes() {
+                let name = node.name().value();
+                let mut node = node.clone();
+                node.clear_fmt();
+                match block
+                    .nodes_mut()
+                    .iter_mut()
+                    .find(|n| n.name().value() == name)
+                {
+                    Some(existing) => *existing = node,
+                    None => block.nodes_mut().push(node),
+                }
+                outcome.queue.push(name.to_string());
+            }
+        }
+
+        for name in ["searches", "queue"] {
+            if let Some(node) = config.get_mut(name) {
+                node.fmt();
+            }
+        }
+
+        outcome
+    }
+}
+
+/// The snippet of the config at the path, with all searches or only the one named.
+pub fn export_from(path: &Path, store: &Store, name: Option<&str>) -> anyhow::Result<QueueSnippet> {
+    let config = Config::load(path)?;
+    let doc = read_config(path)?;
+    let searches = SavedSearches::all(store, &config.searches)?
+        .into_iter()
+        .filter(|s| name.is_none_or(|name| s.name == name))
+        .collect::<Vec<_>>();
+    if let (Some(name), true) = (name, searches.is_empty()) {
+        anyhow::bail!("no search named {name} in the config or the saved searches");
+    }
+
+    Ok(QueueSnippet::export(searches, &doc))
+}
+
+/// Merges the snippet into the config at the path, leaving the config as it was when the merged
+/// one doesn't load.
+pub fn import_into(path: &Path, snippet: &QueueSnippet) -> anyhow::Result<ImportOutcome> {
+    let original = path
+        .exists()
+        .then(|| std::fs::read_to_string(path))
+        .transpose()
+        .with_context(|| format!("failed to read config file: {}", path.display()))?;
+    let mut doc = read_config(path)?;
+    let outcome = snippet.merge_into(&mut doc);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, doc.to_string())
+        .with_context(|| format!("failed to write config file: {}", path.display()))?;
+    if let Err(e) = Config::load(path) {
+        match original {
+            Some(original) => std::fs::write(path, original)?,
+            None => std::fs::remove_file(path)?,
+        }
+        return Err(e.context("the imported queue breaks the config, it was left as it was"));
+    }
+
+    Ok(outcome)
+}
+
+fn read_config(path: &Path) -> anyhow::Result<KdlDocument> {
+    // A new config starts out at the current version, so it isn't migrated on the next load
+    if !path.exists() {
+        return Ok(format!("version {CONFIG_VERSION}\n").parse()?);
+    }
+
+    std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read config file: {}", path.display()))?
+        .parse()
+        .context("failed to parse kdl config file")
+}
+
+/// The children of the top level block, creating the block if needed.
+fn block<'a>(config: &'a mut KdlDocument, name: &str) -> &'a mut KdlDocument {
+    if config.get(name).is_none() {
+        let mut node = KdlNode::new(name);
+        node.set_trailing("\n");
+        config.nodes_mut().push(node);
+    }
+
+    config
+        .get_mut(name)
+        .expect("block was just added")
+        .ensure_children()
+}
+
+fn search(node: &KdlNode) -> anyhow::Result<SavedSearch> {
+    let values = node
+        .entries()
+        .iter()
+        .filter_map(|e| e.value().as_string())
+        .collect::<Vec<_>>();
+    match (node.name().value(), values.as_slice()) {
+        ("search", [name, query]) if !name.trim().is_empty() && !query.trim().is_empty() => {
+            Ok(SavedSearch {
+                name: name.to_string(),
+                query: query.to_string(),
+            })
+        }
+        _ => anyhow::bail!("searches must be in the form search \"name\" \"query\""),
+    }
+}
+
+/// Reads a snippet from a file, or downloads it when it's a url.
+pub async fn fetch(source: &str) -> anyhow::Result<String> {
+    if source.starts_with("https://") || source.starts_with("http://") {
+        return reqwest::get(source)
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("failed to download queue snippet: {source}"))?
+            .text()
+            .await
+            .context("failed to read queue snippet");
+    }
+
+    std::fs::read_to_string(source)
+        .with_context(|| format!("failed to read queue snippet: {source}"))
+}
+
+#[cfg(test)]
+mod test {
+    use kdl::KdlDocument;
+
+    use super::{ImportOutcome, QueueSnippet};
+    use crate::saved_searches::SavedSearch;
+
+    #[test]
+    fn test_export_and_import_queue() -> anyhow::Result<()> {
+        let mine: KdlDocument = r#"queue {
+    labels "backend"
+    scopes "services/payments/**"
+}
+"#
+        .parse()?;
+        let snippet = QueueSnippet::export(
+            vec![SavedSearch {
+                name: "payments".into(),
+                query: "is:pr is:open repo:acme/payments".into(),
+            }],
+            &mine,
+        )
+        .render();
+
+        let mut theirs: KdlDocument = r#"config {
+    committer "alice"
+}
+searches {
+    search "payments" "is:pr repo:acme/old-payments"
+    search "mine" "is:pr author:@me"
+}
+queue {
+    // Kept as it isn't in the snippet
+    org "acme"
+    labels "frontend"
+}
+"#
+        .parse()?;
+        let outcome = QueueSnippet::parse(&snippet)?.merge_into(&mut theirs);
+
+        assert_eq!(
+            ImportOutcome {
+                added: Vec::new(),
+                replaced: vec!["payments".into()],
+                queue: vec!["labels".into(), "scopes".into()],
+            },
+            outcome
+        );
+        let theirs: KdlDocument = theirs.to_string().parse()?;
+        let searches = theirs.get("searches").unwrap().children().unwrap();
+        assert_eq!(2, searches.nodes().len());
+        assert!(theirs
+            .to_string()
+            .contains(r#"search "payments" "is:pr is:open repo:acme/payments""#));
+        let queue = theirs.get("queue").unwrap().children().unwrap();
+        assert!(queue.get("org").is_some());
+        assert_eq!(
+            Some("backend"),
+            queue.get_arg("labels").and_then(|v| v.as_string())
+        );
+        assert!(queue.get("scopes").is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_invalid_snippets() {
+        assert!(QueueSnippet::parse("config { committer \"mallory\" }").is_err());
+        assert!(QueueSnippet::parse("searches { search \"only a name\" }").is_err());
+        assert!(QueueSnippet::parse("queue { out-of-scope \"sometimes\" }").is_err());
+        assert!(QueueSnippet::parse("").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+const PLAN_DOCUMENT: &str = "review_plan";
+/// Ids no longer in the queue are kept around for a while, as the queue is only ever partially
+/// loaded, but not forever.
+const MAX_ORDERED: usize = 200;
+
+/// The order I want to go through my review queue in, instead of the order of the api.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct ReviewPlan {
+    /// Reviews always going first, in this order.
+    pub pinned: Vec<String>,
+    /// The manual order of the rest of the queue.
+    pub order: Vec<String>,
+}
+
+impl ReviewPlan {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(PLAN_DOCUMENT)
+    }
+
+    pub fn save(&self, store: &Store) -> anyhow::Result<()> {
+        store.save(PLAN_DOCUMENT, self)
+    }
+
+    pub fn is_pinned(&self, id: &str) -> bool {
+        self.pinned.iter().any(|p| p == id)
+    }
+
+    /// Sorts items by the plan: pinned first, then the manual order, then whatever the plan
+    /// doesn't know about in the order it came in.
+    pub fn sort<T>(&self, items: &mut [T], id: impl Fn(&T) -> &str) {
+        let rank = |item: &T| {
+            let id = id(item);
+            if let Some(pos) = self.pinned.iter().position(|p| p == id) {
+                (0, pos)
+            } else if let Some(pos) = self.order.iter().position(|o| o == id) {
+                (1, pos)
+            } else {
+                (2, 0)
+            }
+        };
+
+        items.sort_by_key(rank);
+    }
+
+    /// Remembers the order of the queue as it is shown now.
+    pub fn record(&mut self, ids: &[String]) {
+        let rest = self
+            .order
+            .iter()
+            .filter(|o| !ids.contains(o))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.order = ids.iter().cloned().chain(rest).take(MAX_ORDERED).collect();
+    }
+
+    pub fn toggle_pin(&mut self, id: &str) {
+        if self.is_pinned(id) {
+            self.pinned.retain(|p| p != id);
+        } else {
+            self.pinned.push(id.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ReviewPlan;
+
+    #[test]
+    fn test_sort_by_plan() {
+        let mut plan = ReviewPlan::default();
+        plan.record(&["c".into(), "b".into()]);
+        plan.toggle_pin("d");
+
+        let mut items = vec!["a", "b", "c", "d", "e"];
+        plan.sort(&mut items, |i| i);
+
+        assert_eq!(vec!["d", "c", "b", "a", "e"], items);
+
+        plan.toggle_pin("d");
+        plan.record(&["b".into(), "d".into()]);
+        plan.sort(&mut items, |i| i);
+
+        assert_eq!(vec!["b", "d", "c", "a", "e"], items);
+    }
+}
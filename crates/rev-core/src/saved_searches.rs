@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+const SAVED_SEARCHES_DOCUMENT: &str = "saved_searches";
+
+/// A search query used as a queue instead of the review requests.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+}
+
+impl SavedSearch {
+    /// Parses a search written as `name: query`, a search without a name is named by its query.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let (name, query) = match raw.split_once(": ") {
+            Some((name, query)) if !name.contains(':') => (name.trim(), query.trim()),
+            _ => (raw, raw),
+        };
+        if query.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            name: name.to_string(),
+            query: query.to_string(),
+        })
+    }
+
+    pub fn render(&self) -> String {
+        if self.name == self.query {
+            self.query.clone()
+        } else {
+            format!("{}: {}", self.name, self.query)
+        }
+    }
+}
+
+/// The searches saved from within the tui, the ones from the config are kept in the config.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct SavedSearches {
+    pub searches: Vec<SavedSearch>,
+}
+
+impl SavedSearches {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(SAVED_SEARCHES_DOCUMENT)
+    }
+
+    /// Saves the search, replacing any saved search of the same name.
+    pub fn save(store: &Store, search: SavedSearch) -> anyhow::Result<()> {
+        let mut saved = Self::load(store)?;
+        match saved.searches.iter_mut().find(|s| s.name == search.name) {
+            Some(existing) => *existing = search,
+            None => saved.searches.push(search),
+        }
+
+        store.save(SAVED_SEARCHES_DOCUMENT, &saved)
+    }
+
+    /// The searches from the config followed by the saved ones, the config wins on equal names.
+    pub fn all(store: &Store, configured: &[SavedSearch]) -> anyhow::Result<Vec<SavedSearch>> {
+        let saved = Self::load(store)?;
+
+        Ok(configured
+            .iter()
+            .cloned()
+            .chain(
+                saved
+                    .searches
+                    .into_iter()
+                    .filter(|s| configured.iter().all(|c| c.name != s.name)),
+            )
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SavedSearch;
+
+    #[test]
+    fn test_parse_saved_search() {
+        assert_eq!(
+            Some(SavedSearch {
+                name: "payments".into(),
+                query: "is:pr repo:acme/payments".into()
+            }),
+            SavedSearch::parse("payments: is:pr repo:acme/payments")
+        );
+        assert_eq!(
+            Some(SavedSearch {
+                name: "is:pr author:@me".into(),
+                query: "is:pr author:@me".into()
+            }),
+            SavedSearch::parse(" is:pr author:@me ")
+        );
+        assert_eq!(None, SavedSearch::parse("  "));
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use rev_git_provider::{
+    models::{MergeStrategy, Review, ReviewEvent},
+    GitProvider,
+};
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    store::Store,
+    workspace::parse_reference,
+};
+
+/// A command of a script run by `rev script`, one per line.
+///
+/// Commands act on the review in the form `owner/name#number` following the command, or on the
+/// review of the line before when they leave it out, so several commands can follow a review.
+/// Text with spaces is quoted, blank lines and lines starting with `#` are skipped.
+///
+/// ```text
+/// approve kjuulh/rev#12 "looks good"
+/// label add ready-to-merge
+/// comment "merging once the release is out"
+/// label remove kjuulh/octopush#3 needs-review
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptCommand {
+    Approve(Option<String>),
+    RequestChanges(String),
+    Comment(String),
+    AddLabels(Vec<String>),
+    RemoveLabels(Vec<String>),
+    AutoMerge,
+    Merge(MergeStrategy),
+}
+
+impl ScriptCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScriptCommand::Approve(_) => "approve",
+            ScriptCommand::RequestChanges(_) => "request-changes",
+            ScriptCommand::Comment(_) => "comment",
+            ScriptCommand::AddLabels(_) => "label-add",
+            ScriptCommand::RemoveLabels(_) => "label-remove",
+            ScriptCommand::AutoMerge => "auto-merge",
+            ScriptCommand::Merge(_) => "merge",
+        }
+    }
+
+    async fn perform(&self, provider: &GitProvider, review: &Review) -> anyhow::Result<()> {
+        let (owner, name) = review
+            .owner_and_name()
+            .map(|(o, n)| (o.to_string(), n.to_string()))
+            .context("review repository to be in the form owner/name")?;
+
+        match self {
+            ScriptCommand::Approve(body) => {
+                provider
+                    .submit_review(review.id.clone(), ReviewEvent::Approve, body.clone())
+                    .await
+            }
+            ScriptCommand::RequestChanges(body) => {
+                provider
+                    .submit_review(
+                        review.id.clone(),
+                        ReviewEvent::RequestChanges,
+                        Some(body.clone()),
+                    )
+                    .await
+            }
+            ScriptCommand::Comment(body) => {
+                provider.add_comment(review.id.clone(), body.clone()).await
+            }
+            ScriptCommand::AddLabels(labels) => {
+                provider
+                    .add_labels(review.id.clone(), owner, name, labels.clone())
+                    .await
+            }
+            ScriptCommand::RemoveLabels(labels) => {
+                provider
+                    .remove_labels(review.id.clone(), owner, name, labels.clone())
+                    .await
+            }
+            ScriptCommand::AutoMerge => provider.enable_auto_merge(review.id.clone()).await,
+            ScriptCommand::Merge(strategy) => {
+                provider
+                    .merge_review(owner, name, review.number, *strategy)
+                    .await
+            }
+        }
+    }
+
+    fn audit(&self, entry: AuditEntry) -> AuditEntry {
+        match self {
+            ScriptCommand::Approve(Some(body))
+            | ScriptCommand::RequestChanges(body)
+            | ScriptCommand::Comment(body) => entry.parameter("body", body),
+            ScriptCommand::AddLabels(labels) | ScriptCommand::RemoveLabels(labels) => {
+                entry.parameter("labels", labels.join(","))
+            }
+            ScriptCommand::Merge(strategy) => entry.parameter("strategy", strategy.name()),
+            ScriptCommand::Approve(None) | ScriptCommand::AutoMerge => entry,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptLine {
+    /// The line number in the script, starting from 1.
+    pub line: usize,
+    /// The review in the form `owner/name#number`.
+    pub review: String,
+    pub command: ScriptCommand,
+}
+
+/// Parses the whole script up front, so a typo further down doesn't leave a batch half done.
+pub fn parse(script: &str) -> anyhow::Result<Vec<ScriptLine>> {
+    let mut review: Option<String> = None;
+    let mut lines = Vec::new();
+
+    for (index, line) in script.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut args = words(line).with_context(|| format!("line {number}"))?;
+        let mut name = args.remove(0);
+        if name == "label" {
+            if args.is_empty() {
+                anyhow::bail!("line {number}: label needs add or remove, e.g. label add bug");
+            }
+            name = format!("label {}", args.remove(0));
+        }
+        if args.first().is_some_and(|a| parse_reference(a).is_some()) {
+            review = Some(args.remove(0));
+        }
+        let review = review.clone().with_context(|| {
+            format!("line {number}: {name} needs a review in the form owner/name#number")
+        })?;
+
+        let text = |args: Vec<String>| -> anyhow::Result<String> {
+            match &args[..] {
+                [text] => Ok(text.clone()),
+                _ => anyhow::bail!("line {number}: {name} takes a single quoted text"),
+            }
+        };
+        let command = match name.as_str() {
+            "approve" if args.is_empty() => ScriptCommand::Approve(None),
+            "approve" => ScriptCommand::Approve(Some(text(args)?)),
+            "request-changes" => ScriptCommand::RequestChanges(text(args)?),
+            "comment" => ScriptCommand::Comment(text(args)?),
+            "label add" | "label remove" if args.is_empty() => {
+                anyhow::bail!("line {number}: {name} needs at least one label")
+            }
+            "label add" => ScriptCommand::AddLabels(args),
+            "label remove" => ScriptCommand::RemoveLabels(args),
+            "auto-merge" if args.is_empty() => ScriptCommand::AutoMerge,
+            "merge" => {
+                let strategy = match args.first().map(|s| s.as_str()) {
+                    None | Some("merge") => MergeStrategy::Merge,
+                    Some("squash") => MergeStrategy::Squash,
+                    Some("rebase") => MergeStrategy::Rebase,
+                    Some(other) => anyhow::bail!(
+                        "line {number}: merge strategy must be merge, squash or rebase, got: {other}"
+                    ),
+                };
+                ScriptCommand::Merge(strategy)
+            }
+            "auto-merge" => anyhow::bail!("line {number}: auto-merge takes no arguments"),
+            other => anyhow::bail!(
+                "line {number}: unknown command {other}, must be one of approve, request-changes, comment, label add, label remove, auto-merge or merge"
+            ),
+        };
+
+        lines.push(ScriptLine {
+            line: number,
+            review,
+            command,
+        });
+    }
+
+    Ok(lines)
+}
+
+/// Splits a line into words like a shell does, keeping text in double quotes together.
+fn words(line: &str) -> anyhow::Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            '\\' if quoted => match chars.next() {
+                Some('n') => word.push('\n'),
+                Some(c) => word.push(c),
+                None => anyhow::bail!("unfinished escape at the end of the line"),
+            },
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quoted {
+        anyhow::bail!("unterminated quote");
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+/// What happened to a line of the script.
+#[derive(Debug, Clone)]
+pub struct ScriptOutcome {
+    pub line: usize,
+    pub review: String,
+    pub command: &'static str,
+    pub error: Option<String>,
+}
+
+/// Runs every line of the script, carrying on past the ones which fail.
+pub async fn run(
+    provider: &GitProvider,
+    store: &Store,
+    lines: Vec<ScriptLine>,
+) -> Vec<ScriptOutcome> {
+    let mut reviews: HashMap<String, Review> = HashMap::new();
+    let mut outcomes = Vec::new();
+
+    for line in lines {
+        if !reviews.contains_key(&line.review) {
+            match fetch_review(provider, &line.review).await {
+                Ok(review) => {
+                    reviews.insert(line.review.clone(), review);
+                }
+                Err(e) => {
+                    outcomes.push(ScriptOutcome {
+                        line: line.line,
+                        review: line.review,
+                        command: line.command.name(),
+                        error: Some(format!("{e:#}")),
+                    });
+                    continue;
+                }
+            }
+        }
+        let review = &reviews[&line.review];
+
+        let result = line.command.perform(provider, review).await;
+        AuditLog::record_or_warn(
+            store,
+            line.command
+                .audit(AuditEntry::new("script", line.command.name(), &line.review))
+                .result(&result),
+        );
+
+        outcomes.push(ScriptOutcome {
+            line: line.line,
+            review: line.review,
+            command: line.command.name(),
+            error: result.err().map(|e| format!("{e:#}")),
+        });
+    }
+
+    outcomes
+}
+
+/// Runs a single command against the review, as `rev approve` and `rev comment` do, recording it
+/// in the audit log under the source.
+pub async fn run_one(
+    provider: &GitProvider,
+    store: &Store,
+    source: &str,
+    reference: &str,
+    command: ScriptCommand,
+) -> anyhow::Result<()> {
+    let review = fetch_review(provider, reference).await?;
+
+    let result = command.perform(provider, &review).await;
+    AuditLog::record_or_warn(
+        store,
+        command
+            .audit(
+                AuditEntry::new(source, command.name(), reference)
+                    .parameter("author", &review.author),
+            )
+            .result(&result),
+    );
+
+    result
+}
+
+async fn fetch_review(provider: &GitProvider, reference: &str) -> anyhow::Result<Review> {
+    let (owner, name, number) = parse_reference(reference)
+        .with_context(|| format!("{reference} isn't in the form owner/name#number"))?;
+
+    provider
+        .get_review(owner.into(), name.into(), number)
+        .await?
+        .with_context(|| format!("{reference} was not found"))
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::MergeStrategy;
+
+    use super::{parse, words, ScriptCommand, ScriptLine};
+
+    #[test]
+    fn test_parse_script() -> anyhow::Result<()> {
+        let script = r#"
+# ship the release
+approve kjuulh/rev#12 "looks good"
+label add ready-to-merge "needs release"
+comment "merging once the \"release\" is out"
+
+merge kjuulh/octopush#3 squash
+"#;
+
+        let line = |line, review: &str, command| ScriptLine {
+            line,
+            review: review.into(),
+            command,
+        };
+        assert_eq!(
+            vec![
+                line(
+                    3,
+                    "kjuulh/rev#12",
+                    ScriptCommand::Approve(Some("looks good".into()))
+                ),
+                line(
+                    4,
+                    "kjuulh/rev#12",
+                    ScriptCommand::AddLabels(vec!["ready-to-merge".into(), "needs release".into()])
+                ),
+                line(
+                    5,
+                    "kjuulh/rev#12",
+                    ScriptCommand::Comment("merging once the \"release\" is out".into())
+                ),
+                line(
+                    7,
+                    "kjuulh/octopush#3",
+                    ScriptCommand::Merge(MergeStrategy::Squash)
+                ),
+            ],
+            parse(script)?
+        );
+
+        assert_eq!(vec!["a", "", "b c"], words(r#"a "" "b c""#)?);
+
+        for invalid in [
+            "approve",
+            "comment kjuulh/rev#1 unquoted text",
+            "label kjuulh/rev#1",
+            "label add kjuulh/rev#1",
+            "comment kjuulh/rev#1 \"unterminated",
+            "close kjuulh/rev#1",
+        ] {
+            assert!(parse(invalid).is_err(), "{invalid}");
+        }
+
+        Ok(())
+    }
+}
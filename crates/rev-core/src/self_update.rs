@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{config::UpdateSettings, store::Store};
+
+const UPDATE_DOCUMENT: &str = "update";
+/// The version of rev running, rev-core is released along with the binary.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The release asset holding the checksums of the binaries, in the format of `sha256sum`.
+const CHECKSUMS_ASSET: &str = "checksums.txt";
+
+/// The latest release in the feed, in the format of the gitea and github release apis.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+/// When the feed was last checked at startup.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+struct UpdateCheck {
+    checked_at: Option<DateTime<Utc>>,
+}
+
+impl Release {
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    fn asset(&self, name: &str) -> anyhow::Result<&Asset> {
+        self.assets
+            .iter()
+            .find(|a| a.name == name)
+            .with_context(|| format!("release {} has no {name}", self.tag_name))
+    }
+}
+
+/// The name of the release asset built for this platform, e.g. `git-rev-linux-x86_64`.
+pub fn asset_name() -> String {
+    format!(
+        "git-rev-{}-{}{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+pub async fn latest(feed: &str) -> anyhow::Result<Release> {
+    reqwest::Client::new()
+        .get(feed)
+        .header("User-Agent", format!("git-rev/{CURRENT_VERSION}"))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("failed to fetch the release feed: {feed}"))?
+        .json()
+        .await
+        .context("failed to parse the release feed")
+}
+
+/// Whether the version is newer than the current one, comparing the numbers of each part. Missing
+/// parts count as zero, so `0.1` and `0.1.0` are the same version.
+pub fn is_newer(version: &str, current: &str) -> bool {
+    let parts = |v: &str| {
+        v.trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse::<u64>().unwrap_or_default())
+            .collect::<Vec<_>>()
+    };
+    let (mut version, mut current) = (parts(version), parts(current));
+    let len = version.len().max(current.len());
+    version.resize(len, 0);
+    current.resize(len, 0);
+
+    version > current
+}
+
+/// The newer version in the feed, checking at most once per interval so starting rev stays fast
+/// and offline starts don't wait on the network.
+pub async fn check(settings: &UpdateSettings, store: &Store) -> anyhow::Result<Option<String>> {
+    let mut last: UpdateCheck = store.load(UPDATE_DOCUMENT)?;
+    let now = Utc::now();
+    if last
+        .checked_at
+        .is_some_and(|at| now - at < Duration::hours(settings.interval_hours))
+    {
+        return Ok(None);
+    }
+
+    last.checked_at = Some(now);
+    store.save(UPDATE_DOCUMENT, &last)?;
+
+    let release = latest(&settings.feed).await?;
+    Ok(is_newer(release.version(), CURRENT_VERSION).then(|| release.version().to_string()))
+}
+
+/// Replaces the running executable with the binary of the latest release, returning the version
+/// updated to, or `None` when already on the latest.
+pub async fn update(feed: &str) -> anyhow::Result<Option<String>> {
+    let release = latest(feed).await?;
+    if !is_newer(release.version(), CURRENT_VERSION) {
+        return Ok(None);
+    }
+
+    let name = asset_name();
+    let binary = download(&release.asset(&name)?.browser_download_url).await?;
+    let checksums = download(&release.asset(CHECKSUMS_ASSET)?.browser_download_url).await?;
+    let expected = expected_checksum(&String::from_utf8_lossy(&checksums), &name)
+        .with_context(|| format!("{CHECKSUMS_ASSET} of {} has no {name}", release.tag_name))?;
+    let actual = hex(&Sha256::digest(&binary));
+    if !actual.eq_ignore_ascii_case(&expected) {
+        anyhow::bail!("checksum of {name} doesn't match, expected {expected} but got {actual}");
+    }
+
+    let exe = std::env::current_exe().context("failed to find the running executable")?;
+    replace_executable(&exe, &binary)?;
+
+    Ok(Some(release.version().to_string()))
+}
+
+async fn download(url: &str) -> anyhow::Result<Vec<u8>> {
+    Ok(reqwest::get(url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("failed to download {url}"))?
+        .bytes()
+        .await?
+        .to_vec())
+}
+
+/// The checksum of the file from lines in the form `<sha256>  <file>`.
+fn expected_checksum(checksums: &str, file: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let (checksum, name) = line.split_once(char::is_whitespace)?;
+        // sha256sum marks files read in binary mode with a leading `*`
+        (name.trim().trim_start_matches('*') == file).then(|| checksum.to_string())
+    })
+}
+
+/// Writes the binary next to the executable and renames it over it, so a failed download never
+/// leaves a broken executable behind.
+fn replace_executable(exe: &Path, binary: &[u8]) -> anyhow::Result<()> {
+    let new = exe.with_extension("new");
+    std::fs::write(&new, binary)
+        .with_context(|| format!("failed to write the new executable: {}", new.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&new, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    // Windows doesn't allow replacing a running executable, but does allow renaming it
+    if cfg!(windows) {
+        let old = exe.with_extension("old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(exe, &old)?;
+    }
+
+    std::fs::rename(&new, exe)
+        .with_context(|| format!("failed to replace the executable: {}", exe.display()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{expected_checksum, is_newer};
+
+    #[test]
+    fn test_versions_and_checksums() {
+        assert!(is_newer("v0.2.0", "0.1.0"));
+        assert!(is_newer("0.10.0", "0.9.3"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("v0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0-rc.1", "0.1.0"));
+        assert!(!is_newer("0.1.0", "0.1"));
+        assert!(!is_newer("0.1", "0.1.0"));
+        assert!(is_newer("0.1.1", "0.1"));
+
+        let checksums = "\
+abc123  git-rev-linux-x86_64
+def456 *git-rev-macos-aarch64
+";
+        assert_eq!(
+            Some("def456".to_string()),
+            expected_checksum(checksums, "git-rev-macos-aarch64")
+        );
+        assert_eq!(
+            None,
+            expected_checksum(checksums, "git-rev-windows-x86_64.exe")
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use anyhow::Context;
+use rev_git_provider::{
+    models::{ChangedFile, CurrentState, Label, Review, ReviewState},
+    GitProvider,
+};
+
+use crate::i18n::{tr, Text};
+
+/// The formats a review can be rendered to with `rev pr render`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+/// A read-only snapshot of a review, for pasting into design docs or archiving the decision.
+#[derive(Debug, Clone)]
+pub struct ReviewDocument {
+    pub review: Review,
+    pub files: Vec<ChangedFile>,
+}
+
+impl ReviewDocument {
+    /// Fetches the review with its comments, checks and changed files.
+    pub async fn fetch(provider: &GitProvider, reference: &str) -> anyhow::Result<Self> {
+        let (repository, number) = reference
+            .split_once('#')
+            .and_then(|(repository, number)| Some((repository, number.parse().ok()?)))
+            .context("review must be in the form owner/name#number")?;
+        let (owner, name) = repository
+            .split_once('/')
+            .context("review must be in the form owner/name#number")?;
+        let (owner, name) = (owner.to_string(), name.to_string());
+
+        let (review, comments, checks, files) = tokio::try_join!(
+            provider.get_review(owner.clone(), name.clone(), number),
+            provider.get_comments(owner.clone(), name.clone(), number),
+            provider.get_status_checks(owner.clone(), name.clone(), number),
+            provider.get_changed_files(owner, name, number),
+        )?;
+        let mut review = review.with_context(|| format!("{reference} does not exist"))?;
+        review.comments = comments;
+        review.status_checks = checks;
+
+        Ok(Self { review, files })
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Markdown => self.to_markdown(),
+            Format::Html => self.to_html(),
+        }
+    }
+
+    fn additions(&self) -> usize {
+        self.files.iter().map(|f| f.additions).sum()
+    }
+
+    fn deletions(&self) -> usize {
+        self.files.iter().map(|f| f.deletions).sum()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let review = &self.review;
+        let mut md = format!("# {} ({})\n\n", review.title, review.reference());
+
+        md.push_str(&format!("- {}: {}\n", tr(Text::Author), review.author));
+        if let Some(date) = review.publish_at {
+            md.push_str(&format!(
+                "- {}: {}\n",
+                tr(Text::DateCreated),
+                date.format("%Y-%m-%d")
+            ));
+        }
+        if !review.labels.is_empty() {
+            md.push_str(&format!(
+                "- {}: {}\n",
+                tr(Text::Labels),
+                review
+                    .labels
+                    .iter()
+                    .map(|l| l.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        for (login, state) in reviewers(review) {
+            md.push_str(&format!(
+                "- {}: {login} ({state:?})\n",
+                tr(Text::RoleReviewer)
+            ));
+        }
+
+        md.push_str("\n---\n\n");
+        match review.description.trim() {
+            "" => md.push_str(&format!("_{}_\n", tr(Text::NoDescription))),
+            description => md.push_str(&format!("{description}\n")),
+        }
+
+        if !self.files.is_empty() {
+            md.push_str(&format!(
+                "\n## {} (+{} -{})\n\n| | + | - |\n|---|---:|---:|\n",
+                tr(Text::ChangedFiles),
+                self.additions(),
+                self.deletions()
+            ));
+            for file in self.files.iter() {
+                md.push_str(&format!(
+                    "| `{}` | {} | {} |\n",
+                    file.path, file.additions, file.deletions
+                ));
+            }
+        }
+
+        let checks = review.status_checks.as_deref().unwrap_or_default();
+        if !checks.is_empty() {
+            md.push_str(&format!("\n## {}\n\n", tr(Text::StatusChecks)));
+            for check in checks {
+                let name = match check.url() {
+                    Some(url) => format!("[{}]({url})", check.name()),
+                    None => check.name().to_string(),
+                };
+                md.push_str(&format!("- {} {name}\n", state_mark(check.current())));
+            }
+        }
+
+        let comments = review
+            .comments
+            .as_ref()
+            .map(|c| c.comments.as_slice())
+            .unwrap_or_default();
+        if !comments.is_empty() {
+            md.push_str(&format!("\n## {}\n", tr(Text::Comments)));
+            for comment in comments {
+                md.push_str(&format!(
+                    "\n### {}\n\n{}\n",
+                    comment.author,
+                    comment.body.trim()
+                ));
+            }
+        }
+
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let review = &self.review;
+        let mut body = format!(
+            "<h1>{} <small>{}</small></h1>\n<ul>\n<li>{}: {}</li>\n",
+            escape(&review.title),
+            escape(&review.reference()),
+            tr(Text::Author),
+            escape(&review.author)
+        );
+        if let Some(date) = review.publish_at {
+            body.push_str(&format!(
+                "<li>{}: {}</li>\n",
+                tr(Text::DateCreated),
+                date.format("%Y-%m-%d")
+            ));
+        }
+        if !review.labels.is_empty() {
+            let chips = review.labels.iter().map(chip).collect::<Vec<_>>().join(" ");
+            body.push_str(&format!("<li>{}: {chips}</li>\n", tr(Text::Labels)));
+        }
+        for (login, state) in reviewers(review) {
+            body.push_str(&format!(
+                "<li>{}: {} ({state:?})</li>\n",
+                tr(Text::RoleReviewer),
+                escape(login)
+            ));
+        }
+        body.push_str("</ul>\n<hr>\n");
+
+        match review.description.trim() {
+            "" => body.push_str(&format!("<p><em>{}</em></p>\n", tr(Text::NoDescription))),
+            description => body.push_str(&paragraphs(description)),
+        }
+
+        if !self.files.is_empty() {
+            body.push_str(&format!(
+                "<h2>{} <span class=\"added\">+{}</span> <span class=\"removed\">-{}</span></h2>\n<table>\n",
+                tr(Text::ChangedFiles),
+                self.additions(),
+                self.deletions()
+            ));
+            for file in self.files.iter() {
+                body.push_str(&format!(
+                    "<tr><td><code>{}</code></td><td class=\"added\">+{}</td><td class=\"removed\">-{}</td></tr>\n",
+                    escape(&file.path),
+                    file.additions,
+                    file.deletions
+                ));
+            }
+            body.push_str("</table>\n");
+        }
+
+        let checks = review.status_checks.as_deref().unwrap_or_default();
+        if !checks.is_empty() {
+            body.push_str(&format!("<h2>{}</h2>\n<ul>\n", tr(Text::StatusChecks)));
+            for check in checks {
+                let name = match check.url() {
+                    Some(url) => {
+                        format!("<a href=\"{}\">{}</a>", escape(url), escape(check.name()))
+                    }
+                    None => escape(check.name()),
+                };
+                body.push_str(&format!(
+                    "<li>{} {name}</li>\n",
+                    state_mark(check.current())
+                ));
+            }
+            body.push_str("</ul>\n");
+        }
+
+        let comments = review
+            .comments
+            .as_ref()
+            .map(|c| c.comments.as_slice())
+            .unwrap_or_default();
+        if !comments.is_empty() {
+            body.push_str(&format!("<h2>{}</h2>\n", tr(Text::Comments)));
+            for comment in comments {
+                body.push_str(&format!(
+                    "<section class=\"comment\">\n<h3>{}</h3>\n{}</section>\n",
+                    escape(&comment.author),
+                    paragraphs(&comment.body)
+                ));
+            }
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 50rem; margin: 2rem auto; line-height: 1.5; }}
+small {{ color: #666; font-weight: normal; }}
+table {{ border-collapse: collapse; }}
+td {{ padding: 0 1rem 0 0; }}
+.added {{ color: #1a7f37; }}
+.removed {{ color: #cf222e; }}
+.chip {{ background: #ddd; border-radius: 1rem; padding: 0 0.5rem; font-size: 0.9em; }}
+.comment {{ border-left: 3px solid #ddd; padding-left: 1rem; margin-bottom: 1rem; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+            escape(&review.title)
+        )
+    }
+}
+
+/// The participants who submitted a review, with their latest verdict.
+fn reviewers(review: &Review) -> impl Iterator<Item = (&str, &ReviewState)> {
+    review
+        .participants
+        .iter()
+        .filter_map(|p| Some((p.login.as_str(), p.review.as_ref()?)))
+}
+
+/// A label in its color, with text readable on it.
+fn chip(label: &Label) -> String {
+    let style = match (label.rgb(), label.is_light()) {
+        (Some((r, g, b)), Some(light)) => {
+            let text = if light { "#000" } else { "#fff" };
+            format!(" style=\"background: #{r:02x}{g:02x}{b:02x}; color: {text};\"")
+        }
+        _ => String::new(),
+    };
+    let title = label
+        .description
+        .as_deref()
+        .map(|d| format!(" title=\"{}\"", escape(d)))
+        .unwrap_or_default();
+
+    format!(
+        "<span class=\"chip\"{style}{title}>{}</span>",
+        escape(&label.name)
+    )
+}
+
+fn state_mark(state: &CurrentState) -> &'static str {
+    match state {
+        CurrentState::Success => "✓",
+        CurrentState::Failure => "✗",
+        CurrentState::Pending => "…",
+        CurrentState::Expired => "-",
+    }
+}
+
+/// Markdown is kept as written, split into paragraphs on blank lines.
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("<p>{}</p>\n", escape(p).replace('\n', "<br>\n")))
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{ChangedFile, Comment, Comments, Label, Review};
+
+    use super::ReviewDocument;
+
+    fn document() -> ReviewDocument {
+        ReviewDocument {
+            review: Review {
+                id: "1".into(),
+                number: 12,
+                title: "Render <reviews>".into(),
+                repository: "kjuulh/rev".into(),
+                url: "https://github.com/kjuulh/rev/pull/12".into(),
+                description: "Adds rendering.\n\nSecond paragraph".into(),
+                head_sha: "abc".into(),
+                head_branch: "share".into(),
+                author: "kjuulh".into(),
+                publish_at: None,
+                additions: 0,
+                deletions: 0,
+                changed_files: 0,
+                labels: vec![Label {
+                    name: "feature".into(),
+                    color: Some("a2eeef".into()),
+                    description: None,
+                }],
+                comments: Some(Comments {
+                    has_previous: false,
+                    comments: vec![Comment {
+                        id: "c1".into(),
+                        author: "octocat".into(),
+                        text: "LGTM".into(),
+                        body: "LGTM".into(),
+                        url: String::new(),
+                        last_edited_at: None,
+                    }],
+                    threads: Vec::new(),
+                }),
+                status_checks: None,
+                participants: Vec::new(),
+            },
+            files: vec![ChangedFile {
+                path: "src/share.rs".into(),
+                additions: 10,
+                deletions: 2,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_markdown_and_html() {
+        let document = document();
+
+        let md = document.to_markdown();
+        assert!(md.starts_with("# Render <reviews> (kjuulh/rev#12)"));
+        assert!(md.contains("| `src/share.rs` | 10 | 2 |"));
+        assert!(md.contains("### octocat\n\nLGTM"));
+
+        let html = document.to_html();
+        assert!(html.contains("<h1>Render &lt;reviews&gt; <small>kjuulh/rev#12</small></h1>"));
+        assert!(html.contains("<p>Adds rendering.</p>\n<p>Second paragraph</p>"));
+        assert!(!html.contains("<reviews>"));
+        assert!(html.contains("background: #a2eeef; color: #000;\">feature</span>"));
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+const SNOOZE_DOCUMENT: &str = "snoozed";
+
+/// How long skipping a review keeps it out of the queue.
+pub fn skip_duration() -> Duration {
+    Duration::days(1)
+}
+
+/// A review kept out of the queue and the list, by its id.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Snooze {
+    /// The review in the form `owner/name#number`.
+    pub review: String,
+    pub title: String,
+    /// When the review comes back, `None` keeps it away until it's unsnoozed.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// The reviews snoozed from the tui, shown on the snoozed page where they can be brought back.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct Snoozes {
+    pub reviews: BTreeMap<String, Snooze>,
+}
+
+impl Snoozes {
+    /// The snoozes still in effect, forgetting the ones which have run out.
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        let mut snoozes: Self = store.load(SNOOZE_DOCUMENT)?;
+        let count = snoozes.reviews.len();
+        snoozes.expire(Utc::now());
+        if snoozes.reviews.len() != count {
+            store.save(SNOOZE_DOCUMENT, &snoozes)?;
+        }
+
+        Ok(snoozes)
+    }
+
+    pub fn snooze(store: &Store, id: &str, snooze: Snooze) -> anyhow::Result<()> {
+        let mut snoozes = Self::load(store)?;
+        snoozes.reviews.insert(id.to_string(), snooze);
+        store.save(SNOOZE_DOCUMENT, &snoozes)
+    }
+
+    pub fn unsnooze(store: &Store, id: &str) -> anyhow::Result<()> {
+        let mut snoozes = Self::load(store)?;
+        snoozes.reviews.remove(id);
+        store.save(SNOOZE_DOCUMENT, &snoozes)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.reviews.contains_key(id)
+    }
+
+    fn expire(&mut self, now: DateTime<Utc>) {
+        self.reviews
+            .retain(|_, s| s.until.is_none_or(|until| until > now));
+    }
+}
+
+impl Snooze {
+    pub fn new(review: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            review: review.into(),
+            title: title.into(),
+            until: None,
+        }
+    }
+
+    pub fn for_duration(mut self, duration: Duration) -> Self {
+        self.until = Some(Utc::now() + duration);
+        self
+    }
+}
+
+/// Loads the snoozes to filter a stream by, keeping everything when they can't be read.
+pub fn load_or_warn(store: &Store) -> Snoozes {
+    Snoozes::load(store)
+        .context("failed to load snoozed reviews")
+        .unwrap_or_else(|e| {
+            tracing::warn!("{e:#}");
+            Snoozes::default()
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+
+    use super::{Snooze, Snoozes};
+    use crate::store::Store;
+
+    #[test]
+    fn test_snooze_and_expire() -> anyhow::Result<()> {
+        let store = Store::in_memory();
+
+        Snoozes::snooze(&store, "PR_1", Snooze::new("kjuulh/rev#1", "Add retries"))?;
+        Snoozes::snooze(
+            &store,
+            "PR_2",
+            Snooze::new("kjuulh/rev#2", "Bump tokio").for_duration(Duration::hours(4)),
+        )?;
+        Snoozes::snooze(
+            &store,
+            "PR_3",
+            Snooze::new("kjuulh/rev#3", "Old").for_duration(Duration::hours(-1)),
+        )?;
+
+        let snoozes = Snoozes::load(&store)?;
+        assert!(snoozes.contains("PR_1"));
+        assert!(snoozes.contains("PR_2"));
+        assert!(!snoozes.contains("PR_3"));
+        assert!(snoozes.reviews["PR_2"].until.unwrap() > Utc::now());
+
+        Snoozes::unsnooze(&store, "PR_1")?;
+        assert!(!Snoozes::load(&store)?.contains("PR_1"));
+
+        Ok(())
+    }
+}
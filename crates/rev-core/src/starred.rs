@@ -0,0 +1,141 @@
+use rev_git_provider::models::ReviewListItem;
+use serde::{Deserialize, Serialize};
+
+use crate::{review_plan::ReviewPlan, saved_searches::SavedSearch, store::Store};
+
+const STARRED_DOCUMENT: &str = "starred_repositories";
+
+/// The repositories starred from within the tui, in the form `owner/name`, the ones starred in
+/// the config are kept in the config.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct StarredRepositories {
+    pub repositories: Vec<String>,
+}
+
+impl StarredRepositories {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(STARRED_DOCUMENT)
+    }
+
+    /// Stars the repository or takes the star away again, returning whether it's starred now.
+    pub fn toggle(store: &Store, repository: &str) -> anyhow::Result<bool> {
+        let mut starred = Self::load(store)?;
+        let before = starred.repositories.len();
+        starred
+            .repositories
+            .retain(|r| !r.eq_ignore_ascii_case(repository));
+        let now_starred = starred.repositories.len() == before;
+        if now_starred {
+            starred.repositories.push(repository.to_string());
+        }
+        store.save(STARRED_DOCUMENT, &starred)?;
+
+        Ok(now_starred)
+    }
+
+    /// The repositories starred in the config followed by the ones starred from the tui.
+    pub fn all(store: &Store, configured: &[String]) -> anyhow::Result<Vec<String>> {
+        let starred = Self::load(store)?;
+
+        Ok(configured
+            .iter()
+            .cloned()
+            .chain(
+                starred
+                    .repositories
+                    .into_iter()
+                    .filter(|s| configured.iter().all(|c| !c.eq_ignore_ascii_case(s))),
+            )
+            .collect())
+    }
+}
+
+/// Loads the starred repositories to boost the queue with, leaving the ones of the tui out when
+/// they can't be read.
+pub fn load_or_warn(store: &Store, configured: &[String]) -> Vec<String> {
+    StarredRepositories::all(store, configured).unwrap_or_else(|e| {
+        tracing::warn!("failed to load starred repositories: {e:#}");
+        configured.to_vec()
+    })
+}
+
+pub fn is_starred(starred: &[String], item: &ReviewListItem) -> bool {
+    let repository = format!("{}/{}", item.owner, item.name);
+
+    starred.iter().any(|s| s.eq_ignore_ascii_case(&repository))
+}
+
+/// Moves the reviews of starred repositories up to right below the pinned reviews, keeping the
+/// order of the plan otherwise.
+pub fn boost(starred: &[String], plan: &ReviewPlan, items: &mut [ReviewListItem]) {
+    if starred.is_empty() {
+        return;
+    }
+
+    items.sort_by_key(|i| (!plan.is_pinned(&i.id), !is_starred(starred, i)));
+}
+
+/// The queue of the open reviews of the starred repositories, `None` without any.
+pub fn queue(name: &str, starred: &[String]) -> Option<SavedSearch> {
+    if starred.is_empty() {
+        return None;
+    }
+
+    let repositories = starred
+        .iter()
+        .map(|r| format!("repo:{r}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Some(SavedSearch {
+        name: name.to_string(),
+        query: format!("is:pr state:open {repositories}"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::ReviewListItem;
+
+    use super::{boost, queue, StarredRepositories};
+    use crate::{review_plan::ReviewPlan, store::Store};
+
+    #[test]
+    fn test_starred_repositories() -> anyhow::Result<()> {
+        let store = Store::in_memory();
+        assert!(StarredRepositories::toggle(&store, "acme/web")?);
+        assert!(StarredRepositories::toggle(&store, "acme/api")?);
+        assert!(!StarredRepositories::toggle(&store, "Acme/Web")?);
+        let starred = StarredRepositories::all(&store, &["acme/payments".into()])?;
+        assert_eq!(vec!["acme/payments", "acme/api"], starred);
+
+        let item = |id: &str, name: &str| ReviewListItem {
+            id: id.into(),
+            owner: "acme".into(),
+            name: name.into(),
+            ..Default::default()
+        };
+        let mut items = vec![
+            item("1", "web"),
+            item("2", "api"),
+            item("3", "docs"),
+            item("4", "payments"),
+        ];
+        let mut plan = ReviewPlan::default();
+        plan.toggle_pin("3");
+        plan.sort(&mut items, |i| &i.id);
+        boost(&starred, &plan, &mut items);
+        assert_eq!(
+            vec!["3", "2", "4", "1"],
+            items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            "is:pr state:open repo:acme/payments repo:acme/api",
+            queue("starred", &starred).unwrap().query
+        );
+        assert_eq!(None, queue("starred", &[]));
+
+        Ok(())
+    }
+}
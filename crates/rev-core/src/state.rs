@@ -0,0 +1,289 @@
+//! The state shared by the pages, the reviews of the queues and where the session is at, so the
+//! list, the review and other views show the same data.
+//!
+//! The app reduces every action into the state before the pages get it, so a page reading the
+//! state in its update sees what the action brought.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use rev_git_provider::models::{RateLimit, ReviewListItem};
+use tokio::sync::{mpsc::Receiver, Mutex};
+
+use crate::{
+    action::{Action, GitHubPrAction},
+    file_badges::FileBadge,
+    git_pull_requests::GitPullRequests,
+};
+
+/// How many reviews a fetch of the list waits for before they're shown.
+const PAGE_SIZE: usize = 4;
+
+/// A handle on the state, clones share it.
+#[derive(Clone, Default)]
+pub struct State(Arc<RwLock<AppState>>);
+
+impl State {
+    pub fn read(&self) -> RwLockReadGuard<'_, AppState> {
+        self.0.read().unwrap()
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, AppState> {
+        self.0.write().unwrap()
+    }
+
+    pub fn reduce(&self, action: &Action) {
+        self.write().reduce(action)
+    }
+}
+
+#[derive(Default)]
+pub struct AppState {
+    /// The reviews fetched by the name of their saved search, `None` for the review requests.
+    queues: HashMap<Option<String>, Vec<ReviewListItem>>,
+    /// The changed files of the reviews by their id, once fetched.
+    files: HashMap<String, ReviewFiles>,
+    /// Where the fetches of the queues are at, by the name of their saved search.
+    cursors: HashMap<Option<String>, Arc<Mutex<QueueCursor>>>,
+    /// Why fetching the queues failed, by the name of their saved search, until they're fetched.
+    errors: HashMap<Option<String>, String>,
+    pub session: Session,
+}
+
+/// The changed paths of a review, and the badges computed from them.
+#[derive(Clone, Debug, Default)]
+pub struct ReviewFiles {
+    pub badges: Vec<FileBadge>,
+    pub paths: Vec<String>,
+}
+
+/// Where the fetch of a queue is at, shared by its fetches so one replacing another picks up the
+/// reviews the other received but hadn't sent yet.
+#[derive(Default)]
+pub struct QueueCursor {
+    stream: Option<Receiver<anyhow::Result<ReviewListItem>>>,
+    received: Vec<ReviewListItem>,
+    first_page: bool,
+}
+
+impl QueueCursor {
+    /// Receives the next page of the queue with the labels, starting the search for the first
+    /// one. Along with whether it's the first page, which replaces the reviews fetched before.
+    /// The reviews received before a failure are kept for the next page.
+    pub async fn next_page(
+        &mut self,
+        prs: &GitPullRequests,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<(Vec<ReviewListItem>, bool)> {
+        if self.stream.is_none() {
+            self.stream = Some(prs.run(tags).await?);
+            self.first_page = true;
+        }
+
+        if let Some(stream) = self.stream.as_mut() {
+            while self.received.len() < PAGE_SIZE {
+                match stream.recv().await {
+                    Some(pr) => self.received.push(pr?),
+                    None => break,
+                }
+            }
+        }
+
+        Ok((
+            std::mem::take(&mut self.received),
+            std::mem::take(&mut self.first_page),
+        ))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Session {
+    /// The saved search shown on the list, `None` for the review requests.
+    pub queue: Option<String>,
+    /// The keybind profile in use, `None` for the default keybinds.
+    pub keybind_profile: Option<String>,
+    /// The api budget left, as of the last fetch.
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl AppState {
+    pub fn reduce(&mut self, action: &Action) {
+        match action {
+            Action::GitHubPrs(GitHubPrAction::AddReviews {
+                queue,
+                items,
+                replace,
+            }) => {
+                self.errors.remove(queue);
+                let reviews = self.queues.entry(queue.clone()).or_default();
+                if *replace {
+                    reviews.clear();
+                }
+                for item in items {
+                    if !reviews.iter().any(|r| r.id == item.id) {
+                        reviews.push(item.clone());
+                    }
+                }
+            }
+            Action::GitHubPrs(GitHubPrAction::FileBadges { id, badges, paths }) => {
+                self.files.insert(
+                    id.clone(),
+                    ReviewFiles {
+                        badges: badges.clone(),
+                        paths: paths.clone(),
+                    },
+                );
+            }
+            Action::GitHubPrs(GitHubPrAction::RateLimit(limit)) => {
+                self.session.rate_limit = Some(*limit)
+            }
+            Action::GitHubPrs(GitHubPrAction::QueueFailed { queue, message }) => {
+                self.errors.insert(queue.clone(), message.clone());
+            }
+            Action::QueueShown(queue) => self.session.queue = queue.clone(),
+            Action::KeybindProfile(profile) => self.session.keybind_profile = profile.clone(),
+            _ => {}
+        }
+    }
+
+    /// The reviews of the queue in the order fetched, `None` until its first page arrived.
+    pub fn reviews(&self, queue: Option<&str>) -> Option<&[ReviewListItem]> {
+        self.queues
+            .get(&queue.map(str::to_string))
+            .map(|r| r.as_slice())
+    }
+
+    /// The reviews of the queue, for the list to keep them in the order of the plan.
+    pub fn reviews_mut(&mut self, queue: Option<&str>) -> Option<&mut Vec<ReviewListItem>> {
+        self.queues.get_mut(&queue.map(str::to_string))
+    }
+
+    /// The number of reviews fetched for the queue so far.
+    pub fn count(&self, queue: Option<&str>) -> Option<usize> {
+        self.reviews(queue).map(|r| r.len())
+    }
+
+    /// The cursor of the fetch of the queue, the same one until the queue is restarted.
+    pub fn cursor(&mut self, queue: Option<&str>) -> Arc<Mutex<QueueCursor>> {
+        self.cursors
+            .entry(queue.map(str::to_string))
+            .or_default()
+            .clone()
+    }
+
+    /// Fetches the queue from the start on its next fetch, its reviews are shown until the first
+    /// page replaces them.
+    pub fn restart_queue(&mut self, queue: Option<&str>) {
+        let queue = queue.map(str::to_string);
+        self.cursors.remove(&queue);
+        self.errors.remove(&queue);
+    }
+
+    /// Why fetching the queue failed, until it's fetched again.
+    pub fn error(&self, queue: Option<&str>) -> Option<&str> {
+        self.errors
+            .get(&queue.map(str::to_string))
+            .map(|e| e.as_str())
+    }
+
+    pub fn files(&self, id: &str) -> Option<&ReviewFiles> {
+        self.files.get(id)
+    }
+
+    /// Drops the review from every queue, e.g. once it's snoozed.
+    pub fn remove_review(&mut self, id: &str) {
+        for reviews in self.queues.values_mut() {
+            reviews.retain(|r| r.id != id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use rev_git_provider::models::ReviewListItem;
+
+    use super::State;
+    use crate::action::{Action, GitHubPrAction};
+
+    fn item(id: &str) -> ReviewListItem {
+        ReviewListItem {
+            id: id.into(),
+            ..Default::default()
+        }
+    }
+
+    fn add(queue: Option<&str>, ids: &[&str], replace: bool) -> Action {
+        Action::GitHubPrs(GitHubPrAction::AddReviews {
+            queue: queue.map(str::to_string),
+            items: ids.iter().map(|id| item(id)).collect(),
+            replace,
+        })
+    }
+
+    #[test]
+    fn test_reduce_reviews() {
+        let state = State::default();
+        state.reduce(&add(None, &["a", "b"], true));
+        state.reduce(&add(None, &["b", "c"], false));
+        state.reduce(&add(Some("team"), &["d"], true));
+
+        assert_eq!(Some(3), state.read().count(None));
+        assert_eq!(Some(1), state.read().count(Some("team")));
+        assert_eq!(None, state.read().count(Some("other")));
+
+        state.write().remove_review("b");
+        let ids = state
+            .read()
+            .reviews(None)
+            .unwrap()
+            .iter()
+            .map(|r| r.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["a", "c"], ids);
+
+        state.reduce(&add(None, &["e"], true));
+        assert_eq!(Some(1), state.read().count(None));
+
+        state.reduce(&Action::QueueShown(Some("team".into())));
+        assert_eq!(Some("team"), state.read().session.queue.as_deref());
+    }
+
+    #[test]
+    fn test_restart_queue() {
+        let state = State::default();
+        let cursor = state.write().cursor(Some("team"));
+        assert!(Arc::ptr_eq(&cursor, &state.write().cursor(Some("team"))));
+        assert!(!Arc::ptr_eq(&cursor, &state.write().cursor(None)));
+
+        state.write().restart_queue(Some("team"));
+        assert!(!Arc::ptr_eq(&cursor, &state.write().cursor(Some("team"))));
+    }
+
+    #[test]
+    fn test_queue_errors() {
+        let state = State::default();
+        let failed = |queue: Option<&str>| {
+            Action::GitHubPrs(GitHubPrAction::QueueFailed {
+                queue: queue.map(str::to_string),
+                message: "token missing scope read:org".into(),
+            })
+        };
+        state.reduce(&failed(None));
+        state.reduce(&failed(Some("team")));
+
+        assert_eq!(
+            Some("token missing scope read:org"),
+            state.read().error(None)
+        );
+        assert_eq!(None, state.read().error(Some("other")));
+
+        state.reduce(&add(None, &["a"], true));
+        assert_eq!(None, state.read().error(None));
+        state.write().restart_queue(Some("team"));
+        assert_eq!(None, state.read().error(Some("team")));
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    i18n::{tr, Text},
+};
+
+/// The sources whose entries are verdicts given by me, the `ooo` comments aren't.
+const VERDICT_SOURCES: &[&str] = &["tui", "bot", "cli"];
+
+/// How often reviews were approved, sent back or only commented on.
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Verdicts {
+    pub approve: usize,
+    pub request_changes: usize,
+    pub comment: usize,
+}
+
+impl Verdicts {
+    pub fn total(&self) -> usize {
+        self.approve + self.request_changes + self.comment
+    }
+
+    /// The share of verdicts sending the review back to the author, from 0 to 1.
+    pub fn bounce_rate(&self) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.request_changes as f64 / total as f64,
+        }
+    }
+
+    fn add(&mut self, action: &str) {
+        match action {
+            "approve" => self.approve += 1,
+            "request-changes" => self.request_changes += 1,
+            "comment" => self.comment += 1,
+            _ => {}
+        }
+    }
+}
+
+/// My verdicts from the audit log, broken down by repository and by the author of the review.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct VerdictStats {
+    pub total: Verdicts,
+    /// By repository in the form `owner/name`.
+    pub repositories: BTreeMap<String, Verdicts>,
+    /// By the login of the author, verdicts recorded before the author was aren't counted.
+    pub authors: BTreeMap<String, Verdicts>,
+}
+
+impl VerdictStats {
+    pub fn from_log(log: &AuditLog) -> Self {
+        let mut stats = Self::default();
+        for entry in log.entries.iter().filter(|e| is_verdict(e)) {
+            stats.total.add(&entry.action);
+            if let Some((repository, _)) = entry.review.split_once('#') {
+                stats
+                    .repositories
+                    .entry(repository.to_string())
+                    .or_default()
+                    .add(&entry.action);
+            }
+            if let Some(author) = entry.parameters.get("author") {
+                stats
+                    .authors
+                    .entry(author.clone())
+                    .or_default()
+                    .add(&entry.action);
+            }
+        }
+
+        stats
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The breakdowns as tables, the ones bouncing the most reviews first.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&table(tr(Text::Repository), &self.repositories));
+        out.push('\n');
+        out.push_str(&table(tr(Text::Author), &self.authors));
+        out.push('\n');
+        out.push_str(&row(tr(Text::Total), &self.total));
+
+        out
+    }
+}
+
+/// Verdicts which went through, issue comments are recorded as `comment-on-issue`.
+fn is_verdict(entry: &AuditEntry) -> bool {
+    entry.error.is_none()
+        && VERDICT_SOURCES.contains(&entry.source.as_str())
+        && matches!(
+            entry.action.as_str(),
+            "approve" | "request-changes" | "comment"
+        )
+}
+
+fn table(name: &str, verdicts: &BTreeMap<String, Verdicts>) -> String {
+    let mut rows = verdicts.iter().collect::<Vec<_>>();
+    rows.sort_by(|(a_name, a), (b_name, b)| {
+        b.bounce_rate()
+            .total_cmp(&a.bounce_rate())
+            .then(b.total().cmp(&a.total()))
+            .then(a_name.cmp(b_name))
+    });
+
+    let mut out = format!(
+        "{name:<40} {:>8} {:>8} {:>8} {:>8}\n",
+        tr(Text::VerdictApprove),
+        tr(Text::VerdictChanges),
+        tr(Text::VerdictComment),
+        tr(Text::BounceRate)
+    );
+    for (name, verdicts) in rows {
+        out.push_str(&row(name, verdicts));
+    }
+
+    out
+}
+
+fn row(name: &str, verdicts: &Verdicts) -> String {
+    format!(
+        "{name:<40} {:>8} {:>8} {:>8} {:>7.0}%\n",
+        verdicts.approve,
+        verdicts.request_changes,
+        verdicts.comment,
+        verdicts.bounce_rate() * 100.0
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{VerdictStats, Verdicts};
+    use crate::audit::{AuditEntry, AuditLog};
+
+    #[test]
+    fn test_verdicts_by_repository_and_author() {
+        let verdict = |action: &str, review: &str, author: &str| {
+            AuditEntry::new("tui", action, review).parameter("author", author)
+        };
+        let log = AuditLog {
+            entries: vec![
+                verdict("approve", "kjuulh/rev#1", "alice"),
+                verdict("request-changes", "kjuulh/rev#2", "bob"),
+                verdict("request-changes", "kjuulh/rev#2", "bob"),
+                verdict("comment", "acme/api#7", "alice"),
+                // Recorded before the author was
+                AuditEntry::new("tui", "approve", "acme/api#8"),
+                AuditEntry::new("bot", "approve", "acme/api#9").parameter("author", "renovate"),
+                // Neither of these are verdicts
+                verdict("approve", "acme/api#10", "alice")
+                    .result(&Err::<(), _>(anyhow::anyhow!("not allowed"))),
+                AuditEntry::new("ooo", "comment", "acme/api#11"),
+                AuditEntry::new("tui", "comment-on-issue", "acme/api#12"),
+                AuditEntry::new("tui", "merge", "kjuulh/rev#1"),
+            ],
+        };
+
+        let stats = VerdictStats::from_log(&log);
+
+        assert_eq!(6, stats.total.total());
+        assert_eq!(
+            Verdicts {
+                approve: 1,
+                request_changes: 2,
+                comment: 0,
+            },
+            stats.repositories["kjuulh/rev"]
+        );
+        assert_eq!(
+            Verdicts {
+                approve: 2,
+                request_changes: 0,
+                comment: 1,
+            },
+            stats.repositories["acme/api"]
+        );
+        assert_eq!(1.0, stats.authors["bob"].bounce_rate());
+        assert_eq!(2, stats.authors["alice"].total());
+        assert_eq!(3, stats.authors.len());
+
+        let rendered = stats.render();
+        let bob = rendered.lines().position(|l| l.starts_with("bob"));
+        let alice = rendered.lines().position(|l| l.starts_with("alice"));
+        assert!(bob < alice, "the author bouncing the most goes first");
+
+        assert!(stats.to_json().unwrap().contains("\"request_changes\": 2"));
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use anyhow::Context;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Local persistent state for rev, kept as one json document per feature under the data dir.
+///
+/// Documents are small and read/written whole, which keeps them easy to inspect and edit by hand.
+/// When the data dir isn't writable the documents are kept in memory until rev quits instead.
+#[derive(Clone, Debug)]
+pub struct Store {
+    path: PathBuf,
+    memory: Option<Arc<Mutex<HashMap<String, String>>>>,
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        let path = crate::platform::data_dir().join("store");
+        if crate::platform::data_dir_writable() {
+            return Self::new(path);
+        }
+
+        // Shared by the whole process, so every store sees what the others saved
+        static MEMORY: OnceLock<Arc<Mutex<HashMap<String, String>>>> = OnceLock::new();
+        Self {
+            path,
+            memory: Some(MEMORY.get_or_init(Default::default).clone()),
+        }
+    }
+}
+
+impl Store {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            memory: None,
+        }
+    }
+
+    /// A store only kept in memory, for when there is nowhere to write it.
+    pub fn in_memory() -> Self {
+        Self {
+            path: PathBuf::new(),
+            memory: Some(Default::default()),
+        }
+    }
+
+    fn document_path(&self, name: &str) -> PathBuf {
+        self.path.join(format!("{name}.json"))
+    }
+
+    /// Loads a document, returning the default value if it hasn't been written yet.
+    pub fn load<T: DeserializeOwned + Default>(&self, name: &str) -> anyhow::Result<T> {
+        if let Some(memory) = &self.memory {
+            return match memory.lock().unwrap().get(name) {
+                Some(content) => serde_json::from_str(content)
+                    .with_context(|| format!("failed to parse store document: {name}")),
+                None => Ok(T::default()),
+            };
+        }
+
+        let path = self.document_path(name);
+        if !path.exists() {
+            return Ok(T::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read store document: {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("failed to parse store document: {}", path.display()))
+    }
+
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> anyhow::Result<()> {
+        if let Some(memory) = &self.memory {
+            memory
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), serde_json::to_string(value)?);
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.path)
+            .with_context(|| format!("failed to create store at: {}", self.path.display()))?;
+
+        let path = self.document_path(name);
+        let tmp_path = path.with_extension("json.tmp");
+
+        // Write then rename, so a crash never leaves a half written document behind
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(value)?)
+            .with_context(|| format!("failed to write store document: {}", path.display()))?;
+        std::fs::rename(&tmp_path, &path)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    use super::Store;
+
+    #[derive(Serialize, Deserialize, Default, Debug, PartialEq)]
+    struct Document {
+        items: Vec<String>,
+    }
+
+    #[test]
+    fn test_roundtrip_and_default() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("rev-store-test-{}", std::process::id()));
+        let store = Store::new(&dir);
+
+        let doc: Document = store.load("missing")?;
+        assert_eq!(Document::default(), doc);
+
+        let doc = Document {
+            items: vec!["some-item".into()],
+        };
+        store.save("document", &doc)?;
+        assert_eq!(doc, store.load("document")?);
+
+        std::fs::remove_dir_all(dir)?;
+
+        let memory = Store::in_memory();
+        memory.save("document", &doc)?;
+        assert_eq!(doc, memory.clone().load("document")?);
+        assert_eq!(Document::default(), Store::in_memory().load("document")?);
+
+        Ok(())
+    }
+}
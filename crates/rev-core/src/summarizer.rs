@@ -0,0 +1,140 @@
+use std::{process::Stdio, time::Duration};
+
+use anyhow::Context;
+use rev_git_provider::models::Review;
+use tokio::io::AsyncWriteExt;
+
+use crate::platform;
+
+/// How long the summarizer gets before it's given up on, local models can be slow.
+const SUMMARIZE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The conversation of the review as plain text, what's piped to the summarizer.
+pub fn conversation(review: &Review) -> String {
+    let mut out = format!(
+        "# {} ({}#{})\n\n{}\n",
+        review.title,
+        review.repository,
+        review.number,
+        review.description.trim()
+    );
+
+    let Some(comments) = review.comments.as_ref() else {
+        return out;
+    };
+    for comment in &comments.comments {
+        out.push_str(&format!(
+            "\n## {}\n\n{}\n",
+            comment.author,
+            comment.body.trim()
+        ));
+    }
+    for thread in &comments.threads {
+        for comment in &thread.comments {
+            out.push_str(&format!(
+                "\n## {} on {}\n\n{}\n",
+                comment.author,
+                thread.location(),
+                comment.body.trim()
+            ));
+        }
+    }
+
+    out
+}
+
+/// Pipes the conversation into the summarizer command and returns what it printed.
+///
+/// rev doesn't know or care what's behind the command, e.g. `llm` or `ollama run`, anything
+/// reading the conversation on stdin and writing a summary to stdout works.
+pub async fn summarize(command: &str, conversation: String) -> anyhow::Result<String> {
+    let mut child = tokio::process::Command::from(platform::shell_process(command))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("failed to start summarizer: {command}"))?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("summarizer stdin to be available")?;
+    stdin.write_all(conversation.as_bytes()).await?;
+    // Closing stdin tells the summarizer the conversation ended
+    drop(stdin);
+
+    let output = tokio::time::timeout(SUMMARIZE_TIMEOUT, child.wait_with_output())
+        .await
+        .context("summarizer timed out")??;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "summarizer exited with {}: {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+
+    let summary = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if summary.is_empty() {
+        anyhow::bail!("summarizer printed nothing");
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod test {
+    use rev_git_provider::models::{Comment, Comments, Review};
+
+    use super::{conversation, summarize};
+
+    #[tokio::test]
+    async fn test_pipes_conversation_to_command() -> anyhow::Result<()> {
+        let review = Review {
+            id: "1".into(),
+            number: 42,
+            title: "Add retries".into(),
+            repository: "kjuulh/rev".into(),
+            url: "https://github.com/kjuulh/rev/pull/42".into(),
+            description: "Retries failing requests".into(),
+            head_sha: "abc".into(),
+            head_branch: "retries".into(),
+            author: "kjuulh".into(),
+            publish_at: None,
+            additions: 0,
+            deletions: 0,
+            changed_files: 0,
+            labels: Vec::new(),
+            comments: Some(Comments {
+                has_previous: false,
+                comments: vec![Comment {
+                    id: "c1".into(),
+                    author: "alice".into(),
+                    text: "Looks good".into(),
+                    body: "Looks good".into(),
+                    url: String::new(),
+                    last_edited_at: None,
+                }],
+                threads: Vec::new(),
+            }),
+            status_checks: None,
+            participants: Vec::new(),
+        };
+
+        let input = conversation(&review);
+        assert!(input.starts_with("# Add retries (kjuulh/rev#42)\n\nRetries failing requests\n"));
+        assert!(input.contains("## alice\n\nLooks good"));
+
+        if cfg!(unix) {
+            assert_eq!(
+                "Add retries",
+                summarize("head -n 1 | cut -c 3-13", input).await?
+            );
+            assert!(summarize("exit 1", String::new()).await.is_err());
+        }
+
+        Ok(())
+    }
+}
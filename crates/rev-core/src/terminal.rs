@@ -0,0 +1,509 @@
+use std::{cell::RefCell, io::Write};
+
+use base64::Engine;
+use crossterm::{
+    cursor::MoveTo,
+    style::{Attribute, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+
+thread_local! {
+    /// The areas made links while drawing the frame, see [`links_over`].
+    static LINKS: RefCell<Vec<(Rect, String)>> = RefCell::default();
+}
+
+/// How text is moved onto the users clipboard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClipboardBackend {
+    /// Uses the platforms clipboard program, see [`crate::platform::copy_to_clipboard`].
+    Native,
+    /// Asks the terminal to set the clipboard through an OSC 52 escape sequence, this works
+    /// across ssh, as the terminal emulator on the local machine owns the clipboard.
+    Osc52,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// Glyphs used for decorations, with ascii replacements for terminals without unicode support.
+#[derive(Clone, Debug)]
+pub struct Symbols {
+    pub scroll_up: &'static str,
+    pub scroll_down: &'static str,
+    pub success: &'static str,
+    pub pending: &'static str,
+    pub failure: &'static str,
+    pub pinned: &'static str,
+    pub flaky: &'static str,
+    pub warning: &'static str,
+    /// Waiting on something else than checks, such as a review.
+    pub waiting: &'static str,
+    pub draft: &'static str,
+    /// Fills the bars of gauges.
+    pub bar: &'static str,
+}
+
+impl Symbols {
+    pub fn unicode() -> Self {
+        Self {
+            scroll_up: "↑",
+            scroll_down: "↓",
+            success: "✓",
+            pending: "◐",
+            failure: "✗",
+            pinned: "★",
+            flaky: "⚠",
+            warning: "⚠",
+            waiting: "●",
+            draft: "◌",
+            bar: "█",
+        }
+    }
+
+    pub fn ascii() -> Self {
+        Self {
+            scroll_up: "^",
+            scroll_down: "v",
+            success: "+",
+            pending: "o",
+            failure: "x",
+            pinned: "*",
+            flaky: "!",
+            warning: "!",
+            waiting: "*",
+            draft: "~",
+            bar: "#",
+        }
+    }
+}
+
+/// What the terminal we're running in is able to do.
+///
+/// Detected from the environment at startup, each capability can be overridden using
+/// `REV_CLIPBOARD` (auto, osc52, native), `REV_HYPERLINKS` (auto, on, off),
+/// `REV_COLORS` (truecolor, 256, 16), `REV_UNICODE` (auto, on, off) and `REV_SYNC` (auto, on,
+/// off).
+#[derive(Clone, Debug)]
+pub struct Capabilities {
+    pub tmux: bool,
+    pub clipboard: ClipboardBackend,
+    pub hyperlinks: bool,
+    pub colors: ColorDepth,
+    pub unicode: bool,
+    /// Whether frames are drawn inside synchronized updates, which the terminal shows at once
+    /// rather than as they're written.
+    pub synchronized: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::detect()
+    }
+}
+
+impl Capabilities {
+    pub fn detect() -> Self {
+        Self::from_env(|key| std::env::var(key).ok())
+    }
+
+    pub(crate) fn from_env(get: impl Fn(&str) -> Option<String>) -> Self {
+        let remote = ["SSH_TTY", "SSH_CONNECTION", "SSH_CLIENT"]
+            .iter()
+            .any(|k| get(k).is_some());
+        let tmux = get("TMUX").is_some();
+
+        let clipboard = match get("REV_CLIPBOARD").as_deref() {
+            Some("osc52") => ClipboardBackend::Osc52,
+            Some("native") => ClipboardBackend::Native,
+            _ if remote => ClipboardBackend::Osc52,
+            _ => ClipboardBackend::Native,
+        };
+
+        let hyperlinks = match get("REV_HYPERLINKS").as_deref() {
+            Some("on") | Some("true") => true,
+            Some("off") | Some("false") => false,
+            _ => !tmux && supports_hyperlinks(&get),
+        };
+
+        let colors = match get("REV_COLORS").as_deref() {
+            Some("truecolor") | Some("24bit") => ColorDepth::TrueColor,
+            Some("256") => ColorDepth::Ansi256,
+            Some("16") => ColorDepth::Ansi16,
+            _ => detect_color_depth(&get),
+        };
+
+        let unicode = match get("REV_UNICODE").as_deref() {
+            Some("on") | Some("true") => true,
+            Some("off") | Some("false") => false,
+            _ => detect_unicode(&get),
+        };
+
+        // Terminals without synchronized updates ignore the escape sequences, apart from the
+        // linux virtual console, which prints them
+        let synchronized = match get("REV_SYNC").as_deref() {
+            Some("on") | Some("true") => true,
+            Some("off") | Some("false") => false,
+            _ => !matches!(get("TERM").as_deref(), Some("linux") | Some("dumb")),
+        };
+
+        Self {
+            tmux,
+            clipboard,
+            hyperlinks,
+            colors,
+            unicode,
+            synchronized,
+        }
+    }
+
+    pub fn symbols(&self) -> Symbols {
+        if self.unicode {
+            Symbols::unicode()
+        } else {
+            Symbols::ascii()
+        }
+    }
+
+    /// Downgrades a color to something the terminal is able to display.
+    pub fn fit(&self, color: Color) -> Color {
+        match (self.colors, color) {
+            (ColorDepth::TrueColor, c) => c,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => rgb_to_ansi16(r, g, b),
+            (ColorDepth::Ansi16, Color::Indexed(i)) => {
+                let (r, g, b) = ansi256_to_rgb(i);
+                rgb_to_ansi16(r, g, b)
+            }
+            (_, c) => c,
+        }
+    }
+
+    /// Copies text to the clipboard using the detected backend.
+    pub fn copy(&self, text: &str) -> anyhow::Result<()> {
+        match self.clipboard {
+            ClipboardBackend::Native => crate::platform::copy_to_clipboard(text),
+            ClipboardBackend::Osc52 => {
+                let mut stdout = std::io::stdout();
+                stdout.write_all(osc52(text, self.tmux).as_bytes())?;
+                stdout.flush()?;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Makes the text drawn in the area of the frame a clickable link, if the terminal supports
+    /// them. ratatui has no notion of links, so it's written over the frame once drawn, see
+    /// [`links_over`].
+    pub fn link_area(&self, area: Rect, url: &str) {
+        if self.hyperlinks && !url.is_empty() {
+            register_link(area, url);
+        }
+    }
+
+    /// Renders text as a clickable link if the terminal supports it, otherwise returns the text as is.
+    pub fn link(&self, url: &str, text: &str) -> String {
+        if self.hyperlinks {
+            hyperlink(url, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+fn detect_color_depth(get: &impl Fn(&str) -> Option<String>) -> ColorDepth {
+    let colorterm = get("COLORTERM").unwrap_or_default().to_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" || get("WT_SESSION").is_some() {
+        return ColorDepth::TrueColor;
+    }
+
+    match get("TERM") {
+        Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+        _ => ColorDepth::Ansi16,
+    }
+}
+
+fn detect_unicode(get: &impl Fn(&str) -> Option<String>) -> bool {
+    if get("WT_SESSION").is_some() {
+        return true;
+    }
+
+    // The linux virtual console only has a limited font available
+    if get("TERM").as_deref() == Some("linux") {
+        return false;
+    }
+
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|k| get(k).filter(|v| !v.is_empty()))
+        .map(|locale| {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+        .unwrap_or(false)
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            _ => 232 + ((r as u16 - 8) * 24 / 241) as u8,
+        };
+    }
+
+    let scale = |c: u8| (c as u16 * 5 / 255) as u8;
+    16 + 36 * scale(r) + 6 * scale(g) + scale(b)
+}
+
+fn ansi256_to_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => {
+            let (r, g, b) = ANSI16[i as usize].1;
+            (r, g, b)
+        }
+        16..=231 => {
+            let i = i - 16;
+            let level = |c: u8| if c == 0 { 0 } else { 55 + c * 40 };
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (i - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn rgb_to_ansi16(r: u8, g: u8, b: u8) -> Color {
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, cr) + d(g, cg) + d(b, cb)
+    };
+
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(c, _)| *c)
+        .unwrap_or(Color::Reset)
+}
+
+fn supports_hyperlinks(get: &impl Fn(&str) -> Option<String>) -> bool {
+    if get("WT_SESSION").is_some() || get("KITTY_WINDOW_ID").is_some() {
+        return true;
+    }
+
+    if let Some(version) = get("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()) {
+        return version >= 5000;
+    }
+
+    match get("TERM_PROGRAM").as_deref() {
+        Some("iTerm.app") | Some("WezTerm") | Some("vscode") | Some("Hyper") => true,
+        _ => get("TERM")
+            .map(|t| t.contains("kitty") || t.contains("alacritty") || t.contains("foot"))
+            .unwrap_or(false),
+    }
+}
+
+/// OSC 52 sets the clipboard of the terminal, tmux requires the sequence to be wrapped in a
+/// passthrough for it to reach the outer terminal.
+pub fn osc52(text: &str, tmux: bool) -> String {
+    let payload = base64::engine::general_purpose::STANDARD.encode(text);
+    let sequence = format!("\x1b]52;c;{payload}\x07");
+
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// OSC 8 hyperlink, renders `text` as a link to `url` in supporting terminals.
+/// Makes the area a link regardless of the capabilities, for widgets given the url only when the
+/// terminal supports links.
+pub fn register_link(area: Rect, url: &str) {
+    LINKS.with(|links| links.borrow_mut().push((area, url.to_string())));
+}
+
+/// Drops the links of the frame, for frames drawn by an app embedding rev, which writes them.
+pub fn discard_links() {
+    LINKS.with(|links| links.borrow_mut().clear());
+}
+
+/// Writes the text drawn in the areas made links since the last frame again, within the links,
+/// to write after the frame.
+pub fn links_over(buffer: &Buffer) -> std::io::Result<Vec<u8>> {
+    let links = LINKS.with(|links| std::mem::take(&mut *links.borrow_mut()));
+
+    let mut out = Vec::new();
+    for (area, url) in links {
+        let area = area.intersection(buffer.area);
+        for y in area.top()..area.bottom() {
+            let mut text = Vec::new();
+            for x in area.left()..area.right() {
+                let cell = buffer.get(x, y);
+                crossterm::queue!(
+                    text,
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(cell.fg.into()),
+                    SetBackgroundColor(cell.bg.into())
+                )?;
+                for (modifier, attribute) in [
+                    (Modifier::BOLD, Attribute::Bold),
+                    (Modifier::DIM, Attribute::Dim),
+                    (Modifier::ITALIC, Attribute::Italic),
+                    (Modifier::UNDERLINED, Attribute::Underlined),
+                    (Modifier::REVERSED, Attribute::Reverse),
+                    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+                ] {
+                    if cell.modifier.contains(modifier) {
+                        crossterm::queue!(text, SetAttribute(attribute))?;
+                    }
+                }
+                crossterm::queue!(text, Print(&cell.symbol))?;
+            }
+            crossterm::queue!(text, SetAttribute(Attribute::Reset), ResetColor)?;
+
+            crossterm::queue!(
+                out,
+                MoveTo(area.x, y),
+                Print(hyperlink(&url, &String::from_utf8_lossy(&text)))
+            )?;
+        }
+    }
+
+    Ok(out)
+}
+
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn caps(env: &[(&str, &str)]) -> Capabilities {
+        let env = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        Capabilities::from_env(|k| env.get(k).cloned())
+    }
+
+    #[test]
+    fn test_ssh_uses_osc52() {
+        let c = caps(&[("SSH_TTY", "/dev/pts/1")]);
+
+        assert_eq!(ClipboardBackend::Osc52, c.clipboard);
+    }
+
+    #[test]
+    fn test_overrides_win_over_detection() {
+        let c = caps(&[
+            ("SSH_TTY", "/dev/pts/1"),
+            ("REV_CLIPBOARD", "native"),
+            ("TERM_PROGRAM", "WezTerm"),
+            ("REV_HYPERLINKS", "off"),
+        ]);
+
+        assert_eq!(ClipboardBackend::Native, c.clipboard);
+        assert!(!c.hyperlinks);
+    }
+
+    #[test]
+    fn test_detects_synchronized_updates() {
+        assert!(caps(&[("TERM", "xterm-256color")]).synchronized);
+        assert!(!caps(&[("TERM", "linux")]).synchronized);
+        assert!(caps(&[("TERM", "linux"), ("REV_SYNC", "on")]).synchronized);
+        assert!(!caps(&[("TERM", "xterm-kitty"), ("REV_SYNC", "off")]).synchronized);
+    }
+
+    #[test]
+    fn test_detects_color_depth_and_unicode() {
+        let c = caps(&[("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")]);
+        assert_eq!(ColorDepth::Ansi256, c.colors);
+        assert!(c.unicode);
+        assert_eq!("↑", c.symbols().scroll_up);
+
+        let c = caps(&[("TERM", "linux"), ("LANG", "en_US.UTF-8")]);
+        assert_eq!(ColorDepth::Ansi16, c.colors);
+        assert!(!c.unicode);
+        assert_eq!("^", c.symbols().scroll_up);
+
+        let c = caps(&[("COLORTERM", "truecolor"), ("REV_COLORS", "16")]);
+        assert_eq!(ColorDepth::Ansi16, c.colors);
+    }
+
+    #[test]
+    fn test_fit_downgrades_colors() {
+        let c = caps(&[("REV_COLORS", "16")]);
+        assert_eq!(Color::Red, c.fit(Color::Rgb(200, 10, 10)));
+        assert_eq!(Color::White, c.fit(Color::Indexed(231)));
+
+        let c = caps(&[("REV_COLORS", "256")]);
+        assert_eq!(Color::Indexed(196), c.fit(Color::Rgb(255, 0, 0)));
+        assert_eq!(Color::Blue, c.fit(Color::Blue));
+    }
+
+    #[test]
+    fn test_links_over_the_frame() -> anyhow::Result<()> {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 2));
+        buffer.set_string(2, 1, "#12", ratatui::style::Style::default());
+
+        caps(&[("REV_HYPERLINKS", "off")]).link_area(Rect::new(2, 1, 3, 1), "https://a");
+        caps(&[("REV_HYPERLINKS", "on")]).link_area(Rect::new(2, 1, 3, 1), "https://b");
+        let links = String::from_utf8(links_over(&buffer)?)?;
+
+        assert!(!links.contains("https://a"));
+        assert!(links.contains("\x1b]8;;https://b\x1b\\"));
+        // The cells are written one by one, each with its style
+        let text = links
+            .split('\x1b')
+            .filter_map(|s| s.strip_prefix("[39m").or(s.strip_prefix("[49m")))
+            .collect::<String>();
+        assert_eq!("#12", text);
+        assert!(links_over(&buffer)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_escape_sequences() {
+        assert_eq!("\x1b]52;c;cmV2\x07", osc52("rev", false));
+        assert_eq!("\x1bPtmux;\x1b\x1b]52;c;cmV2\x07\x1b\\", osc52("rev", true));
+        assert_eq!(
+            "\x1b]8;;https://github.com\x1b\\github\x1b]8;;\x1b\\",
+            hyperlink("https://github.com", "github")
+        );
+    }
+}
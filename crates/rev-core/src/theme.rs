@@ -0,0 +1,258 @@
+use anyhow::Context;
+use kdl::KdlDocument;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders},
+};
+
+use crate::terminal::Capabilities;
+
+/// The colors of the tui, from the `theme {}` block of rev.kdl.
+///
+/// A theme starts out from one of the presets, `dark`, `light` or `solarized`, and the colors set
+/// in the block replace the ones of the preset. Colors are names like `red` and `darkgray`, hex
+/// like `#268bd2` or an index into the 256 color palette.
+///
+/// ```kdl
+/// theme {
+///     preset "solarized"
+///     selection "#073642"
+///     border "blue"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// The background of the selected row, `None` swaps the colors of the row instead.
+    pub selection: Option<Color>,
+    pub selection_text: Option<Color>,
+    pub border: Color,
+    pub background: Color,
+    /// Column headers and titles.
+    pub header: Color,
+    pub success: Color,
+    pub failure: Color,
+    pub warning: Color,
+    pub info: Color,
+    /// Branches, hunk headers and comments by the committer.
+    pub accent: Color,
+    pub muted: Color,
+    pub status_bar: Color,
+    pub status_bar_text: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// The colors rev always had, drawn on top of the terminals own colors.
+    pub fn dark() -> Self {
+        Self {
+            selection: None,
+            selection_text: None,
+            border: Color::Reset,
+            background: Color::Reset,
+            header: Color::White,
+            success: Color::Green,
+            failure: Color::Red,
+            warning: Color::Yellow,
+            info: Color::Blue,
+            accent: Color::Cyan,
+            muted: Color::DarkGray,
+            status_bar: Color::White,
+            status_bar_text: Color::Black,
+        }
+    }
+
+    /// For terminals with a light background, where white headers and yellow text get lost.
+    pub fn light() -> Self {
+        Self {
+            header: Color::Black,
+            warning: Color::Rgb(0xb0, 0x6d, 0x00),
+            accent: Color::Rgb(0x00, 0x7f, 0x8c),
+            muted: Color::Gray,
+            status_bar: Color::Black,
+            status_bar_text: Color::White,
+            ..Self::dark()
+        }
+    }
+
+    /// The solarized dark palette.
+    pub fn solarized() -> Self {
+        Self {
+            selection: Some(Color::Rgb(0x07, 0x36, 0x42)),
+            selection_text: Some(Color::Rgb(0x93, 0xa1, 0xa1)),
+            border: Color::Rgb(0x58, 0x6e, 0x75),
+            background: Color::Rgb(0x00, 0x2b, 0x36),
+            header: Color::Rgb(0x93, 0xa1, 0xa1),
+            success: Color::Rgb(0x85, 0x99, 0x00),
+            failure: Color::Rgb(0xdc, 0x32, 0x2f),
+            warning: Color::Rgb(0xb5, 0x89, 0x00),
+            info: Color::Rgb(0x26, 0x8b, 0xd2),
+            accent: Color::Rgb(0x2a, 0xa1, 0x98),
+            muted: Color::Rgb(0x58, 0x6e, 0x75),
+            status_bar: Color::Rgb(0x07, 0x36, 0x42),
+            status_bar_text: Color::Rgb(0x93, 0xa1, 0xa1),
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            _ => None,
+        }
+    }
+
+    pub fn from_kdl(doc: &KdlDocument) -> anyhow::Result<Self> {
+        let mut theme = match doc.get_arg("preset") {
+            Some(preset) => {
+                let preset = preset
+                    .as_string()
+                    .context("theme.preset must be a string")?;
+                Self::preset(preset).with_context(|| {
+                    format!("theme.preset must be dark, light or solarized, found {preset}")
+                })?
+            }
+            None => Self::default(),
+        };
+
+        for node in doc.nodes() {
+            let name = node.name().value();
+            if name == "preset" {
+                continue;
+            }
+            let value = node
+                .entries()
+                .first()
+                .and_then(|e| e.value().as_string())
+                .with_context(|| format!("theme.{name} must be a color"))?;
+            let color = value
+                .parse::<Color>()
+                .map_err(|_| anyhow::anyhow!("theme.{name} is not a color: {value}"))?;
+
+            match name {
+                "selection" => theme.selection = Some(color),
+                "selection-text" => theme.selection_text = Some(color),
+                "border" => theme.border = color,
+                "background" => theme.background = color,
+                "header" => theme.header = color,
+                "success" => theme.success = color,
+                "failure" => theme.failure = color,
+                "warning" => theme.warning = color,
+                "info" => theme.info = color,
+                "accent" => theme.accent = color,
+                "muted" => theme.muted = color,
+                "status-bar" => theme.status_bar = color,
+                "status-bar-text" => theme.status_bar_text = color,
+                _ => anyhow::bail!("unknown theme color: {name}"),
+            }
+        }
+
+        Ok(theme)
+    }
+
+    /// The theme with every color downgraded to what the terminal is able to display.
+    pub fn fit(&self, terminal: &Capabilities) -> Self {
+        let fit = |c: Color| terminal.fit(c);
+        Self {
+            selection: self.selection.map(fit),
+            selection_text: self.selection_text.map(fit),
+            border: fit(self.border),
+            background: fit(self.background),
+            header: fit(self.header),
+            success: fit(self.success),
+            failure: fit(self.failure),
+            warning: fit(self.warning),
+            info: fit(self.info),
+            accent: fit(self.accent),
+            muted: fit(self.muted),
+            status_bar: fit(self.status_bar),
+            status_bar_text: fit(self.status_bar_text),
+        }
+    }
+
+    /// The style of the selected row of lists and tables.
+    pub fn selection(&self) -> Style {
+        match (self.selection, self.selection_text) {
+            (None, None) => Style::default().add_modifier(Modifier::REVERSED),
+            (bg, fg) => {
+                let mut style = Style::default();
+                if let Some(bg) = bg {
+                    style = style.bg(bg);
+                }
+                if let Some(fg) = fg {
+                    style = style.fg(fg);
+                }
+                style
+            }
+        }
+    }
+
+    pub fn status_bar(&self) -> Style {
+        Style::default()
+            .fg(self.status_bar_text)
+            .bg(self.status_bar)
+    }
+
+    pub fn header(&self) -> Style {
+        Style::default().fg(self.header)
+    }
+
+    /// A bordered block in the colors of the theme.
+    pub fn block<'a>(&self) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.border))
+            .style(Style::default().bg(self.background))
+    }
+
+    /// A block with the accent border, for the pane the keys go to.
+    pub fn focused_block<'a>(&self) -> Block<'a> {
+        self.block().border_style(Style::default().fg(self.accent))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use kdl::KdlDocument;
+    use ratatui::style::{Color, Modifier, Style};
+
+    use super::Theme;
+
+    #[test]
+    fn test_theme_from_preset_and_overrides() -> anyhow::Result<()> {
+        let doc: KdlDocument = r##"
+preset "solarized"
+border "blue"
+selection "#112233"
+failure "196"
+"##
+        .parse()?;
+        let theme = Theme::from_kdl(&doc)?;
+
+        assert_eq!(Color::Blue, theme.border);
+        assert_eq!(Some(Color::Rgb(0x11, 0x22, 0x33)), theme.selection);
+        assert_eq!(Color::Indexed(196), theme.failure);
+        assert_eq!(Theme::solarized().success, theme.success);
+
+        assert_eq!(
+            Style::default().add_modifier(Modifier::REVERSED),
+            Theme::default().selection()
+        );
+        assert_eq!(Theme::dark(), Theme::from_kdl(&KdlDocument::new())?);
+
+        for invalid in [
+            r#"preset "monokai""#,
+            r#"border "not a color""#,
+            r#"sparkles "red""#,
+        ] {
+            assert!(Theme::from_kdl(&invalid.parse()?).is_err(), "{invalid}");
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,19 @@
 use std::{
+    io::Write,
     ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, Ordering},
     time::Duration,
 };
 
 use crossterm::{
     cursor,
-    event::{DisableMouseCapture, EnableMouseCapture, KeyEvent, KeyEventKind, MouseEvent},
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+    event::{
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        KeyEvent, KeyEventKind, MouseEvent,
+    },
+    terminal::{
+        BeginSynchronizedUpdate, EndSynchronizedUpdate, EnterAlternateScreen, LeaveAlternateScreen,
+    },
 };
 use futures::{FutureExt, StreamExt};
 use ratatui::prelude::CrosstermBackend;
@@ -16,6 +23,14 @@ use tokio::{
 };
 use tokio_util::sync::CancellationToken;
 
+/// Whether the tui owns the terminal, anything written to it meanwhile tears through the
+/// interface.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub enum Event {
@@ -23,6 +38,8 @@ pub enum Event {
     Quit,
     Key(KeyEvent),
     Mouse(MouseEvent),
+    /// Text pasted in one go, when the terminal supports bracketed paste.
+    Paste(String),
     Resize(u16, u16),
     Error,
     FocusGained,
@@ -42,6 +59,10 @@ pub struct Tui {
     pub frame_rate: f64,
     pub tick_rate: f64,
     pub mouse: bool,
+    /// Whether frames are drawn inside synchronized updates, see [`Tui::draw`].
+    pub synchronized: bool,
+    /// Whether the screen is cleared as part of the next frame, rather than right away.
+    clear_pending: bool,
 }
 
 impl Tui {
@@ -53,6 +74,7 @@ impl Tui {
         let cancellation_token = CancellationToken::new();
         let task = tokio::spawn(async {});
         let mouse = false;
+        let synchronized = false;
 
         Ok(Self {
             terminal,
@@ -63,6 +85,8 @@ impl Tui {
             frame_rate,
             tick_rate,
             mouse,
+            synchronized,
+            clear_pending: false,
         })
     }
 
@@ -75,15 +99,55 @@ impl Tui {
         self
     }
 
-    #[allow(dead_code)]
     pub fn mouse(mut self, mouse: bool) -> Self {
         self.mouse = mouse;
         self
     }
 
+    pub fn synchronized(mut self, synchronized: bool) -> Self {
+        self.synchronized = synchronized;
+        self
+    }
+
+    /// Draws a frame, inside a synchronized update when enabled, so the terminal shows it at once
+    /// rather than as it's written, e.g. when the page switches. Clearing the screen and
+    /// resizing to the terminal are part of the update, so they don't flash an empty screen.
+    pub fn draw(&mut self, f: impl FnOnce(&mut Frame<'_>)) -> anyhow::Result<()> {
+        if self.synchronized {
+            crossterm::queue!(self.terminal.backend_mut(), BeginSynchronizedUpdate)?;
+        }
+        let cleared = match std::mem::take(&mut self.clear_pending) {
+            true => self.terminal.clear(),
+            false => Ok(()),
+        };
+
+        let drawn = cleared
+            .and_then(|_| self.terminal.draw(f))
+            .and_then(|frame| crate::terminal::links_over(frame.buffer))
+            .and_then(|links| self.terminal.backend_mut().write_all(&links));
+        if self.synchronized {
+            // Ends the update even when the draw failed, or the terminal stops showing output
+            crossterm::execute!(self.terminal.backend_mut(), EndSynchronizedUpdate)?;
+        }
+        drawn?;
+
+        Ok(())
+    }
+
+    /// Clears the screen together with the next frame, see [`Tui::draw`].
+    pub fn clear_on_next_draw(&mut self) {
+        self.clear_pending = true;
+    }
+
     pub fn enter(&mut self) -> anyhow::Result<()> {
+        ACTIVE.store(true, Ordering::Relaxed);
         crossterm::terminal::enable_raw_mode()?;
-        crossterm::execute!(std::io::stdout(), EnterAlternateScreen, cursor::Hide)?;
+        crossterm::execute!(
+            std::io::stdout(),
+            EnterAlternateScreen,
+            EnableBracketedPaste,
+            cursor::Hide
+        )?;
         if self.mouse {
             crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
         }
@@ -136,7 +200,7 @@ impl Tui {
                                         }
                                     },
                                     crossterm::event::Event::Mouse(mouse) => { event_tx.send(Event::Mouse(mouse)).expect("to send event"); },
-                                    crossterm::event::Event::Paste(_s) => { },
+                                    crossterm::event::Event::Paste(text) => { event_tx.send(Event::Paste(text)).expect("to send event"); },
                                     crossterm::event::Event::Resize(x, y) => { event_tx.send(Event::Resize(x, y)).expect("to send event"); },
                                 }
                             },
@@ -177,6 +241,19 @@ impl Tui {
         Ok(())
     }
 
+    /// Hands the terminal back, e.g. to run an editor, until [`Tui::resume`].
+    pub fn suspend(&mut self) -> anyhow::Result<()> {
+        self.exit()
+    }
+
+    pub fn resume(&mut self) -> anyhow::Result<()> {
+        self.enter()?;
+        // Whatever ran in between drew over the screen
+        self.clear_on_next_draw();
+
+        Ok(())
+    }
+
     pub fn exit(&mut self) -> anyhow::Result<()> {
         self.stop()?;
         if crossterm::terminal::is_raw_mode_enabled()? {
@@ -185,9 +262,15 @@ impl Tui {
                 crossterm::execute!(std::io::stdout(), DisableMouseCapture)?;
             }
 
-            crossterm::execute!(std::io::stdout(), LeaveAlternateScreen, cursor::Show)?;
+            crossterm::execute!(
+                std::io::stdout(),
+                DisableBracketedPaste,
+                LeaveAlternateScreen,
+                cursor::Show
+            )?;
             crossterm::terminal::disable_raw_mode()?;
         }
+        ACTIVE.store(false, Ordering::Relaxed);
 
         Ok(())
     }
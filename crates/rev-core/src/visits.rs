@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::Store;
+
+const VISITS_DOCUMENT: &str = "visits";
+/// How many reviews are remembered, the least recently visited are forgotten first.
+const MAX_VISITS: usize = 500;
+
+/// The head of a review when it was last shown.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Visit {
+    pub head: String,
+    pub at: DateTime<Utc>,
+}
+
+/// The last visit of each review, keyed by the review in the form `owner/name#number`, so a
+/// re-review can be limited to what was pushed since.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct ReviewVisits {
+    pub reviews: BTreeMap<String, Visit>,
+}
+
+impl ReviewVisits {
+    pub fn load(store: &Store) -> anyhow::Result<Self> {
+        store.load(VISITS_DOCUMENT)
+    }
+
+    /// Records a visit of the review at the head, returning the visit before it.
+    pub fn record(store: &Store, review: &str, head: &str) -> anyhow::Result<Option<Visit>> {
+        let mut visits = Self::load(store)?;
+        let previous = visits.visit(review, head, Utc::now());
+        store.save(VISITS_DOCUMENT, &visits)?;
+
+        Ok(previous)
+    }
+
+    fn visit(&mut self, review: &str, head: &str, at: DateTime<Utc>) -> Option<Visit> {
+        let previous = self.reviews.insert(
+            review.to_string(),
+            Visit {
+                head: head.to_string(),
+                at,
+            },
+        );
+
+        while self.reviews.len() > MAX_VISITS {
+            let oldest = self
+                .reviews
+                .iter()
+                .min_by_key(|(_, v)| v.at)
+                .map(|(r, _)| r.clone());
+            match oldest {
+                Some(oldest) => self.reviews.remove(&oldest),
+                None => break,
+            };
+        }
+
+        previous
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+
+    use super::ReviewVisits;
+
+    #[test]
+    fn test_remembers_previous_head() {
+        let now = Utc::now();
+        let mut visits = ReviewVisits::default();
+
+        assert_eq!(None, visits.visit("kjuulh/rev#1", "aaa", now));
+
+        let previous = visits
+            .visit("kjuulh/rev#1", "bbb", now + Duration::hours(1))
+            .unwrap();
+        assert_eq!("aaa", previous.head);
+        assert_eq!("bbb", visits.reviews["kjuulh/rev#1"].head);
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use crate::{config::WorkspaceSettings, store::Store};
+
+const WORKSPACE_DOCUMENT: &str = "workspace";
+
+/// Local checkouts of reviews, shared by everything which needs the code on disk.
+///
+/// Every repository is cloned once as a bare repository, and each review gets a worktree next to
+/// it, so checking out another review of the same repository only fetches what's new:
+///
+/// ```text
+/// <root>/<owner>/<name>.git       bare clone
+/// <root>/<owner>/<name>/<number>  worktree of the review
+/// ```
+#[derive(Clone, Debug)]
+pub struct Workspace {
+    settings: WorkspaceSettings,
+    root: PathBuf,
+    store: Store,
+}
+
+/// When each worktree was last checked out, keyed by the review in the form `owner/name#number`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+struct WorktreeUsage {
+    worktrees: BTreeMap<String, DateTime<Utc>>,
+}
+
+/// A review checked out in the workspace.
+#[derive(Debug, Clone)]
+pub struct Worktree {
+    pub review: String,
+    pub path: PathBuf,
+    pub last_used: DateTime<Utc>,
+}
+
+impl Workspace {
+    pub fn new(settings: WorkspaceSettings, store: Store) -> Self {
+        let root = settings
+            .root
+            .clone()
+            .unwrap_or_else(|| crate::platform::data_dir().join("workspace"));
+
+        Self {
+            settings,
+            root,
+            store,
+        }
+    }
+
+    fn repository_path(&self, owner: &str, name: &str) -> PathBuf {
+        self.root.join(owner).join(format!("{name}.git"))
+    }
+
+    fn worktree_path(&self, owner: &str, name: &str, number: usize) -> PathBuf {
+        self.root.join(owner).join(name).join(number.to_string())
+    }
+
+    /// Clones the repository if it isn't in the workspace yet, returning the bare clone.
+    async fn ensure_repository(&self, owner: &str, name: &str) -> anyhow::Result<PathBuf> {
+        let path = self.repository_path(owner, name);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let remote = self
+            .settings
+            .remote
+            .replace("{owner}", owner)
+            .replace("{name}", name);
+        tokio::fs::create_dir_all(&self.root).await?;
+        git(
+            &self.root,
+            &["clone", "--bare", &remote, &path.to_string_lossy()],
+        )
+        .await
+        .with_context(|| format!("failed to clone {remote}"))?;
+
+        Ok(path)
+    }
+
+    /// Checks out the head of a review, reusing its worktree when it's already there, and prunes
+    /// the least recently used worktrees beyond the configured limit.
+    pub async fn checkout(
+        &self,
+        owner: &str,
+        name: &str,
+        number: usize,
+    ) -> anyhow::Result<PathBuf> {
+        let repository = self.ensure_repository(owner, name).await?;
+        let head = format!("refs/rev/pull/{number}");
+        git(
+            &repository,
+            &[
+                "fetch",
+                "--force",
+                "origin",
+                &format!("refs/pull/{number}/head:{head}"),
+            ],
+        )
+        .await
+        .with_context(|| format!("failed to fetch {owner}/{name}#{number}"))?;
+
+        let path = self.worktree_path(owner, name, number);
+        if path.exists() {
+            git(&path, &["checkout", "--force", "--detach", &head]).await?;
+        } else {
+            git(
+                &repository,
+                &[
+                    "worktree",
+                    "add",
+                    "--detach",
+                    &path.to_string_lossy(),
+                    &head,
+                ],
+            )
+            .await?;
+        }
+
+        let mut usage: WorktreeUsage = self.store.load(WORKSPACE_DOCUMENT)?;
+        usage
+            .worktrees
+            .insert(format!("{owner}/{name}#{number}"), Utc::now());
+        self.store.save(WORKSPACE_DOCUMENT, &usage)?;
+
+        self.prune(self.settings.max_worktrees).await?;
+
+        Ok(path)
+    }
+
+    /// Fetches a commit which may no longer be on any branch, e.g. the head of a review before it
+    /// was force-pushed.
+    pub async fn fetch_commit(&self, owner: &str, name: &str, sha: &str) -> anyhow::Result<()> {
+        let repository = self.ensure_repository(owner, name).await?;
+        if git(
+            &repository,
+            &["cat-file", "-e", &format!("{sha}^{{commit}}")],
+        )
+        .await
+        .is_ok()
+        {
+            return Ok(());
+        }
+
+        git(&repository, &["fetch", "origin", sha])
+            .await
+            .with_context(|| format!("failed to fetch {sha} of {owner}/{name}"))?;
+
+        Ok(())
+    }
+
+    /// The checked out reviews, most recently used first.
+    pub fn list(&self) -> anyhow::Result<Vec<Worktree>> {
+        let usage: WorktreeUsage = self.store.load(WORKSPACE_DOCUMENT)?;
+        let mut worktrees = usage
+            .worktrees
+            .into_iter()
+            .filter_map(|(review, last_used)| {
+                let (owner, name, number) = parse_reference(&review)?;
+                Some(Worktree {
+                    path: self.worktree_path(owner, name, number),
+                    review,
+                    last_used,
+                })
+            })
+            .collect::<Vec<_>>();
+        worktrees.sort_by_key(|w| std::cmp::Reverse(w.last_used));
+
+        Ok(worktrees)
+    }
+
+    /// Removes all but the `keep` most recently used worktrees, returning the removed reviews.
+    pub async fn prune(&self, keep: usize) -> anyhow::Result<Vec<String>> {
+        let mut usage: WorktreeUsage = self.store.load(WORKSPACE_DOCUMENT)?;
+        let stale = least_recently_used(&usage.worktrees, keep);
+
+        for review in stale.iter() {
+            let Some((owner, name, number)) = parse_reference(review) else {
+                continue;
+            };
+            let repository = self.repository_path(owner, name);
+            let path = self.worktree_path(owner, name, number);
+
+            if path.exists() {
+                git(
+                    &repository,
+                    &["worktree", "remove", "--force", &path.to_string_lossy()],
+                )
+                .await?;
+            }
+            if repository.exists() {
+                // Drops the bookkeeping of worktrees removed by hand as well
+                git(&repository, &["worktree", "prune"]).await?;
+                git(
+                    &repository,
+                    &["update-ref", "-d", &format!("refs/rev/pull/{number}")],
+                )
+                .await?;
+            }
+
+            usage.worktrees.remove(review);
+        }
+
+        self.store.save(WORKSPACE_DOCUMENT, &usage)?;
+
+        Ok(stale)
+    }
+}
+
+/// Splits a review in the form `owner/name#number` into its parts.
+pub fn parse_reference(reference: &str) -> Option<(&str, &str, usize)> {
+    let (repository, number) = reference.split_once('#')?;
+    let (owner, name) = repository.split_once('/')?;
+
+    Some((owner, name, number.parse().ok()?))
+}
+
+/// The reviews beyond the `keep` most recently used ones.
+fn least_recently_used(worktrees: &BTreeMap<String, DateTime<Utc>>, keep: usize) -> Vec<String> {
+    let mut by_use = worktrees.iter().collect::<Vec<_>>();
+    by_use.sort_by_key(|(_, last_used)| std::cmp::Reverse(*last_used));
+
+    by_use
+        .into_iter()
+        .skip(keep)
+        .map(|(review, _)| review.clone())
+        .collect()
+}
+
+pub(crate) async fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await
+        .context("failed to run git, is it installed?")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use chrono::{Duration, Utc};
+
+    use super::{least_recently_used, parse_reference};
+
+    #[test]
+    fn test_prunes_least_recently_used() {
+        let now = Utc::now();
+        let worktrees = BTreeMap::from([
+            ("kjuulh/rev#1".to_string(), now - Duration::days(3)),
+            ("kjuulh/rev#2".to_string(), now),
+            ("kjuulh/rev#3".to_string(), now - Duration::days(1)),
+        ]);
+
+        assert_eq!(
+            vec!["kjuulh/rev#1".to_string()],
+            least_recently_used(&worktrees, 2)
+        );
+        assert!(least_recently_used(&worktrees, 5).is_empty());
+        assert_eq!(Some(("kjuulh", "rev", 3)), parse_reference("kjuulh/rev#3"));
+        assert_eq!(None, parse_reference("kjuulh/rev"));
+    }
+}
@@ -0,0 +1,189 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::Context;
+use reqwest::Client;
+use serde::Deserialize;
+
+/// The scopes rev needs of a github token, to read reviews of private repositories and teams and
+/// to review them.
+pub const SCOPES: &[&str] = &["repo", "read:org"];
+
+/// Logs in to github with the oauth device flow, where the user enters a code on github while
+/// rev waits for the token.
+///
+/// Needs the client id of an oauth app with the device flow enabled.
+pub struct DeviceFlow {
+    client: Client,
+    /// The web host, e.g. `https://github.com` or the host of github enterprise.
+    host: String,
+    client_id: String,
+}
+
+/// The code to enter at the verification uri.
+#[derive(Deserialize, Debug, Clone)]
+pub struct DeviceCode {
+    device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    /// Seconds until the code expires.
+    pub expires_in: u64,
+    /// Seconds to wait between polls for the token.
+    interval: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+    /// The new interval in seconds, sent along with `slow_down`.
+    interval: Option<u64>,
+}
+
+impl DeviceFlow {
+    pub fn new(host: impl Into<String>, client_id: impl Into<String>) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: Client::builder().user_agent("rev").build()?,
+            host: host.into().trim_end_matches('/').to_string(),
+            client_id: client_id.into(),
+        })
+    }
+
+    /// Asks github for a code for the user to enter.
+    pub async fn start(&self) -> anyhow::Result<DeviceCode> {
+        let res = self
+            .client
+            .post(format!("{}/login/device/code", self.host))
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("scope", &SCOPES.join(" ")),
+            ])
+            .send()
+            .await
+            .with_context(|| format!("failed to reach {}", self.host))?;
+        if !res.status().is_success() {
+            anyhow::bail!(
+                "{} refused to start the device flow with {}, check that the oauth app has it enabled",
+                self.host,
+                res.status()
+            );
+        }
+
+        res.json()
+            .await
+            .context("failed to parse the device code of github")
+    }
+
+    /// Polls for the token until the user has entered the code, denied access or the code has
+    /// expired.
+    pub async fn wait_for_token(&self, code: &DeviceCode) -> anyhow::Result<String> {
+        let mut interval = code.interval.max(1);
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+
+            let res: TokenResponse = self
+                .client
+                .post(format!("{}/login/oauth/access_token", self.host))
+                .header(reqwest::header::ACCEPT, "application/json")
+                .form(&[
+                    ("client_id", self.client_id.as_str()),
+                    ("device_code", code.device_code.as_str()),
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ])
+                .send()
+                .await
+                .with_context(|| format!("failed to reach {}", self.host))?
+                .json()
+                .await
+                .context("failed to parse the token response of github")?;
+
+            match (res.access_token, res.error.as_deref()) {
+                (Some(token), _) => return Ok(token),
+                (None, Some("authorization_pending")) => {}
+                (None, Some("slow_down")) => interval = res.interval.unwrap_or(interval + 5),
+                (None, Some("expired_token")) => {
+                    anyhow::bail!(
+                        "the code expired before it was entered, run rev auth login again"
+                    )
+                }
+                (None, Some("access_denied")) => anyhow::bail!("access was denied on github"),
+                (None, error) => anyhow::bail!(
+                    "github didn't hand out a token: {}",
+                    res.error_description
+                        .as_deref()
+                        .or(error)
+                        .unwrap_or("no reason given")
+                ),
+            }
+        }
+    }
+}
+
+/// Reads the token stored by [`store_token`], `None` when there's none.
+pub fn read_token(path: &Path) -> Option<String> {
+    let token = std::fs::read_to_string(path).ok()?;
+
+    Some(token.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Stores the token in a file only the user can read, replacing any stored before.
+pub fn store_token(path: &Path, token: &str) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("failed to write token to {}", path.display()))?;
+    // The mode only applies to new files, a token stored before may be readable by others
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    std::io::Write::write_all(&mut file, token.as_bytes())?;
+
+    Ok(())
+}
+
+/// Removes the stored token, returning whether there was one.
+pub fn remove_token(path: &Path) -> anyhow::Result<bool> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("failed to remove {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_token, remove_token, store_token};
+
+    #[test]
+    fn test_store_token() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("rev-auth-{}", std::process::id()));
+        let path = dir.join("credentials").join("github-token");
+
+        assert_eq!(None, read_token(&path));
+        store_token(&path, "gho_first")?;
+        store_token(&path, "gho_second\n")?;
+        assert_eq!(Some("gho_second".to_string()), read_token(&path));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)?.permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+
+        assert!(remove_token(&path)?);
+        assert!(!remove_token(&path)?);
+        std::fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1512 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::sync::OnceCell;
+
+use crate::{
+    github::PendingRequest,
+    models::{
+        ChangedFile, Comment, Comments, Commit, CurrentState, Ecosystem, Issue, MergeStrategy,
+        NewReview, Participant, ParticipantRole, ProviderStats, RepositoryListItem, Review,
+        ReviewEvent, ReviewList, ReviewListItem, ReviewState, ReviewThread, StatusCheck,
+        TokenStatus, Vulnerability,
+    },
+    traits::{
+        GitAdvisory, GitAuth, GitComment, GitIssue, GitReview, GitReviewAction, GitUserReview,
+    },
+    Provider,
+};
+
+/// How many pull requests a page holds, per repository.
+pub const PAGE_SIZE: usize = 20;
+
+pub struct BitbucketOptions {
+    url: String,
+    username: Option<String>,
+    token: Option<String>,
+    repositories: Vec<String>,
+}
+
+impl BitbucketOptions {
+    /// The repositories, in the form `workspace/repo`, the review queue is made of. Bitbucket
+    /// can't search pull requests across repositories.
+    pub fn new(repositories: Vec<String>) -> Self {
+        Self {
+            url: "https://api.bitbucket.org/2.0".into(),
+            username: None,
+            token: None,
+            repositories,
+        }
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// An app password is used together with the username, access tokens are used alone.
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    /// Used instead of `BITBUCKET_TOKEN`.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+}
+
+/// A provider for Bitbucket Cloud, through its 2.0 rest api.
+///
+/// Like [`crate::gitea::Gitea`], ids are references such as `workspace/repo#12` for reviews and
+/// `workspace/repo#12:345` for comments, as the api addresses everything by repository.
+pub struct Bitbucket {
+    client: reqwest::Client,
+    credentials: Credentials,
+    api: String,
+    repositories: Vec<String>,
+    /// The user the token belongs to, needed to find the reviews requested from them.
+    user: OnceCell<BitbucketUser>,
+    /// Requests sent which haven't been answered yet.
+    pending: AtomicUsize,
+}
+
+enum Credentials {
+    AccessToken(String),
+    AppPassword { username: String, password: String },
+}
+
+impl Bitbucket {
+    pub fn new(options: BitbucketOptions) -> anyhow::Result<Self> {
+        let token = match options.token {
+            Some(token) => token,
+            None => std::env::var("BITBUCKET_TOKEN").context(
+                "no bitbucket token found, set token in the provider block of rev.kdl or BITBUCKET_TOKEN",
+            )?,
+        };
+        let username = options
+            .username
+            .or_else(|| std::env::var("BITBUCKET_USERNAME").ok());
+
+        let client = reqwest::Client::builder().user_agent("rev").build()?;
+
+        Ok(Self {
+            client,
+            credentials: match username {
+                Some(username) => Credentials::AppPassword {
+                    username,
+                    password: token,
+                },
+                None => Credentials::AccessToken(token),
+            },
+            api: options.url.trim_end_matches('/').to_string(),
+            repositories: options.repositories,
+            user: OnceCell::new(),
+            pending: AtomicUsize::new(0),
+        })
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.credentials {
+            Credentials::AccessToken(token) => request.bearer_auth(token),
+            Credentials::AppPassword { username, password } => {
+                request.basic_auth(username, Some(password))
+            }
+        }
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<reqwest::Response> {
+        self.fetch(method.clone(), path, query, body)
+            .await?
+            .with_context(|| {
+                format!(
+                    "bitbucket call {method} {path} failed with {}",
+                    StatusCode::NOT_FOUND
+                )
+            })
+    }
+
+    /// Sends the request, a missing resource is `None` rather than an error.
+    async fn fetch(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<Option<reqwest::Response>> {
+        let _pending = PendingRequest::start(&self.pending);
+        let url = format!("{}{path}", self.api);
+
+        let mut request = self
+            .authorize(self.client.request(method.clone(), &url))
+            .query(query);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let res = request
+            .send()
+            .await
+            .with_context(|| format!("bitbucket call {method} {path} failed"))?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if res.status() == StatusCode::UNAUTHORIZED {
+            anyhow::bail!(
+                "bitbucket rejected the token, it may be expired or revoked, create a new access token or app password"
+            );
+        }
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            tracing::error!("bitbucket error: {body}");
+            anyhow::bail!("bitbucket call {method} {path} failed with {status}");
+        }
+
+        Ok(Some(res))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> anyhow::Result<T> {
+        self.request(Method::GET, path, query, None)
+            .await?
+            .json()
+            .await
+            .with_context(|| format!("failed to parse the response of {path}"))
+    }
+
+    async fn get_optional<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> anyhow::Result<Option<T>> {
+        let Some(res) = self.fetch(Method::GET, path, query, None).await? else {
+            return Ok(None);
+        };
+
+        res.json()
+            .await
+            .map(Some)
+            .with_context(|| format!("failed to parse the response of {path}"))
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        self.request(method, path, &[], body).await?;
+
+        Ok(())
+    }
+
+    async fn current_user(&self) -> anyhow::Result<&BitbucketUser> {
+        self.user
+            .get_or_try_init(|| self.get::<BitbucketUser>("/user", &[]))
+            .await
+    }
+
+    /// A page of the pull requests matching the query across the repositories, newest first.
+    async fn search(
+        &self,
+        search: &SearchQuery,
+        cursor: Option<String>,
+        pagelen: usize,
+    ) -> anyhow::Result<(Vec<BitbucketPull>, usize, bool)> {
+        let page = cursor
+            .as_deref()
+            .map(|c| c.parse::<usize>().context("cursor to be a page number"))
+            .transpose()?
+            .unwrap_or(1);
+        let user = match search.needs_user() {
+            true => Some(self.current_user().await?.uuid.clone()),
+            false => None,
+        };
+        let q = search.bbql(user.as_deref());
+
+        let repositories = match search.repository.as_ref() {
+            Some(repository) => vec![repository.clone()],
+            None => self
+                .repositories
+                .iter()
+                .filter(|r| match search.workspace.as_ref() {
+                    Some(workspace) => r.starts_with(&format!("{workspace}/")),
+                    None => true,
+                })
+                .cloned()
+                .collect(),
+        };
+        if repositories.is_empty() {
+            anyhow::bail!("no bitbucket repositories to search, add them to repositories in the provider block of rev.kdl");
+        }
+
+        let pages = futures::future::join_all(repositories.iter().map(|repository| {
+            let query = vec![
+                ("q", q.clone()),
+                ("page", page.to_string()),
+                ("pagelen", pagelen.to_string()),
+                ("sort", "-created_on".to_string()),
+            ];
+            async move {
+                self.get_optional::<Paginated<BitbucketPull>>(
+                    &format!("/repositories/{repository}/pullrequests"),
+                    &query,
+                )
+                .await
+            }
+        }))
+        .await;
+
+        let mut pulls = Vec::new();
+        let mut total = 0;
+        let mut has_more = false;
+        for page in pages {
+            let Some(page) = page? else {
+                continue;
+            };
+            has_more |= page.next.is_some();
+            total += page.size.unwrap_or(page.values.len());
+            pulls.extend(page.values);
+        }
+        pulls.sort_by_key(|p| std::cmp::Reverse(p.created_on));
+
+        Ok((pulls, total, has_more))
+    }
+
+    async fn search_list(
+        &self,
+        search: SearchQuery,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        let page = cursor
+            .as_deref()
+            .and_then(|c| c.parse::<usize>().ok())
+            .unwrap_or(1);
+        let (pulls, _, has_more) = self.search(&search, cursor, PAGE_SIZE).await?;
+
+        Ok(ReviewList {
+            items: pulls
+                .into_iter()
+                .filter_map(BitbucketPull::into_list_item)
+                .collect(),
+            last_cursor: has_more.then(|| (page + 1).to_string()),
+            has_more,
+        })
+    }
+
+    async fn get_pull(
+        &self,
+        owner: &str,
+        name: &str,
+        number: usize,
+    ) -> anyhow::Result<Option<BitbucketPull>> {
+        self.get_optional(
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}"),
+            &[],
+        )
+        .await
+    }
+
+    /// Collects every page of a paginated resource, bitbucket pages hold at most 100 values.
+    async fn get_all<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<Vec<T>> {
+        let mut values = Vec::new();
+        let mut page = 1;
+        loop {
+            let Some(res) = self
+                .get_optional::<Paginated<T>>(
+                    path,
+                    &[("page", page.to_string()), ("pagelen", "100".into())],
+                )
+                .await?
+            else {
+                break;
+            };
+            values.extend(res.values);
+            if res.next.is_none() {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(values)
+    }
+}
+
+/// Splits a review reference, `workspace/repo#number`, into its parts.
+fn split_reference(reference: &str) -> anyhow::Result<(String, String, usize)> {
+    let parts = reference.split_once('#').and_then(|(repository, number)| {
+        let (owner, name) = repository.split_once('/')?;
+        Some((owner.to_string(), name.to_string(), number.parse().ok()?))
+    });
+
+    parts.with_context(|| format!("{reference} is not in the form workspace/repo#number"))
+}
+
+/// Splits a comment id, `workspace/repo#number:id`, into the review and the id of the comment.
+fn split_comment_id(id: &str) -> anyhow::Result<(String, String, usize, &str)> {
+    let (review, comment) = id
+        .rsplit_once(':')
+        .with_context(|| format!("{id} is not in the form workspace/repo#number:id"))?;
+    let (owner, name, number) = split_reference(review)?;
+
+    Ok((owner, name, number, comment))
+}
+
+/// The qualifiers of a github style search query, turned into a bitbucket query.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SearchQuery {
+    state: Option<String>,
+    workspace: Option<String>,
+    repository: Option<String>,
+    reviewer: Option<Reviewer>,
+    authored: bool,
+    words: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Reviewer {
+    Me,
+    Nickname(String),
+}
+
+impl SearchQuery {
+    fn parse(query: &str) -> anyhow::Result<Self> {
+        let mut search = Self::default();
+
+        for word in query.split_whitespace() {
+            match word.split_once(':') {
+                Some(("is", "pr")) => {}
+                Some(("is" | "state", state @ ("open" | "closed" | "merged"))) => {
+                    search.state = Some(state.to_string())
+                }
+                Some(("org" | "user", workspace)) => search.workspace = Some(workspace.to_string()),
+                Some(("repo", repository)) => search.repository = Some(repository.to_string()),
+                Some(("review-requested", "@me")) => search.reviewer = Some(Reviewer::Me),
+                Some(("review-requested", nickname)) => {
+                    search.reviewer = Some(Reviewer::Nickname(nickname.to_string()))
+                }
+                Some(("author", "@me")) => search.authored = true,
+                // Bitbucket has no labels, so there is nothing to narrow by
+                Some(("label", _)) => {}
+                Some((qualifier, _)) => {
+                    anyhow::bail!("bitbucket can't search by {qualifier}:, only by is, state, org, repo, review-requested and author:@me")
+                }
+                None => search.words.push(word.to_string()),
+            }
+        }
+
+        Ok(search)
+    }
+
+    fn needs_user(&self) -> bool {
+        self.authored || self.reviewer == Some(Reviewer::Me)
+    }
+
+    /// The query in the bitbucket query language, `user` is the uuid of the current user.
+    fn bbql(&self, user: Option<&str>) -> String {
+        let state = match self.state.as_deref() {
+            Some("closed") => "DECLINED",
+            Some("merged") => "MERGED",
+            _ => "OPEN",
+        };
+        let mut clauses = vec![format!("state=\"{state}\"")];
+        match (&self.reviewer, user) {
+            (Some(Reviewer::Me), Some(user)) => clauses.push(format!("reviewers.uuid=\"{user}\"")),
+            (Some(Reviewer::Nickname(nickname)), _) => {
+                clauses.push(format!("reviewers.nickname=\"{nickname}\""))
+            }
+            _ => {}
+        }
+        if let (true, Some(user)) = (self.authored, user) {
+            clauses.push(format!("author.uuid=\"{user}\""));
+        }
+        if !self.words.is_empty() {
+            clauses.push(format!(
+                "title~\"{}\"",
+                self.words.join(" ").replace('"', "\\\"")
+            ));
+        }
+
+        clauses.join(" AND ")
+    }
+}
+
+#[derive(Deserialize)]
+struct Paginated<T> {
+    values: Vec<T>,
+    #[serde(default)]
+    next: Option<String>,
+    #[serde(default)]
+    size: Option<usize>,
+}
+
+#[derive(Deserialize, Clone)]
+struct BitbucketUser {
+    #[serde(default)]
+    uuid: String,
+    #[serde(default)]
+    nickname: Option<String>,
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+impl BitbucketUser {
+    fn login(&self) -> String {
+        self.nickname
+            .clone()
+            .or_else(|| self.display_name.clone())
+            .unwrap_or_else(|| "ghost".into())
+    }
+}
+
+#[derive(Deserialize)]
+struct BitbucketRepository {
+    full_name: String,
+}
+
+#[derive(Deserialize)]
+struct BitbucketRepositoryListing {
+    full_name: String,
+    #[serde(default)]
+    description: String,
+    updated_on: Option<DateTime<Utc>>,
+    #[serde(default)]
+    links: BitbucketLinks,
+}
+
+#[derive(Deserialize)]
+struct BitbucketCommitRef {
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct BitbucketEndpoint {
+    #[serde(default)]
+    branch: Option<BitbucketBranch>,
+    #[serde(default)]
+    commit: Option<BitbucketCommitRef>,
+    #[serde(default)]
+    repository: Option<BitbucketRepository>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketBranch {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct BitbucketParticipant {
+    user: BitbucketUser,
+    role: String,
+    #[serde(default)]
+    approved: bool,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketPull {
+    id: usize,
+    title: String,
+    #[serde(default)]
+    description: String,
+    author: BitbucketUser,
+    created_on: DateTime<Utc>,
+    source: BitbucketEndpoint,
+    destination: BitbucketEndpoint,
+    #[serde(default)]
+    participants: Vec<BitbucketParticipant>,
+    #[serde(default)]
+    reviewers: Vec<BitbucketUser>,
+    #[serde(default)]
+    links: BitbucketLinks,
+}
+
+#[derive(Deserialize, Default)]
+struct BitbucketLinks {
+    html: Option<BitbucketLink>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketLink {
+    href: String,
+}
+
+impl BitbucketPull {
+    fn repository(&self) -> Option<&str> {
+        self.destination
+            .repository
+            .as_ref()
+            .map(|r| r.full_name.as_str())
+    }
+
+    /// Listings don't include the size or checks of pull requests, so those are left empty
+    /// rather than fetched for every item.
+    fn into_list_item(self) -> Option<ReviewListItem> {
+        let (owner, name) = self.repository()?.split_once('/')?;
+        let (owner, name) = (owner.to_string(), name.to_string());
+        let approvals = self.participants.iter().filter(|p| p.approved).count();
+        let url = self.url();
+
+        Some(ReviewListItem {
+            id: format!("{owner}/{name}#{}", self.id),
+            name,
+            title: self.title,
+            url,
+            owner,
+            date: self.created_on,
+            number: self.id,
+            author: self.author.login(),
+            approvals,
+            ..Default::default()
+        })
+    }
+
+    fn url(&self) -> String {
+        self.links
+            .html
+            .as_ref()
+            .map(|l| l.href.clone())
+            .unwrap_or_default()
+    }
+
+    fn participants(&self) -> Vec<Participant> {
+        let mut participants = vec![Participant {
+            login: self.author.login(),
+            name: self.author.display_name.clone(),
+            role: ParticipantRole::Author,
+            requested: false,
+            review: None,
+        }];
+
+        for participant in self.participants.iter() {
+            let review = match (participant.approved, participant.state.as_deref()) {
+                (true, _) | (_, Some("approved")) => Some(ReviewState::Approved),
+                (_, Some("changes_requested")) => Some(ReviewState::ChangesRequested),
+                _ => None,
+            };
+            let role = match participant.role.as_str() {
+                "REVIEWER" => ParticipantRole::Reviewer,
+                _ => ParticipantRole::Commenter,
+            };
+            participants.push(Participant {
+                login: participant.user.login(),
+                name: participant.user.display_name.clone(),
+                requested: role == ParticipantRole::Reviewer && review.is_none(),
+                role,
+                review,
+            });
+        }
+        // Reviewers who haven't looked at the pull request yet aren't participants
+        for reviewer in self.reviewers.iter() {
+            if !participants.iter().any(|p| p.login == reviewer.login()) {
+                participants.push(Participant {
+                    login: reviewer.login(),
+                    name: reviewer.display_name.clone(),
+                    role: ParticipantRole::Reviewer,
+                    requested: true,
+                    review: None,
+                });
+            }
+        }
+
+        participants
+    }
+}
+
+#[derive(Deserialize)]
+struct BitbucketContent {
+    #[serde(default)]
+    raw: String,
+}
+
+#[derive(Deserialize)]
+struct BitbucketComment {
+    id: u64,
+    #[serde(default)]
+    user: Option<BitbucketUser>,
+    content: BitbucketContent,
+    created_on: DateTime<Utc>,
+    updated_on: DateTime<Utc>,
+    #[serde(default)]
+    deleted: bool,
+    #[serde(default)]
+    inline: Option<BitbucketInline>,
+    #[serde(default)]
+    parent: Option<BitbucketParent>,
+    #[serde(default)]
+    resolution: Option<serde_json::Value>,
+    #[serde(default)]
+    links: BitbucketLinks,
+}
+
+/// Where an inline comment is, `to` is the line in the new version of the file and `from` the
+/// line in the old one.
+#[derive(Deserialize)]
+struct BitbucketInline {
+    path: String,
+    #[serde(default)]
+    from: Option<usize>,
+    #[serde(default)]
+    to: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketParent {
+    id: u64,
+}
+
+/// Splits the comments of a pull request into the conversation and the threads on lines of the
+/// diff, replies are placed in the thread of the comment they reply to.
+fn split_comments(prefix: &str, comments: Vec<BitbucketComment>) -> Comments {
+    let mut conversation = Vec::new();
+    let mut threads: Vec<ReviewThread> = Vec::new();
+    // The thread each inline comment and reply went into, by comment id
+    let mut thread_of = std::collections::HashMap::<u64, usize>::new();
+
+    for c in comments.into_iter().filter(|c| !c.deleted) {
+        let comment = Comment {
+            id: format!("{prefix}:{}", c.id),
+            author: c.user.map(|u| u.login()).unwrap_or_else(|| "ghost".into()),
+            text: c.content.raw.clone(),
+            body: c.content.raw,
+            url: c
+                .links
+                .html
+                .as_ref()
+                .map(|l| l.href.clone())
+                .unwrap_or_default(),
+            last_edited_at: (c.updated_on != c.created_on).then_some(c.updated_on),
+        };
+
+        let reply_to = c.parent.and_then(|p| thread_of.get(&p.id).copied());
+        match (reply_to, c.inline) {
+            (Some(thread), _) => {
+                threads[thread].comments.push(comment);
+                thread_of.insert(c.id, thread);
+            }
+            (None, Some(inline)) => {
+                thread_of.insert(c.id, threads.len());
+                threads.push(ReviewThread {
+                    id: comment.id.clone(),
+                    path: inline.path,
+                    line: inline.to.or(inline.from),
+                    // Bitbucket doesn't return the diff around the comment
+                    diff_hunk: String::new(),
+                    resolved: c.resolution.is_some(),
+                    outdated: false,
+                    comments: vec![comment],
+                });
+            }
+            (None, None) => conversation.push(comment),
+        }
+    }
+
+    Comments {
+        has_previous: false,
+        comments: conversation,
+        threads,
+    }
+}
+
+#[derive(Deserialize)]
+struct BitbucketStatus {
+    key: String,
+    #[serde(default)]
+    name: Option<String>,
+    state: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+impl BitbucketStatus {
+    fn into_check(self) -> StatusCheck {
+        let current = match self.state.as_str() {
+            "SUCCESSFUL" => CurrentState::Success,
+            "FAILED" | "STOPPED" => CurrentState::Failure,
+            _ => CurrentState::Pending,
+        };
+        let name = self.name.unwrap_or_else(|| self.key.clone());
+
+        // Pipelines report their results as statuses linking to the pipeline
+        let pipeline = self
+            .url
+            .as_deref()
+            .map(|u| u.contains("/pipelines/"))
+            .unwrap_or_default();
+        if pipeline {
+            return StatusCheck::CheckRun {
+                id: self.key,
+                name,
+                status: self.state.clone(),
+                conclusion: self.state,
+                current,
+                url: self.url,
+                app: "Bitbucket Pipelines".into(),
+            };
+        }
+
+        StatusCheck::StatusContext {
+            id: self.key.clone(),
+            state: self.state,
+            description: self.description.filter(|d| !d.is_empty()),
+            context: name,
+            current,
+            url: self.url,
+            app: self.key,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BitbucketPath {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct BitbucketDiffStat {
+    #[serde(default)]
+    new: Option<BitbucketPath>,
+    #[serde(default)]
+    old: Option<BitbucketPath>,
+    lines_added: usize,
+    lines_removed: usize,
+}
+
+#[derive(Deserialize)]
+struct BitbucketCommitAuthor {
+    raw: String,
+    #[serde(default)]
+    user: Option<BitbucketUser>,
+}
+
+#[derive(Deserialize)]
+struct BitbucketCommit {
+    hash: String,
+    message: String,
+    author: BitbucketCommitAuthor,
+}
+
+#[async_trait]
+impl GitUserReview for Bitbucket {
+    async fn get_user_reviews(
+        &self,
+        requested: Option<&str>,
+        org: Option<&str>,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<ReviewList> {
+        self.get_user_reviews_cursor(requested, org, tags, None)
+            .await
+    }
+
+    async fn get_user_reviews_cursor(
+        &self,
+        requested: Option<&str>,
+        org: Option<&str>,
+        _tags: Option<Vec<String>>,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        let reviewer = match requested {
+            None => Reviewer::Me,
+            Some(team) if team.contains('/') => {
+                anyhow::bail!("bitbucket has no team review requests, use a user instead")
+            }
+            Some(nickname) => Reviewer::Nickname(nickname.to_string()),
+        };
+        let search = SearchQuery {
+            reviewer: Some(reviewer),
+            workspace: org.map(|o| o.to_string()),
+            ..Default::default()
+        };
+
+        self.search_list(search, cursor).await
+    }
+
+    async fn search_reviews(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        self.search_list(SearchQuery::parse(query)?, cursor).await
+    }
+
+    async fn count_search(&self, query: &str) -> anyhow::Result<usize> {
+        let (_, total, _) = self.search(&SearchQuery::parse(query)?, None, 1).await?;
+
+        Ok(total)
+    }
+
+    async fn get_repositories(&self, owner: &str) -> anyhow::Result<Vec<RepositoryListItem>> {
+        let listings = self
+            .get_all::<BitbucketRepositoryListing>(&format!("/repositories/{owner}"))
+            .await?;
+
+        // Bitbucket doesn't count the open pull requests of a repository, a page of one does
+        let counts = futures::future::join_all(listings.iter().map(|listing| async move {
+            self.get::<Paginated<serde_json::Value>>(
+                &format!("/repositories/{}/pullrequests", listing.full_name),
+                &[("state", "OPEN".into()), ("pagelen", "1".into())],
+            )
+            .await
+            .map(|page| page.size.unwrap_or(page.values.len()))
+        }))
+        .await;
+
+        let mut repositories = listings
+            .into_iter()
+            .zip(counts)
+            .map(|(listing, count)| {
+                let (owner, name) = listing
+                    .full_name
+                    .split_once('/')
+                    .context("repository to be in the form workspace/repo")?;
+
+                Ok(RepositoryListItem {
+                    owner: owner.to_string(),
+                    name: name.to_string(),
+                    description: Some(listing.description).filter(|d| !d.is_empty()),
+                    url: listing.links.html.map(|l| l.href).unwrap_or_default(),
+                    open_reviews: count?,
+                    pushed_at: listing.updated_on,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        repositories.sort_by_key(|r| std::cmp::Reverse(r.pushed_at));
+
+        Ok(repositories)
+    }
+}
+
+#[async_trait]
+impl GitReview for Bitbucket {
+    async fn get_review(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Review>> {
+        let Some(pull) = self.get_pull(&owner, &name, number).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Review {
+            id: format!("{owner}/{name}#{number}"),
+            number,
+            participants: pull.participants(),
+            url: pull.url(),
+            title: pull.title,
+            repository: format!("{owner}/{name}"),
+            description: pull.description,
+            head_sha: pull.source.commit.map(|c| c.hash).unwrap_or_default(),
+            head_branch: pull.source.branch.map(|b| b.name).unwrap_or_default(),
+            author: pull.author.login(),
+            publish_at: Some(pull.created_on),
+            // Bitbucket has no labels, and only tells the size through the diffstat
+            labels: Vec::new(),
+            additions: 0,
+            deletions: 0,
+            changed_files: 0,
+            comments: None,
+            status_checks: None,
+        }))
+    }
+
+    async fn get_comments(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Comments>> {
+        if self.get_pull(&owner, &name, number).await?.is_none() {
+            return Ok(None);
+        }
+        let comments = self
+            .get_all::<BitbucketComment>(&format!(
+                "/repositories/{owner}/{name}/pullrequests/{number}/comments"
+            ))
+            .await?;
+
+        Ok(Some(split_comments(
+            &format!("{owner}/{name}#{number}"),
+            comments,
+        )))
+    }
+
+    async fn get_status_checks(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Vec<StatusCheck>>> {
+        if self.get_pull(&owner, &name, number).await?.is_none() {
+            return Ok(None);
+        }
+        let statuses = self
+            .get_all::<BitbucketStatus>(&format!(
+                "/repositories/{owner}/{name}/pullrequests/{number}/statuses"
+            ))
+            .await?;
+
+        Ok(Some(
+            statuses
+                .into_iter()
+                .map(BitbucketStatus::into_check)
+                .collect(),
+        ))
+    }
+
+    async fn get_changed_files(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Vec<ChangedFile>> {
+        let stats = self
+            .get_all::<BitbucketDiffStat>(&format!(
+                "/repositories/{owner}/{name}/pullrequests/{number}/diffstat"
+            ))
+            .await?;
+
+        Ok(stats
+            .into_iter()
+            .filter_map(|s| {
+                Some(ChangedFile {
+                    path: s.new.or(s.old)?.path,
+                    additions: s.lines_added,
+                    deletions: s.lines_removed,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_diff(&self, owner: String, name: String, number: usize) -> anyhow::Result<String> {
+        self.request(
+            Method::GET,
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}/diff"),
+            &[],
+            None,
+        )
+        .await?
+        .text()
+        .await
+        .context("failed to get diff from response")
+    }
+
+    async fn get_reviews(&self, ids: Vec<String>) -> anyhow::Result<Vec<Review>> {
+        let reviews = futures::future::join_all(ids.iter().map(|id| async move {
+            let (owner, name, number) = split_reference(id)?;
+            self.get_review(owner, name, number).await
+        }))
+        .await;
+
+        Ok(reviews
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    // Nothing is cached, every fetch is fresh
+    fn invalidate_review(&self, _owner: String, _name: String, _number: usize) {}
+
+    async fn get_head_sha(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .get_pull(&owner, &name, number)
+            .await?
+            .and_then(|p| p.source.commit)
+            .map(|c| c.hash))
+    }
+
+    async fn get_commits(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Vec<Commit>> {
+        let mut commits = self
+            .get_all::<BitbucketCommit>(&format!(
+                "/repositories/{owner}/{name}/pullrequests/{number}/commits"
+            ))
+            .await?;
+        // Bitbucket lists the latest first
+        commits.reverse();
+
+        Ok(commits
+            .into_iter()
+            .map(|c| Commit {
+                short_oid: c.hash.chars().take(7).collect(),
+                oid: c.hash,
+                headline: c.message.lines().next().unwrap_or_default().into(),
+                author: c.author.user.map(|u| u.login()).unwrap_or(c.author.raw),
+                // Bitbucket doesn't verify signatures
+                signature: None,
+            })
+            .collect())
+    }
+
+    // Bitbucket keeps default descriptions in the repository settings, not in a template file
+    async fn get_review_template(
+        &self,
+        _owner: String,
+        _name: String,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl GitComment for Bitbucket {
+    async fn add_comment(&self, subject_id: String, body: String) -> anyhow::Result<()> {
+        let (owner, name, number) = split_reference(&subject_id)?;
+
+        self.send(
+            Method::POST,
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}/comments"),
+            Some(serde_json::json!({ "content": { "raw": body } })),
+        )
+        .await
+    }
+
+    async fn update_comment(&self, id: String, body: String) -> anyhow::Result<()> {
+        let (owner, name, number, id) = split_comment_id(&id)?;
+
+        self.send(
+            Method::PUT,
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}/comments/{id}"),
+            Some(serde_json::json!({ "content": { "raw": body } })),
+        )
+        .await
+    }
+
+    async fn delete_comment(&self, id: String) -> anyhow::Result<()> {
+        let (owner, name, number, id) = split_comment_id(&id)?;
+
+        self.send(
+            Method::DELETE,
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}/comments/{id}"),
+            None,
+        )
+        .await
+    }
+
+    /// Threads are identified by their first comment, replies are comments with it as parent.
+    async fn reply_to_thread(&self, thread_id: String, body: String) -> anyhow::Result<()> {
+        let (owner, name, number, id) = split_comment_id(&thread_id)?;
+
+        self.send(
+            Method::POST,
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}/comments"),
+            Some(serde_json::json!({
+                "content": { "raw": body },
+                "parent": { "id": id.parse::<u64>()? },
+            })),
+        )
+        .await
+    }
+
+    async fn resolve_thread(&self, thread_id: String) -> anyhow::Result<()> {
+        let (owner, name, number, id) = split_comment_id(&thread_id)?;
+
+        self.send(
+            Method::POST,
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}/comments/{id}/resolve"),
+            None,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl GitReviewAction for Bitbucket {
+    /// Bitbucket verdicts carry no message, so the body is added as a comment next to it.
+    async fn submit_review(
+        &self,
+        review_id: String,
+        event: ReviewEvent,
+        body: Option<String>,
+    ) -> anyhow::Result<()> {
+        let (owner, name, number) = split_reference(&review_id)?;
+        let path = format!("/repositories/{owner}/{name}/pullrequests/{number}");
+
+        match event {
+            ReviewEvent::Approve => {
+                self.send(Method::POST, &format!("{path}/approve"), None)
+                    .await?
+            }
+            ReviewEvent::RequestChanges => {
+                self.send(Method::POST, &format!("{path}/request-changes"), None)
+                    .await?
+            }
+            ReviewEvent::Comment => {}
+        }
+        if let Some(body) = body.filter(|b| !b.trim().is_empty()) {
+            self.add_comment(review_id, body).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn add_labels(
+        &self,
+        _subject_id: String,
+        _owner: String,
+        _name: String,
+        _labels: Vec<String>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("bitbucket has no labels")
+    }
+
+    async fn remove_labels(
+        &self,
+        _subject_id: String,
+        _owner: String,
+        _name: String,
+        _labels: Vec<String>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("bitbucket has no labels")
+    }
+
+    async fn enable_auto_merge(&self, _review_id: String) -> anyhow::Result<()> {
+        anyhow::bail!("bitbucket cloud can't merge automatically once checks pass")
+    }
+
+    async fn merge_review(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+        strategy: MergeStrategy,
+    ) -> anyhow::Result<()> {
+        let strategy = match strategy {
+            MergeStrategy::Merge => "merge_commit",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::Rebase => "rebase_fast_forward",
+        };
+
+        self.send(
+            Method::POST,
+            &format!("/repositories/{owner}/{name}/pullrequests/{number}/merge"),
+            Some(serde_json::json!({ "merge_strategy": strategy })),
+        )
+        .await
+    }
+
+    async fn create_review(
+        &self,
+        owner: String,
+        name: String,
+        review: NewReview,
+    ) -> anyhow::Result<usize> {
+        let mut body = serde_json::json!({
+            "title": review.title,
+            "description": review.body,
+            "source": { "branch": { "name": review.head } },
+            "draft": review.draft,
+        });
+        // Bitbucket targets the main branch of the repository without a destination
+        if let Some(base) = review.base {
+            body["destination"] = serde_json::json!({ "branch": { "name": base } });
+        }
+
+        let pull = self
+            .request(
+                Method::POST,
+                &format!("/repositories/{owner}/{name}/pullrequests"),
+                &[],
+                Some(body),
+            )
+            .await?
+            .json::<BitbucketPull>()
+            .await
+            .context("failed to parse the created pull request")?;
+
+        Ok(pull.id)
+    }
+
+    async fn reassign_reviewer(
+        &self,
+        _owner: String,
+        _name: String,
+        _number: usize,
+        _from: String,
+        _to: String,
+    ) -> anyhow::Result<()> {
+        // Reviewers are set by their uuid, which can't be looked up from a nickname
+        anyhow::bail!("reassigning reviewers isn't supported for bitbucket")
+    }
+}
+
+#[async_trait]
+impl GitIssue for Bitbucket {
+    async fn search_issues(
+        &self,
+        _query: &str,
+        _cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        anyhow::bail!("discussions aren't supported for bitbucket")
+    }
+
+    async fn get_issue(
+        &self,
+        _owner: String,
+        _name: String,
+        _number: usize,
+    ) -> anyhow::Result<Option<Issue>> {
+        anyhow::bail!("discussions aren't supported for bitbucket")
+    }
+}
+
+#[async_trait]
+impl GitAdvisory for Bitbucket {
+    async fn get_vulnerabilities(
+        &self,
+        _ecosystem: Ecosystem,
+        _package: &str,
+    ) -> anyhow::Result<Vec<Vulnerability>> {
+        anyhow::bail!("bitbucket has no advisory database")
+    }
+}
+
+#[async_trait]
+impl GitAuth for Bitbucket {
+    async fn get_token_status(&self) -> anyhow::Result<TokenStatus> {
+        let res = self
+            .authorize(self.client.get(format!("{}/user", self.api)))
+            .send()
+            .await
+            .with_context(|| format!("failed to reach {}", self.api))?;
+
+        if res.status() == StatusCode::UNAUTHORIZED {
+            return Ok(TokenStatus::Rejected);
+        }
+        if !res.status().is_success() {
+            anyhow::bail!("{}/user answered with {}", self.api, res.status());
+        }
+        let user = res
+            .json::<BitbucketUser>()
+            .await
+            .context("failed to parse the user")?;
+
+        Ok(TokenStatus::Valid {
+            login: user.login(),
+            scopes: None,
+        })
+    }
+}
+
+impl Provider for Bitbucket {
+    fn stats(&self) -> ProviderStats {
+        ProviderStats {
+            pending_requests: self.pending.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{
+        split_comment_id, split_comments, Bitbucket, BitbucketComment, BitbucketOptions,
+        BitbucketPull, SearchQuery, PAGE_SIZE,
+    };
+    use crate::{
+        conformance::{self, Backend, Fixture, Reply, Request},
+        models::{ParticipantRole, ReviewState},
+        Provider,
+    };
+
+    struct BitbucketBackend;
+
+    impl Backend for BitbucketBackend {
+        const PAGE_SIZE: usize = PAGE_SIZE;
+
+        fn provider(base: &str) -> anyhow::Result<Arc<dyn Provider + Send + Sync>> {
+            Ok(Arc::new(Bitbucket::new(
+                BitbucketOptions::new(vec!["kjuulh/rev".into()])
+                    .with_url(format!("{base}/2.0"))
+                    .with_token("fixture"),
+            )?))
+        }
+
+        fn respond(fixture: &Fixture, request: &Request) -> Reply {
+            let (path, query) = request
+                .path
+                .split_once('?')
+                .unwrap_or((request.path.as_str(), ""));
+            match path {
+                "/2.0/user" => {
+                    return Reply::ok(r#"{"uuid":"{me}","nickname":"kjuulh"}"#);
+                }
+                "/2.0/repositories/kjuulh/rev/pullrequests" => {}
+                _ => {
+                    return Reply {
+                        status: 404,
+                        body: r#"{"error":{"message":"not in the fixture"}}"#.into(),
+                    }
+                }
+            }
+
+            let param = |name: &str| {
+                query
+                    .split('&')
+                    .filter_map(|p| p.split_once('='))
+                    .find(|(n, _)| *n == name)
+                    .and_then(|(_, v)| v.parse::<usize>().ok())
+            };
+            let page = param("page").unwrap_or(1);
+            let pagelen = param("pagelen").unwrap_or(10);
+            // Sorted newest first, so the fixture hands out the reviews oldest last
+            let values = fixture
+                .reviews
+                .iter()
+                .skip((page - 1) * pagelen)
+                .take(pagelen)
+                .map(|r| {
+                    serde_json::json!({
+                        "id": r.number,
+                        "title": r.title,
+                        "author": { "uuid": "{other}", "nickname": "octocat" },
+                        "created_on": format!("2023-10-12T08:{:02}:00Z", 59 - r.number.min(59)),
+                        "source": { "commit": { "hash": "abc123" } },
+                        "destination": { "repository": { "full_name": format!("{}/{}", r.owner, r.name) } },
+                    })
+                })
+                .collect::<Vec<_>>();
+            let next = (page * pagelen < fixture.reviews.len()).then_some("next");
+
+            Reply::ok(
+                serde_json::json!({
+                    "values": values,
+                    "next": next,
+                    "size": fixture.reviews.len(),
+                })
+                .to_string(),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() -> anyhow::Result<()> {
+        conformance::run::<BitbucketBackend>().await
+    }
+
+    #[test]
+    fn test_maps_participants() -> anyhow::Result<()> {
+        let pull: BitbucketPull = serde_json::from_value(serde_json::json!({
+            "id": 3,
+            "title": "Add pipelines",
+            "author": { "nickname": "kjuulh", "display_name": "Kasper" },
+            "created_on": "2023-10-12T08:14:03Z",
+            "source": {},
+            "destination": { "repository": { "full_name": "kjuulh/rev" } },
+            "participants": [
+                { "user": { "nickname": "a" }, "role": "REVIEWER", "approved": true },
+                { "user": { "nickname": "b" }, "role": "REVIEWER", "approved": false, "state": "changes_requested" },
+                { "user": { "nickname": "c" }, "role": "PARTICIPANT", "approved": false },
+            ],
+            "reviewers": [{ "nickname": "a" }, { "nickname": "d" }],
+        }))?;
+
+        let participants = pull.participants();
+
+        let find = |login: &str| participants.iter().find(|p| p.login == login).unwrap();
+        assert_eq!(ParticipantRole::Author, find("kjuulh").role);
+        assert_eq!(Some(ReviewState::Approved), find("a").review);
+        assert_eq!(Some(ReviewState::ChangesRequested), find("b").review);
+        assert_eq!(ParticipantRole::Commenter, find("c").role);
+        assert!(find("d").requested);
+        assert_eq!(5, participants.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_groups_inline_comments() -> anyhow::Result<()> {
+        let comment = |id: u64, extra: serde_json::Value| -> anyhow::Result<BitbucketComment> {
+            let mut value = serde_json::json!({
+                "id": id,
+                "user": { "nickname": "kjuulh" },
+                "content": { "raw": format!("comment {id}") },
+                "created_on": "2023-10-12T08:14:03Z",
+                "updated_on": "2023-10-12T08:14:03Z",
+            });
+            value
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            Ok(serde_json::from_value(value)?)
+        };
+
+        let comments = split_comments(
+            "kjuulh/rev#3",
+            vec![
+                comment(1, serde_json::json!({}))?,
+                comment(
+                    2,
+                    serde_json::json!({ "inline": { "path": "src/main.rs", "to": 12 } }),
+                )?,
+                comment(3, serde_json::json!({ "parent": { "id": 2 } }))?,
+                comment(4, serde_json::json!({ "parent": { "id": 3 } }))?,
+                comment(
+                    5,
+                    serde_json::json!({
+                        "inline": { "path": "src/lib.rs", "from": 4 },
+                        "resolution": { "type": "comment_resolution" },
+                    }),
+                )?,
+            ],
+        );
+
+        assert_eq!(1, comments.comments.len());
+        assert_eq!(2, comments.threads.len());
+        assert_eq!("src/main.rs:12", comments.threads[0].location());
+        assert_eq!(3, comments.threads[0].comments.len());
+        assert!(!comments.threads[0].resolved);
+        assert_eq!("src/lib.rs:4", comments.threads[1].location());
+        assert!(comments.threads[1].resolved);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_query() -> anyhow::Result<()> {
+        let search = SearchQuery::parse("is:pr review-requested:@me state:open fix bug")?;
+        assert_eq!(
+            r#"state="OPEN" AND reviewers.uuid="{me}" AND title~"fix bug""#,
+            search.bbql(Some("{me}"))
+        );
+        assert!(SearchQuery::parse("is:pr review:required").is_err());
+
+        assert_eq!(
+            ("kjuulh".into(), "rev".into(), 3, "45"),
+            split_comment_id("kjuulh/rev#3:45")?
+        );
+
+        Ok(())
+    }
+}
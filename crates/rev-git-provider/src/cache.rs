@@ -0,0 +1,124 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+/// An in-memory least recently used cache of raw graphql responses for the current session.
+///
+/// Responses are keyed by the serialized query body, which contains both the query and its
+/// variables, so identical requests are only sent once.
+pub struct ResponseCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, String>,
+    /// Keys ordered from least to most recently used.
+    order: VecDeque<String>,
+}
+
+impl Inner {
+    fn touch(&mut self, key: &str) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            if let Some(key) = self.order.remove(pos) {
+                self.order.push_back(key);
+            }
+        }
+    }
+}
+
+impl ResponseCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let mut inner = self.inner.lock().unwrap();
+        let value = inner.entries.get(key).cloned()?;
+        inner.touch(key);
+
+        Some(value)
+    }
+
+    pub fn insert(&self, key: String, value: String) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.entries.insert(key.clone(), value).is_some() {
+            inner.touch(&key);
+            return;
+        }
+
+        inner.order.push_back(key);
+        while inner.order.len() > self.capacity {
+            if let Some(evicted) = inner.order.pop_front() {
+                inner.entries.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn remove(&self, key: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.entries.remove(key).is_some() {
+            inner.order.retain(|k| k != key);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The bytes held by the cached responses and their keys.
+    pub fn size(&self) -> usize {
+        self.inner
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .map(|(k, v)| k.len() + v.len())
+            .sum()
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.entries.clear();
+        inner.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ResponseCache;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = ResponseCache::new(2);
+        cache.insert("a".into(), "1".into());
+        cache.insert("b".into(), "2".into());
+
+        // touching a makes b the least recently used
+        assert_eq!(Some("1".into()), cache.get("a"));
+        cache.insert("c".into(), "3".into());
+
+        assert_eq!(None, cache.get("b"));
+        assert_eq!(Some("1".into()), cache.get("a"));
+        assert_eq!(Some("3".into()), cache.get("c"));
+
+        cache.remove("a");
+        assert_eq!(None, cache.get("a"));
+
+        cache.clear();
+        assert_eq!(None, cache.get("c"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// Set to `record` to send requests to github and store the responses in the cassette.
+pub const MODE_ENV: &str = "REV_CASSETTE_MODE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Answers every request from the cassette, without a token or network.
+    Replay,
+    /// Sends requests to github and writes the responses into the cassette.
+    Record,
+}
+
+impl Mode {
+    pub fn from_env() -> Self {
+        match std::env::var(MODE_ENV).as_deref() {
+            Ok("record") => Self::Record,
+            _ => Self::Replay,
+        }
+    }
+}
+
+/// A request and the raw body github answered it with.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Interaction {
+    pub operation: Option<String>,
+    pub variables: serde_json::Value,
+    pub response: String,
+}
+
+/// Records graphql responses into a json file and replays them, so tests against the provider
+/// don't need credentials and aren't at the mercy of what is open on github right now.
+///
+/// Requests are matched on the operation name and variables, not the query text, so editing a
+/// query doesn't invalidate the cassettes of every test.
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl Cassette {
+    pub fn load(path: impl Into<PathBuf>, mode: Mode) -> anyhow::Result<Self> {
+        let path = path.into();
+        let interactions = match mode {
+            Mode::Replay => read(&path)?,
+            Mode::Record => Vec::new(),
+        };
+
+        Ok(Self {
+            path,
+            mode,
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The recorded response to the request, replaying fails when nothing was recorded for it.
+    pub fn replay(&self, request: &serde_json::Value) -> anyhow::Result<Option<String>> {
+        if self.mode == Mode::Record {
+            return Ok(None);
+        }
+
+        let (operation, variables) = split(request);
+        let response = self
+            .interactions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|i| i.operation == operation && i.variables == variables)
+            .map(|i| i.response.clone());
+
+        match response {
+            Some(response) => Ok(Some(response)),
+            None => anyhow::bail!(
+                "no response recorded in {} for {} with {variables}, record it with {MODE_ENV}=record",
+                self.path.display(),
+                operation.as_deref().unwrap_or("query"),
+            ),
+        }
+    }
+
+    /// Stores the response and writes the cassette, replacing an earlier recording of the request.
+    pub fn record(&self, request: &serde_json::Value, response: &str) -> anyhow::Result<()> {
+        let (operation, variables) = split(request);
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.retain(|i| !(i.operation == operation && i.variables == variables));
+        interactions.push(Interaction {
+            operation,
+            variables,
+            response: response.to_string(),
+        });
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&*interactions)?)
+            .with_context(|| format!("failed to write cassette {}", self.path.display()))
+    }
+}
+
+fn read(path: &Path) -> anyhow::Result<Vec<Interaction>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read cassette {}", path.display()))?;
+
+    serde_json::from_str(&content)
+        .with_context(|| format!("failed to parse cassette {}", path.display()))
+}
+
+/// The operation name and variables of a serialized graphql request body.
+fn split(request: &serde_json::Value) -> (Option<String>, serde_json::Value) {
+    (
+        request["operationName"].as_str().map(|o| o.to_string()),
+        request["variables"].clone(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Cassette, Mode};
+
+    #[test]
+    fn test_records_and_replays() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("rev-cassette-{}.json", std::process::id()));
+        let request = serde_json::json!({
+            "operationName": "PullRequests",
+            "query": "query PullRequests { ... }",
+            "variables": { "query": "is:pr", "cursor": null },
+        });
+
+        let recorder = Cassette::load(&path, Mode::Record)?;
+        assert_eq!(None, recorder.replay(&request)?);
+        recorder.record(&request, r#"{"data":{}}"#)?;
+
+        let player = Cassette::load(&path, Mode::Replay)?;
+        std::fs::remove_file(&path)?;
+
+        let mut edited = request.clone();
+        edited["query"] = "query PullRequests { edited }".into();
+        assert_eq!(Some(r#"{"data":{}}"#.to_string()), player.replay(&edited)?);
+
+        edited["variables"]["query"] = "is:issue".into();
+        assert!(player.replay(&edited).is_err());
+
+        Ok(())
+    }
+}
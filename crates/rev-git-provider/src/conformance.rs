@@ -0,0 +1,230 @@
+//! Behaviour every [`Provider`] has to share, checked against a fixture server speaking the api of
+//! the backend.
+//!
+//! A backend implements [`Backend`] to build its provider against the server and to answer
+//! requests from a [`Fixture`], then runs [`run`] from its tests.
+
+use std::{collections::HashSet, sync::Arc};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::Provider;
+
+/// A request received by the fixture server.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+/// What the fixture server answers a request with.
+#[derive(Debug, Clone)]
+pub struct Reply {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Reply {
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            body: body.into(),
+        }
+    }
+}
+
+/// A review waiting for the user, as the backend would list it.
+#[derive(Debug, Clone)]
+pub struct Seed {
+    pub id: String,
+    pub owner: String,
+    pub name: String,
+    pub number: usize,
+    pub title: String,
+}
+
+/// The state of the backend served by the fixture server.
+#[derive(Debug, Clone, Default)]
+pub struct Fixture {
+    pub reviews: Vec<Seed>,
+    /// Fails every request with the status, e.g. 401 for a rejected token.
+    pub fail_with: Option<u16>,
+}
+
+impl Fixture {
+    fn with_reviews(count: usize) -> Self {
+        Self {
+            reviews: (1..=count)
+                .map(|number| Seed {
+                    id: format!("review-{number}"),
+                    owner: "kjuulh".into(),
+                    name: "rev".into(),
+                    number,
+                    title: format!("Review {number}"),
+                })
+                .collect(),
+            fail_with: None,
+        }
+    }
+}
+
+pub trait Backend {
+    /// How many reviews the backend lists per page.
+    const PAGE_SIZE: usize;
+
+    /// Builds the provider talking to the fixture server at `base`, e.g. `http://127.0.0.1:4321`.
+    fn provider(base: &str) -> anyhow::Result<Arc<dyn Provider + Send + Sync>>;
+
+    /// Answers the request the way the backend would with the fixture as its state.
+    fn respond(fixture: &Fixture, request: &Request) -> Reply;
+}
+
+/// Runs the whole suite against the backend.
+pub async fn run<B: Backend>() -> anyhow::Result<()> {
+    paginates::<B>().await?;
+    lists_nothing::<B>().await?;
+    maps_errors::<B>().await?;
+
+    Ok(())
+}
+
+/// Following the cursor lists every review once, in order, a page at a time.
+async fn paginates<B: Backend>() -> anyhow::Result<()> {
+    let count = B::PAGE_SIZE * 2 + 3;
+    let provider = B::provider(&serve::<B>(Fixture::with_reviews(count)).await?)?;
+
+    let first = provider.get_user_reviews(None, None, None).await?;
+    assert_eq!(B::PAGE_SIZE, first.items.len());
+    assert!(first.has_more);
+
+    let mut numbers = first.items.iter().map(|i| i.number).collect::<Vec<_>>();
+    let mut page = first;
+    while page.has_more {
+        page = provider
+            .get_user_reviews_cursor(None, None, None, page.last_cursor.clone())
+            .await?;
+        numbers.extend(page.items.iter().map(|i| i.number));
+        assert!(numbers.len() <= count, "the cursor never reached the end");
+    }
+
+    assert_eq!((1..=count).collect::<Vec<_>>(), numbers);
+    assert_eq!(count, numbers.iter().collect::<HashSet<_>>().len());
+
+    Ok(())
+}
+
+/// No reviews is an empty page, not an error.
+async fn lists_nothing<B: Backend>() -> anyhow::Result<()> {
+    let provider = B::provider(&serve::<B>(Fixture::default()).await?)?;
+
+    let page = provider.get_user_reviews(None, None, None).await?;
+    assert!(page.items.is_empty());
+    assert!(!page.has_more);
+
+    Ok(())
+}
+
+/// A rejected token tells the user what to do about it, other failures are errors rather than
+/// empty results.
+async fn maps_errors<B: Backend>() -> anyhow::Result<()> {
+    let fixture = |status| Fixture {
+        fail_with: Some(status),
+        ..Fixture::with_reviews(1)
+    };
+
+    let provider = B::provider(&serve::<B>(fixture(401)).await?)?;
+    let Err(error) = provider.get_user_reviews(None, None, None).await else {
+        panic!("expected a rejected token to fail");
+    };
+    assert!(
+        format!("{error:#}").contains("token"),
+        "expected the error to mention the token: {error:#}"
+    );
+
+    let provider = B::provider(&serve::<B>(fixture(500)).await?)?;
+    assert!(provider.get_user_reviews(None, None, None).await.is_err());
+
+    Ok(())
+}
+
+/// Starts a fixture server answering with the backend, returning its base url.
+async fn serve<B: Backend>(fixture: Fixture) -> anyhow::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base = format!("http://{}", listener.local_addr()?);
+    let fixture = Arc::new(fixture);
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let fixture = fixture.clone();
+            tokio::spawn(async move {
+                if let Err(e) = answer::<B>(stream, &fixture).await {
+                    tracing::warn!("fixture server failed to answer: {e}");
+                }
+            });
+        }
+    });
+
+    Ok(base)
+}
+
+async fn answer<B: Backend>(mut stream: TcpStream, fixture: &Fixture) -> anyhow::Result<()> {
+    let request = read_request(&mut stream).await?;
+    let reply = match fixture.fail_with {
+        Some(status) => Reply {
+            status,
+            body: r#"{"message":"fixture failure"}"#.into(),
+        },
+        None => B::respond(fixture, &request),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {} Fixture\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+        reply.status,
+        reply.body.len(),
+        reply.body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+
+    Ok(())
+}
+
+async fn read_request(stream: &mut TcpStream) -> anyhow::Result<Request> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0; 4096];
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        anyhow::ensure!(read > 0, "connection closed before the request was read");
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let length = lines
+        .filter_map(|l| l.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or_default();
+
+    while buffer.len() < header_end + length {
+        let read = stream.read(&mut chunk).await?;
+        anyhow::ensure!(read > 0, "connection closed before the body was read");
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    Ok(Request {
+        method,
+        path,
+        body: String::from_utf8_lossy(&buffer[header_end..header_end + length]).to_string(),
+    })
+}
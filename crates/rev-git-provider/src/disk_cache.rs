@@ -0,0 +1,208 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How much the responses on disk may take up before the oldest are evicted.
+const MAX_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Tells the temporary files of the writes of a session apart.
+static WRITES: AtomicU64 = AtomicU64::new(0);
+
+/// Raw responses kept on disk between sessions, one json file per response, so the first screen
+/// shows up without waiting for the api.
+///
+/// Responses are keyed like the [`crate::cache::ResponseCache`], by the serialized request which
+/// holds the query, its variables and cursor. Entries older than the ttl are stale, it's up to
+/// the caller whether a stale entry is good enough or has to be revalidated. Once the responses
+/// take up more than the max size, the ones written longest ago are evicted.
+pub struct DiskCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DiskEntry {
+    /// The full key, as file names are hashes which may collide.
+    pub key: String,
+    pub body: String,
+    /// The ETag of the response, for revalidating it with `If-None-Match`.
+    pub etag: Option<String>,
+    pub stored_at: DateTime<Utc>,
+}
+
+impl DiskCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+            max_bytes: MAX_BYTES,
+        }
+    }
+
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<DiskEntry> {
+        let content = std::fs::read_to_string(self.path(key)).ok()?;
+        let entry = serde_json::from_str::<DiskEntry>(&content).ok()?;
+
+        (entry.key == key).then_some(entry)
+    }
+
+    /// Whether the entry is younger than the ttl.
+    pub fn is_fresh(&self, entry: &DiskEntry) -> bool {
+        let age = Utc::now() - entry.stored_at;
+
+        age.to_std().map(|age| age < self.ttl).unwrap_or(true)
+    }
+
+    /// Stores the response, failing to write only costs a request next session.
+    pub fn insert(&self, key: &str, body: &str, etag: Option<String>) {
+        let entry = DiskEntry {
+            key: key.to_string(),
+            body: body.to_string(),
+            etag,
+            stored_at: Utc::now(),
+        };
+
+        let path = self.path(key);
+        if let Err(e) = write(&self.dir, &path, &entry) {
+            tracing::warn!("failed to write response cache: {e:#}");
+            return;
+        }
+
+        self.evict(&path);
+    }
+
+    pub fn remove(&self, key: &str) {
+        let _ = std::fs::remove_file(self.path(key));
+    }
+
+    pub fn clear(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if entry.path().extension().is_some_and(|e| e == "json") {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key)))
+    }
+
+    /// Removes the entries written longest ago until the cache fits in its max size again,
+    /// keeping the one just written.
+    fn evict(&self, written: &Path) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut files = entries
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|e| e == "json"))
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                Some((metadata.modified().ok()?, metadata.len(), e.path()))
+            })
+            .collect::<Vec<_>>();
+        let mut size = files.iter().map(|(_, len, _)| len).sum::<u64>();
+        files.sort();
+
+        for (_, len, path) in files {
+            if size <= self.max_bytes {
+                break;
+            }
+            if path != written && std::fs::remove_file(&path).is_ok() {
+                size -= len;
+            }
+        }
+    }
+}
+
+fn write(dir: &Path, path: &Path, entry: &DiskEntry) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    // Write then rename, so a concurrent session never reads half a response. The temporary file
+    // is unique to the write, so sessions writing the same response don't write into each other
+    let tmp = path.with_extension(format!(
+        "json.{}.{}.tmp",
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&tmp, serde_json::to_vec(entry)?)?;
+    std::fs::rename(&tmp, path)?;
+
+    Ok(())
+}
+
+/// A hash which stays the same between builds, unlike the one of the standard library, so the
+/// cache outlives upgrades.
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::DiskCache;
+
+    #[test]
+    fn test_outlives_the_session() {
+        let dir = std::env::temp_dir().join(format!("rev-disk-cache-test-{}", std::process::id()));
+        let cache = DiskCache::new(&dir, Duration::from_secs(60));
+
+        assert_eq!(None, cache.get("query"));
+        cache.insert("query", "body", Some("\"etag\"".into()));
+
+        // A new session reads what the last one wrote
+        let cache = DiskCache::new(&dir, Duration::from_secs(60));
+        let entry = cache.get("query").unwrap();
+        assert_eq!("body", entry.body);
+        assert_eq!(Some("\"etag\"".into()), entry.etag);
+        assert!(cache.is_fresh(&entry));
+        assert!(!DiskCache::new(&dir, Duration::ZERO).is_fresh(&entry));
+
+        cache.remove("query");
+        assert_eq!(None, cache.get("query"));
+
+        cache.insert("other", "body", None);
+        cache.clear();
+        assert_eq!(None, cache.get("other"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_evicts_the_oldest_entries() {
+        let dir = std::env::temp_dir().join(format!("rev-disk-cache-evict-{}", std::process::id()));
+        let body = "x".repeat(1000);
+        // Room for a single entry of the body
+        let cache = DiskCache::new(&dir, Duration::from_secs(60)).with_max_bytes(1500);
+
+        cache.insert("first", &body, None);
+        cache.insert("second", &body, None);
+        assert_eq!(None, cache.get("first"));
+        assert!(cache.get("second").is_some());
+
+        cache.insert("third", &body, None);
+        assert_eq!(None, cache.get("second"));
+        assert!(cache.get("third").is_some());
+        // Only the responses are left, no temporary files
+        assert_eq!(1, std::fs::read_dir(&dir).unwrap().count());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
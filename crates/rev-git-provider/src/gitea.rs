@@ -0,0 +1,1362 @@
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    github::PendingRequest,
+    models::{
+        ChangedFile, Comment, Comments, Commit, CommitSignature, CurrentState, Ecosystem, Issue,
+        Label, MergeStrategy, NewReview, Participant, ParticipantRole, ProviderStats,
+        RepositoryListItem, Review, ReviewEvent, ReviewList, ReviewListItem, ReviewState,
+        StatusCheck, TokenStatus, Vulnerability,
+    },
+    traits::{
+        GitAdvisory, GitAuth, GitComment, GitIssue, GitReview, GitReviewAction, GitUserReview,
+    },
+    Provider,
+};
+
+/// How many reviews or issues a page of a search holds.
+pub const PAGE_SIZE: usize = 20;
+
+/// Where the pull request template of a repository can be, in the order gitea looks for it.
+const TEMPLATE_PATHS: &[&str] = &[
+    ".gitea/pull_request_template.md",
+    ".gitea/PULL_REQUEST_TEMPLATE.md",
+    ".github/pull_request_template.md",
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+];
+
+pub struct GiteaOptions {
+    /// The address of the instance, e.g. `https://codeberg.org`.
+    url: String,
+    token: Option<String>,
+}
+
+impl GiteaOptions {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            token: None,
+        }
+    }
+
+    /// Used instead of `GITEA_TOKEN`.
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+}
+
+/// A provider for Gitea and Forgejo, through their rest api.
+///
+/// Gitea addresses reviews and comments by their repository, so ids handed out by this provider
+/// are references such as `owner/name#12` for reviews and issues and `owner/name:345` for
+/// comments.
+pub struct Gitea {
+    client: reqwest::Client,
+    api: String,
+    /// Requests sent which haven't been answered yet.
+    pending: AtomicUsize,
+}
+
+impl Gitea {
+    pub fn new(options: GiteaOptions) -> anyhow::Result<Self> {
+        let token = match options.token {
+            Some(token) => token,
+            None => std::env::var("GITEA_TOKEN").context(
+                "no gitea token found, set token in the provider block of rev.kdl or GITEA_TOKEN",
+            )?,
+        };
+
+        let client = reqwest::Client::builder()
+            .user_agent("rev")
+            .default_headers(
+                std::iter::once((
+                    reqwest::header::AUTHORIZATION,
+                    reqwest::header::HeaderValue::from_str(&format!("token {token}"))?,
+                ))
+                .collect(),
+            )
+            .build()?;
+
+        Ok(Self {
+            client,
+            api: format!("{}/api/v1", options.url.trim_end_matches('/')),
+            pending: AtomicUsize::new(0),
+        })
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<reqwest::Response> {
+        self.fetch(method.clone(), path, query, body)
+            .await?
+            .with_context(|| {
+                format!(
+                    "gitea call {method} {path} failed with {}",
+                    StatusCode::NOT_FOUND
+                )
+            })
+    }
+
+    /// Sends the request, a missing resource is `None` rather than an error.
+    async fn fetch(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<Option<reqwest::Response>> {
+        let _pending = PendingRequest::start(&self.pending);
+        let url = format!("{}{path}", self.api);
+
+        let mut request = self.client.request(method.clone(), &url).query(query);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let res = request
+            .send()
+            .await
+            .with_context(|| format!("gitea call {method} {path} failed"))?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if res.status() == StatusCode::UNAUTHORIZED {
+            anyhow::bail!(
+                "gitea rejected the token, it may be expired or revoked, create a new one under settings/applications"
+            );
+        }
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            tracing::error!("gitea error: {body}");
+            anyhow::bail!("gitea call {method} {path} failed with {status}");
+        }
+
+        Ok(Some(res))
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> anyhow::Result<T> {
+        self.request(Method::GET, path, query, None)
+            .await?
+            .json()
+            .await
+            .with_context(|| format!("failed to parse the response of {path}"))
+    }
+
+    /// Like [`Gitea::get`], but a missing resource is `None` rather than an error.
+    async fn get_optional<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<Option<T>> {
+        let Some(res) = self.fetch(Method::GET, path, &[], None).await? else {
+            return Ok(None);
+        };
+
+        res.json()
+            .await
+            .map(Some)
+            .with_context(|| format!("failed to parse the response of {path}"))
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        self.request(method, path, &[], body).await?;
+
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        kind: &str,
+        search: SearchQuery,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> anyhow::Result<(Vec<GiteaIssue>, usize, bool)> {
+        let page = cursor
+            .as_deref()
+            .map(|c| c.parse::<usize>().context("cursor to be a page number"))
+            .transpose()?
+            .unwrap_or(1);
+
+        let mut query = search.params();
+        query.push(("type", kind.to_string()));
+        query.push(("page", page.to_string()));
+        query.push(("limit", limit.to_string()));
+
+        let res = self
+            .request(Method::GET, "/repos/issues/search", &query, None)
+            .await?;
+        let total = res
+            .headers()
+            .get("x-total-count")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.parse::<usize>().ok());
+        let issues = res
+            .json::<Vec<GiteaIssue>>()
+            .await
+            .context("failed to parse the search results")?;
+
+        let has_more = match total {
+            Some(total) => page * limit < total,
+            None => issues.len() == limit,
+        };
+        let total = total.unwrap_or(issues.len());
+
+        Ok((issues, total, has_more))
+    }
+
+    async fn search_list(
+        &self,
+        kind: &str,
+        search: SearchQuery,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        let page = cursor
+            .as_deref()
+            .and_then(|c| c.parse::<usize>().ok())
+            .unwrap_or(1);
+        let (issues, _, has_more) = self.search(kind, search, cursor, PAGE_SIZE).await?;
+
+        Ok(ReviewList {
+            items: issues.into_iter().map(GiteaIssue::into_list_item).collect(),
+            last_cursor: has_more.then(|| (page + 1).to_string()),
+            has_more,
+        })
+    }
+
+    async fn get_pull(
+        &self,
+        owner: &str,
+        name: &str,
+        number: usize,
+    ) -> anyhow::Result<Option<GiteaPull>> {
+        self.get_optional(&format!("/repos/{owner}/{name}/pulls/{number}"))
+            .await
+    }
+
+    async fn label_ids(
+        &self,
+        owner: &str,
+        name: &str,
+        labels: &[String],
+    ) -> anyhow::Result<Vec<u64>> {
+        let known = self
+            .get::<Vec<GiteaLabel>>(
+                &format!("/repos/{owner}/{name}/labels"),
+                &[("limit", "50".into())],
+            )
+            .await?;
+
+        labels
+            .iter()
+            .map(|label| {
+                known
+                    .iter()
+                    .find(|l| l.name.eq_ignore_ascii_case(label))
+                    .and_then(|l| l.id)
+                    .with_context(|| format!("{owner}/{name} has no label named {label}"))
+            })
+            .collect()
+    }
+
+    async fn get_issue_comments(
+        &self,
+        owner: &str,
+        name: &str,
+        number: usize,
+    ) -> anyhow::Result<Comments> {
+        let comments = self
+            .get::<Vec<GiteaComment>>(
+                &format!("/repos/{owner}/{name}/issues/{number}/comments"),
+                &[],
+            )
+            .await?;
+
+        Ok(Comments {
+            has_previous: false,
+            comments: comments
+                .into_iter()
+                .map(|c| Comment {
+                    id: format!("{owner}/{name}:{}", c.id),
+                    author: c.user.login,
+                    text: c.body.clone(),
+                    body: c.body,
+                    url: c.html_url,
+                    last_edited_at: (c.updated_at != c.created_at).then_some(c.updated_at),
+                })
+                .collect(),
+            // Code comments belong to reviews on Gitea and aren't fetched
+            threads: Vec::new(),
+        })
+    }
+}
+
+/// Splits a review or issue reference, `owner/name#number`, into its parts.
+fn split_reference(reference: &str) -> anyhow::Result<(String, String, usize)> {
+    let parts = reference.split_once('#').and_then(|(repository, number)| {
+        let (owner, name) = repository.split_once('/')?;
+        Some((owner.to_string(), name.to_string(), number.parse().ok()?))
+    });
+
+    parts.with_context(|| format!("{reference} is not in the form owner/name#number"))
+}
+
+/// Splits a comment id, `owner/name:id`, into the repository and the id of the comment.
+fn split_comment_id(id: &str) -> anyhow::Result<(&str, &str)> {
+    id.rsplit_once(':')
+        .filter(|(repository, _)| repository.contains('/'))
+        .with_context(|| format!("{id} is not in the form owner/name:id"))
+}
+
+/// The qualifiers of a github style search query, as far as gitea can search by them.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SearchQuery {
+    state: Option<String>,
+    owner: Option<String>,
+    team: Option<String>,
+    labels: Vec<String>,
+    review_requested: bool,
+    created: bool,
+    assigned: bool,
+    mentioned: bool,
+    words: Vec<String>,
+}
+
+impl SearchQuery {
+    fn parse(query: &str) -> anyhow::Result<Self> {
+        let mut search = Self::default();
+
+        for word in query.split_whitespace() {
+            match word.split_once(':') {
+                Some(("is", "pr" | "issue")) => {}
+                Some(("is" | "state", state @ ("open" | "closed"))) => {
+                    search.state = Some(state.to_string())
+                }
+                Some(("org" | "user", owner)) => search.owner = Some(owner.to_string()),
+                Some(("label", labels)) => search
+                    .labels
+                    .extend(labels.split(',').map(|l| l.trim_matches('"').to_string())),
+                Some(("review-requested", "@me")) => search.review_requested = true,
+                Some(("team-review-requested", team)) => {
+                    let (org, team) = team
+                        .split_once('/')
+                        .context("team-review-requested must be in the form org/team")?;
+                    search.owner = Some(org.to_string());
+                    search.team = Some(team.to_string());
+                    search.review_requested = true;
+                }
+                Some(("author", "@me")) => search.created = true,
+                Some(("assignee", "@me")) => search.assigned = true,
+                Some(("mentions", "@me")) => search.mentioned = true,
+                Some((qualifier, _)) => {
+                    anyhow::bail!("gitea can't search by {qualifier}:, only by is, state, org, label, review-requested:@me, author:@me, assignee:@me and mentions:@me")
+                }
+                None => search.words.push(word.to_string()),
+            }
+        }
+
+        Ok(search)
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("state", self.state.clone().unwrap_or_else(|| "open".into()))];
+        if let Some(owner) = self.owner.as_ref() {
+            params.push(("owner", owner.clone()));
+        }
+        if let Some(team) = self.team.as_ref() {
+            params.push(("team", team.clone()));
+        }
+        if !self.labels.is_empty() {
+            params.push(("labels", self.labels.join(",")));
+        }
+        for (name, set) in [
+            ("review_requested", self.review_requested),
+            ("created", self.created),
+            ("assigned", self.assigned),
+            ("mentioned", self.mentioned),
+        ] {
+            if set {
+                params.push((name, "true".into()));
+            }
+        }
+        if !self.words.is_empty() {
+            params.push(("q", self.words.join(" ")));
+        }
+
+        params
+    }
+}
+
+#[derive(Deserialize)]
+struct GiteaUser {
+    login: String,
+    #[serde(default)]
+    full_name: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaLabel {
+    id: Option<u64>,
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+impl From<GiteaLabel> for Label {
+    fn from(label: GiteaLabel) -> Self {
+        Self {
+            name: label.name,
+            color: label.color.map(|c| c.trim_start_matches('#').to_string()),
+            description: label.description.filter(|d| !d.is_empty()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GiteaRepositoryMeta {
+    owner: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaRepository {
+    default_branch: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaRepositoryListing {
+    name: String,
+    owner: GiteaUser,
+    #[serde(default)]
+    description: String,
+    html_url: String,
+    #[serde(default)]
+    open_pr_counter: usize,
+    #[serde(default)]
+    archived: bool,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<GiteaRepositoryListing> for RepositoryListItem {
+    fn from(repository: GiteaRepositoryListing) -> Self {
+        Self {
+            owner: repository.owner.login,
+            name: repository.name,
+            description: Some(repository.description).filter(|d| !d.is_empty()),
+            url: repository.html_url,
+            open_reviews: repository.open_pr_counter,
+            pushed_at: repository.updated_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GiteaIssue {
+    number: usize,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    user: GiteaUser,
+    #[serde(default)]
+    labels: Vec<GiteaLabel>,
+    created_at: DateTime<Utc>,
+    repository: GiteaRepositoryMeta,
+    #[serde(default)]
+    html_url: String,
+}
+
+impl GiteaIssue {
+    /// Searches don't include the size, approvals or checks of pull requests, so those are left
+    /// empty rather than fetched for every item.
+    fn into_list_item(self) -> ReviewListItem {
+        ReviewListItem {
+            id: format!(
+                "{}/{}#{}",
+                self.repository.owner, self.repository.name, self.number
+            ),
+            name: self.repository.name,
+            title: self.title,
+            url: self.html_url,
+            owner: self.repository.owner,
+            date: self.created_at,
+            number: self.number,
+            author: self.user.login,
+            labels: self.labels.into_iter().map(Label::from).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GiteaBranch {
+    sha: String,
+    #[serde(rename = "ref", default)]
+    ref_name: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaPull {
+    number: usize,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    user: GiteaUser,
+    #[serde(default)]
+    labels: Vec<GiteaLabel>,
+    head: GiteaBranch,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    requested_reviewers: Option<Vec<GiteaUser>>,
+    #[serde(default)]
+    html_url: String,
+    // Only returned by newer versions of gitea
+    #[serde(default)]
+    additions: usize,
+    #[serde(default)]
+    deletions: usize,
+    #[serde(default)]
+    changed_files: usize,
+}
+
+#[derive(Deserialize)]
+struct GiteaReview {
+    user: Option<GiteaUser>,
+    state: String,
+    #[serde(default)]
+    dismissed: bool,
+}
+
+#[derive(Deserialize)]
+struct GiteaComment {
+    id: u64,
+    user: GiteaUser,
+    body: String,
+    #[serde(default)]
+    html_url: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct GiteaStatus {
+    id: u64,
+    status: String,
+    context: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    target_url: Option<String>,
+    #[serde(default)]
+    creator: Option<GiteaUser>,
+}
+
+#[derive(Deserialize)]
+struct GiteaCombinedStatus {
+    #[serde(default)]
+    statuses: Option<Vec<GiteaStatus>>,
+}
+
+#[derive(Deserialize)]
+struct GiteaChangedFile {
+    filename: String,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Deserialize)]
+struct GiteaCommitAuthor {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaSigner {
+    #[serde(default)]
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaVerification {
+    verified: bool,
+    #[serde(default)]
+    signature: String,
+    #[serde(default)]
+    signer: Option<GiteaSigner>,
+}
+
+#[derive(Deserialize)]
+struct GiteaCommitDetail {
+    message: String,
+    author: GiteaCommitAuthor,
+    #[serde(default)]
+    verification: Option<GiteaVerification>,
+}
+
+#[derive(Deserialize)]
+struct GiteaCommit {
+    sha: String,
+    commit: GiteaCommitDetail,
+    #[serde(default)]
+    author: Option<GiteaUser>,
+}
+
+fn review_state(review: &GiteaReview) -> Option<ReviewState> {
+    if review.dismissed {
+        return Some(ReviewState::Dismissed);
+    }
+
+    match review.state.as_str() {
+        "APPROVED" => Some(ReviewState::Approved),
+        "REQUEST_CHANGES" => Some(ReviewState::ChangesRequested),
+        "COMMENT" => Some(ReviewState::Commented),
+        "PENDING" => Some(ReviewState::Pending),
+        // `REQUEST_REVIEW` marks the request itself, not a review
+        _ => None,
+    }
+}
+
+fn status_state(status: &str) -> CurrentState {
+    match status {
+        // Warnings don't block merging in gitea
+        "success" | "warning" => CurrentState::Success,
+        "error" | "failure" => CurrentState::Failure,
+        _ => CurrentState::Pending,
+    }
+}
+
+#[async_trait]
+impl GitUserReview for Gitea {
+    async fn get_user_reviews(
+        &self,
+        requested: Option<&str>,
+        org: Option<&str>,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<ReviewList> {
+        self.get_user_reviews_cursor(requested, org, tags, None)
+            .await
+    }
+
+    async fn get_user_reviews_cursor(
+        &self,
+        requested: Option<&str>,
+        org: Option<&str>,
+        tags: Option<Vec<String>>,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        // Gitea only knows the review requests of the user the token belongs to, and of teams
+        let mut search = SearchQuery {
+            review_requested: true,
+            owner: org.map(|o| o.to_string()),
+            labels: tags.unwrap_or_default(),
+            ..Default::default()
+        };
+        match requested.map(|r| r.split_once('/')) {
+            None => {}
+            Some(Some((org, team))) => {
+                search.owner = Some(org.to_string());
+                search.team = Some(team.to_string());
+            }
+            Some(None) => {
+                anyhow::bail!("gitea only lists the review requests of yourself or a team in the form org/team")
+            }
+        }
+
+        self.search_list("pulls", search, cursor).await
+    }
+
+    async fn search_reviews(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        self.search_list("pulls", SearchQuery::parse(query)?, cursor)
+            .await
+    }
+
+    async fn count_search(&self, query: &str) -> anyhow::Result<usize> {
+        let (_, total, _) = self
+            .search("pulls", SearchQuery::parse(query)?, None, 1)
+            .await?;
+
+        Ok(total)
+    }
+
+    async fn get_repositories(&self, owner: &str) -> anyhow::Result<Vec<RepositoryListItem>> {
+        // Organizations and users list their repositories under different paths
+        let path = match self
+            .fetch(Method::GET, &format!("/orgs/{owner}"), &[], None)
+            .await?
+        {
+            Some(_) => format!("/orgs/{owner}/repos"),
+            None => format!("/users/{owner}/repos"),
+        };
+
+        let mut repositories = Vec::new();
+        for page in 1.. {
+            let listings = self
+                .get::<Vec<GiteaRepositoryListing>>(
+                    &path,
+                    &[("page", page.to_string()), ("limit", PAGE_SIZE.to_string())],
+                )
+                .await?;
+            let done = listings.len() < PAGE_SIZE;
+            repositories.extend(
+                listings
+                    .into_iter()
+                    .filter(|r| !r.archived)
+                    .map(RepositoryListItem::from),
+            );
+            if done {
+                break;
+            }
+        }
+        repositories.sort_by_key(|r| std::cmp::Reverse(r.pushed_at));
+
+        Ok(repositories)
+    }
+}
+
+#[async_trait]
+impl GitReview for Gitea {
+    async fn get_review(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Review>> {
+        let Some(pull) = self.get_pull(&owner, &name, number).await? else {
+            return Ok(None);
+        };
+        let reviews = self
+            .get::<Vec<GiteaReview>>(
+                &format!("/repos/{owner}/{name}/pulls/{number}/reviews"),
+                &[],
+            )
+            .await?;
+
+        // The latest review of each reviewer, reviews come oldest first
+        let mut latest = BTreeMap::new();
+        for review in reviews.iter() {
+            if let (Some(user), Some(state)) = (review.user.as_ref(), review_state(review)) {
+                latest.insert(user.login.clone(), (user.full_name.clone(), state));
+            }
+        }
+        let requested = pull.requested_reviewers.unwrap_or_default();
+
+        let mut participants = vec![Participant {
+            login: pull.user.login.clone(),
+            name: Some(pull.user.full_name.clone()).filter(|n| !n.is_empty()),
+            role: ParticipantRole::Author,
+            requested: false,
+            review: None,
+        }];
+        for user in requested.iter() {
+            participants.push(Participant {
+                login: user.login.clone(),
+                name: Some(user.full_name.clone()).filter(|n| !n.is_empty()),
+                role: ParticipantRole::Reviewer,
+                requested: true,
+                review: latest.remove(&user.login).map(|(_, state)| state),
+            });
+        }
+        for (login, (name, state)) in latest {
+            participants.push(Participant {
+                login,
+                name: Some(name).filter(|n| !n.is_empty()),
+                role: ParticipantRole::Reviewer,
+                requested: false,
+                review: Some(state),
+            });
+        }
+
+        Ok(Some(Review {
+            id: format!("{owner}/{name}#{}", pull.number),
+            number: pull.number,
+            title: pull.title,
+            repository: format!("{owner}/{name}"),
+            url: pull.html_url,
+            description: pull.body.unwrap_or_default(),
+            head_sha: pull.head.sha,
+            head_branch: pull.head.ref_name,
+            author: pull.user.login,
+            publish_at: Some(pull.created_at),
+            labels: pull.labels.into_iter().map(Label::from).collect(),
+            additions: pull.additions,
+            deletions: pull.deletions,
+            changed_files: pull.changed_files,
+            comments: None,
+            status_checks: None,
+            participants,
+        }))
+    }
+
+    async fn get_comments(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Comments>> {
+        if self.get_pull(&owner, &name, number).await?.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.get_issue_comments(&owner, &name, number).await?))
+    }
+
+    async fn get_status_checks(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Vec<StatusCheck>>> {
+        let Some(pull) = self.get_pull(&owner, &name, number).await? else {
+            return Ok(None);
+        };
+        let status = self
+            .get::<GiteaCombinedStatus>(
+                &format!("/repos/{owner}/{name}/commits/{}/status", pull.head.sha),
+                &[],
+            )
+            .await?;
+
+        Ok(Some(
+            status
+                .statuses
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| StatusCheck::StatusContext {
+                    id: s.id.to_string(),
+                    current: status_state(&s.status),
+                    state: s.status.to_uppercase(),
+                    description: s.description.filter(|d| !d.is_empty()),
+                    context: s.context,
+                    url: s.target_url.filter(|u| !u.is_empty()),
+                    app: s.creator.map(|c| c.login).unwrap_or_default(),
+                })
+                .collect(),
+        ))
+    }
+
+    async fn get_changed_files(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Vec<ChangedFile>> {
+        let files = self
+            .get::<Vec<GiteaChangedFile>>(
+                &format!("/repos/{owner}/{name}/pulls/{number}/files"),
+                &[],
+            )
+            .await?;
+
+        Ok(files
+            .into_iter()
+            .map(|f| ChangedFile {
+                path: f.filename,
+                additions: f.additions,
+                deletions: f.deletions,
+            })
+            .collect())
+    }
+
+    async fn get_diff(&self, owner: String, name: String, number: usize) -> anyhow::Result<String> {
+        self.request(
+            Method::GET,
+            &format!("/repos/{owner}/{name}/pulls/{number}.diff"),
+            &[],
+            None,
+        )
+        .await?
+        .text()
+        .await
+        .context("failed to get diff from response")
+    }
+
+    async fn get_reviews(&self, ids: Vec<String>) -> anyhow::Result<Vec<Review>> {
+        let reviews = futures::future::join_all(ids.iter().map(|id| async move {
+            let (owner, name, number) = split_reference(id)?;
+            self.get_review(owner, name, number).await
+        }))
+        .await;
+
+        Ok(reviews
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    // Nothing is cached, every fetch is fresh
+    fn invalidate_review(&self, _owner: String, _name: String, _number: usize) {}
+
+    async fn get_head_sha(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .get_pull(&owner, &name, number)
+            .await?
+            .map(|p| p.head.sha))
+    }
+
+    async fn get_commits(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Vec<Commit>> {
+        let commits = self
+            .get::<Vec<GiteaCommit>>(
+                &format!("/repos/{owner}/{name}/pulls/{number}/commits"),
+                &[],
+            )
+            .await?;
+
+        Ok(commits
+            .into_iter()
+            .map(|c| Commit {
+                short_oid: c.sha.chars().take(7).collect(),
+                oid: c.sha,
+                headline: c.commit.message.lines().next().unwrap_or_default().into(),
+                author: c.author.map(|a| a.login).unwrap_or(c.commit.author.name),
+                signature: c
+                    .commit
+                    .verification
+                    .filter(|v| !v.signature.is_empty())
+                    .map(|v| CommitSignature {
+                        kind: if v.signature.contains("SSH SIGNATURE") {
+                            "ssh".into()
+                        } else {
+                            "gpg".into()
+                        },
+                        valid: v.verified,
+                        signer: v.signer.map(|s| s.username).filter(|s| !s.is_empty()),
+                    }),
+            })
+            .collect())
+    }
+
+    async fn get_review_template(
+        &self,
+        owner: String,
+        name: String,
+    ) -> anyhow::Result<Option<String>> {
+        for path in TEMPLATE_PATHS {
+            let res = self
+                .fetch(
+                    Method::GET,
+                    &format!("/repos/{owner}/{name}/raw/{path}"),
+                    &[],
+                    None,
+                )
+                .await?;
+            if let Some(res) = res {
+                return Ok(Some(res.text().await?));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl GitComment for Gitea {
+    async fn add_comment(&self, subject_id: String, body: String) -> anyhow::Result<()> {
+        let (owner, name, number) = split_reference(&subject_id)?;
+
+        self.send(
+            Method::POST,
+            &format!("/repos/{owner}/{name}/issues/{number}/comments"),
+            Some(serde_json::json!({ "body": body })),
+        )
+        .await
+    }
+
+    async fn update_comment(&self, id: String, body: String) -> anyhow::Result<()> {
+        let (repository, id) = split_comment_id(&id)?;
+
+        self.send(
+            Method::PATCH,
+            &format!("/repos/{repository}/issues/comments/{id}"),
+            Some(serde_json::json!({ "body": body })),
+        )
+        .await
+    }
+
+    async fn delete_comment(&self, id: String) -> anyhow::Result<()> {
+        let (repository, id) = split_comment_id(&id)?;
+
+        self.send(
+            Method::DELETE,
+            &format!("/repos/{repository}/issues/comments/{id}"),
+            None,
+        )
+        .await
+    }
+
+    async fn reply_to_thread(&self, _thread_id: String, _body: String) -> anyhow::Result<()> {
+        anyhow::bail!("file comments aren't supported for gitea")
+    }
+
+    async fn resolve_thread(&self, _thread_id: String) -> anyhow::Result<()> {
+        anyhow::bail!("file comments aren't supported for gitea")
+    }
+}
+
+#[async_trait]
+impl GitReviewAction for Gitea {
+    async fn submit_review(
+        &self,
+        review_id: String,
+        event: ReviewEvent,
+        body: Option<String>,
+    ) -> anyhow::Result<()> {
+        let (owner, name, number) = split_reference(&review_id)?;
+        let event = match event {
+            ReviewEvent::Approve => "APPROVED",
+            ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+            ReviewEvent::Comment => "COMMENT",
+        };
+
+        self.send(
+            Method::POST,
+            &format!("/repos/{owner}/{name}/pulls/{number}/reviews"),
+            Some(serde_json::json!({ "event": event, "body": body.unwrap_or_default() })),
+        )
+        .await
+    }
+
+    async fn add_labels(
+        &self,
+        subject_id: String,
+        owner: String,
+        name: String,
+        labels: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let (_, _, number) = split_reference(&subject_id)?;
+        let label_ids = self.label_ids(&owner, &name, &labels).await?;
+
+        self.send(
+            Method::POST,
+            &format!("/repos/{owner}/{name}/issues/{number}/labels"),
+            Some(serde_json::json!({ "labels": label_ids })),
+        )
+        .await
+    }
+
+    async fn remove_labels(
+        &self,
+        subject_id: String,
+        owner: String,
+        name: String,
+        labels: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let (_, _, number) = split_reference(&subject_id)?;
+
+        for id in self.label_ids(&owner, &name, &labels).await? {
+            self.send(
+                Method::DELETE,
+                &format!("/repos/{owner}/{name}/issues/{number}/labels/{id}"),
+                None,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn enable_auto_merge(&self, review_id: String) -> anyhow::Result<()> {
+        let (owner, name, number) = split_reference(&review_id)?;
+
+        self.send(
+            Method::POST,
+            &format!("/repos/{owner}/{name}/pulls/{number}/merge"),
+            Some(serde_json::json!({ "Do": "merge", "merge_when_checks_succeed": true })),
+        )
+        .await
+    }
+
+    async fn merge_review(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+        strategy: MergeStrategy,
+    ) -> anyhow::Result<()> {
+        self.send(
+            Method::POST,
+            &format!("/repos/{owner}/{name}/pulls/{number}/merge"),
+            Some(serde_json::json!({ "Do": strategy.name() })),
+        )
+        .await
+    }
+
+    async fn create_review(
+        &self,
+        owner: String,
+        name: String,
+        review: NewReview,
+    ) -> anyhow::Result<usize> {
+        let base = match review.base {
+            Some(base) => base,
+            None => {
+                self.get::<GiteaRepository>(&format!("/repos/{owner}/{name}"), &[])
+                    .await?
+                    .default_branch
+            }
+        };
+        // Gitea marks drafts by the title, like its web ui
+        let title = if review.draft {
+            format!("WIP: {}", review.title)
+        } else {
+            review.title
+        };
+
+        let pull = self
+            .request(
+                Method::POST,
+                &format!("/repos/{owner}/{name}/pulls"),
+                &[],
+                Some(serde_json::json!({
+                    "head": review.head,
+                    "base": base,
+                    "title": title,
+                    "body": review.body,
+                })),
+            )
+            .await?
+            .json::<GiteaPull>()
+            .await
+            .context("failed to parse the created pull request")?;
+
+        Ok(pull.number)
+    }
+
+    async fn reassign_reviewer(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+        from: String,
+        to: String,
+    ) -> anyhow::Result<()> {
+        let path = format!("/repos/{owner}/{name}/pulls/{number}/requested_reviewers");
+
+        self.send(
+            Method::POST,
+            &path,
+            Some(serde_json::json!({ "reviewers": [to] })),
+        )
+        .await?;
+        self.send(
+            Method::DELETE,
+            &path,
+            Some(serde_json::json!({ "reviewers": [from] })),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl GitIssue for Gitea {
+    async fn search_issues(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        self.search_list("issues", SearchQuery::parse(query)?, cursor)
+            .await
+    }
+
+    async fn get_issue(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Issue>> {
+        let Some(issue) = self
+            .get_optional::<GiteaIssue>(&format!("/repos/{owner}/{name}/issues/{number}"))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let comments = self.get_issue_comments(&owner, &name, number).await?;
+
+        Ok(Some(Issue {
+            id: format!("{owner}/{name}#{number}"),
+            number,
+            repository: format!("{owner}/{name}"),
+            title: issue.title,
+            description: issue.body.unwrap_or_default(),
+            author: issue.user.login,
+            created_at: issue.created_at,
+            labels: issue.labels.into_iter().map(Label::from).collect(),
+            comments,
+        }))
+    }
+}
+
+#[async_trait]
+impl GitAdvisory for Gitea {
+    async fn get_vulnerabilities(
+        &self,
+        _ecosystem: Ecosystem,
+        _package: &str,
+    ) -> anyhow::Result<Vec<Vulnerability>> {
+        anyhow::bail!("gitea has no advisory database")
+    }
+}
+
+#[async_trait]
+impl GitAuth for Gitea {
+    async fn get_token_status(&self) -> anyhow::Result<TokenStatus> {
+        let res = self
+            .client
+            .get(format!("{}/user", self.api))
+            .send()
+            .await
+            .with_context(|| format!("failed to reach {}", self.api))?;
+
+        if res.status() == StatusCode::UNAUTHORIZED {
+            return Ok(TokenStatus::Rejected);
+        }
+        if !res.status().is_success() {
+            anyhow::bail!("{}/user answered with {}", self.api, res.status());
+        }
+        let user = res
+            .json::<GiteaUser>()
+            .await
+            .context("failed to parse the user")?;
+
+        // Gitea tokens have scopes, but doesn't tell which ones a token has
+        Ok(TokenStatus::Valid {
+            login: user.login,
+            scopes: None,
+        })
+    }
+}
+
+impl Provider for Gitea {
+    fn stats(&self) -> ProviderStats {
+        ProviderStats {
+            pending_requests: self.pending.load(Ordering::Relaxed),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::{split_comment_id, split_reference, Gitea, GiteaOptions, SearchQuery, PAGE_SIZE};
+    use crate::{
+        conformance::{self, Backend, Fixture, Reply, Request},
+        Provider,
+    };
+
+    struct GiteaBackend;
+
+    impl Backend for GiteaBackend {
+        const PAGE_SIZE: usize = PAGE_SIZE;
+
+        fn provider(base: &str) -> anyhow::Result<Arc<dyn Provider + Send + Sync>> {
+            Ok(Arc::new(Gitea::new(
+                GiteaOptions::new(base).with_token("fixture"),
+            )?))
+        }
+
+        fn respond(fixture: &Fixture, request: &Request) -> Reply {
+            let Some(("/api/v1/repos/issues/search", query)) = request.path.split_once('?') else {
+                return Reply {
+                    status: 404,
+                    body: r#"{"message":"not in the fixture"}"#.into(),
+                };
+            };
+            let param = |name: &str| {
+                query
+                    .split('&')
+                    .filter_map(|p| p.split_once('='))
+                    .find(|(n, _)| *n == name)
+                    .and_then(|(_, v)| v.parse::<usize>().ok())
+            };
+            let page = param("page").unwrap_or(1);
+            let limit = param("limit").unwrap_or(PAGE_SIZE);
+
+            let issues = fixture
+                .reviews
+                .iter()
+                .skip((page - 1) * limit)
+                .take(limit)
+                .map(|r| {
+                    serde_json::json!({
+                        "id": r.number,
+                        "number": r.number,
+                        "title": r.title,
+                        "body": "",
+                        "user": { "login": "octocat" },
+                        "labels": [],
+                        "created_at": "2023-10-12T08:14:03Z",
+                        "repository": { "owner": r.owner, "name": r.name },
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            Reply::ok(serde_json::Value::from(issues).to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conformance() -> anyhow::Result<()> {
+        conformance::run::<GiteaBackend>().await
+    }
+
+    #[test]
+    fn test_search_query() -> anyhow::Result<()> {
+        let search = SearchQuery::parse("is:pr review-requested:@me state:open org:acme fix")?;
+        assert!(search.review_requested);
+        assert_eq!(Some("acme".to_string()), search.owner);
+        assert_eq!(vec!["fix".to_string()], search.words);
+        assert!(search.params().contains(&("q", "fix".into())));
+
+        let search = SearchQuery::parse("is:issue label:rfc,design")?;
+        assert_eq!(vec!["rfc", "design"], search.labels);
+
+        assert!(SearchQuery::parse("is:pr repo:acme/rev").is_err());
+
+        assert_eq!(
+            ("acme".to_string(), "rev".to_string(), 12),
+            split_reference("acme/rev#12")?
+        );
+        assert_eq!(("acme/rev", "345"), split_comment_id("acme/rev:345")?);
+        assert!(split_comment_id("345").is_err());
+
+        Ok(())
+    }
+}
@@ -1,27 +1,55 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
 use anyhow::Context;
 use async_trait::async_trait;
-use graphql_client::{GraphQLQuery, Response};
+use graphql_client::{GraphQLQuery, QueryBody, Response};
 use reqwest::Client;
 use which::which;
 
 use crate::{
-    models::{Comment, Comments, CurrentState, Review, ReviewList, ReviewListItem, StatusCheck},
-    traits::{GitReview, GitUserReview},
+    auth,
+    cache::ResponseCache,
+    disk_cache::DiskCache,
+    models::{
+        ChangedFile, Comment, Comments, Commit, CommitSignature, CurrentState, Ecosystem, Issue,
+        Label, MergeStrategy, NewReview, Participant, ParticipantRole, ProviderStats, RateLimit,
+        RepositoryListItem, Review, ReviewDecision, ReviewEvent, ReviewList, ReviewListItem,
+        ReviewState, ReviewThread, StatusCheck, TokenStatus, Vulnerability,
+    },
+    retry,
+    traits::{
+        GitAdvisory, GitAuth, GitComment, GitIssue, GitReview, GitReviewAction, GitUserReview,
+    },
     Provider,
 };
 
 use self::graphql::{
-    pull_request::{
-        self, CheckConclusionState, CheckStatusState,
-        PullRequestRepositoryPullRequestCommitsNodesCommitStatusCheckRollupContextsNodes,
-    },
-    pull_requests, PullRequest, PullRequests,
+    add_comment, add_labels, delete_issue_comment, enable_auto_merge, issue, issues,
+    pull_request::{self, PullRequestReviewState},
+    pull_request_checks::{self, CheckConclusionState, CheckStatusState},
+    pull_request_comments, pull_request_commits, pull_request_files, pull_request_head,
+    pull_requests, pull_requests_by_ids, remove_labels, reply_to_thread, repository_labels,
+    repository_templates, resolve_thread, search_count, security_vulnerabilities, submit_review,
+    update_issue_comment, user_repositories, AddComment, AddLabels, DeleteIssueComment,
+    EnableAutoMerge, Issue as IssueQuery, Issues, PullRequest, PullRequestChecks,
+    PullRequestComments, PullRequestCommits, PullRequestFiles, PullRequestHead, PullRequests,
+    PullRequestsByIds, RemoveLabels, ReplyToThread, RepositoryLabels, RepositoryTemplates,
+    ResolveThread, SearchCount, SecurityVulnerabilities, SubmitReview, UpdateIssueComment,
+    UserRepositories,
 };
 
 pub mod graphql {
     use graphql_client::GraphQLQuery;
 
     pub type DateTime = chrono::DateTime<chrono::Utc>;
+    pub type GitObjectID = String;
+    pub type URI = String;
 
     #[derive(GraphQLQuery)]
     #[graphql(
@@ -46,79 +74,329 @@ pub mod graphql {
         response_derives = "Clone,Debug"
     )]
     pub struct PullRequest;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct PullRequestHead;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct RepositoryTemplates;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct PullRequestCommits;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct PullRequestComments;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct PullRequestChecks;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct PullRequestsByIds;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct UpdateIssueComment;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct DeleteIssueComment;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct ReplyToThread;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct ResolveThread;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct PullRequestFiles;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct SecurityVulnerabilities;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct SearchCount;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct Issues;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct Issue;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct AddComment;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct SubmitReview;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct EnableAutoMerge;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct AddLabels;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/query.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct RepositoryLabels;
+
+    #[derive(GraphQLQuery)]
+    #[graphql(
+        schema_path = "github/graphql/schema.graphql",
+        query_path = "github/graphql/mutation.graphql",
+        response_derives = "Clone,Debug"
+    )]
+    pub struct RemoveLabels;
 }
 
 pub struct Github {
     client: reqwest::Client,
     uri: String,
+    /// The rest api, for what the graphql api can't do such as diffs.
+    rest_uri: String,
+    cache: ResponseCache,
+    /// Responses of earlier sessions, shared with the refreshes running in the background.
+    disk: Option<Arc<DiskCache>>,
+    /// Requests sent which haven't been answered yet, counting the refreshes in the background.
+    pending: Arc<AtomicUsize>,
+    rate_limit: Arc<std::sync::Mutex<Option<RateLimit>>>,
+    #[cfg(feature = "cassette")]
+    cassette: Option<crate::cassette::Cassette>,
 }
 
+/// How many responses to keep around in memory for the session.
+const CACHE_CAPACITY: usize = 128;
+/// How long responses on disk are served without asking github again.
+const DISK_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
 pub struct GithubOptions {
     uri: String,
+    rest_uri: String,
+    /// The host whose token `gh` hands out, `github.com` unless set.
+    host: Option<String>,
     use_gh: bool,
+    /// Used instead of looking up the token of `gh` or `GITHUB_API_TOKEN`.
+    token: Option<String>,
+    /// Where `rev auth login` stores its token, used before the one of `gh`.
+    token_file: Option<std::path::PathBuf>,
+    disk_cache: Option<std::path::PathBuf>,
+    #[cfg(feature = "cassette")]
+    cassette: Option<std::path::PathBuf>,
 }
 
 impl Default for GithubOptions {
     fn default() -> Self {
         Self {
             uri: "https://api.github.com/graphql".into(),
+            rest_uri: "https://api.github.com".into(),
+            host: None,
             use_gh: true,
+            token: None,
+            token_file: None,
+            disk_cache: None,
+            #[cfg(feature = "cassette")]
+            cassette: None,
+        }
+    }
+}
+
+impl GithubOptions {
+    /// The graphql endpoint, e.g. `https://github.example.com/api/graphql` for github enterprise.
+    /// The rest api is found next to it, at `/api/v3` on github enterprise.
+    pub fn with_uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = uri.into();
+        self.rest_uri = match self.uri.strip_suffix("/api/graphql") {
+            Some(base) => format!("{base}/api/v3"),
+            None => self.uri.trim_end_matches("/graphql").to_string(),
+        };
+        self
+    }
+
+    /// Talks to the host, e.g. `github.example.com` for github enterprise, taking its token from
+    /// `gh` as well.
+    pub fn with_host(mut self, host: impl Into<String>) -> Self {
+        let host = host.into();
+        if host != GITHUB_HOST {
+            self.uri = format!("https://{host}/api/graphql");
+            self.rest_uri = format!("https://{host}/api/v3");
         }
+        self.host = Some(host);
+        self
+    }
+
+    /// Takes the token from `gh` for the host, when it's logged in to github enterprise under
+    /// another name than the one the api is reached at.
+    pub fn with_gh_host(mut self, host: impl Into<String>) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Uses the token stored at the path by `rev auth login`, when there is one.
+    pub fn with_token_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.token_file = Some(path.into());
+        self
+    }
+
+    /// Keeps responses in the directory between sessions, see [`DiskCache`].
+    pub fn with_disk_cache(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.disk_cache = Some(dir.into());
+        self
+    }
+}
+
+#[cfg(feature = "cassette")]
+impl GithubOptions {
+    /// Replays graphql responses from the cassette, or records them when `REV_CASSETTE_MODE` is
+    /// `record`.
+    pub fn cassette(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.cassette = Some(path.into());
+        self
     }
 }
 
 impl Github {
     pub fn new(options: GithubOptions) -> anyhow::Result<Self> {
-        let token = if options.use_gh {
-            let token = which("gh")
-                .ok()
-                .filter(|p| {
-                    if p.exists() {
-                        tracing::debug!("gh is on path");
-                        true
-                    } else {
-                        tracing::debug!("gh is not on path");
-                        false
-                    }
-                })
-                .and_then(|p| {
-                    let output = std::process::Command::new(p)
-                        .arg("auth")
-                        .arg("token")
-                        .output()
-                        .ok()
-                        .filter(|o| o.status.success())
-                        .and_then(|o| {
-                            let token = std::str::from_utf8(&o.stdout).ok().map(|s| s.to_string());
-                            if token.is_some() {
-                                tracing::trace!("found github token using gh");
-                            }
-                            token
-                        })
-                        .map(|s| s.trim().to_string());
+        #[cfg(feature = "cassette")]
+        let cassette = options
+            .cassette
+            .map(|path| crate::cassette::Cassette::load(path, crate::cassette::Mode::from_env()))
+            .transpose()?;
+        // Replaying never reaches github, so it works without credentials
+        #[cfg(feature = "cassette")]
+        let replaying = matches!(&cassette, Some(c) if c.mode() == crate::cassette::Mode::Replay);
+        #[cfg(not(feature = "cassette"))]
+        let replaying = false;
 
-                    output
-                });
-            token
+        let token = if options.token.is_some() {
+            options.token
+        } else if replaying {
+            None
+        } else if let Some(token) = options.token_file.as_deref().and_then(auth::read_token) {
+            tracing::trace!("found github token stored by rev auth login");
+            Some(token)
+        } else if options.use_gh {
+            gh_token(options.host.as_deref())
         } else {
             None
         };
+        let token = match token {
+            Some(token) => token,
+            None if replaying => "replay".into(),
+            None => {
+                tracing::debug!("falling back on GITHUB_API_TOKEN");
+                std::env::var("GITHUB_API_TOKEN")
+                    .ok()
+                    .filter(|t| !t.is_empty())
+                    .context(NO_TOKEN)?
+            }
+        };
 
         let client = Client::builder()
             .user_agent("graphql-rust/0.10.0")
             .default_headers(
                 std::iter::once((
                     reqwest::header::AUTHORIZATION,
-                    reqwest::header::HeaderValue::from_str(&format!(
-                        "Bearer {}",
-                        token.unwrap_or_else(|| {
-                            tracing::debug!("falling back on GITHUB_API_TOKEN");
-
-                            std::env::var("GITHUB_API_TOKEN")
-                                .context("GITHUB_API_TOKEN was not found")
-                                .unwrap()
-                        })
-                    ))?,
+                    reqwest::header::HeaderValue::from_str(&format!("Bearer {token}"))?,
                 ))
                 .collect(),
             )
@@ -127,98 +405,529 @@ impl Github {
         Ok(Self {
             client,
             uri: options.uri,
+            rest_uri: options.rest_uri,
+            cache: ResponseCache::new(CACHE_CAPACITY),
+            disk: options
+                .disk_cache
+                .map(|dir| Arc::new(DiskCache::new(dir, DISK_CACHE_TTL))),
+            pending: Arc::default(),
+            rate_limit: Arc::default(),
+            #[cfg(feature = "cassette")]
+            cassette,
         })
     }
-}
-
-struct AggregateGraphQLError {
-    errors: Vec<graphql_client::Error>,
-}
 
-impl std::fmt::Display for AggregateGraphQLError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "GitHub error: {:?}", self.errors)
+    /// Sends a query or mutation to the graphql endpoint, turning http and graphql errors into
+    /// a single error.
+    async fn execute<Q: GraphQLQuery>(
+        &self,
+        operation: &str,
+        variables: Q::Variables,
+    ) -> anyhow::Result<Q::ResponseData> {
+        self.execute_as::<Q, Q::ResponseData>(operation, variables)
+            .await
     }
-}
 
-impl std::fmt::Debug for AggregateGraphQLError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "GitHub error: {:?}", self.errors)
-    }
-}
+    /// Like [`Github::execute`], but parses the response into `T` instead of the generated
+    /// response type.
+    async fn execute_as<Q: GraphQLQuery, T: serde::de::DeserializeOwned>(
+        &self,
+        operation: &str,
+        variables: Q::Variables,
+    ) -> anyhow::Result<T> {
+        let query = Q::build_query(variables);
+        let body = self.send(&query).await?;
 
-impl std::error::Error for AggregateGraphQLError {}
+        parse_response::<T>(operation, &body)
+    }
 
-#[async_trait]
-impl GitUserReview for Github {
-    async fn get_user_reviews(
+    /// Like [`Github::execute`], but answers identical queries from the in-memory cache, or
+    /// the disk cache while its entry is fresh.
+    async fn execute_cached<Q: GraphQLQuery>(
         &self,
-        requested: Option<&str>,
-        org: Option<&str>,
-        tags: Option<Vec<String>>,
-    ) -> anyhow::Result<ReviewList> {
-        self.get_user_reviews_cursor(requested, org, tags, None)
-            .await
+        operation: &str,
+        variables: Q::Variables,
+    ) -> anyhow::Result<Q::ResponseData> {
+        let query = Q::build_query(variables);
+        let key = cache_key(&query)?;
+
+        if let Some(body) = self.cache.get(&key) {
+            tracing::trace!(operation, "graphql cache hit");
+            return parse_response::<Q::ResponseData>(operation, &body);
+        }
+        // Entries written before the query changed shape are fetched again
+        let stored = self.disk.as_ref().and_then(|disk| {
+            disk.get(&key)
+                .filter(|e| parse_response::<Q::ResponseData>(operation, &e.body).is_ok())
+        });
+        if let (Some(disk), Some(entry)) = (self.disk.as_ref(), stored.as_ref()) {
+            if disk.is_fresh(entry) {
+                tracing::trace!(operation, "graphql disk cache hit");
+                self.cache.insert(key, entry.body.clone());
+                return parse_response::<Q::ResponseData>(operation, &entry.body);
+            }
+        }
+
+        // A stale entry is sent along, github answers a 304 while it's still current
+        let (body, etag) = match self
+            .send_conditional(&query, stored.as_ref().and_then(|e| e.etag.as_deref()))
+            .await?
+        {
+            Posted::Body { body, etag } => (body, etag),
+            Posted::NotModified => {
+                let entry = stored.context("a stored response to be unmodified")?;
+                (entry.body, entry.etag)
+            }
+        };
+        let data = parse_response::<Q::ResponseData>(operation, &body)?;
+        if let Some(disk) = self.disk.as_ref() {
+            disk.insert(&key, &body, etag);
+        }
+        self.cache.insert(key, body);
+
+        Ok(data)
     }
 
-    async fn get_user_reviews_cursor(
+    /// Like [`Github::execute`], but answers from the disk cache however old its entry is, as
+    /// the first screen is better off showing what was there last session right away. A stale
+    /// entry is refreshed in the background, for the next time the query is sent.
+    async fn execute_revalidated<Q: GraphQLQuery>(
         &self,
-        requested: Option<&str>,
-        org: Option<&str>,
-        tags: Option<Vec<String>>,
-        cursor: Option<String>,
-    ) -> anyhow::Result<ReviewList> {
-        let review_requested = match requested {
-            Some(review) => match review.split_once('/') {
-                Some((org, squad)) => format!("team-review-requested:{}/{}", org, squad),
-                None => format!("review-requested:{}", review),
-            },
-            None => "review-requested:@me".into(),
+        operation: &str,
+        variables: Q::Variables,
+    ) -> anyhow::Result<Q::ResponseData> {
+        let query = Q::build_query(variables);
+        let key = cache_key(&query)?;
+        let Some(disk) = self.disk.clone() else {
+            return self
+                .execute_as::<Q, Q::ResponseData>(operation, query.variables)
+                .await;
         };
 
-        let query = format!(
-            "is:pr {} state:open {} {}",
-            review_requested,
-            org.map(|o| format!("org:{}", o)).unwrap_or("".into()),
-            tags.map(|tags| format!("label:{}", tags.join(",")))
-                .unwrap_or("".into())
-        );
+        if let Some(body) = self.cache.get(&key) {
+            return parse_response::<Q::ResponseData>(operation, &body);
+        }
+        if let Some(entry) = disk.get(&key) {
+            if let Ok(data) = parse_response::<Q::ResponseData>(operation, &entry.body) {
+                if disk.is_fresh(&entry) {
+                    self.cache.insert(key, entry.body);
+                } else {
+                    tracing::debug!(operation, "refreshing stale disk cache entry");
+                    let endpoint = self.endpoint();
+                    tokio::spawn(async move {
+                        if let Err(e) = refresh(&endpoint, &disk, &key, entry.etag).await {
+                            tracing::warn!("failed to refresh response cache: {e:#}");
+                        }
+                    });
+                }
+                return Ok(data);
+            }
+        }
 
-        let vars = pull_requests::Variables { cursor, query };
-        let query = PullRequests::build_query(vars);
+        let Posted::Body { body, etag } = self.send_conditional(&query, None).await? else {
+            anyhow::bail!("{operation} failed: github answered a 304 to an unconditional query");
+        };
+        let data = parse_response::<Q::ResponseData>(operation, &body)?;
+        disk.insert(&key, &body, etag);
+        self.cache.insert(key, body);
 
-        let res = self
-            .client
-            .post(&self.uri)
-            .json(&query)
-            .send()
-            .await
-            .context("github call graphql query failed")?;
+        Ok(data)
+    }
 
-        if !res.status().is_success() {
-            let error_body = res.text().await?;
-            tracing::error!("GraphQL Error: {}", error_body);
-            anyhow::bail!("failed to query graphql endpoint");
+    /// Forgets every cached response, after a change which may show up in any of them.
+    fn clear_cache(&self) {
+        self.cache.clear();
+        if let Some(disk) = self.disk.as_ref() {
+            disk.clear();
         }
+    }
 
-        let resp: Response<pull_requests::ResponseData> = res
-            .json()
-            .await
-            .context("failed to get json from response")?;
+    async fn send<V: serde::Serialize>(&self, query: &QueryBody<V>) -> anyhow::Result<String> {
+        match self.send_conditional(query, None).await? {
+            Posted::Body { body, .. } => Ok(body),
+            Posted::NotModified => anyhow::bail!("github answered a 304 to an unconditional query"),
+        }
+    }
+
+    /// Sends the query with `If-None-Match` when there's an ETag, so github can answer that the
+    /// response it belongs to is still current.
+    async fn send_conditional<V: serde::Serialize>(
+        &self,
+        query: &QueryBody<V>,
+        etag: Option<&str>,
+    ) -> anyhow::Result<Posted> {
+        // A mutation may have gone through before the server failed, so it's only sent once
+        let idempotent = !query.query.trim_start().starts_with("mutation");
+        let body = cache_key(query)?;
+
+        #[cfg(feature = "cassette")]
+        if let Some(cassette) = self.cassette.as_ref() {
+            let request =
+                serde_json::to_value(query).context("failed to serialize graphql query")?;
+            if let Some(body) = cassette.replay(&request)? {
+                return Ok(Posted::Body { body, etag: None });
+            }
 
-        if let Some(errors) = resp.errors {
-            let error = AggregateGraphQLError { errors };
-            anyhow::bail!("get_user_reviews failed with: {}", error);
+            let body = match self.endpoint().post(&body, idempotent, None).await? {
+                Posted::Body { body, .. } => body,
+                Posted::NotModified => unreachable!("an unconditional query is never unmodified"),
+            };
+            cassette.record(&request, &body)?;
+            return Ok(Posted::Body { body, etag: None });
         }
 
-        let prs = resp.data.context("data to be present")?.search;
+        self.endpoint().post(&body, idempotent, etag).await
+    }
 
-        let repos = prs
-            .nodes
-            .context("nodes to be present")?
+    /// The endpoint queries are posted to, handed to the refreshes running in the background.
+    fn endpoint(&self) -> Endpoint {
+        Endpoint {
+            client: self.client.clone(),
+            uri: self.uri.clone(),
+            pending: self.pending.clone(),
+            rate_limit: self.rate_limit.clone(),
+        }
+    }
+
+    /// The ids of the labels of the repository by name, ignoring case.
+    async fn label_ids(
+        &self,
+        owner: String,
+        name: String,
+        labels: &[String],
+    ) -> anyhow::Result<Vec<String>> {
+        let repository = format!("{owner}/{name}");
+        let known = self
+            .execute_cached::<RepositoryLabels>(
+                "get_labels",
+                repository_labels::Variables { owner, name },
+            )
+            .await?
+            .repository
+            .and_then(|r| r.labels)
+            .and_then(|l| l.nodes)
             .into_iter()
             .flatten()
-            .filter_map(|pr| match pr {
+            .flatten()
+            .collect::<Vec<_>>();
+
+        labels
+            .iter()
+            .map(|label| {
+                known
+                    .iter()
+                    .find(|l| l.name.eq_ignore_ascii_case(label))
+                    .map(|l| l.id.clone())
+                    .with_context(|| format!("{repository} has no label named {label}"))
+            })
+            .collect()
+    }
+}
+
+/// The host of github.com, as opposed to that of github enterprise.
+pub const GITHUB_HOST: &str = "github.com";
+
+const NO_TOKEN: &str =
+    "no github token found, run rev auth login or gh auth login, or set GITHUB_API_TOKEN";
+
+/// Where the token rev authenticates with comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    /// Set in the provider of rev.kdl.
+    Configured,
+    /// Stored by `rev auth login`.
+    Stored,
+    Gh,
+    Env,
+}
+
+impl TokenSource {
+    /// How the token was handed to rev, as shown by `rev doctor` and `rev auth status`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenSource::Configured => "rev.kdl",
+            TokenSource::Stored => "rev auth login",
+            TokenSource::Gh => "gh auth token",
+            TokenSource::Env => "GITHUB_API_TOKEN",
+        }
+    }
+}
+
+/// Finds the token [`Github::new`] would use with the options, preferring the one they're given
+/// over the one stored at their token file over the one of `gh` over `GITHUB_API_TOKEN`.
+pub fn token_source(options: &GithubOptions) -> Option<TokenSource> {
+    if options.token.is_some() {
+        return Some(TokenSource::Configured);
+    }
+    if options
+        .token_file
+        .as_deref()
+        .and_then(auth::read_token)
+        .is_some()
+    {
+        return Some(TokenSource::Stored);
+    }
+    if options.use_gh && gh_token(options.host.as_deref()).is_some() {
+        return Some(TokenSource::Gh);
+    }
+
+    std::env::var("GITHUB_API_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty())
+        .map(|_| TokenSource::Env)
+}
+
+/// The token of the user logged in to the host with `gh`, if it's installed.
+fn gh_token(host: Option<&str>) -> Option<String> {
+    let gh = which("gh").ok().filter(|p| p.exists());
+    if gh.is_none() {
+        tracing::debug!("gh is not on path");
+    }
+
+    let mut command = std::process::Command::new(gh?);
+    command.arg("auth").arg("token");
+    if let Some(host) = host {
+        command.arg("--hostname").arg(host);
+    }
+    let output = command.output().ok().filter(|o| o.status.success())?;
+    let token = std::str::from_utf8(&output.stdout).ok()?.trim().to_string();
+    tracing::trace!("found github token using gh");
+
+    Some(token).filter(|t| !t.is_empty())
+}
+
+/// Counts a request as pending until it's dropped, which also covers requests which are
+/// cancelled by dropping their future.
+pub(crate) struct PendingRequest<'a>(&'a AtomicUsize);
+
+impl<'a> PendingRequest<'a> {
+    pub(crate) fn start(pending: &'a AtomicUsize) -> Self {
+        pending.fetch_add(1, Ordering::Relaxed);
+        Self(pending)
+    }
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// The graphql endpoint, with the counters every request to it is tracked in.
+#[derive(Clone)]
+struct Endpoint {
+    client: Client,
+    uri: String,
+    pending: Arc<AtomicUsize>,
+    rate_limit: Arc<std::sync::Mutex<Option<RateLimit>>>,
+}
+
+/// The answer to a query.
+enum Posted {
+    Body {
+        body: String,
+        etag: Option<String>,
+    },
+    /// The response of the ETag sent along is still current.
+    NotModified,
+}
+
+impl Endpoint {
+    /// Posts the serialized query, see [`cache_key`]. Server errors are only retried when it's
+    /// `idempotent`, see [`retry::send`].
+    async fn post(
+        &self,
+        query: &str,
+        idempotent: bool,
+        etag: Option<&str>,
+    ) -> anyhow::Result<Posted> {
+        let _pending = PendingRequest::start(&self.pending);
+
+        let res = retry::send(
+            || {
+                let request = self
+                    .client
+                    .post(&self.uri)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(query.to_string());
+                match etag {
+                    Some(etag) => request.header(reqwest::header::IF_NONE_MATCH, etag),
+                    None => request,
+                }
+            },
+            idempotent,
+            &self.rate_limit,
+        )
+        .await
+        .context("github call graphql query failed")?;
+
+        if res.status() == reqwest::StatusCode::NOT_MODIFIED && etag.is_some() {
+            return Ok(Posted::NotModified);
+        }
+        if res.status() == reqwest::StatusCode::UNAUTHORIZED {
+            anyhow::bail!(
+                "github rejected the token, it may be expired or revoked, run `rev auth login` or `gh auth login`, or update GITHUB_API_TOKEN"
+            );
+        }
+        if !res.status().is_success() {
+            let error_body = res.text().await?;
+            tracing::error!("GraphQL Error: {}", error_body);
+            anyhow::bail!("failed to query graphql endpoint");
+        }
+
+        let etag = res
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = res
+            .text()
+            .await
+            .context("failed to get body from response")?;
+
+        Ok(Posted::Body { body, etag })
+    }
+}
+
+/// Sends the query again and stores the response on disk, the key being the serialized query.
+/// An unchanged response is only marked as fresh again.
+async fn refresh(
+    endpoint: &Endpoint,
+    disk: &DiskCache,
+    key: &str,
+    etag: Option<String>,
+) -> anyhow::Result<()> {
+    // Only queries are cached, so it's safe to send again
+    match endpoint.post(key, true, etag.as_deref()).await? {
+        Posted::Body { body, etag } => disk.insert(key, &body, etag),
+        Posted::NotModified => {
+            let entry = disk
+                .get(key)
+                .context("the refreshed response to be stored")?;
+            disk.insert(key, &entry.body, etag);
+        }
+    }
+
+    Ok(())
+}
+
+fn cache_key<V: serde::Serialize>(query: &QueryBody<V>) -> anyhow::Result<String> {
+    serde_json::to_string(query).context("failed to serialize graphql query")
+}
+
+fn parse_response<T: serde::de::DeserializeOwned>(
+    operation: &str,
+    body: &str,
+) -> anyhow::Result<T> {
+    let resp: Response<T> =
+        serde_json::from_str(body).context("failed to get json from response")?;
+
+    if let Some(errors) = resp.errors {
+        if let Some(scopes) = errors.iter().find_map(|e| missing_scopes(&e.message)) {
+            anyhow::bail!(
+                "{operation} failed: the token is missing the {} scope, run `gh auth refresh -s {}`",
+                scopes.join(" or "),
+                scopes.join(",")
+            );
+        }
+        let error = AggregateGraphQLError { errors };
+        anyhow::bail!("{} failed with: {}", operation, error);
+    }
+
+    resp.data.context("data to be present")
+}
+
+/// The scopes github asks for when the token lacks them, read from errors such as `The 'login'
+/// field requires one of the following scopes: ['read:org'], but your token has only been granted
+/// the: ['repo'] scopes.`
+fn missing_scopes(message: &str) -> Option<Vec<String>> {
+    let (_, rest) = message.split_once("requires one of the following scopes: [")?;
+    let (scopes, _) = rest.split_once(']')?;
+
+    let scopes = scopes
+        .split(',')
+        .map(|s| s.trim().trim_matches(['\'', '"']).to_string())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    (!scopes.is_empty()).then_some(scopes)
+}
+
+struct AggregateGraphQLError {
+    errors: Vec<graphql_client::Error>,
+}
+
+impl std::fmt::Display for AggregateGraphQLError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GitHub error: {:?}", self.errors)
+    }
+}
+
+impl std::fmt::Debug for AggregateGraphQLError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GitHub error: {:?}", self.errors)
+    }
+}
+
+impl std::error::Error for AggregateGraphQLError {}
+
+#[async_trait]
+impl GitUserReview for Github {
+    async fn get_user_reviews(
+        &self,
+        requested: Option<&str>,
+        org: Option<&str>,
+        tags: Option<Vec<String>>,
+    ) -> anyhow::Result<ReviewList> {
+        self.get_user_reviews_cursor(requested, org, tags, None)
+            .await
+    }
+
+    async fn get_user_reviews_cursor(
+        &self,
+        requested: Option<&str>,
+        org: Option<&str>,
+        tags: Option<Vec<String>>,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        let review_requested = match requested {
+            Some(review) => match review.split_once('/') {
+                Some((org, squad)) => format!("team-review-requested:{}/{}", org, squad),
+                None => format!("review-requested:{}", review),
+            },
+            None => "review-requested:@me".into(),
+        };
+
+        let query = format!(
+            "is:pr {} state:open {} {}",
+            review_requested,
+            org.map(|o| format!("org:{}", o)).unwrap_or("".into()),
+            tags.map(|tags| format!("label:{}", tags.join(",")))
+                .unwrap_or("".into())
+        );
+
+        self.search_reviews(&query, cursor).await
+    }
+
+    async fn search_reviews(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        let vars = pull_requests::Variables {
+            cursor,
+            query: query.to_string(),
+        };
+        let prs = self
+            .execute_revalidated::<PullRequests>("get_user_reviews", vars)
+            .await?
+            .search;
+
+        let repos = prs
+            .nodes
+            .context("nodes to be present")?
+            .into_iter()
+            .flatten()
+            .filter_map(|pr| match pr {
                 pull_requests::PullRequestsSearchNodes::PullRequest(pr) => Some(pr),
                 _ => None,
             })
@@ -226,193 +935,1459 @@ impl GitUserReview for Github {
                 id: pr.id,
                 name: pr.repository.name,
                 title: pr.title,
+                url: pr.url,
                 owner: pr.repository.owner.login,
                 date: pr.created_at,
                 number: pr.number as usize,
+                author: pr.author.map(|a| a.login).unwrap_or("ghost".to_string()),
+                labels: pr
+                    .labels
+                    .and_then(|l| l.nodes)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|l| Label {
+                        name: l.name,
+                        color: Some(l.color),
+                        description: l.description,
+                    })
+                    .collect(),
+                additions: pr.additions as usize,
+                deletions: pr.deletions as usize,
+                changed_files: pr.changed_files as usize,
+                approvals: pr
+                    .latest_opinionated_reviews
+                    .and_then(|r| r.nodes)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .filter(|r| matches!(r.state, pull_requests::PullRequestReviewState::APPROVED))
+                    .count(),
+                checks: pr
+                    .commits
+                    .nodes
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .last()
+                    .and_then(|c| c.commit.status_check_rollup)
+                    .map(|r| match r.state {
+                        pull_requests::StatusState::SUCCESS => CurrentState::Success,
+                        pull_requests::StatusState::ERROR | pull_requests::StatusState::FAILURE => {
+                            CurrentState::Failure
+                        }
+                        pull_requests::StatusState::EXPECTED
+                        | pull_requests::StatusState::PENDING
+                        | pull_requests::StatusState::Other(_) => CurrentState::Pending,
+                    }),
+                review_decision: pr.review_decision.and_then(|d| match d {
+                    pull_requests::PullRequestReviewDecision::APPROVED => {
+                        Some(ReviewDecision::Approved)
+                    }
+                    pull_requests::PullRequestReviewDecision::CHANGES_REQUESTED => {
+                        Some(ReviewDecision::ChangesRequested)
+                    }
+                    pull_requests::PullRequestReviewDecision::REVIEW_REQUIRED => {
+                        Some(ReviewDecision::ReviewRequired)
+                    }
+                    pull_requests::PullRequestReviewDecision::Other(_) => None,
+                }),
+                draft: pr.is_draft,
             })
             .collect::<Vec<_>>();
 
-        Ok(ReviewList {
-            items: repos,
-            last_cursor: prs.page_info.end_cursor,
-            has_more: prs.page_info.has_next_page,
-        })
+        Ok(ReviewList {
+            items: repos,
+            last_cursor: prs.page_info.end_cursor,
+            has_more: prs.page_info.has_next_page,
+        })
+    }
+
+    async fn count_search(&self, query: &str) -> anyhow::Result<usize> {
+        let vars = search_count::Variables {
+            query: query.to_string(),
+        };
+        let search = self
+            .execute::<SearchCount>("count_search", vars)
+            .await?
+            .search;
+
+        Ok(search.issue_count as usize)
+    }
+
+    async fn get_repositories(&self, owner: &str) -> anyhow::Result<Vec<RepositoryListItem>> {
+        let mut repositories = Vec::new();
+        let mut cursor = None;
+        loop {
+            let vars = user_repositories::Variables {
+                owner: owner.to_string(),
+                cursor,
+            };
+            let page = self
+                .execute_revalidated::<UserRepositories>("get_repositories", vars)
+                .await?
+                .repository_owner
+                .with_context(|| format!("{owner} is not a user or organization"))?
+                .repositories;
+
+            repositories.extend(page.nodes.into_iter().flatten().flatten().map(|r| {
+                RepositoryListItem {
+                    owner: r.owner.login,
+                    name: r.name,
+                    description: r.description.filter(|d| !d.is_empty()),
+                    url: r.url,
+                    open_reviews: r.pull_requests.total_count as usize,
+                    pushed_at: r.pushed_at,
+                }
+            }));
+
+            if !page.page_info.has_next_page {
+                return Ok(repositories);
+            }
+            cursor = page.page_info.end_cursor;
+        }
+    }
+}
+
+/// The response of [`PullRequestsByIds`], parsed into the same fragment type as a single
+/// [`PullRequest`] so both share the mapping into a [`Review`].
+#[derive(serde::Deserialize)]
+struct ReviewNodes {
+    nodes: Vec<Option<ReviewNode>>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "__typename")]
+enum ReviewNode {
+    PullRequest(Box<pull_request::ReviewDetails>),
+    #[serde(other)]
+    Other,
+}
+
+fn review(pr: pull_request::ReviewDetails) -> Review {
+    let author = pr
+        .author
+        .as_ref()
+        .map(|a| a.login.clone())
+        .unwrap_or("ghost".to_string());
+    let participants = participants(&pr, &author);
+
+    Review {
+        id: pr.id,
+        number: pr.number as usize,
+        repository: pr.repository.name_with_owner,
+        title: pr.title,
+        url: pr.url,
+        description: pr.body_text,
+        head_sha: pr.head_ref_oid,
+        head_branch: pr.head_ref_name,
+        author,
+        participants,
+        publish_at: pr.published_at,
+        additions: pr.additions as usize,
+        deletions: pr.deletions as usize,
+        changed_files: pr.changed_files as usize,
+        labels: pr
+            .labels
+            .into_iter()
+            .filter_map(|l| l.nodes)
+            .flat_map(|n| {
+                n.iter()
+                    .flatten()
+                    .map(|n| Label {
+                        name: n.name.clone(),
+                        color: Some(n.color.clone()),
+                        description: n.description.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        comments: None,
+        status_checks: None,
+    }
+}
+
+fn comments(
+    comments: pull_request_comments::PullRequestCommentsRepositoryPullRequestComments,
+    threads: pull_request_comments::PullRequestCommentsRepositoryPullRequestReviewThreads,
+) -> Comments {
+    Comments {
+        has_previous: comments.page_info.has_previous_page,
+        threads: threads
+            .nodes
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|t| {
+                let thread_comments = t
+                    .comments
+                    .nodes
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                ReviewThread {
+                    id: t.id,
+                    path: t.path,
+                    line: t.line.or(t.original_line).map(|l| l as usize),
+                    diff_hunk: thread_comments
+                        .first()
+                        .map(|c| c.diff_hunk.clone())
+                        .unwrap_or_default(),
+                    resolved: t.is_resolved,
+                    outdated: t.is_outdated,
+                    comments: thread_comments
+                        .into_iter()
+                        .map(|n| Comment {
+                            id: n.id,
+                            author: n.author.map(|a| a.login).unwrap_or("ghost".to_string()),
+                            text: n.body_text,
+                            body: n.body,
+                            url: n.url,
+                            last_edited_at: n.last_edited_at,
+                        })
+                        .collect(),
+                }
+            })
+            .collect(),
+        comments: comments
+            .nodes
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|n| Comment {
+                id: n.id,
+                author: n.author.map(|a| a.login).unwrap_or("ghost".to_string()),
+                text: n.body_text,
+                body: n.body,
+                url: n.url,
+                last_edited_at: n.last_edited_at,
+            })
+            .collect(),
+    }
+}
+
+fn status_checks(
+    commits: pull_request_checks::PullRequestChecksRepositoryPullRequestCommits,
+) -> Vec<StatusCheck> {
+    commits
+        .nodes
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|n| n.commit.status_check_rollup)
+        .filter_map(|n| n.contexts.nodes)
+        .flatten()
+        .flatten()
+        .map(|c| match c {
+            StatusChecks::CheckRun(c) => StatusCheck::CheckRun {
+                id: c.id,
+                name: c.name,
+                url: c.details_url,
+                app: c
+                    .check_suite
+                    .app
+                    .map(|a| a.name)
+                    .unwrap_or("checks".to_string()),
+                current: c
+                    .conclusion
+                    .as_ref()
+                    .map(|c| match c {
+                        CheckConclusionState::ACTION_REQUIRED => CurrentState::Pending,
+                        CheckConclusionState::CANCELLED => CurrentState::Failure,
+                        CheckConclusionState::FAILURE => CurrentState::Failure,
+                        CheckConclusionState::NEUTRAL => CurrentState::Pending,
+                        CheckConclusionState::SKIPPED => CurrentState::Success,
+                        CheckConclusionState::STALE => CurrentState::Expired,
+                        CheckConclusionState::STARTUP_FAILURE => CurrentState::Failure,
+                        CheckConclusionState::SUCCESS => CurrentState::Success,
+                        CheckConclusionState::TIMED_OUT => CurrentState::Failure,
+                        CheckConclusionState::Other(_) => CurrentState::Pending,
+                    })
+                    .unwrap_or(CurrentState::Pending),
+                status: {
+                    let status_name = match c.status {
+                        CheckStatusState::COMPLETED => "completed",
+                        CheckStatusState::IN_PROGRESS => "in progress",
+                        CheckStatusState::PENDING => "pending",
+                        CheckStatusState::QUEUED => "queued",
+                        CheckStatusState::REQUESTED => "requested",
+                        CheckStatusState::WAITING => "waiting",
+                        CheckStatusState::Other(ref e) => e,
+                    };
+                    status_name.to_string()
+                },
+                conclusion: c
+                    .conclusion
+                    .map(|c| {
+                        let conclusion = match c {
+                            CheckConclusionState::ACTION_REQUIRED => "action required",
+                            CheckConclusionState::CANCELLED => "cancelled",
+                            CheckConclusionState::FAILURE => "failure",
+                            CheckConclusionState::NEUTRAL => "neutral",
+                            CheckConclusionState::SKIPPED => "skipped",
+                            CheckConclusionState::STALE => "stale",
+                            CheckConclusionState::STARTUP_FAILURE => "startup failure",
+                            CheckConclusionState::SUCCESS => "success",
+                            CheckConclusionState::TIMED_OUT => "timed out",
+                            CheckConclusionState::Other(ref o) => o,
+                        };
+                        conclusion.to_string()
+                    })
+                    .unwrap_or("unknown".to_string()),
+            },
+            StatusChecks::StatusContext(sc) => StatusCheck::StatusContext {
+                id: sc.id,
+                current: match sc.state {
+                    pull_request_checks::StatusState::ERROR => CurrentState::Failure,
+                    pull_request_checks::StatusState::EXPECTED => CurrentState::Pending,
+                    pull_request_checks::StatusState::FAILURE => CurrentState::Failure,
+                    pull_request_checks::StatusState::PENDING => CurrentState::Pending,
+                    pull_request_checks::StatusState::SUCCESS => CurrentState::Success,
+                    pull_request_checks::StatusState::Other(_) => CurrentState::Pending,
+                },
+                state: match sc.state {
+                    pull_request_checks::StatusState::ERROR => "error",
+                    pull_request_checks::StatusState::EXPECTED => "expected",
+                    pull_request_checks::StatusState::FAILURE => "failure",
+                    pull_request_checks::StatusState::PENDING => "pending",
+                    pull_request_checks::StatusState::SUCCESS => "succeess",
+                    pull_request_checks::StatusState::Other(ref o) => o,
+                }
+                .to_string(),
+                description: sc.description,
+                context: sc.context,
+                url: sc.target_url,
+                app: sc
+                    .creator
+                    .map(|c| c.login)
+                    .unwrap_or("statuses".to_string()),
+            },
+        })
+        .collect()
+}
+
+fn commit(
+    commit: pull_request_commits::PullRequestCommitsRepositoryPullRequestCommitsNodesCommit,
+) -> Commit {
+    use pull_request_commits::PullRequestCommitsRepositoryPullRequestCommitsNodesCommitSignatureOn as Kind;
+
+    let author = commit
+        .author
+        .map(|a| a.user.map(|u| u.login).or(a.name).unwrap_or_default());
+
+    Commit {
+        oid: commit.oid,
+        short_oid: commit.abbreviated_oid,
+        headline: commit.message_headline,
+        author: author.unwrap_or_default(),
+        signature: commit.signature.map(|s| CommitSignature {
+            kind: match s.on {
+                Kind::GpgSignature => "gpg",
+                Kind::SshSignature => "ssh",
+                Kind::SmimeSignature => "s/mime",
+                Kind::UnknownSignature => "unknown",
+            }
+            .into(),
+            valid: s.is_valid,
+            signer: s.signer.map(|s| s.login),
+        }),
+    }
+}
+
+fn participants(pr: &pull_request::ReviewDetails, author: &str) -> Vec<Participant> {
+    use pull_request::ReviewDetailsReviewRequestsNodesRequestedReviewer as Requested;
+
+    let requested = pr
+        .review_requests
+        .iter()
+        .filter_map(|r| r.nodes.as_ref())
+        .flatten()
+        .flatten()
+        .filter_map(|n| match n.requested_reviewer.as_ref()? {
+            Requested::User(u) => Some(u.login.clone()),
+            Requested::Team(t) => Some(t.slug.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let reviews = pr
+        .latest_reviews
+        .iter()
+        .filter_map(|r| r.nodes.as_ref())
+        .flatten()
+        .flatten()
+        .filter_map(|n| {
+            let state = match n.state {
+                PullRequestReviewState::APPROVED => ReviewState::Approved,
+                PullRequestReviewState::CHANGES_REQUESTED => ReviewState::ChangesRequested,
+                PullRequestReviewState::COMMENTED => ReviewState::Commented,
+                PullRequestReviewState::DISMISSED => ReviewState::Dismissed,
+                PullRequestReviewState::PENDING => ReviewState::Pending,
+                PullRequestReviewState::Other(_) => ReviewState::Pending,
+            };
+            Some((n.author.as_ref()?.login.clone(), state))
+        })
+        .collect::<Vec<_>>();
+
+    let mut participants = pr
+        .participants
+        .nodes
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|p| {
+            let review = reviews
+                .iter()
+                .find(|(login, _)| login == &p.login)
+                .map(|(_, state)| state.clone());
+            let requested = requested.contains(&p.login);
+
+            Participant {
+                login: p.login.clone(),
+                name: p.name.clone(),
+                role: if p.login == author {
+                    ParticipantRole::Author
+                } else if review.is_some() || requested {
+                    ParticipantRole::Reviewer
+                } else {
+                    ParticipantRole::Commenter
+                },
+                requested,
+                review,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Requested reviewers and teams don't show up as participants until they've interacted
+    for login in requested {
+        if !participants.iter().any(|p| p.login == login) {
+            participants.push(Participant {
+                login,
+                name: None,
+                role: ParticipantRole::Reviewer,
+                requested: true,
+                review: None,
+            });
+        }
+    }
+
+    participants
+}
+
+type StatusChecks =
+    pull_request_checks::PullRequestChecksRepositoryPullRequestCommitsNodesCommitStatusCheckRollupContextsNodes;
+
+#[async_trait]
+impl GitReview for Github {
+    async fn get_review(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Review>> {
+        let vars = pull_request::Variables {
+            owner,
+            name,
+            number: number as i64,
+        };
+        let repository = self
+            .execute_cached::<PullRequest>("get_review", vars)
+            .await?
+            .repository;
+        let repository = match repository {
+            Some(pr) => pr,
+            None => return Ok(None),
+        };
+
+        let pr = match repository.pull_request {
+            Some(pr) => pr,
+            None => return Ok(None),
+        };
+
+        Ok(Some(review(pr)))
+    }
+
+    async fn get_reviews(&self, ids: Vec<String>) -> anyhow::Result<Vec<Review>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let nodes = self
+            .execute_as::<PullRequestsByIds, ReviewNodes>(
+                "get_reviews",
+                pull_requests_by_ids::Variables { ids },
+            )
+            .await?
+            .nodes;
+
+        Ok(nodes
+            .into_iter()
+            .flatten()
+            .filter_map(|n| match n {
+                ReviewNode::PullRequest(pr) => Some(review(*pr)),
+                ReviewNode::Other => None,
+            })
+            .collect())
+    }
+
+    async fn get_comments(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Comments>> {
+        let vars = pull_request_comments::Variables {
+            owner,
+            name,
+            number: number as i64,
+        };
+
+        Ok(self
+            .execute_cached::<PullRequestComments>("get_comments", vars)
+            .await?
+            .repository
+            .and_then(|r| r.pull_request)
+            .map(|pr| comments(pr.comments, pr.review_threads)))
+    }
+
+    async fn get_status_checks(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Vec<StatusCheck>>> {
+        let vars = pull_request_checks::Variables {
+            owner,
+            name,
+            number: number as i64,
+        };
+
+        // Checks are polled while they run, so they are never served from the cache
+        Ok(self
+            .execute::<PullRequestChecks>("get_status_checks", vars)
+            .await?
+            .repository
+            .and_then(|r| r.pull_request)
+            .map(|pr| status_checks(pr.commits)))
+    }
+
+    async fn get_changed_files(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Vec<ChangedFile>> {
+        let vars = pull_request_files::Variables {
+            owner,
+            name,
+            number: number as i64,
+        };
+
+        Ok(self
+            .execute_cached::<PullRequestFiles>("get_changed_files", vars)
+            .await?
+            .repository
+            .and_then(|r| r.pull_request)
+            .and_then(|pr| pr.files)
+            .and_then(|f| f.nodes)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|f| ChangedFile {
+                path: f.path,
+                additions: f.additions as usize,
+                deletions: f.deletions as usize,
+            })
+            .collect())
+    }
+
+    async fn get_diff(&self, owner: String, name: String, number: usize) -> anyhow::Result<String> {
+        // The graphql api has no diffs, so this goes through the rest api
+        let url = format!("{}/repos/{owner}/{name}/pulls/{number}", self.rest_uri);
+        let _pending = PendingRequest::start(&self.pending);
+
+        // An unchanged diff is answered with a 304, which doesn't count against the rate limit
+        let cached = self.disk.as_ref().and_then(|disk| disk.get(&url));
+        let mut request = self
+            .client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github.diff");
+        if let Some(etag) = cached.as_ref().and_then(|e| e.etag.as_deref()) {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+
+        let res = request
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch the diff of {owner}/{name}#{number}"))?;
+        if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let (Some(disk), Some(entry)) = (self.disk.as_ref(), cached) {
+                disk.insert(&url, &entry.body, entry.etag);
+                return Ok(entry.body);
+            }
+        }
+        if !res.status().is_success() {
+            anyhow::bail!(
+                "failed to fetch the diff of {owner}/{name}#{number}: {}",
+                res.status()
+            );
+        }
+
+        let etag = res
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let diff = res
+            .text()
+            .await
+            .context("failed to get diff from response")?;
+        if let (Some(disk), Some(etag)) = (self.disk.as_ref(), etag) {
+            disk.insert(&url, &diff, Some(etag));
+        }
+
+        Ok(diff)
+    }
+
+    fn invalidate_review(&self, owner: String, name: String, number: usize) {
+        let review = PullRequest::build_query(pull_request::Variables {
+            owner: owner.clone(),
+            name: name.clone(),
+            number: number as i64,
+        });
+        let comments = PullRequestComments::build_query(pull_request_comments::Variables {
+            owner,
+            name,
+            number: number as i64,
+        });
+
+        for key in [cache_key(&review), cache_key(&comments)]
+            .into_iter()
+            .flatten()
+        {
+            self.cache.remove(&key);
+            if let Some(disk) = self.disk.as_ref() {
+                disk.remove(&key);
+            }
+        }
     }
-}
 
-type StatusChecks =
-    PullRequestRepositoryPullRequestCommitsNodesCommitStatusCheckRollupContextsNodes;
+    async fn get_head_sha(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<String>> {
+        let vars = pull_request_head::Variables {
+            owner,
+            name,
+            number: number as i64,
+        };
 
-#[async_trait]
-impl GitReview for Github {
-    async fn get_review(
+        Ok(self
+            .execute::<PullRequestHead>("get_head_sha", vars)
+            .await?
+            .repository
+            .and_then(|r| r.pull_request)
+            .map(|pr| pr.head_ref_oid))
+    }
+
+    async fn get_commits(
         &self,
         owner: String,
         name: String,
         number: usize,
-    ) -> anyhow::Result<Option<Review>> {
-        let vars = pull_request::Variables {
+    ) -> anyhow::Result<Vec<Commit>> {
+        let vars = pull_request_commits::Variables {
             owner,
             name,
             number: number as i64,
         };
-        let query = PullRequest::build_query(vars);
+
+        // Pushes don't change the variables, so the commits are never served from the cache
+        Ok(self
+            .execute::<PullRequestCommits>("get_commits", vars)
+            .await?
+            .repository
+            .and_then(|r| r.pull_request)
+            .and_then(|pr| pr.commits.nodes)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|c| commit(c.commit))
+            .collect())
+    }
+
+    async fn get_review_template(
+        &self,
+        owner: String,
+        name: String,
+    ) -> anyhow::Result<Option<String>> {
+        let vars = repository_templates::Variables { owner, name };
+
+        let templates = self
+            .execute_cached::<RepositoryTemplates>("get_review_template", vars)
+            .await?
+            .repository
+            .and_then(|r| r.pull_request_templates)
+            .unwrap_or_default();
+
+        // Prefer the default template over the ones only picked through a query parameter
+        let default = templates.iter().position(|t| {
+            t.filename
+                .as_deref()
+                .map(|f| f.to_lowercase().ends_with("pull_request_template.md"))
+                .unwrap_or_default()
+        });
+
+        Ok(templates
+            .into_iter()
+            .nth(default.unwrap_or_default())
+            .and_then(|t| t.body)
+            .filter(|b| !b.trim().is_empty()))
+    }
+}
+
+#[async_trait]
+impl GitComment for Github {
+    async fn add_comment(&self, subject_id: String, body: String) -> anyhow::Result<()> {
+        self.execute::<AddComment>("add_comment", add_comment::Variables { subject_id, body })
+            .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn update_comment(&self, id: String, body: String) -> anyhow::Result<()> {
+        self.execute::<UpdateIssueComment>(
+            "update_comment",
+            update_issue_comment::Variables { id, body },
+        )
+        .await?;
+        // We don't know which review the comment belongs to, so every cached review may be stale
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn delete_comment(&self, id: String) -> anyhow::Result<()> {
+        self.execute::<DeleteIssueComment>(
+            "delete_comment",
+            delete_issue_comment::Variables { id },
+        )
+        .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn reply_to_thread(&self, thread_id: String, body: String) -> anyhow::Result<()> {
+        self.execute::<ReplyToThread>(
+            "reply_to_thread",
+            reply_to_thread::Variables { thread_id, body },
+        )
+        .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn resolve_thread(&self, thread_id: String) -> anyhow::Result<()> {
+        self.execute::<ResolveThread>("resolve_thread", resolve_thread::Variables { thread_id })
+            .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GitReviewAction for Github {
+    async fn submit_review(
+        &self,
+        review_id: String,
+        event: ReviewEvent,
+        body: Option<String>,
+    ) -> anyhow::Result<()> {
+        let event = match event {
+            ReviewEvent::Approve => submit_review::PullRequestReviewEvent::APPROVE,
+            ReviewEvent::RequestChanges => submit_review::PullRequestReviewEvent::REQUEST_CHANGES,
+            ReviewEvent::Comment => submit_review::PullRequestReviewEvent::COMMENT,
+        };
+
+        self.execute::<SubmitReview>(
+            "submit_review",
+            submit_review::Variables {
+                pull_request_id: review_id,
+                event,
+                body,
+            },
+        )
+        .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn add_labels(
+        &self,
+        subject_id: String,
+        owner: String,
+        name: String,
+        labels: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let label_ids = self.label_ids(owner, name, &labels).await?;
+
+        self.execute::<AddLabels>(
+            "add_labels",
+            add_labels::Variables {
+                labelable_id: subject_id,
+                label_ids,
+            },
+        )
+        .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn remove_labels(
+        &self,
+        subject_id: String,
+        owner: String,
+        name: String,
+        labels: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let label_ids = self.label_ids(owner, name, &labels).await?;
+
+        self.execute::<RemoveLabels>(
+            "remove_labels",
+            remove_labels::Variables {
+                labelable_id: subject_id,
+                label_ids,
+            },
+        )
+        .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn enable_auto_merge(&self, review_id: String) -> anyhow::Result<()> {
+        self.execute::<EnableAutoMerge>(
+            "enable_auto_merge",
+            enable_auto_merge::Variables {
+                pull_request_id: review_id,
+            },
+        )
+        .await?;
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    async fn merge_review(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+        strategy: MergeStrategy,
+    ) -> anyhow::Result<()> {
+        // The rest api merges by number, the graphql mutation would need the node id
+        let url = format!(
+            "{}/repos/{owner}/{name}/pulls/{number}/merge",
+            self.rest_uri
+        );
+        let _pending = PendingRequest::start(&self.pending);
 
         let res = self
             .client
-            .post(&self.uri)
-            .json(&query)
+            .put(&url)
+            .json(&serde_json::json!({ "merge_method": strategy.name() }))
             .send()
             .await
-            .context("github call graphql query failed")?;
-
+            .with_context(|| format!("failed to merge {owner}/{name}#{number}"))?;
         if !res.status().is_success() {
-            let error_body = res.text().await?;
-            tracing::error!("GraphQL Error: {}", error_body);
-            anyhow::bail!("failed to query graphql endpoint");
+            let status = res.status();
+            // Github tells why, e.g. that the review isn't approved or checks are pending
+            let message = res
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|b| b.get("message")?.as_str().map(|m| m.to_string()))
+                .unwrap_or_else(|| status.to_string());
+            anyhow::bail!("failed to merge {owner}/{name}#{number}: {message}");
         }
+        self.invalidate_review(owner, name, number);
 
-        let resp: Response<pull_request::ResponseData> = res
-            .json()
-            .await
-            .context("failed to get json from response")?;
+        Ok(())
+    }
+
+    async fn create_review(
+        &self,
+        owner: String,
+        name: String,
+        review: NewReview,
+    ) -> anyhow::Result<usize> {
+        let api = &self.rest_uri;
+        let _pending = PendingRequest::start(&self.pending);
+
+        // The rest api requires the base, unlike the web form
+        let base = match review.base {
+            Some(base) => base,
+            None => self
+                .client
+                .get(format!("{api}/repos/{owner}/{name}"))
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .with_context(|| format!("failed to get {owner}/{name}"))?
+                .json::<serde_json::Value>()
+                .await?
+                .get("default_branch")
+                .and_then(|b| b.as_str())
+                .with_context(|| format!("{owner}/{name} has no default branch"))?
+                .to_string(),
+        };
 
-        if let Some(errors) = resp.errors {
-            let error = AggregateGraphQLError { errors };
-            anyhow::bail!("get_user_reviews failed with: {}", error);
+        let res = self
+            .client
+            .post(format!("{api}/repos/{owner}/{name}/pulls"))
+            .json(&serde_json::json!({
+                "head": review.head,
+                "base": base,
+                "title": review.title,
+                "body": review.body,
+                "draft": review.draft,
+            }))
+            .send()
+            .await
+            .with_context(|| format!("failed to create a pull request on {owner}/{name}"))?;
+        let status = res.status();
+        let body = res.json::<serde_json::Value>().await.unwrap_or_default();
+        if !status.is_success() {
+            // Validation failures, e.g. an unpushed head, are only described in the errors
+            let message = body
+                .get("errors")
+                .and_then(|e| e.get(0))
+                .and_then(|e| e.get("message"))
+                .or_else(|| body.get("message"))
+                .and_then(|m| m.as_str())
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| status.to_string());
+            anyhow::bail!("failed to create a pull request on {owner}/{name}: {message}");
         }
 
-        let repository = resp.data.context("data to be present")?.repository;
-        let repository = match repository {
-            Some(pr) => pr,
-            None => return Ok(None),
+        body.get("number")
+            .and_then(|n| n.as_u64())
+            .map(|n| n as usize)
+            .context("the created pull request has no number")
+    }
+
+    async fn reassign_reviewer(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+        from: String,
+        to: String,
+    ) -> anyhow::Result<()> {
+        let url = format!(
+            "{}/repos/{owner}/{name}/pulls/{number}/requested_reviewers",
+            self.rest_uri
+        );
+        let _pending = PendingRequest::start(&self.pending);
+
+        // Requested first, so the review isn't left without the reviewer when it fails
+        self.client
+            .post(&url)
+            .json(&serde_json::json!({ "reviewers": [to] }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| {
+                format!("failed to request a review of {owner}/{name}#{number} from {to}")
+            })?;
+        self.client
+            .delete(&url)
+            .json(&serde_json::json!({ "reviewers": [from] }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| {
+                format!("failed to remove {from} from the reviewers of {owner}/{name}#{number}")
+            })?;
+        self.invalidate_review(owner, name, number);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl GitIssue for Github {
+    async fn search_issues(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList> {
+        let vars = issues::Variables {
+            cursor,
+            query: query.to_string(),
         };
+        let search = self.execute::<Issues>("search_issues", vars).await?.search;
 
-        let pr = match repository.pull_request {
-            Some(pr) => pr,
-            None => return Ok(None),
+        let items = search
+            .nodes
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|node| match node {
+                issues::IssuesSearchNodes::Issue(issue) => Some(issue),
+                _ => None,
+            })
+            .map(|issue| ReviewListItem {
+                id: issue.id,
+                name: issue.repository.name,
+                title: issue.title,
+                owner: issue.repository.owner.login,
+                date: issue.created_at,
+                number: issue.number as usize,
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(ReviewList {
+            items,
+            last_cursor: search.page_info.end_cursor,
+            has_more: search.page_info.has_next_page,
+        })
+    }
+
+    async fn get_issue(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Issue>> {
+        let vars = issue::Variables {
+            owner,
+            name,
+            number: number as i64,
+        };
+        let Some(issue) = self
+            .execute::<IssueQuery>("get_issue", vars)
+            .await?
+            .repository
+            .and_then(|r| r.issue)
+        else {
+            return Ok(None);
         };
 
-        Ok(Some(Review {
-            id: pr.id,
-            number: pr.number as usize,
-            repository: pr.repository.name_with_owner,
-            title: pr.title,
-            description: pr.body_text,
-            author: pr.author.map(|a| a.login).unwrap_or("ghost".to_string()),
-            publish_at: pr.published_at,
-            labels: pr
+        Ok(Some(Issue {
+            id: issue.id,
+            number: issue.number as usize,
+            repository: issue.repository.name_with_owner,
+            title: issue.title,
+            description: issue.body_text,
+            author: issue.author.map(|a| a.login).unwrap_or("ghost".to_string()),
+            created_at: issue.created_at,
+            labels: issue
                 .labels
+                .and_then(|l| l.nodes)
                 .into_iter()
-                .filter_map(|l| l.nodes)
-                .flat_map(|n| {
-                    n.iter()
-                        .flatten()
-                        .map(|n| n.name.clone())
-                        .collect::<Vec<_>>()
+                .flatten()
+                .flatten()
+                .map(|l| Label {
+                    name: l.name,
+                    color: Some(l.color),
+                    description: l.description,
                 })
                 .collect(),
             comments: Comments {
-                has_previous: pr.comments.page_info.has_previous_page,
-                comments: pr
+                has_previous: issue.comments.page_info.has_previous_page,
+                threads: Vec::new(),
+                comments: issue
                     .comments
                     .nodes
                     .into_iter()
                     .flatten()
                     .flatten()
                     .map(|n| Comment {
+                        id: n.id,
                         author: n.author.map(|a| a.login).unwrap_or("ghost".to_string()),
                         text: n.body_text,
+                        body: n.body,
+                        url: n.url,
+                        last_edited_at: n.last_edited_at,
                     })
                     .collect(),
             },
-            status_checks: pr
-                .commits
-                .nodes
-                .into_iter()
-                .flatten()
-                .flatten()
-                .filter_map(|n| n.commit.status_check_rollup)
-                .filter_map(|n| n.contexts.nodes)
-                .flatten()
-                .flatten()
-                .map(|c| match c {
-                    StatusChecks::CheckRun(c) => StatusCheck::CheckRun {
-                        id: c.id,
-                        name: c.name,
-                        current: c
-                            .conclusion
-                            .as_ref()
-                            .map(|c| match c {
-                                CheckConclusionState::ACTION_REQUIRED => CurrentState::Pending,
-                                CheckConclusionState::CANCELLED => CurrentState::Failure,
-                                CheckConclusionState::FAILURE => CurrentState::Failure,
-                                CheckConclusionState::NEUTRAL => CurrentState::Pending,
-                                CheckConclusionState::SKIPPED => CurrentState::Success,
-                                CheckConclusionState::STALE => CurrentState::Expired,
-                                CheckConclusionState::STARTUP_FAILURE => CurrentState::Failure,
-                                CheckConclusionState::SUCCESS => CurrentState::Success,
-                                CheckConclusionState::TIMED_OUT => CurrentState::Failure,
-                                CheckConclusionState::Other(_) => CurrentState::Pending,
-                            })
-                            .unwrap_or(CurrentState::Pending),
-                        status: {
-                            let status_name = match c.status {
-                                CheckStatusState::COMPLETED => "completed",
-                                CheckStatusState::IN_PROGRESS => "in progress",
-                                CheckStatusState::PENDING => "pending",
-                                CheckStatusState::QUEUED => "queued",
-                                CheckStatusState::REQUESTED => "requested",
-                                CheckStatusState::WAITING => "waiting",
-                                CheckStatusState::Other(ref e) => e,
-                            };
-                            status_name.to_string()
-                        },
-                        conclusion: c
-                            .conclusion
-                            .map(|c| {
-                                let conclusion = match c {
-                                    CheckConclusionState::ACTION_REQUIRED => "action required",
-                                    CheckConclusionState::CANCELLED => "cancelled",
-                                    CheckConclusionState::FAILURE => "failure",
-                                    CheckConclusionState::NEUTRAL => "neutral",
-                                    CheckConclusionState::SKIPPED => "skipped",
-                                    CheckConclusionState::STALE => "stale",
-                                    CheckConclusionState::STARTUP_FAILURE => "startup failure",
-                                    CheckConclusionState::SUCCESS => "success",
-                                    CheckConclusionState::TIMED_OUT => "timed out",
-                                    CheckConclusionState::Other(ref o) => o,
-                                };
-                                conclusion.to_string()
-                            })
-                            .unwrap_or("unknown".to_string()),
-                    },
-                    StatusChecks::StatusContext(sc) => StatusCheck::StatusContext {
-                        id: sc.id,
-                        current: match sc.state {
-                            pull_request::StatusState::ERROR => CurrentState::Failure,
-                            pull_request::StatusState::EXPECTED => CurrentState::Pending,
-                            pull_request::StatusState::FAILURE => CurrentState::Failure,
-                            pull_request::StatusState::PENDING => CurrentState::Pending,
-                            pull_request::StatusState::SUCCESS => CurrentState::Success,
-                            pull_request::StatusState::Other(_) => CurrentState::Pending,
+        }))
+    }
+}
+
+#[async_trait]
+impl GitAdvisory for Github {
+    async fn get_vulnerabilities(
+        &self,
+        ecosystem: Ecosystem,
+        package: &str,
+    ) -> anyhow::Result<Vec<Vulnerability>> {
+        use security_vulnerabilities::{
+            SecurityAdvisoryEcosystem as GithubEcosystem, SecurityAdvisorySeverity as Severity,
+        };
+
+        let vars = security_vulnerabilities::Variables {
+            ecosystem: match ecosystem {
+                Ecosystem::Actions => GithubEcosystem::ACTIONS,
+                Ecosystem::Composer => GithubEcosystem::COMPOSER,
+                Ecosystem::Erlang => GithubEcosystem::ERLANG,
+                Ecosystem::Go => GithubEcosystem::GO,
+                Ecosystem::Maven => GithubEcosystem::MAVEN,
+                Ecosystem::Npm => GithubEcosystem::NPM,
+                Ecosystem::Nuget => GithubEcosystem::NUGET,
+                Ecosystem::Pip => GithubEcosystem::PIP,
+                Ecosystem::Pub => GithubEcosystem::PUB,
+                Ecosystem::Rubygems => GithubEcosystem::RUBYGEMS,
+                Ecosystem::Rust => GithubEcosystem::RUST,
+                Ecosystem::Swift => GithubEcosystem::SWIFT,
+            },
+            package: package.to_string(),
+        };
+
+        let vulnerabilities = self
+            .execute_cached::<SecurityVulnerabilities>("get_vulnerabilities", vars)
+            .await?
+            .security_vulnerabilities;
+
+        Ok(vulnerabilities
+            .nodes
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|v| Vulnerability {
+                ghsa_id: v.advisory.ghsa_id,
+                summary: v.advisory.summary,
+                severity: match v.severity {
+                    Severity::CRITICAL => "critical".to_string(),
+                    Severity::HIGH => "high".to_string(),
+                    Severity::MODERATE => "moderate".to_string(),
+                    Severity::LOW => "low".to_string(),
+                    Severity::Other(other) => other.to_lowercase(),
+                },
+                vulnerable_range: v.vulnerable_version_range,
+                first_patched: v.first_patched_version.map(|p| p.identifier),
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl GitAuth for Github {
+    async fn get_token_status(&self) -> anyhow::Result<TokenStatus> {
+        #[derive(serde::Deserialize)]
+        struct User {
+            login: String,
+        }
+
+        let url = format!("{}/user", self.rest_uri);
+        let res = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("failed to reach {url}"))?;
+
+        if res.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(TokenStatus::Rejected);
+        }
+        if !res.status().is_success() {
+            anyhow::bail!("{url} answered with {}", res.status());
+        }
+
+        // Fine-grained tokens don't send the header, as they have no scopes
+        let scopes = res
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|h| h.to_str().ok())
+            .map(|scopes| {
+                scopes
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            });
+        let user = res
+            .json::<User>()
+            .await
+            .context("failed to parse the user of the token")?;
+
+        Ok(TokenStatus::Valid {
+            login: user.login,
+            scopes,
+        })
+    }
+}
+
+impl Provider for Github {
+    fn stats(&self) -> ProviderStats {
+        ProviderStats {
+            pending_requests: self.pending.load(Ordering::Relaxed),
+            cached_responses: self.cache.len(),
+            cached_bytes: self.cache.size(),
+            rate_limit: *self.rate_limit.lock().unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::{
+        parse_response, refresh, review, Endpoint, Github, GithubOptions, ReviewNode, ReviewNodes,
+    };
+    use crate::{
+        conformance::{self, Backend, Fixture, Reply, Request},
+        disk_cache::DiskCache,
+        Provider,
+    };
+
+    struct GithubBackend;
+
+    impl Backend for GithubBackend {
+        const PAGE_SIZE: usize = 10;
+
+        fn provider(base: &str) -> anyhow::Result<Arc<dyn Provider + Send + Sync>> {
+            Ok(Arc::new(Github::new(
+                GithubOptions::default()
+                    .with_uri(format!("{base}/graphql"))
+                    .with_token("fixture"),
+            )?))
+        }
+
+        fn respond(fixture: &Fixture, request: &Request) -> Reply {
+            let body: serde_json::Value = serde_json::from_str(&request.body).unwrap_or_default();
+            if request.method != "POST"
+                || request.path != "/graphql"
+                || body["operationName"] != "PullRequests"
+            {
+                return Reply::ok(r#"{"data":null,"errors":[{"message":"not in the fixture"}]}"#);
+            }
+
+            // The cursor is opaque to rev, the fixture uses the offset of the next page
+            let offset = body["variables"]["cursor"]
+                .as_str()
+                .and_then(|c| c.parse::<usize>().ok())
+                .unwrap_or_default();
+            let end = (offset + Self::PAGE_SIZE).min(fixture.reviews.len());
+            let nodes = fixture.reviews[offset.min(end)..end]
+                .iter()
+                .map(|r| {
+                    serde_json::json!({
+                        "__typename": "PullRequest",
+                        "id": r.id,
+                        "title": r.title,
+                        "url": format!("https://github.com/{}/{}/pull/{}", r.owner, r.name, r.number),
+                        "createdAt": "2023-10-12T08:14:03Z",
+                        "number": r.number,
+                        "isDraft": false,
+                        "reviewDecision": null,
+                        "repository": {
+                            "name": r.name,
+                            "owner": { "__typename": "User", "id": "owner", "login": r.owner },
                         },
-                        state: match sc.state {
-                            pull_request::StatusState::ERROR => "error",
-                            pull_request::StatusState::EXPECTED => "expected",
-                            pull_request::StatusState::FAILURE => "failure",
-                            pull_request::StatusState::PENDING => "pending",
-                            pull_request::StatusState::SUCCESS => "succeess",
-                            pull_request::StatusState::Other(ref o) => o,
+                        "author": { "__typename": "User", "login": "octocat" },
+                        "labels": { "nodes": [] },
+                        "additions": 1,
+                        "deletions": 1,
+                        "changedFiles": 1,
+                        "latestOpinionatedReviews": { "nodes": [] },
+                        "commits": { "nodes": [] },
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            Reply::ok(
+                serde_json::json!({
+                    "data": {
+                        "search": {
+                            "issueCount": fixture.reviews.len(),
+                            "pageInfo": {
+                                "endCursor": (end > 0).then(|| end.to_string()),
+                                "hasNextPage": end < fixture.reviews.len(),
+                            },
+                            "nodes": nodes,
                         }
-                        .to_string(),
-                        description: sc.description,
-                        context: sc.context,
-                    },
+                    }
                 })
-                .collect(),
-        }))
+                .to_string(),
+            )
+        }
     }
-}
 
-impl Provider for Github {}
+    #[tokio::test]
+    async fn test_conformance() -> anyhow::Result<()> {
+        conformance::run::<GithubBackend>().await
+    }
+
+    /// Answers a 304 to requests with `If-None-Match: "v1"`, and the body of `"v2"` otherwise.
+    async fn etag_server() -> anyhow::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base = format!("http://{}", listener.local_addr()?);
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut chunk = [0; 4096];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut chunk).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&chunk[..read]),
+                    }
+                }
+
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let response = if request.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nconnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = r#"{"data":{"v":2}}"#;
+                    format!(
+                        "HTTP/1.1 200 OK\r\netag: \"v2\"\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        Ok(base)
+    }
+
+    #[test]
+    fn test_enterprise_urls() {
+        let options = GithubOptions::default().with_host("github.example.com");
+        assert_eq!("https://github.example.com/api/graphql", options.uri);
+        assert_eq!("https://github.example.com/api/v3", options.rest_uri);
+
+        let options = GithubOptions::default().with_uri("https://github.example.com/api/graphql");
+        assert_eq!("https://github.example.com/api/v3", options.rest_uri);
+
+        let options = GithubOptions::default().with_host("github.com");
+        assert_eq!("https://api.github.com/graphql", options.uri);
+        assert_eq!("https://api.github.com", options.rest_uri);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_revalidates_with_etag() -> anyhow::Result<()> {
+        let base = etag_server().await?;
+        let endpoint = Endpoint {
+            client: reqwest::Client::new(),
+            uri: format!("{base}/graphql"),
+            pending: Arc::default(),
+            rate_limit: Arc::default(),
+        };
+        let dir = std::env::temp_dir().join(format!("rev-etag-test-{}", std::process::id()));
+        let disk = DiskCache::new(&dir, std::time::Duration::from_secs(60));
+        let key = r#"{"query":"query V { v }"}"#;
+
+        // Still current, so the stored response is kept
+        disk.insert(key, r#"{"data":{"v":1}}"#, Some("\"v1\"".into()));
+        refresh(&endpoint, &disk, key, Some("\"v1\"".into())).await?;
+        let entry = disk.get(key).unwrap();
+        assert_eq!(r#"{"data":{"v":1}}"#, entry.body);
+        assert_eq!(Some("\"v1\"".into()), entry.etag);
+
+        // Changed, so the new response is stored with its etag
+        refresh(&endpoint, &disk, key, Some("\"v0\"".into())).await?;
+        let entry = disk.get(key).unwrap();
+        assert_eq!(r#"{"data":{"v":2}}"#, entry.body);
+        assert_eq!(Some("\"v2\"".into()), entry.etag);
+        assert_eq!(
+            0,
+            endpoint.pending.load(std::sync::atomic::Ordering::Relaxed)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_scope_error() {
+        let body = r#"{
+  "data": null,
+  "errors": [
+    {
+      "type": "INSUFFICIENT_SCOPES",
+      "message": "Your token has not been granted the required scopes to execute this query. The 'slug' field requires one of the following scopes: ['read:org'], but your token has only been granted the: ['repo'] scopes. Please modify your token's scopes at: https://github.com/settings/tokens."
+    }
+  ]
+}"#;
+
+        let Err(error) = parse_response::<ReviewNodes>("get_user_reviews", body) else {
+            panic!("expected the response to fail");
+        };
+
+        assert_eq!(
+            "get_user_reviews failed: the token is missing the read:org scope, run `gh auth refresh -s read:org`",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_review_nodes() -> anyhow::Result<()> {
+        let body = r#"{
+  "data": {
+    "nodes": [
+      {
+        "__typename": "PullRequest",
+        "id": "PR_1",
+        "number": 42,
+        "title": "Add batching",
+        "url": "https://github.com/kjuulh/rev/pull/42",
+        "bodyText": "",
+        "headRefOid": "abc",
+        "headRefName": "feat/history",
+        "author": { "__typename": "User", "login": "kjuulh" },
+        "labels": null,
+        "publishedAt": null,
+        "additions": 12,
+        "deletions": 3,
+        "changedFiles": 2,
+        "repository": { "nameWithOwner": "kjuulh/rev" },
+        "participants": { "nodes": [] },
+        "reviewRequests": null,
+        "latestReviews": null
+      },
+      null,
+      { "__typename": "Issue" }
+    ]
+  }
+}"#;
+
+        let nodes = parse_response::<ReviewNodes>("get_reviews", body)?.nodes;
+        let reviews = nodes
+            .into_iter()
+            .flatten()
+            .filter_map(|n| match n {
+                ReviewNode::PullRequest(pr) => Some(review(*pr)),
+                ReviewNode::Other => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(1, reviews.len());
+        assert_eq!("kjuulh/rev", reviews[0].repository);
+        assert_eq!(42, reviews[0].number);
+        assert_eq!(2, reviews[0].changed_files);
+        assert!(reviews[0].comments.is_none());
+
+        Ok(())
+    }
+}
@@ -1,9 +1,18 @@
 use std::{ops::Deref, sync::Arc};
 
+use bitbucket::{Bitbucket, BitbucketOptions};
+use gitea::{Gitea, GiteaOptions};
 use github::{Github, GithubOptions};
-use traits::{GitReview, GitUserReview};
+use models::ProviderStats;
+use traits::{
+    GitAdvisory, GitAuth, GitComment, GitIssue, GitReview, GitReviewAction, GitUserReview,
+};
 
-pub trait Provider: GitUserReview + GitReview {}
+pub trait Provider:
+    GitUserReview + GitReview + GitComment + GitReviewAction + GitIssue + GitAdvisory + GitAuth
+{
+    fn stats(&self) -> ProviderStats;
+}
 
 #[derive(Clone)]
 pub struct GitProvider {
@@ -16,6 +25,36 @@ impl GitProvider {
 
         Ok(Self { provider: github })
     }
+
+    pub fn github_with(options: GithubOptions) -> anyhow::Result<Self> {
+        let github = Arc::new(Github::new(options)?);
+
+        Ok(Self { provider: github })
+    }
+
+    /// A Gitea or Forgejo instance.
+    pub fn gitea(options: GiteaOptions) -> anyhow::Result<Self> {
+        let gitea = Arc::new(Gitea::new(options)?);
+
+        Ok(Self { provider: gitea })
+    }
+
+    /// Any other implementation of the provider, e.g. the forge of an internal tool embedding
+    /// the components of rev.
+    pub fn custom(provider: impl Provider + Send + Sync + 'static) -> Self {
+        Self {
+            provider: Arc::new(provider),
+        }
+    }
+
+    /// Bitbucket Cloud, limited to the repositories in the options.
+    pub fn bitbucket(options: BitbucketOptions) -> anyhow::Result<Self> {
+        let bitbucket = Arc::new(Bitbucket::new(options)?);
+
+        Ok(Self {
+            provider: bitbucket,
+        })
+    }
 }
 
 impl Deref for GitProvider {
@@ -26,8 +65,18 @@ impl Deref for GitProvider {
     }
 }
 
+pub mod auth;
+pub mod bitbucket;
+pub mod cache;
+#[cfg(feature = "cassette")]
+pub mod cassette;
+#[cfg(test)]
+mod conformance;
+pub mod disk_cache;
+pub mod gitea;
 pub mod github;
 pub mod models;
+mod retry;
 pub mod traits;
 
 #[cfg(test)]
@@ -39,6 +88,16 @@ mod test {
     #[tokio::test]
     #[traced_test]
     async fn test_can_call_github() -> anyhow::Result<()> {
+        #[cfg(feature = "cassette")]
+        let g = GitProvider {
+            provider: std::sync::Arc::new(crate::github::Github::new(
+                crate::github::GithubOptions::default().cassette(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/cassettes/test_can_call_github.json"
+                )),
+            )?),
+        };
+        #[cfg(not(feature = "cassette"))]
         let g = GitProvider::github()?;
 
         //let titles = g.get_user_reviews("kjuulh", &["dependencies"]).await?;
@@ -6,27 +6,163 @@ pub struct Review {
     pub number: usize,
     pub title: String,
     pub repository: String,
+    /// The page of the pull request on the provider.
+    pub url: String,
     pub description: String,
+    /// The commit sha the pull request branch currently points at.
+    pub head_sha: String,
+    /// The name of the pull request branch, e.g. `feat/history`.
+    pub head_branch: String,
     pub author: String,
     pub publish_at: Option<DateTime<Utc>>,
-    pub labels: Vec<String>,
-    pub comments: Comments,
-    pub status_checks: Vec<StatusCheck>,
+    pub labels: Vec<Label>,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Zero when the provider doesn't tell, along with the additions and deletions.
+    pub changed_files: usize,
+    /// Fetched separately with `get_comments`, `None` until then.
+    pub comments: Option<Comments>,
+    /// Fetched separately with `get_status_checks`, `None` until then.
+    pub status_checks: Option<Vec<StatusCheck>>,
+    pub participants: Vec<Participant>,
+}
+
+impl Review {
+    /// The review in the form `owner/name#number`.
+    pub fn reference(&self) -> String {
+        format!("{}#{}", self.repository, self.number)
+    }
+
+    /// Splits `owner/name` into its parts.
+    pub fn owner_and_name(&self) -> Option<(&str, &str)> {
+        self.repository.split_once('/')
+    }
+
+    pub fn has_pending_checks(&self) -> bool {
+        self.status_checks
+            .iter()
+            .flatten()
+            .any(|c| matches!(c.current(), CurrentState::Pending))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParticipantRole {
+    Author,
+    Reviewer,
+    Commenter,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Commented,
+    Dismissed,
+    Pending,
 }
 
 #[derive(Debug, Clone)]
+pub struct Participant {
+    pub login: String,
+    pub name: Option<String>,
+    pub role: ParticipantRole,
+    /// Whether a review is currently requested from the participant.
+    pub requested: bool,
+    /// The latest review submitted by the participant.
+    pub review: Option<ReviewState>,
+}
+
+impl Participant {
+    /// Two letter initials, used in place of an avatar.
+    pub fn initials(&self) -> String {
+        let name = self.name.as_deref().filter(|n| !n.trim().is_empty());
+        let initials = match name {
+            Some(name) => name
+                .split_whitespace()
+                .filter_map(|w| w.chars().next())
+                .take(2)
+                .collect::<String>(),
+            None => self.login.chars().take(2).collect(),
+        };
+
+        initials.to_uppercase()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Comments {
     pub has_previous: bool,
     pub comments: Vec<Comment>,
+    /// The comments left on lines of the diff, empty for issues.
+    pub threads: Vec<ReviewThread>,
+}
+
+impl Comments {
+    /// The threads grouped by the file they are on, in the order the files first appear.
+    pub fn threads_by_file(&self) -> Vec<(&str, Vec<&ReviewThread>)> {
+        let mut files: Vec<(&str, Vec<&ReviewThread>)> = Vec::new();
+        for thread in &self.threads {
+            match files.iter_mut().find(|(path, _)| *path == thread.path) {
+                Some((_, threads)) => threads.push(thread),
+                None => files.push((&thread.path, vec![thread])),
+            }
+        }
+
+        files
+    }
+}
+
+/// A conversation on a line of a file in the diff of a review.
+#[derive(Debug, Clone)]
+pub struct ReviewThread {
+    pub id: String,
+    pub path: String,
+    /// The line in the current diff, falls back to the original line once the thread is outdated.
+    pub line: Option<usize>,
+    /// The hunk of the diff the thread starts on, ending with the commented line.
+    pub diff_hunk: String,
+    pub resolved: bool,
+    /// Whether the lines commented on have since changed.
+    pub outdated: bool,
+    pub comments: Vec<Comment>,
+}
+
+impl ReviewThread {
+    /// Where the thread is, e.g. `src/main.rs:42`.
+    pub fn location(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{line}", self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    /// The last lines of the hunk leading up to the commented line.
+    pub fn context(&self, lines: usize) -> Vec<&str> {
+        let hunk = self
+            .diff_hunk
+            .lines()
+            .filter(|l| !l.starts_with("@@"))
+            .collect::<Vec<_>>();
+
+        hunk[hunk.len().saturating_sub(lines)..].to_vec()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Comment {
+    pub id: String,
     pub author: String,
+    /// The comment rendered as plain text.
     pub text: String,
+    /// The raw markdown of the comment, used when editing.
+    pub body: String,
+    /// The permalink to the comment, empty where the provider has none.
+    pub url: String,
+    pub last_edited_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CurrentState {
     Success,
     Pending,
@@ -42,6 +178,9 @@ pub enum StatusCheck {
         description: Option<String>,
         context: String,
         current: CurrentState,
+        url: Option<String>,
+        /// Who posted the status, statuses don't belong to an app.
+        app: String,
     },
     CheckRun {
         id: String,
@@ -49,17 +188,203 @@ pub enum StatusCheck {
         status: String,
         conclusion: String,
         current: CurrentState,
+        url: Option<String>,
+        /// The app running the check, e.g. GitHub Actions.
+        app: String,
     },
 }
 
+impl StatusCheck {
+    pub fn current(&self) -> &CurrentState {
+        match self {
+            StatusCheck::StatusContext { current, .. } => current,
+            StatusCheck::CheckRun { current, .. } => current,
+        }
+    }
+
+    /// Where the details of the check can be found, e.g. the logs of the ci run.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            StatusCheck::StatusContext { url, .. } => url.as_deref(),
+            StatusCheck::CheckRun { url, .. } => url.as_deref(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            StatusCheck::StatusContext { context, .. } => context,
+            StatusCheck::CheckRun { name, .. } => name,
+        }
+    }
+
+    pub fn app(&self) -> &str {
+        match self {
+            StatusCheck::StatusContext { app, .. } => app,
+            StatusCheck::CheckRun { app, .. } => app,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub oid: String,
+    pub short_oid: String,
+    pub headline: String,
+    /// The login of the author, or the name in the commit when it isn't a user.
+    pub author: String,
+    /// `None` when the commit isn't signed.
+    pub signature: Option<CommitSignature>,
+}
+
+impl Commit {
+    /// Whether the commit is signed and the signature verified by the provider.
+    pub fn is_verified(&self) -> bool {
+        self.signature.as_ref().map(|s| s.valid).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitSignature {
+    /// How the commit was signed, e.g. `gpg` or `ssh`.
+    pub kind: String,
+    pub valid: bool,
+    /// The user the signing key belongs to, if it's known.
+    pub signer: Option<String>,
+}
+
+/// The verdict submitted with a review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewEvent {
+    Approve,
+    RequestChanges,
+    Comment,
+}
+
+/// How the commits of a review land on the base branch when it's merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeStrategy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
+/// A review to open from a pushed branch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewReview {
+    /// The branch with the changes.
+    pub head: String,
+    /// The branch to merge into, the default branch of the repository when unset.
+    pub base: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub draft: bool,
+}
+
+/// A long-form issue, such as an RFC, read and discussed rather than reviewed.
 #[derive(Debug, Clone)]
+pub struct Issue {
+    pub id: String,
+    pub number: usize,
+    pub repository: String,
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    pub labels: Vec<Label>,
+    pub comments: Comments,
+}
+
+impl Issue {
+    /// The issue in the form `owner/name#number`.
+    pub fn reference(&self) -> String {
+        format!("{}#{}", self.repository, self.number)
+    }
+}
+
+/// A label of a review or issue, with the color and description it has on the provider.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Label {
+    pub name: String,
+    /// The color in hex without the leading `#`, e.g. `d73a4a`, `None` where the provider has
+    /// no colors.
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Label {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The color as rgb, when it's a valid hex color.
+    pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+        let color = self.color.as_deref()?.trim_start_matches('#');
+        if color.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(color.get(i..i + 2)?, 16).ok();
+
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Whether dark text reads better than light text on the color, by its perceived
+    /// brightness.
+    pub fn is_light(&self) -> Option<bool> {
+        let (r, g, b) = self.rgb()?;
+
+        Some(0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32 > 150.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct ReviewListItem {
     pub id: String,
     pub name: String,
     pub title: String,
+    /// The page of the pull request on the provider, empty for the issues of discussions.
+    pub url: String,
     pub owner: String,
     pub date: chrono::DateTime<chrono::Utc>,
     pub number: usize,
+    pub author: String,
+    pub labels: Vec<Label>,
+    pub additions: usize,
+    pub deletions: usize,
+    pub changed_files: usize,
+    pub approvals: usize,
+    /// The combined state of the checks of the head commit, if it has any.
+    pub checks: Option<CurrentState>,
+    /// Whether the required reviews are in, if the repository requires any.
+    pub review_decision: Option<ReviewDecision>,
+    pub draft: bool,
+}
+
+/// Where a pull request stands with the reviews its repository requires to merge it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
 }
 
 #[derive(Debug, Clone)]
@@ -68,3 +393,84 @@ pub struct ReviewList {
     pub last_cursor: Option<String>,
     pub has_more: bool,
 }
+
+/// A repository of an organization or user, with how many reviews are open in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepositoryListItem {
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub open_reviews: usize,
+    /// When the repository was last pushed to, where the provider tells.
+    pub pushed_at: Option<DateTime<Utc>>,
+}
+
+impl RepositoryListItem {
+    /// The repository in the form `owner/name`.
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+/// The package ecosystems known to the advisory database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    Actions,
+    Composer,
+    Erlang,
+    Go,
+    Maven,
+    Npm,
+    Nuget,
+    Pip,
+    Pub,
+    Rubygems,
+    Rust,
+    Swift,
+}
+
+/// A known vulnerability of a package, from the advisory database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vulnerability {
+    pub ghsa_id: String,
+    pub summary: String,
+    /// e.g. `critical`, `high`, `moderate` or `low`.
+    pub severity: String,
+    /// e.g. `>= 4.3.0, < 4.3.5`.
+    pub vulnerable_range: String,
+    pub first_patched: Option<String>,
+}
+
+/// What the provider is doing right now, for diagnosing a slow ui.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProviderStats {
+    /// Requests sent which haven't been answered yet.
+    pub pending_requests: usize,
+    pub cached_responses: usize,
+    /// The size of the cached responses and their keys.
+    pub cached_bytes: usize,
+    /// The api budget as of the last response, `None` when the provider doesn't report it.
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// How many requests are left until the rate limit of the provider resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: usize,
+    pub remaining: usize,
+    pub reset: DateTime<Utc>,
+}
+
+/// The token rev authenticates with, as github sees it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenStatus {
+    Valid {
+        login: String,
+        /// The oauth scopes of the token, `None` for fine-grained tokens which have permissions
+        /// instead.
+        scopes: Option<Vec<String>>,
+    },
+    /// The token is expired or revoked.
+    Rejected,
+}
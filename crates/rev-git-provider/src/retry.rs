@@ -0,0 +1,184 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::{header::HeaderMap, RequestBuilder, Response, StatusCode};
+
+use crate::models::RateLimit;
+
+/// How many times a request is sent before its failure is returned.
+const MAX_ATTEMPTS: u32 = 4;
+/// The backoff before the first retry of a server error, doubled for every retry after it.
+const BASE_BACKOFF: Duration = Duration::from_millis(250);
+/// Waiting for a rate limit to reset for longer than this fails the request instead, the primary
+/// limit can take up to an hour to reset.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Sends the request built by `request`, retrying when github is rate limiting or failing.
+///
+/// Rate limited requests wait as long as `Retry-After` or `X-RateLimit-Reset` asks. Server errors
+/// are retried with a jittered exponential backoff, but only when `idempotent` as a mutation may
+/// have gone through before the server failed. The rate limit of every response is kept in
+/// `rate_limit`.
+pub(crate) async fn send(
+    request: impl Fn() -> RequestBuilder,
+    idempotent: bool,
+    rate_limit: &Mutex<Option<RateLimit>>,
+) -> anyhow::Result<Response> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let res = request().send().await.context("github request failed")?;
+        if let Some(limit) = parse_rate_limit(res.headers()) {
+            *rate_limit.lock().unwrap() = Some(limit);
+        }
+
+        let delay = match retry_delay(res.status(), res.headers(), Utc::now()) {
+            Some(Retry::RateLimited(delay)) if delay > MAX_RATE_LIMIT_WAIT => {
+                let reset =
+                    chrono::Local::now() + chrono::Duration::seconds(delay.as_secs() as i64);
+                anyhow::bail!(
+                    "github rate limit exceeded, it resets at {}",
+                    reset.format("%H:%M")
+                );
+            }
+            Some(Retry::RateLimited(delay)) => delay,
+            Some(Retry::ServerError) if idempotent => backoff(attempt),
+            _ => return Ok(res),
+        };
+        if attempt >= MAX_ATTEMPTS {
+            return Ok(res);
+        }
+
+        tracing::warn!(
+            status = %res.status(),
+            attempt,
+            "github request failed, retrying in {}ms",
+            delay.as_millis()
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Retry {
+    /// Github asked to wait this long before sending the request again.
+    RateLimited(Duration),
+    ServerError,
+}
+
+/// Whether a response is worth sending the request again for.
+///
+/// Github answers both its primary and secondary rate limits with a 403 or 429, and says how long
+/// to wait in the headers. A 403 without those is a missing permission, which won't go away.
+fn retry_delay(status: StatusCode, headers: &HeaderMap, now: DateTime<Utc>) -> Option<Retry> {
+    if status.is_server_error() {
+        return Some(Retry::ServerError);
+    }
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    if let Some(seconds) = header(headers, "retry-after").and_then(|s| s.parse::<u64>().ok()) {
+        return Some(Retry::RateLimited(Duration::from_secs(seconds)));
+    }
+    let limit = parse_rate_limit(headers).filter(|l| l.remaining == 0)?;
+    let wait = (limit.reset - now).to_std().unwrap_or_default();
+
+    Some(Retry::RateLimited(wait))
+}
+
+fn parse_rate_limit(headers: &HeaderMap) -> Option<RateLimit> {
+    let number = |name| header(headers, name)?.parse::<i64>().ok();
+
+    Some(RateLimit {
+        limit: number("x-ratelimit-limit")?.try_into().ok()?,
+        remaining: number("x-ratelimit-remaining")?.try_into().ok()?,
+        reset: Utc
+            .timestamp_opt(number("x-ratelimit-reset")?, 0)
+            .single()?,
+    })
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+/// The exponential backoff before the retry after `attempt`, with up to half of it added as
+/// jitter so clients failing together don't retry together.
+fn backoff(attempt: u32) -> Duration {
+    let backoff = BASE_BACKOFF * 2u32.pow(attempt.saturating_sub(1));
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let jitter = backoff.mul_f64(f64::from(nanos % 1000) / 2000.0);
+
+    backoff + jitter
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        StatusCode,
+    };
+
+    use super::{backoff, parse_rate_limit, retry_delay, Retry, BASE_BACKOFF};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| (*name, HeaderValue::from_str(value).unwrap()))
+            .fold(HeaderMap::new(), |mut headers, (name, value)| {
+                headers.insert(name, value);
+                headers
+            })
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let exhausted = headers(&[
+            ("x-ratelimit-limit", "5000"),
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1700000030"),
+        ]);
+
+        assert_eq!(
+            Some(Retry::RateLimited(Duration::from_secs(30))),
+            retry_delay(StatusCode::FORBIDDEN, &exhausted, now)
+        );
+        assert_eq!(
+            Some(Retry::RateLimited(Duration::from_secs(60))),
+            retry_delay(
+                StatusCode::FORBIDDEN,
+                &headers(&[("retry-after", "60")]),
+                now
+            )
+        );
+        assert_eq!(
+            Some(Retry::ServerError),
+            retry_delay(StatusCode::BAD_GATEWAY, &HeaderMap::new(), now)
+        );
+        // Missing permissions and bad queries don't go away by sending them again
+        assert_eq!(
+            None,
+            retry_delay(StatusCode::FORBIDDEN, &HeaderMap::new(), now)
+        );
+        assert_eq!(None, retry_delay(StatusCode::BAD_REQUEST, &exhausted, now));
+
+        let limit = parse_rate_limit(&exhausted).unwrap();
+        assert_eq!((5000, 0), (limit.limit, limit.remaining));
+        assert_eq!(None, parse_rate_limit(&HeaderMap::new()));
+
+        assert!(backoff(1) >= BASE_BACKOFF && backoff(1) <= BASE_BACKOFF * 3 / 2);
+        assert!(backoff(3) >= BASE_BACKOFF * 4);
+    }
+}
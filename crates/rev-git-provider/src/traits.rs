@@ -1,6 +1,9 @@
 use async_trait::async_trait;
 
-use crate::models::{Review, ReviewList};
+use crate::models::{
+    ChangedFile, Comments, Commit, Ecosystem, Issue, MergeStrategy, NewReview, RepositoryListItem,
+    Review, ReviewEvent, ReviewList, StatusCheck, TokenStatus, Vulnerability,
+};
 
 #[async_trait]
 pub trait GitUserReview {
@@ -17,6 +20,18 @@ pub trait GitUserReview {
         tags: Option<Vec<String>>,
         cursor: Option<String>,
     ) -> anyhow::Result<ReviewList>;
+    /// Runs a raw search query, e.g. `is:pr author:@me state:open`.
+    async fn search_reviews(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList>;
+    /// Runs a search query for a single item, returning how many items match it. Fails if the
+    /// query is invalid.
+    async fn count_search(&self, query: &str) -> anyhow::Result<usize>;
+    /// The repositories of an organization or user which aren't archived, the most recently
+    /// pushed to first, with how many reviews are open in each.
+    async fn get_repositories(&self, owner: &str) -> anyhow::Result<Vec<RepositoryListItem>>;
 }
 
 #[async_trait]
@@ -27,4 +42,148 @@ pub trait GitReview {
         name: String,
         number: usize,
     ) -> anyhow::Result<Option<Review>>;
+    /// Fetches the comments of a review, these are left out of [`GitReview::get_review`] as they
+    /// are only needed once the review is shown.
+    async fn get_comments(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Comments>>;
+    /// Fetches the status checks of the latest commit of a review.
+    async fn get_status_checks(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Vec<StatusCheck>>>;
+    async fn get_changed_files(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Vec<ChangedFile>>;
+    /// The unified diff of the review against its base, as `git diff` prints it.
+    async fn get_diff(&self, owner: String, name: String, number: usize) -> anyhow::Result<String>;
+    /// Fetches many reviews by their ids in a single request, reviews which no longer exist are
+    /// left out.
+    async fn get_reviews(&self, ids: Vec<String>) -> anyhow::Result<Vec<Review>>;
+    /// Drops any cached response for the review, so the next fetch returns fresh data.
+    fn invalidate_review(&self, owner: String, name: String, number: usize);
+    /// Fetches only the head commit of the review, which is cheap enough to poll.
+    async fn get_head_sha(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<String>>;
+    /// The commits of the review with their signatures, the latest last.
+    async fn get_commits(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Vec<Commit>>;
+    /// The body of the pull request template of the repository, if it has one.
+    async fn get_review_template(
+        &self,
+        owner: String,
+        name: String,
+    ) -> anyhow::Result<Option<String>>;
+}
+
+#[async_trait]
+pub trait GitComment {
+    /// Adds a comment to a review or issue.
+    async fn add_comment(&self, subject_id: String, body: String) -> anyhow::Result<()>;
+    async fn update_comment(&self, id: String, body: String) -> anyhow::Result<()>;
+    async fn delete_comment(&self, id: String) -> anyhow::Result<()>;
+    /// Replies to a thread on the diff of a review, by the id of the thread.
+    async fn reply_to_thread(&self, thread_id: String, body: String) -> anyhow::Result<()>;
+    async fn resolve_thread(&self, thread_id: String) -> anyhow::Result<()>;
+}
+
+/// Mutations on a review itself, these take the node id of the review unless they say otherwise.
+#[async_trait]
+pub trait GitReviewAction {
+    async fn submit_review(
+        &self,
+        review_id: String,
+        event: ReviewEvent,
+        body: Option<String>,
+    ) -> anyhow::Result<()>;
+    /// Adds labels, by name, to a review or issue of the repository.
+    async fn add_labels(
+        &self,
+        subject_id: String,
+        owner: String,
+        name: String,
+        labels: Vec<String>,
+    ) -> anyhow::Result<()>;
+    async fn remove_labels(
+        &self,
+        subject_id: String,
+        owner: String,
+        name: String,
+        labels: Vec<String>,
+    ) -> anyhow::Result<()>;
+    /// Merges the review once its requirements are met.
+    async fn enable_auto_merge(&self, review_id: String) -> anyhow::Result<()>;
+    /// Merges the review now, failing when the provider doesn't allow it yet, e.g. because
+    /// required checks haven't passed.
+    async fn merge_review(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+        strategy: MergeStrategy,
+    ) -> anyhow::Result<()>;
+    /// Opens a review of a branch already pushed to the repository, returning its number.
+    async fn create_review(
+        &self,
+        owner: String,
+        name: String,
+        review: NewReview,
+    ) -> anyhow::Result<usize>;
+    /// Requests a review from `to` and withdraws the request from `from`, both by login.
+    async fn reassign_reviewer(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+        from: String,
+        to: String,
+    ) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+pub trait GitIssue {
+    /// Runs a raw issue search query, e.g. `is:issue label:rfc state:open`.
+    async fn search_issues(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> anyhow::Result<ReviewList>;
+    async fn get_issue(
+        &self,
+        owner: String,
+        name: String,
+        number: usize,
+    ) -> anyhow::Result<Option<Issue>>;
+}
+
+#[async_trait]
+pub trait GitAdvisory {
+    /// The known vulnerabilities of a package, across all its versions.
+    async fn get_vulnerabilities(
+        &self,
+        ecosystem: Ecosystem,
+        package: &str,
+    ) -> anyhow::Result<Vec<Vulnerability>>;
+}
+
+#[async_trait]
+pub trait GitAuth {
+    /// Checks the token with github, failing only when github can't be reached.
+    async fn get_token_status(&self) -> anyhow::Result<TokenStatus>;
 }
@@ -1,12 +1,50 @@
+use std::{io::IsTerminal, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::Context;
 use clap::{Parser, Subcommand};
-use tokio::io::AsyncWriteExt;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
 
-use crate::{
+use rev_core::{
     app::App,
-    application_config::{inner_application_config::InnerApplicationConfig, ApplicationConfig},
-    logging,
+    application_config::{
+        inner_application_config::InnerApplicationConfig, ApplicationConfig, ApplicationSettings,
+    },
+    audit::AuditLog,
+    bot::{self, BotOutcome},
+    config::{Config, CONFIG_VERSION},
+    digest, doctor,
+    drafts::{Drafts, ReviewDraft},
+    due_dates::{self, DueDates, DueReview},
+    git_pull_requests::{GitPullRequest, GitPullRequests},
+    i18n::{tr, Text},
+    links::ReviewTarget,
+    listing::{self, ListedReview},
+    metrics::{self, Metrics},
+    new_review::BranchReview,
+    out_of_office::{self, Absence},
+    platform,
+    queue_share::{self, QueueSnippet},
+    review_plan::ReviewPlan,
+    saved_searches::SavedSearches,
+    script, self_update,
+    share::{Format, ReviewDocument},
+    stats::VerdictStats,
+    store::Store,
+    terminal::Capabilities,
+    workspace::{parse_reference, Workspace},
+};
+use rev_git_provider::{
+    auth::{self, DeviceFlow},
+    github::token_source,
+    models::TokenStatus,
+    GitProvider,
 };
 
+use crate::logging;
+
 #[derive(Parser)]
 #[command(author, version, about, long_about = None, subcommand_required = true)]
 struct Command {
@@ -15,6 +53,15 @@ struct Command {
 
     #[clap(flatten)]
     global_args: InnerApplicationConfig,
+
+    /// The profile in rev.kdl whose provider and queue to use
+    #[arg(long = "profile", env = "REV_PROFILE", global = true)]
+    profile: Option<String>,
+
+    /// The graphql endpoint of github enterprise, e.g. https://github.acme.com/api/graphql,
+    /// instead of the one in rev.kdl
+    #[arg(long = "github-api-url", env = "REV_GITHUB_API_URL", global = true)]
+    github_api_url: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -23,13 +70,304 @@ enum Commands {
         #[arg(long = "force", default_value = "false")]
         force: bool,
     },
-    Review,
+    Review {
+        /// A review to open before the queue, as owner/name#number or its url. The url of a
+        /// comment opens the review at the comment
+        target: Option<String>,
+
+        /// The user, or team in the form org/team, whose review requests to queue, defaults to
+        /// your own
+        #[arg(long = "reviewer")]
+        reviewer: Option<String>,
+
+        /// Only queue reviews in repositories of the organization
+        #[arg(long = "org")]
+        org: Option<String>,
+
+        /// Only queue reviews with the label, can be given more than once
+        #[arg(long = "label")]
+        labels: Vec<String>,
+    },
+    /// Prints the review queue without the tui, as a table or as json for scripts
+    List {
+        /// The saved search to list instead of the review requests, by its name
+        #[arg(long = "search")]
+        search: Option<String>,
+
+        /// The user, or team in the form org/team, whose review requests to list, defaults to
+        /// your own
+        #[arg(long = "reviewer")]
+        reviewer: Option<String>,
+
+        /// Only list reviews in repositories of the organization
+        #[arg(long = "org")]
+        org: Option<String>,
+
+        /// Only list reviews with the label, can be given more than once
+        #[arg(long = "label")]
+        labels: Vec<String>,
+
+        #[arg(long = "format", default_value = "table", value_parser = ["table", "json"])]
+        format: String,
+    },
+    /// Prints a summary of pending review requests and your own pull requests
+    Digest {
+        /// Number of pull requests to show in the waiting longest section
+        #[arg(long = "limit", default_value = "5")]
+        limit: usize,
+
+        /// Command to pipe the digest into, e.g. a mail or slack cli
+        #[arg(long = "hook")]
+        hook: Option<String>,
+    },
+    /// Applies the bot rules from the config to the review queue, without the tui
+    Bot {
+        /// Prints what would be done without changing anything
+        #[arg(long = "dry-run", default_value = "false")]
+        dry_run: bool,
+
+        /// The search query for the reviews to consider
+        #[arg(
+            long = "query",
+            default_value = "is:pr review-requested:@me state:open"
+        )]
+        query: String,
+
+        /// Keeps running, applying the rules again every given number of seconds, at least one
+        #[arg(long = "watch", value_parser = clap::value_parser!(u64).range(1..))]
+        watch: Option<u64>,
+
+        /// Serves prometheus metrics of the rounds at /metrics on the port while watching
+        #[arg(long = "metrics-port", requires = "watch")]
+        metrics_port: Option<u16>,
+    },
+    /// Runs commands such as `approve owner/name#1 "lgtm"`, one per line, against the provider
+    Script {
+        /// The file of commands, - reads them from stdin
+        script: String,
+    },
+    /// Approves a review without the tui
+    Approve {
+        /// The review as owner/name#number
+        review: String,
+
+        /// The body of the approval
+        #[arg(short = 'm', long = "message")]
+        message: Option<String>,
+    },
+    /// Comments on a review without the tui
+    Comment {
+        /// The review as owner/name#number
+        review: String,
+
+        #[arg(short = 'm', long = "message")]
+        message: String,
+    },
+    /// Logs in to github without gh, and shows where the token comes from
+    Auth {
+        #[command(subcommand)]
+        subcommand: AuthCommand,
+    },
+    /// Checks that rev can reach github with a valid token and read its config and data
+    Doctor,
+    /// Lists the changes made to reviews through rev, newest first
+    History {
+        /// Number of entries to show
+        #[arg(long = "limit", default_value = "50")]
+        limit: usize,
+    },
+    /// Breaks down your verdicts by repository and author, to spot where reviews bounce
+    Stats {
+        /// Prints the breakdown as json
+        #[arg(long = "json")]
+        json: bool,
+    },
+    /// Replaces git-rev with the latest release from the feed in the update block of the config
+    SelfUpdate,
+    /// Shares queue definitions, the searches and the queue block of the config, with teammates
+    Queue {
+        #[command(subcommand)]
+        subcommand: QueueCommand,
+    },
+    /// Hands over review requests while you're away, see the out-of-office block in the config
+    Ooo {
+        #[command(subcommand)]
+        subcommand: OooCommand,
+    },
+    /// Keeps track of when reviews are due, see the due block in the config
+    Due {
+        #[command(subcommand)]
+        subcommand: DueCommand,
+    },
+    /// Works with a single pull request without the tui
+    Pr {
+        #[command(subcommand)]
+        subcommand: PrCommand,
+    },
+    /// Moves unsubmitted reviews between machines and submits them
+    Draft {
+        #[command(subcommand)]
+        subcommand: DraftCommand,
+    },
+    /// Manages the local checkouts of reviews
+    Workspace {
+        #[command(subcommand)]
+        subcommand: WorkspaceCommand,
+    },
     Config {
         #[command(subcommand)]
         subcommand: Option<ConfigCommand>,
     },
 }
 
+#[derive(Subcommand)]
+enum WorkspaceCommand {
+    /// Checks out a review and prints the path of its worktree
+    Checkout {
+        /// The review in the form owner/name#number
+        review: String,
+    },
+    /// Lists the checked out reviews, most recently used first
+    List,
+    /// Removes all but the most recently used worktrees
+    Prune {
+        /// Number of worktrees to keep, defaults to max-worktrees from the config
+        #[arg(long = "keep")]
+        keep: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PrCommand {
+    /// Prints the description, diff stats, checks and discussion of a pull request as a static
+    /// document
+    Render {
+        /// The pull request in the form owner/name#number
+        review: String,
+
+        #[arg(long = "format", default_value = "md", value_parser = ["md", "html"])]
+        format: String,
+
+        /// Writes the document to the file instead of stdout
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+    },
+    /// Opens a pull request of the branch checked out in the current directory, with the title
+    /// of its first commit and the pull request template of the repository as the body
+    Create {
+        /// The branch to merge into, defaults to the default branch of the repository
+        #[arg(long = "base")]
+        base: Option<String>,
+
+        #[arg(long = "title")]
+        title: Option<String>,
+
+        #[arg(long = "body")]
+        body: Option<String>,
+
+        #[arg(long = "draft", default_value = "false")]
+        draft: bool,
+
+        /// Creates the pull request right away instead of opening the form to edit it first
+        #[arg(long = "no-edit", default_value = "false")]
+        no_edit: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum OooCommand {
+    /// Starts being out of office through the day, in the form 2024-07-31
+    Until { date: String },
+    /// Stops being out of office
+    Back,
+    /// Prints the last day away, if away
+    Status,
+    /// Reassigns or comments on the review requests which arrived since the last run, e.g. from
+    /// cron
+    Run {
+        /// Prints what would be done without changing anything
+        #[arg(long = "dry-run", default_value = "false")]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DraftCommand {
+    /// Lists the unsubmitted drafts
+    List,
+    /// Writes the draft of a review to a file, as markdown if it ends in .md and json otherwise
+    Export {
+        /// The review in the form owner/name#number
+        review: String,
+        file: PathBuf,
+    },
+    /// Reads a draft written by export, replacing any draft of the same review
+    Import { file: PathBuf },
+    /// Submits the draft of a review with its verdict
+    Submit {
+        /// The review in the form owner/name#number
+        review: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommand {
+    /// Logs in with the github device flow and stores the token readable only by you
+    Login {
+        /// The client id of a github oauth app with the device flow enabled
+        #[arg(long = "client-id", env = "REV_GITHUB_CLIENT_ID")]
+        client_id: String,
+    },
+    /// Removes the token stored by login
+    Logout,
+    /// Prints where the token comes from and who it belongs to
+    Status,
+}
+
+#[derive(Subcommand)]
+enum DueCommand {
+    /// Sets when a review is due, in the form 2024-07-31 or 2024-07-31 14:00
+    Set {
+        /// The review in the form owner/name#number
+        review: String,
+        date: String,
+    },
+    /// Forgets the due date set for a review, leaving it to the rules
+    Clear {
+        /// The review in the form owner/name#number
+        review: String,
+    },
+    /// Prints the queued reviews which are due, soonest first
+    List,
+    /// Writes the queued reviews which are due as an ics calendar
+    Calendar {
+        /// Writes the calendar to the file instead of stdout
+        #[arg(long = "output")]
+        output: Option<PathBuf>,
+
+        /// Command to pipe the calendar into, defaults to the hook of the due block
+        #[arg(long = "hook")]
+        hook: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueueCommand {
+    /// Prints the searches and the queue block as a snippet to share
+    Export {
+        /// Only export the search of the name, all searches are exported otherwise
+        name: Option<String>,
+        /// Writes the snippet to the file instead of stdout
+        #[arg(long = "file")]
+        file: Option<PathBuf>,
+    },
+    /// Merges a snippet shared by a teammate into the config
+    Import {
+        /// The file or url of the snippet
+        source: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum ConfigCommand {
     Get,
@@ -38,18 +376,25 @@ enum ConfigCommand {
 
 pub async fn run() -> anyhow::Result<()> {
     let cli = Command::parse();
+    let profile = cli.profile.clone();
+    let profile = profile.as_deref();
+    let github_api_url = cli.github_api_url.clone();
+    let github_api_url = github_api_url.as_deref();
 
     match cli.command.unwrap() {
         Commands::Init { force } => {
             let config = ApplicationConfig::new(cli.global_args).await?;
+            let settings = ApplicationSettings::default();
 
-            let config_home_path = config.get_config_file_path();
-            let config_file_path = config_home_path.join("rev.kdl");
+            let config_home_path = &settings.config_home;
+            let config_file_path = settings.config_file_path();
 
             if config_file_path.exists() && !force {
                 println!(
-                    "config file already exists at: {}\nUse --force to override, be careful you may want to back up your config first",
-                    config_file_path.display()
+                    "{}: {}\n{}",
+                    tr(Text::ConfigAlreadyExists),
+                    config_file_path.display(),
+                    tr(Text::UseForceToOverride)
                 );
                 return Ok(());
             }
@@ -59,22 +404,48 @@ pub async fn run() -> anyhow::Result<()> {
 
             file.write_all(
                 format!(
-                    r#"config {{
+                    r#"version {}
+config {{
     committer "{}"
 }}"#,
-                    config.committer
+                    CONFIG_VERSION, config.committer
                 )
                 .as_bytes(),
             )
             .await?;
 
-            println!("wrote config to: {}", config_file_path.display());
+            println!("{}: {}", tr(Text::WroteConfig), config_file_path.display());
         }
-        Commands::Review => {
+        Commands::Review {
+            target,
+            reviewer,
+            org,
+            labels,
+        } => {
+            let target = target
+                .map(|t| {
+                    ReviewTarget::parse(&t).ok_or(anyhow::anyhow!(
+                        "review must be in the form owner/name#number, or the url of a pull request or one of its comments"
+                    ))
+                })
+                .transpose()?;
             logging::initialize_panic_handler()?;
 
+            let committer = ApplicationConfig::new(cli.global_args)
+                .await
+                .ok()
+                .map(|c| c.committer.clone());
+
             tracing::info!("starting tui");
-            match App::default().register_pages().await {
+            match App::default()
+                .committer(committer)
+                .profile(profile)?
+                .github_api_url(github_api_url)
+                .review_query(reviewer, org, labels)
+                .review_target(target)
+                .register_pages()
+                .await
+            {
                 Ok(a) => {
                     if let Err(e) = a.run().await {
                         tracing::error!("{}", e);
@@ -88,6 +459,540 @@ pub async fn run() -> anyhow::Result<()> {
             }
             tracing::info!("stopping tui");
         }
+        Commands::List {
+            search,
+            reviewer,
+            org,
+            labels,
+            format,
+        } => {
+            let config = load_config(profile, github_api_url)?;
+            let store = Store::default();
+            let prs = GitPullRequests::new(config.provider.connect()?)
+                .queue(config.queue.overridden(reviewer, org, labels))
+                .store(store.clone());
+            if let Some(name) = search {
+                let search = SavedSearches::all(&store, &config.searches)?
+                    .into_iter()
+                    .find(|s| s.name == name)
+                    .ok_or(anyhow::anyhow!("no saved search named {name}"))?;
+                prs.set_search(Some(search.query));
+            }
+
+            let mut items = prs.collect(None).await?;
+            ReviewPlan::load(&store)?.sort(&mut items, |i| &i.id);
+            let reviews = items.iter().map(ListedReview::from).collect::<Vec<_>>();
+
+            match format.as_str() {
+                "json" => println!("{}", listing::to_json(&reviews)?),
+                _ => {
+                    // Links are only for people reading the table, not for what it's piped to
+                    let mut terminal = config.terminal.clone();
+                    terminal.hyperlinks &= std::io::stdout().is_terminal();
+                    print!("{}", listing::render(&reviews, &terminal))
+                }
+            }
+        }
+        Commands::Digest { limit, hook } => {
+            let config = load_config(profile, github_api_url)?;
+            let store = Store::default();
+            // The hook notifies, which waits until you're back
+            if hook.is_some() && Absence::current(&store)?.is_some() {
+                println!("{}", tr(Text::OutOfOfficeDigestSkipped));
+                return Ok(());
+            }
+
+            let digest = digest::run(config.provider.connect()?, store, limit).await?;
+            let rendered = digest.render();
+
+            match hook {
+                Some(hook) => digest::send_to_hook(&hook, &rendered)?,
+                None => print!("{rendered}"),
+            }
+        }
+        Commands::Bot {
+            dry_run,
+            query,
+            watch,
+            metrics_port,
+        } => {
+            let config_file_path = ApplicationSettings::default().config_file_path();
+            let config = Config::load(&config_file_path)?
+                .with_profile(profile)?
+                .with_github_api_url(github_api_url);
+            if config.bot.is_empty() {
+                println!("{} {}", tr(Text::BotNoRules), config_file_path.display());
+                return Ok(());
+            }
+
+            let provider = config.provider.connect()?;
+            let Some(seconds) = watch else {
+                let outcomes = bot::run(
+                    provider,
+                    Store::default(),
+                    &config.bot,
+                    &config.labels,
+                    &query,
+                    dry_run,
+                )
+                .await?;
+                print_bot_outcomes(&outcomes, dry_run);
+                return Ok(());
+            };
+
+            let metrics = Arc::new(Metrics::default());
+            if let Some(port) = metrics_port {
+                let listener = TcpListener::bind(("0.0.0.0", port))
+                    .await
+                    .with_context(|| format!("failed to listen for metrics on port {port}"))?;
+                tokio::spawn(metrics::serve(listener, metrics.clone()));
+            }
+
+            bot::watch(
+                provider,
+                Store::default(),
+                &config.bot,
+                &config.labels,
+                &query,
+                dry_run,
+                Duration::from_secs(seconds),
+                &metrics,
+                |round| match round {
+                    Ok(outcomes) => print_bot_outcomes(&outcomes, dry_run),
+                    Err(e) => eprintln!("{}: {e:#}", tr(Text::BotRoundFailed)),
+                },
+            )
+            .await;
+        }
+        Commands::Script { script } => {
+            let content = if script == "-" {
+                let mut content = String::new();
+                tokio::io::stdin().read_to_string(&mut content).await?;
+                content
+            } else {
+                tokio::fs::read_to_string(&script).await?
+            };
+            let lines = script::parse(&content)?;
+
+            let config = load_config(profile, github_api_url)?;
+            let outcomes = script::run(&config.provider.connect()?, &Store::default(), lines).await;
+
+            let mut failed = 0;
+            for outcome in outcomes.iter() {
+                match &outcome.error {
+                    Some(error) => {
+                        failed += 1;
+                        println!(
+                            "{}: {} {} ({error})",
+                            outcome.line, outcome.review, outcome.command
+                        );
+                    }
+                    None => println!("{}: {} {}", outcome.line, outcome.review, outcome.command),
+                }
+            }
+            println!(
+                "{} {}, {failed} {}",
+                outcomes.len() - failed,
+                tr(Text::ScriptSucceeded),
+                tr(Text::ScriptFailed)
+            );
+            if failed > 0 {
+                anyhow::bail!("{failed} {}", tr(Text::ScriptFailed));
+            }
+        }
+        Commands::Approve { review, message } => {
+            let config = load_config(profile, github_api_url)?;
+            script::run_one(
+                &config.provider.connect()?,
+                &Store::default(),
+                "cli",
+                &review,
+                script::ScriptCommand::Approve(message),
+            )
+            .await?;
+            println!("{review} {}", tr(Text::ReviewApproved));
+        }
+        Commands::Comment { review, message } => {
+            let config = load_config(profile, github_api_url)?;
+            script::run_one(
+                &config.provider.connect()?,
+                &Store::default(),
+                "cli",
+                &review,
+                script::ScriptCommand::Comment(message),
+            )
+            .await?;
+            println!("{review} {}", tr(Text::CommentAdded));
+        }
+        Commands::Auth { subcommand } => {
+            let config = load_config(profile, github_api_url)?;
+            let host = config
+                .provider
+                .github_host()
+                .context("rev auth only logs in to github, which isn't the provider")?
+                .to_string();
+
+            match subcommand {
+                AuthCommand::Login { client_id } => {
+                    let flow = DeviceFlow::new(format!("https://{host}"), client_id)?;
+                    let code = flow.start().await?;
+                    println!(
+                        "{} {} {} {}",
+                        tr(Text::AuthEnterCode),
+                        code.user_code,
+                        tr(Text::AuthAt),
+                        code.verification_uri
+                    );
+                    println!("{}", tr(Text::AuthWaiting));
+
+                    let token = flow.wait_for_token(&code).await?;
+                    auth::store_token(&platform::token_file(&host), &token)?;
+                    let options = config
+                        .provider
+                        .github_options()?
+                        .context("a github provider")?
+                        .with_token(token);
+                    print_token_owner(GitProvider::github_with(options)?).await?;
+                }
+                AuthCommand::Logout => match auth::remove_token(&platform::token_file(&host))? {
+                    true => println!("{}", tr(Text::LoggedOut)),
+                    false => println!("{}", tr(Text::NotLoggedIn)),
+                },
+                AuthCommand::Status => {
+                    let options = config
+                        .provider
+                        .github_options()?
+                        .context("a github provider")?;
+                    let source = token_source(&options)
+                        .ok_or(anyhow::anyhow!("{}", tr(Text::DoctorLogin)))?;
+                    println!("{} {} ({host})", tr(Text::TokenFrom), source.name());
+
+                    print_token_owner(config.provider.connect()?).await?;
+                }
+            }
+        }
+        Commands::Doctor => {
+            let symbols = Capabilities::detect().symbols();
+            let checks = doctor::run(
+                &ApplicationSettings::default().config_file_path(),
+                profile,
+                github_api_url,
+            )
+            .await;
+            for check in checks.iter() {
+                println!("{}", check.render(&symbols));
+            }
+
+            if checks.iter().any(|c| !c.passed) {
+                anyhow::bail!("{}", tr(Text::DoctorFailed));
+            }
+        }
+        Commands::History { limit } => {
+            let log = AuditLog::load(&Store::default())?;
+            if log.entries.is_empty() {
+                println!("{}", tr(Text::HistoryEmpty));
+            }
+
+            for entry in log.latest(limit) {
+                println!("{}", entry.render());
+            }
+        }
+        Commands::Stats { json } => {
+            let stats = VerdictStats::from_log(&AuditLog::load(&Store::default())?);
+            if json {
+                println!("{}", stats.to_json()?);
+            } else if stats.total.total() == 0 {
+                println!("{}", tr(Text::NoVerdicts));
+            } else {
+                print!("{}", stats.render());
+            }
+        }
+        Commands::SelfUpdate => {
+            let config = load_config(profile, github_api_url)?;
+            match self_update::update(&config.update.feed).await? {
+                Some(version) => println!("{} {version}", tr(Text::UpdatedTo)),
+                None => println!("{} ({})", tr(Text::UpToDate), self_update::CURRENT_VERSION),
+            }
+        }
+        Commands::Queue { subcommand } => {
+            let path = ApplicationSettings::default().config_file_path();
+            match subcommand {
+                QueueCommand::Export { name, file } => {
+                    let snippet =
+                        queue_share::export_from(&path, &Store::default(), name.as_deref())?
+                            .render();
+                    match file {
+                        Some(file) => {
+                            std::fs::write(&file, snippet)?;
+                            println!("{} {}", tr(Text::WroteQueue), file.display());
+                        }
+                        None => print!("{snippet}"),
+                    }
+                }
+                QueueCommand::Import { source } => {
+                    let snippet = QueueSnippet::parse(&queue_share::fetch(&source).await?)?;
+                    let outcome = queue_share::import_into(&path, &snippet)?;
+                    for name in outcome.added {
+                        println!("{} {name}", tr(Text::SearchAdded));
+                    }
+                    for name in outcome.replaced {
+                        println!("{} {name}", tr(Text::SearchReplaced));
+                    }
+                    if !outcome.queue.is_empty() {
+                        println!(
+                            "{} {}",
+                            tr(Text::QueueSettingsSet),
+                            outcome.queue.join(", ")
+                        );
+                    }
+                }
+            }
+        }
+        Commands::Due { subcommand } => match subcommand {
+            DueCommand::Set { review, date } => {
+                parse_reference(&review).ok_or(anyhow::anyhow!(
+                    "review must be in the form owner/name#number"
+                ))?;
+                let due = due_dates::parse_due(&date)?;
+                DueDates::set(&Store::default(), &review, due)?;
+                println!(
+                    "{review} {} {}",
+                    tr(Text::DueAt),
+                    due_dates::format_due(due)
+                );
+            }
+            DueCommand::Clear { review } => match DueDates::clear(&Store::default(), &review)? {
+                true => println!("{review} {}", tr(Text::DueDateCleared)),
+                false => println!("{review} {}", tr(Text::NoDueDate)),
+            },
+            DueCommand::List => {
+                let reviews = due_queue(profile, github_api_url).await?;
+                if reviews.is_empty() {
+                    println!("{}", tr(Text::NoDueReviews));
+                }
+                for review in reviews {
+                    println!(
+                        "{}  {}  {}",
+                        due_dates::format_due(review.due),
+                        review.reference,
+                        review.title
+                    );
+                }
+            }
+            DueCommand::Calendar { output, hook } => {
+                let config = load_config(profile, github_api_url)?;
+                let calendar = due_dates::calendar(&due_queue(profile, github_api_url).await?);
+
+                match (output, hook.or(config.due.hook)) {
+                    (Some(output), _) => {
+                        tokio::fs::write(&output, calendar).await?;
+                        println!("{} {}", tr(Text::CalendarWritten), output.display());
+                    }
+                    (None, Some(hook)) => due_dates::send_to_hook(&hook, &calendar)?,
+                    (None, None) => print!("{calendar}"),
+                }
+            }
+        },
+        Commands::Ooo { subcommand } => match subcommand {
+            OooCommand::Until { date } => {
+                let until = out_of_office::parse_until(&date)?;
+                Absence::start(&Store::default(), until)?;
+                println!("{} {until}", tr(Text::OutOfOfficeUntil));
+            }
+            OooCommand::Back => {
+                Absence::end(&Store::default())?;
+                println!("{}", tr(Text::OutOfOfficeEnded));
+            }
+            OooCommand::Status => match Absence::current(&Store::default())? {
+                Some(absence) => println!(
+                    "{} {}",
+                    tr(Text::OutOfOfficeUntil),
+                    absence.until.map(|u| u.to_string()).unwrap_or_default()
+                ),
+                None => println!("{}", tr(Text::NotOutOfOffice)),
+            },
+            OooCommand::Run { dry_run } => {
+                let config_file_path = ApplicationSettings::default().config_file_path();
+                let config = Config::load(&config_file_path)?
+                    .with_profile(profile)?
+                    .with_github_api_url(github_api_url);
+                let settings = &config.out_of_office;
+                if !settings.reassign && settings.comment.is_none() {
+                    println!(
+                        "{} {}",
+                        tr(Text::OutOfOfficeNoRules),
+                        config_file_path.display()
+                    );
+                    return Ok(());
+                }
+                let store = Store::default();
+                if Absence::current(&store)?.is_none() {
+                    println!("{}", tr(Text::NotOutOfOffice));
+                    return Ok(());
+                }
+
+                let committer = ApplicationConfig::new(cli.global_args)
+                    .await
+                    .ok()
+                    .map(|c| c.committer.clone());
+
+                let outcomes = out_of_office::run(
+                    config.provider.connect()?,
+                    store,
+                    settings,
+                    &config.team,
+                    committer.as_deref(),
+                    dry_run,
+                )
+                .await?;
+                if outcomes.is_empty() {
+                    println!("{}", tr(Text::OutOfOfficeNothingNew));
+                }
+
+                for outcome in outcomes {
+                    let mut done = Vec::new();
+                    if let Some(to) = outcome.reassigned_to {
+                        done.push(format!("reassign to {to}"));
+                    }
+                    if outcome.commented {
+                        done.push("comment".to_string());
+                    }
+                    done.extend(outcome.errors.into_iter().map(|e| format!("failed: {e}")));
+                    println!("{} {}: {}", outcome.review, outcome.title, done.join(", "));
+                }
+
+                if dry_run {
+                    println!("{}", tr(Text::BotDryRun));
+                }
+            }
+        },
+        Commands::Pr { subcommand } => match subcommand {
+            PrCommand::Render {
+                review,
+                format,
+                output,
+            } => {
+                let config = load_config(profile, github_api_url)?;
+                let document = ReviewDocument::fetch(&config.provider.connect()?, &review).await?;
+                let format = match format.as_str() {
+                    "html" => Format::Html,
+                    _ => Format::Markdown,
+                };
+
+                let rendered = document.render(format);
+                match output {
+                    Some(output) => tokio::fs::write(output, rendered).await?,
+                    None => print!("{rendered}"),
+                }
+            }
+            PrCommand::Create {
+                base,
+                title,
+                body,
+                draft,
+                no_edit,
+            } => {
+                let config = load_config(profile, github_api_url)?;
+                let provider = config.provider.connect()?;
+                let mut branch =
+                    BranchReview::from_checkout(&std::env::current_dir()?, base, &provider).await?;
+                if let Some(title) = title {
+                    branch.review.title = title;
+                }
+                if let Some(body) = body {
+                    branch.review.body = body;
+                }
+                branch.review.draft = draft;
+
+                if no_edit {
+                    let reference = branch.create(&provider, &Store::default(), "cli").await?;
+                    println!("{} {reference}", tr(Text::CreatedReview));
+                    return Ok(());
+                }
+
+                logging::initialize_panic_handler()?;
+                App::default()
+                    .new_review(branch)
+                    .register_pages()
+                    .await?
+                    .run()
+                    .await?;
+            }
+        },
+        Commands::Draft { subcommand } => {
+            let store = Store::default();
+            let missing = |review: &str| anyhow::anyhow!("{} {review}", tr(Text::NoDraftFor));
+
+            match subcommand {
+                DraftCommand::List => {
+                    let drafts = Drafts::load(&store)?;
+                    if drafts.drafts.is_empty() {
+                        println!("{}", tr(Text::NoDrafts));
+                    }
+
+                    for draft in drafts.drafts.values() {
+                        println!(
+                            "{} {}",
+                            draft.review,
+                            draft.verdict.map(|v| v.name()).unwrap_or("-")
+                        );
+                    }
+                }
+                DraftCommand::Export { review, file } => {
+                    let draft = Drafts::get(&store, &review)?.ok_or_else(|| missing(&review))?;
+                    draft.export(&file)?;
+                    println!("{} {}", tr(Text::WroteDraft), file.display());
+                }
+                DraftCommand::Import { file } => {
+                    let draft = ReviewDraft::import(&file)?;
+                    let review = draft.review.clone();
+                    Drafts::save(&store, draft)?;
+                    println!("{} {review}", tr(Text::ImportedDraft));
+                }
+                DraftCommand::Submit { review } => {
+                    let draft = Drafts::get(&store, &review)?.ok_or_else(|| missing(&review))?;
+                    let config = load_config(profile, github_api_url)?;
+                    let provider = config.provider.connect()?;
+                    let prs = GitPullRequest::new(
+                        provider.clone(),
+                        GitPullRequests::new(provider),
+                        store,
+                    );
+                    prs.submit_draft(&draft, &config.labels).await?;
+                    println!("{}", tr(Text::ReviewSubmitted));
+                }
+            }
+        }
+        Commands::Workspace { subcommand } => {
+            let config = load_config(profile, github_api_url)?;
+            let keep = config.workspace.max_worktrees;
+            let workspace = Workspace::new(config.workspace, Store::default());
+
+            match subcommand {
+                WorkspaceCommand::Checkout { review } => {
+                    let (owner, name, number) = parse_reference(&review).ok_or(anyhow::anyhow!(
+                        "review must be in the form owner/name#number"
+                    ))?;
+                    let path = workspace.checkout(owner, name, number).await?;
+                    println!("{}", path.display());
+                }
+                WorkspaceCommand::List => {
+                    for worktree in workspace.list()? {
+                        println!(
+                            "{} {} {}",
+                            worktree.last_used.format("%Y-%m-%d %H:%M"),
+                            worktree.review,
+                            worktree.path.display()
+                        );
+                    }
+                }
+                WorkspaceCommand::Prune { keep: keep_arg } => {
+                    for review in workspace.prune(keep_arg.unwrap_or(keep)).await? {
+                        println!("{review}");
+                    }
+                }
+            }
+        }
         Commands::Config { subcommand } => match subcommand {
             Some(subcommand) => match subcommand {
                 ConfigCommand::Get => todo!(),
@@ -106,3 +1011,72 @@ pub async fn run() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// The reviews of the queue which are due, by the dates set with `rev due set` and the rules.
+async fn due_queue(
+    profile: Option<&str>,
+    github_api_url: Option<&str>,
+) -> anyhow::Result<Vec<DueReview>> {
+    let config = load_config(profile, github_api_url)?;
+    let store = Store::default();
+    let items = GitPullRequests::new(config.provider.connect()?)
+        .queue(config.queue.clone())
+        .store(store.clone())
+        .collect(None)
+        .await?;
+
+    Ok(due_dates::due_reviews(
+        &items,
+        &DueDates::load(&store)?,
+        &config.due,
+    ))
+}
+
+/// Prints what the bot did, or would do in a dry run, for each review.
+fn print_bot_outcomes(outcomes: &[BotOutcome], dry_run: bool) {
+    for outcome in outcomes {
+        let Some(rule) = &outcome.rule else {
+            println!(
+                "{} {}: {}",
+                outcome.review,
+                outcome.title,
+                tr(Text::BotNoMatchingRule)
+            );
+            continue;
+        };
+
+        let actions = outcome
+            .actions
+            .iter()
+            .map(|(action, error)| match error {
+                Some(error) => format!("{} ({error})", action.name()),
+                None => action.name().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("{} {}: {rule} -> {actions}", outcome.review, outcome.title);
+    }
+
+    if dry_run {
+        println!("{}", tr(Text::BotDryRun));
+    }
+}
+
+/// Loads rev.kdl with the profile and github api url applied.
+fn load_config(profile: Option<&str>, github_api_url: Option<&str>) -> anyhow::Result<Config> {
+    Ok(
+        Config::load(&ApplicationSettings::default().config_file_path())?
+            .with_profile(profile)?
+            .with_github_api_url(github_api_url),
+    )
+}
+
+/// Prints who the token of the provider belongs to, failing when it's rejected.
+async fn print_token_owner(provider: GitProvider) -> anyhow::Result<()> {
+    match provider.get_token_status().await? {
+        TokenStatus::Valid { login, .. } => println!("{} {login}", tr(Text::LoggedInAs)),
+        TokenStatus::Rejected => anyhow::bail!("{}", tr(Text::DoctorTokenRejected)),
+    }
+
+    Ok(())
+}
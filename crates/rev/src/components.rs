@@ -1,53 +0,0 @@
-use crossterm::event::{KeyEvent, MouseEvent};
-use ratatui::layout::Rect;
-use tokio::sync::mpsc::UnboundedSender;
-
-pub mod diff;
-pub mod github_pr;
-pub mod github_prs;
-pub mod home;
-
-use crate::{
-    action::Action,
-    config::Config,
-    tui::{Event, Frame},
-};
-
-#[allow(unused_variables)]
-pub trait Component {
-    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> anyhow::Result<()> {
-        Ok(())
-    }
-
-    fn register_config_handler(&mut self, config: Config) -> anyhow::Result<()> {
-        Ok(())
-    }
-
-    fn init(&mut self) -> anyhow::Result<()> {
-        Ok(())
-    }
-
-    fn handle_events(&mut self, event: Option<Event>) -> anyhow::Result<Option<Action>> {
-        let r = match event {
-            Some(Event::Key(key_event)) => self.handle_key_events(key_event)?,
-            Some(Event::Mouse(mouse_event)) => self.handle_mouse_events(mouse_event)?,
-            _ => None,
-        };
-
-        Ok(r)
-    }
-
-    fn handle_key_events(&mut self, key: KeyEvent) -> anyhow::Result<Option<Action>> {
-        Ok(None)
-    }
-
-    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> anyhow::Result<Option<Action>> {
-        Ok(None)
-    }
-
-    fn update(&mut self, action: Action) -> anyhow::Result<Option<Action>> {
-        Ok(None)
-    }
-
-    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> anyhow::Result<()>;
-}
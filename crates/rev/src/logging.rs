@@ -1,33 +1,45 @@
-use std::path::PathBuf;
+use std::{io::Write, sync::Mutex};
 
-use directories::ProjectDirs;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{
-    prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, Layer,
+    fmt::writer::BoxMakeWriter, prelude::__tracing_subscriber_SubscriberExt,
+    util::SubscriberInitExt, Layer,
 };
 
 pub fn initialize_logging() -> anyhow::Result<()> {
-    let project = match ProjectDirs::from("io", "kjuulh", env!("CARGO_PKG_NAME")) {
-        Some(p) => p.data_local_dir().to_path_buf(),
-        None => PathBuf::from(".").join(".data"),
-    };
-
-    std::fs::create_dir_all(&project)?;
+    let project = rev_core::platform::data_dir();
     let log_path = project.join("rev.log");
 
     //println!("logging to: {}", log_path.display());
 
-    let log_file = std::fs::File::create(log_path)?;
+    let log_file = std::fs::create_dir_all(&project).and_then(|_| std::fs::File::create(&log_path));
+    // Without a log file only warnings are worth printing, they end up in the terminal
+    let default_level = if log_file.is_ok() { "info" } else { "warn" };
     std::env::set_var(
         "RUST_LOG",
         std::env::var("RUST_LOG")
             .or_else(|_| std::env::var("REV_LOG_LEVEL"))
-            .unwrap_or_else(|_| format!("{}=info", env!("CARGO_CRATE_NAME"))),
+            .unwrap_or_else(|_| {
+                format!(
+                    "{}={default_level},rev_core={default_level}",
+                    env!("CARGO_CRATE_NAME")
+                )
+            }),
     );
+    let writer = match log_file {
+        Ok(file) => BoxMakeWriter::new(file),
+        Err(e) => {
+            eprintln!(
+                "rev: unable to log to {}, logging to stderr instead: {e}",
+                log_path.display()
+            );
+            BoxMakeWriter::new(|| HeldStderr)
+        }
+    };
     let file_subscriber = tracing_subscriber::fmt::layer()
         .with_file(true)
         .with_line_number(true)
-        .with_writer(log_file)
+        .with_writer(writer)
         .with_target(false)
         .with_ansi(false)
         .with_filter(tracing_subscriber::filter::EnvFilter::from_default_env());
@@ -38,9 +50,37 @@ pub fn initialize_logging() -> anyhow::Result<()> {
     Ok(())
 }
 
+static HELD_LOGS: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+/// Writes logs to stderr, holding them back while the tui is drawn so they don't garble it.
+struct HeldStderr;
+
+impl std::io::Write for HeldStderr {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if rev_core::tui::is_active() {
+            HELD_LOGS.lock().unwrap().extend_from_slice(buf);
+            return Ok(buf.len());
+        }
+
+        std::io::stderr().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stderr().flush()
+    }
+}
+
+/// Prints the logs held back while the tui was drawn, once the terminal is restored.
+pub fn flush_held_logs() {
+    let held = std::mem::take(&mut *HELD_LOGS.lock().unwrap());
+    if !held.is_empty() {
+        let _ = std::io::stderr().write_all(&held);
+    }
+}
+
 pub fn initialize_panic_handler() -> anyhow::Result<()> {
     std::panic::set_hook(Box::new(move |panic_info| {
-        if let Ok(mut t) = crate::tui::Tui::new() {
+        if let Ok(mut t) = rev_core::tui::Tui::new() {
             if let Err(r) = t.exit() {
                 tracing::error!("Unable to exit Terminal: {:?}", r);
             }
@@ -1,13 +1,5 @@
-mod action;
-mod app;
-mod application_config;
 mod cli;
-mod components;
-mod config;
-mod git_pull_requests;
 mod logging;
-mod page;
-mod tui;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -16,7 +8,8 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::debug!("starting app");
 
-    cli::run().await?;
+    let result = cli::run().await;
+    logging::flush_held_logs();
 
-    Ok(())
+    result
 }